@@ -0,0 +1,55 @@
+use crate::communications::mailers::insert_event_template_data;
+use crate::config::Config;
+use crate::errors::*;
+use crate::SITE_NAME;
+use db::models::*;
+use diesel::PgConnection;
+use uuid::Uuid;
+
+pub fn event_rescheduled(
+    config: &Config,
+    user: &User,
+    event: &Event,
+    event_reschedule: &EventReschedule,
+    order_id: Option<Uuid>,
+    conn: &PgConnection,
+) -> Result<(), ApiError> {
+    let email = match user.email.clone() {
+        Some(email) => email,
+        None => return Ok(()),
+    };
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let title = format!("{}: {} has been rescheduled", SITE_NAME, event.name);
+    let template_id = config.email_templates.event_rescheduled.to_string();
+    let mut template_data = TemplateData::new();
+    template_data.insert("name".to_string(), user.full_name());
+    insert_event_template_data(&mut template_data, event, conn)?;
+    if let Some(previous_event_start) = event_reschedule.previous_event_start {
+        template_data.insert("previous_event_start".to_string(), previous_event_start.to_string());
+    }
+    template_data.insert("event_reschedule_id".to_string(), event_reschedule.id.to_string());
+    template_data.insert(
+        "response_deadline".to_string(),
+        event_reschedule.response_deadline.to_string(),
+    );
+    if let Some(order_id) = order_id {
+        template_data.insert("order_id".to_string(), order_id.to_string());
+    }
+
+    let mut communication = Communication::new(
+        CommunicationType::EmailTemplate,
+        title,
+        None,
+        Some(source),
+        destinations,
+        Some(template_id),
+        Some(vec![template_data]),
+        Some(vec!["event_reschedules"]),
+        None,
+    );
+    communication.main_table = Some(Tables::Events);
+    communication.main_table_id = Some(event.id);
+    communication.queue(conn)?;
+    Ok(())
+}
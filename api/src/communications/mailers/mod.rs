@@ -3,11 +3,15 @@ use db::models::*;
 use diesel::PgConnection;
 use url::form_urlencoded::byte_serialize;
 
+pub mod event_reschedules;
 pub mod orders;
 pub mod organization_invites;
+pub mod organizations;
+pub mod refund_requests;
 pub mod reports;
 pub mod tickets;
 pub mod user;
+pub mod waitlist;
 
 pub fn insert_event_template_data(
     template_data: &mut TemplateData,
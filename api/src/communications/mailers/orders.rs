@@ -25,10 +25,12 @@ pub fn confirmation_email(
     let mut total_fees = 0;
     let mut total_initial_fees = 0;
     let mut total_refunded_fees = 0;
+    let mut total_tax = 0;
+    let mut total_included_tax = 0;
 
     for oi in &display_order.items {
         match oi.item_type {
-            OrderItemTypes::Tickets => {
+            OrderItemTypes::Tickets | OrderItemTypes::Products | OrderItemTypes::NameChangeFees => {
                 item_breakdown.push_str(&generate_item_row(
                     &oi.description,
                     oi.quantity,
@@ -58,6 +60,10 @@ pub fn confirmation_email(
             }
             // Do nothing, included above with ticket for display
             OrderItemTypes::Discount => (),
+            OrderItemTypes::Tax => {
+                total_tax += (oi.quantity - oi.refunded_quantity) * oi.unit_price_in_cents;
+                total_included_tax += (oi.quantity - oi.refunded_quantity) * oi.included_tax_in_cents;
+            }
             _ => {
                 //Accumulate fees
                 total_initial_fees += oi.quantity * oi.unit_price_in_cents;
@@ -81,6 +87,18 @@ pub fn confirmation_email(
             format!("{:.*}", 2, total_refunded_fees as f64 / 100.0)
         ));
     }
+    if total_tax > 0 {
+        total_breakdown.push_str(&format!(
+            "<tr><th>Tax Total</th><td>{}</td></tr>",
+            format!("{:.*}", 2, total_tax as f64 / 100.0)
+        ));
+    }
+    if total_included_tax > 0 {
+        total_breakdown.push_str(&format!(
+            "<tr><th>Includes Tax</th><td>{}</td></tr>",
+            format!("{:.*}", 2, total_included_tax as f64 / 100.0)
+        ));
+    }
     total_breakdown.push_str(&format!(
         "<tr><th>Order Total</th><td>{}</td></tr>",
         format!("{:.*}", 2, display_order.total_in_cents as f64 / 100.0)
@@ -107,6 +125,7 @@ pub fn confirmation_email(
         format!("{:.*}", 2, total_refunded_fees as f64 / 100.0),
     );
     template_data.insert("total_fees".to_string(), format!("{:.*}", 2, total_fees as f64 / 100.0));
+    template_data.insert("total_tax".to_string(), format!("{:.*}", 2, total_tax as f64 / 100.0));
     template_data.insert(
         "total_price".to_string(),
         format!("{:.*}", 2, display_order.total_in_cents as f64 / 100.0),
@@ -115,6 +134,26 @@ pub fn confirmation_email(
     template_data.insert("total_breakdown".to_string(), total_breakdown);
     template_data.insert("tickets_link".to_string(), format!("{}/hub", config.front_end_url));
 
+    let gift_cards = GiftCard::find_for_order(display_order.id, conn)?;
+    if !gift_cards.is_empty() {
+        let mut gift_card_breakdown = r#"<table style="width:100%"><tbody>"#.to_string();
+        gift_card_breakdown.push_str("<tr><th>Gift Card Code</th><th>Value</th><th>Expires</th></tr>");
+        for gift_card in gift_cards {
+            gift_card_breakdown.push_str(&format!(
+                r#"<tr><td>{}</td><td align="right">${:.*}</td><td>{}</td></tr>"#,
+                gift_card.code,
+                2,
+                gift_card.initial_value_in_cents as f64 / 100.0,
+                gift_card
+                    .expires_at
+                    .map(|e| e.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default()
+            ));
+        }
+        gift_card_breakdown.push_str("</tbody></table>");
+        template_data.insert("gift_cards".to_string(), gift_card_breakdown);
+    }
+
     // TODO: Perhaps move this to an event subscription
     Ok(Communication::new(
         CommunicationType::EmailTemplate,
@@ -0,0 +1,54 @@
+use crate::config::Config;
+use crate::errors::*;
+use crate::SITE_NAME;
+use db::models::*;
+use diesel::PgConnection;
+
+pub fn chargeback_received(
+    config: &Config,
+    organization: &Organization,
+    order: &Order,
+    dispute: &Dispute,
+    conn: &PgConnection,
+) -> Result<(), ApiError> {
+    let recipients = organization
+        .users(None, conn)?
+        .into_iter()
+        .filter(|(organization_user, _)| {
+            organization_user.role.contains(&Roles::OrgOwner) || organization_user.role.contains(&Roles::OrgAdmin)
+        })
+        .filter_map(|(_, user)| user.email);
+
+    for email in recipients {
+        let source = CommAddress::from(config.communication_default_source_email.clone());
+        let destinations = CommAddress::from(email);
+        let title = format!("{}: Chargeback received for order {}", SITE_NAME, order.id);
+        let template_id = config.email_templates.chargeback_received.to_string();
+        let mut template_data = TemplateData::new();
+        template_data.insert("organization_name".to_string(), organization.name.clone());
+        template_data.insert("order_id".to_string(), order.id.to_string());
+        template_data.insert("amount_in_cents".to_string(), dispute.amount_in_cents.to_string());
+        template_data.insert("currency".to_string(), order.currency.clone());
+        template_data.insert("reason".to_string(), dispute.reason.clone().unwrap_or_default());
+        if let Some(evidence_due_by) = dispute.evidence_due_by {
+            template_data.insert("evidence_due_by".to_string(), evidence_due_by.to_string());
+        }
+
+        let mut communication = Communication::new(
+            CommunicationType::EmailTemplate,
+            title,
+            None,
+            Some(source),
+            destinations,
+            Some(template_id),
+            Some(vec![template_data]),
+            Some(vec!["chargebacks"]),
+            None,
+        );
+        communication.main_table = Some(Tables::Orders);
+        communication.main_table_id = Some(order.id);
+        communication.queue(conn)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use crate::config::Config;
+use crate::errors::*;
+use crate::SITE_NAME;
+use db::models::*;
+use diesel::PgConnection;
+
+pub fn request_received(
+    config: &Config,
+    user: &User,
+    refund_request: &RefundRequest,
+    conn: &PgConnection,
+) -> Result<(), ApiError> {
+    let email = match user.email.clone() {
+        Some(email) => email,
+        None => return Ok(()),
+    };
+    let event = refund_request.event(conn)?;
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let title = format!("{}: Refund request received for {}", SITE_NAME, event.name);
+    let template_id = config.email_templates.refund_request_received.to_string();
+    let mut template_data = TemplateData::new();
+    template_data.insert("name".to_string(), user.full_name());
+    insert_refund_request_template_data(&mut template_data, &event, refund_request);
+
+    queue(
+        title,
+        source,
+        destinations,
+        template_id,
+        template_data,
+        refund_request,
+        conn,
+    )
+}
+
+pub fn request_submitted(config: &Config, refund_request: &RefundRequest, conn: &PgConnection) -> Result<(), ApiError> {
+    let event = refund_request.event(conn)?;
+    let organization = event.organization(conn)?;
+    let recipients = organization
+        .users(None, conn)?
+        .into_iter()
+        .filter(|(organization_user, _)| {
+            organization_user.role.contains(&Roles::OrgOwner) || organization_user.role.contains(&Roles::OrgAdmin)
+        })
+        .filter_map(|(_, user)| user.email);
+
+    for email in recipients {
+        let source = CommAddress::from(config.communication_default_source_email.clone());
+        let destinations = CommAddress::from(email);
+        let title = format!("{}: Refund requested for {}", SITE_NAME, event.name);
+        let template_id = config.email_templates.refund_request_submitted.to_string();
+        let mut template_data = TemplateData::new();
+        template_data.insert("organization_name".to_string(), organization.name.clone());
+        insert_refund_request_template_data(&mut template_data, &event, refund_request);
+
+        queue(
+            title,
+            source,
+            destinations,
+            template_id,
+            template_data,
+            refund_request,
+            conn,
+        )?;
+    }
+
+    Ok(())
+}
+
+pub fn request_denied(
+    config: &Config,
+    user: &User,
+    refund_request: &RefundRequest,
+    conn: &PgConnection,
+) -> Result<(), ApiError> {
+    let email = match user.email.clone() {
+        Some(email) => email,
+        None => return Ok(()),
+    };
+    let event = refund_request.event(conn)?;
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let title = format!("{}: Refund request for {} was declined", SITE_NAME, event.name);
+    let template_id = config.email_templates.refund_request_denied.to_string();
+    let mut template_data = TemplateData::new();
+    template_data.insert("name".to_string(), user.full_name());
+    template_data.insert(
+        "response_note".to_string(),
+        refund_request.response_note.clone().unwrap_or_default(),
+    );
+    insert_refund_request_template_data(&mut template_data, &event, refund_request);
+
+    queue(
+        title,
+        source,
+        destinations,
+        template_id,
+        template_data,
+        refund_request,
+        conn,
+    )
+}
+
+fn insert_refund_request_template_data(
+    template_data: &mut TemplateData,
+    event: &Event,
+    refund_request: &RefundRequest,
+) {
+    template_data.insert("refund_request_id".to_string(), refund_request.id.to_string());
+    template_data.insert("order_id".to_string(), refund_request.order_id.to_string());
+    template_data.insert(
+        "order_number".to_string(),
+        Order::parse_order_number(refund_request.order_id),
+    );
+    template_data.insert("event_name".to_string(), event.name.clone());
+    template_data.insert(
+        "ticket_count".to_string(),
+        refund_request.ticket_instance_ids.len().to_string(),
+    );
+    template_data.insert("reason".to_string(), refund_request.reason.clone());
+}
+
+fn queue(
+    title: String,
+    source: CommAddress,
+    destinations: CommAddress,
+    template_id: String,
+    template_data: TemplateData,
+    refund_request: &RefundRequest,
+    conn: &PgConnection,
+) -> Result<(), ApiError> {
+    let mut communication = Communication::new(
+        CommunicationType::EmailTemplate,
+        title,
+        None,
+        Some(source),
+        destinations,
+        Some(template_id),
+        Some(vec![template_data]),
+        Some(vec!["refund_requests"]),
+        None,
+    );
+    communication.main_table = Some(Tables::Orders);
+    communication.main_table_id = Some(refund_request.order_id);
+    communication.queue(conn)?;
+    Ok(())
+}
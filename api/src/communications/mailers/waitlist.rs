@@ -0,0 +1,50 @@
+use crate::communications::mailers::insert_event_template_data;
+use crate::config::Config;
+use crate::errors::*;
+use crate::SITE_NAME;
+use db::models::*;
+use diesel::PgConnection;
+
+pub fn offer_made(config: &Config, waitlist_entry: &WaitlistEntry, conn: &PgConnection) -> Result<(), ApiError> {
+    let user = waitlist_entry.user(conn)?;
+    let email = match user.email.clone() {
+        Some(email) => email,
+        None => return Ok(()),
+    };
+    let ticket_type = waitlist_entry.ticket_type(conn)?;
+    let event = ticket_type.event(conn)?;
+
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let title = format!("{}: Tickets are available for {}", SITE_NAME, event.name);
+    let template_id = config.email_templates.waitlist_offer.to_string();
+    let mut template_data = TemplateData::new();
+    template_data.insert("name".to_string(), user.full_name());
+    template_data.insert("ticket_type_name".to_string(), ticket_type.name.clone());
+    template_data.insert("quantity".to_string(), waitlist_entry.quantity.to_string());
+    if let Some(offer_expires_at) = waitlist_entry.offer_expires_at {
+        template_data.insert(
+            "offer_expires_at".to_string(),
+            offer_expires_at.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        );
+    }
+    template_data.insert("checkout_link".to_string(), format!("{}/cart", config.front_end_url));
+    insert_event_template_data(&mut template_data, &event, conn)?;
+
+    let mut communication = Communication::new(
+        CommunicationType::EmailTemplate,
+        title,
+        None,
+        Some(source),
+        destinations,
+        Some(template_id),
+        Some(vec![template_data]),
+        Some(vec!["waitlist", "waitlist_offer"]),
+        None,
+    );
+    communication.main_table = Some(Tables::WaitlistEntries);
+    communication.main_table_id = Some(waitlist_entry.id);
+    communication.queue(conn)?;
+
+    Ok(())
+}
@@ -4,6 +4,7 @@ use crate::SITE_NAME;
 use chrono::Duration;
 use db::models::{EmailProvider, Environment};
 use db::utils::errors::EnumParseError;
+use db::utils::signing;
 use dotenv::dotenv;
 use itertools::Itertools;
 use std::env;
@@ -44,6 +45,11 @@ pub struct Config {
     pub block_external_comms: bool,
     pub primary_currency: String,
     pub stripe_secret_key: String,
+    pub stripe_base_url: String,
+    pub stripe_use_payment_intents: bool,
+    pub stripe_webhook_secret: Option<String>,
+    pub mock_payments_use_payment_page: bool,
+    pub offline_scanning_private_key: Option<String>,
     pub token_issuer: Box<DefaultTokenIssuer>,
     pub tari_client: Box<dyn TariClient + Send + Sync>,
     pub communication_default_source_email: String,
@@ -104,12 +110,18 @@ pub struct SharetribeConfig {
 
 #[derive(Clone)]
 pub struct EmailTemplates {
+    pub chargeback_received: EmailTemplate,
     pub custom_broadcast: EmailTemplate,
+    pub event_rescheduled: EmailTemplate,
     pub org_invite: EmailTemplate,
     pub password_reset: EmailTemplate,
+    pub refund_request_denied: EmailTemplate,
+    pub refund_request_received: EmailTemplate,
+    pub refund_request_submitted: EmailTemplate,
     pub ticket_count_report: EmailTemplate,
     pub resend_download_link: EmailTemplate,
     pub user_registered_magic_link: EmailTemplate,
+    pub waitlist_offer: EmailTemplate,
 }
 
 #[derive(Clone, Deserialize, Serialize)]
@@ -177,12 +189,18 @@ const CLIENT_CACHE_PERIOD: &str = "CLIENT_CACHE_PERIOD";
 const READONLY_DATABASE_URL: &str = "READONLY_DATABASE_URL";
 const DOMAIN: &str = "DOMAIN";
 const EMAIL_ONLY_REGISTRATION_ALLOWED: &str = "EMAIL_ONLY_REGISTRATION_ALLOWED";
+const EMAIL_TEMPLATES_CHARGEBACK_RECEIVED: &str = "EMAIL_TEMPLATES_CHARGEBACK_RECEIVED";
 const EMAIL_TEMPLATES_CUSTOM_BROADCAST: &str = "EMAIL_TEMPLATES_CUSTOM_BROADCAST";
+const EMAIL_TEMPLATES_EVENT_RESCHEDULED: &str = "EMAIL_TEMPLATES_EVENT_RESCHEDULED";
 const EMAIL_TEMPLATES_ORG_INVITE: &str = "EMAIL_TEMPLATES_ORG_INVITE";
 const EMAIL_TEMPLATES_PASSWORD_RESET: &str = "EMAIL_TEMPLATES_PASSWORD_RESET";
+const EMAIL_TEMPLATES_REFUND_REQUEST_DENIED: &str = "EMAIL_TEMPLATES_REFUND_REQUEST_DENIED";
+const EMAIL_TEMPLATES_REFUND_REQUEST_RECEIVED: &str = "EMAIL_TEMPLATES_REFUND_REQUEST_RECEIVED";
+const EMAIL_TEMPLATES_REFUND_REQUEST_SUBMITTED: &str = "EMAIL_TEMPLATES_REFUND_REQUEST_SUBMITTED";
 const EMAIL_TEMPLATES_TICKET_COUNT_REPORT: &str = "EMAIL_TEMPLATES_TICKET_COUNT_REPORT";
 const EMAIL_TEMPLATES_RESEND_DOWNLOAD_LINK: &str = "EMAIL_TEMPLATES_RESEND_DOWNLOAD_LINK";
 const EMAIL_TEMPLATES_USER_REGISTERED_MAGIC_LINK: &str = "EMAIL_TEMPLATES_USER_REGISTERED_MAGIC_LINK";
+const EMAIL_TEMPLATES_WAITLIST_OFFER: &str = "EMAIL_TEMPLATES_WAITLIST_OFFER";
 const ENVIRONMENT: &str = "ENVIRONMENT";
 const FACEBOOK_APP_ID: &str = "FACEBOOK_APP_ID";
 const FACEBOOK_APP_SECRET: &str = "FACEBOOK_APP_SECRET";
@@ -193,6 +211,11 @@ const API_BASE_URL: &str = "API_BASE_URL";
 const GOOGLE_RECAPTCHA_SECRET_KEY: &str = "GOOGLE_RECAPTCHA_SECRET_KEY";
 const PRIMARY_CURRENCY: &str = "PRIMARY_CURRENCY";
 const STRIPE_SECRET_KEY: &str = "STRIPE_SECRET_KEY";
+const STRIPE_BASE_URL: &str = "STRIPE_BASE_URL";
+const STRIPE_USE_PAYMENT_INTENTS: &str = "STRIPE_USE_PAYMENT_INTENTS";
+const STRIPE_WEBHOOK_SECRET: &str = "STRIPE_WEBHOOK_SECRET";
+const MOCK_PAYMENTS_USE_PAYMENT_PAGE: &str = "MOCK_PAYMENTS_USE_PAYMENT_PAGE";
+const OFFLINE_SCANNING_PRIVATE_KEY: &str = "OFFLINE_SCANNING_PRIVATE_KEY";
 const STATIC_FILE_PATH: &str = "STATIC_FILE_PATH";
 const TARI_URL: &str = "TARI_URL";
 const TEST_DATABASE_URL: &str = "TEST_DATABASE_URL";
@@ -336,6 +359,26 @@ impl Config {
 
         let primary_currency = env::var(&PRIMARY_CURRENCY).unwrap_or_else(|_| "usd".to_string());
         let stripe_secret_key = env::var(&STRIPE_SECRET_KEY).unwrap_or_else(|_| "<stripe not enabled>".to_string());
+        let stripe_base_url = env::var(&STRIPE_BASE_URL).unwrap_or_else(|_| "https://api.stripe.com".to_string());
+        let stripe_use_payment_intents = env::var(&STRIPE_USE_PAYMENT_INTENTS)
+            .unwrap_or("false".to_string())
+            .parse()
+            .expect(&format!("{} is not a valid boolean value", STRIPE_USE_PAYMENT_INTENTS));
+        let stripe_webhook_secret = env::var(&STRIPE_WEBHOOK_SECRET).ok();
+        let mock_payments_use_payment_page = env::var(&MOCK_PAYMENTS_USE_PAYMENT_PAGE)
+            .unwrap_or("false".to_string())
+            .parse()
+            .expect(&format!(
+                "{} is not a valid boolean value",
+                MOCK_PAYMENTS_USE_PAYMENT_PAGE
+            ));
+        let offline_scanning_private_key = env::var(&OFFLINE_SCANNING_PRIVATE_KEY).ok().map(|key| {
+            signing::public_key(&key).expect(&format!(
+                "{} is not a valid Ed25519 private key",
+                OFFLINE_SCANNING_PRIVATE_KEY
+            ));
+            key
+        });
 
         let token_issuer = Box::new(DefaultTokenIssuer::new(
             get_env_var(TOKEN_SECRET),
@@ -390,12 +433,18 @@ impl Config {
 
         let email_only_registration_allowed = get_env_var(EMAIL_ONLY_REGISTRATION_ALLOWED).parse().unwrap();
         let email_templates = EmailTemplates {
+            chargeback_received: get_env_var(EMAIL_TEMPLATES_CHARGEBACK_RECEIVED).parse().unwrap(),
             custom_broadcast: get_env_var(EMAIL_TEMPLATES_CUSTOM_BROADCAST).parse().unwrap(),
+            event_rescheduled: get_env_var(EMAIL_TEMPLATES_EVENT_RESCHEDULED).parse().unwrap(),
             org_invite: get_env_var(EMAIL_TEMPLATES_ORG_INVITE).parse().unwrap(),
             password_reset: get_env_var(EMAIL_TEMPLATES_PASSWORD_RESET).parse().unwrap(),
+            refund_request_denied: get_env_var(EMAIL_TEMPLATES_REFUND_REQUEST_DENIED).parse().unwrap(),
+            refund_request_received: get_env_var(EMAIL_TEMPLATES_REFUND_REQUEST_RECEIVED).parse().unwrap(),
+            refund_request_submitted: get_env_var(EMAIL_TEMPLATES_REFUND_REQUEST_SUBMITTED).parse().unwrap(),
             ticket_count_report: get_env_var(EMAIL_TEMPLATES_TICKET_COUNT_REPORT).parse().unwrap(),
             resend_download_link: get_env_var(EMAIL_TEMPLATES_RESEND_DOWNLOAD_LINK).parse().unwrap(),
             user_registered_magic_link: get_env_var(EMAIL_TEMPLATES_USER_REGISTERED_MAGIC_LINK).parse().unwrap(),
+            waitlist_offer: get_env_var(EMAIL_TEMPLATES_WAITLIST_OFFER).parse().unwrap(),
         };
 
         let customer_io_base_url = get_env_var(CUSTOMER_IO_BASE_URL);
@@ -527,6 +576,11 @@ impl Config {
             block_external_comms,
             primary_currency,
             stripe_secret_key,
+            stripe_base_url,
+            stripe_use_payment_intents,
+            stripe_webhook_secret,
+            mock_payments_use_payment_page,
+            offline_scanning_private_key,
             token_issuer,
             front_end_url,
             tari_client,
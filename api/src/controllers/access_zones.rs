@@ -0,0 +1,152 @@
+use crate::auth::user::User;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::models::{AccessZoneUserPathParameters, PathParameters};
+use actix_web::{web::Path, HttpResponse};
+use db::models::User as DbUser;
+use db::models::*;
+use diesel::PgConnection;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct CreateAccessZoneRequest {
+    pub name: String,
+    #[serde(default)]
+    pub ticket_type_ids: Vec<Uuid>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateAccessZoneRequest {
+    #[serde(flatten)]
+    pub attributes: AccessZoneEditableAttributes,
+    pub ticket_type_ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Deserialize)]
+pub struct AssignAccessZoneUserRequest {
+    pub user_id: Uuid,
+}
+
+#[derive(Serialize)]
+pub struct DisplayAccessZone {
+    #[serde(flatten)]
+    pub access_zone: AccessZone,
+    pub ticket_type_ids: Vec<Uuid>,
+    pub user_ids: Vec<Uuid>,
+}
+
+impl DisplayAccessZone {
+    fn from_access_zone(access_zone: AccessZone, conn: &PgConnection) -> Result<Self, ApiError> {
+        let ticket_type_ids = access_zone.ticket_types(conn)?.iter().map(|tt| tt.id).collect();
+        let user_ids = access_zone.users(conn)?.iter().map(|u| u.user_id).collect();
+        Ok(DisplayAccessZone {
+            access_zone,
+            ticket_type_ids,
+            user_ids,
+        })
+    }
+}
+
+pub async fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::RedeemTicket,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    let access_zones = AccessZone::find_for_event(event.id, connection)?
+        .into_iter()
+        .map(|z| DisplayAccessZone::from_access_zone(z, connection))
+        .collect::<Result<Vec<DisplayAccessZone>, ApiError>>()?;
+
+    Ok(HttpResponse::Ok().json(&access_zones))
+}
+
+pub async fn create(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<CreateAccessZoneRequest>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    let json = json.into_inner();
+    let access_zone = AccessZone::create(event.id, json.name).commit(Some(user.id()), connection)?;
+    access_zone.set_ticket_types(&json.ticket_type_ids, Some(user.id()), connection)?;
+
+    Ok(HttpResponse::Created().json(&DisplayAccessZone::from_access_zone(access_zone, connection)?))
+}
+
+pub async fn update(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<UpdateAccessZoneRequest>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let access_zone = AccessZone::find(path.id, connection)?;
+    let event = Event::find(access_zone.event_id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    let json = json.into_inner();
+    let access_zone = access_zone.update(json.attributes, Some(user.id()), connection)?;
+    if let Some(ticket_type_ids) = json.ticket_type_ids {
+        access_zone.set_ticket_types(&ticket_type_ids, Some(user.id()), connection)?;
+    }
+
+    Ok(HttpResponse::Ok().json(&DisplayAccessZone::from_access_zone(access_zone, connection)?))
+}
+
+pub async fn assign_user(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<AssignAccessZoneUserRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let access_zone = AccessZone::find(path.id, connection)?;
+    let event = Event::find(access_zone.event_id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    let scanner = DbUser::find(json.user_id, connection)?;
+    access_zone.assign_user(&scanner, Some(user.id()), connection)?;
+
+    Ok(HttpResponse::Ok().json(&DisplayAccessZone::from_access_zone(access_zone, connection)?))
+}
+
+pub async fn unassign_user(
+    (connection, path, user): (Connection, Path<AccessZoneUserPathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let access_zone = AccessZone::find(path.id, connection)?;
+    let event = Event::find(access_zone.event_id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    access_zone.unassign_user(path.user_id, Some(user.id()), connection)?;
+
+    Ok(HttpResponse::Ok().json(&DisplayAccessZone::from_access_zone(access_zone, connection)?))
+}
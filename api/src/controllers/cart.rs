@@ -6,6 +6,8 @@ use crate::extractors::*;
 use crate::helpers::application;
 use crate::models::*;
 use crate::payments::AuthThenCompletePaymentBehavior;
+use crate::payments::PaymentIntentBehavior;
+use crate::payments::PaymentIntentStatus;
 use crate::payments::PaymentProcessor;
 use crate::payments::PaymentProcessorBehavior;
 use crate::payments::RedirectToPaymentPageBehavior;
@@ -19,6 +21,7 @@ use actix_web::{
 use db::models::TicketType as Dbticket_types;
 use db::models::User as DbUser;
 use db::models::*;
+use db::utils::dates;
 use db::utils::errors::Optional;
 use db::utils::rand::random_alpha_string;
 use diesel::pg::PgConnection;
@@ -36,11 +39,23 @@ pub struct CartItem {
     pub quantity: u32,
     #[serde(default, deserialize_with = "deserialize_unless_blank")]
     pub redemption_code: Option<String>,
+    #[serde(default)]
+    pub seat_ids: Option<Vec<Uuid>>,
+    #[serde(default)]
+    pub event_time_slot_id: Option<Uuid>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CartProductItem {
+    pub product_variant_id: Uuid,
+    pub quantity: u32,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct UpdateCartRequest {
     pub items: Vec<CartItem>,
+    #[serde(default)]
+    pub products: Vec<CartProductItem>,
     pub box_office_pricing: Option<bool>,
     pub tracking_data: Option<Value>,
 }
@@ -83,6 +98,8 @@ pub async fn update_cart(
     }
 
     cart.update_quantities(user.id(), &order_items, box_office_pricing, false, connection)?;
+    assign_seats_and_time_slots(&mut cart, &json.items, connection)?;
+    update_products(&mut cart, user.id(), &json.products, false, connection)?;
 
     cart.set_browser_data(request_info.user_agent.clone(), false, connection)?;
     cart.set_tracking_data(json.tracking_data.clone(), Some(user.id()), connection)?;
@@ -90,6 +107,88 @@ pub async fn update_cart(
     Ok(HttpResponse::Ok().json(Order::find(cart.id, connection)?.for_display(None, user.id(), connection)?))
 }
 
+fn assign_seats_and_time_slots(
+    cart: &mut Order,
+    items: &[CartItem],
+    connection: &PgConnection,
+) -> Result<(), ApiError> {
+    let mut ticket_type_ids: Vec<Uuid> = items
+        .iter()
+        .filter(|i| i.quantity > 0)
+        .map(|i| i.ticket_type_id)
+        .collect();
+    ticket_type_ids.sort();
+    ticket_type_ids.dedup();
+
+    for ticket_type_id in ticket_type_ids {
+        let seat_ids: Vec<Uuid> = items
+            .iter()
+            .filter(|i| i.ticket_type_id == ticket_type_id)
+            .flat_map(|i| i.seat_ids.clone().unwrap_or_default())
+            .collect();
+        cart.assign_seats(ticket_type_id, &seat_ids, connection)?;
+
+        let event_time_slot_id = items
+            .iter()
+            .filter(|i| i.ticket_type_id == ticket_type_id)
+            .find_map(|i| i.event_time_slot_id);
+        cart.assign_time_slot(ticket_type_id, event_time_slot_id, connection)?;
+    }
+
+    Ok(())
+}
+
+fn update_products(
+    cart: &mut Order,
+    user_id: Uuid,
+    products: &[CartProductItem],
+    remove_others: bool,
+    connection: &PgConnection,
+) -> Result<(), ApiError> {
+    if remove_others {
+        for item in cart.items(connection)? {
+            if let Some(product_variant_id) = item.product_variant_id {
+                if !products.iter().any(|p| p.product_variant_id == product_variant_id) {
+                    cart.update_product_quantity(user_id, product_variant_id, 0, connection)?;
+                }
+            }
+        }
+    }
+
+    for product in products {
+        cart.update_product_quantity(user_id, product.product_variant_id, product.quantity, connection)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UpdateCartPassRequest {
+    pub pass_id: Uuid,
+    pub quantity: u32,
+    #[serde(default)]
+    pub event_time_slot_ids: Vec<Uuid>,
+}
+
+pub async fn update_pass(
+    (connection, json, user): (Connection, Json<UpdateCartPassRequest>, User),
+) -> Result<HttpResponse, ApiError> {
+    let json = json.into_inner();
+    jlog!(Debug, "Update Cart Pass", {"pass_id": json.pass_id, "quantity": json.quantity, "user_id": user.id()});
+    let connection = connection.get();
+
+    let mut cart = Order::find_or_create_cart(&user.user, connection)?;
+    cart.update_pass_quantity(
+        user.id(),
+        json.pass_id,
+        json.quantity,
+        &json.event_time_slot_ids,
+        connection,
+    )?;
+
+    Ok(HttpResponse::Ok().json(Order::find(cart.id, connection)?.for_display(None, user.id(), connection)?))
+}
+
 pub async fn duplicate(
     (connection, path, user): (Connection, Path<PathParameters>, User),
 ) -> Result<HttpResponse, ApiError> {
@@ -154,6 +253,8 @@ pub async fn replace_cart(
     }
 
     cart.update_quantities(user.id(), &order_items, box_office_pricing, true, connection)?;
+    assign_seats_and_time_slots(&mut cart, &json.items, connection)?;
+    update_products(&mut cart, user.id(), &json.products, true, connection)?;
 
     cart.set_browser_data(request_info.user_agent.clone(), false, connection)?;
     cart.set_tracking_data(json.tracking_data.clone(), Some(user.id()), connection)?;
@@ -173,6 +274,14 @@ pub async fn show((connection, user): (Connection, User)) -> Result<HttpResponse
 pub struct CheckoutCartRequest {
     pub method: PaymentRequest,
     pub tracking_data: Option<serde_json::Value>,
+    /// Applied in order before the payment method, which is charged any remaining balance
+    #[serde(default)]
+    pub gift_card_codes: Vec<String>,
+    #[serde(default)]
+    pub use_store_credit: bool,
+    /// Pays a deposit now and the remainder in scheduled charges to the saved payment method
+    #[serde(default)]
+    pub installment_plan: bool,
 }
 
 #[derive(Deserialize)]
@@ -206,6 +315,16 @@ pub enum PaymentRequest {
     },
     // Only for 0 amount carts
     Free,
+    /// Several payments each covering part of the order, amounts must add up to the amount due
+    Split {
+        payments: Vec<SplitPaymentRequest>,
+    },
+}
+
+#[derive(Deserialize)]
+pub struct SplitPaymentRequest {
+    pub amount: i64,
+    pub method: PaymentRequest,
 }
 
 pub async fn clear_invalid_items((connection, user): (Connection, User)) -> Result<HttpResponse, ApiError> {
@@ -249,6 +368,20 @@ pub async fn checkout(
 
     order.set_tracking_data(req.tracking_data.clone(), Some(user.id()), connection.get())?;
 
+    // A plan left behind by an earlier checkout attempt would otherwise reduce the amount due
+    InstallmentPlan::remove_pending_for_order(order.id, connection.get())?;
+    if req.installment_plan {
+        let supported_method = match req.method {
+            PaymentRequest::Card { .. } | PaymentRequest::PaymentMethod { .. } => true,
+            _ => false,
+        };
+        if !supported_method || !req.gift_card_codes.is_empty() || req.use_store_credit {
+            return application::unprocessable(
+                "Could not complete this cart because installment plans can only be paid by card",
+            );
+        }
+    }
+
     let order_items = order.items(connection.get())?;
 
     //Assemble token ids and ticket instance ids for each asset in the order
@@ -275,10 +408,56 @@ pub async fn checkout(
         }
     }
 
+    if !req.gift_card_codes.is_empty() || req.use_store_credit {
+        info!("CART: Applying gift cards and store credit");
+        checkout_stored_value(
+            &connection,
+            &mut order,
+            &req.gift_card_codes,
+            req.use_store_credit,
+            &user,
+        )?;
+        if order.status == OrderStatus::Paid {
+            let conn = connection.get();
+            order.set_browser_data(request_info.user_agent.clone(), true, conn)?;
+            return Ok(HttpResponse::Ok().json(json!(order.for_display(None, user.id(), conn)?)));
+        }
+    }
+
     let payment_response = match &req.method {
+        PaymentRequest::Split { payments } => {
+            info!("CART: Received split payment");
+            checkout_split(&connection, &mut order, payments, &user, &state, &request_info).await?
+        }
+        method => {
+            checkout_payment(
+                &connection,
+                &mut order,
+                method,
+                req.installment_plan,
+                &user,
+                &state,
+                &request_info,
+            )
+            .await?
+        }
+    };
+    Ok(payment_response)
+}
+
+async fn checkout_payment(
+    connection: &Connection,
+    order: &mut Order,
+    method: &PaymentRequest,
+    installment_plan: bool,
+    user: &User,
+    state: &Data<AppState>,
+    request_info: &RequestInfo,
+) -> Result<HttpResponse, ApiError> {
+    let payment_response = match method {
         PaymentRequest::Free => {
             info!("CART: Received checkout for free cart");
-            checkout_free(&connection, order, &user, &request_info)?
+            checkout_free(connection, order, user, request_info)?
         }
         PaymentRequest::External {
             reference,
@@ -291,7 +470,7 @@ pub async fn checkout(
         } => {
             info!("CART: Received external payment");
             checkout_external(
-                &connection,
+                connection,
                 order,
                 *external_payment_type,
                 reference.clone(),
@@ -300,8 +479,9 @@ pub async fn checkout(
                 email.clone(),
                 phone.clone(),
                 note.clone(),
-                &user,
-                &request_info,
+                None,
+                user,
+                request_info,
             )?
         }
         PaymentRequest::PaymentMethod { provider } => {
@@ -319,35 +499,35 @@ pub async fn checkout(
             };
 
             checkout_payment_processor(
-                &connection,
-                &mut order,
+                connection,
+                order,
                 None,
-                &user,
-                &state.config.primary_currency,
+                user,
                 provider.clone(),
                 true,
                 false,
                 false,
+                installment_plan,
                 &state.service_locator,
                 &state.config,
-                &request_info,
+                request_info,
             )
             .await?
         }
         PaymentRequest::Provider { provider } => {
             checkout_payment_processor(
-                &connection,
-                &mut order,
+                connection,
+                order,
                 None,
-                &user,
-                &state.config.primary_currency,
+                user,
                 *provider,
                 false,
                 false,
                 false,
+                false,
                 &state.service_locator,
                 &state.config,
-                &request_info,
+                request_info,
             )
             .await?
         }
@@ -358,28 +538,151 @@ pub async fn checkout(
             set_default,
         } => {
             checkout_payment_processor(
-                &connection,
-                &mut order,
-                Some(&token),
-                &user,
-                &state.config.primary_currency,
+                connection,
+                order,
+                Some(token),
+                user,
                 *provider,
                 false,
                 *save_payment_method,
                 *set_default,
+                installment_plan,
                 &state.service_locator,
                 &state.config,
-                &request_info,
+                request_info,
             )
             .await?
         }
+        PaymentRequest::Split { .. } => {
+            return application::unprocessable("Could not complete this cart because split payments cannot be nested");
+        }
     };
     Ok(payment_response)
 }
 
+/// External payments are recorded first so that any card or provider payment, which is charged
+/// the amount still due, is taken last. Only one card or provider payment is supported.
+async fn checkout_split(
+    connection: &Connection,
+    order: &mut Order,
+    payments: &[SplitPaymentRequest],
+    user: &User,
+    state: &Data<AppState>,
+    request_info: &RequestInfo,
+) -> Result<HttpResponse, ApiError> {
+    if order.status != OrderStatus::Draft {
+        return application::unprocessable("Could not complete this cart because it is not in the correct status");
+    }
+    if payments.is_empty() {
+        return application::unprocessable("Could not complete this cart because no payments were provided");
+    }
+    if payments.iter().any(|p| p.amount <= 0) {
+        return application::unprocessable("Could not complete this cart because payment amounts must be positive");
+    }
+    if payments.iter().map(|p| p.amount).sum::<i64>() != order.amount_due(connection.get())? {
+        return application::unprocessable(
+            "Could not complete this cart because the payment amounts do not add up to the amount due",
+        );
+    }
+
+    let (external_payments, processor_payments): (Vec<&SplitPaymentRequest>, Vec<&SplitPaymentRequest>) =
+        payments.iter().partition(|p| match p.method {
+            PaymentRequest::External { .. } => true,
+            _ => false,
+        });
+    if processor_payments.len() > 1 {
+        return application::unprocessable(
+            "Could not complete this cart because only one card or provider payment can be split",
+        );
+    }
+    if processor_payments.iter().any(|p| match p.method {
+        PaymentRequest::Free | PaymentRequest::Split { .. } => true,
+        _ => false,
+    }) {
+        return application::unprocessable("Could not complete this cart because the split payment method is invalid");
+    }
+
+    let mut payment_response = None;
+    for payment in external_payments {
+        if let PaymentRequest::External {
+            reference,
+            external_payment_type,
+            first_name,
+            last_name,
+            email,
+            phone,
+            note,
+        } = &payment.method
+        {
+            info!("CART: Received external payment for split payment");
+            payment_response = Some(checkout_external(
+                connection,
+                order,
+                *external_payment_type,
+                reference.clone(),
+                first_name.to_string(),
+                last_name.to_string(),
+                email.clone(),
+                phone.clone(),
+                note.clone(),
+                Some(payment.amount),
+                user,
+                request_info,
+            )?);
+        }
+    }
+
+    for payment in processor_payments {
+        payment_response =
+            Some(checkout_payment(connection, order, &payment.method, false, user, state, request_info).await?);
+    }
+
+    match payment_response {
+        Some(payment_response) => Ok(payment_response),
+        None => application::unprocessable("Could not complete this cart because no payments were provided"),
+    }
+}
+
+fn checkout_stored_value(
+    conn: &Connection,
+    order: &mut Order,
+    gift_card_codes: &[String],
+    use_store_credit: bool,
+    user: &User,
+) -> Result<(), ApiError> {
+    let conn = conn.get();
+    if order.status != OrderStatus::Draft {
+        return Err(application::unprocessable::<HttpResponse>(
+            "Could not complete this cart because it is not in the correct status",
+        )
+        .unwrap_err());
+    }
+
+    for code in gift_card_codes {
+        if order.amount_due(conn)? == 0 {
+            break;
+        }
+        let gift_card = match GiftCard::find_by_code(code, conn).optional()? {
+            Some(gift_card) => gift_card,
+            None => {
+                return Err(
+                    application::unprocessable::<HttpResponse>("Could not find a gift card with this code")
+                        .unwrap_err(),
+                );
+            }
+        };
+        order.add_gift_card_payment(&gift_card, user.id(), conn)?;
+    }
+
+    if use_store_credit && order.amount_due(conn)? > 0 {
+        order.add_store_credit_payment(user.id(), conn)?;
+    }
+    Ok(())
+}
+
 fn checkout_free(
     conn: &Connection,
-    order: Order,
+    order: &mut Order,
     user: &User,
     request_info: &RequestInfo,
 ) -> Result<HttpResponse, ApiError> {
@@ -392,7 +695,6 @@ fn checkout_free(
             "Could not use free payment method this cart because it has a total greater than zero",
         );
     }
-    let mut order = order;
     order.add_free_payment(false, user.id(), conn)?;
 
     let mut order = Order::find(order.id, conn)?;
@@ -404,7 +706,7 @@ fn checkout_free(
 // user will not be calling this.
 fn checkout_external(
     conn: &Connection,
-    mut order: Order,
+    order: &mut Order,
     external_payment_type: ExternalPaymentType,
     reference: Option<String>,
     first_name: String,
@@ -412,6 +714,7 @@ fn checkout_external(
     email: Option<String>,
     phone: Option<String>,
     note: Option<String>,
+    amount: Option<i64>,
     user: &User,
     request_info: &RequestInfo,
 ) -> Result<HttpResponse, ApiError> {
@@ -435,35 +738,43 @@ fn checkout_external(
         return application::unprocessable("Could not complete this cart because it is not in the correct status");
     }
 
-    let mut guest: Option<DbUser> = None;
+    // Later legs of a split payment keep the guest assigned by the first leg
+    if amount.is_none() || order.on_behalf_of_user_id.is_none() {
+        let mut guest: Option<DbUser> = None;
 
-    if let Some(ref e) = email {
-        // Guest can be a deleted user
-        guest = DbUser::find_by_email(e, true, conn).optional()?;
-    };
-    if guest.is_none() {
-        if let Some(ref p) = phone {
+        if let Some(ref e) = email {
             // Guest can be a deleted user
-            guest = DbUser::find_by_phone(p, true, conn).optional()?;
+            guest = DbUser::find_by_email(e, true, conn).optional()?;
+        };
+        if guest.is_none() {
+            if let Some(ref p) = phone {
+                // Guest can be a deleted user
+                guest = DbUser::find_by_phone(p, true, conn).optional()?;
+            }
         }
+        let guest = match guest {
+            Some(g) => g,
+            None => DbUser::create_stub(first_name, last_name, email, phone, Some(user.id()), conn)?,
+        };
+        order.set_behalf_of_user(guest, user.id(), conn)?;
     }
-    let guest = match guest {
-        Some(g) => g,
-        None => DbUser::create_stub(first_name, last_name, email, phone, Some(user.id()), conn)?,
-    };
 
     if let Some(note) = note {
         order.create_note(note, user.id(), conn)?;
     }
-    order.set_behalf_of_user(guest, user.id(), conn)?;
-    let total = order.calculate_total(conn)?;
+    let total = match amount {
+        Some(amount) => amount,
+        None => order.amount_due(conn)?,
+    };
 
     if total == 0 {
         order.add_free_payment(true, user.id(), conn)?;
     } else {
         order.add_external_payment(reference, external_payment_type, user.id(), total, conn)?;
     }
-    order.set_browser_data(request_info.user_agent.clone(), true, conn)?;
+    if order.status == OrderStatus::Paid {
+        order.set_browser_data(request_info.user_agent.clone(), true, conn)?;
+    }
 
     let order = Order::find(order.id, conn)?;
     Ok(HttpResponse::Ok().json(json!(order.for_display(None, user.id(), conn)?)))
@@ -474,11 +785,11 @@ async fn checkout_payment_processor(
     order: &mut Order,
     token: Option<&str>,
     auth_user: &User,
-    currency: &str,
     provider: PaymentProviders,
     use_stored_payment: bool,
     save_payment_method: bool,
     set_default: bool,
+    installment_plan: bool,
     service_locator: &ServiceLocator,
     config: &Config,
     request_info: &RequestInfo,
@@ -490,7 +801,7 @@ async fn checkout_payment_processor(
         return application::forbidden("This cart does not belong to you");
     } else if order.status != OrderStatus::Draft {
         return application::unprocessable("Could not complete this cart because it is not in the correct status");
-    } else if order.calculate_total(connection)? == 0 {
+    } else if order.amount_due(connection)? == 0 {
         return application::unprocessable("Could not complete this cart; only paid orders require payment processing");
     }
 
@@ -506,9 +817,61 @@ async fn checkout_payment_processor(
     let client = service_locator.create_payment_processor(provider, &event.organization(connection)?)?;
     match client.behavior() {
         PaymentProcessorBehavior::RedirectToPaymentPage(behavior) => {
+            if installment_plan {
+                return application::unprocessable(
+                    "Could not complete this cart because installment plans are not supported for this payment processor",
+                );
+            }
             return redirect_to_payment_page(&*behavior, &auth_user.user, order, conn.get(), config).await;
         }
+        PaymentProcessorBehavior::PaymentIntent(behavior) => {
+            if installment_plan {
+                return application::unprocessable(
+                    "Could not complete this cart because installment plans are not supported for this payment processor",
+                );
+            }
+            if save_payment_method {
+                return application::unprocessable(
+                    "Could not complete this cart using saved payment methods is not supported for this payment processor",
+                );
+            }
+            let token = if use_stored_payment {
+                info!("CART: Using stored payment");
+                match auth_user.user.payment_method(provider, connection).optional()? {
+                    Some(payment_method) => payment_method.provider,
+                    None => {
+                        return application::unprocessable(
+                            "Could not complete this cart because stored provider does not exist",
+                        );
+                    }
+                }
+            } else {
+                match token {
+                    Some(t) => t.to_string(),
+                    None => {
+                        return application::unprocessable("Could not complete this cart because no token provided");
+                    }
+                }
+            };
+
+            return payment_intent(
+                &*behavior,
+                token,
+                order,
+                auth_user,
+                conn,
+                &*client,
+                config,
+                request_info,
+            )
+            .await;
+        }
         PaymentProcessorBehavior::AuthThenComplete(behavior) => {
+            if installment_plan && !use_stored_payment && !save_payment_method {
+                return application::unprocessable(
+                    "Could not complete this cart because installment plans require a saved payment method",
+                );
+            }
             let token = if use_stored_payment {
                 info!("CART: Using stored payment");
                 match auth_user.user.payment_method(provider, connection).optional()? {
@@ -572,17 +935,13 @@ async fn checkout_payment_processor(
                 }
             };
 
-            return auth_then_complete(
-                &*behavior,
-                token,
-                currency,
-                order,
-                auth_user,
-                conn,
-                &*client,
-                request_info,
-            )
-            .await;
+            if installment_plan {
+                info!("CART: Creating installment plan");
+                let payment_method = auth_user.user.payment_method(provider, connection)?;
+                InstallmentPlan::create_for_order(order, &payment_method, auth_user.id(), connection)?;
+            }
+
+            return auth_then_complete(&*behavior, token, order, auth_user, conn, &*client, request_info).await;
         }
     };
 }
@@ -590,7 +949,6 @@ async fn checkout_payment_processor(
 async fn auth_then_complete(
     client: &dyn AuthThenCompletePaymentBehavior,
     token: String,
-    currency: &str,
     order: &mut Order,
     auth_user: &User,
     conn: &Connection,
@@ -599,12 +957,12 @@ async fn auth_then_complete(
 ) -> Result<HttpResponse, ApiError> {
     let connection = conn.get();
     info!("CART: Auth'ing to payment provider");
-    let amount = order.calculate_total(connection)?;
+    let amount = order.amount_due(connection)?;
     let auth_result = client
         .auth(
             &token,
             amount,
-            currency,
+            &order.currency,
             SITE_NAME,
             order.purchase_metadata(connection)?,
         )
@@ -647,6 +1005,93 @@ async fn auth_then_complete(
     }
 }
 
+async fn payment_intent(
+    client: &dyn PaymentIntentBehavior,
+    token: String,
+    order: &mut Order,
+    auth_user: &User,
+    conn: &Connection,
+    payment_processor: &dyn PaymentProcessor,
+    config: &Config,
+    request_info: &RequestInfo,
+) -> Result<HttpResponse, ApiError> {
+    let connection = conn.get();
+    info!("CART: Creating payment intent with payment provider");
+    let amount = order.amount_due(connection)?;
+    let intent = client
+        .create_payment_intent(
+            &token,
+            amount,
+            &order.currency,
+            SITE_NAME,
+            &format!("{}/payments/intents/callback/{}", config.api_base_url, order.id),
+            order.purchase_metadata(connection)?,
+        )
+        .await?;
+
+    jlog!(Info, "Payment intent created", {"order_id": order.id, "payment_intent_id": &intent.id, "status": format!("{:?}", intent.status)});
+
+    let status = match intent.status {
+        PaymentIntentStatus::Succeeded => PaymentStatus::Authorized,
+        PaymentIntentStatus::RequiresAction | PaymentIntentStatus::Processing => PaymentStatus::Requested,
+        PaymentIntentStatus::Failed => {
+            return application::unprocessable("Could not complete this cart because the payment was declined");
+        }
+    };
+
+    info!("CART: Saving payment to order");
+    let payment = match order.add_credit_card_payment(
+        auth_user.id(),
+        amount,
+        client.payment_provider(),
+        intent.id.clone(),
+        status,
+        intent.to_json()?,
+        connection,
+    ) {
+        Ok(p) => p,
+        Err(e) => {
+            if intent.status == PaymentIntentStatus::Succeeded {
+                payment_processor.refund(&intent.id).await?;
+            }
+            return Err(e.into());
+        }
+    };
+
+    match intent.status {
+        PaymentIntentStatus::Succeeded => {
+            info!("CART: Completing payment on order");
+            if let Err(e) = payment.mark_complete(intent.to_json()?, Some(auth_user.id()), connection) {
+                payment_processor.refund(&intent.id).await?;
+                return Err(e.into());
+            }
+        }
+        PaymentIntentStatus::RequiresAction => {
+            // The customer must authenticate with their bank before the payment can succeed,
+            // the result is confirmed via the callback or the payment provider's webhook
+            let redirect_url = match intent.redirect_url {
+                Some(ref url) => url.clone(),
+                None => {
+                    return application::unprocessable(
+                        "Could not complete this cart because the payment provider did not supply a redirect",
+                    );
+                }
+            };
+            order.add_checkout_url(
+                auth_user.id(),
+                redirect_url,
+                dates::now().add_minutes(30).finish(),
+                connection,
+            )?;
+        }
+        _ => payment.mark_pending_ipn(Some(auth_user.id()), connection)?,
+    }
+
+    let mut order = Order::find(order.id, connection)?;
+    order.set_browser_data(request_info.user_agent.clone(), true, connection)?;
+    Ok(HttpResponse::Ok().json(json!(order.for_display(None, auth_user.id(), connection)?)))
+}
+
 async fn redirect_to_payment_page(
     client: &dyn RedirectToPaymentPageBehavior,
     user: &DbUser,
@@ -658,11 +1103,12 @@ async fn redirect_to_payment_page(
         return application::unprocessable("User must have an email to check out");
     }
 
-    let amount = order.calculate_total(conn)?;
+    let amount = order.amount_due(conn)?;
 
     let email = user.email.as_ref().unwrap().to_string();
 
-    let ipn = Some(format!("{}/ipns/globee", config.api_base_url));
+    let provider_name = client.payment_provider().to_string().to_lowercase();
+    let ipn = Some(format!("{}/ipns/{}", config.api_base_url, provider_name));
 
     let nonce = random_alpha_string(12);
     let response = client
@@ -686,7 +1132,7 @@ async fn redirect_to_payment_page(
 
     order.add_checkout_url(user.id, response.redirect_url.clone(), response.expires_at, conn)?;
 
-    let external_reference = format!("globee-{}", response.id);
+    let external_reference = format!("{}-{}", provider_name, response.id);
 
     order.add_provider_payment(
         Some(external_reference),
@@ -0,0 +1,74 @@
+use crate::auth::user::User;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::models::PathParameters;
+use actix_web::{web::Path, HttpResponse};
+use chrono::NaiveDateTime;
+use db::models::*;
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct NewDisputeRequest {
+    pub payment_id: Uuid,
+    pub amount_in_cents: i64,
+    pub reason: Option<String>,
+    pub external_reference: Option<String>,
+    pub evidence_due_by: Option<NaiveDateTime>,
+    #[serde(default)]
+    pub nullify_tickets: bool,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ResolveDisputeRequest {
+    pub outcome: DisputeOutcome,
+}
+
+pub async fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let order = Order::find(path.id, connection)?;
+    user.requires_scope_for_order(Scopes::OrderRead, &order, connection)?;
+    Ok(HttpResponse::Ok().json(Dispute::find_for_order(order.id, connection)?))
+}
+
+pub async fn create(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<NewDisputeRequest>, User),
+) -> Result<HttpResponse, ApiError> {
+    user.requires_scope(Scopes::DisputeWrite)?;
+    let connection = connection.get();
+    let order = Order::find(path.id, connection)?;
+    let json = json.into_inner();
+    let dispute = Dispute::create(
+        order.id,
+        json.payment_id,
+        json.amount_in_cents,
+        json.reason,
+        json.external_reference,
+        json.evidence_due_by,
+        Some(user.id()),
+    )
+    .commit(json.nullify_tickets, connection)?;
+    Ok(HttpResponse::Created().json(&dispute))
+}
+
+pub async fn update(
+    (connection, path, attributes, user): (Connection, Path<PathParameters>, Json<DisputeEditableAttributes>, User),
+) -> Result<HttpResponse, ApiError> {
+    user.requires_scope(Scopes::DisputeWrite)?;
+    let connection = connection.get();
+    let dispute = Dispute::find(path.id, connection)?;
+    let dispute = dispute.update(attributes.into_inner(), Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(&dispute))
+}
+
+pub async fn resolve(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<ResolveDisputeRequest>, User),
+) -> Result<HttpResponse, ApiError> {
+    user.requires_scope(Scopes::DisputeWrite)?;
+    let connection = connection.get();
+    let dispute = Dispute::find(path.id, connection)?;
+    let dispute = dispute.resolve(json.outcome, Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(&dispute))
+}
@@ -0,0 +1,87 @@
+use crate::auth::user::User;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::helpers::application;
+use crate::models::EventTicketPathParameters;
+use actix_web::{web::Path, HttpResponse};
+use db::models::*;
+
+pub async fn show(
+    (connection, path, user): (Connection, Path<EventTicketPathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.event_id, connection)?;
+    let organization = event.organization(connection)?;
+    user.requires_scope_for_organization_event(Scopes::TicketTypeRead, &organization, &event, connection)?;
+
+    let ticket_type = TicketType::find(path.ticket_type_id, connection)?;
+    if ticket_type.event_id != event.id {
+        return application::not_found();
+    }
+
+    match DynamicPricingStrategy::find_by_ticket_type_id(ticket_type.id, connection)? {
+        Some(strategy) => Ok(HttpResponse::Ok().json(&strategy)),
+        None => application::not_found(),
+    }
+}
+
+pub async fn update(
+    (connection, path, json, user): (
+        Connection,
+        Path<EventTicketPathParameters>,
+        Json<DynamicPricingStrategyEditableAttributes>,
+        User,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.event_id, connection)?;
+    let organization = event.organization(connection)?;
+    user.requires_scope_for_organization_event(Scopes::TicketTypeWrite, &organization, &event, connection)?;
+
+    let ticket_type = TicketType::find(path.ticket_type_id, connection)?;
+    if ticket_type.event_id != event.id {
+        return application::not_found();
+    }
+
+    let attributes = json.into_inner();
+    let strategy = match DynamicPricingStrategy::find_by_ticket_type_id(ticket_type.id, connection)? {
+        Some(strategy) => strategy.update(attributes, Some(user.id()), connection)?,
+        None => {
+            let (floor_price_in_cents, ceiling_price_in_cents) =
+                match (attributes.floor_price_in_cents, attributes.ceiling_price_in_cents) {
+                    (Some(floor_price_in_cents), Some(ceiling_price_in_cents)) => {
+                        (floor_price_in_cents, ceiling_price_in_cents)
+                    }
+                    _ => return application::unprocessable("Floor and ceiling prices are required"),
+                };
+            let strategy = DynamicPricingStrategy::create(
+                ticket_type.id,
+                floor_price_in_cents,
+                ceiling_price_in_cents,
+                attributes
+                    .adjustment_percent
+                    .unwrap_or(DYNAMIC_PRICING_DEFAULT_ADJUSTMENT_PERCENT),
+                attributes
+                    .adjustment_interval_minutes
+                    .unwrap_or(DYNAMIC_PRICING_DEFAULT_ADJUSTMENT_INTERVAL_MINUTES),
+            )
+            .commit(Some(user.id()), connection)?;
+
+            if attributes.is_active == Some(false) {
+                strategy.update(
+                    DynamicPricingStrategyEditableAttributes {
+                        is_active: Some(false),
+                        ..Default::default()
+                    },
+                    Some(user.id()),
+                    connection,
+                )?
+            } else {
+                strategy
+            }
+        }
+    };
+
+    Ok(HttpResponse::Ok().json(&strategy))
+}
@@ -0,0 +1,115 @@
+use crate::auth::user::User;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::models::PathParameters;
+use actix_web::{web::Path, HttpResponse};
+use db::models::*;
+use diesel::PgConnection;
+
+#[derive(Serialize)]
+pub struct DisplayEventRefundBatch {
+    #[serde(flatten)]
+    pub event_refund_batch: EventRefundBatch,
+    pub summary: EventRefundBatchSummary,
+    pub failed_orders: Vec<EventRefundBatchOrder>,
+}
+
+impl DisplayEventRefundBatch {
+    fn from_event_refund_batch(event_refund_batch: EventRefundBatch, conn: &PgConnection) -> Result<Self, ApiError> {
+        let summary = event_refund_batch.summary(conn)?;
+        let failed_orders = event_refund_batch
+            .orders(conn)?
+            .into_iter()
+            .filter(|o| o.status == EventRefundBatchOrderStatus::Failed)
+            .collect();
+        Ok(DisplayEventRefundBatch {
+            event_refund_batch,
+            summary,
+            failed_orders,
+        })
+    }
+}
+
+pub async fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::OrderRefund,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    let event_refund_batches = EventRefundBatch::find_for_event(event.id, connection)?
+        .into_iter()
+        .map(|b| DisplayEventRefundBatch::from_event_refund_batch(b, connection))
+        .collect::<Result<Vec<DisplayEventRefundBatch>, ApiError>>()?;
+
+    Ok(HttpResponse::Ok().json(&event_refund_batches))
+}
+
+pub async fn create(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::OrderRefund,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    let event_refund_batch = EventRefundBatch::create(&event, user.id(), connection)?.commit(connection)?;
+    Ok(
+        HttpResponse::Created().json(&DisplayEventRefundBatch::from_event_refund_batch(
+            event_refund_batch,
+            connection,
+        )?),
+    )
+}
+
+pub async fn show(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event_refund_batch = EventRefundBatch::find(path.id, connection)?;
+    let event = Event::find(event_refund_batch.event_id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::OrderRefund,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    Ok(
+        HttpResponse::Ok().json(&DisplayEventRefundBatch::from_event_refund_batch(
+            event_refund_batch,
+            connection,
+        )?),
+    )
+}
+
+pub async fn retry(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event_refund_batch = EventRefundBatch::find(path.id, connection)?;
+    let event = Event::find(event_refund_batch.event_id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::OrderRefund,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    let event_refund_batch = event_refund_batch.retry_failed(connection)?;
+    Ok(
+        HttpResponse::Ok().json(&DisplayEventRefundBatch::from_event_refund_batch(
+            event_refund_batch,
+            connection,
+        )?),
+    )
+}
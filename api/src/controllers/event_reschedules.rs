@@ -0,0 +1,114 @@
+use crate::auth::user::User;
+use crate::controllers::orders;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::helpers::application;
+use crate::models::PathParameters;
+use crate::server::AppState;
+use actix_web::{
+    web::{Data, Path},
+    HttpResponse,
+};
+use chrono::NaiveDateTime;
+use db::models::*;
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct NewEventRescheduleRequest {
+    pub event_start: NaiveDateTime,
+    pub event_end: Option<NaiveDateTime>,
+    pub door_time: Option<NaiveDateTime>,
+    pub response_deadline: NaiveDateTime,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct EventRescheduleResponseRequest {
+    pub order_id: Uuid,
+    pub response: EventRescheduleResponseTypes,
+}
+
+pub async fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+    Ok(HttpResponse::Ok().json(EventReschedule::find_for_event(event.id, connection)?))
+}
+
+pub async fn create(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<NewEventRescheduleRequest>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    let json = json.into_inner();
+    let event_reschedule = EventReschedule::create(
+        &event,
+        json.event_start,
+        json.event_end,
+        json.door_time,
+        json.response_deadline,
+        Some(user.id()),
+    )
+    .commit(connection)?;
+    Ok(HttpResponse::Created().json(&event_reschedule))
+}
+
+pub async fn respond(
+    (conn, path, json, user, state): (
+        Connection,
+        Path<PathParameters>,
+        Json<EventRescheduleResponseRequest>,
+        User,
+        Data<AppState>,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = conn.get();
+    let event_reschedule = EventReschedule::find(path.id, connection)?;
+    let json = json.into_inner();
+    let mut order = Order::find(json.order_id, connection)?;
+    if event_reschedule
+        .tickets_held_by(&order, user.id(), connection)?
+        .is_empty()
+    {
+        return application::forbidden("You do not hold tickets from this order");
+    }
+
+    let refund_id = match json.response {
+        EventRescheduleResponseTypes::Keep => None,
+        EventRescheduleResponseTypes::Refund => {
+            event_reschedule.validate_response(order.id, user.id(), connection)?;
+            let items = event_reschedule.refund_items(&order, user.id(), connection)?;
+            let (refund, _) = orders::perform_refund(
+                &mut order,
+                &items,
+                user.id(),
+                Some("Event rescheduled".to_string()),
+                false,
+                false,
+                false,
+                &conn,
+                &state.config,
+                &state.service_locator,
+            )?;
+            Some(refund.id)
+        }
+    };
+
+    let response = EventRescheduleResponse::create(event_reschedule.id, order.id, user.id(), json.response, refund_id)
+        .commit(connection)?;
+    Ok(HttpResponse::Created().json(&response))
+}
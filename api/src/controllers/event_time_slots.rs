@@ -0,0 +1,82 @@
+use crate::auth::user::User;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::helpers::application;
+use crate::models::{EventTimeSlotPathParameters, PathParameters};
+use actix_web::{web::Path, HttpResponse};
+use chrono::NaiveDateTime;
+use db::models::*;
+
+#[derive(Deserialize)]
+pub struct CreateEventTimeSlotsRequest {
+    pub starts_at: NaiveDateTime,
+    pub ends_at: NaiveDateTime,
+    pub capacity: i32,
+    /// When present, the range is split into back to back slots of this many minutes
+    pub interval_minutes: Option<i64>,
+}
+
+pub async fn index((connection, path): (Connection, Path<PathParameters>)) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+
+    Ok(HttpResponse::Ok().json(&EventTimeSlot::availability_for_event(event.id, connection)?))
+}
+
+pub async fn create(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateEventTimeSlotsRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    let time_slots = match json.interval_minutes {
+        Some(interval_minutes) => EventTimeSlot::create_for_interval(
+            event.id,
+            json.starts_at,
+            json.ends_at,
+            interval_minutes,
+            json.capacity,
+            Some(user.id()),
+            connection,
+        )?,
+        None => vec![
+            EventTimeSlot::create(event.id, json.starts_at, json.ends_at, json.capacity)
+                .commit(Some(user.id()), connection)?,
+        ],
+    };
+
+    Ok(HttpResponse::Created().json(&time_slots))
+}
+
+pub async fn destroy(
+    (connection, path, user): (Connection, Path<EventTimeSlotPathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    let time_slot = EventTimeSlot::find(path.time_slot_id, connection)?;
+    if time_slot.event_id != event.id {
+        return application::not_found();
+    }
+    time_slot.destroy(Some(user.id()), connection)?;
+
+    Ok(HttpResponse::Ok().finish())
+}
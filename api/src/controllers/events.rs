@@ -23,6 +23,8 @@ use chrono::prelude::*;
 use chrono::Duration;
 use db::dev::times;
 use db::prelude::*;
+use db::utils::signing;
+use diesel::Connection as DieselConnection;
 use diesel::PgConnection;
 use serde::Serialize;
 use serde_json::Value;
@@ -638,6 +640,16 @@ pub async fn ticket_holder_count(
 pub struct TicketRedeemRequest {
     pub redeem_key: String,
     pub check_in_source: Option<CheckInSource>,
+    /// Redeems the ticket even if it is scanned outside of its entry time slot
+    #[serde(default)]
+    pub ignore_time_slot: bool,
+    /// Current code from the ticket holder's app, required by events using rotating redeem codes
+    /// unless the ticket is checked in from the guest list
+    #[serde(default)]
+    pub redeem_code: Option<String>,
+    /// Scanning out lets the holder back in later if the ticket type allows re-entry, defaults to in
+    #[serde(default)]
+    pub direction: Option<TicketScanDirection>,
 }
 
 pub async fn redeem_ticket(
@@ -657,49 +669,109 @@ pub async fn redeem_ticket(
     let ticket =
         TicketInstance::find_by_event_id_redeem_key(parameters.id, redeem_parameters.redeem_key.clone(), connection)?;
     let redeemable = TicketInstance::show_redeemable_ticket(ticket.id, connection)?;
+    let direction = redeem_parameters.direction.unwrap_or(TicketScanDirection::In);
+    let access_zone = AccessZone::find_for_user(db_event.id, auth_user.id(), connection)?;
+
+    if redeem_code_rejected(
+        &db_event,
+        &ticket,
+        redeem_parameters.redeem_code.as_ref().map(|c| c.as_str()),
+        redeem_parameters.check_in_source,
+        direction,
+        Utc::now().naive_utc(),
+    )? {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Ticket code has expired, ask the ticket holder to show the current code in the app.".to_string()
+        })));
+    }
 
-    let result = TicketInstance::redeem_ticket(
-        ticket.id,
-        redeem_parameters.redeem_key.clone(),
-        auth_user.id(),
-        redeem_parameters.check_in_source.unwrap_or(CheckInSource::GuestList),
-        connection,
-    )?;
+    let result = match (&access_zone, direction) {
+        (Some(access_zone), _) => TicketInstance::scan_zone(
+            ticket.id,
+            redeem_parameters.redeem_key.clone(),
+            access_zone,
+            auth_user.id(),
+            direction,
+            Utc::now().naive_utc(),
+            connection,
+        )?,
+        (None, TicketScanDirection::In) => TicketInstance::redeem_ticket_at(
+            ticket.id,
+            redeem_parameters.redeem_key.clone(),
+            auth_user.id(),
+            redeem_parameters.check_in_source.unwrap_or(CheckInSource::GuestList),
+            Utc::now().naive_utc(),
+            redeem_parameters.ignore_time_slot,
+            connection,
+        )?,
+        (None, TicketScanDirection::Out) => TicketInstance::scan_out(
+            ticket.id,
+            redeem_parameters.redeem_key.clone(),
+            auth_user.id(),
+            Utc::now().naive_utc(),
+            connection,
+        )?,
+    };
 
     match result {
         RedeemResults::TicketRedeemSuccess => {
-            //Redeem ticket on chain
-            let asset = Asset::find(ticket.asset_id, connection)?;
-            match asset.blockchain_asset_id {
-                Some(a) => {
-                    let wallet = Wallet::find(ticket.wallet_id, connection)?;
-                    state.config.tari_client.modify_asset_redeem_token(&wallet.secret_key, &wallet.public_key,
-                                                                       &a,
-                                                                       vec![ticket.token_id as u64],
-                    )?;
-
-                    //Fetch the redeemable again to include the redeemed_by and redeemed_at fields
-                    let redeemable = TicketInstance::show_redeemable_ticket(ticket.id, connection)?;
-
-                    // Publish redeem event for redis pubsub
-                    cache_database
-                        .inner
-                        .clone()
-                        .and_then(|conn| caching::publish(conn, RedisPubSubChannel::TicketRedemptions, messages::TicketRedemption {
-                            ticket_id: ticket.id,
-                            event_id: db_event.id,
-                            redeemer_id: auth_user.id()
-                        }).ok());
-
-                    Ok(HttpResponse::Ok().json(redeemable))
-                }
-                None => Ok(HttpResponse::BadRequest().json(json!({ "error": "Could not redeem because the asset has not been assigned on the blockchain.".to_string()}))),
+            if complete_ticket_redemption(
+                &ticket,
+                db_event.id,
+                auth_user.id(),
+                &state,
+                &cache_database,
+                connection,
+            )? {
+                //Fetch the redeemable again to include the redeemed_by and redeemed_at fields
+                let redeemable = TicketInstance::show_redeemable_ticket(ticket.id, connection)?;
+                Ok(HttpResponse::Ok().json(redeemable))
+            } else {
+                Ok(HttpResponse::BadRequest().json(json!({ "error": "Could not redeem because the asset has not been assigned on the blockchain.".to_string()})))
             }
         }
+        RedeemResults::TicketReentrySuccess
+        | RedeemResults::TicketExitSuccess
+        | RedeemResults::TicketZoneEntrySuccess => {
+            publish_ticket_scan(
+                &ticket,
+                db_event.id,
+                auth_user.id(),
+                direction,
+                access_zone.as_ref(),
+                &cache_database,
+                connection,
+            )?;
+            let redeemable = TicketInstance::show_redeemable_ticket(ticket.id, connection)?;
+            Ok(HttpResponse::Ok().json(redeemable))
+        }
+        RedeemResults::TicketZoneAccessDenied => Ok(HttpResponse::BadRequest().json(json!({
+            "error": format!(
+                "Ticket does not grant access to {}.",
+                access_zone.as_ref().map(|z| z.name.as_str()).unwrap_or("this zone")
+            )
+        }))),
+        RedeemResults::TicketMaxEntriesReached => Ok(HttpResponse::BadRequest()
+            .json(json!({"error": "Ticket has reached its maximum number of entries.".to_string()}))),
+        RedeemResults::TicketNotCheckedIn => {
+            Ok(HttpResponse::BadRequest().json(json!({"error": "Ticket is not checked in.".to_string()})))
+        }
         RedeemResults::TicketTransferInProcess => {
             Ok(HttpResponse::BadRequest()
                 .json(json!({"error": "Ticket has pending transfer in progress.".to_string()})))
         }
+        RedeemResults::TicketPaymentOutstanding => Ok(HttpResponse::BadRequest()
+            .json(json!({"error": "Ticket is on an installment plan that has not been fully paid.".to_string()}))),
+        RedeemResults::TicketOutsideTimeSlot => {
+            let time_slot = ticket.time_slot(connection)?;
+            Ok(HttpResponse::BadRequest().json(json!({
+                "error": "Ticket is outside of its entry time slot.".to_string(),
+                "time_slot_starts_at": time_slot.as_ref().map(|s| s.starts_at),
+                "time_slot_ends_at": time_slot.as_ref().map(|s| s.ends_at)
+            })))
+        }
+        RedeemResults::TicketAlreadyRedeemed if access_zone.is_some() => Ok(HttpResponse::Conflict()
+            .json(json!({"error": "Ticket has already been scanned into this zone.".to_string()}))),
         RedeemResults::TicketAlreadyRedeemed => Ok(HttpResponse::Conflict().json(json!({
         "error": "Ticket has already been redeemed.".to_string(),
         "redeemed_by": redeemable.redeemed_by,
@@ -711,6 +783,385 @@ pub async fn redeem_ticket(
     }
 }
 
+/// Whether the scan needs the holder's current rotating redeem code, for events using them, and
+/// the code given was missing or not valid at `scanned_at`. Scans back in after leaving need the
+/// code as well as the first entry.
+fn redeem_code_rejected(
+    event: &Event,
+    ticket: &TicketInstance,
+    redeem_code: Option<&str>,
+    check_in_source: Option<CheckInSource>,
+    direction: TicketScanDirection,
+    scanned_at: NaiveDateTime,
+) -> Result<bool, ApiError> {
+    Ok(event.rotating_redeem_codes
+        && direction == TicketScanDirection::In
+        && (ticket.status == TicketInstanceStatus::Purchased || ticket.status == TicketInstanceStatus::Redeemed)
+        && check_in_source != Some(CheckInSource::GuestList)
+        && !ticket.verify_redeem_code(redeem_code, scanned_at)?)
+}
+
+/// Redeems the ticket on chain and publishes the redemption, returns false if the ticket's asset
+/// has not been assigned on the blockchain yet
+fn complete_ticket_redemption(
+    ticket: &TicketInstance,
+    event_id: Uuid,
+    redeemer_id: Uuid,
+    state: &AppState,
+    cache_database: &CacheDatabase,
+    connection: &PgConnection,
+) -> Result<bool, ApiError> {
+    let asset = Asset::find(ticket.asset_id, connection)?;
+    let blockchain_asset_id = match asset.blockchain_asset_id {
+        Some(blockchain_asset_id) => blockchain_asset_id,
+        None => return Ok(false),
+    };
+    let wallet = Wallet::find(ticket.wallet_id, connection)?;
+    state.config.tari_client.modify_asset_redeem_token(
+        &wallet.secret_key,
+        &wallet.public_key,
+        &blockchain_asset_id,
+        vec![ticket.token_id as u64],
+    )?;
+
+    publish_ticket_scan(
+        ticket,
+        event_id,
+        redeemer_id,
+        TicketScanDirection::In,
+        None,
+        cache_database,
+        connection,
+    )?;
+
+    Ok(true)
+}
+
+/// Publishes the scan for the event's websocket listeners along with the occupancy of the event,
+/// or of the access zone for zone scans
+fn publish_ticket_scan(
+    ticket: &TicketInstance,
+    event_id: Uuid,
+    redeemer_id: Uuid,
+    direction: TicketScanDirection,
+    access_zone: Option<&AccessZone>,
+    cache_database: &CacheDatabase,
+    connection: &PgConnection,
+) -> Result<(), ApiError> {
+    if let Some(conn) = cache_database.inner.clone() {
+        let occupancy = match access_zone {
+            Some(access_zone) => TicketScan::zone_occupancy(access_zone, connection)?,
+            None => TicketScan::current_occupancy(event_id, connection)?,
+        };
+        let message = messages::TicketRedemption {
+            ticket_id: ticket.id,
+            event_id,
+            redeemer_id,
+            direction: Some(direction),
+            occupancy: Some(occupancy),
+            access_zone_id: access_zone.map(|z| z.id),
+        };
+        caching::publish(conn, RedisPubSubChannel::TicketRedemptions, message).ok();
+    }
+    Ok(())
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct OfflineManifestParameters {
+    /// `generated_at` of the last manifest the device received, only changes since then are returned
+    pub since: Option<NaiveDateTime>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct OfflineScanningManifest {
+    pub event_id: Uuid,
+    pub generated_at: NaiveDateTime,
+    pub since: Option<NaiveDateTime>,
+    /// PBKDF2 iterations devices use to hash scanned redeem keys
+    pub redeem_key_hash_iterations: u32,
+    pub tickets: Vec<OfflineScanningTicket>,
+}
+
+/// Header containing the hex encoded Ed25519 signature of the manifest body
+pub const OFFLINE_MANIFEST_SIGNATURE_HEADER: &str = "X-Manifest-Signature";
+
+/// Tickets door apps can redeem while offline. Redeem keys are only included as hashes (see
+/// `TicketInstance::offline_redeem_key_hash`) and the body is signed with the offline scanning
+/// private key so devices, which only hold the public key, can check a cached manifest has not
+/// been tampered with.
+pub async fn offline_manifest(
+    (connection, path, query, auth_user, state): (
+        Connection,
+        Path<PathParameters>,
+        Query<OfflineManifestParameters>,
+        AuthUser,
+        Data<AppState>,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    auth_user.requires_scope_for_organization_event(
+        Scopes::RedeemTicket,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+    let private_key = match state.config.offline_scanning_private_key.as_ref() {
+        Some(private_key) => private_key,
+        None => return application::unprocessable("Offline scanning is not enabled"),
+    };
+
+    // Changes made by transactions still in flight are stamped before they are visible, the overlap
+    // makes sure the next incremental manifest still includes them
+    let generated_at = Utc::now().naive_utc() - Duration::minutes(1);
+    let manifest = OfflineScanningManifest {
+        event_id: event.id,
+        generated_at,
+        since: query.since,
+        redeem_key_hash_iterations: OFFLINE_REDEEM_KEY_HASH_ITERATIONS,
+        tickets: TicketInstance::find_for_offline_scanning(event.id, query.since, connection)?,
+    };
+    let body = serde_json::to_string(&manifest)?;
+
+    Ok(HttpResponse::Ok()
+        .header(OFFLINE_MANIFEST_SIGNATURE_HEADER, signing::sign(&body, private_key)?)
+        .content_type("application/json")
+        .body(body))
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct OfflineRedemption {
+    pub redeem_key: String,
+    /// When the ticket was scanned according to the device, limited to the event's doors to end
+    /// and to no later than the sync
+    pub redeemed_at: NaiveDateTime,
+    pub check_in_source: Option<CheckInSource>,
+    #[serde(default)]
+    pub direction: Option<TicketScanDirection>,
+    /// Code the ticket holder's app showed when scanned, required by events using rotating redeem codes
+    #[serde(default)]
+    pub redeem_code: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct OfflineRedemptionSyncRequest {
+    pub redemptions: Vec<OfflineRedemption>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OfflineRedemptionStatus {
+    Redeemed,
+    Reentered,
+    Exited,
+    /// The ticket was already redeemed, by an earlier scan on this or another device or online
+    AlreadyRedeemed,
+    MaxEntriesReached,
+    NotCheckedIn,
+    /// Scanned into the scanner's access zone
+    ZoneEntered,
+    ZoneAccessDenied,
+    NotFound,
+    Invalid,
+    TransferInProcess,
+    PaymentOutstanding,
+    OutsideTimeSlot,
+    /// The rotating redeem code was missing or had already expired when the ticket was scanned
+    RedeemCodeExpired,
+    /// The ticket's asset has not been assigned on the blockchain so the scan was not recorded
+    AssetNotAssigned,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct OfflineRedemptionResult {
+    pub redeem_key: String,
+    pub ticket_instance_id: Option<Uuid>,
+    pub status: OfflineRedemptionStatus,
+    /// The redemption that stands for the ticket, which for `already_redeemed` is the earlier one
+    pub redeemed_at: Option<NaiveDateTime>,
+    pub redeemed_by: Option<Uuid>,
+}
+
+/// Records redemptions made while a door app was offline. Redemptions are applied in the order
+/// they were scanned so that when the same ticket was scanned more than once, on one device or
+/// several, the earliest scan is the one that admitted the holder and the rest are reported as
+/// `already_redeemed`.
+pub async fn sync_offline_redemptions(
+    (connection, path, sync_parameters, auth_user, state, cache_database): (
+        Connection,
+        Path<PathParameters>,
+        Json<OfflineRedemptionSyncRequest>,
+        AuthUser,
+        Data<AppState>,
+        CacheDatabase,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    auth_user.requires_scope_for_organization_event(
+        Scopes::RedeemTicket,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    let access_zone = AccessZone::find_for_user(event.id, auth_user.id(), connection)?;
+    let mut redemptions: Vec<(&OfflineRedemption, NaiveDateTime)> = sync_parameters
+        .redemptions
+        .iter()
+        .map(|r| (r, event.clamp_scan_time(r.redeemed_at)))
+        .collect();
+    redemptions.sort_by_key(|(r, _)| r.redeemed_at);
+
+    let mut results = Vec::new();
+    for (redemption, redeemed_at) in redemptions {
+        let ticket =
+            match TicketInstance::find_by_event_id_redeem_key(event.id, redemption.redeem_key.clone(), connection) {
+                Ok(ticket) => ticket,
+                Err(err) if err.error_code == ErrorCode::NoResults => {
+                    results.push(OfflineRedemptionResult {
+                        redeem_key: redemption.redeem_key.clone(),
+                        ticket_instance_id: None,
+                        status: OfflineRedemptionStatus::NotFound,
+                        redeemed_at: None,
+                        redeemed_by: None,
+                    });
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+        let direction = redemption.direction.unwrap_or(TicketScanDirection::In);
+        if redeem_code_rejected(
+            &event,
+            &ticket,
+            redemption.redeem_code.as_ref().map(|c| c.as_str()),
+            redemption.check_in_source,
+            direction,
+            redeemed_at,
+        )? {
+            results.push(OfflineRedemptionResult {
+                redeem_key: redemption.redeem_key.clone(),
+                ticket_instance_id: Some(ticket.id),
+                status: OfflineRedemptionStatus::RedeemCodeExpired,
+                redeemed_at: ticket.redeemed_at,
+                redeemed_by: ticket.redeemed_by_user_id,
+            });
+            continue;
+        }
+        // Each scan is applied in its own savepoint so that a scan which cannot be completed is
+        // rolled back on its own, as the online redemption would be, without failing the sync
+        let mut asset_not_assigned = false;
+        let scan = connection.transaction::<_, ApiError, _>(|| {
+            let result = match (&access_zone, direction) {
+                (Some(access_zone), _) => TicketInstance::scan_zone(
+                    ticket.id,
+                    redemption.redeem_key.clone(),
+                    access_zone,
+                    auth_user.id(),
+                    direction,
+                    redeemed_at,
+                    connection,
+                )?,
+                (None, TicketScanDirection::In) => TicketInstance::redeem_ticket_at(
+                    ticket.id,
+                    redemption.redeem_key.clone(),
+                    auth_user.id(),
+                    redemption.check_in_source.unwrap_or(CheckInSource::Scanned),
+                    redeemed_at,
+                    false,
+                    connection,
+                )?,
+                (None, TicketScanDirection::Out) => TicketInstance::scan_out(
+                    ticket.id,
+                    redemption.redeem_key.clone(),
+                    auth_user.id(),
+                    redeemed_at,
+                    connection,
+                )?,
+            };
+            Ok(match result {
+                RedeemResults::TicketRedeemSuccess => {
+                    if !complete_ticket_redemption(
+                        &ticket,
+                        event.id,
+                        auth_user.id(),
+                        &state,
+                        &cache_database,
+                        connection,
+                    )? {
+                        asset_not_assigned = true;
+                        return Err(ApplicationError::new(
+                            "Could not redeem because the asset has not been assigned on the blockchain.".to_string(),
+                        )
+                        .into());
+                    }
+                    OfflineRedemptionStatus::Redeemed
+                }
+                RedeemResults::TicketReentrySuccess => {
+                    publish_ticket_scan(
+                        &ticket,
+                        event.id,
+                        auth_user.id(),
+                        direction,
+                        None,
+                        &cache_database,
+                        connection,
+                    )?;
+                    OfflineRedemptionStatus::Reentered
+                }
+                RedeemResults::TicketExitSuccess => {
+                    publish_ticket_scan(
+                        &ticket,
+                        event.id,
+                        auth_user.id(),
+                        direction,
+                        access_zone.as_ref(),
+                        &cache_database,
+                        connection,
+                    )?;
+                    OfflineRedemptionStatus::Exited
+                }
+                RedeemResults::TicketZoneEntrySuccess => {
+                    publish_ticket_scan(
+                        &ticket,
+                        event.id,
+                        auth_user.id(),
+                        direction,
+                        access_zone.as_ref(),
+                        &cache_database,
+                        connection,
+                    )?;
+                    OfflineRedemptionStatus::ZoneEntered
+                }
+                RedeemResults::TicketZoneAccessDenied => OfflineRedemptionStatus::ZoneAccessDenied,
+                RedeemResults::TicketAlreadyRedeemed => OfflineRedemptionStatus::AlreadyRedeemed,
+                RedeemResults::TicketMaxEntriesReached => OfflineRedemptionStatus::MaxEntriesReached,
+                RedeemResults::TicketNotCheckedIn => OfflineRedemptionStatus::NotCheckedIn,
+                RedeemResults::TicketInvalid => OfflineRedemptionStatus::Invalid,
+                RedeemResults::TicketTransferInProcess => OfflineRedemptionStatus::TransferInProcess,
+                RedeemResults::TicketPaymentOutstanding => OfflineRedemptionStatus::PaymentOutstanding,
+                RedeemResults::TicketOutsideTimeSlot => OfflineRedemptionStatus::OutsideTimeSlot,
+            })
+        });
+        let status = match scan {
+            Ok(status) => status,
+            Err(_) if asset_not_assigned => OfflineRedemptionStatus::AssetNotAssigned,
+            Err(e) => return Err(e),
+        };
+        let ticket = TicketInstance::find(ticket.id, connection)?;
+        results.push(OfflineRedemptionResult {
+            redeem_key: redemption.redeem_key.clone(),
+            ticket_instance_id: Some(ticket.id),
+            status,
+            redeemed_at: ticket.redeemed_at,
+            redeemed_by: ticket.redeemed_by_user_id,
+        });
+    }
+
+    Ok(HttpResponse::Ok().json(results))
+}
+
 pub async fn show_from_organizations(
     (connection, path, paging, user): (Connection, Path<PathParameters>, Query<PagingParameters>, AuthUser),
 ) -> Result<WebPayload<EventSummaryResult>, ApiError> {
@@ -845,7 +1296,10 @@ pub async fn create(
     let organization = Organization::find(new_event.organization_id, connection)?;
     user.requires_scope_for_organization(Scopes::EventWrite, &organization, connection)?;
 
-    let new_event = new_event.into_inner();
+    let mut new_event = new_event.into_inner();
+    if new_event.currency.is_none() {
+        new_event.currency = Some(state.config.primary_currency.clone());
+    }
 
     let event = new_event.commit(Some(user.id()), connection)?;
 
@@ -913,16 +1367,28 @@ pub async fn delete(
     Ok(HttpResponse::Ok().json({}))
 }
 
+#[derive(Default, Deserialize, Serialize)]
+pub struct CancelEventParameters {
+    #[serde(default)]
+    pub refund_orders: bool,
+}
+
 pub async fn cancel(
-    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+    (connection, parameters, query, user): (Connection, Path<PathParameters>, Query<CancelEventParameters>, AuthUser),
 ) -> Result<HttpResponse, ApiError> {
     let connection = connection.get();
     let event = Event::find(parameters.id, connection)?;
     let organization = event.organization(connection)?;
     user.requires_scope_for_organization_event(Scopes::EventCancel, &organization, &event, connection)?;
+    if query.refund_orders {
+        user.requires_scope_for_organization_event(Scopes::OrderRefund, &organization, &event, connection)?;
+    }
 
     //Doing this in the DB layer so it can use the DB time as now.
     let updated_event = event.cancel(Some(user.id()), connection)?;
+    if query.refund_orders {
+        EventRefundBatch::create(&updated_event, user.id(), connection)?.commit(connection)?;
+    }
 
     Ok(HttpResponse::Ok().json(&updated_event))
 }
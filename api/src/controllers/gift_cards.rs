@@ -0,0 +1,98 @@
+use crate::auth::user::User;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::helpers::application;
+use crate::models::{PathParameters, StringPathParameters};
+use actix_web::{
+    web::{Path, Query},
+    HttpResponse,
+};
+use chrono::NaiveDateTime;
+use db::models::*;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct CreateGiftCardRequest {
+    pub initial_value_in_cents: i64,
+    pub currency: String,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+/// Balance details shown to the gift card's purchaser, redeemers and organization staff
+#[derive(Serialize)]
+pub struct DisplayGiftCardBalance {
+    pub code: String,
+    pub organization_id: Uuid,
+    pub balance_in_cents: i64,
+    pub currency: String,
+    pub expires_at: Option<NaiveDateTime>,
+    pub expired: bool,
+    pub voided: bool,
+}
+
+impl From<GiftCard> for DisplayGiftCardBalance {
+    fn from(gift_card: GiftCard) -> Self {
+        DisplayGiftCardBalance {
+            expired: gift_card.is_expired(),
+            voided: gift_card.is_voided(),
+            code: gift_card.code,
+            organization_id: gift_card.organization_id,
+            balance_in_cents: gift_card.balance_in_cents,
+            currency: gift_card.currency,
+            expires_at: gift_card.expires_at,
+        }
+    }
+}
+
+pub async fn index(
+    (connection, path, query_parameters, user): (Connection, Path<PathParameters>, Query<PagingParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgFinancialReports, &organization, connection)?;
+
+    let gift_cards = GiftCard::find_for_organization(organization.id, connection)?;
+    Ok(HttpResponse::Ok().json(&Payload::from_data(
+        gift_cards,
+        query_parameters.page(),
+        query_parameters.limit(),
+        None,
+    )))
+}
+
+pub async fn create(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<CreateGiftCardRequest>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdmin, &organization, connection)?;
+
+    let json = json.into_inner();
+    let gift_card = GiftCard::create(
+        organization.id,
+        json.initial_value_in_cents,
+        json.currency,
+        json.expires_at,
+        None,
+        Some(user.id()),
+    )
+    .commit(connection)?;
+    Ok(HttpResponse::Created().json(&gift_card))
+}
+
+/// Balances are only shown to the purchaser, users who have redeemed the card and organization staff
+pub async fn show(
+    (connection, path, user): (Connection, Path<StringPathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let gift_card = GiftCard::find_by_code(&path.id, connection)?;
+    let organization = Organization::find(gift_card.organization_id, connection)?;
+    if gift_card.purchaser_id(connection)? != Some(user.id())
+        && !gift_card.redeemed_by(user.id(), connection)?
+        && !user.has_scope_for_organization(Scopes::OrderRead, &organization, connection)?
+    {
+        return application::forbidden("You do not have access to this gift card");
+    }
+    Ok(HttpResponse::Ok().json(&DisplayGiftCardBalance::from(gift_card)))
+}
@@ -0,0 +1,102 @@
+use crate::auth::user::User;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::helpers::application;
+use crate::models::PathParameters;
+use actix_web::{web::Path, HttpResponse};
+use db::models::*;
+
+#[derive(Deserialize, Serialize)]
+pub struct InstallmentPolicyRequest {
+    pub deposit_percent: i32,
+    pub installment_count: i32,
+    pub interval_days: i32,
+    pub max_attempts: i32,
+    pub retry_interval_hours: i32,
+    pub refund_deposit_on_default: bool,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct DisplayInstallmentPlan {
+    #[serde(flatten)]
+    pub installment_plan: InstallmentPlan,
+    pub installments: Vec<Installment>,
+}
+
+pub async fn show_for_order(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let order = Order::find(path.id, connection)?;
+    if order.on_behalf_of_user_id.unwrap_or(order.user_id) != user.id() {
+        user.requires_scope_for_order(Scopes::OrderRead, &order, connection)?;
+    }
+
+    match InstallmentPlan::find_for_order(order.id, connection)? {
+        Some(installment_plan) => Ok(HttpResponse::Ok().json(DisplayInstallmentPlan {
+            installments: installment_plan.installments(connection)?,
+            installment_plan,
+        })),
+        None => application::not_found(),
+    }
+}
+
+pub async fn show_policy(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    match InstallmentPolicy::find_for_event(event.id, connection)? {
+        Some(installment_policy) => Ok(HttpResponse::Ok().json(&installment_policy)),
+        None => application::not_found(),
+    }
+}
+
+pub async fn update_policy(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<InstallmentPolicyRequest>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    let json = json.into_inner();
+    let installment_policy = match InstallmentPolicy::find_for_event(event.id, connection)? {
+        Some(installment_policy) => installment_policy.update(
+            InstallmentPolicyEditableAttributes {
+                deposit_percent: Some(json.deposit_percent),
+                installment_count: Some(json.installment_count),
+                interval_days: Some(json.interval_days),
+                max_attempts: Some(json.max_attempts),
+                retry_interval_hours: Some(json.retry_interval_hours),
+                refund_deposit_on_default: Some(json.refund_deposit_on_default),
+            },
+            Some(user.id()),
+            connection,
+        )?,
+        None => InstallmentPolicy::create(
+            event.id,
+            json.deposit_percent,
+            json.installment_count,
+            json.interval_days,
+            json.max_attempts,
+            json.retry_interval_hours,
+            json.refund_deposit_on_default,
+        )
+        .commit(Some(user.id()), connection)?,
+    };
+
+    Ok(HttpResponse::Ok().json(&installment_policy))
+}
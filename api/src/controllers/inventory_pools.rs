@@ -0,0 +1,97 @@
+use crate::auth::user::User;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::models::PathParameters;
+use actix_web::{web::Path, HttpResponse};
+use db::models::*;
+use diesel::PgConnection;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct CreateInventoryPoolRequest {
+    pub name: String,
+    pub capacity: i32,
+}
+
+#[derive(Serialize)]
+pub struct DisplayInventoryPool {
+    #[serde(flatten)]
+    pub inventory_pool: InventoryPool,
+    pub available: u32,
+    pub ticket_type_ids: Vec<Uuid>,
+}
+
+impl DisplayInventoryPool {
+    fn from_inventory_pool(inventory_pool: InventoryPool, conn: &PgConnection) -> Result<Self, ApiError> {
+        let available = inventory_pool.available_count(conn)?;
+        let ticket_type_ids = inventory_pool.ticket_types(conn)?.iter().map(|tt| tt.id).collect();
+        Ok(DisplayInventoryPool {
+            inventory_pool,
+            available,
+            ticket_type_ids,
+        })
+    }
+}
+
+pub async fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::TicketTypeRead,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    let inventory_pools = InventoryPool::find_for_event(event.id, connection)?
+        .into_iter()
+        .map(|p| DisplayInventoryPool::from_inventory_pool(p, connection))
+        .collect::<Result<Vec<DisplayInventoryPool>, ApiError>>()?;
+
+    Ok(HttpResponse::Ok().json(&inventory_pools))
+}
+
+pub async fn create(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<CreateInventoryPoolRequest>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::TicketTypeWrite,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    let json = json.into_inner();
+    let inventory_pool =
+        InventoryPool::create(event.id, json.name, json.capacity).commit(Some(user.id()), connection)?;
+
+    Ok(HttpResponse::Created().json(&DisplayInventoryPool::from_inventory_pool(inventory_pool, connection)?))
+}
+
+pub async fn update(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<InventoryPoolEditableAttributes>,
+        User,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let inventory_pool = InventoryPool::find(path.id, connection)?;
+    let event = Event::find(inventory_pool.event_id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::TicketTypeWrite,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    let inventory_pool = inventory_pool.update(json.into_inner(), Some(user.id()), connection)?;
+
+    Ok(HttpResponse::Ok().json(&DisplayInventoryPool::from_inventory_pool(inventory_pool, connection)?))
+}
@@ -1,11 +1,17 @@
 use crate::database::Connection;
 use crate::errors::ApiError;
 use crate::extractors::Json;
-use actix_web::HttpResponse;
+use crate::helpers::application;
+use crate::payments::mock::{self, MockIpnRequest};
+use crate::server::AppState;
+use actix_web::web::{Bytes, Data};
+use actix_web::{HttpRequest, HttpResponse};
 use db::prelude::*;
 use db::utils::dates::IntoDateBuilder;
 use globee::GlobeeIpnRequest;
-use log::Level::Debug;
+use log::Level::{Debug, Warn};
+use serde_json::Value;
+use stripe::{verify_webhook_signature, DEFAULT_TOLERANCE_IN_SECONDS};
 use uuid::Uuid;
 
 pub async fn globee((data, conn): (Json<GlobeeIpnRequest>, Connection)) -> Result<HttpResponse, ApiError> {
@@ -33,3 +39,78 @@ pub async fn globee((data, conn): (Json<GlobeeIpnRequest>, Connection)) -> Resul
 
     Ok(HttpResponse::Ok().finish())
 }
+
+/// Scripted IPNs for the mock payment provider, only accepted in development and test
+pub async fn mock(
+    (data, conn, state): (Json<MockIpnRequest>, Connection, Data<AppState>),
+) -> Result<HttpResponse, ApiError> {
+    if !mock::is_enabled(state.config.environment) {
+        return application::not_found();
+    }
+    let data = data.into_inner();
+    jlog!(Debug, "Mock IPN received", { "data": &data });
+    let mut payload = json!(data);
+    payload["object"] = json!("mock_ipn");
+    let mut action = DomainAction::create(
+        None,
+        DomainActionTypes::PaymentProviderIPN,
+        None,
+        payload,
+        Some(Tables::Orders),
+        Some(data.order_id),
+    );
+    if let Some(delay_seconds) = data.delay_seconds {
+        action.scheduled_at = action.scheduled_at.into_builder().add_seconds(delay_seconds).finish();
+    }
+    action.expires_at = action.scheduled_at.into_builder().add_days(30).finish();
+    action.max_attempt_count = 5;
+    action.commit(conn.get())?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+pub async fn stripe(
+    (request, body, conn, state): (HttpRequest, Bytes, Connection, Data<AppState>),
+) -> Result<HttpResponse, ApiError> {
+    // Webhooks are only accepted once a signing secret has been configured
+    let webhook_secret = match state.config.stripe_webhook_secret {
+        Some(ref secret) => secret,
+        None => return application::not_found(),
+    };
+    let signature = match request.headers().get("Stripe-Signature").and_then(|h| h.to_str().ok()) {
+        Some(s) => s,
+        None => return application::bad_request("Missing Stripe signature"),
+    };
+    if let Err(e) = verify_webhook_signature(&body, signature, webhook_secret, DEFAULT_TOLERANCE_IN_SECONDS) {
+        jlog!(Warn, "Stripe webhook rejected", { "error": e.to_string() });
+        return application::bad_request("Invalid Stripe signature");
+    }
+
+    let data: Value = serde_json::from_slice(&body)?;
+    jlog!(Debug, "Stripe webhook received", { "id": &data["id"], "type": &data["type"] });
+    // Only payment intent and charge (including refund and dispute) events affect orders
+    let event_type = data["type"].as_str().unwrap_or("");
+    if !event_type.starts_with("payment_intent.") && !event_type.starts_with("charge.") {
+        return Ok(HttpResponse::Ok().finish());
+    }
+    let object = &data["data"]["object"];
+    // Charges, refunds and disputes only carry our order id when it was set in the metadata,
+    // otherwise the executor finds the payment by its provider reference
+    let order_id = match object["metadata"]["order_id"].as_str() {
+        Some(id) => Some(id.parse::<Uuid>()?),
+        None => None,
+    };
+    let mut action = DomainAction::create(
+        None,
+        DomainActionTypes::PaymentProviderIPN,
+        None,
+        data,
+        Some(Tables::Orders),
+        order_id,
+    );
+    action.expires_at = action.scheduled_at.into_builder().add_days(30).finish();
+    action.max_attempt_count = 5;
+    action.commit(conn.get())?;
+
+    Ok(HttpResponse::Ok().finish())
+}
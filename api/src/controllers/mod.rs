@@ -1,3 +1,4 @@
+pub mod access_zones;
 pub mod admin;
 pub mod analytics;
 pub mod announcements;
@@ -9,11 +10,19 @@ pub mod codes;
 pub mod collection_items;
 pub mod collections;
 pub mod comps;
+pub mod disputes;
+pub mod dynamic_pricing_strategies;
+pub mod event_refund_batches;
 pub mod event_report_subscribers;
+pub mod event_reschedules;
+pub mod event_time_slots;
 pub mod events;
 pub mod external;
 pub mod genres;
+pub mod gift_cards;
 pub mod holds;
+pub mod installment_plans;
+pub mod inventory_pools;
 pub mod ipns;
 pub mod listings;
 pub mod notes;
@@ -21,11 +30,14 @@ pub mod orders;
 pub mod organization_invites;
 pub mod organization_venues;
 pub mod organizations;
+pub mod passes;
 pub mod password_resets;
 pub mod payment_methods;
 pub mod payments;
+pub mod products;
 pub mod rarities;
 pub mod redemption_codes;
+pub mod refund_requests;
 pub mod regions;
 pub mod reports;
 pub mod send_download_link;
@@ -35,10 +47,14 @@ pub mod sitemap_gen;
 pub mod slugs;
 pub mod stages;
 pub mod status;
+pub mod store_credits;
+pub mod tax_rules;
 pub mod ticket_types;
 pub mod tickets;
 pub mod transfers;
 pub mod user_invites;
 pub mod users;
+pub mod venue_sections;
 pub mod venues;
+pub mod waitlist_entries;
 pub mod websockets;
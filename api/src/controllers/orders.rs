@@ -1,6 +1,7 @@
 use crate::auth::user::User;
 use crate::communications::mailers;
 use crate::communications::smsers;
+use crate::config::Config;
 use crate::database::Connection;
 use crate::errors::ApiError;
 use crate::extractors::*;
@@ -8,6 +9,7 @@ use crate::helpers::application;
 use crate::models::*;
 use crate::server::AppState;
 use crate::utils::serializers::default_as_false;
+use crate::utils::ServiceLocator;
 use actix_web::{
     http::StatusCode,
     web::{Data, Path, Query},
@@ -20,7 +22,6 @@ use diesel::pg::PgConnection;
 use diesel::Connection as DieselConnection;
 use log::Level::Debug;
 use phonenumber::PhoneNumber;
-use std::cmp;
 use std::collections::HashMap;
 use uuid::Uuid;
 
@@ -167,6 +168,8 @@ pub struct RefundAttributes {
     pub reason: Option<String>,
     #[serde(default = "default_as_false")]
     pub manual_override: bool,
+    #[serde(default = "default_as_false")]
+    pub refund_to_store_credit: bool,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -190,6 +193,7 @@ pub async fn refund(
     let reason = refund_attributes.reason;
     let items = refund_attributes.items;
     let manual_override = refund_attributes.manual_override;
+    let refund_to_store_credit = refund_attributes.refund_to_store_credit;
     let mut order = Order::find(path.id, connection)?;
 
     if order.status != OrderStatus::Paid {
@@ -203,6 +207,38 @@ pub async fn refund(
         return application::unauthorized(Some(user), Some(details_data));
     }
 
+    let (_refund, refund_response) = perform_refund(
+        &mut order,
+        &items,
+        user.id(),
+        reason,
+        manual_override,
+        false,
+        refund_to_store_credit,
+        &conn,
+        &state.config,
+        &state.service_locator,
+    )?;
+    Ok(HttpResponse::Ok().json(json!(refund_response)))
+}
+
+/// Refunds the items through the payment providers that took the original payments, returns the
+/// refunded tickets to the organization wallets and emails the purchaser. Refund requests that
+/// retain fees pass `retain_fees` to keep per unit fees with the organizer. With
+/// `refund_to_store_credit` card and external payments are refunded to the purchaser's store credit.
+pub(crate) fn perform_refund(
+    order: &mut Order,
+    items: &[RefundItemRequest],
+    user_id: Uuid,
+    reason: Option<String>,
+    manual_override: bool,
+    retain_fees: bool,
+    refund_to_store_credit: bool,
+    conn: &Connection,
+    config: &Config,
+    service_locator: &ServiceLocator,
+) -> Result<(Refund, RefundResponse), ApiError> {
+    let connection = conn.get();
     let ticket_instance_ids = items
         .iter()
         .filter(|i| i.ticket_instance_id.is_some())
@@ -210,7 +246,15 @@ pub async fn refund(
         .collect::<Vec<Uuid>>();
 
     // Refund amount is fee inclusive if fee no longer applies to the order
-    let (refund, refund_due) = order.refund(&items, user.id(), reason, manual_override, connection)?;
+    let (refund, refund_due) = if retain_fees {
+        order.refund_retaining_fees(items, user_id, reason, connection)?
+    } else {
+        order.refund(items, user_id, reason, manual_override, connection)?
+    };
+    let refund_due = match InstallmentPlan::find_for_order(order.id, connection)? {
+        Some(installment_plan) => installment_plan.refund_order(order, refund_due, Some(user_id), connection)?,
+        None => refund_due,
+    };
 
     // Transfer tickets back to the organization wallets
     let mut tokens_per_asset: HashMap<Uuid, Vec<u64>> = HashMap::new();
@@ -234,7 +278,6 @@ pub async fn refund(
     let mut modified_tokens: HashMap<Uuid, Vec<u64>> = HashMap::new();
 
     let mut refund_breakdown: HashMap<PaymentMethods, i64> = HashMap::new();
-    let mut payment_remaining_balance_map: HashMap<Option<String>, i64> = HashMap::new();
     let mut amount_refunded = 0;
 
     // Begin transaction, if it fails at this point all transferred tickets are returned to wallets
@@ -255,7 +298,7 @@ pub async fn refund(
                         }
                     };
                     let user_wallet = Wallet::find(wallet_id, connection)?;
-                    state.config.tari_client.transfer_tokens(
+                    config.tari_client.transfer_tokens(
                         &user_wallet.secret_key,
                         &user_wallet.public_key,
                         &a,
@@ -288,32 +331,19 @@ pub async fn refund(
 
         // Perform refunds
 
-        // Negative payments / refunds cancel out remaining payment balance
-        for payment in order.payments(connection)? {
-            // Ignore payments that were only authorized
-            if payment.status == PaymentStatus::Authorized {
-                continue;
-            }
-
-            *payment_remaining_balance_map
-                .entry(payment.external_reference)
-                .or_insert(0) += payment.amount;
-        }
-
-        for payment in order.payments(connection)? {
-            if payment.status != PaymentStatus::Completed {
+        // Split payments are refunded back across each payment in proportion to what it covered
+        for (payment, amount_to_refund) in order.refund_allocations(refund_due, connection)? {
+            // Gift card and store credit payments always return to their own balance
+            if refund_to_store_credit
+                && payment.payment_method != PaymentMethods::GiftCard
+                && payment.payment_method != PaymentMethods::StoreCredit
+            {
+                payment.log_store_credit_refund(user_id, &refund, amount_to_refund, connection)?;
+                *refund_breakdown.entry(PaymentMethods::StoreCredit).or_insert(0) += amount_to_refund;
+                amount_refunded += amount_to_refund;
                 continue;
             }
 
-            let remaining_balance = payment_remaining_balance_map
-                .get(&payment.external_reference)
-                .map(|n| *n)
-                .unwrap_or(0);
-            if remaining_balance == 0 {
-                continue;
-            }
-
-            let amount_to_refund = cmp::min(refund_due - amount_refunded, remaining_balance);
             let mut refund_data = None;
             if !manual_override && payment.payment_method == PaymentMethods::CreditCard {
                 let mut organizations = order.organizations(connection)?;
@@ -324,9 +354,7 @@ pub async fn refund(
                     .unwrap_err());
                 }
                 let organization = organizations.remove(0);
-                let client = &state
-                    .service_locator
-                    .create_payment_processor(payment.provider, &organization)?;
+                let client = &service_locator.create_payment_processor(payment.provider, &organization)?;
 
                 refund_data = match payment.external_reference {
                     Some(ref external_reference) => Some(
@@ -343,7 +371,7 @@ pub async fn refund(
                     }
                 };
             }
-            payment.log_refund(user.id(), &refund, amount_to_refund, refund_data, connection)?;
+            payment.log_refund(user_id, &refund, amount_to_refund, refund_data, connection)?;
             *refund_breakdown.entry(payment.payment_method).or_insert(0) += amount_to_refund;
             amount_refunded += amount_to_refund;
         }
@@ -368,13 +396,14 @@ pub async fn refund(
                         let wallet_id = match wallet_id_per_asset.get(asset_id) {
                             Some(w) => w.clone(),
                             None => {
-                                return application::internal_server_error(
+                                return Err(application::internal_server_error::<HttpResponse>(
                                     "Could not complete this refund because wallet id not found for asset",
-                                );
+                                )
+                                .unwrap_err());
                             }
                         };
                         let user_wallet = Wallet::find(wallet_id, connection)?;
-                        state.config.tari_client.transfer_tokens(
+                        config.tari_client.transfer_tokens(
                             &organization_wallet.secret_key,
                             &organization_wallet.public_key,
                             &a,
@@ -383,9 +412,10 @@ pub async fn refund(
                         )?;
                     }
                     None => {
-                        return application::internal_server_error(
+                        return Err(application::internal_server_error::<HttpResponse>(
                             "Could not complete this refund because the asset is not assigned on the blockchain",
-                        );
+                        )
+                        .unwrap_err());
                     }
                 }
             }
@@ -397,7 +427,7 @@ pub async fn refund(
     }
 
     // Commit changes as payment completed
-    if state.config.environment != Environment::Test {
+    if config.environment != Environment::Test {
         conn.commit_transaction()?;
         conn.begin_transaction()?;
     }
@@ -408,13 +438,16 @@ pub async fn refund(
 
     // Communicate refund to user
     if let (Some(first_name), Some(email)) = (user.first_name, user.email) {
-        mailers::orders::refund_email(&first_name, email, &refund, &state.config, connection)?;
+        mailers::orders::refund_email(&first_name, email, &refund, config, connection)?;
     }
 
-    Ok(HttpResponse::Ok().json(json!(RefundResponse {
-        amount_refunded,
-        refund_breakdown
-    })))
+    Ok((
+        refund,
+        RefundResponse {
+            amount_refunded,
+            refund_breakdown,
+        },
+    ))
 }
 
 fn is_authorized_to_refund(
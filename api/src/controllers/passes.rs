@@ -0,0 +1,158 @@
+use crate::auth::user::User;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::helpers::application;
+use crate::models::{PassEventPathParameters, PathParameters};
+use actix_web::{
+    web::{Path, Query},
+    HttpResponse,
+};
+use db::models::*;
+use diesel::PgConnection;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct CreatePassRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub price_in_cents: i64,
+}
+
+#[derive(Deserialize)]
+pub struct AddPassEventRequest {
+    pub ticket_type_id: Uuid,
+    pub allocated_price_in_cents: i64,
+}
+
+#[derive(Serialize)]
+pub struct DisplayPass {
+    #[serde(flatten)]
+    pub pass: Pass,
+    pub events: Vec<PassEvent>,
+}
+
+impl DisplayPass {
+    fn from_pass(pass: Pass, conn: &PgConnection) -> Result<Self, ApiError> {
+        let events = pass.events(conn)?;
+        Ok(DisplayPass { pass, events })
+    }
+}
+
+pub async fn index(
+    (connection, path, query_parameters, user): (Connection, Path<PathParameters>, Query<PagingParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::TicketTypeRead, &organization, connection)?;
+
+    let passes = Pass::find_for_organization(organization.id, connection)?
+        .into_iter()
+        .map(|p| DisplayPass::from_pass(p, connection))
+        .collect::<Result<Vec<DisplayPass>, ApiError>>()?;
+
+    Ok(HttpResponse::Ok().json(&Payload::from_data(
+        passes,
+        query_parameters.page(),
+        query_parameters.limit(),
+        None,
+    )))
+}
+
+pub async fn create(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<CreatePassRequest>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::TicketTypeWrite, &organization, connection)?;
+
+    let json = json.into_inner();
+    let pass = Pass::create(organization.id, json.name, json.description, json.price_in_cents)
+        .commit(Some(user.id()), connection)?;
+
+    Ok(HttpResponse::Created().json(&DisplayPass::from_pass(pass, connection)?))
+}
+
+pub async fn show(
+    (connection, path, user): (Connection, Path<PathParameters>, OptionalUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let pass = Pass::find(path.id, connection)?;
+    if pass.status != PassStatus::Published {
+        match user.into_inner() {
+            Some(user) => user.requires_scope_for_organization(
+                Scopes::TicketTypeRead,
+                &pass.organization(connection)?,
+                connection,
+            )?,
+            None => return application::not_found(),
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(&DisplayPass::from_pass(pass, connection)?))
+}
+
+pub async fn update(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<PassEditableAttributes>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let pass = Pass::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::TicketTypeWrite, &pass.organization(connection)?, connection)?;
+
+    let pass = pass.update(json.into_inner(), Some(user.id()), connection)?;
+
+    Ok(HttpResponse::Ok().json(&DisplayPass::from_pass(pass, connection)?))
+}
+
+pub async fn publish(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let pass = Pass::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::TicketTypeWrite, &pass.organization(connection)?, connection)?;
+
+    let pass = pass.publish(Some(user.id()), connection)?;
+
+    Ok(HttpResponse::Ok().json(&DisplayPass::from_pass(pass, connection)?))
+}
+
+pub async fn cancel(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let pass = Pass::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::TicketTypeWrite, &pass.organization(connection)?, connection)?;
+
+    let pass = pass.cancel(Some(user.id()), connection)?;
+
+    Ok(HttpResponse::Ok().json(&DisplayPass::from_pass(pass, connection)?))
+}
+
+pub async fn add_event(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<AddPassEventRequest>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let pass = Pass::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::TicketTypeWrite, &pass.organization(connection)?, connection)?;
+
+    let pass_event = pass.add_event(
+        json.ticket_type_id,
+        json.allocated_price_in_cents,
+        Some(user.id()),
+        connection,
+    )?;
+
+    Ok(HttpResponse::Created().json(&pass_event))
+}
+
+pub async fn remove_event(
+    (connection, path, user): (Connection, Path<PassEventPathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let pass = Pass::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::TicketTypeWrite, &pass.organization(connection)?, connection)?;
+
+    pass.remove_event(path.event_id, Some(user.id()), connection)?;
+
+    Ok(HttpResponse::Ok().finish())
+}
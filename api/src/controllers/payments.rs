@@ -2,6 +2,7 @@ use crate::database::Connection;
 use crate::errors::*;
 use crate::extractors::OptionalUser;
 use crate::helpers::application;
+use crate::payments::{PaymentIntentStatus, PaymentProcessorBehavior};
 use crate::server::AppState;
 use actix_web::web::{Data, Path, Query};
 use actix_web::HttpResponse;
@@ -96,3 +97,83 @@ pub async fn callback(
         ))
     }
 }
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PaymentIntentQueryParams {
+    pub payment_intent: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PaymentIntentPathParams {
+    pub id: Uuid,
+}
+
+/// Landing point for customers returning from an extra authentication step (e.g. 3D Secure).
+/// The payment intent is always re-fetched from the provider rather than trusting the query.
+pub async fn payment_intent_callback(
+    (query, path, connection, state, user): (
+        Query<PaymentIntentQueryParams>,
+        Path<PaymentIntentPathParams>,
+        Connection,
+        Data<AppState>,
+        OptionalUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let conn = connection.get();
+    let mut order = Order::find(path.id, conn)?;
+    order.lock_version(conn)?;
+
+    let payment = match Payment::find_by_order(order.id, &query.payment_intent, conn).optional()? {
+        Some(p) => p,
+        None => return application::not_found(),
+    };
+
+    let success_url = format!(
+        "{}/tickets/{}/tickets/success?order_id={}",
+        state.config.front_end_url,
+        order.event_slug(conn)?,
+        order.id
+    );
+    if payment.status == PaymentStatus::Completed {
+        return application::redirect(&success_url);
+    }
+
+    let mut organizations = order.organizations(conn)?;
+    if organizations.len() != 1 {
+        return application::unprocessable("Orders containing more than one organization are not supported");
+    }
+    let processor = state
+        .service_locator
+        .create_payment_processor(payment.provider, &organizations.remove(0))?;
+    let behavior = match processor.behavior() {
+        PaymentProcessorBehavior::PaymentIntent(b) => b,
+        _ => return application::unprocessable("Payment provider does not support payment intents"),
+    };
+    let intent = behavior.retrieve_payment_intent(&query.payment_intent).await?;
+
+    jlog!(Debug, "Payments: payment intent callback", {"payment_id": payment.id, "order_id": order.id, "status": format!("{:?}", intent.status)});
+
+    match intent.status {
+        PaymentIntentStatus::Succeeded => {
+            if order.is_expired() {
+                if order.try_refresh_expired_cart(user.id(), conn).is_err() {
+                    jlog!(Debug, "Payments: Attempted to refresh expired cart but failed", {"payment_id": payment.id, "order_id": order.id})
+                }
+            }
+            payment.mark_complete(intent.to_json()?, user.id(), conn)?;
+            application::redirect(&success_url)
+        }
+        PaymentIntentStatus::Processing | PaymentIntentStatus::RequiresAction => {
+            payment.mark_pending_ipn(user.id(), conn)?;
+            application::redirect(&success_url)
+        }
+        PaymentIntentStatus::Failed => {
+            payment.mark_cancelled(intent.to_json()?, user.id(), conn)?;
+            application::redirect(&format!(
+                "{}/tickets/{}/tickets/confirmation",
+                state.config.front_end_url,
+                order.event_slug(conn)?
+            ))
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use crate::auth::user::User;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::helpers::application;
+use crate::models::{OrderItemPathParameters, PathParameters, ProductVariantPathParameters};
+use actix_web::{
+    web::{Path, Query},
+    HttpResponse,
+};
+use db::models::*;
+use diesel::PgConnection;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct CreateProductRequest {
+    pub event_id: Option<Uuid>,
+    pub name: String,
+    pub description: Option<String>,
+    pub price_in_cents: i64,
+    pub per_ticket_limit: Option<i32>,
+    #[serde(default)]
+    pub gift_card: bool,
+    #[serde(default)]
+    pub variants: Vec<CreateProductVariantRequest>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateProductVariantRequest {
+    pub name: String,
+    pub price_in_cents: Option<i64>,
+    pub stock_quantity: i32,
+}
+
+#[derive(Deserialize)]
+pub struct RedeemProductVoucherRequest {
+    pub quantity: u32,
+}
+
+#[derive(Serialize)]
+pub struct DisplayProductVariant {
+    #[serde(flatten)]
+    pub variant: ProductVariant,
+    pub unit_price_in_cents: i64,
+    pub available: i64,
+}
+
+#[derive(Serialize)]
+pub struct DisplayProduct {
+    #[serde(flatten)]
+    pub product: Product,
+    pub variants: Vec<DisplayProductVariant>,
+}
+
+impl DisplayProduct {
+    fn from_product(product: Product, conn: &PgConnection) -> Result<Self, ApiError> {
+        let mut variants = Vec::new();
+        for variant in product.variants(conn)? {
+            variants.push(DisplayProductVariant {
+                unit_price_in_cents: variant.unit_price_in_cents(&product),
+                available: variant.available_count(None, conn)?,
+                variant,
+            });
+        }
+        Ok(DisplayProduct { product, variants })
+    }
+}
+
+pub async fn index(
+    (connection, path, query_parameters, user): (Connection, Path<PathParameters>, Query<PagingParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::TicketTypeRead, &organization, connection)?;
+
+    let products = Product::find_for_organization(organization.id, connection)?
+        .into_iter()
+        .map(|p| DisplayProduct::from_product(p, connection))
+        .collect::<Result<Vec<DisplayProduct>, ApiError>>()?;
+
+    Ok(HttpResponse::Ok().json(&Payload::from_data(
+        products,
+        query_parameters.page(),
+        query_parameters.limit(),
+        None,
+    )))
+}
+
+pub async fn event_index(
+    (connection, path, query_parameters): (Connection, Path<PathParameters>, Query<PagingParameters>),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let products = Product::find_published_for_event(path.id, connection)?
+        .into_iter()
+        .map(|p| DisplayProduct::from_product(p, connection))
+        .collect::<Result<Vec<DisplayProduct>, ApiError>>()?;
+
+    Ok(HttpResponse::Ok().json(&Payload::from_data(
+        products,
+        query_parameters.page(),
+        query_parameters.limit(),
+        None,
+    )))
+}
+
+pub async fn create(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<CreateProductRequest>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::TicketTypeWrite, &organization, connection)?;
+
+    let json = json.into_inner();
+    let mut new_product = Product::create(
+        organization.id,
+        json.event_id,
+        json.name,
+        json.description,
+        json.price_in_cents,
+        json.per_ticket_limit,
+    );
+    new_product.gift_card = json.gift_card;
+    let product = new_product.commit(Some(user.id()), connection)?;
+    for variant in json.variants {
+        ProductVariant::create(product.id, variant.name, variant.price_in_cents, variant.stock_quantity)
+            .commit(Some(user.id()), connection)?;
+    }
+
+    Ok(HttpResponse::Created().json(&DisplayProduct::from_product(product, connection)?))
+}
+
+pub async fn show(
+    (connection, path, user): (Connection, Path<PathParameters>, OptionalUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let product = Product::find(path.id, connection)?;
+    if product.status != ProductStatus::Published {
+        match user.into_inner() {
+            Some(user) => user.requires_scope_for_organization(
+                Scopes::TicketTypeRead,
+                &product.organization(connection)?,
+                connection,
+            )?,
+            None => return application::not_found(),
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(&DisplayProduct::from_product(product, connection)?))
+}
+
+pub async fn update(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<ProductEditableAttributes>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let product = Product::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::TicketTypeWrite, &product.organization(connection)?, connection)?;
+
+    let product = product.update(json.into_inner(), Some(user.id()), connection)?;
+
+    Ok(HttpResponse::Ok().json(&DisplayProduct::from_product(product, connection)?))
+}
+
+pub async fn create_variant(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateProductVariantRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let product = Product::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::TicketTypeWrite, &product.organization(connection)?, connection)?;
+
+    let json = json.into_inner();
+    let variant = ProductVariant::create(product.id, json.name, json.price_in_cents, json.stock_quantity)
+        .commit(Some(user.id()), connection)?;
+
+    Ok(HttpResponse::Created().json(&variant))
+}
+
+pub async fn update_variant(
+    (connection, path, json, user): (
+        Connection,
+        Path<ProductVariantPathParameters>,
+        Json<ProductVariantEditableAttributes>,
+        User,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let product = Product::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::TicketTypeWrite, &product.organization(connection)?, connection)?;
+
+    let variant = ProductVariant::find_for_update(path.variant_id, connection)?;
+    if variant.product_id != product.id {
+        return application::not_found();
+    }
+    let variant = variant.update(json.into_inner(), Some(user.id()), connection)?;
+
+    Ok(HttpResponse::Ok().json(&variant))
+}
+
+/// Box office redemption of product vouchers purchased with an order
+pub async fn redeem_voucher(
+    (connection, path, json, user): (
+        Connection,
+        Path<OrderItemPathParameters>,
+        Json<RedeemProductVoucherRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let order_item = OrderItem::find(path.order_item_id, connection)?;
+    if order_item.order_id != path.id {
+        return application::not_found();
+    }
+    let event = order_item.event(connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::RedeemTicket,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    let order_item = order_item.redeem_product_voucher(json.quantity, Some(user.id()), connection)?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "id": order_item.id,
+        "product_variant_id": order_item.product_variant_id,
+        "quantity": order_item.quantity,
+        "refunded_quantity": order_item.refunded_quantity,
+        "redeemed_quantity": order_item.redeemed_quantity,
+    })))
+}
@@ -0,0 +1,233 @@
+use crate::auth::user::User;
+use crate::communications::mailers;
+use crate::controllers::orders;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::helpers::application;
+use crate::models::{PathParameters, WebPayload};
+use crate::server::AppState;
+use actix_web::{
+    http::StatusCode,
+    web::{Data, Path, Query},
+    HttpResponse,
+};
+use db::models::*;
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct NewRefundRequestRequest {
+    pub ticket_instance_ids: Vec<Uuid>,
+    pub reason: String,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct DenyRefundRequestRequest {
+    pub response_note: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct RefundPolicyRequest {
+    pub approval_type: RefundApprovalTypes,
+    pub deadline_hours_before_event: i32,
+    pub retain_fees: bool,
+}
+
+pub async fn index_for_order(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let order = Order::find(path.id, connection)?;
+    if order.on_behalf_of_user_id.unwrap_or(order.user_id) != user.id() {
+        user.requires_scope_for_order(Scopes::OrderRead, &order, connection)?;
+    }
+    Ok(HttpResponse::Ok().json(RefundRequest::find_for_order(order.id, connection)?))
+}
+
+pub async fn index_for_organization(
+    (connection, path, query, user): (Connection, Path<PathParameters>, Query<PagingParameters>, User),
+) -> Result<WebPayload<RefundRequest>, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrderRefund, &organization, connection)?;
+
+    let event_id = match query.get_tag_as_str("event_id") {
+        Some(s) => Some(s.parse::<Uuid>()?),
+        None => None,
+    };
+    let status = match query.get_tag_as_str("status") {
+        Some(s) => Some(s.parse()?),
+        None => Some(RefundRequestStatus::Pending),
+    };
+    let refund_requests = RefundRequest::find_for_organization(
+        organization.id,
+        event_id,
+        status,
+        query.page(),
+        query.limit(),
+        connection,
+    )?;
+
+    Ok(WebPayload::new(StatusCode::OK, refund_requests))
+}
+
+pub async fn create(
+    (conn, path, json, user, state): (
+        Connection,
+        Path<PathParameters>,
+        Json<NewRefundRequestRequest>,
+        User,
+        Data<AppState>,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = conn.get();
+    let mut order = Order::find(path.id, connection)?;
+    if order.on_behalf_of_user_id.unwrap_or(order.user_id) != user.id() {
+        return application::forbidden("You do not have access to this order");
+    }
+
+    let json = json.into_inner();
+    let mut refund_request =
+        RefundRequest::create(order.id, user.id(), json.ticket_instance_ids, json.reason.clone()).commit(connection)?;
+    mailers::refund_requests::request_received(&state.config, &user.user, &refund_request, connection)?;
+
+    let refund_policy = refund_request.refund_policy(connection)?;
+    if refund_policy.approval_type == RefundApprovalTypes::Automatic {
+        let items = refund_request.refund_items(connection)?;
+        let (refund, _) = orders::perform_refund(
+            &mut order,
+            &items,
+            user.id(),
+            Some(json.reason),
+            false,
+            refund_policy.retain_fees,
+            false,
+            &conn,
+            &state.config,
+            &state.service_locator,
+        )?;
+        refund_request = refund_request.approve(&refund, None, connection)?;
+    } else {
+        mailers::refund_requests::request_submitted(&state.config, &refund_request, connection)?;
+    }
+
+    Ok(HttpResponse::Created().json(&refund_request))
+}
+
+pub async fn approve(
+    (conn, path, user, state): (Connection, Path<PathParameters>, User, Data<AppState>),
+) -> Result<HttpResponse, ApiError> {
+    let connection = conn.get();
+    let refund_request = RefundRequest::find(path.id, connection)?;
+    let event = refund_request.event(connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::OrderRefund,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+    // Checked before refunding as the payment processor refund cannot be rolled back
+    refund_request.validate_pending()?;
+
+    let refund_policy = refund_request.refund_policy(connection)?;
+    let items = refund_request.refund_items(connection)?;
+    let mut order = refund_request.order(connection)?;
+    let (refund, _) = orders::perform_refund(
+        &mut order,
+        &items,
+        user.id(),
+        Some(refund_request.reason.clone()),
+        false,
+        refund_policy.retain_fees,
+        false,
+        &conn,
+        &state.config,
+        &state.service_locator,
+    )?;
+    let refund_request = refund_request.approve(&refund, Some(user.id()), connection)?;
+
+    Ok(HttpResponse::Ok().json(&refund_request))
+}
+
+pub async fn deny(
+    (conn, path, json, user, state): (
+        Connection,
+        Path<PathParameters>,
+        Json<DenyRefundRequestRequest>,
+        User,
+        Data<AppState>,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = conn.get();
+    let refund_request = RefundRequest::find(path.id, connection)?;
+    let event = refund_request.event(connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::OrderRefund,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    let refund_request = refund_request.deny(json.into_inner().response_note, user.id(), connection)?;
+    mailers::refund_requests::request_denied(
+        &state.config,
+        &refund_request.user(connection)?,
+        &refund_request,
+        connection,
+    )?;
+
+    Ok(HttpResponse::Ok().json(&refund_request))
+}
+
+pub async fn show_policy(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    match RefundPolicy::find_for_event(event.id, connection)? {
+        Some(refund_policy) => Ok(HttpResponse::Ok().json(&refund_policy)),
+        None => application::not_found(),
+    }
+}
+
+pub async fn update_policy(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<RefundPolicyRequest>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    let json = json.into_inner();
+    let refund_policy = match RefundPolicy::find_for_event(event.id, connection)? {
+        Some(refund_policy) => refund_policy.update(
+            RefundPolicyEditableAttributes {
+                approval_type: Some(json.approval_type),
+                deadline_hours_before_event: Some(json.deadline_hours_before_event),
+                retain_fees: Some(json.retain_fees),
+            },
+            Some(user.id()),
+            connection,
+        )?,
+        None => RefundPolicy::create(
+            event.id,
+            json.approval_type,
+            json.deadline_hours_before_event,
+            json.retain_fees,
+        )
+        .commit(Some(user.id()), connection)?,
+    };
+
+    Ok(HttpResponse::Ok().json(&refund_policy))
+}
@@ -68,6 +68,7 @@ pub async fn get_report(
         "audit_report" => audit_report((connection, query, path, user)),
         "reconciliation_summary" => reconciliation_summary_report((connection, query, path, user)),
         "reconciliation_details" => reconciliation_detail_report((connection, query, path, user)),
+        "reconciliation_payments" => reconciliation_payments_report((connection, query, path, user)),
         "promo_code" => promo_code_report((connection, query, path, user)),
         _ => application::not_found(),
     }
@@ -197,12 +198,15 @@ pub fn scan_counts(
         let organization = event.organization(connection)?;
         user.requires_scope_for_organization_event(Scopes::ScanReportRead, &organization, &event, connection)?;
 
-        let result = Report::scan_count_report(
-            query.event_id.unwrap(),
-            query.page.unwrap_or(0),
-            query.limit.unwrap_or(100),
-            connection,
-        )?;
+        let result = ScanCountReport {
+            scan_counts: Report::scan_count_report(
+                event_id,
+                query.page.unwrap_or(0),
+                query.limit.unwrap_or(100),
+                connection,
+            )?,
+            occupancy: Report::occupancy_report(event_id, connection)?,
+        };
         Ok(HttpResponse::Ok().json(result))
     } else {
         application::bad_request("event_id parameter is required")
@@ -256,6 +260,17 @@ pub fn reconciliation_summary_report(
     Ok(HttpResponse::Ok().json(result))
 }
 
+pub fn reconciliation_payments_report(
+    (connection, query, path, user): (Connection, Query<ReportQueryParameters>, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgFinancialReports, &organization, connection)?;
+
+    let result = Report::reconciliation_payments_report(path.id, query.start_utc, query.end_utc, connection)?;
+    Ok(HttpResponse::Ok().json(result))
+}
+
 pub fn reconciliation_detail_report(
     (connection, query, path, user): (Connection, Query<ReportQueryParameters>, Path<PathParameters>, AuthUser),
 ) -> Result<HttpResponse, ApiError> {
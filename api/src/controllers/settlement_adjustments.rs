@@ -4,7 +4,11 @@ use crate::errors::*;
 use crate::extractors::*;
 use crate::helpers::application;
 use crate::models::PathParameters;
-use actix_web::{web::Path, HttpResponse};
+use crate::server::AppState;
+use actix_web::{
+    web::{Data, Path},
+    HttpResponse,
+};
 use db::models::*;
 
 pub async fn index(
@@ -22,14 +26,18 @@ pub struct NewSettlementAdjustmentRequest {
     pub amount_in_cents: i64,
     pub note: Option<String>,
     pub settlement_adjustment_type: SettlementAdjustmentTypes,
+    /// Defaults to the primary currency
+    #[serde(default)]
+    pub currency: Option<String>,
 }
 
 pub async fn create(
-    (connection, path, json, user): (
+    (connection, path, json, user, state): (
         Connection,
         Path<PathParameters>,
         Json<NewSettlementAdjustmentRequest>,
         AuthUser,
+        Data<AppState>,
     ),
 ) -> Result<HttpResponse, ApiError> {
     user.requires_scope(Scopes::SettlementAdjustmentWrite)?;
@@ -45,6 +53,9 @@ pub async fn create(
         json.settlement_adjustment_type,
         json.note.clone(),
         json.amount_in_cents,
+        json.currency
+            .clone()
+            .unwrap_or_else(|| state.config.primary_currency.clone()),
     )
     .commit(connection)?;
     Ok(HttpResponse::Created().json(&settlement_adjustment))
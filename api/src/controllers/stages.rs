@@ -85,7 +85,7 @@ pub async fn delete(
     Ok(HttpResponse::Ok().json(json!({})))
 }
 
-fn check_access(venue: &Venue, user: &AuthUser, connection: &PgConnection) -> Result<(), ApiError> {
+pub(crate) fn check_access(venue: &Venue, user: &AuthUser, connection: &PgConnection) -> Result<(), ApiError> {
     let mut has_create_access = false;
     for organization in venue.organizations(connection)? {
         has_create_access =
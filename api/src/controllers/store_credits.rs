@@ -0,0 +1,65 @@
+use crate::auth::user::User;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::models::{OrganizationUserPathParameters, PathParameters};
+use actix_web::{web::Path, HttpResponse};
+use db::models::*;
+use std::collections::HashMap;
+
+#[derive(Deserialize)]
+pub struct CreateStoreCreditRequest {
+    pub amount_in_cents: i64,
+    pub currency: String,
+    pub note: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct DisplayStoreCredit {
+    pub balances: HashMap<String, i64>,
+    pub transactions: Vec<StoreCreditTransaction>,
+}
+
+/// The current user's store credit with the organization
+pub async fn show(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+
+    let transactions = StoreCreditTransaction::find_for_user(user.id(), organization.id, connection)?;
+    let mut balances: HashMap<String, i64> = HashMap::new();
+    for transaction in &transactions {
+        *balances.entry(transaction.currency.clone()).or_insert(0) += transaction.amount_in_cents;
+    }
+
+    Ok(HttpResponse::Ok().json(&DisplayStoreCredit { balances, transactions }))
+}
+
+/// Credits or, with a negative amount, debits a user's store credit with the organization
+pub async fn create(
+    (connection, path, json, user): (
+        Connection,
+        Path<OrganizationUserPathParameters>,
+        Json<CreateStoreCreditRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdmin, &organization, connection)?;
+
+    let json = json.into_inner();
+    let transaction = StoreCreditTransaction::create(
+        path.user_id,
+        organization.id,
+        json.amount_in_cents,
+        json.currency,
+        None,
+        None,
+        json.note,
+        Some(user.id()),
+    )
+    .commit(connection)?;
+    Ok(HttpResponse::Created().json(&transaction))
+}
@@ -0,0 +1,45 @@
+use crate::auth::user::User;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::models::PathParameters;
+use actix_web::{
+    web::{Path, Query},
+    HttpResponse,
+};
+use db::models::*;
+
+pub async fn index(
+    (connection, query_parameters, user): (Connection, Query<PagingParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    user.requires_scope(Scopes::TaxRuleRead)?;
+    let tax_rules = TaxRule::all(query_parameters.page(), query_parameters.limit(), connection.get())?;
+
+    Ok(HttpResponse::Ok().json(&tax_rules))
+}
+
+pub async fn show(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    user.requires_scope(Scopes::TaxRuleRead)?;
+    let tax_rule = TaxRule::find(path.id, connection.get())?;
+    Ok(HttpResponse::Ok().json(&tax_rule))
+}
+
+pub async fn create(
+    (connection, new_tax_rule, user): (Connection, Json<NewTaxRule>, User),
+) -> Result<HttpResponse, ApiError> {
+    user.requires_scope(Scopes::TaxRuleWrite)?;
+    let tax_rule = new_tax_rule.into_inner().commit(Some(user.id()), connection.get())?;
+    Ok(HttpResponse::Created().json(&tax_rule))
+}
+
+pub async fn update(
+    (connection, path, attributes, user): (Connection, Path<PathParameters>, Json<TaxRuleEditableAttributes>, User),
+) -> Result<HttpResponse, ApiError> {
+    user.requires_scope(Scopes::TaxRuleWrite)?;
+    let connection = connection.get();
+    let tax_rule = TaxRule::find(path.id, connection)?;
+    let tax_rule = tax_rule.update(attributes.into_inner(), Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(&tax_rule))
+}
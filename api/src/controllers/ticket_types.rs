@@ -158,6 +158,18 @@ pub struct UpdateTicketTypeRequest {
     #[serde(default)]
     pub app_sales_enabled: Option<bool>,
     pub rank: Option<i32>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub venue_section_id: Option<Option<Uuid>>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub inventory_pool_id: Option<Option<Uuid>>,
+    #[serde(default)]
+    pub max_entries: Option<i32>,
+    #[serde(default)]
+    pub required_attendee_details: Option<Vec<AttendeeDetails>>,
+    #[serde(default)]
+    pub attendee_name_change_fee_in_cents: Option<i64>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub attendee_details_lock_hours: Option<Option<i32>>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -364,6 +376,12 @@ pub async fn update(
         additional_fee_in_cents: data.additional_fee_in_cents,
         app_sales_enabled: data.app_sales_enabled,
         rank: data.rank,
+        venue_section_id: data.venue_section_id,
+        inventory_pool_id: data.inventory_pool_id,
+        max_entries: data.max_entries,
+        required_attendee_details: data.required_attendee_details.clone(),
+        attendee_name_change_fee_in_cents: data.attendee_name_change_fee_in_cents,
+        attendee_details_lock_hours: data.attendee_details_lock_hours,
     };
     let updated_ticket_type = ticket_type.update(update_parameters, Some(user.id()), connection)?;
 
@@ -5,7 +5,9 @@ use crate::errors::*;
 use crate::extractors::*;
 use crate::helpers::application;
 use crate::models::{OptionalPathParameters, PathParameters};
+use crate::payments::PaymentProcessorBehavior;
 use crate::server::AppState;
+use crate::SITE_NAME;
 use actix_web::{
     web::{Data, Path, Query},
     HttpResponse,
@@ -93,24 +95,95 @@ pub async fn show(
     Ok(HttpResponse::Ok().json(&ticket_response))
 }
 
+#[derive(Deserialize, Serialize)]
+pub struct UpdateTicketRequest {
+    #[serde(flatten)]
+    pub attributes: UpdateTicketInstanceAttributes,
+    /// Name change fee shown to the ticket holder, the update is rejected if it does not match the
+    /// fee that would be charged
+    #[serde(default)]
+    pub expected_fee_in_cents: Option<i64>,
+}
+
 pub async fn update(
-    (connection, parameters, ticket_parameters, user): (
+    (connection, parameters, update_request, user, state): (
         Connection,
         Path<PathParameters>,
-        Json<UpdateTicketInstanceAttributes>,
+        Json<UpdateTicketRequest>,
         User,
+        Data<AppState>,
     ),
 ) -> Result<HttpResponse, ApiError> {
     let connection = connection.get();
-    let ticket_parameters = ticket_parameters.into_inner();
+    let update_request = update_request.into_inner();
+    let ticket_parameters = update_request.attributes;
     let ticket = TicketInstance::find(parameters.id, connection)?;
-    if ticket.owner(connection)?.id == user.id() {
+    let is_owner = ticket.owner(connection)?.id == user.id();
+    if is_owner {
         user.requires_scope(Scopes::TicketWriteOwn)?;
     } else {
         let organization = ticket.organization(connection)?;
         user.requires_scope_for_organization(Scopes::TicketWrite, &organization, connection)?;
     }
-    ticket.update(ticket_parameters.into(), user.id(), connection)?;
+
+    // Staff updating attendee details on the holder's behalf waive the name change fee
+    let fee_in_cents = if is_owner {
+        ticket.attendee_name_change_fee(&ticket_parameters, connection)?
+    } else {
+        0
+    };
+    if fee_in_cents > 0 && update_request.expected_fee_in_cents != Some(fee_in_cents) {
+        return application::unprocessable(&format!(
+            "Changing the attendee name incurs a fee of {} cents which must be accepted",
+            fee_in_cents
+        ));
+    }
+    if fee_in_cents > 0 {
+        let payment_method = match user.user.default_payment_method(connection).optional()? {
+            Some(payment_method) => payment_method,
+            None => {
+                return application::unprocessable(
+                    "Could not change the attendee name because user has no default payment method",
+                );
+            }
+        };
+        let event = ticket.event(connection)?;
+        let client = state
+            .service_locator
+            .create_payment_processor(payment_method.name, &event.organization(connection)?)?;
+        let behavior = match client.behavior() {
+            PaymentProcessorBehavior::AuthThenComplete(behavior) => behavior,
+            _ => {
+                return application::unprocessable(
+                    "Could not change the attendee name because the payment processor does not support saved payment methods",
+                );
+            }
+        };
+
+        let auth_result = behavior
+            .auth(
+                &payment_method.provider,
+                fee_in_cents,
+                &event.currency,
+                SITE_NAME,
+                vec![("ticket_id".to_string(), ticket.id.to_string())],
+            )
+            .await?;
+        let charge_result = behavior.complete_authed_charge(&auth_result.id).await?;
+        let fee_payment = NameChangeFeePayment {
+            amount_in_cents: fee_in_cents,
+            payment_provider: behavior.payment_provider(),
+            external_reference: auth_result.id.clone(),
+            provider_data: charge_result.to_json()?,
+        };
+        if let Err(e) = ticket.update_with_name_change_fee(ticket_parameters, user.id(), Some(fee_payment), connection)
+        {
+            client.refund(&auth_result.id).await?;
+            return Err(e.into());
+        }
+    } else {
+        ticket.update(ticket_parameters, user.id(), connection)?;
+    }
 
     let (event, user, ticket) = TicketInstance::find_for_display(parameters.id, connection)?;
     let ticket_response = ShowTicketResponse { event, user, ticket };
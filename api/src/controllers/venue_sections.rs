@@ -0,0 +1,122 @@
+use crate::auth::user::User as AuthUser;
+use crate::controllers::stages::check_access;
+use crate::database::Connection;
+use actix_web::{
+    web::{Path, Query},
+    HttpResponse,
+};
+use db::models::*;
+use uuid::Uuid;
+
+use crate::errors::*;
+use crate::extractors::*;
+use crate::models::PathParameters;
+
+pub async fn index(
+    (connection, path_parameters, query_parameters): (Connection, Path<PathParameters>, Query<PagingParameters>),
+) -> Result<HttpResponse, ApiError> {
+    let venue_sections = VenueSection::find_by_venue_id(path_parameters.id, connection.get())?;
+
+    Ok(HttpResponse::Ok().json(&Payload::from_data(
+        venue_sections,
+        query_parameters.page(),
+        query_parameters.limit(),
+        None,
+    )))
+}
+
+#[derive(Deserialize)]
+pub struct CreateVenueSection {
+    pub name: String,
+    pub stage_id: Option<Uuid>,
+}
+
+pub async fn create(
+    (connection, parameters, create_venue_section, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateVenueSection>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let venue = Venue::find(parameters.id, connection)?;
+    check_access(&venue, &user, connection)?;
+
+    let venue_section = VenueSection::create(
+        venue.id,
+        create_venue_section.stage_id,
+        create_venue_section.name.clone(),
+    )
+    .commit(connection)?;
+
+    Ok(HttpResponse::Created().json(&venue_section))
+}
+
+pub async fn update(
+    (connection, parameters, venue_section_parameters, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<VenueSectionEditableAttributes>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let venue_section = VenueSection::find(parameters.id, connection)?;
+    check_access(&venue_section.venue(connection)?, &user, connection)?;
+
+    let updated_venue_section = venue_section.update(venue_section_parameters.into_inner(), connection)?;
+    Ok(HttpResponse::Ok().json(updated_venue_section))
+}
+
+pub async fn seats((connection, parameters): (Connection, Path<PathParameters>)) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let venue_section = VenueSection::find(parameters.id, connection)?;
+
+    Ok(HttpResponse::Ok().json(&venue_section.seats(connection)?))
+}
+
+#[derive(Deserialize)]
+pub struct CreateVenueSeat {
+    pub row_label: String,
+    pub seat_number: String,
+    #[serde(default)]
+    pub accessible: bool,
+}
+
+#[derive(Deserialize)]
+pub struct CreateVenueSeatsRequest {
+    pub seats: Vec<CreateVenueSeat>,
+}
+
+pub async fn create_seats(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateVenueSeatsRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let venue_section = VenueSection::find(parameters.id, connection)?;
+    check_access(&venue_section.venue(connection)?, &user, connection)?;
+
+    let new_seats: Vec<NewVenueSeat> = json
+        .into_inner()
+        .seats
+        .into_iter()
+        .map(|s| VenueSeat::create(venue_section.id, s.row_label, s.seat_number, s.accessible))
+        .collect();
+    let seats = NewVenueSeat::commit_multiple(&new_seats, connection)?;
+
+    Ok(HttpResponse::Created().json(&seats))
+}
+
+pub async fn event_seats(
+    (connection, parameters): (Connection, Path<PathParameters>),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(parameters.id, connection)?;
+
+    Ok(HttpResponse::Ok().json(&VenueSeat::find_for_event(event.id, connection)?))
+}
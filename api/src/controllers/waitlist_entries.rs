@@ -0,0 +1,83 @@
+use crate::auth::user::User as AuthUser;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::helpers::application;
+use crate::models::EventTicketPathParameters;
+use actix_web::{
+    web::{Path, Query},
+    HttpResponse,
+};
+use db::models::*;
+use diesel::PgConnection;
+
+#[derive(Deserialize)]
+pub struct JoinWaitlistRequest {
+    pub quantity: i32,
+}
+
+#[derive(Serialize)]
+pub struct DisplayWaitlistEntry {
+    #[serde(flatten)]
+    pub waitlist_entry: WaitlistEntry,
+    pub position: Option<i64>,
+}
+
+impl DisplayWaitlistEntry {
+    fn from_waitlist_entry(waitlist_entry: WaitlistEntry, conn: &PgConnection) -> Result<Self, ApiError> {
+        let position = waitlist_entry.position(conn)?;
+        Ok(DisplayWaitlistEntry {
+            waitlist_entry,
+            position,
+        })
+    }
+}
+
+pub async fn index(
+    (connection, query_parameters, user): (Connection, Query<PagingParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let waitlist_entries = WaitlistEntry::find_for_user(user.id(), connection)?
+        .into_iter()
+        .map(|e| DisplayWaitlistEntry::from_waitlist_entry(e, connection))
+        .collect::<Result<Vec<DisplayWaitlistEntry>, ApiError>>()?;
+
+    Ok(HttpResponse::Ok().json(&Payload::from_data(
+        waitlist_entries,
+        query_parameters.page(),
+        query_parameters.limit(),
+        None,
+    )))
+}
+
+pub async fn create(
+    (connection, path, json, user): (
+        Connection,
+        Path<EventTicketPathParameters>,
+        Json<JoinWaitlistRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let ticket_type = TicketType::find(path.ticket_type_id, connection)?;
+    if ticket_type.event_id != path.event_id {
+        return application::not_found();
+    }
+
+    let waitlist_entry = WaitlistEntry::create(ticket_type.id, user.id(), json.quantity).commit(connection)?;
+
+    Ok(HttpResponse::Created().json(&DisplayWaitlistEntry::from_waitlist_entry(waitlist_entry, connection)?))
+}
+
+pub async fn destroy(
+    (connection, path, user): (Connection, Path<EventTicketPathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let waitlist_entry = match WaitlistEntry::find_active_for_user(path.ticket_type_id, user.id(), connection)? {
+        Some(waitlist_entry) => waitlist_entry,
+        None => return application::not_found(),
+    };
+    waitlist_entry.cancel(Some(user.id()), connection)?;
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,92 @@
+use crate::config::Config;
+use crate::database::Connection;
+use crate::domain_events::executor_future::ExecutorFuture;
+use crate::domain_events::routing::DomainActionExecutor;
+use crate::errors::*;
+use crate::utils::ServiceLocator;
+use db::prelude::*;
+use futures::future;
+use log::Level::Error;
+use uuid::Uuid;
+
+pub struct DefaultInstallmentPlanExecutor {
+    config: Config,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct DefaultInstallmentPlanPayload {
+    pub installment_plan_id: Uuid,
+}
+
+impl DomainActionExecutor for DefaultInstallmentPlanExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::pin(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Default installment plan action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::pin(future::err(e)))
+            }
+        }
+    }
+}
+
+impl DefaultInstallmentPlanExecutor {
+    pub fn new(config: Config) -> DefaultInstallmentPlanExecutor {
+        DefaultInstallmentPlanExecutor { config }
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), ApiError> {
+        let connection = conn.get();
+        let payload: DefaultInstallmentPlanPayload = serde_json::from_value(action.payload.clone())?;
+        let plan = InstallmentPlan::find(payload.installment_plan_id, connection)?;
+        // A retry after a failed refund resumes with the payments that have not been refunded yet
+        let plan = match plan.status {
+            InstallmentPlanStatus::Active => plan.default(None, connection)?,
+            InstallmentPlanStatus::Defaulted => plan,
+            _ => return Ok(()),
+        };
+        if !plan.refund_deposit_on_default {
+            return Ok(());
+        }
+        self.commit(conn)?;
+
+        // Everything collected on the plan is returned to the payments it was charged to. Each
+        // charge is refunded and recorded on its own so a failure part way through is never
+        // refunded twice.
+        let order = Order::find(plan.order_id, connection)?;
+        let organization = Event::find(plan.event_id, connection)?.organization(connection)?;
+        let service_locator = ServiceLocator::new(&self.config)?;
+        for (payment, amount_to_refund) in order.refundable_payments(connection)? {
+            let refund_data = match (payment.payment_method, &payment.external_reference) {
+                (PaymentMethods::CreditCard, Some(external_reference)) => {
+                    let client = service_locator.create_payment_processor(payment.provider, &organization)?;
+                    Some(
+                        client
+                            .partial_refund_blocking(external_reference, amount_to_refund)?
+                            .to_json()?,
+                    )
+                }
+                _ => None,
+            };
+            let refund = Refund::create(
+                order.id,
+                plan.created_by,
+                Some("Installment plan defaulted".to_string()),
+                false,
+            )
+            .commit(connection)?;
+            payment.log_refund(plan.created_by, &refund, amount_to_refund, refund_data, connection)?;
+            self.commit(conn)?;
+        }
+
+        Ok(())
+    }
+
+    fn commit(&self, conn: &Connection) -> Result<(), ApiError> {
+        if self.config.environment != Environment::Test {
+            conn.commit_transaction()?;
+            conn.begin_transaction()?;
+        }
+        Ok(())
+    }
+}
@@ -1,27 +1,39 @@
 pub use self::broadcast_push_notification::*;
+pub use self::default_installment_plan::*;
 pub use self::finalize_settlements::*;
+pub use self::process_event_refund_batch::*;
+pub use self::process_installment_payment::*;
 pub use self::process_payment_ipn::*;
 pub use self::process_settlement_report::*;
 pub use self::process_transfer_drip_event::*;
+pub use self::process_waitlist::*;
 pub use self::regenerate_drip_actions::*;
 pub use self::release_hold_inventory::*;
 pub use self::retarget_abandoned_orders::*;
 pub use self::send_automatic_report_emails::*;
 pub use self::send_communication::*;
+pub use self::send_event_rescheduled_communications::*;
 pub use self::send_order_complete::*;
 pub use self::submit_sitemap_to_search_engines::*;
+pub use self::update_dynamic_pricing::*;
 pub use self::update_genres::*;
 
 mod broadcast_push_notification;
+mod default_installment_plan;
 mod finalize_settlements;
+mod process_event_refund_batch;
+mod process_installment_payment;
 mod process_payment_ipn;
 mod process_settlement_report;
 mod process_transfer_drip_event;
+mod process_waitlist;
 mod regenerate_drip_actions;
 mod release_hold_inventory;
 mod retarget_abandoned_orders;
 mod send_automatic_report_emails;
 mod send_communication;
+mod send_event_rescheduled_communications;
 mod send_order_complete;
 mod submit_sitemap_to_search_engines;
+mod update_dynamic_pricing;
 mod update_genres;
@@ -0,0 +1,111 @@
+use crate::config::Config;
+use crate::controllers::orders;
+use crate::database::Connection;
+use crate::domain_events::executor_future::ExecutorFuture;
+use crate::domain_events::routing::DomainActionExecutor;
+use crate::errors::*;
+use crate::utils::ServiceLocator;
+use db::prelude::*;
+use diesel::Connection as DieselConnection;
+use futures::future;
+use log::Level::Error;
+use uuid::Uuid;
+
+pub struct ProcessEventRefundBatchExecutor {
+    config: Config,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ProcessEventRefundBatchPayload {
+    pub event_refund_batch_id: Uuid,
+}
+
+impl DomainActionExecutor for ProcessEventRefundBatchExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::pin(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Process event refund batch action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::pin(future::err(e)))
+            }
+        }
+    }
+}
+
+impl ProcessEventRefundBatchExecutor {
+    pub fn new(config: Config) -> ProcessEventRefundBatchExecutor {
+        ProcessEventRefundBatchExecutor { config }
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), ApiError> {
+        let connection = conn.get();
+        let payload: ProcessEventRefundBatchPayload = serde_json::from_value(action.payload.clone())?;
+        let event_refund_batch = EventRefundBatch::find(payload.event_refund_batch_id, connection)?;
+        if event_refund_batch.status == EventRefundBatchStatus::Completed {
+            return Ok(());
+        }
+        let event_refund_batch = event_refund_batch.mark_in_progress(connection)?;
+        let service_locator = ServiceLocator::new(&self.config)?;
+
+        for _ in 0..EVENT_REFUND_BATCH_SIZE {
+            let batch_order = match event_refund_batch.pending_orders(1, connection)?.pop() {
+                Some(batch_order) => batch_order,
+                None => break,
+            };
+            let batch_order = batch_order.mark_in_progress(connection)?;
+            // Persist the claim before calling the payment processor so that a concurrent or later
+            // run does not refund the order a second time
+            if self.config.environment != Environment::Test {
+                conn.commit_transaction()?;
+                conn.begin_transaction()?;
+            }
+
+            let mut order = Order::find(batch_order.order_id, connection)?;
+            // Each refund runs in its own savepoint so a failure only rolls back that order
+            let result = connection.transaction::<_, ApiError, _>(|| {
+                let items = order.refundable_items_for_event(
+                    event_refund_batch.event_id,
+                    event_refund_batch.refund_fees,
+                    connection,
+                )?;
+                if items.is_empty() {
+                    return Err(
+                        ApplicationError::new("Order has no refundable tickets for this event".to_string()).into(),
+                    );
+                }
+                orders::perform_refund(
+                    &mut order,
+                    &items,
+                    event_refund_batch.created_by,
+                    Some("Event cancelled".to_string()),
+                    false,
+                    !event_refund_batch.refund_fees,
+                    false,
+                    conn,
+                    &self.config,
+                    &service_locator,
+                )
+            });
+            match result {
+                Ok((refund, refund_response)) => {
+                    batch_order.mark_refunded(refund.id, refund_response.amount_refunded, connection)?
+                }
+                Err(error) => batch_order.mark_failed(&error.to_string(), connection)?,
+            };
+
+            // Persist progress so a later run resumes with the remaining orders
+            if self.config.environment != Environment::Test {
+                conn.commit_transaction()?;
+                conn.begin_transaction()?;
+            }
+        }
+
+        if event_refund_batch.pending_orders(1, connection)?.is_empty() {
+            event_refund_batch.complete(connection)?;
+        } else {
+            event_refund_batch.queue_processing(Some(EVENT_REFUND_BATCH_DELAY_SECONDS), connection)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use crate::config::Config;
+use crate::database::Connection;
+use crate::domain_events::executor_future::ExecutorFuture;
+use crate::domain_events::routing::DomainActionExecutor;
+use crate::errors::*;
+use crate::payments::PaymentProcessorBehavior;
+use crate::utils::ServiceLocator;
+use crate::SITE_NAME;
+use db::prelude::*;
+use futures::future::TryFutureExt;
+use log::Level::{Error, Warn};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct ProcessInstallmentPaymentExecutor {
+    config: Config,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ProcessInstallmentPaymentPayload {
+    pub installment_id: Uuid,
+}
+
+impl DomainActionExecutor for ProcessInstallmentPaymentExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        let fut = self.clone().perform_job(action.clone(), conn.clone())
+            .inspect_err({
+                let action = action.clone();
+                move |e| jlog!(Error, "Process installment payment action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()})
+            });
+        ExecutorFuture::new(action, conn, Box::pin(fut))
+    }
+}
+
+impl ProcessInstallmentPaymentExecutor {
+    pub fn new(config: Config) -> ProcessInstallmentPaymentExecutor {
+        ProcessInstallmentPaymentExecutor { config }
+    }
+
+    pub async fn perform_job(self, action: DomainAction, conn: Connection) -> Result<(), ApiError> {
+        let connection = conn.get();
+        let payload: ProcessInstallmentPaymentPayload = serde_json::from_value(action.payload.clone())?;
+        let installment = Installment::find(payload.installment_id, connection)?;
+        let plan = installment.installment_plan(connection)?;
+        // Retries queued before the plan was completed or defaulted have nothing left to do
+        if installment.status != InstallmentStatus::Scheduled || plan.status != InstallmentPlanStatus::Active {
+            return Ok(());
+        }
+
+        let order = Order::find(plan.order_id, connection)?;
+        let payment_method = plan.payment_method(connection)?;
+        let organization = Event::find(plan.event_id, connection)?.organization(connection)?;
+        let client = ServiceLocator::new(&self.config)?.create_payment_processor(payment_method.name, &organization)?;
+        let behavior = match client.behavior() {
+            PaymentProcessorBehavior::AuthThenComplete(behavior) => behavior,
+            _ => {
+                installment.record_failed_attempt("Payment processor does not support repeat charges", connection)?;
+                return Ok(());
+            }
+        };
+
+        let charge = async {
+            let auth_result = behavior
+                .auth(
+                    &payment_method.provider,
+                    installment.amount_in_cents,
+                    &order.currency,
+                    SITE_NAME,
+                    order.purchase_metadata(connection)?,
+                )
+                .await?;
+            let charge_result = behavior.complete_authed_charge(&auth_result.id).await?;
+            Ok::<_, ApiError>((auth_result.id, charge_result.to_json()?))
+        }
+        .await;
+
+        match charge {
+            Ok((external_reference, charge_data)) => {
+                if let Err(e) = installment.record_payment(
+                    behavior.payment_provider(),
+                    external_reference.clone(),
+                    charge_data,
+                    connection,
+                ) {
+                    client.refund(&external_reference).await?;
+                    return Err(e.into());
+                }
+            }
+            Err(e) => {
+                jlog!(Warn, "Installment payment failed", {"installment_id": installment.id, "installment_plan_id": plan.id, "error": e.to_string()});
+                installment.record_failed_attempt(&e.to_string(), connection)?;
+            }
+        }
+
+        Ok(())
+    }
+}
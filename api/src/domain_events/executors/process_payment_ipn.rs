@@ -1,20 +1,28 @@
+use crate::communications::mailers;
 use crate::config::Config;
 use crate::database::Connection;
 use crate::domain_events::executor_future::ExecutorFuture;
 use crate::domain_events::routing::DomainActionExecutor;
 use crate::errors::ApiError;
 use crate::errors::ApplicationError;
+use crate::payments::mock::MockIpnRequest;
+use crate::payments::{PaymentIntentResult, PaymentIntentStatus, PaymentProcessorError};
+use chrono::NaiveDateTime;
 use db::prelude::*;
 use futures::future::TryFutureExt;
 use globee::GlobeeClient;
 use globee::GlobeeIpnRequest;
-use log::Level::{Debug, Error};
+use log::Level::{Debug, Error, Warn};
+use stripe::{PaymentIntent, StripeClient};
 use uuid::Uuid;
 
 #[derive(Clone)]
 pub struct ProcessPaymentIPNExecutor {
+    config: Config,
     globee_api_key: String,
     globee_base_url: String,
+    stripe_secret_key: String,
+    stripe_base_url: String,
     validate_ipn: bool,
     api_keys_encryption_key: String,
 }
@@ -33,14 +41,24 @@ impl DomainActionExecutor for ProcessPaymentIPNExecutor {
 impl ProcessPaymentIPNExecutor {
     pub fn new(config: &Config) -> ProcessPaymentIPNExecutor {
         ProcessPaymentIPNExecutor {
+            config: config.clone(),
             globee_api_key: config.globee_api_key.clone(),
             globee_base_url: config.globee_base_url.clone(),
+            stripe_secret_key: config.stripe_secret_key.clone(),
+            stripe_base_url: config.stripe_base_url.clone(),
             validate_ipn: config.validate_ipns,
             api_keys_encryption_key: config.api_keys_encryption_key.clone(),
         }
     }
 
     pub async fn perform_job(self, action: DomainAction, conn: Connection) -> Result<(), ApiError> {
+        if action.payload["object"].as_str() == Some("event") {
+            return self.perform_stripe_job(action, conn).await;
+        }
+        if action.payload["object"].as_str() == Some("mock_ipn") {
+            return self.perform_mock_job(action, conn);
+        }
+
         let mut ipn: GlobeeIpnRequest = serde_json::from_value(action.payload.clone())?;
         if ipn.custom_payment_id.is_none() {
             return Err(
@@ -166,4 +184,219 @@ impl ProcessPaymentIPNExecutor {
 
         Ok(())
     }
+
+    fn perform_mock_job(self, action: DomainAction, conn: Connection) -> Result<(), ApiError> {
+        let ipn: MockIpnRequest = serde_json::from_value(action.payload.clone())?;
+        let connection = conn.get();
+        let mut order = Order::find(ipn.order_id, connection)?;
+        order.lock_version(connection)?;
+
+        if order.is_expired() && (order.status == OrderStatus::PendingPayment || order.status == OrderStatus::Draft) {
+            if order.try_refresh_expired_cart(None, connection).is_err() {
+                jlog!(Debug, "Mock IPN: Attempted to refresh expired cart but failed", {"ipn_id": &ipn.id, "order_id": order.id})
+            }
+        }
+
+        let external_reference = format!("mock-{}", ipn.id);
+        let status = match ipn.status.to_lowercase().as_str() {
+            "pending" => PaymentStatus::PendingConfirmation,
+            "completed" => PaymentStatus::Completed,
+            "cancelled" => PaymentStatus::Cancelled,
+            _ => PaymentStatus::Unknown,
+        };
+        jlog!(Debug, "Found mock IPN", {"ipn_id": &ipn.id, "order_id": order.id, "status": status});
+
+        let payment = match Payment::find_by_order(order.id, &external_reference, connection).optional()? {
+            Some(p) => p,
+            None => {
+                let amount = match ipn.amount {
+                    Some(amount) => amount,
+                    None => order.amount_due(connection)?,
+                };
+                order.add_provider_payment(
+                    Some(external_reference),
+                    PaymentProviders::Mock,
+                    None,
+                    amount,
+                    status,
+                    None,
+                    action.payload.clone(),
+                    connection,
+                )?
+            }
+        };
+
+        if order.status == OrderStatus::Draft {
+            payment.mark_pending_ipn(None, connection)?;
+        }
+
+        if status == PaymentStatus::Completed {
+            if let Some(amount) = ipn.amount {
+                payment.update_amount(None, amount, connection)?;
+            }
+            payment.mark_complete(action.payload, None, connection)?;
+        } else {
+            payment.add_ipn(status, action.payload, None, connection)?;
+        }
+
+        Ok(())
+    }
+
+    async fn perform_stripe_job(self, action: DomainAction, conn: Connection) -> Result<(), ApiError> {
+        let event_type = action.payload["type"].as_str().unwrap_or("").to_string();
+        if event_type.starts_with("payment_intent.") {
+            return self.perform_stripe_payment_intent_job(action, conn).await;
+        }
+
+        let object = action.payload["data"]["object"].clone();
+        // Payments made through payment intents are referenced by the intent, older ones by the charge
+        let external_reference = object["payment_intent"]
+            .as_str()
+            .or_else(|| object["charge"].as_str())
+            .or_else(|| object["id"].as_str())
+            .ok_or(ApplicationError::new(
+                "Invalid Stripe event, no charge reference found".to_string(),
+            ))?;
+
+        let connection = conn.get();
+        let payment = match Payment::find_by_external_reference(external_reference, connection).optional()? {
+            Some(p) => p,
+            None => {
+                jlog!(Debug, "Stripe event: No payment found, ignoring", {"external_reference": external_reference, "type": &event_type});
+                return Ok(());
+            }
+        };
+        let mut order = Order::find(payment.order_id, connection)?;
+        order.lock_version(connection)?;
+
+        jlog!(Debug, "Stripe event: found payment", {"payment_id": payment.id, "order_id": order.id, "type": &event_type});
+
+        match event_type.as_str() {
+            "charge.failed" if payment.status != PaymentStatus::Completed => {
+                payment.add_ipn(PaymentStatus::Cancelled, object, None, connection)?
+            }
+            "charge.dispute.created" => {
+                payment.log_ipn(object.clone(), None, connection)?;
+                let dispute_reference = object["id"].as_str().unwrap_or("");
+                if Dispute::find_by_external_reference(dispute_reference, connection)?.is_some() {
+                    jlog!(Debug, "Stripe event: Dispute already recorded", {"external_reference": dispute_reference, "order_id": order.id});
+                    return Ok(());
+                }
+                let dispute = Dispute::create(
+                    order.id,
+                    payment.id,
+                    object["amount"].as_i64().unwrap_or(payment.amount),
+                    object["reason"].as_str().map(|r| r.to_string()),
+                    Some(dispute_reference.to_string()),
+                    object["evidence_details"]["due_by"]
+                        .as_i64()
+                        .map(|due_by| NaiveDateTime::from_timestamp(due_by, 0)),
+                    None,
+                )
+                .commit(false, connection)?;
+                if dispute.settlement_adjustment_id.is_none() {
+                    jlog!(Warn, "Stripe event: Chargeback will be added to the next settlement", {"dispute_id": dispute.id, "order_id": order.id});
+                }
+                for organization in order.organizations(connection)? {
+                    mailers::organizations::chargeback_received(
+                        &self.config,
+                        &organization,
+                        &order,
+                        &dispute,
+                        connection,
+                    )?;
+                }
+            }
+            "charge.dispute.closed" => {
+                payment.log_ipn(object.clone(), None, connection)?;
+                let dispute_reference = object["id"].as_str().unwrap_or("");
+                match Dispute::find_by_external_reference(dispute_reference, connection)? {
+                    Some(dispute) if dispute.status != DisputeStatus::Closed => {
+                        let outcome = if object["status"].as_str() == Some("won") {
+                            DisputeOutcome::Won
+                        } else {
+                            DisputeOutcome::Lost
+                        };
+                        dispute.resolve(outcome, None, connection)?;
+                    }
+                    _ => {
+                        jlog!(Debug, "Stripe event: No open dispute found, ignoring", {"external_reference": dispute_reference, "order_id": order.id})
+                    }
+                }
+            }
+            // Refunds made at Stripe, or completed there after an asynchronous refund, are recorded
+            // against the payment. Refunds we already recorded only have the event kept on the payment.
+            "charge.refunded" => {
+                let unrecorded_amount =
+                    object["amount_refunded"].as_i64().unwrap_or(0) - payment.refunded_amount(connection)?;
+                if unrecorded_amount > 0 {
+                    let user_id = order.on_behalf_of_user_id.unwrap_or(order.user_id);
+                    let refund = Refund::create(order.id, user_id, Some("Refunded through Stripe".to_string()), true)
+                        .commit(connection)?;
+                    payment.log_refund(user_id, &refund, unrecorded_amount, Some(object), connection)?;
+                    jlog!(Debug, "Stripe event: Recorded refund", {"payment_id": payment.id, "order_id": order.id, "amount": unrecorded_amount});
+                } else {
+                    payment.log_ipn(object, None, connection)?;
+                }
+            }
+            // Refund updates and other charge events are kept on the payment's history
+            _ => payment.log_ipn(object, None, connection)?,
+        }
+
+        Ok(())
+    }
+
+    async fn perform_stripe_payment_intent_job(self, action: DomainAction, conn: Connection) -> Result<(), ApiError> {
+        let order_id = action.main_table_id.ok_or(ApplicationError::new(
+            "Invalid Stripe event, the payment intent has no order_id".to_string(),
+        ))?;
+        let mut intent = PaymentIntent::from_json(action.payload["data"]["object"].to_string())
+            .map_err(PaymentProcessorError::from)?;
+
+        if self.validate_ipn {
+            let client = StripeClient::with_base_url(self.stripe_secret_key.clone(), self.stripe_base_url.clone());
+            intent = client
+                .retrieve_payment_intent(&intent.id)
+                .await
+                .map_err(PaymentProcessorError::from)?;
+        }
+        let intent: PaymentIntentResult = intent.into();
+
+        let connection = conn.get();
+        let mut order = Order::find(order_id, connection)?;
+        order.lock_version(connection)?;
+
+        let payment = Payment::find_by_order(order_id, &intent.id, connection)
+            .optional()?
+            .ok_or(ApplicationError::new(
+                "Invalid Stripe event, no payment found for the payment intent".to_string(),
+            ))?;
+
+        jlog!(Debug, "Stripe event: found payment intent", {"payment_intent_id": &intent.id, "order_id": order_id, "status": format!("{:?}", intent.status)});
+
+        match intent.status {
+            PaymentIntentStatus::Succeeded => {
+                if order.is_expired()
+                    && (order.status == OrderStatus::PendingPayment || order.status == OrderStatus::Draft)
+                {
+                    if order.try_refresh_expired_cart(None, connection).is_err() {
+                        jlog!(Debug, "Stripe event: Attempted to refresh expired cart but failed", {"payment_intent_id": &intent.id, "order_id": order.id})
+                    }
+                }
+                payment.mark_complete(intent.to_json()?, None, connection)?;
+            }
+            PaymentIntentStatus::Processing => {
+                payment.add_ipn(PaymentStatus::PendingConfirmation, intent.to_json()?, None, connection)?
+            }
+            PaymentIntentStatus::Failed => {
+                if payment.status != PaymentStatus::Completed {
+                    payment.add_ipn(PaymentStatus::Cancelled, intent.to_json()?, None, connection)?;
+                }
+            }
+            // Still waiting on the customer to authenticate
+            PaymentIntentStatus::RequiresAction => (),
+        }
+
+        Ok(())
+    }
 }
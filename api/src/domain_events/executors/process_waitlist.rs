@@ -0,0 +1,53 @@
+use crate::communications::mailers;
+use crate::config::Config;
+use crate::database::Connection;
+use crate::domain_events::executor_future::ExecutorFuture;
+use crate::domain_events::routing::DomainActionExecutor;
+use crate::errors::*;
+use db::prelude::*;
+use futures::future;
+use log::Level::Error;
+
+pub struct ProcessWaitlistExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for ProcessWaitlistExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::pin(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Process waitlist action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::pin(future::err(e)))
+            }
+        }
+    }
+}
+
+impl ProcessWaitlistExecutor {
+    pub fn new(config: Config) -> ProcessWaitlistExecutor {
+        ProcessWaitlistExecutor { config }
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), ApiError> {
+        let conn = conn.get();
+        let id = action
+            .main_table_id
+            .clone()
+            .ok_or(ApplicationError::new("No id supplied in the action".to_string()))?;
+
+        match action
+            .main_table
+            .clone()
+            .ok_or(ApplicationError::new("No table supplied in the action".to_string()))?
+        {
+            Tables::TicketTypes => {
+                for waitlist_entry in WaitlistEntry::process(id, conn)? {
+                    mailers::waitlist::offer_made(&self.config, &waitlist_entry, conn)?;
+                }
+            }
+            _ => return Err(ApplicationError::new("Table not supported".to_string()).into()),
+        }
+        Ok(())
+    }
+}
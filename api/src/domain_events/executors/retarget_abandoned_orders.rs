@@ -28,6 +28,7 @@ impl RetargetAbandonedOrdersExecutor {
     pub fn perform_job(&self, conn: &Connection) -> Result<(), ApiError> {
         let conn = conn.get();
         Order::retarget_abandoned_carts(conn)?;
+        Order::reverse_expired_stored_value_payments(conn)?;
 
         Order::create_next_retarget_abandoned_cart_domain_action(conn)?;
 
@@ -0,0 +1,60 @@
+use crate::communications::mailers;
+use crate::config::Config;
+use crate::database::Connection;
+use crate::domain_events::executor_future::ExecutorFuture;
+use crate::domain_events::routing::DomainActionExecutor;
+use crate::errors::*;
+use db::prelude::*;
+use futures::future;
+use log::Level::Error;
+use uuid::Uuid;
+
+pub struct SendEventRescheduledCommunicationsExecutor {
+    config: Config,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct SendEventRescheduledCommunicationsPayload {
+    pub event_reschedule_id: Uuid,
+}
+
+impl DomainActionExecutor for SendEventRescheduledCommunicationsExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::pin(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Send event rescheduled communications action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::pin(future::err(e)))
+            }
+        }
+    }
+}
+
+impl SendEventRescheduledCommunicationsExecutor {
+    pub fn new(config: Config) -> SendEventRescheduledCommunicationsExecutor {
+        SendEventRescheduledCommunicationsExecutor { config }
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), ApiError> {
+        let conn = conn.get();
+        let payload: SendEventRescheduledCommunicationsPayload = serde_json::from_value(action.payload.clone())?;
+        let event_reschedule = EventReschedule::find(payload.event_reschedule_id, conn)?;
+        let event = event_reschedule.event(conn)?;
+
+        // Ticket holders are grouped by order so each email links to the order they can respond for
+        for (user, _, order_id) in
+            Event::find_all_ticket_holders(event.id, conn, TicketHoldersCountType::WithEmailAddress)?
+        {
+            mailers::event_reschedules::event_rescheduled(
+                &self.config,
+                &user,
+                &event,
+                &event_reschedule,
+                order_id,
+                conn,
+            )?;
+        }
+
+        Ok(())
+    }
+}
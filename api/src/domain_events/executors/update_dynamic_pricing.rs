@@ -0,0 +1,49 @@
+use crate::database::Connection;
+use crate::domain_events::executor_future::ExecutorFuture;
+use crate::domain_events::routing::DomainActionExecutor;
+use crate::errors::*;
+use db::prelude::*;
+use futures::future;
+use log::Level::Error;
+
+pub struct UpdateDynamicPricingExecutor {}
+
+impl DomainActionExecutor for UpdateDynamicPricingExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::pin(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Update dynamic pricing action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::pin(future::err(e)))
+            }
+        }
+    }
+}
+
+impl UpdateDynamicPricingExecutor {
+    pub fn new() -> UpdateDynamicPricingExecutor {
+        UpdateDynamicPricingExecutor {}
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), ApiError> {
+        let conn = conn.get();
+        let id = action
+            .main_table_id
+            .clone()
+            .ok_or(ApplicationError::new("No id supplied in the action".to_string()))?;
+
+        match action
+            .main_table
+            .clone()
+            .ok_or(ApplicationError::new("No table supplied in the action".to_string()))?
+        {
+            Tables::TicketTypes => {
+                if let Some(strategy) = DynamicPricingStrategy::find_by_ticket_type_id(id, conn)? {
+                    strategy.adjust_pricing(conn)?;
+                }
+            }
+            _ => return Err(ApplicationError::new("Table not supported".to_string()).into()),
+        }
+        Ok(())
+    }
+}
@@ -48,14 +48,20 @@ impl DomainActionRouter {
             match action_type {
                 Communication => Box::new(SendCommunicationExecutor::new(conf)),
                 BroadcastPushNotification => Box::new(BroadcastPushNotificationExecutor::new(&conf)),
+                DefaultInstallmentPlan => Box::new(DefaultInstallmentPlanExecutor::new(conf)),
                 FinalizeSettlements => Box::new(FinalizeSettlementsExecutor::new()),
                 PaymentProviderIPN => Box::new(ProcessPaymentIPNExecutor::new(&conf)),
+                ProcessEventRefundBatch => Box::new(ProcessEventRefundBatchExecutor::new(conf)),
+                ProcessInstallmentPayment => Box::new(ProcessInstallmentPaymentExecutor::new(conf)),
                 RegenerateDripActions => Box::new(RegenerateDripActionsExecutor::new(conf)),
                 ReleaseHoldInventory => Box::new(ReleaseHoldInventoryExecutor::new()),
+                SendEventRescheduledCommunications => Box::new(SendEventRescheduledCommunicationsExecutor::new(conf)),
                 SendPurchaseCompletedCommunication => Box::new(SendOrderCompleteExecutor::new(conf)),
+                UpdateDynamicPricing => Box::new(UpdateDynamicPricingExecutor::new()),
                 UpdateGenres => Box::new(UpdateGenresExecutor::new()),
                 ProcessSettlementReport => Box::new(ProcessSettlementReportExecutor::new(conf)),
                 ProcessTransferDrip => Box::new(ProcessTransferDripEventExecutor::new(conf)),
+                ProcessWaitlist => Box::new(ProcessWaitlistExecutor::new(conf)),
                 RetargetAbandonedOrders => Box::new(RetargetAbandonedOrdersExecutor::new()),
                 SendAutomaticReportEmails => Box::new(SendAutomaticReportEmailsExecutor::new(conf)),
                 SubmitSitemapToSearchEngines => Box::new(SubmitSitemapToSearchEnginesExecutor::new(
@@ -73,18 +79,30 @@ impl DomainActionRouter {
         self.add_executor(BroadcastPushNotification, find_executor(BroadcastPushNotification))
             .expect("Configuration error");
 
+        self.add_executor(DefaultInstallmentPlan, find_executor(DefaultInstallmentPlan))
+            .expect("Configuration error");
+
         self.add_executor(FinalizeSettlements, find_executor(FinalizeSettlements))
             .expect("Configuration error");
 
         self.add_executor(PaymentProviderIPN, find_executor(PaymentProviderIPN))
             .expect("Configuration error");
 
+        self.add_executor(ProcessEventRefundBatch, find_executor(ProcessEventRefundBatch))
+            .expect("Configuration error");
+
+        self.add_executor(ProcessInstallmentPayment, find_executor(ProcessInstallmentPayment))
+            .expect("Configuration error");
+
         self.add_executor(ProcessSettlementReport, find_executor(ProcessSettlementReport))
             .expect("Configuration error");
 
         self.add_executor(ProcessTransferDrip, find_executor(ProcessTransferDrip))
             .expect("Configuration error");
 
+        self.add_executor(ProcessWaitlist, find_executor(ProcessWaitlist))
+            .expect("Configuration error");
+
         self.add_executor(RegenerateDripActions, find_executor(RegenerateDripActions))
             .expect("Configuration error");
 
@@ -94,12 +112,21 @@ impl DomainActionRouter {
         self.add_executor(RetargetAbandonedOrders, find_executor(RetargetAbandonedOrders))
             .expect("Configuration error");
 
+        self.add_executor(UpdateDynamicPricing, find_executor(UpdateDynamicPricing))
+            .expect("Configuration error");
+
         self.add_executor(UpdateGenres, find_executor(UpdateGenres))
             .expect("Configuration error");
 
         self.add_executor(SendAutomaticReportEmails, find_executor(SendAutomaticReportEmails))
             .expect("Configuration error");
 
+        self.add_executor(
+            SendEventRescheduledCommunications,
+            find_executor(SendEventRescheduledCommunications),
+        )
+        .expect("Configuration error");
+
         self.add_executor(
             SendPurchaseCompletedCommunication,
             find_executor(SendPurchaseCompletedCommunication),
@@ -265,6 +265,8 @@ impl WebhookPublisher {
         let mut refunded_fees_total = 0;
         let mut discount_total = 0;
         let mut refunded_discount_total = 0;
+        let mut tax_total = 0;
+        let mut refunded_tax_total = 0;
         let mut j_items = Vec::<R>::new();
         for item in order.items(conn)? {
             let item_total = item.unit_price_in_cents * item.quantity;
@@ -284,14 +286,25 @@ impl WebhookPublisher {
                     sub_total = sub_total + item_total;
                     refunded_sub_total = refunded_sub_total + refunded_total;
                 }
+                OrderItemTypes::Products => {
+                    sub_total = sub_total + item_total;
+                    refunded_sub_total = refunded_sub_total + refunded_total;
+                }
                 OrderItemTypes::Discount => {
                     discount_total = discount_total + item_total;
                     refunded_discount_total = refunded_discount_total + refunded_total;
                 }
-                OrderItemTypes::PerUnitFees | OrderItemTypes::EventFees | OrderItemTypes::CreditCardFees => {
+                OrderItemTypes::PerUnitFees
+                | OrderItemTypes::EventFees
+                | OrderItemTypes::CreditCardFees
+                | OrderItemTypes::NameChangeFees => {
                     fees_total = fees_total + item_total;
                     refunded_fees_total = refunded_fees_total + refunded_total;
                 }
+                OrderItemTypes::Tax => {
+                    tax_total = tax_total + item_total;
+                    refunded_tax_total = refunded_tax_total + refunded_total;
+                }
             }
         }
 
@@ -303,6 +316,8 @@ impl WebhookPublisher {
         data.insert("refunded_fees_total".to_string(), json!(refunded_fees_total));
         data.insert("discount_total".to_string(), json!(discount_total));
         data.insert("refunded_discount_total".to_string(), json!(refunded_discount_total));
+        data.insert("tax_total".to_string(), json!(tax_total));
+        data.insert("refunded_tax_total".to_string(), json!(refunded_tax_total));
 
         data.insert(
             "user_id".to_string(),
@@ -0,0 +1,243 @@
+use crate::database::Connection;
+use crate::errors::ApiError;
+use crate::extractors::*;
+use crate::helpers::application;
+use crate::server::GetAppState;
+use crate::utils::logging::log_request;
+use actix_service::Service;
+use actix_web::error;
+use actix_web::http::{Method, StatusCode};
+use actix_web::{dev, FromRequest, HttpMessage, HttpRequest, HttpResponse};
+use bytes::{Bytes, BytesMut};
+use db::models::*;
+use db::utils::hash::sha256;
+use futures::future::{ok, Ready};
+use futures::StreamExt;
+use log::Level;
+
+const IDEMPOTENCY_KEY_HEADER: &'static str = "Idempotency-Key";
+const IDEMPOTENT_REPLAYED_HEADER: &'static str = "Idempotent-Replayed";
+const IDEMPOTENCY_KEY_MAX_LENGTH: usize = 255;
+
+pub enum Idempotency {
+    // Key claimed, the response is stored once the handler completes. The claim's connection is
+    // kept to release the key if the request is rolled back.
+    Claimed(IdempotencyKey, Connection),
+    // Duplicate request, answered without running the handler
+    Respond(HttpResponse),
+    Skip,
+}
+
+/// Honours the `Idempotency-Key` header on mutating requests. The response committed for a key is
+/// replayed for retries while duplicates received before it completes are rejected.
+#[derive(Clone)]
+pub struct IdempotentRequest;
+
+impl IdempotentRequest {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    fn has_key(request: &HttpRequest) -> bool {
+        request.method() != Method::GET && request.headers().contains_key(IDEMPOTENCY_KEY_HEADER)
+    }
+
+    /// Claims the request's key on `claim_connection` or answers a duplicate of an earlier request
+    pub fn start(request: &HttpRequest, body: &[u8], claim_connection: Connection) -> Result<Idempotency, ApiError> {
+        if !IdempotentRequest::has_key(request) {
+            return Ok(Idempotency::Skip);
+        }
+        let key = request
+            .headers()
+            .get(IDEMPOTENCY_KEY_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("")
+            .trim()
+            .to_string();
+        if key.is_empty() || key.len() > IDEMPOTENCY_KEY_MAX_LENGTH {
+            return Ok(Idempotency::Respond(HttpResponse::BadRequest().json(json!({
+                "error": format!("{} header must be between 1 and {} characters", IDEMPOTENCY_KEY_HEADER, IDEMPOTENCY_KEY_MAX_LENGTH)
+            }))));
+        }
+        // Anonymous requests are rejected by the handlers so are not tracked
+        let user = match OptionalUser::from_request(request, &mut dev::Payload::None).into_inner()? {
+            OptionalUser(Some(user)) => user,
+            OptionalUser(None) => return Ok(Idempotency::Skip),
+        };
+
+        let method = request.method().to_string();
+        let path = request.path().to_string();
+        let request_hash = sha256::digest(body);
+        match IdempotencyKey::claim(user.id(), &key, &method, &path, &request_hash, claim_connection.get())? {
+            IdempotencyKeyClaim::Claimed(idempotency_key) => {
+                Ok(Idempotency::Claimed(idempotency_key, claim_connection))
+            }
+            IdempotencyKeyClaim::Existing(existing) => {
+                if !existing.matches_request(&method, &path, &request_hash) {
+                    return Ok(Idempotency::Respond(HttpResponse::UnprocessableEntity().json(json!({
+                        "error": format!("{} has already been used for a different request", IDEMPOTENCY_KEY_HEADER)
+                    }))));
+                }
+                match (existing.response_status, existing.response_body) {
+                    (Some(status), Some(body)) => Ok(Idempotency::Respond(
+                        HttpResponse::build(StatusCode::from_u16(status as u16).unwrap_or(StatusCode::OK))
+                            .content_type("application/json")
+                            .header(IDEMPOTENT_REPLAYED_HEADER, "true")
+                            .body(body),
+                    )),
+                    _ => Ok(Idempotency::Respond(HttpResponse::Conflict().json(json!({
+                        "error": format!("A request with this {} is already in progress", IDEMPOTENCY_KEY_HEADER)
+                    })))),
+                }
+            }
+        }
+    }
+
+    // Responses are stored in the request's transaction so whatever outcome is committed, successful
+    // or not, is what gets replayed. Error responses are rolled back, so the key is released on the
+    // claim's connection for the client to retry. If the transaction never commits the key's lock
+    // lapses and a retry takes it over.
+    pub fn finish(idempotency_key: IdempotencyKey, claim_connection: Connection, response: &dev::ServiceResponse) {
+        let result = if response.response().error().is_some() {
+            idempotency_key.release(claim_connection.get())
+        } else {
+            // Requests that never used the database have no transaction to store the response in
+            let connection = response
+                .request()
+                .extensions()
+                .get::<Connection>()
+                .cloned()
+                .unwrap_or_else(|| claim_connection.clone());
+            match application::unwrap_body_to_string(response.response()) {
+                Ok(body) => idempotency_key
+                    .complete(response.status().as_u16() as i32, body, connection.get())
+                    .map(|_| ()),
+                Err(_) => idempotency_key.release(claim_connection.get()),
+            }
+        };
+        if let Err(error) = result {
+            error!("IdempotentRequest Middleware finish: {:?}", error);
+        }
+    }
+
+    // Reads the body so that retries can be matched against it, putting it back for the handler
+    async fn take_body(request: &mut dev::ServiceRequest) -> Result<Bytes, error::Error> {
+        let mut body = BytesMut::new();
+        let mut payload = request.take_payload();
+        while let Some(chunk) = payload.next().await {
+            body.extend_from_slice(&chunk?);
+        }
+        let body = body.freeze();
+        let (_, mut restored_payload) = actix_http::h1::Payload::create(true);
+        restored_payload.unread_data(body.clone());
+        request.set_payload(restored_payload.into());
+        Ok(body)
+    }
+}
+
+impl<S> dev::Transform<S> for IdempotentRequest
+where
+    S: Service<Request = dev::ServiceRequest, Response = dev::ServiceResponse, Error = error::Error> + 'static,
+{
+    type Request = S::Request;
+    type Response = S::Response;
+    type Error = S::Error;
+    type InitError = ();
+    type Transform = IdempotentRequestService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(IdempotentRequestService::new(service))
+    }
+}
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
+pub struct IdempotentRequestService<S> {
+    service: Rc<RefCell<S>>,
+}
+
+impl<S> IdempotentRequestService<S> {
+    fn new(service: S) -> Self {
+        Self {
+            service: Rc::new(RefCell::new(service)),
+        }
+    }
+}
+
+impl<S> Service for IdempotentRequestService<S>
+where
+    S: Service<Request = dev::ServiceRequest, Response = dev::ServiceResponse, Error = error::Error> + 'static,
+{
+    type Request = S::Request;
+    type Response = dev::ServiceResponse;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.borrow_mut().poll_ready(cx).map_err(error::Error::from)
+    }
+
+    fn call(&mut self, mut request: Self::Request) -> Self::Future {
+        let service = self.service.clone();
+        if !IdempotentRequest::has_key(request.request()) {
+            let fut = service.borrow_mut().call(request);
+            return Box::pin(fut);
+        }
+
+        Box::pin(async move {
+            let body = IdempotentRequest::take_body(&mut request).await?;
+            let (http_req, payload) = request.into_parts();
+            // Claims are made outside of the request's transaction so concurrent duplicates see them
+            let idempotency = http_req
+                .state()
+                .database
+                .get_connection()
+                .map_err(ApiError::from)
+                .and_then(|claim_connection| IdempotentRequest::start(&http_req, &body, claim_connection));
+
+            match idempotency {
+                Ok(Idempotency::Claimed(idempotency_key, claim_connection)) => {
+                    let request = dev::ServiceRequest::from_parts(http_req, payload).unwrap_or_else(|_| {
+                        unreachable!("Failed to recompose request in IdempotentRequestService::call")
+                    });
+                    let fut = service.borrow_mut().call(request);
+                    match fut.await {
+                        Ok(response) => {
+                            IdempotentRequest::finish(idempotency_key, claim_connection, &response);
+                            Ok(response)
+                        }
+                        Err(error) => {
+                            if let Err(release_error) = idempotency_key.release(claim_connection.get()) {
+                                error!("IdempotentRequest Middleware release: {:?}", release_error);
+                            }
+                            Err(error)
+                        }
+                    }
+                }
+                Ok(Idempotency::Respond(response)) => {
+                    log_request(
+                        Level::Debug,
+                        "api::idempotency",
+                        "Answered from idempotency key",
+                        &http_req,
+                        json!({"status": response.status().as_u16()}),
+                    );
+                    Ok(dev::ServiceResponse::new(http_req, response))
+                }
+                Ok(Idempotency::Skip) => {
+                    let request = dev::ServiceRequest::from_parts(http_req, payload).unwrap_or_else(|_| {
+                        unreachable!("Failed to recompose request in IdempotentRequestService::call")
+                    });
+                    let fut = service.borrow_mut().call(request);
+                    fut.await
+                }
+                Err(error) => Ok(dev::ServiceResponse::from_err(error, http_req)),
+            }
+        })
+    }
+}
@@ -2,10 +2,12 @@ pub use self::api_logger::*;
 pub use self::app_version_header::*;
 pub use self::cache_resource::*;
 pub use self::database_transaction::*;
+pub use self::idempotency::*;
 pub use self::metatags::*;
 
 mod api_logger;
 mod app_version_header;
 mod cache_resource;
 mod database_transaction;
+mod idempotency;
 mod metatags;
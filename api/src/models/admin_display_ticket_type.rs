@@ -28,6 +28,12 @@ pub struct AdminDisplayTicketType {
     pub app_sales_enabled: bool,
     pub web_sales_enabled: bool,
     pub box_office_sales_enabled: bool,
+    pub venue_section_id: Option<Uuid>,
+    pub inventory_pool_id: Option<Uuid>,
+    pub max_entries: i32,
+    pub required_attendee_details: Vec<AttendeeDetails>,
+    pub attendee_name_change_fee_in_cents: i64,
+    pub attendee_details_lock_hours: Option<i32>,
 }
 
 impl AdminDisplayTicketType {
@@ -80,6 +86,12 @@ impl AdminDisplayTicketType {
             app_sales_enabled: ticket_type.app_sales_enabled,
             web_sales_enabled: ticket_type.web_sales_enabled,
             box_office_sales_enabled: ticket_type.box_office_sales_enabled,
+            venue_section_id: ticket_type.venue_section_id,
+            inventory_pool_id: ticket_type.inventory_pool_id,
+            max_entries: ticket_type.max_entries,
+            required_attendee_details: ticket_type.required_attendee_details.clone(),
+            attendee_name_change_fee_in_cents: ticket_type.attendee_name_change_fee_in_cents,
+            attendee_details_lock_hours: ticket_type.attendee_details_lock_hours,
         };
         Ok(result)
     }
@@ -56,3 +56,33 @@ pub struct CompPathParameters {
     pub hold_id: Uuid,
     pub comp_id: Uuid,
 }
+
+#[derive(Deserialize)]
+pub struct PassEventPathParameters {
+    pub id: Uuid, // Pass Id
+    pub event_id: Uuid,
+}
+
+#[derive(Deserialize)]
+pub struct EventTimeSlotPathParameters {
+    pub id: Uuid, // Event Id
+    pub time_slot_id: Uuid,
+}
+
+#[derive(Deserialize)]
+pub struct ProductVariantPathParameters {
+    pub id: Uuid, // Product Id
+    pub variant_id: Uuid,
+}
+
+#[derive(Deserialize)]
+pub struct OrderItemPathParameters {
+    pub id: Uuid, // Order Id
+    pub order_item_id: Uuid,
+}
+
+#[derive(Deserialize)]
+pub struct AccessZoneUserPathParameters {
+    pub id: Uuid, // Access Zone Id
+    pub user_id: Uuid,
+}
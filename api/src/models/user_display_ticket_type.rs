@@ -19,6 +19,9 @@ pub struct UserDisplayTicketType {
     pub redemption_code: Option<String>,
     pub event_id: Uuid,
     pub rank: i32,
+    pub venue_section_id: Option<Uuid>,
+    pub required_attendee_details: Vec<AttendeeDetails>,
+    pub attendee_name_change_fee_in_cents: i64,
 }
 
 impl UserDisplayTicketType {
@@ -60,6 +63,9 @@ impl UserDisplayTicketType {
             increment: ticket_type.increment,
             limit_per_person: ticket_type.limit_per_person as u32,
             rank: ticket_type.rank,
+            venue_section_id: ticket_type.venue_section_id,
+            required_attendee_details: ticket_type.required_attendee_details.clone(),
+            attendee_name_change_fee_in_cents: ticket_type.attendee_name_change_fee_in_cents,
         };
 
         if let Some(ref redemption_code) = redemption_code {
@@ -0,0 +1,198 @@
+use crate::payments::*;
+use db::models::{Environment, PaymentProviders};
+use db::utils::dates;
+use uuid::Uuid;
+
+/// Tokens (and saved payment methods created from them) containing this are declined on auth
+pub const MOCK_DECLINE: &str = "decline";
+/// Tokens containing this are authorized but fail when the charge is completed
+pub const MOCK_COMPLETE_FAIL: &str = "complete_fail";
+/// Charges made with tokens containing this fail to be refunded, fully or partially
+pub const MOCK_REFUND_FAIL: &str = "refund_fail";
+
+/// Payment provider that never leaves the process, for use in development and tests.
+///
+/// Outcomes are scripted through the token, or the customer's email for payment pages, so that
+/// the same input always produces the same result. IPNs for payment pages are not sent
+/// automatically, they are posted to `/ipns/mock` when (and as late as) the test requires.
+pub struct MockPaymentProcessor {
+    use_payment_page: bool,
+}
+
+impl MockPaymentProcessor {
+    pub fn new(use_payment_page: bool) -> MockPaymentProcessor {
+        MockPaymentProcessor { use_payment_page }
+    }
+
+    fn refund_result(auth_token: &str, amount: Option<i64>) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        if auth_token.contains(MOCK_REFUND_FAIL) {
+            return Err(mock_error("Refund failed", None));
+        }
+        let id = format!("mock_re_{}", Uuid::new_v4());
+        Ok(ChargeAuthResult {
+            raw: json!({"id": &id, "object": "refund", "charge": auth_token, "amount": amount}).to_string(),
+            id,
+        })
+    }
+}
+
+/// Whether the mock provider can be used, it is never available in shared environments
+pub fn is_enabled(environment: Environment) -> bool {
+    environment == Environment::Development || environment == Environment::Test
+}
+
+fn mock_error(description: &str, validation_response: Option<&str>) -> PaymentProcessorError {
+    PaymentProcessorError {
+        description: description.to_string(),
+        cause: None,
+        validation_response: validation_response.map(|v| v.to_string()),
+    }
+}
+
+#[async_trait::async_trait]
+impl PaymentProcessor for MockPaymentProcessor {
+    fn behavior(&self) -> PaymentProcessorBehavior {
+        if self.use_payment_page {
+            return PaymentProcessorBehavior::RedirectToPaymentPage(Box::new(MockPaymentBehavior {}));
+        }
+        PaymentProcessorBehavior::AuthThenComplete(Box::new(MockPaymentBehavior {}))
+    }
+
+    async fn refund(&self, auth_token: &str) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        MockPaymentProcessor::refund_result(auth_token, None)
+    }
+
+    async fn partial_refund(&self, auth_token: &str, amount: i64) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        MockPaymentProcessor::refund_result(auth_token, Some(amount))
+    }
+
+    fn partial_refund_blocking(
+        &self,
+        auth_token: &str,
+        amount: i64,
+    ) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        MockPaymentProcessor::refund_result(auth_token, Some(amount))
+    }
+
+    async fn update_metadata(
+        &self,
+        charge_id: &str,
+        metadata: Vec<(String, String)>,
+    ) -> Result<UpdateMetadataResult, PaymentProcessorError> {
+        Ok(UpdateMetadataResult {
+            id: charge_id.to_string(),
+            raw: json!({"id": charge_id, "object": "charge", "metadata": metadata}).to_string(),
+        })
+    }
+}
+
+pub struct MockPaymentBehavior {}
+
+#[async_trait::async_trait]
+impl AuthThenCompletePaymentBehavior for MockPaymentBehavior {
+    fn payment_provider(&self) -> PaymentProviders {
+        PaymentProviders::Mock
+    }
+
+    async fn create_token_for_repeat_charges(
+        &self,
+        token: &str,
+        description: &str,
+    ) -> Result<RepeatChargeToken, PaymentProcessorError> {
+        // The card token is kept so that its scripted outcome applies to later charges too
+        let repeat_token = format!("mock_cus_{}", token);
+        Ok(RepeatChargeToken {
+            raw: json!({"id": &repeat_token, "object": "customer", "description": description}).to_string(),
+            token: repeat_token,
+        })
+    }
+
+    async fn update_repeat_token(
+        &self,
+        _repeat_token: &str,
+        token: &str,
+        description: &str,
+    ) -> Result<RepeatChargeToken, PaymentProcessorError> {
+        self.create_token_for_repeat_charges(token, description).await
+    }
+
+    async fn auth(
+        &self,
+        token: &str,
+        amount: i64,
+        currency: &str,
+        description: &str,
+        metadata: Vec<(String, String)>,
+    ) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        if token.contains(MOCK_DECLINE) {
+            return Err(mock_error("Your card was declined", Some("Card has been declined")));
+        }
+        let id = format!("mock_ch_{}_{}", token, Uuid::new_v4());
+        Ok(ChargeAuthResult {
+            raw: json!({
+                "id": &id,
+                "object": "charge",
+                "amount": amount,
+                "currency": currency,
+                "description": description,
+                "captured": false,
+                "metadata": metadata
+            })
+            .to_string(),
+            id,
+        })
+    }
+
+    async fn complete_authed_charge(&self, auth_token: &str) -> Result<ChargeResult, PaymentProcessorError> {
+        if auth_token.contains(MOCK_COMPLETE_FAIL) {
+            return Err(mock_error("Charge could not be captured", None));
+        }
+        Ok(ChargeResult {
+            id: auth_token.to_string(),
+            raw: json!({"id": auth_token, "object": "charge", "captured": true}).to_string(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl RedirectToPaymentPageBehavior for MockPaymentBehavior {
+    fn payment_provider(&self) -> PaymentProviders {
+        PaymentProviders::Mock
+    }
+
+    async fn create_payment_request(
+        &self,
+        _total: f64,
+        email: String,
+        _order_id: Uuid,
+        _ipn_url: Option<String>,
+        success_url: Option<String>,
+        cancel_url: Option<String>,
+    ) -> Result<RedirectInfo, PaymentProcessorError> {
+        // There is no payment page, the customer is sent straight back as if they had paid or cancelled
+        let redirect_url = if email.contains(MOCK_DECLINE) {
+            cancel_url
+        } else {
+            success_url
+        };
+        Ok(RedirectInfo {
+            id: format!("mock_pr_{}", Uuid::new_v4()),
+            redirect_url: redirect_url.unwrap_or_default(),
+            expires_at: dates::now().add_hours(1).finish(),
+        })
+    }
+}
+
+/// Notification posted to `/ipns/mock` to script the outcome of a payment page payment
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MockIpnRequest {
+    /// The payment request id returned when the payment page was created
+    pub id: String,
+    pub order_id: Uuid,
+    /// One of `pending`, `completed` or `cancelled`
+    pub status: String,
+    /// Amount received in cents, defaults to the amount requested
+    pub amount: Option<i64>,
+    /// Seconds to wait before processing, to simulate IPNs that arrive after the customer returns
+    pub delay_seconds: Option<i64>,
+}
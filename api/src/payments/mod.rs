@@ -1,5 +1,6 @@
 pub use self::charge_auth_result::*;
 pub use self::charge_result::*;
+pub use self::payment_intent_result::*;
 pub use self::payment_processor::*;
 pub use self::payment_processor_error::*;
 pub use self::repeat_charge_token::*;
@@ -8,6 +9,8 @@ pub use self::update_metadata_result::*;
 mod charge_auth_result;
 mod charge_result;
 pub mod globee;
+pub mod mock;
+mod payment_intent_result;
 pub mod payment_processor;
 mod payment_processor_error;
 mod repeat_charge_token;
@@ -0,0 +1,21 @@
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PaymentIntentStatus {
+    RequiresAction,
+    Processing,
+    Succeeded,
+    Failed,
+}
+
+pub struct PaymentIntentResult {
+    pub id: String,
+    pub status: PaymentIntentStatus,
+    pub amount: i64,
+    pub redirect_url: Option<String>,
+    pub raw: String,
+}
+
+impl PaymentIntentResult {
+    pub fn to_json(&self) -> Result<serde_json::Value, serde_json::Error> {
+        serde_json::from_str(&self.raw)
+    }
+}
@@ -6,6 +6,7 @@ use uuid::Uuid;
 pub enum PaymentProcessorBehavior {
     AuthThenComplete(Box<dyn AuthThenCompletePaymentBehavior>),
     RedirectToPaymentPage(Box<dyn RedirectToPaymentPageBehavior>),
+    PaymentIntent(Box<dyn PaymentIntentBehavior>),
 }
 
 #[async_trait::async_trait]
@@ -52,6 +53,25 @@ pub trait RedirectToPaymentPageBehavior {
     ) -> Result<RedirectInfo, PaymentProcessorError>;
 }
 
+/// Payments that may need the customer to complete an extra step (e.g. 3D Secure)
+/// before the charge succeeds. The result is confirmed later via callback or webhook.
+#[async_trait::async_trait]
+pub trait PaymentIntentBehavior {
+    fn payment_provider(&self) -> PaymentProviders;
+
+    async fn create_payment_intent(
+        &self,
+        token: &str,
+        amount: i64,
+        currency: &str,
+        description: &str,
+        return_url: &str,
+        metadata: Vec<(String, String)>,
+    ) -> Result<PaymentIntentResult, PaymentProcessorError>;
+
+    async fn retrieve_payment_intent(&self, id: &str) -> Result<PaymentIntentResult, PaymentProcessorError>;
+}
+
 #[async_trait::async_trait]
 pub trait PaymentProcessor {
     fn behavior(&self) -> PaymentProcessorBehavior;
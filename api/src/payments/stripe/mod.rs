@@ -1,5 +1,5 @@
 use crate::payments::*;
-use ::stripe::{StripeClient, StripeError};
+use ::stripe::{PaymentIntent, PaymentIntentStatus as StripePaymentIntentStatus, StripeClient, StripeError};
 use db::models::PaymentProviders;
 
 impl From<StripeError> for PaymentProcessorError {
@@ -34,12 +34,14 @@ impl From<StripeError> for PaymentProcessorError {
 
 pub struct StripePaymentProcessor {
     client: StripeClient,
+    use_payment_intents: bool,
 }
 
 impl StripePaymentProcessor {
-    pub fn new(stripe_secret_key: String) -> StripePaymentProcessor {
+    pub fn new(stripe_secret_key: String, base_url: String, use_payment_intents: bool) -> StripePaymentProcessor {
         StripePaymentProcessor {
-            client: StripeClient::new(stripe_secret_key),
+            client: StripeClient::with_base_url(stripe_secret_key, base_url),
+            use_payment_intents,
         }
     }
 }
@@ -48,9 +50,36 @@ pub struct StripePaymentBehavior {
     client: StripeClient,
 }
 
+pub struct StripePaymentIntentBehavior {
+    client: StripeClient,
+}
+
+impl From<PaymentIntent> for PaymentIntentResult {
+    fn from(p: PaymentIntent) -> PaymentIntentResult {
+        let status = match p.status {
+            StripePaymentIntentStatus::Succeeded => PaymentIntentStatus::Succeeded,
+            StripePaymentIntentStatus::Processing => PaymentIntentStatus::Processing,
+            StripePaymentIntentStatus::RequiresAction => PaymentIntentStatus::RequiresAction,
+            _ => PaymentIntentStatus::Failed,
+        };
+        PaymentIntentResult {
+            id: p.id,
+            status,
+            amount: p.amount,
+            redirect_url: p.redirect_url,
+            raw: p.raw_data,
+        }
+    }
+}
+
 #[async_trait::async_trait]
 impl PaymentProcessor for StripePaymentProcessor {
     fn behavior(&self) -> PaymentProcessorBehavior {
+        if self.use_payment_intents {
+            return PaymentProcessorBehavior::PaymentIntent(Box::new(StripePaymentIntentBehavior {
+                client: self.client.clone(),
+            }));
+        }
         PaymentProcessorBehavior::AuthThenComplete(Box::new(StripePaymentBehavior {
             client: self.client.clone(),
         }))
@@ -165,3 +194,30 @@ impl<'a> AuthThenCompletePaymentBehavior for StripePaymentBehavior {
         })?)
     }
 }
+
+#[async_trait::async_trait]
+impl PaymentIntentBehavior for StripePaymentIntentBehavior {
+    fn payment_provider(&self) -> PaymentProviders {
+        PaymentProviders::Stripe
+    }
+
+    async fn create_payment_intent(
+        &self,
+        token: &str,
+        amount: i64,
+        currency: &str,
+        description: &str,
+        return_url: &str,
+        metadata: Vec<(String, String)>,
+    ) -> Result<PaymentIntentResult, PaymentProcessorError> {
+        Ok(self
+            .client
+            .create_payment_intent(token, amount, currency, description, return_url, metadata)
+            .await
+            .map(|r| r.into())?)
+    }
+
+    async fn retrieve_payment_intent(&self, id: &str) -> Result<PaymentIntentResult, PaymentProcessorError> {
+        Ok(self.client.retrieve_payment_intent(id).await.map(|r| r.into())?)
+    }
+}
@@ -1,5 +1,5 @@
 use crate::controllers::*;
-use crate::middleware::{CacheResource, CacheUsersBy, OrganizationLoad};
+use crate::middleware::{CacheResource, CacheUsersBy, IdempotentRequest, OrganizationLoad};
 use actix_web::web;
 use db::models::Scopes;
 
@@ -7,6 +7,11 @@ pub fn routes(app: &mut web::ServiceConfig) {
     // Please try to keep in alphabetical order
 
     app.service(
+        web::resource("/access_zones/{id}/users/{user_id}").route(web::delete().to(access_zones::unassign_user)),
+    )
+    .service(web::resource("/access_zones/{id}/users").route(web::post().to(access_zones::assign_user)))
+    .service(web::resource("/access_zones/{id}").route(web::patch().to(access_zones::update)))
+    .service(
         web::resource("/admin/stuck_domain_actions").route(web::get().to(admin::admin::admin_stuck_domain_actions)),
     )
     .service(web::resource("/admin/ticket_count").route(web::get().to(admin::admin::admin_ticket_count)))
@@ -65,7 +70,12 @@ pub fn routes(app: &mut web::ServiceConfig) {
     )
     .service(web::resource("/cart/{id}/duplicate").route(web::post().to(cart::duplicate)))
     .service(web::resource("/cart/clear_invalid_items").route(web::delete().to(cart::clear_invalid_items)))
-    .service(web::resource("/cart/checkout").route(web::post().to(cart::checkout)))
+    .service(
+        web::resource("/cart/checkout")
+            .wrap(IdempotentRequest::new())
+            .route(web::post().to(cart::checkout)),
+    )
+    .service(web::resource("/cart/passes").route(web::post().to(cart::update_pass)))
     .service(web::resource("/codes/{id}/link").route(web::get().to(codes::link)))
     .service(
         web::resource("/codes/{id}")
@@ -79,7 +89,12 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::patch().to(comps::update))
             .route(web::delete().to(comps::destroy)),
     )
+    .service(web::resource("/disputes/{id}/resolve").route(web::post().to(disputes::resolve)))
+    .service(web::resource("/disputes/{id}").route(web::patch().to(disputes::update)))
+    .service(web::resource("/event_refund_batches/{id}/retry").route(web::post().to(event_refund_batches::retry)))
+    .service(web::resource("/event_refund_batches/{id}").route(web::get().to(event_refund_batches::show)))
     .service(web::resource("/event_report_subscribers/{id}").route(web::delete().to(event_report_subscribers::destroy)))
+    .service(web::resource("/event_reschedules/{id}/responses").route(web::post().to(event_reschedules::respond)))
     .service(
         web::resource("/events")
         // In future it may be better to cache this for every user to save the database hit
@@ -97,6 +112,11 @@ pub fn routes(app: &mut web::ServiceConfig) {
         .route(web::delete().to(events::cancel)),
     )
     .service(web::resource("/events/{id}/delete").route(web::delete().to(events::delete)))
+    .service(
+        web::resource("/events/{id}/access_zones")
+            .route(web::get().to(access_zones::index))
+            .route(web::post().to(access_zones::create)),
+    )
     .service(
         web::resource("/events/{id}/artists")
             .route(web::post().to(events::add_artist))
@@ -116,12 +136,25 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::post().to(holds::create))
             .route(web::get().to(events::holds)),
     )
+    .service(
+        web::resource("/events/{id}/installment_policy")
+            .route(web::get().to(installment_plans::show_policy))
+            .route(web::put().to(installment_plans::update_policy)),
+    )
     .service(
         web::resource("/events/{id}/interest")
             .route(web::get().to(events::list_interested_users))
             .route(web::post().to(events::add_interest))
             .route(web::delete().to(events::remove_interest)),
     )
+    .service(
+        web::resource("/events/{id}/inventory_pools")
+            .route(web::get().to(inventory_pools::index))
+            .route(web::post().to(inventory_pools::create)),
+    )
+    .service(web::resource("/events/{id}/offline_manifest").route(web::get().to(events::offline_manifest)))
+    .service(web::resource("/events/{id}/offline_redemptions").route(web::post().to(events::sync_offline_redemptions)))
+    .service(web::resource("/events/{id}/products").route(web::get().to(products::event_index)))
     .service(web::resource("/events/{id}/publish").route(web::post().to(events::publish)))
     .service(
         web::resource("/events/{id}/broadcasts")
@@ -133,11 +166,27 @@ pub fn routes(app: &mut web::ServiceConfig) {
     .service(web::resource("/events/{id}/rarities").route(web::post().to(rarities::create)))
     .service(web::resource("/events/{id}/redeem/{ticket_instance_id}").route(web::post().to(events::redeem_ticket)))
     .service(web::resource("/events/{id}/redeem").route(web::post().to(events::redeem_ticket)))
+    .service(
+        web::resource("/events/{id}/refund_batches")
+            .route(web::get().to(event_refund_batches::index))
+            .route(web::post().to(event_refund_batches::create)),
+    )
+    .service(
+        web::resource("/events/{id}/refund_policy")
+            .route(web::get().to(refund_requests::show_policy))
+            .route(web::put().to(refund_requests::update_policy)),
+    )
     .service(
         web::resource("/events/{id}/report_subscribers")
             .route(web::get().to(event_report_subscribers::index))
             .route(web::post().to(event_report_subscribers::create)),
     )
+    .service(
+        web::resource("/events/{id}/reschedules")
+            .route(web::get().to(event_reschedules::index))
+            .route(web::post().to(event_reschedules::create)),
+    )
+    .service(web::resource("/events/{id}/seats").route(web::get().to(venue_sections::event_seats)))
     .service(web::resource("/events/{id}/tickets").route(web::get().to(tickets::index)))
     .service(
         web::resource("/events/{id}/ticket_types")
@@ -150,6 +199,22 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::patch().to(ticket_types::update))
             .route(web::delete().to(ticket_types::cancel)),
     )
+    .service(
+        web::resource("/events/{event_id}/ticket_types/{ticket_type_id}/dynamic_pricing")
+            .route(web::get().to(dynamic_pricing_strategies::show))
+            .route(web::put().to(dynamic_pricing_strategies::update)),
+    )
+    .service(
+        web::resource("/events/{event_id}/ticket_types/{ticket_type_id}/waitlist")
+            .route(web::post().to(waitlist_entries::create))
+            .route(web::delete().to(waitlist_entries::destroy)),
+    )
+    .service(
+        web::resource("/events/{id}/time_slots")
+            .route(web::get().to(event_time_slots::index))
+            .route(web::post().to(event_time_slots::create)),
+    )
+    .service(web::resource("/events/{id}/time_slots/{time_slot_id}").route(web::delete().to(event_time_slots::destroy)))
     .service(web::resource("/events/{id}/unpublish").route(web::post().to(events::unpublish)))
     .service(web::resource("/events/{id}/users").route(web::get().to(events::users)))
     .service(web::resource("/events/{id}/users/invites").route(web::post().to(organization_invites::create_for_event)))
@@ -168,9 +233,13 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .wrap(CacheResource::new(CacheUsersBy::None))
             .route(web::get().to(genres::index)),
     )
+    .service(web::resource("/gift_cards/{id}").route(web::get().to(gift_cards::show)))
+    .service(web::resource("/inventory_pools/{id}").route(web::patch().to(inventory_pools::update)))
     .service(web::resource("/invitations/{id}").route(web::get().to(organization_invites::view)))
     .service(web::resource("/invitations").route(web::post().to(organization_invites::accept_request)))
     .service(web::resource("/ipns/globee").route(web::post().to(ipns::globee)))
+    .service(web::resource("/ipns/mock").route(web::post().to(ipns::mock)))
+    .service(web::resource("/ipns/stripe").route(web::post().to(ipns::stripe)))
     .service(
         web::resource("/holds/{id}/comps")
             .route(web::get().to(comps::index))
@@ -196,7 +265,25 @@ pub fn routes(app: &mut web::ServiceConfig) {
     .service(web::resource("/orders").route(web::get().to(orders::index)))
     .service(web::resource("/orders/{id}/activity").route(web::get().to(orders::activity)))
     .service(web::resource("/orders/{id}/details").route(web::get().to(orders::details)))
-    .service(web::resource("/orders/{id}/refund").route(web::patch().to(orders::refund)))
+    .service(
+        web::resource("/orders/{id}/disputes")
+            .route(web::get().to(disputes::index))
+            .route(web::post().to(disputes::create)),
+    )
+    .service(
+        web::resource("/orders/{id}/products/{order_item_id}/redeem").route(web::post().to(products::redeem_voucher)),
+    )
+    .service(web::resource("/orders/{id}/installment_plan").route(web::get().to(installment_plans::show_for_order)))
+    .service(
+        web::resource("/orders/{id}/refund")
+            .wrap(IdempotentRequest::new())
+            .route(web::patch().to(orders::refund)),
+    )
+    .service(
+        web::resource("/orders/{id}/refund_requests")
+            .route(web::get().to(refund_requests::index_for_order))
+            .route(web::post().to(refund_requests::create)),
+    )
     .service(web::resource("/orders/{id}/resend_confirmation").route(web::post().to(orders::resend_confirmation)))
     .service(
         web::resource("/orders/{id}/send_box_office_instructions")
@@ -257,6 +344,11 @@ pub fn routes(app: &mut web::ServiceConfig) {
             )))
             .route(web::get().to(organizations::search_fans)),
     )
+    .service(
+        web::resource("/organizations/{id}/gift_cards")
+            .route(web::get().to(gift_cards::index))
+            .route(web::post().to(gift_cards::create)),
+    )
     .service(
         web::resource("/organizations/{id}/invites/{invite_id}").route(web::delete().to(organization_invites::destroy)),
     )
@@ -265,11 +357,26 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::get().to(organization_venues::organizations_index))
             .route(web::post().to(organization_venues::create)),
     )
+    .service(
+        web::resource("/organizations/{id}/passes")
+            .route(web::get().to(passes::index))
+            .route(web::post().to(passes::create)),
+    )
+    .service(
+        web::resource("/organizations/{id}/products")
+            .route(web::get().to(products::index))
+            .route(web::post().to(products::create)),
+    )
+    .service(
+        web::resource("/organizations/{id}/refund_requests")
+            .route(web::get().to(refund_requests::index_for_organization)),
+    )
     .service(
         web::resource("/organizations/{id}/settlements")
             .route(web::get().to(settlements::index))
             .route(web::post().to(settlements::create)),
     )
+    .service(web::resource("/organizations/{id}/store_credit").route(web::get().to(store_credits::show)))
     .service(
         web::resource("/organizations/{id}/invites")
             .route(web::get().to(organization_invites::index))
@@ -281,6 +388,9 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::put().to(organizations::add_or_replace_user))
             .route(web::get().to(organizations::list_organization_members)),
     )
+    .service(
+        web::resource("/organizations/{id}/users/{user_id}/store_credit").route(web::post().to(store_credits::create)),
+    )
     .service(web::resource("/organizations/{id}/users/{user_id}").route(web::delete().to(organizations::remove_user)))
     .service(web::resource("/organizations/{id}/venues").route(web::get().to(venues::show_from_organizations)))
     .service(
@@ -293,14 +403,33 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::get().to(organizations::index))
             .route(web::post().to(organizations::create)),
     )
+    .service(web::resource("/passes/{id}/cancel").route(web::post().to(passes::cancel)))
+    .service(web::resource("/passes/{id}/events").route(web::post().to(passes::add_event)))
+    .service(web::resource("/passes/{id}/events/{event_id}").route(web::delete().to(passes::remove_event)))
+    .service(web::resource("/passes/{id}/publish").route(web::post().to(passes::publish)))
+    .service(
+        web::resource("/passes/{id}")
+            .route(web::get().to(passes::show))
+            .route(web::patch().to(passes::update)),
+    )
     .service(
         web::resource("/password_reset")
             .route(web::post().to(password_resets::create))
             .route(web::put().to(password_resets::update)),
     )
     .service(web::resource("/payments/callback/{nonce}/{id}").route(web::get().to(payments::callback)))
+    .service(web::resource("/payments/intents/callback/{id}").route(web::get().to(payments::payment_intent_callback)))
     .service(web::resource("/payment_methods").route(web::get().to(payment_methods::index)))
+    .service(web::resource("/products/{id}/variants").route(web::post().to(products::create_variant)))
+    .service(web::resource("/products/{id}/variants/{variant_id}").route(web::patch().to(products::update_variant)))
+    .service(
+        web::resource("/products/{id}")
+            .route(web::get().to(products::show))
+            .route(web::patch().to(products::update)),
+    )
     .service(web::resource("/redemption_codes/{code}").route(web::get().to(redemption_codes::show)))
+    .service(web::resource("/refund_requests/{id}/approve").route(web::post().to(refund_requests::approve)))
+    .service(web::resource("/refund_requests/{id}/deny").route(web::post().to(refund_requests::deny)))
     .service(
         web::resource("/regions/{id}")
             .wrap(CacheResource::new(CacheUsersBy::None))
@@ -340,6 +469,16 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::get().to(settlements::show))
             .route(web::delete().to(settlements::destroy)),
     )
+    .service(
+        web::resource("/tax_rules/{id}")
+            .route(web::get().to(tax_rules::show))
+            .route(web::patch().to(tax_rules::update)),
+    )
+    .service(
+        web::resource("/tax_rules")
+            .route(web::get().to(tax_rules::index))
+            .route(web::post().to(tax_rules::create)),
+    )
     .service(web::resource("/tickets/transfer").route(web::post().to(tickets::transfer_authorization)))
     .service(web::resource("/tickets/receive").route(web::post().to(tickets::receive_transfer)))
     .service(web::resource("/tickets/send").route(web::post().to(tickets::send_via_email_or_phone)))
@@ -377,6 +516,12 @@ pub fn routes(app: &mut web::ServiceConfig) {
     .service(web::resource("/user_invites").route(web::post().to(user_invites::create)))
     .service(web::resource("/users/{id}/organizations").route(web::get().to(users::list_organizations)))
     .service(web::resource("/users/me/marketplace_account").route(web::post().to(users::create_marketplace_account)))
+    .service(
+        web::resource("/venue_sections/{id}/seats")
+            .route(web::get().to(venue_sections::seats))
+            .route(web::post().to(venue_sections::create_seats)),
+    )
+    .service(web::resource("/venue_sections/{id}").route(web::put().to(venue_sections::update)))
     .service(
         web::resource("/venues/{id}/organization_venues")
             .route(web::get().to(organization_venues::venues_index))
@@ -387,6 +532,11 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::post().to(stages::create))
             .route(web::get().to(stages::index)),
     )
+    .service(
+        web::resource("/venues/{id}/sections")
+            .route(web::post().to(venue_sections::create))
+            .route(web::get().to(venue_sections::index)),
+    )
     .service(web::resource("/venues/{id}/toggle_privacy").route(web::put().to(venues::toggle_privacy)))
     .service(
         web::resource("/venues/{id}")
@@ -400,6 +550,7 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::get().to(venues::index))
             .route(web::post().to(venues::create)),
     )
+    .service(web::resource("/waitlist_entries").route(web::get().to(waitlist_entries::index)))
     .service(
         web::resource("/sitemap.xml")
             .wrap(CacheResource::new(CacheUsersBy::None))
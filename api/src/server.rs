@@ -131,9 +131,10 @@ impl Server {
                                     http::header::AUTHORIZATION,
                                     http::header::ACCEPT,
                                     "X-API-Client-Version".parse::<http::header::HeaderName>().unwrap(),
+                                    "Idempotency-Key".parse::<http::header::HeaderName>().unwrap(),
                                 ])
                                 .allowed_header(http::header::CONTENT_TYPE)
-                                .expose_headers(vec!["x-app-version", "x-cached-response"])
+                                .expose_headers(vec!["x-app-version", "x-cached-response", "idempotent-replayed"])
                                 .max_age(3600)
                                 .finish()
                         })
@@ -1,3 +1,4 @@
+use db::models::TicketScanDirection;
 use uuid::Uuid;
 
 #[derive(Deserialize, Default, Serialize)]
@@ -5,4 +6,13 @@ pub struct TicketRedemption {
     pub ticket_id: Uuid,
     pub redeemer_id: Uuid,
     pub event_id: Uuid,
+    /// Whether the ticket was scanned in or out, messages without it are entries
+    #[serde(default)]
+    pub direction: Option<TicketScanDirection>,
+    /// Tickets inside the event, or the access zone for zone scans, after the scan
+    #[serde(default)]
+    pub occupancy: Option<i64>,
+    /// Access zone the ticket was scanned at, gate scans have none
+    #[serde(default)]
+    pub access_zone_id: Option<Uuid>,
 }
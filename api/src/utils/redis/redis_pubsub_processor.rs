@@ -15,6 +15,7 @@ use crate::errors::*;
 use crate::models::*;
 use crate::utils::redis::*;
 use actix::Addr;
+use db::models::TicketScanDirection;
 use logging::*;
 use uuid::Uuid;
 
@@ -83,6 +84,9 @@ impl RedisPubSubProcessor {
                                     EventWebSocketMessage::new(json!({
                                             "event_id": payload.event_id,
                                             "ticket_id": payload.ticket_id,
+                                            "direction": payload.direction.unwrap_or(TicketScanDirection::In),
+                                            "occupancy": payload.occupancy,
+                                            "access_zone_id": payload.access_zone_id,
                                             "event_web_socket_type": EventWebSocketType::TicketRedemption
                                     })),
                                 );
@@ -1,6 +1,7 @@
 use crate::config::Config;
 use crate::errors::*;
 use crate::payments::globee::GlobeePaymentProcessor;
+use crate::payments::mock::{self, MockPaymentProcessor};
 use crate::payments::stripe::StripePaymentProcessor;
 use crate::payments::PaymentProcessor;
 use crate::utils::deep_linker::BranchDeepLinker;
@@ -13,8 +14,12 @@ use db::utils::errors::DatabaseError;
 
 pub struct ServiceLocator {
     stripe_secret_key: String,
+    stripe_base_url: String,
+    stripe_use_payment_intents: bool,
     globee_api_key: String,
     globee_base_url: String,
+    mock_payments_use_payment_page: bool,
+    environment: Environment,
     branch_io_base_url: String,
     branch_io_branch_key: String,
     branch_io_timeout: u64,
@@ -36,8 +41,12 @@ impl ServiceLocator {
         };
         Ok(ServiceLocator {
             stripe_secret_key: config.stripe_secret_key.clone(),
+            stripe_base_url: config.stripe_base_url.clone(),
+            stripe_use_payment_intents: config.stripe_use_payment_intents,
             globee_api_key: config.globee_api_key.clone(),
             globee_base_url: config.globee_base_url.clone(),
+            mock_payments_use_payment_page: config.mock_payments_use_payment_page,
+            environment: config.environment,
             branch_io_base_url: config.branch_io_base_url.clone(),
             branch_io_branch_key: config.branch_io_branch_key.clone(),
             branch_io_timeout: config.branch_io_timeout,
@@ -63,7 +72,11 @@ impl ServiceLocator {
         organization: &Organization,
     ) -> Result<Box<dyn PaymentProcessor>, ApiError> {
         match provider {
-            PaymentProviders::Stripe => Ok(Box::new(StripePaymentProcessor::new(self.stripe_secret_key.clone()))),
+            PaymentProviders::Stripe => Ok(Box::new(StripePaymentProcessor::new(
+                self.stripe_secret_key.clone(),
+                self.stripe_base_url.clone(),
+                self.stripe_use_payment_intents,
+            ))),
             PaymentProviders::Globee => {
                 let mut org = organization.clone();
                 org.decrypt(&self.api_keys_encryption_key)?;
@@ -74,8 +87,20 @@ impl ServiceLocator {
                     self.globee_base_url.clone(),
                 )))
             }
-            // External is not valid for service locator
-            PaymentProviders::Free | PaymentProviders::External => {
+            PaymentProviders::Mock if mock::is_enabled(self.environment) => {
+                Ok(Box::new(MockPaymentProcessor::new(self.mock_payments_use_payment_page)))
+            }
+            PaymentProviders::Mock => {
+                return Err(ApplicationError::new(
+                    "Mock payment provider is only available in development and test".into(),
+                )
+                .into());
+            }
+            // External and stored value providers are not valid for service locator
+            PaymentProviders::Free
+            | PaymentProviders::External
+            | PaymentProviders::GiftCard
+            | PaymentProviders::StoreCredit => {
                 return Err(ApplicationError::new("Unknown payment provider".into()).into());
             }
         }
@@ -100,6 +125,7 @@ impl ServiceLocator {
         match provider.to_lowercase().as_str() {
             "stripe" => true,
             "globee" => false,
+            "mock" => true,
             "external" => false,
             _ => false,
         }
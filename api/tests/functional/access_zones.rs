@@ -0,0 +1,99 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::access_zones::{self, AssignAccessZoneUserRequest, CreateAccessZoneRequest};
+use api::extractors::*;
+use api::models::PathParameters;
+use db::models::*;
+use serde_json;
+use serde_json::Value;
+
+#[actix_rt::test]
+async fn create() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = event.id;
+    let json = Json(CreateAccessZoneRequest {
+        name: "VIP Lounge".to_string(),
+        ticket_type_ids: vec![ticket_type.id],
+    });
+
+    let response: HttpResponse = access_zones::create((database.connection.clone(), path, json, auth_user))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let access_zone: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(access_zone["name"], "VIP Lounge");
+    assert_eq!(access_zone["ticket_type_ids"], json!([ticket_type.id]));
+    let access_zone = AccessZone::find_for_event(event.id, connection).unwrap().remove(0);
+    assert!(access_zone.grants_ticket_type(ticket_type.id, connection).unwrap());
+}
+
+#[actix_rt::test]
+async fn create_without_access() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database.create_event().with_organization(&organization).finish();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::DoorPerson, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = event.id;
+    let json = Json(CreateAccessZoneRequest {
+        name: "VIP Lounge".to_string(),
+        ticket_type_ids: vec![],
+    });
+
+    let response: HttpResponse = access_zones::create((database.connection.clone(), path, json, auth_user))
+        .await
+        .into();
+    support::expects_unauthorized(&response);
+}
+
+#[actix_rt::test]
+async fn assign_user() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let door_person = database.create_user().finish();
+    let organization = database
+        .create_organization()
+        .with_member(&door_person, Roles::DoorPerson)
+        .finish();
+    let event = database.create_event().with_organization(&organization).finish();
+    let access_zone = AccessZone::create(event.id, "Backstage".to_string())
+        .commit(None, connection)
+        .unwrap();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = access_zone.id;
+    let json = Json(AssignAccessZoneUserRequest {
+        user_id: door_person.id,
+    });
+
+    let response: HttpResponse = access_zones::assign_user((database.connection.clone(), path, json, auth_user))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        AccessZone::find_for_user(event.id, door_person.id, connection).unwrap(),
+        Some(access_zone)
+    );
+}
@@ -47,6 +47,7 @@ pub async fn sales_summary_report(role: Roles, should_succeed: bool) {
                 total: 2,
                 event_name: event.name.clone(),
                 event_date: event.event_start,
+                currency: "USD".to_string(),
                 ticket_name: ticket_type.name.clone(),
                 face_value_in_cents: ticket_pricing.price_in_cents,
                 online_sale_count: 1,
@@ -58,6 +59,7 @@ pub async fn sales_summary_report(role: Roles, should_succeed: bool) {
                 total: 2,
                 event_name: event.name.clone(),
                 event_date: event.event_start,
+                currency: "USD".to_string(),
                 ticket_name: "Per Order Fee".to_string(),
                 face_value_in_cents: 0,
                 online_sale_count: 0,
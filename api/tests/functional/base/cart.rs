@@ -42,7 +42,10 @@ pub async fn update_box_office_pricing(role: Roles, should_test_succeed: bool) {
             ticket_type_id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
+            event_time_slot_id: None,
         }],
+        products: vec![],
         tracking_data: None,
     });
 
@@ -137,7 +140,10 @@ pub async fn replace_box_office_pricing(role: Roles, should_test_succeed: bool)
             ticket_type_id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
+            event_time_slot_id: None,
         }],
+        products: vec![],
         tracking_data: None,
     });
 
@@ -98,6 +98,9 @@ pub async fn redeem_ticket(role: Roles, should_test_succeed: bool) {
     let request_data = TicketRedeemRequest {
         redeem_key: "WrongKey".to_string(),
         check_in_source: Some(CheckInSource::Scanned),
+        ignore_time_slot: false,
+        redeem_code: None,
+        direction: None,
     };
 
     let response: HttpResponse = events::redeem_ticket((
@@ -117,6 +120,9 @@ pub async fn redeem_ticket(role: Roles, should_test_succeed: bool) {
         let request_data = TicketRedeemRequest {
             redeem_key: ticket.redeem_key.unwrap(),
             check_in_source: Some(CheckInSource::Scanned),
+            ignore_time_slot: false,
+            redeem_code: None,
+            direction: None,
         };
 
         let response: HttpResponse = events::redeem_ticket((
@@ -363,8 +369,11 @@ pub async fn cancel(role: Roles, should_test_succeed: bool) {
     let test_request = TestRequest::create();
     let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
     path.id = event.id;
+    let query = Query::<CancelEventParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
 
-    let response: HttpResponse = events::cancel((database.connection.into(), path, auth_user))
+    let response: HttpResponse = events::cancel((database.connection.into(), path, query, auth_user))
         .await
         .into();
     if should_test_succeed {
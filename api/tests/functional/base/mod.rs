@@ -23,4 +23,5 @@ pub mod ticket_types;
 pub mod tickets;
 pub mod transfers;
 pub mod users;
+pub mod venue_sections;
 pub mod venues;
@@ -307,6 +307,7 @@ pub async fn details(role: Roles, should_succeed: bool) {
             code_type: None,
             pending_transfer_id: None,
             discount_price_in_cents: None,
+            seat_label: None,
         },
         OrderDetailsLineItem {
             ticket_instance_id: Some(ticket.id),
@@ -327,6 +328,7 @@ pub async fn details(role: Roles, should_succeed: bool) {
             code_type: None,
             pending_transfer_id: None,
             discount_price_in_cents: None,
+            seat_label: None,
         },
     ];
 
@@ -350,6 +352,7 @@ pub async fn details(role: Roles, should_succeed: bool) {
         code_type: None,
         pending_transfer_id: None,
         discount_price_in_cents: None,
+        seat_label: None,
     });
 
     let test_request = TestRequest::create();
@@ -432,6 +435,7 @@ pub async fn refund(role: Roles, manual_override: bool, should_succeed: bool) {
         items: refund_items,
         reason: None,
         manual_override,
+        refund_to_store_credit: false,
     });
 
     let test_request = TestRequest::create();
@@ -82,16 +82,21 @@ pub async fn scan_counts(role: Roles, should_succeed: bool) {
 
     assert_eq!(response.status(), StatusCode::OK);
     let body = support::unwrap_body_to_string(&response).unwrap();
-    let report_data: Payload<ScanCountReportRow> = serde_json::from_str(&body).unwrap();
+    let report_data: ScanCountReport = serde_json::from_str(&body).unwrap();
     assert_eq!(
         vec![ScanCountReportRow {
             total: None,
             ticket_type_name: ticket_types[0].name.clone(),
             scanned_count: 1,
-            not_scanned_count: 7
+            not_scanned_count: 7,
+            inside_count: 1,
         }],
-        report_data.data
+        report_data.scan_counts.data
     );
+    assert_eq!(report_data.occupancy.len(), 1);
+    assert_eq!(report_data.occupancy[0].entries, 2);
+    assert_eq!(report_data.occupancy[0].exits, 0);
+    assert_eq!(report_data.occupancy[0].occupancy, 2);
 }
 
 pub async fn box_office_sales_summary(role: Roles, should_succeed: bool) {
@@ -197,6 +202,7 @@ pub async fn box_office_sales_summary(role: Roles, should_succeed: bool) {
                     BoxOfficeSalesSummaryOperatorEventRow {
                         event_name: Some("Event1".to_string()),
                         event_date: Some(NaiveDate::from_ymd(2016, 7, 8).and_hms(9, 10, 11)),
+                        currency: "USD".to_string(),
                         number_of_tickets: 2,
                         face_value_in_cents: 150,
                         revenue_share_value_in_cents: 0,
@@ -205,6 +211,7 @@ pub async fn box_office_sales_summary(role: Roles, should_succeed: bool) {
                     BoxOfficeSalesSummaryOperatorEventRow {
                         event_name: Some("Event2".to_string()),
                         event_date: Some(NaiveDate::from_ymd(2017, 7, 8).and_hms(9, 10, 11)),
+                        currency: "USD".to_string(),
                         number_of_tickets: 2,
                         face_value_in_cents: 150,
                         revenue_share_value_in_cents: 0,
@@ -213,6 +220,7 @@ pub async fn box_office_sales_summary(role: Roles, should_succeed: bool) {
                 ],
                 payments: vec![BoxOfficeSalesSummaryPaymentRow {
                     payment_type: ExternalPaymentType::CreditCard,
+                    currency: "USD".to_string(),
                     quantity: 4,
                     total_sales_in_cents: 600,
                 }],
@@ -223,6 +231,7 @@ pub async fn box_office_sales_summary(role: Roles, should_succeed: bool) {
                 events: vec![BoxOfficeSalesSummaryOperatorEventRow {
                     event_name: Some("Event1".to_string()),
                     event_date: Some(NaiveDate::from_ymd(2016, 7, 8).and_hms(9, 10, 11)),
+                    currency: "USD".to_string(),
                     number_of_tickets: 2,
                     face_value_in_cents: 150,
                     revenue_share_value_in_cents: 0,
@@ -230,6 +239,7 @@ pub async fn box_office_sales_summary(role: Roles, should_succeed: bool) {
                 }],
                 payments: vec![BoxOfficeSalesSummaryPaymentRow {
                     payment_type: ExternalPaymentType::CreditCard,
+                    currency: "USD".to_string(),
                     quantity: 2,
                     total_sales_in_cents: 300,
                 }],
@@ -237,6 +247,7 @@ pub async fn box_office_sales_summary(role: Roles, should_succeed: bool) {
         ],
         payments: vec![BoxOfficeSalesSummaryPaymentRow {
             payment_type: ExternalPaymentType::CreditCard,
+            currency: "USD".to_string(),
             quantity: 6,
             total_sales_in_cents: 900,
         }],
@@ -484,8 +495,12 @@ fn build_transaction_report_row(
         client_fee_in_cents_total: fee_schedule_range.client_fee_in_cents * quantity,
         event_fee_client_in_cents: organization.client_event_fee_in_cents,
         event_fee_client_in_cents_total: organization.client_event_fee_in_cents,
+        tax_in_cents: 0,
+        included_tax_in_cents: 0,
+        tax_in_cents_total: 0,
         fee_range_id: Some(fee_schedule_range.id),
         order_type: OrderTypes::Cart,
+        currency: "USD".to_string(),
         payment_method: Some(PaymentMethods::CreditCard.to_string()),
         payment_provider: Some(PaymentProviders::Stripe.to_string()),
         transaction_date: order.paid_at.clone().unwrap(),
@@ -508,6 +523,7 @@ fn build_transaction_report_row(
         content: None,
         platform: None,
         check_in_source: None,
+        seat_label: None,
         headline_artist_alt_genres: None,
         headline_artist_main_genre: None,
     }
@@ -114,6 +114,7 @@ pub async fn domain_transaction_detail_report(role: Roles, should_succeed: bool)
             transaction_date: order.paid_at.unwrap(),
             point_of_sale: None,
             payment_method: PaymentMethods::CreditCard.to_string(),
+            currency: "USD".to_string(),
             qty_tickets_sold: 2,
             qty_tickets_refunded: 0,
             qty_tickets_sold_net: 2,
@@ -50,14 +50,16 @@ pub async fn create(role: Roles, should_succeed: bool) {
         note: None,
         settlement_adjustment_type: SettlementAdjustmentTypes::ManualCredit,
         amount_in_cents: 100,
+        currency: None,
     });
 
     let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
     let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
     let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
     path.id = settlement.id;
     let response: HttpResponse =
-        settlement_adjustments::create((database.connection.clone().into(), path, json, auth_user))
+        settlement_adjustments::create((database.connection.clone().into(), path, json, auth_user, state))
             .await
             .into();
     if !should_succeed {
@@ -69,6 +71,7 @@ pub async fn create(role: Roles, should_succeed: bool) {
     let returned_settlement_adjustment: SettlementAdjustment = serde_json::from_str(&body).unwrap();
     assert_eq!(returned_settlement_adjustment.settlement_id, settlement.id);
     assert_eq!(returned_settlement_adjustment.amount_in_cents, 100);
+    assert_eq!(returned_settlement_adjustment.currency, "USD");
     assert_eq!(
         returned_settlement_adjustment.settlement_adjustment_type,
         SettlementAdjustmentTypes::ManualCredit
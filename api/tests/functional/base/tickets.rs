@@ -2,7 +2,7 @@ use crate::support;
 use crate::support::database::TestDatabase;
 use crate::support::test_request::TestRequest;
 use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
-use api::controllers::tickets::{self, ShowTicketResponse};
+use api::controllers::tickets::{self, ShowTicketResponse, UpdateTicketRequest};
 use api::extractors::*;
 use api::models::PathParameters;
 use db::models::*;
@@ -55,6 +55,8 @@ pub async fn show_other_user_ticket(role: Roles, should_test_succeed: bool) {
             transfer_address: None,
             check_in_source: None,
             promo_image_url: None,
+            seat_label: None,
+            redeem_secret: None,
         };
 
         let expected_result = ShowTicketResponse {
@@ -92,14 +94,25 @@ pub async fn update(role: Roles, owns_ticket: bool, should_test_succeed: bool) {
 
     let mut path = Path::<PathParameters>::extract(&request.request).await.unwrap();
     path.id = ticket.id;
-    let json = Json(UpdateTicketInstanceAttributes {
-        first_name_override: Some(Some("First".to_string())),
-        last_name_override: Some(Some("Last".to_string())),
+    let json = Json(UpdateTicketRequest {
+        attributes: UpdateTicketInstanceAttributes {
+            first_name_override: Some(Some("First".to_string())),
+            last_name_override: Some(Some("Last".to_string())),
+            attendee_email: None,
+            attendee_birth_date: None,
+        },
+        expected_fee_in_cents: None,
     });
 
-    let response: HttpResponse = tickets::update((database.connection.clone().into(), path, json, auth_user))
-        .await
-        .into();
+    let response: HttpResponse = tickets::update((
+        database.connection.clone().into(),
+        path,
+        json,
+        auth_user,
+        request.extract_state().await,
+    ))
+    .await
+    .into();
 
     if should_test_succeed {
         assert_eq!(response.status(), StatusCode::OK);
@@ -123,6 +136,8 @@ pub async fn update(role: Roles, owns_ticket: bool, should_test_succeed: bool) {
             transfer_address: None,
             check_in_source: None,
             promo_image_url: None,
+            seat_label: None,
+            redeem_secret: None,
         };
 
         let expected_result = ShowTicketResponse {
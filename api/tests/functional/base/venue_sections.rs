@@ -0,0 +1,77 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::venue_sections;
+use api::extractors::*;
+use api::models::PathParameters;
+use db::models::{Roles, VenueSeat, VenueSection};
+use serde_json;
+
+pub async fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let venue = database.create_venue().finish();
+    let name = "Orchestra";
+
+    let user = support::create_auth_user(role, None, &database);
+    let json = Json(venue_sections::CreateVenueSection {
+        name: name.to_string(),
+        stage_id: None,
+    });
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = venue.id;
+    let response: HttpResponse = venue_sections::create((database.connection.into(), path, json, user))
+        .await
+        .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let venue_section: VenueSection = serde_json::from_str(&body).unwrap();
+    assert_eq!(venue_section.name, name);
+    assert_eq!(venue_section.venue_id, venue.id);
+}
+
+pub async fn create_seats(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let venue = database.create_venue().finish();
+    let venue_section = database.create_venue_section().with_venue_id(venue.id).finish();
+
+    let user = support::create_auth_user(role, None, &database);
+    let json = Json(venue_sections::CreateVenueSeatsRequest {
+        seats: vec![
+            venue_sections::CreateVenueSeat {
+                row_label: "A".to_string(),
+                seat_number: "1".to_string(),
+                accessible: true,
+            },
+            venue_sections::CreateVenueSeat {
+                row_label: "A".to_string(),
+                seat_number: "2".to_string(),
+                accessible: false,
+            },
+        ],
+    });
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = venue_section.id;
+    let response: HttpResponse = venue_sections::create_seats((database.connection.clone().into(), path, json, user))
+        .await
+        .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let seats: Vec<VenueSeat> = serde_json::from_str(&body).unwrap();
+    assert_eq!(seats.len(), 2);
+    assert_eq!(venue_section.seats(database.connection.get()).unwrap(), seats);
+}
@@ -1,10 +1,11 @@
 use crate::functional::base;
 use crate::support::database::TestDatabase;
+use crate::support::mock_stripe::MockStripe;
 use crate::support::test_request::TestRequest;
 use crate::support::{self, *};
 use actix_web::{
     http::StatusCode,
-    web::{Path, Query},
+    web::{Bytes, Path, Query},
     FromRequest, HttpResponse,
 };
 use api::controllers;
@@ -13,6 +14,8 @@ use api::controllers::cart::*;
 use api::domain_events::executors::ProcessPaymentIPNExecutor;
 use api::extractors::*;
 use api::models::*;
+use api::payments::mock::MockIpnRequest;
+use api::utils::ServiceLocator;
 use chrono::prelude::*;
 use chrono::Duration;
 use db::models::*;
@@ -370,7 +373,10 @@ async fn update() {
             ticket_type_id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
+            event_time_slot_id: None,
         }],
+        products: vec![],
         tracking_data: None,
     });
 
@@ -398,6 +404,75 @@ async fn update() {
     assert_eq!(order_item.unit_price_in_cents, ticket_pricing.price_in_cents);
 }
 
+#[actix_rt::test]
+async fn update_with_seats() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let venue = database.create_venue().finish();
+    let venue_section = database
+        .create_venue_section()
+        .with_venue_id(venue.id)
+        .with_seats(vec!["A"], 4)
+        .finish();
+    let seats = venue_section.seats(connection).unwrap();
+    let event = database
+        .create_event()
+        .with_venue(&venue)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event
+        .ticket_types(true, None, connection)
+        .unwrap()
+        .remove(0)
+        .update(
+            TicketTypeEditableAttributes {
+                venue_section_id: Some(Some(venue_section.id)),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+
+    let user = database.create_user().finish();
+    let input = Json(cart::UpdateCartRequest {
+        box_office_pricing: None,
+        items: vec![cart::CartItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+            seat_ids: Some(vec![seats[1].id, seats[3].id]),
+            event_time_slot_id: None,
+        }],
+        products: vec![],
+        tracking_data: None,
+    });
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let response = cart::update_cart((
+        database.connection.clone().into(),
+        input,
+        auth_user,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let cart = Order::find_cart_for_user(user.id, &connection).unwrap().unwrap();
+    let mut seat_ids: Vec<Option<Uuid>> = cart
+        .tickets(Some(ticket_type.id), connection)
+        .unwrap()
+        .into_iter()
+        .map(|t| t.venue_seat_id)
+        .collect();
+    seat_ids.sort();
+    let mut expected_seat_ids = vec![Some(seats[1].id), Some(seats[3].id)];
+    expected_seat_ids.sort();
+    assert_eq!(seat_ids, expected_seat_ids);
+}
+
 #[actix_rt::test]
 async fn update_with_draft_event() {
     let database = TestDatabase::new();
@@ -417,7 +492,10 @@ async fn update_with_draft_event() {
             ticket_type_id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
+            event_time_slot_id: None,
         }],
+        products: vec![],
         tracking_data: None,
         box_office_pricing: None,
     });
@@ -451,17 +529,22 @@ async fn update_multiple() {
     let ticket_type_id2 = ticket_types[1].id;
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: None,
+        products: vec![],
         tracking_data: None,
         items: vec![
             cart::CartItem {
                 ticket_type_id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
+                event_time_slot_id: None,
             },
             cart::CartItem {
                 ticket_type_id: ticket_type_id2,
                 quantity: 3,
                 redemption_code: None,
+                seat_ids: None,
+                event_time_slot_id: None,
             },
         ],
     });
@@ -520,11 +603,14 @@ async fn add_with_increment() {
 
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: None,
+        products: vec![],
         tracking_data: None,
         items: vec![cart::CartItem {
             ticket_type_id,
             quantity: 4,
             redemption_code: None,
+            seat_ids: None,
+            event_time_slot_id: None,
         }],
     });
 
@@ -569,11 +655,14 @@ async fn update_with_increment_failure_invalid_quantity() {
 
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: None,
+        products: vec![],
         tracking_data: None,
         items: vec![cart::CartItem {
             ticket_type_id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
+            event_time_slot_id: None,
         }],
     });
 
@@ -609,11 +698,14 @@ async fn update_with_existing_cart() {
 
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: None,
+        products: vec![],
         tracking_data: None,
         items: vec![cart::CartItem {
             ticket_type_id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
+            event_time_slot_id: None,
         }],
     });
 
@@ -671,11 +763,14 @@ async fn reduce() {
 
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: None,
+        products: vec![],
         tracking_data: None,
         items: vec![cart::CartItem {
             ticket_type_id,
             quantity: 6,
             redemption_code: None,
+            seat_ids: None,
+            event_time_slot_id: None,
         }],
     });
 
@@ -737,11 +832,14 @@ async fn remove() {
 
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: None,
+        products: vec![],
         tracking_data: None,
         items: vec![cart::CartItem {
             ticket_type_id,
             quantity: 0,
             redemption_code: None,
+            seat_ids: None,
+            event_time_slot_id: None,
         }],
     });
 
@@ -805,11 +903,14 @@ async fn remove_with_increment() {
 
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: None,
+        products: vec![],
         tracking_data: None,
         items: vec![cart::CartItem {
             ticket_type_id,
             quantity: 8,
             redemption_code: None,
+            seat_ids: None,
+            event_time_slot_id: None,
         }],
     });
 
@@ -876,11 +977,14 @@ async fn remove_with_increment_failure_invalid_quantity() {
 
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: None,
+        products: vec![],
         tracking_data: None,
         items: vec![cart::CartItem {
             ticket_type_id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
+            event_time_slot_id: None,
         }],
     });
 
@@ -916,6 +1020,9 @@ async fn checkout_external() {
 
     let input = Json(cart::CheckoutCartRequest {
         tracking_data: None,
+        gift_card_codes: vec![],
+        use_store_credit: false,
+        installment_plan: false,
         method: PaymentRequest::External {
             reference: Some("TestRef".to_string()),
             external_payment_type: ExternalPaymentType::Voucher,
@@ -954,6 +1061,115 @@ async fn checkout_external() {
     assert_eq!("Example note".to_string(), note.note);
 }
 
+#[actix_rt::test]
+async fn checkout_split_external() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = database.create_user().finish();
+    let order = database.create_cart().for_user(&user).for_event(&event).finish();
+    let total = order.calculate_total(connection).unwrap();
+    let request = TestRequest::create();
+
+    let external_payment = |external_payment_type: ExternalPaymentType| PaymentRequest::External {
+        reference: None,
+        external_payment_type,
+        first_name: "First".to_string(),
+        last_name: "Last".to_string(),
+        email: None,
+        phone: None,
+        note: None,
+    };
+    let input = Json(cart::CheckoutCartRequest {
+        tracking_data: None,
+        gift_card_codes: vec![],
+        use_store_credit: false,
+        installment_plan: false,
+        method: PaymentRequest::Split {
+            payments: vec![
+                cart::SplitPaymentRequest {
+                    amount: 1000,
+                    method: external_payment(ExternalPaymentType::Cash),
+                },
+                cart::SplitPaymentRequest {
+                    amount: total - 1000,
+                    method: external_payment(ExternalPaymentType::CreditCard),
+                },
+            ],
+        },
+    });
+    let user = support::create_auth_user_from_user(&user, Roles::Admin, None, &database);
+
+    let response = cart::checkout((
+        database.connection.clone().into(),
+        input,
+        user,
+        request.extract_state().await,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let order = Order::find(order.id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+    let payments = order.payments(connection).unwrap();
+    assert_eq!(
+        payments.iter().map(|p| p.amount).collect::<Vec<i64>>(),
+        vec![1000, total - 1000]
+    );
+    // Both payments are attributed to a single guest
+    assert!(order.on_behalf_of_user_id.is_some());
+}
+
+#[actix_rt::test]
+async fn checkout_split_amounts_must_match_amount_due() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = database.create_user().finish();
+    let order = database.create_cart().for_user(&user).for_event(&event).finish();
+    let total = order.calculate_total(connection).unwrap();
+    let request = TestRequest::create();
+
+    let input = Json(cart::CheckoutCartRequest {
+        tracking_data: None,
+        gift_card_codes: vec![],
+        use_store_credit: false,
+        installment_plan: false,
+        method: PaymentRequest::Split {
+            payments: vec![cart::SplitPaymentRequest {
+                amount: total - 1,
+                method: PaymentRequest::External {
+                    reference: None,
+                    external_payment_type: ExternalPaymentType::Cash,
+                    first_name: "First".to_string(),
+                    last_name: "Last".to_string(),
+                    email: None,
+                    phone: None,
+                    note: None,
+                },
+            }],
+        },
+    });
+    let user = support::create_auth_user_from_user(&user, Roles::Admin, None, &database);
+
+    let response: HttpResponse = cart::checkout((
+        database.connection.clone().into(),
+        input,
+        user,
+        request.extract_state().await,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let order = Order::find(order.id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Draft);
+    assert!(order.payments(connection).unwrap().is_empty());
+}
+
 #[actix_rt::test]
 async fn checkout_external_with_free_cart() {
     let database = TestDatabase::new();
@@ -971,6 +1187,9 @@ async fn checkout_external_with_free_cart() {
 
     let input = Json(cart::CheckoutCartRequest {
         tracking_data: None,
+        gift_card_codes: vec![],
+        use_store_credit: false,
+        installment_plan: false,
         method: PaymentRequest::External {
             reference: Some("TestRef".to_string()),
             external_payment_type: ExternalPaymentType::Cash,
@@ -1025,6 +1244,9 @@ async fn checkout_paid_fails_with_free_cart() {
 
     let input = Json(cart::CheckoutCartRequest {
         tracking_data: None,
+        gift_card_codes: vec![],
+        use_store_credit: false,
+        installment_plan: false,
         method: PaymentRequest::Card {
             token: "abc".into(),
             provider: PaymentProviders::Stripe,
@@ -1077,6 +1299,9 @@ async fn checkout_free() {
 
     let input = Json(cart::CheckoutCartRequest {
         tracking_data: None,
+        gift_card_codes: vec![],
+        use_store_credit: false,
+        installment_plan: false,
         method: PaymentRequest::Free,
     });
 
@@ -1118,6 +1343,9 @@ async fn checkout_free_for_paid_items() {
 
     let input = Json(cart::CheckoutCartRequest {
         tracking_data: None,
+        gift_card_codes: vec![],
+        use_store_credit: false,
+        installment_plan: false,
         method: PaymentRequest::Free,
     });
 
@@ -1227,6 +1455,9 @@ async fn checkout_fails_for_invalid_items() {
 
     let input = Json(cart::CheckoutCartRequest {
         tracking_data: None,
+        gift_card_codes: vec![],
+        use_store_credit: false,
+        installment_plan: false,
         method: PaymentRequest::External {
             reference: Some("TestRef".to_string()),
             external_payment_type: ExternalPaymentType::Cash,
@@ -1276,6 +1507,9 @@ async fn checkout_provider_globee() {
 
     let input = Json(cart::CheckoutCartRequest {
         tracking_data: None,
+        gift_card_codes: vec![],
+        use_store_credit: false,
+        installment_plan: false,
         method: PaymentRequest::Provider {
             provider: PaymentProviders::Globee,
         },
@@ -1384,3 +1618,675 @@ async fn checkout_provider_globee() {
     let order = Order::find(order.id, conn).unwrap();
     assert_eq!(order.status, OrderStatus::Paid);
 }
+
+#[actix_rt::test]
+async fn checkout_card_mock() {
+    let database = TestDatabase::new();
+    let conn = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = database.create_user().finish();
+    let order = database.create_cart().for_user(&user).for_event(&event).finish();
+    let request = TestRequest::create();
+    let user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let checkout_input = |token: &str| {
+        Json(cart::CheckoutCartRequest {
+            tracking_data: None,
+            gift_card_codes: vec![],
+            use_store_credit: false,
+            installment_plan: false,
+            method: PaymentRequest::Card {
+                token: token.to_string(),
+                provider: PaymentProviders::Mock,
+                save_payment_method: false,
+                set_default: false,
+            },
+        })
+    };
+    // Checkout commits the authorized payment before completing it, keep that inside the test transaction
+    database.connection.begin_transaction().unwrap();
+
+    let response: HttpResponse = cart::checkout((
+        database.connection.clone().into(),
+        checkout_input("tok_decline"),
+        user.clone(),
+        request.extract_state().await,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let order = Order::find(order.id, conn).unwrap();
+    assert_eq!(order.status, OrderStatus::Draft);
+    assert!(order.payments(conn).unwrap().is_empty());
+
+    let response = cart::checkout((
+        database.connection.clone().into(),
+        checkout_input("tok_refund_fail"),
+        user,
+        request.extract_state().await,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let order = Order::find(order.id, conn).unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+    let payment = order.payments(conn).unwrap().remove(0);
+    assert_eq!(payment.status, PaymentStatus::Completed);
+    assert_eq!(payment.provider, PaymentProviders::Mock);
+
+    let organization = event.organization(conn).unwrap();
+    let client = ServiceLocator::new(&request.config)
+        .unwrap()
+        .create_payment_processor(PaymentProviders::Mock, &organization)
+        .unwrap();
+    let external_reference = payment.external_reference.unwrap();
+    assert!(client.partial_refund_blocking(&external_reference, 100).is_err());
+    assert!(client.partial_refund_blocking("mock_ch_tok_visa", 100).is_ok());
+
+    // Never available outside of development and test
+    let mut config = request.config.clone();
+    config.environment = Environment::Production;
+    assert!(ServiceLocator::new(&config)
+        .unwrap()
+        .create_payment_processor(PaymentProviders::Mock, &organization)
+        .is_err());
+}
+
+#[actix_rt::test]
+async fn checkout_provider_mock_with_delayed_ipn() {
+    let database = TestDatabase::new();
+    let conn = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = database.create_user().finish();
+    let cart = database.create_cart().for_user(&user).for_event(&event).finish();
+    let total = cart.calculate_total(conn).unwrap();
+    let mut config = TestRequest::config();
+    config.mock_payments_use_payment_page = true;
+    let request = TestRequest::create_with_config(config);
+    let user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let input = Json(cart::CheckoutCartRequest {
+        tracking_data: None,
+        gift_card_codes: vec![],
+        use_store_credit: false,
+        installment_plan: false,
+        method: PaymentRequest::Provider {
+            provider: PaymentProviders::Mock,
+        },
+    });
+    let response = cart::checkout((
+        database.connection.clone().into(),
+        input,
+        user,
+        request.extract_state().await,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let order = Order::find(cart.id, conn).unwrap();
+    let payment = order.payments(conn).unwrap().remove(0);
+    assert_eq!(payment.status, PaymentStatus::Requested);
+    assert_eq!(payment.provider, PaymentProviders::Mock);
+    // No payment page, the customer is sent straight back to the success callback
+    assert!(order.checkout_url.unwrap().contains("success=true"));
+    let payment_request_id = payment.external_reference.unwrap().replacen("mock-", "", 1);
+
+    let ipn = MockIpnRequest {
+        id: payment_request_id,
+        order_id: order.id,
+        status: "completed".to_string(),
+        amount: Some(total),
+        delay_seconds: Some(3600),
+    };
+    let response = controllers::ipns::mock((
+        Json(ipn),
+        database.connection.clone().into(),
+        request.extract_state().await,
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Delayed IPNs are not processed until they are due
+    assert!(
+        DomainAction::find_pending(Some(DomainActionTypes::PaymentProviderIPN), conn)
+            .unwrap()
+            .is_empty()
+    );
+    let mut domain_actions = DomainAction::find_by_resource(
+        Some(Tables::Orders),
+        Some(order.id),
+        DomainActionTypes::PaymentProviderIPN,
+        DomainActionStatus::Pending,
+        conn,
+    )
+    .unwrap();
+    assert_eq!(domain_actions.len(), 1);
+    let domain_action = domain_actions.remove(0);
+    assert!(domain_action.scheduled_at > dates::now().add_minutes(59).finish());
+
+    ProcessPaymentIPNExecutor::new(&request.config)
+        .perform_job(domain_action, database.connection.clone())
+        .await
+        .unwrap();
+    let order = Order::find(order.id, conn).unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+    assert_eq!(order.payments(conn).unwrap()[0].status, PaymentStatus::Completed);
+}
+
+#[actix_rt::test]
+async fn stripe_payment_intent_webhook() {
+    let database = TestDatabase::new();
+    let conn = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = database.create_user().finish();
+    let mut cart = database.create_cart().for_user(&user).for_event(&event).finish();
+    let total = cart.calculate_total(conn).unwrap();
+    // Payment awaiting 3D Secure authentication
+    cart.add_credit_card_payment(
+        user.id,
+        total,
+        PaymentProviders::Stripe,
+        "pi_123".to_string(),
+        PaymentStatus::Requested,
+        json!({}),
+        conn,
+    )
+    .unwrap();
+    let request = TestRequest::create();
+
+    // Requests without a valid signature are rejected
+    let body =
+        serde_json::to_vec(&json!({"id": "evt_0", "object": "event", "type": "payment_intent.succeeded"})).unwrap();
+    let http_request = actix_web::test::TestRequest::default()
+        .header("Stripe-Signature", format!("t={},v1=00", Utc::now().timestamp()))
+        .to_http_request();
+    let response = controllers::ipns::stripe((
+        http_request,
+        Bytes::from(body),
+        database.connection.clone().into(),
+        request.extract_state().await,
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Webhooks are disabled when no signing secret is configured
+    let mut config = TestRequest::config();
+    config.stripe_webhook_secret = None;
+    let unconfigured_request = TestRequest::create_with_config(config);
+    let body =
+        serde_json::to_vec(&json!({"id": "evt_0", "object": "event", "type": "payment_intent.succeeded"})).unwrap();
+    let http_request = actix_web::test::TestRequest::default()
+        .header(
+            "Stripe-Signature",
+            stripe::webhook_signature_header(&body, "", Utc::now().timestamp()),
+        )
+        .to_http_request();
+    let response = controllers::ipns::stripe((
+        http_request,
+        Bytes::from(body),
+        database.connection.clone().into(),
+        unconfigured_request.extract_state().await,
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // Events unrelated to payment intents are ignored
+    let response = post_stripe_webhook(
+        &database,
+        &request,
+        json!({"id": "evt_1", "object": "event", "type": "customer.created", "data": {"object": {}}}),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(
+        DomainAction::find_pending(Some(DomainActionTypes::PaymentProviderIPN), conn)
+            .unwrap()
+            .is_empty()
+    );
+
+    let response = post_stripe_webhook(
+        &database,
+        &request,
+        json!({
+            "id": "evt_2",
+            "object": "event",
+            "type": "payment_intent.succeeded",
+            "data": {"object": {
+                "id": "pi_123",
+                "object": "payment_intent",
+                "status": "succeeded",
+                "amount": total,
+                "client_secret": "pi_123_secret",
+                "next_action": null,
+                "metadata": {"order_id": cart.id.to_string()}
+            }}
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mut domain_actions = DomainAction::find_pending(Some(DomainActionTypes::PaymentProviderIPN), conn).unwrap();
+    assert_eq!(domain_actions.len(), 1);
+    let domain_action = domain_actions.remove(0);
+    assert_eq!(domain_action.main_table_id, Some(cart.id));
+
+    let processor = ProcessPaymentIPNExecutor::new(&request.config);
+    processor
+        .perform_job(domain_action, database.connection.clone())
+        .await
+        .unwrap();
+
+    let order = Order::find(cart.id, conn).unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+    assert_eq!(order.payments(conn).unwrap()[0].status, PaymentStatus::Completed);
+}
+
+#[actix_rt::test]
+async fn stripe_dispute_webhook() {
+    let database = TestDatabase::new();
+    let conn = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = database.create_user().finish();
+    let mut cart = database.create_cart().for_user(&user).for_event(&event).finish();
+    let total = cart.calculate_total(conn).unwrap();
+    cart.add_credit_card_payment(
+        user.id,
+        total,
+        PaymentProviders::Stripe,
+        "pi_123".to_string(),
+        PaymentStatus::Completed,
+        json!({}),
+        conn,
+    )
+    .unwrap();
+    let settlement = database.create_settlement().with_organization(&organization).finish();
+    let request = TestRequest::create();
+
+    // Dispute events carry no order metadata, the payment is found by its payment intent
+    let response = post_stripe_webhook(
+        &database,
+        &request,
+        json!({
+            "id": "evt_3",
+            "object": "event",
+            "type": "charge.dispute.created",
+            "data": {"object": {
+                "id": "dp_123",
+                "object": "dispute",
+                "charge": "ch_123",
+                "payment_intent": "pi_123",
+                "amount": 500,
+                "reason": "fraudulent"
+            }}
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mut domain_actions = DomainAction::find_pending(Some(DomainActionTypes::PaymentProviderIPN), conn).unwrap();
+    assert_eq!(domain_actions.len(), 1);
+    let processor = ProcessPaymentIPNExecutor::new(&request.config);
+    processor
+        .perform_job(domain_actions.remove(0), database.connection.clone())
+        .await
+        .unwrap();
+
+    let dispute = Dispute::find_by_external_reference("dp_123", conn).unwrap().unwrap();
+    assert_eq!(dispute.order_id, cart.id);
+    assert_eq!(dispute.amount_in_cents, 500);
+    assert_eq!(dispute.reason, Some("fraudulent".to_string()));
+    let adjustments = settlement.adjustments(conn).unwrap();
+    assert_eq!(adjustments.len(), 1);
+    assert_eq!(Some(adjustments[0].id), dispute.settlement_adjustment_id);
+    assert_eq!(
+        adjustments[0].settlement_adjustment_type,
+        SettlementAdjustmentTypes::Chargeback
+    );
+    assert_eq!(adjustments[0].amount_in_cents, 500);
+    let order = Order::find(cart.id, conn).unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+}
+
+#[actix_rt::test]
+async fn stripe_refund_webhook() {
+    let database = TestDatabase::new();
+    let conn = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = database.create_user().finish();
+    let mut cart = database.create_cart().for_user(&user).for_event(&event).finish();
+    let total = cart.calculate_total(conn).unwrap();
+    let payment = cart
+        .add_credit_card_payment(
+            user.id,
+            total,
+            PaymentProviders::Stripe,
+            "pi_123".to_string(),
+            PaymentStatus::Completed,
+            json!({}),
+            conn,
+        )
+        .unwrap();
+    let request = TestRequest::create();
+    let charge_refunded = |id: &str, amount_refunded: i64| {
+        json!({
+            "id": id,
+            "object": "event",
+            "type": "charge.refunded",
+            "data": {"object": {
+                "id": "ch_123",
+                "object": "charge",
+                "payment_intent": "pi_123",
+                "amount": total,
+                "amount_refunded": amount_refunded,
+                "refunded": amount_refunded == total,
+                "metadata": {"order_id": cart.id.to_string()}
+            }}
+        })
+    };
+
+    // Partial refund made at Stripe
+    process_stripe_webhook(&database, &request, charge_refunded("evt_1", 500)).await;
+    assert_eq!(payment.refunded_amount(conn).unwrap(), 500);
+    let refund_payments: Vec<Payment> = Order::find(cart.id, conn)
+        .unwrap()
+        .payments(conn)
+        .unwrap()
+        .into_iter()
+        .filter(|p| p.refund_id.is_some())
+        .collect();
+    assert_eq!(refund_payments.len(), 1);
+    assert_eq!(refund_payments[0].status, PaymentStatus::Refunded);
+    assert_eq!(refund_payments[0].amount, -500);
+    assert_eq!(refund_payments[0].external_reference, Some("pi_123".to_string()));
+    let refund = Refund::find(refund_payments[0].refund_id.unwrap(), conn).unwrap();
+    assert_eq!(refund.order_id, cart.id);
+
+    // Redelivered events do not record the refund twice
+    process_stripe_webhook(&database, &request, charge_refunded("evt_1", 500)).await;
+    assert_eq!(payment.refunded_amount(conn).unwrap(), 500);
+
+    // Refunding the rest of the charge fully refunds the payment
+    process_stripe_webhook(&database, &request, charge_refunded("evt_2", total)).await;
+    assert_eq!(payment.refunded_amount(conn).unwrap(), total);
+    let order = Order::find(cart.id, conn).unwrap();
+    assert!(order.refundable_payments(conn).unwrap().is_empty());
+}
+
+#[actix_rt::test]
+async fn stripe_payment_intent_checkout_requires_action() {
+    let database = TestDatabase::new();
+    let conn = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = database.create_user().finish();
+    let cart = database.create_cart().for_user(&user).for_event(&event).finish();
+    let total = cart.calculate_total(conn).unwrap();
+    let stripe = MockStripe::start(vec![
+        (
+            200,
+            stripe_payment_intent(
+                "pi_3ds",
+                "requires_action",
+                total,
+                json!({"type": "redirect_to_url", "redirect_to_url": {"url": "https://hooks.stripe.com/3ds"}}),
+            ),
+        ),
+        (200, stripe_payment_intent("pi_3ds", "succeeded", total, json!(null))),
+    ]);
+    let request = stripe_payment_intent_request(&stripe);
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let response = stripe_card_checkout(&database, &request, auth_user, "tok_visa").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let order = Order::find(cart.id, conn).unwrap();
+    assert_ne!(order.status, OrderStatus::Paid);
+    assert_eq!(order.checkout_url, Some("https://hooks.stripe.com/3ds".to_string()));
+    let payment = order.payments(conn).unwrap().remove(0);
+    assert_eq!(payment.status, PaymentStatus::Requested);
+    assert_eq!(payment.external_reference, Some("pi_3ds".to_string()));
+    let requests = stripe.requests();
+    assert!(requests[0].starts_with("POST /v1/payment_intents "));
+    assert!(requests[0].contains("payment_method_data%5Bcard%5D%5Btoken%5D=tok_visa"));
+    assert!(requests[0].contains("return_url="));
+
+    // The customer returns from authenticating with their bank
+    let response = stripe_payment_intent_callback(&database, &request, order.id, "pi_3ds").await;
+    assert_eq!(response.status(), StatusCode::FOUND);
+    assert!(response
+        .headers()
+        .get("Location")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .contains("/tickets/success"));
+    assert_eq!(stripe.requests()[1], "GET /v1/payment_intents/pi_3ds ");
+    let order = Order::find(cart.id, conn).unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+    assert_eq!(order.payments(conn).unwrap()[0].status, PaymentStatus::Completed);
+}
+
+#[actix_rt::test]
+async fn stripe_payment_intent_checkout_succeeded() {
+    let database = TestDatabase::new();
+    let conn = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = database.create_user().finish();
+    database
+        .create_payment_method()
+        .with_user(&user)
+        .make_default()
+        .finish();
+    let cart = database.create_cart().for_user(&user).for_event(&event).finish();
+    let total = cart.calculate_total(conn).unwrap();
+    let stripe = MockStripe::start(vec![
+        (
+            200,
+            json!({"id": "cus_example", "default_source": "card_1", "invoice_settings": {"default_payment_method": null}}),
+        ),
+        (200, stripe_payment_intent("pi_paid", "succeeded", total, json!(null))),
+    ]);
+    let request = stripe_payment_intent_request(&stripe);
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    // Stored payment methods are charged off session with the customer's default card
+    let response = cart::checkout((
+        database.connection.clone().into(),
+        Json(cart::CheckoutCartRequest {
+            tracking_data: None,
+            gift_card_codes: vec![],
+            use_store_credit: false,
+            installment_plan: false,
+            method: PaymentRequest::PaymentMethod {
+                provider: Some(PaymentProviders::Stripe),
+            },
+        }),
+        auth_user,
+        request.extract_state().await,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let requests = stripe.requests();
+    assert_eq!(requests[0], "GET /v1/customers/cus_example ");
+    assert!(requests[1].starts_with("POST /v1/payment_intents "));
+    assert!(requests[1].contains("customer=cus_example"));
+    assert!(requests[1].contains("payment_method=card_1"));
+    assert!(requests[1].contains("off_session=true"));
+    assert!(!requests[1].contains("return_url="));
+
+    let order = Order::find(cart.id, conn).unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+    let payment = order.payments(conn).unwrap().remove(0);
+    assert_eq!(payment.status, PaymentStatus::Completed);
+    assert_eq!(payment.provider, PaymentProviders::Stripe);
+    assert_eq!(payment.external_reference, Some("pi_paid".to_string()));
+}
+
+#[actix_rt::test]
+async fn stripe_payment_intent_checkout_failed() {
+    let database = TestDatabase::new();
+    let conn = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = database.create_user().finish();
+    let cart = database.create_cart().for_user(&user).for_event(&event).finish();
+    let total = cart.calculate_total(conn).unwrap();
+    let stripe = MockStripe::start(vec![
+        (
+            402,
+            json!({"error": {"type": "card_error", "code": "card_declined", "message": "Your card was declined."}}),
+        ),
+        (
+            200,
+            stripe_payment_intent(
+                "pi_3ds",
+                "requires_action",
+                total,
+                json!({"type": "redirect_to_url", "redirect_to_url": {"url": "https://hooks.stripe.com/3ds"}}),
+            ),
+        ),
+        (
+            200,
+            stripe_payment_intent("pi_3ds", "requires_payment_method", total, json!(null)),
+        ),
+    ]);
+    let request = stripe_payment_intent_request(&stripe);
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    // Declined when confirming
+    let response = stripe_card_checkout(&database, &request, auth_user.clone(), "tok_chargeDeclined").await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let order = Order::find(cart.id, conn).unwrap();
+    assert_eq!(order.status, OrderStatus::Draft);
+    assert!(order.payments(conn).unwrap().is_empty());
+
+    // Authentication fails after the redirect
+    let response = stripe_card_checkout(&database, &request, auth_user, "tok_threeDSecure2Required").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = stripe_payment_intent_callback(&database, &request, cart.id, "pi_3ds").await;
+    assert_eq!(response.status(), StatusCode::FOUND);
+    assert!(response
+        .headers()
+        .get("Location")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .contains("/tickets/confirmation"));
+    let order = Order::find(cart.id, conn).unwrap();
+    assert_ne!(order.status, OrderStatus::Paid);
+    assert_eq!(order.payments(conn).unwrap()[0].status, PaymentStatus::Cancelled);
+}
+
+fn stripe_payment_intent(id: &str, status: &str, amount: i64, next_action: serde_json::Value) -> serde_json::Value {
+    json!({
+        "id": id,
+        "object": "payment_intent",
+        "status": status,
+        "amount": amount,
+        "client_secret": format!("{}_secret", id),
+        "next_action": next_action
+    })
+}
+
+fn stripe_payment_intent_request(stripe: &MockStripe) -> TestRequest {
+    let mut config = TestRequest::config();
+    config.stripe_base_url = stripe.base_url.clone();
+    config.stripe_use_payment_intents = true;
+    TestRequest::create_with_config(config)
+}
+
+async fn stripe_card_checkout(
+    database: &TestDatabase,
+    request: &TestRequest,
+    user: api::auth::user::User,
+    token: &str,
+) -> HttpResponse {
+    cart::checkout((
+        database.connection.clone().into(),
+        Json(cart::CheckoutCartRequest {
+            tracking_data: None,
+            gift_card_codes: vec![],
+            use_store_credit: false,
+            installment_plan: false,
+            method: PaymentRequest::Card {
+                token: token.to_string(),
+                provider: PaymentProviders::Stripe,
+                save_payment_method: false,
+                set_default: false,
+            },
+        }),
+        user,
+        request.extract_state().await,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .into()
+}
+
+async fn stripe_payment_intent_callback(
+    database: &TestDatabase,
+    request: &TestRequest,
+    order_id: Uuid,
+    payment_intent: &str,
+) -> HttpResponse {
+    controllers::payments::payment_intent_callback((
+        Query(controllers::payments::PaymentIntentQueryParams {
+            payment_intent: payment_intent.to_string(),
+        }),
+        Path::from(controllers::payments::PaymentIntentPathParams { id: order_id }),
+        database.connection.clone().into(),
+        request.extract_state().await,
+        OptionalUser(None),
+    ))
+    .await
+    .unwrap()
+}
+
+/// Posts the webhook and runs the IPN action it creates
+async fn process_stripe_webhook(database: &TestDatabase, request: &TestRequest, event: serde_json::Value) {
+    let conn = database.connection.get();
+    let response = post_stripe_webhook(database, request, event).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let mut domain_actions = DomainAction::find_pending(Some(DomainActionTypes::PaymentProviderIPN), conn).unwrap();
+    assert_eq!(domain_actions.len(), 1);
+    let domain_action = domain_actions.remove(0);
+    let domain_action_id = domain_action.id;
+    ProcessPaymentIPNExecutor::new(&request.config)
+        .perform_job(domain_action, database.connection.clone())
+        .await
+        .unwrap();
+    DomainAction::find(domain_action_id, conn)
+        .unwrap()
+        .set_done(conn)
+        .unwrap();
+}
+
+async fn post_stripe_webhook(database: &TestDatabase, request: &TestRequest, event: serde_json::Value) -> HttpResponse {
+    let body = serde_json::to_vec(&event).unwrap();
+    let signature = stripe::webhook_signature_header(
+        &body,
+        request.config.stripe_webhook_secret.as_ref().unwrap(),
+        Utc::now().timestamp(),
+    );
+    let http_request = actix_web::test::TestRequest::default()
+        .header("Stripe-Signature", signature)
+        .to_http_request();
+    controllers::ipns::stripe((
+        http_request,
+        Bytes::from(body),
+        database.connection.clone().into(),
+        request.extract_state().await,
+    ))
+    .await
+    .unwrap()
+}
@@ -0,0 +1,88 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::event_time_slots::{self, CreateEventTimeSlotsRequest};
+use api::extractors::*;
+use api::models::PathParameters;
+use chrono::prelude::*;
+use db::models::*;
+use serde_json;
+use serde_json::Value;
+
+#[actix_rt::test]
+async fn create() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database.create_event().with_organization(&organization).finish();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = event.id;
+    let json = Json(CreateEventTimeSlotsRequest {
+        starts_at: NaiveDate::from_ymd(2055, 7, 8).and_hms(10, 0, 0),
+        ends_at: NaiveDate::from_ymd(2055, 7, 8).and_hms(12, 0, 0),
+        capacity: 25,
+        interval_minutes: Some(30),
+    });
+
+    let response: HttpResponse = event_time_slots::create((database.connection.clone(), path, json, auth_user))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let time_slots: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(time_slots.as_array().unwrap().len(), 4);
+    assert_eq!(EventTimeSlot::find_for_event(event.id, connection).unwrap().len(), 4);
+}
+
+#[actix_rt::test]
+async fn create_without_access() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database.create_event().with_organization(&organization).finish();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = event.id;
+    let json = Json(CreateEventTimeSlotsRequest {
+        starts_at: NaiveDate::from_ymd(2055, 7, 8).and_hms(10, 0, 0),
+        ends_at: NaiveDate::from_ymd(2055, 7, 8).and_hms(10, 30, 0),
+        capacity: 25,
+        interval_minutes: None,
+    });
+
+    let response: HttpResponse = event_time_slots::create((database.connection.clone(), path, json, auth_user))
+        .await
+        .into();
+    support::expects_unauthorized(&response);
+}
+
+#[actix_rt::test]
+async fn index() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database.create_event().finish();
+    let starts_at = NaiveDate::from_ymd(2055, 7, 8).and_hms(10, 0, 0);
+    EventTimeSlot::create(event.id, starts_at, starts_at + chrono::Duration::minutes(30), 25)
+        .commit(None, connection)
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = event.id;
+
+    let response: HttpResponse = event_time_slots::index((database.connection.clone(), path))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let time_slots: Vec<EventTimeSlotAvailability> = serde_json::from_str(&body).unwrap();
+    assert_eq!(time_slots.len(), 1);
+    assert_eq!(time_slots[0].available, 25);
+}
@@ -7,15 +7,21 @@ use actix_web::{
     web::{Path, Query},
     FromRequest, HttpResponse,
 };
+use api::auth::user::User as AuthUser;
 use api::controllers::events;
 use api::controllers::events::*;
+use api::database::CacheDatabase;
 use api::extractors::*;
 use api::models::*;
 use chrono::prelude::*;
 use chrono::Duration;
 use db::models::*;
 use db::utils::dates;
+use db::utils::signing;
+use db::utils::totp;
+use diesel::sql_types;
 use diesel::PgConnection;
+use diesel::RunQueryDsl;
 use serde_json;
 use serde_json::Value;
 use std::collections::HashMap;
@@ -2179,6 +2185,586 @@ mod redeem_ticket {
     }
 }
 
+#[actix_rt::test]
+async fn redeem_ticket_with_rotating_redeem_code() {
+    let database = TestDatabase::new();
+    let conn = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .with_rotating_redeem_codes()
+        .finish();
+    let user = database.create_user().finish();
+    let ticket_type = event.ticket_types(true, None, conn).unwrap().remove(0);
+    ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                max_entries: Some(2),
+                ..Default::default()
+            },
+            None,
+            conn,
+        )
+        .unwrap();
+    let ticket = database.create_purchased_tickets(&user, ticket_type.id, 1).remove(0);
+    let door_person = database.create_user().finish();
+    let auth_user =
+        support::create_auth_user_from_user(&door_person, Roles::DoorPerson, Some(&organization), &database);
+    let secret = ticket.redeem_secret.clone().unwrap();
+
+    // A screenshot of the static key or an old code is rejected
+    let response = scan_ticket(&database, &auth_user, &event, &ticket, None, None).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let expired_code = totp::code_at(&secret, dates::now().add_minutes(-5).finish()).unwrap();
+    let response = scan_ticket(&database, &auth_user, &event, &ticket, Some(expired_code), None).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        TicketInstance::find(ticket.id, conn).unwrap().status,
+        TicketInstanceStatus::Purchased
+    );
+
+    let current_code = totp::code_at(&secret, Utc::now().naive_utc()).unwrap();
+    let response = scan_ticket(&database, &auth_user, &event, &ticket, Some(current_code), None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        TicketInstance::find(ticket.id, conn).unwrap().status,
+        TicketInstanceStatus::Redeemed
+    );
+
+    // Coming back in after leaving needs the current code too
+    let response = scan_ticket(
+        &database,
+        &auth_user,
+        &event,
+        &ticket,
+        None,
+        Some(TicketScanDirection::Out),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = scan_ticket(&database, &auth_user, &event, &ticket, None, None).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(TicketScan::current_occupancy(event.id, conn).unwrap(), 0);
+    let current_code = totp::code_at(&secret, Utc::now().naive_utc()).unwrap();
+    let response = scan_ticket(&database, &auth_user, &event, &ticket, Some(current_code), None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(TicketScan::current_occupancy(event.id, conn).unwrap(), 1);
+}
+
+#[actix_rt::test]
+async fn redeem_ticket_reentry() {
+    let database = TestDatabase::new();
+    let conn = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, conn).unwrap().remove(0);
+    let ticket_type = ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                max_entries: Some(2),
+                ..Default::default()
+            },
+            None,
+            conn,
+        )
+        .unwrap();
+    let user = database.create_user().finish();
+    let ticket = database.create_purchased_tickets(&user, ticket_type.id, 1).remove(0);
+    let door_person = database.create_user().finish();
+    let auth_user =
+        support::create_auth_user_from_user(&door_person, Roles::DoorPerson, Some(&organization), &database);
+    let scan = |direction| scan_ticket(&database, &auth_user, &event, &ticket, None, Some(direction));
+
+    let response = scan(TicketScanDirection::Out).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = scan(TicketScanDirection::In).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = scan(TicketScanDirection::In).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(TicketScan::current_occupancy(event.id, conn).unwrap(), 1);
+
+    let response = scan(TicketScanDirection::Out).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(TicketScan::current_occupancy(event.id, conn).unwrap(), 0);
+    let response = scan(TicketScanDirection::In).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(TicketScan::current_occupancy(event.id, conn).unwrap(), 1);
+
+    let response = scan(TicketScanDirection::Out).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = scan(TicketScanDirection::In).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert!(body.contains("maximum number of entries"));
+}
+
+#[actix_rt::test]
+async fn redeem_ticket_in_access_zone() {
+    let database = TestDatabase::new();
+    let conn = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .with_ticket_type_count(2)
+        .finish();
+    let mut ticket_types = event.ticket_types(true, None, conn).unwrap();
+    let vip_ticket_type = ticket_types.remove(0);
+    let ga_ticket_type = ticket_types.remove(0);
+    let lounge = AccessZone::create(event.id, "VIP Lounge".to_string())
+        .commit(None, conn)
+        .unwrap();
+    lounge.set_ticket_types(&[vip_ticket_type.id], None, conn).unwrap();
+    let user = database.create_user().finish();
+    let vip_ticket = database
+        .create_purchased_tickets(&user, vip_ticket_type.id, 1)
+        .remove(0);
+    let ga_ticket = database.create_purchased_tickets(&user, ga_ticket_type.id, 1).remove(0);
+    let gate_scanner = database.create_user().finish();
+    let gate_auth_user =
+        support::create_auth_user_from_user(&gate_scanner, Roles::DoorPerson, Some(&organization), &database);
+    let lounge_scanner = database.create_user().finish();
+    let lounge_auth_user =
+        support::create_auth_user_from_user(&lounge_scanner, Roles::DoorPerson, Some(&organization), &database);
+    lounge.assign_user(&lounge_scanner, None, conn).unwrap();
+
+    let response = scan_ticket(&database, &lounge_auth_user, &event, &vip_ticket, None, None).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert!(body.contains("not checked in"));
+
+    for ticket in &[&vip_ticket, &ga_ticket] {
+        let response = scan_ticket(&database, &gate_auth_user, &event, ticket, None, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let response = scan_ticket(&database, &lounge_auth_user, &event, &ga_ticket, None, None).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert!(body.contains("does not grant access to VIP Lounge"));
+
+    let response = scan_ticket(&database, &lounge_auth_user, &event, &vip_ticket, None, None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = scan_ticket(&database, &lounge_auth_user, &event, &vip_ticket, None, None).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(TicketScan::zone_occupancy(&lounge, conn).unwrap(), 1);
+    assert_eq!(TicketScan::current_occupancy(event.id, conn).unwrap(), 2);
+
+    let response = scan_ticket(
+        &database,
+        &lounge_auth_user,
+        &event,
+        &vip_ticket,
+        None,
+        Some(TicketScanDirection::Out),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(TicketScan::zone_occupancy(&lounge, conn).unwrap(), 0);
+    assert_eq!(TicketScan::current_occupancy(event.id, conn).unwrap(), 2);
+}
+
+async fn scan_ticket(
+    database: &TestDatabase,
+    auth_user: &AuthUser,
+    event: &Event,
+    ticket: &TicketInstance,
+    redeem_code: Option<String>,
+    direction: Option<TicketScanDirection>,
+) -> HttpResponse {
+    let request = TestRequest::create_with_uri_custom_params("/", vec!["id", "ticket_instance_id"]);
+    let mut path = Path::<PathParameters>::extract(&request.request).await.unwrap();
+    path.id = event.id;
+    let request_data = TicketRedeemRequest {
+        redeem_key: ticket.redeem_key.clone().unwrap(),
+        check_in_source: Some(CheckInSource::Scanned),
+        ignore_time_slot: false,
+        redeem_code,
+        direction,
+    };
+    events::redeem_ticket((
+        database.connection.clone().into(),
+        path,
+        Json(request_data),
+        auth_user.clone(),
+        request.extract_state().await,
+        CacheDatabase { inner: None },
+    ))
+    .await
+    .into()
+}
+
+#[actix_rt::test]
+async fn offline_manifest() {
+    let database = TestDatabase::new();
+    let conn = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let user = database.create_user().finish();
+    let ticket_type_id = event.ticket_types(true, None, conn).unwrap()[0].id;
+    let tickets = database.create_purchased_tickets(&user, ticket_type_id, 2);
+    let door_person = database.create_user().finish();
+    let auth_user =
+        support::create_auth_user_from_user(&door_person, Roles::DoorPerson, Some(&organization), &database);
+
+    // Unavailable until a signing key is configured
+    let response = fetch_offline_manifest(&database, &auth_user, &event, None, None).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let private_key = signing::generate_private_key().unwrap();
+    let response = fetch_offline_manifest(&database, &auth_user, &event, None, Some(&private_key)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let signature = response
+        .headers()
+        .get(OFFLINE_MANIFEST_SIGNATURE_HEADER)
+        .unwrap()
+        .to_str()
+        .unwrap();
+    // Devices only need the public key to check the signature
+    let public_key = signing::public_key(&private_key).unwrap();
+    assert!(signing::verify(body, signature, &public_key));
+    assert!(!signing::verify(
+        &body.replace("Purchased", "Redeemed"),
+        signature,
+        &public_key
+    ));
+    let manifest: OfflineScanningManifest = serde_json::from_str(body).unwrap();
+    assert_eq!(manifest.event_id, event.id);
+    assert_eq!(manifest.redeem_key_hash_iterations, OFFLINE_REDEEM_KEY_HASH_ITERATIONS);
+    assert_eq!(manifest.tickets.len(), 2);
+    for ticket in &tickets {
+        let offline_ticket = manifest.tickets.iter().find(|t| t.id == ticket.id).unwrap();
+        assert_eq!(
+            offline_ticket.redeem_key_hash,
+            TicketInstance::offline_redeem_key_hash(event.id, &ticket.redeem_key.clone().unwrap())
+        );
+        assert_eq!(offline_ticket.status, TicketInstanceStatus::Purchased);
+    }
+
+    // Incremental manifests only include tickets that changed since the last one
+    diesel::sql_query("UPDATE ticket_instances SET updated_at = $1 WHERE id = ANY($2);")
+        .bind::<sql_types::Timestamp, _>(dates::now().add_days(-1).finish())
+        .bind::<sql_types::Array<sql_types::Uuid>, _>(tickets.iter().map(|t| t.id).collect::<Vec<Uuid>>())
+        .execute(conn)
+        .unwrap();
+    TicketInstance::redeem_ticket(
+        tickets[0].id,
+        tickets[0].redeem_key.clone().unwrap(),
+        door_person.id,
+        CheckInSource::Scanned,
+        conn,
+    )
+    .unwrap();
+    let since = dates::now().add_hours(-1).finish();
+    let response = fetch_offline_manifest(&database, &auth_user, &event, Some(since), Some(&private_key)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let manifest: OfflineScanningManifest =
+        serde_json::from_str(support::unwrap_body_to_string(&response).unwrap()).unwrap();
+    assert_eq!(manifest.since, Some(since.with_nanosecond(0).unwrap()));
+    assert_eq!(manifest.tickets.len(), 1);
+    assert_eq!(manifest.tickets[0].id, tickets[0].id);
+    assert_eq!(manifest.tickets[0].status, TicketInstanceStatus::Redeemed);
+}
+
+async fn fetch_offline_manifest(
+    database: &TestDatabase,
+    auth_user: &AuthUser,
+    event: &Event,
+    since: Option<NaiveDateTime>,
+    private_key: Option<&str>,
+) -> HttpResponse {
+    let mut config = TestRequest::config();
+    config.offline_scanning_private_key = private_key.map(|k| k.to_string());
+    let uri = match since {
+        Some(since) => format!("/?since={}", since.format("%Y-%m-%dT%H:%M:%S")),
+        None => "/".to_string(),
+    };
+    let request = TestRequest::create_with_uri_and_config(&uri, vec!["id"], config);
+    let mut path = Path::<PathParameters>::extract(&request.request).await.unwrap();
+    path.id = event.id;
+    let query = Query::<OfflineManifestParameters>::extract(&request.request)
+        .await
+        .unwrap();
+    events::offline_manifest((
+        database.connection.clone().into(),
+        path,
+        query,
+        auth_user.clone(),
+        request.extract_state().await,
+    ))
+    .await
+    .into()
+}
+
+#[actix_rt::test]
+async fn sync_offline_redemptions() {
+    let database = TestDatabase::new();
+    let conn = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_event_start(dates::now().add_hours(-1).finish())
+        .with_door_time(dates::now().add_hours(-2).finish())
+        .with_ticket_pricing()
+        .finish();
+    let user = database.create_user().finish();
+    let ticket_type_id = event.ticket_types(true, None, conn).unwrap()[0].id;
+    let tickets = database.create_purchased_tickets(&user, ticket_type_id, 3);
+    let door_person = database.create_user().finish();
+    let auth_user =
+        support::create_auth_user_from_user(&door_person, Roles::DoorPerson, Some(&organization), &database);
+    let first_scan = dates::now().add_minutes(-20).finish().with_nanosecond(0).unwrap();
+    let second_scan = dates::now().add_minutes(-10).finish().with_nanosecond(0).unwrap();
+
+    // The same ticket scanned on two devices, uploaded out of order
+    let redemptions = vec![
+        OfflineRedemption {
+            redeem_key: tickets[0].redeem_key.clone().unwrap(),
+            redeemed_at: second_scan,
+            check_in_source: Some(CheckInSource::Scanned),
+            direction: None,
+            redeem_code: None,
+        },
+        OfflineRedemption {
+            redeem_key: tickets[0].redeem_key.clone().unwrap(),
+            redeemed_at: first_scan,
+            check_in_source: Some(CheckInSource::Scanned),
+            direction: None,
+            redeem_code: None,
+        },
+        OfflineRedemption {
+            redeem_key: tickets[1].redeem_key.clone().unwrap(),
+            redeemed_at: second_scan,
+            check_in_source: Some(CheckInSource::GuestList),
+            direction: None,
+            redeem_code: None,
+        },
+        OfflineRedemption {
+            redeem_key: "WrongKey".to_string(),
+            redeemed_at: second_scan,
+            check_in_source: None,
+            direction: None,
+            redeem_code: None,
+        },
+    ];
+    let results = sync_redemptions(&database, &auth_user, &event, redemptions).await;
+    assert_eq!(
+        results
+            .iter()
+            .map(|r| r.status)
+            .collect::<Vec<OfflineRedemptionStatus>>(),
+        vec![
+            OfflineRedemptionStatus::Redeemed,
+            OfflineRedemptionStatus::AlreadyRedeemed,
+            OfflineRedemptionStatus::Redeemed,
+            OfflineRedemptionStatus::NotFound,
+        ]
+    );
+    assert_eq!(results[1].ticket_instance_id, Some(tickets[0].id));
+    assert_eq!(results[1].redeemed_at, Some(first_scan));
+    assert_eq!(results[1].redeemed_by, Some(door_person.id));
+
+    let ticket = TicketInstance::find(tickets[0].id, conn).unwrap();
+    assert_eq!(ticket.status, TicketInstanceStatus::Redeemed);
+    assert_eq!(ticket.redeemed_at, Some(first_scan));
+    assert_eq!(ticket.check_in_source, Some(CheckInSource::Scanned));
+    let ticket = TicketInstance::find(tickets[1].id, conn).unwrap();
+    assert_eq!(ticket.check_in_source, Some(CheckInSource::GuestList));
+
+    // Scans synced later by another device lose to the redemption already recorded
+    let redemptions = vec![OfflineRedemption {
+        redeem_key: tickets[0].redeem_key.clone().unwrap(),
+        redeemed_at: dates::now().add_minutes(-30).finish(),
+        check_in_source: Some(CheckInSource::Scanned),
+        direction: None,
+        redeem_code: None,
+    }];
+    let results = sync_redemptions(&database, &auth_user, &event, redemptions).await;
+    assert_eq!(results[0].status, OfflineRedemptionStatus::AlreadyRedeemed);
+    assert_eq!(results[0].redeemed_at, Some(first_scan));
+
+    // Device clocks can't place scans before doors or after the sync
+    let redemptions = vec![OfflineRedemption {
+        redeem_key: tickets[2].redeem_key.clone().unwrap(),
+        redeemed_at: dates::now().add_days(1).finish(),
+        check_in_source: Some(CheckInSource::Scanned),
+        direction: None,
+        redeem_code: None,
+    }];
+    let results = sync_redemptions(&database, &auth_user, &event, redemptions).await;
+    assert_eq!(results[0].status, OfflineRedemptionStatus::Redeemed);
+    assert!(results[0].redeemed_at.unwrap() <= Utc::now().naive_utc());
+    assert!(results[0].redeemed_at.unwrap() > dates::now().add_minutes(-1).finish());
+    assert_eq!(
+        event.clamp_scan_time(dates::now().add_hours(-5).finish()),
+        event.door_time.unwrap()
+    );
+}
+
+#[actix_rt::test]
+async fn sync_offline_redemptions_with_rotating_redeem_codes() {
+    let database = TestDatabase::new();
+    let conn = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_event_start(dates::now().add_hours(-1).finish())
+        .with_rotating_redeem_codes()
+        .with_ticket_pricing()
+        .finish();
+    let user = database.create_user().finish();
+    let ticket_type_id = event.ticket_types(true, None, conn).unwrap()[0].id;
+    let tickets = database.create_purchased_tickets(&user, ticket_type_id, 2);
+    let door_person = database.create_user().finish();
+    let auth_user =
+        support::create_auth_user_from_user(&door_person, Roles::DoorPerson, Some(&organization), &database);
+    let scanned_at = dates::now().add_minutes(-10).finish();
+
+    // Codes are checked against the time of the scan, not the sync
+    let redemptions = tickets
+        .iter()
+        .map(|ticket| OfflineRedemption {
+            redeem_key: ticket.redeem_key.clone().unwrap(),
+            redeemed_at: scanned_at,
+            check_in_source: Some(CheckInSource::Scanned),
+            direction: None,
+            redeem_code: Some(totp::code_at(ticket.redeem_secret.as_ref().unwrap(), scanned_at).unwrap()),
+        })
+        .chain(vec![OfflineRedemption {
+            redeem_key: tickets[1].redeem_key.clone().unwrap(),
+            redeemed_at: dates::now().add_minutes(-11).finish(),
+            check_in_source: Some(CheckInSource::Scanned),
+            direction: None,
+            redeem_code: Some(
+                totp::code_at(
+                    tickets[1].redeem_secret.as_ref().unwrap(),
+                    dates::now().add_minutes(-30).finish(),
+                )
+                .unwrap(),
+            ),
+        }])
+        .collect();
+    let results = sync_redemptions(&database, &auth_user, &event, redemptions).await;
+    assert_eq!(
+        results
+            .iter()
+            .map(|r| r.status)
+            .collect::<Vec<OfflineRedemptionStatus>>(),
+        vec![
+            OfflineRedemptionStatus::RedeemCodeExpired,
+            OfflineRedemptionStatus::Redeemed,
+            OfflineRedemptionStatus::Redeemed,
+        ]
+    );
+    assert_eq!(results[0].ticket_instance_id, Some(tickets[1].id));
+    assert_eq!(results[0].redeemed_at, None);
+}
+
+#[actix_rt::test]
+async fn sync_offline_redemptions_with_unassigned_asset() {
+    let database = TestDatabase::new();
+    let conn = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_event_start(dates::now().add_hours(-1).finish())
+        .with_ticket_type_count(2)
+        .with_ticket_pricing()
+        .finish();
+    let user = database.create_user().finish();
+    let ticket_types = event.ticket_types(true, None, conn).unwrap();
+    let unassigned_ticket = database
+        .create_purchased_tickets(&user, ticket_types[0].id, 1)
+        .remove(0);
+    let ticket = database
+        .create_purchased_tickets(&user, ticket_types[1].id, 1)
+        .remove(0);
+    diesel::sql_query("UPDATE assets SET blockchain_asset_id = NULL WHERE id = $1;")
+        .bind::<sql_types::Uuid, _>(unassigned_ticket.asset_id)
+        .execute(conn)
+        .unwrap();
+    let door_person = database.create_user().finish();
+    let auth_user =
+        support::create_auth_user_from_user(&door_person, Roles::DoorPerson, Some(&organization), &database);
+    let scanned_at = dates::now().add_minutes(-10).finish().with_nanosecond(0).unwrap();
+
+    // Scans that cannot be completed are reported without recording them or failing the others
+    let redemptions = vec![
+        OfflineRedemption {
+            redeem_key: unassigned_ticket.redeem_key.clone().unwrap(),
+            redeemed_at: scanned_at,
+            check_in_source: Some(CheckInSource::Scanned),
+            direction: None,
+            redeem_code: None,
+        },
+        OfflineRedemption {
+            redeem_key: ticket.redeem_key.clone().unwrap(),
+            redeemed_at: scanned_at,
+            check_in_source: Some(CheckInSource::Scanned),
+            direction: None,
+            redeem_code: None,
+        },
+    ];
+    let results = sync_redemptions(&database, &auth_user, &event, redemptions).await;
+    assert_eq!(
+        results
+            .iter()
+            .map(|r| r.status)
+            .collect::<Vec<OfflineRedemptionStatus>>(),
+        vec![
+            OfflineRedemptionStatus::AssetNotAssigned,
+            OfflineRedemptionStatus::Redeemed,
+        ]
+    );
+    assert_eq!(results[0].ticket_instance_id, Some(unassigned_ticket.id));
+    assert_eq!(results[0].redeemed_at, None);
+
+    let unassigned_ticket = TicketInstance::find(unassigned_ticket.id, conn).unwrap();
+    assert_eq!(unassigned_ticket.status, TicketInstanceStatus::Purchased);
+    assert_eq!(unassigned_ticket.redeemed_at, None);
+    let ticket = TicketInstance::find(ticket.id, conn).unwrap();
+    assert_eq!(ticket.status, TicketInstanceStatus::Redeemed);
+    assert_eq!(ticket.redeemed_at, Some(scanned_at));
+}
+
+async fn sync_redemptions(
+    database: &TestDatabase,
+    auth_user: &AuthUser,
+    event: &Event,
+    redemptions: Vec<OfflineRedemption>,
+) -> Vec<OfflineRedemptionResult> {
+    let request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&request.request).await.unwrap();
+    path.id = event.id;
+    let response: HttpResponse = events::sync_offline_redemptions((
+        database.connection.clone().into(),
+        path,
+        Json(OfflineRedemptionSyncRequest { redemptions }),
+        auth_user.clone(),
+        request.extract_state().await,
+        CacheDatabase { inner: None },
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    serde_json::from_str(support::unwrap_body_to_string(&response).unwrap()).unwrap()
+}
+
 #[actix_rt::test]
 pub async fn delete_fails_has_ticket_in_cart() {
     let database = TestDatabase::new();
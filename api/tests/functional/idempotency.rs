@@ -0,0 +1,136 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{dev, http::StatusCode, test, HttpMessage, HttpRequest, HttpResponse};
+use api::errors::*;
+use api::middleware::{Idempotency, IdempotentRequest};
+use chrono::Duration;
+use db::models::*;
+
+async fn idempotent_request(database: &TestDatabase, user: &User, key: &str) -> HttpRequest {
+    let test_request = TestRequest::create();
+    let token = test_request
+        .config
+        .token_issuer
+        .issue(user.id, Duration::minutes(30))
+        .unwrap();
+    let request = test::TestRequest::post()
+        .uri("/cart/checkout")
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Idempotency-Key", key)
+        .app_data(test_request.extract_state().await)
+        .to_http_request();
+    request.extensions_mut().insert(database.connection.clone());
+    request
+}
+
+fn claim(database: &TestDatabase, request: &HttpRequest, body: &str) -> IdempotencyKey {
+    match IdempotentRequest::start(request, body.as_bytes(), database.connection.clone()).unwrap() {
+        Idempotency::Claimed(idempotency_key, _) => idempotency_key,
+        _ => panic!("Expected the idempotency key to be claimed"),
+    }
+}
+
+fn respond(database: &TestDatabase, request: &HttpRequest, body: &str) -> HttpResponse {
+    match IdempotentRequest::start(request, body.as_bytes(), database.connection.clone()).unwrap() {
+        Idempotency::Respond(response) => response,
+        _ => panic!("Expected the request to be answered from the idempotency key"),
+    }
+}
+
+#[actix_rt::test]
+async fn replays_stored_responses() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let body = r#"{"method":{"type":"Card"}}"#;
+
+    // Successful response
+    let request = idempotent_request(&database, &user, "key-1").await;
+    let idempotency_key = claim(&database, &request, body);
+    let response = dev::ServiceResponse::new(request.clone(), HttpResponse::Ok().json(json!({"status": "Paid"})));
+    IdempotentRequest::finish(idempotency_key, database.connection.clone(), &response);
+
+    let replayed = respond(&database, &request, body);
+    assert_eq!(replayed.status(), StatusCode::OK);
+    assert_eq!(replayed.headers().get("Idempotent-Replayed").unwrap(), "true");
+    assert_eq!(
+        support::unwrap_body_to_string(&replayed).unwrap(),
+        json!({"status": "Paid"}).to_string()
+    );
+
+    // Committed unsuccessful response, e.g. a declined card
+    let request = idempotent_request(&database, &user, "key-2").await;
+    let idempotency_key = claim(&database, &request, body);
+    let response = dev::ServiceResponse::new(
+        request.clone(),
+        HttpResponse::UnprocessableEntity().json(json!({"error": "Your card was declined"})),
+    );
+    IdempotentRequest::finish(idempotency_key, database.connection.clone(), &response);
+
+    let replayed = respond(&database, &request, body);
+    assert_eq!(replayed.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        support::unwrap_body_to_string(&replayed).unwrap(),
+        json!({"error": "Your card was declined"}).to_string()
+    );
+
+    // Errors are rolled back so the key is released for the retry
+    let request = idempotent_request(&database, &user, "key-3").await;
+    let idempotency_key = claim(&database, &request, body);
+    let error: ApiError = ApplicationError::new("Payment processor unavailable".to_string()).into();
+    let response = dev::ServiceResponse::from_err(error, request.clone());
+    IdempotentRequest::finish(idempotency_key.clone(), database.connection.clone(), &response);
+
+    let retried_key = claim(&database, &request, body);
+    assert_ne!(retried_key.id, idempotency_key.id);
+}
+
+#[actix_rt::test]
+async fn rejects_concurrent_duplicates() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let other_user = database.create_user().finish();
+    let body = r#"{"method":{"type":"Card"}}"#;
+    let request = idempotent_request(&database, &user, "key-1").await;
+    let idempotency_key = claim(&database, &request, body);
+
+    let duplicate = respond(&database, &request, body);
+    assert_eq!(duplicate.status(), StatusCode::CONFLICT);
+    let expected_json = HttpResponse::Conflict().json(json!({
+        "error": "A request with this Idempotency-Key is already in progress"
+    }));
+    assert_eq!(
+        support::unwrap_body_to_string(&duplicate).unwrap(),
+        support::unwrap_body_to_string(&expected_json).unwrap()
+    );
+
+    // Keys are scoped to the user
+    let other_request = idempotent_request(&database, &other_user, "key-1").await;
+    let other_key = claim(&database, &other_request, body);
+    assert_ne!(other_key.id, idempotency_key.id);
+
+    // Once the first request completes its response is replayed
+    let response = dev::ServiceResponse::new(request.clone(), HttpResponse::Ok().json(json!({"status": "Paid"})));
+    IdempotentRequest::finish(idempotency_key, database.connection.clone(), &response);
+    assert_eq!(respond(&database, &request, body).status(), StatusCode::OK);
+}
+
+#[actix_rt::test]
+async fn rejects_key_reused_for_different_request() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let request = idempotent_request(&database, &user, "key-1").await;
+    let idempotency_key = claim(&database, &request, r#"{"method":{"type":"Card"}}"#);
+    let response = dev::ServiceResponse::new(request.clone(), HttpResponse::Ok().json(json!({"status": "Paid"})));
+    IdempotentRequest::finish(idempotency_key, database.connection.clone(), &response);
+
+    let reused = respond(&database, &request, r#"{"method":{"type":"Free"}}"#);
+    assert_eq!(reused.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let expected_json = HttpResponse::UnprocessableEntity().json(json!({
+        "error": "Idempotency-Key has already been used for a different request"
+    }));
+    assert_eq!(
+        support::unwrap_body_to_string(&reused).unwrap(),
+        support::unwrap_body_to_string(&expected_json).unwrap()
+    );
+}
@@ -1,3 +1,4 @@
+mod access_zones;
 mod admin;
 mod announcements;
 mod artists;
@@ -10,17 +11,22 @@ mod collection_items;
 mod collections;
 mod comps;
 mod event_report_subscribers;
+mod event_time_slots;
 mod events;
 mod genres;
 mod holds;
+mod idempotency;
 mod notes;
 mod orders;
 mod organization_invites;
 mod organization_venues;
 mod organizations;
+mod passes;
 mod password_resets;
 mod payment_methods;
+mod products;
 mod redemption_codes;
+mod refund_requests;
 mod regions;
 mod reports;
 mod reports_admin;
@@ -34,4 +40,6 @@ mod tickets;
 mod transfers;
 mod user_invites;
 mod users;
+mod venue_sections;
 mod venues;
+mod waitlist_entries;
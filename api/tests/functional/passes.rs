@@ -0,0 +1,120 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::cart::{self, UpdateCartPassRequest};
+use api::controllers::passes::{self, CreatePassRequest};
+use api::extractors::*;
+use api::models::PathParameters;
+use db::models::*;
+use serde_json;
+use serde_json::Value;
+
+#[actix_rt::test]
+async fn create() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let json = Json(CreatePassRequest {
+        name: "Season pass".to_string(),
+        description: None,
+        price_in_cents: 500,
+    });
+
+    let response: HttpResponse = passes::create((database.connection.clone(), path, json, auth_user))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let pass: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(pass["name"], json!("Season pass"));
+    assert_eq!(pass["status"], json!("Draft"));
+    assert_eq!(
+        Pass::find_for_organization(organization.id, connection).unwrap().len(),
+        1
+    );
+}
+
+#[actix_rt::test]
+async fn create_without_access() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let json = Json(CreatePassRequest {
+        name: "Season pass".to_string(),
+        description: None,
+        price_in_cents: 500,
+    });
+
+    let response: HttpResponse = passes::create((database.connection.clone(), path, json, auth_user))
+        .await
+        .into();
+    support::expects_unauthorized(&response);
+}
+
+#[actix_rt::test]
+async fn show_draft() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let pass = Pass::create(organization.id, "Season pass".to_string(), None, 500)
+        .commit(None, connection)
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = pass.id;
+
+    let response: HttpResponse = passes::show((database.connection.clone(), path, OptionalUser(None)))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_rt::test]
+async fn add_pass_to_cart() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let pass = Pass::create(organization.id, "Season pass".to_string(), None, 500)
+        .commit(None, connection)
+        .unwrap();
+    for allocated_price_in_cents in vec![300, 200] {
+        let event = database
+            .create_event()
+            .with_organization(&organization)
+            .with_ticket_pricing()
+            .finish();
+        let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+        pass.add_event(ticket_type.id, allocated_price_in_cents, None, connection)
+            .unwrap();
+    }
+    let pass = pass.publish(None, connection).unwrap();
+    let user = database.create_user().finish();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let json = Json(UpdateCartPassRequest {
+        pass_id: pass.id,
+        quantity: 1,
+        event_time_slot_ids: vec![],
+    });
+    let response: HttpResponse = cart::update_pass((database.connection.clone(), json, auth_user))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let cart = Order::find_cart_for_user(user.id, connection).unwrap().unwrap();
+    assert_eq!(cart.tickets(None, connection).unwrap().len(), 2);
+    assert_eq!(cart.events(connection).unwrap().len(), 2);
+}
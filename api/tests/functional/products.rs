@@ -0,0 +1,209 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::cart::{self, CartItem, CartProductItem, UpdateCartRequest};
+use api::controllers::products::{
+    self, CreateProductRequest, CreateProductVariantRequest, RedeemProductVoucherRequest,
+};
+use api::extractors::*;
+use api::models::{OrderItemPathParameters, PathParameters};
+use db::models::*;
+use serde_json;
+use serde_json::Value;
+
+fn create_request() -> CreateProductRequest {
+    CreateProductRequest {
+        event_id: None,
+        name: "T-Shirt".to_string(),
+        description: None,
+        price_in_cents: 2500,
+        per_ticket_limit: Some(1),
+        gift_card: false,
+        variants: vec![
+            CreateProductVariantRequest {
+                name: "Small".to_string(),
+                price_in_cents: None,
+                stock_quantity: 10,
+            },
+            CreateProductVariantRequest {
+                name: "Large".to_string(),
+                price_in_cents: Some(3000),
+                stock_quantity: 5,
+            },
+        ],
+    }
+}
+
+#[actix_rt::test]
+async fn create() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+
+    let response: HttpResponse =
+        products::create((database.connection.clone(), path, Json(create_request()), auth_user))
+            .await
+            .into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let product: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(product["name"], json!("T-Shirt"));
+    assert_eq!(product["status"], json!("Draft"));
+    assert_eq!(product["variants"][0]["name"], json!("Small"));
+    assert_eq!(product["variants"][0]["unit_price_in_cents"], json!(2500));
+    assert_eq!(product["variants"][1]["unit_price_in_cents"], json!(3000));
+    assert_eq!(product["variants"][1]["available"], json!(5));
+    assert_eq!(
+        Product::find_for_organization(organization.id, connection)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[actix_rt::test]
+async fn create_without_access() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+
+    let response: HttpResponse =
+        products::create((database.connection.clone(), path, Json(create_request()), auth_user))
+            .await
+            .into();
+    support::expects_unauthorized(&response);
+}
+
+#[actix_rt::test]
+async fn add_products_to_cart_and_redeem() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let product = Product::create(
+        organization.id,
+        Some(event.id),
+        "Drink voucher".to_string(),
+        None,
+        500,
+        None,
+    )
+    .commit(None, connection)
+    .unwrap()
+    .update(
+        ProductEditableAttributes {
+            status: Some(ProductStatus::Published),
+            ..Default::default()
+        },
+        None,
+        connection,
+    )
+    .unwrap();
+    let variant = ProductVariant::create(product.id, "Beer".to_string(), None, 100)
+        .commit(None, connection)
+        .unwrap();
+    let user = database.create_user().finish();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let input = Json(UpdateCartRequest {
+        box_office_pricing: None,
+        items: vec![CartItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: None,
+            event_time_slot_id: None,
+        }],
+        products: vec![CartProductItem {
+            product_variant_id: variant.id,
+            quantity: 3,
+        }],
+        tracking_data: None,
+    });
+    let response = cart::update_cart((
+        database.connection.clone().into(),
+        input,
+        auth_user,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mut cart = Order::find_cart_for_user(user.id, connection).unwrap().unwrap();
+    let product_item = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Products)
+        .unwrap();
+    assert_eq!(product_item.quantity, 3);
+    assert_eq!(product_item.unit_price_in_cents, 500);
+
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("Test".to_string()),
+        ExternalPaymentType::CreditCard,
+        user.id,
+        total,
+        connection,
+    )
+    .unwrap();
+
+    // Fans cannot redeem their own vouchers
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id", "order_item_id"]);
+    let mut path = Path::<OrderItemPathParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    path.id = cart.id;
+    path.order_item_id = product_item.id;
+    let response: HttpResponse = products::redeem_voucher((
+        database.connection.clone(),
+        path,
+        Json(RedeemProductVoucherRequest { quantity: 1 }),
+        auth_user,
+    ))
+    .await
+    .into();
+    support::expects_unauthorized(&response);
+
+    let door_person = database.create_user().finish();
+    let auth_user =
+        support::create_auth_user_from_user(&door_person, Roles::DoorPerson, Some(&organization), &database);
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id", "order_item_id"]);
+    let mut path = Path::<OrderItemPathParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    path.id = cart.id;
+    path.order_item_id = product_item.id;
+    let response: HttpResponse = products::redeem_voucher((
+        database.connection.clone(),
+        path,
+        Json(RedeemProductVoucherRequest { quantity: 2 }),
+        auth_user,
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let redeemed: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(redeemed["redeemed_quantity"], json!(2));
+}
@@ -0,0 +1,258 @@
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use serde_json;
+use uuid::Uuid;
+
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use api::controllers::refund_requests::{self, *};
+use api::extractors::Json;
+use api::models::PathParameters;
+use db::models::*;
+
+#[actix_rt::test]
+pub async fn create_with_automatic_approval() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (event, user, order) = paid_order(&database);
+    RefundPolicy::create(event.id, RefundApprovalTypes::Automatic, 0, true)
+        .commit(None, connection)
+        .unwrap();
+    let ticket = &order.tickets(None, connection).unwrap()[0];
+    let order_item = OrderItem::find(ticket.order_item_id.unwrap(), connection).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let response = create_refund_request(&database, &order, auth_user, vec![ticket.id]).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let refund_request: RefundRequest = serde_json::from_str(&body).unwrap();
+    assert_eq!(refund_request.status, RefundRequestStatus::Approved);
+    assert_eq!(refund_request.reviewed_by, None);
+    assert!(refund_request.refund_id.is_some());
+
+    // The policy retains fees so only the ticket price is refunded
+    let refund_payments: Vec<Payment> = order
+        .payments(connection)
+        .unwrap()
+        .into_iter()
+        .filter(|p| p.refund_id == refund_request.refund_id)
+        .collect();
+    assert_eq!(refund_payments.len(), 1);
+    assert_eq!(refund_payments[0].amount, -order_item.unit_price_in_cents);
+    let refunded_ticket = RefundedTicket::find_or_create_by_ticket_instance(ticket, connection).unwrap();
+    assert!(refunded_ticket.ticket_refunded_at.is_some());
+    assert!(refunded_ticket.fee_refunded_at.is_none());
+}
+
+#[actix_rt::test]
+pub async fn create_with_manual_approval() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (event, user, order) = paid_order(&database);
+    RefundPolicy::create(event.id, RefundApprovalTypes::Manual, 0, false)
+        .commit(None, connection)
+        .unwrap();
+    let tickets = order.tickets(None, connection).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let organization = event.organization(connection).unwrap();
+    let reviewer = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let response = create_refund_request(&database, &order, auth_user.clone(), vec![tickets[0].id]).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let approved_request: RefundRequest = serde_json::from_str(&body).unwrap();
+    assert_eq!(approved_request.status, RefundRequestStatus::Pending);
+    assert_eq!(approved_request.refund_id, None);
+
+    let response = create_refund_request(&database, &order, auth_user, vec![tickets[1].id]).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let denied_request: RefundRequest = serde_json::from_str(&body).unwrap();
+    assert_eq!(denied_request.status, RefundRequestStatus::Pending);
+    assert!(order
+        .payments(connection)
+        .unwrap()
+        .iter()
+        .all(|p| p.refund_id.is_none()));
+
+    let response = approve_refund_request(&database, approved_request.id, reviewer.clone()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let approved_request: RefundRequest = serde_json::from_str(&body).unwrap();
+    assert_eq!(approved_request.status, RefundRequestStatus::Approved);
+    assert_eq!(approved_request.reviewed_by, Some(reviewer.id()));
+    assert!(approved_request.refund_id.is_some());
+
+    let response = deny_refund_request(&database, denied_request.id, reviewer.clone()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let denied_request: RefundRequest = serde_json::from_str(&body).unwrap();
+    assert_eq!(denied_request.status, RefundRequestStatus::Denied);
+    assert_eq!(denied_request.response_note, Some("Event is still on".to_string()));
+    assert_eq!(denied_request.refund_id, None);
+
+    // Only the approved ticket was refunded
+    let refund_payments: Vec<Payment> = order
+        .payments(connection)
+        .unwrap()
+        .into_iter()
+        .filter(|p| p.refund_id.is_some())
+        .collect();
+    assert_eq!(refund_payments.len(), 1);
+    assert_eq!(refund_payments[0].refund_id, approved_request.refund_id);
+    let ticket = TicketInstance::find(tickets[1].id, connection).unwrap();
+    assert_eq!(ticket.order_item_id, tickets[1].order_item_id);
+}
+
+#[actix_rt::test]
+pub async fn create_for_order_of_another_user() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (event, _, order) = paid_order(&database);
+    RefundPolicy::create(event.id, RefundApprovalTypes::Automatic, 0, true)
+        .commit(None, connection)
+        .unwrap();
+    let ticket = &order.tickets(None, connection).unwrap()[0];
+    let auth_user = support::create_auth_user(Roles::User, None, &database);
+
+    let response = create_refund_request(&database, &order, auth_user, vec![ticket.id]).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(RefundRequest::find_for_order(order.id, connection).unwrap().is_empty());
+}
+
+#[actix_rt::test]
+pub async fn approve_reviewed_request() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (event, user, order) = paid_order(&database);
+    RefundPolicy::create(event.id, RefundApprovalTypes::Manual, 0, true)
+        .commit(None, connection)
+        .unwrap();
+    let tickets = order.tickets(None, connection).unwrap();
+    let organization = event.organization(connection).unwrap();
+    let reviewer = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let denied_request = RefundRequest::create(order.id, user.id, vec![tickets[0].id], "Can't attend".to_string())
+        .commit(connection)
+        .unwrap()
+        .deny(None, reviewer.id(), connection)
+        .unwrap();
+    let approved_request = RefundRequest::create(order.id, user.id, vec![tickets[1].id], "Can't attend".to_string())
+        .commit(connection)
+        .unwrap();
+    let response = approve_refund_request(&database, approved_request.id, reviewer.clone()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // No further refund is issued for requests that have already been reviewed
+    for refund_request_id in vec![denied_request.id, approved_request.id] {
+        let response = approve_refund_request(&database, refund_request_id, reviewer.clone()).await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+    let refund_payments: Vec<Payment> = order
+        .payments(connection)
+        .unwrap()
+        .into_iter()
+        .filter(|p| p.refund_id.is_some())
+        .collect();
+    assert_eq!(refund_payments.len(), 1);
+    let denied_request = RefundRequest::find(denied_request.id, connection).unwrap();
+    assert_eq!(denied_request.status, RefundRequestStatus::Denied);
+}
+
+fn paid_order(database: &TestDatabase) -> (Event, User, Order) {
+    let connection = database.connection.get();
+    let organization = database.create_organization().with_fees().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = database.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("Test".to_string()),
+        ExternalPaymentType::CreditCard,
+        user.id,
+        total,
+        connection,
+    )
+    .unwrap();
+    let order = Order::find(cart.id, connection).unwrap();
+    (event, user, order)
+}
+
+async fn create_refund_request(
+    database: &TestDatabase,
+    order: &Order,
+    user: api::auth::user::User,
+    ticket_instance_ids: Vec<Uuid>,
+) -> HttpResponse {
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = order.id;
+    refund_requests::create((
+        database.connection.clone(),
+        path,
+        Json(NewRefundRequestRequest {
+            ticket_instance_ids,
+            reason: "Can't attend".to_string(),
+        }),
+        user,
+        test_request.extract_state().await,
+    ))
+    .await
+    .into()
+}
+
+async fn approve_refund_request(
+    database: &TestDatabase,
+    refund_request_id: Uuid,
+    user: api::auth::user::User,
+) -> HttpResponse {
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = refund_request_id;
+    refund_requests::approve((
+        database.connection.clone(),
+        path,
+        user,
+        test_request.extract_state().await,
+    ))
+    .await
+    .into()
+}
+
+async fn deny_refund_request(
+    database: &TestDatabase,
+    refund_request_id: Uuid,
+    user: api::auth::user::User,
+) -> HttpResponse {
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = refund_request_id;
+    refund_requests::deny((
+        database.connection.clone(),
+        path,
+        Json(DenyRefundRequestRequest {
+            response_note: Some("Event is still on".to_string()),
+        }),
+        user,
+        test_request.extract_state().await,
+    ))
+    .await
+    .into()
+}
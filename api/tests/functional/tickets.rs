@@ -12,7 +12,9 @@ use crate::support;
 use crate::support::database::TestDatabase;
 use crate::support::test_request::TestRequest;
 use api::controllers::tickets::SendTicketsRequest;
-use api::controllers::tickets::{self, SearchParameters, ShowTicketResponse, TransferTicketRequest};
+use api::controllers::tickets::{
+    self, SearchParameters, ShowTicketResponse, TransferTicketRequest, UpdateTicketRequest,
+};
 use api::extractors::*;
 use api::models::{OptionalPathParameters, PathParameters};
 use db::prelude::*;
@@ -140,6 +142,8 @@ pub async fn index() {
         transfer_address: None,
         check_in_source: None,
         promo_image_url: None,
+        seat_label: None,
+        redeem_secret: None,
     };
     assert_eq!(vec![expected_ticket.clone()], found_data.data);
     // Test without specified event
@@ -171,6 +175,8 @@ pub async fn index() {
         transfer_address: None,
         check_in_source: None,
         promo_image_url: None,
+        seat_label: None,
+        redeem_secret: None,
     };
     assert_eq!(
         vec![
@@ -294,6 +300,8 @@ pub async fn show() {
         transfer_address: None,
         check_in_source: None,
         promo_image_url: None,
+        seat_label: None,
+        redeem_secret: None,
     };
 
     let expected_result = ShowTicketResponse {
@@ -465,6 +473,160 @@ mod show_redeem_key {
     }
 }
 
+#[actix_rt::test]
+async fn update_with_name_change_fee() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                attendee_name_change_fee_in_cents: Some(500),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    let ticket = database.create_purchased_tickets(&user, ticket_type.id, 1).remove(0);
+    let request = TestRequest::create();
+    let attributes = |first_name: &str, expected_fee_in_cents: Option<i64>| UpdateTicketRequest {
+        attributes: UpdateTicketInstanceAttributes {
+            first_name_override: Some(Some(first_name.to_string())),
+            last_name_override: Some(Some("Last".to_string())),
+            attendee_email: None,
+            attendee_birth_date: None,
+        },
+        expected_fee_in_cents,
+    };
+
+    // Naming the ticket for the first time is free
+    let mut path = Path::<PathParameters>::extract(&request.request).await.unwrap();
+    path.id = ticket.id;
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let response: HttpResponse = tickets::update((
+        database.connection.clone().into(),
+        path,
+        Json(attributes("First", None)),
+        auth_user,
+        request.extract_state().await,
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Changing the name requires accepting the fee
+    let mut path = Path::<PathParameters>::extract(&request.request).await.unwrap();
+    path.id = ticket.id;
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let response: HttpResponse = tickets::update((
+        database.connection.clone().into(),
+        path,
+        Json(attributes("Other", Some(300))),
+        auth_user,
+        request.extract_state().await,
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let expected_json = HttpResponse::UnprocessableEntity().json(json!({
+        "error": "Changing the attendee name incurs a fee of 500 cents which must be accepted"
+    }));
+    let expected_text = support::unwrap_body_to_string(&expected_json).unwrap();
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, expected_text);
+
+    // And paying it
+    let mut path = Path::<PathParameters>::extract(&request.request).await.unwrap();
+    path.id = ticket.id;
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let response: HttpResponse = tickets::update((
+        database.connection.clone().into(),
+        path,
+        Json(attributes("Other", Some(500))),
+        auth_user,
+        request.extract_state().await,
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let expected_json = HttpResponse::UnprocessableEntity().json(json!({
+        "error": "Could not change the attendee name because user has no default payment method"
+    }));
+    let expected_text = support::unwrap_body_to_string(&expected_json).unwrap();
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, expected_text);
+
+    database
+        .create_payment_method()
+        .with_name(PaymentProviders::Mock)
+        .with_user(&user)
+        .make_default()
+        .finish();
+    let mut path = Path::<PathParameters>::extract(&request.request).await.unwrap();
+    path.id = ticket.id;
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let response: HttpResponse = tickets::update((
+        database.connection.clone().into(),
+        path,
+        Json(attributes("Paid", Some(500))),
+        auth_user,
+        request.extract_state().await,
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let change = TicketAttendeeChange::find_for_ticket_instance(ticket.id, connection)
+        .unwrap()
+        .pop()
+        .unwrap();
+    assert_eq!(change.first_name, Some("Paid".to_string()));
+    assert_eq!(change.fee_in_cents, 500);
+    assert_eq!(change.payment_provider, Some(PaymentProviders::Mock));
+    let order = Order::find(change.order_id.unwrap(), connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+    assert_eq!(order.user_id, user.id);
+    let items = order.items(connection).unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].item_type, OrderItemTypes::NameChangeFees);
+    assert_eq!(items[0].event_id, Some(event.id));
+    assert_eq!(items[0].unit_price_in_cents, 500);
+    let payment = order.payments(connection).unwrap().remove(0);
+    assert_eq!(payment.amount, 500);
+    assert_eq!(payment.status, PaymentStatus::Completed);
+    assert_eq!(payment.external_reference, change.external_payment_reference);
+
+    // Organization staff changing the name waive the fee
+    let mut path = Path::<PathParameters>::extract(&request.request).await.unwrap();
+    path.id = ticket.id;
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let response: HttpResponse = tickets::update((
+        database.connection.clone().into(),
+        path,
+        Json(attributes("Other", None)),
+        auth_user,
+        request.extract_state().await,
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let ticket = TicketInstance::find(ticket.id, connection).unwrap();
+    assert_eq!(ticket.first_name_override, Some("Other".to_string()));
+    let change = TicketAttendeeChange::find_for_ticket_instance(ticket.id, connection)
+        .unwrap()
+        .pop()
+        .unwrap();
+    assert_eq!(change.fee_in_cents, 0);
+    assert_eq!(change.order_id, None);
+}
+
 #[actix_rt::test]
 async fn ticket_transfer_authorization() {
     let database = TestDatabase::new();
@@ -0,0 +1,84 @@
+use crate::functional::base;
+use db::models::*;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn create_org_member() {
+        base::venue_sections::create(Roles::OrgMember, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_admin() {
+        base::venue_sections::create(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_user() {
+        base::venue_sections::create(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_org_owner() {
+        base::venue_sections::create(Roles::OrgOwner, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_door_person() {
+        base::venue_sections::create(Roles::DoorPerson, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_promoter() {
+        base::venue_sections::create(Roles::Promoter, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_promoter_read_only() {
+        base::venue_sections::create(Roles::PromoterReadOnly, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_org_admin() {
+        base::venue_sections::create(Roles::OrgAdmin, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_box_office() {
+        base::venue_sections::create(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod create_seats_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn create_seats_org_member() {
+        base::venue_sections::create_seats(Roles::OrgMember, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_seats_admin() {
+        base::venue_sections::create_seats(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_seats_user() {
+        base::venue_sections::create_seats(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_seats_org_owner() {
+        base::venue_sections::create_seats(Roles::OrgOwner, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_seats_door_person() {
+        base::venue_sections::create_seats(Roles::DoorPerson, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_seats_promoter() {
+        base::venue_sections::create_seats(Roles::Promoter, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_seats_promoter_read_only() {
+        base::venue_sections::create_seats(Roles::PromoterReadOnly, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_seats_org_admin() {
+        base::venue_sections::create_seats(Roles::OrgAdmin, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_seats_box_office() {
+        base::venue_sections::create_seats(Roles::OrgBoxOffice, false).await;
+    }
+}
@@ -0,0 +1,115 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{
+    http::StatusCode,
+    web::{Path, Query},
+    FromRequest, HttpResponse,
+};
+use api::controllers::waitlist_entries::{self, JoinWaitlistRequest};
+use api::extractors::*;
+use api::models::EventTicketPathParameters;
+use db::models::*;
+use serde_json;
+use serde_json::Value;
+
+#[actix_rt::test]
+async fn index() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let waitlist_entry = database.create_waitlist_entry().with_user(&user).finish();
+    database.create_waitlist_entry().finish();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let test_request = TestRequest::create_with_uri("/waitlist_entries");
+    let query_parameters = Query::<PagingParameters>::extract(&test_request.request).await.unwrap();
+    let response: HttpResponse = waitlist_entries::index((database.connection.clone(), query_parameters, auth_user))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let payload: Value = serde_json::from_str(&body).unwrap();
+    let data = payload["data"].as_array().unwrap();
+    assert_eq!(data.len(), 1);
+    assert_eq!(data[0]["id"], json!(waitlist_entry.id));
+    assert_eq!(data[0]["position"], json!(1));
+}
+
+#[actix_rt::test]
+async fn create() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let user = database.create_user().finish();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["event_id", "ticket_type_id"]);
+    let mut path = Path::<EventTicketPathParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    path.event_id = event.id;
+    path.ticket_type_id = ticket_type.id;
+    let json = Json(JoinWaitlistRequest { quantity: 2 });
+
+    let response: HttpResponse = waitlist_entries::create((database.connection.clone(), path, json, auth_user))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let waitlist_entry = WaitlistEntry::find_active_for_user(ticket_type.id, user.id, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(waitlist_entry.quantity, 2);
+    assert_eq!(waitlist_entry.status, WaitlistEntryStatus::Waiting);
+}
+
+#[actix_rt::test]
+async fn create_for_ticket_type_from_other_event() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let event2 = database.create_event().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let user = database.create_user().finish();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["event_id", "ticket_type_id"]);
+    let mut path = Path::<EventTicketPathParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    path.event_id = event2.id;
+    path.ticket_type_id = ticket_type.id;
+    let json = Json(JoinWaitlistRequest { quantity: 1 });
+
+    let response: HttpResponse = waitlist_entries::create((database.connection.clone(), path, json, auth_user))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(WaitlistEntry::find_active_for_user(ticket_type.id, user.id, connection)
+        .unwrap()
+        .is_none());
+}
+
+#[actix_rt::test]
+async fn destroy() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let waitlist_entry = database.create_waitlist_entry().with_user(&user).finish();
+    let ticket_type = TicketType::find(waitlist_entry.ticket_type_id, connection).unwrap();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["event_id", "ticket_type_id"]);
+    let mut path = Path::<EventTicketPathParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    path.event_id = ticket_type.event_id;
+    path.ticket_type_id = ticket_type.id;
+
+    let response: HttpResponse = waitlist_entries::destroy((database.connection.clone(), path, auth_user))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let waitlist_entry = WaitlistEntry::find(waitlist_entry.id, connection).unwrap();
+    assert_eq!(waitlist_entry.status, WaitlistEntryStatus::Cancelled);
+}
@@ -132,6 +132,14 @@ impl TestDatabase {
         StageBuilder::new(self.connection.get())
     }
 
+    pub fn create_venue_section(&self) -> VenueSectionBuilder {
+        VenueSectionBuilder::new(self.connection.get())
+    }
+
+    pub fn create_waitlist_entry(&self) -> WaitlistEntryBuilder {
+        WaitlistEntryBuilder::new(self.connection.get())
+    }
+
     pub fn create_settlement_entry(&self) -> SettlementEntryBuilder {
         SettlementEntryBuilder::new(self.connection.get())
     }
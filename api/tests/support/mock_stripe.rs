@@ -0,0 +1,70 @@
+use serde_json;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Minimal stand in for the Stripe API, point `stripe_base_url` at `base_url`.
+/// Each request is answered with the next canned response and recorded as "METHOD /path body".
+pub struct MockStripe {
+    pub base_url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockStripe {
+    pub fn start(responses: Vec<(u16, serde_json::Value)>) -> MockStripe {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let mut stream = match listener.accept() {
+                    Ok((stream, _)) => stream,
+                    Err(_) => return,
+                };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut content_length = 0;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    let mut parts = header.splitn(2, ':');
+                    if parts.next().unwrap().eq_ignore_ascii_case("content-length") {
+                        content_length = parts.next().unwrap().trim().parse().unwrap();
+                    }
+                }
+                let mut request_body = vec![0; content_length];
+                reader.read_exact(&mut request_body).unwrap();
+                let mut request_line = request_line.split_whitespace();
+                recorded.lock().unwrap().push(format!(
+                    "{} {} {}",
+                    request_line.next().unwrap_or_default(),
+                    request_line.next().unwrap_or_default(),
+                    String::from_utf8_lossy(&request_body)
+                ));
+
+                let body = body.to_string();
+                write!(
+                    stream,
+                    "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+
+        MockStripe { base_url, requests }
+    }
+
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
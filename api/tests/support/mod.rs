@@ -1,4 +1,5 @@
 pub mod database;
+pub mod mock_stripe;
 pub mod test_request;
 
 use crate::support::database::TestDatabase;
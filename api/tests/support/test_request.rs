@@ -33,10 +33,24 @@ impl TestRequest {
     }
 
     pub fn create_with_uri_custom_params(path: &str, params: Vec<&'static str>) -> TestRequest {
+        TestRequest::create_with_uri_and_config(path, params, TestRequest::config())
+    }
+
+    /// For tests that need settings other than the defaults, e.g. `TestRequest::config()` with changes
+    pub fn create_with_config(config: Config) -> TestRequest {
+        TestRequest::create_with_uri_and_config("/", vec!["id"], config)
+    }
+
+    pub fn config() -> Config {
         let mut config = Config::new(Environment::Test);
         config.token_issuer = Box::new(DefaultTokenIssuer::new("test_secret".into(), "bn-api-test".into()));
         config.api_keys_encryption_key = "test_encryption_key".to_string();
         config.google_recaptcha_secret_key = None;
+        config.stripe_webhook_secret = Some("whsec_test".to_string());
+        config
+    }
+
+    pub fn create_with_uri_and_config(path: &str, params: Vec<&'static str>, config: Config) -> TestRequest {
         if config.spotify_auth_token.is_some() {
             spotify::SINGLETON.set_auth_token(&config.spotify_auth_token.clone().unwrap());
         }
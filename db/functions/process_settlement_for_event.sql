@@ -26,11 +26,14 @@ INSERT INTO order_item_ids(id, refund_id)
 SELECT oi.id, NULL
 FROM order_items oi
 INNER JOIN orders o on oi.order_id = o.id
+LEFT JOIN installment_plans ip ON ip.order_id = o.id
 LEFT JOIN holds h ON oi.hold_id = h.id
 LEFT JOIN order_items oi_promo_code ON (oi_promo_code.item_type = 'Discount' AND oi.id = oi_promo_code.parent_id)
-WHERE ($3 IS NULL OR o.paid_at >= $3)
-AND (start_override IS NULL OR o.paid_at >= start_override)
-AND ($4 IS NULL OR o.paid_at <= $4)
+-- Orders on an installment plan are settled once every installment has been collected
+WHERE ($3 IS NULL OR COALESCE(ip.completed_at, o.paid_at) >= $3)
+AND (start_override IS NULL OR COALESCE(ip.completed_at, o.paid_at) >= start_override)
+AND ($4 IS NULL OR COALESCE(ip.completed_at, o.paid_at) <= $4)
+AND (ip.id IS NULL OR ip.status = 'Completed')
 AND oi.event_id = $2
 AND (oi.item_type <> 'EventFees' OR oi.client_fee_in_cents > 0)
 AND oi.item_type <> 'CreditCardFees'
@@ -46,9 +49,14 @@ FROM refunds r
 INNER JOIN refund_items ri ON ri.refund_id = r.id
 INNER JOIN order_items oi ON oi.id = ri.order_item_id
 INNER JOIN orders o on oi.order_id = o.id
+LEFT JOIN installment_plans ip ON ip.order_id = o.id
 WHERE oi.event_id = $2
 AND (oi.item_type <> 'EventFees' OR oi.client_fee_in_cents > 0)
 AND oi.item_type <> 'CreditCardFees'
+-- Orders whose installments were never fully collected were not settled so neither are their refunds
+AND (ip.id IS NULL OR ip.status = 'Completed')
+-- Taxes are refunded with the item they were charged on
+AND oi.item_type <> 'Tax'
 AND (start_override IS NULL OR r.created_at >= start_override)
 AND ($3 IS NULL OR r.created_at >= $3)
 AND ($4 IS NULL OR r.created_at <= $4)
@@ -57,22 +65,26 @@ AND ri.amount > 0
 AND r.settlement_id IS NULL
 AND o.box_office_pricing IS FALSE;
 
-INSERT INTO settlement_entries (settlement_id, event_id, ticket_type_id, face_value_in_cents, revenue_share_value_in_cents, online_sold_quantity, fee_sold_quantity, total_sales_in_cents, settlement_entry_type)
+INSERT INTO settlement_entries (settlement_id, event_id, ticket_type_id, product_variant_id, face_value_in_cents, revenue_share_value_in_cents, online_sold_quantity, fee_sold_quantity, total_sales_in_cents, settlement_entry_type, tax_in_cents, currency)
 SELECT -- Group result set by face price to prevent multiple records for holds that match code discounts
   entries.settlement_id,
   entries.event_id,
   entries.ticket_type_id,
+  entries.product_variant_id,
   entries.face_value_in_cents,
   entries.revenue_share_value_in_cents,
   SUM(online_sold_quantity),
   SUM(fee_sold_quantity),
   SUM(online_sold_quantity) * entries.face_value_in_cents + SUM(fee_sold_quantity) * entries.revenue_share_value_in_cents,
-  entries.settlement_entry_type
+  entries.settlement_entry_type,
+  SUM(online_sold_quantity) * entries.face_value_tax_in_cents + SUM(fee_sold_quantity) * entries.fee_tax_in_cents,
+  (SELECT e.currency FROM events e WHERE e.id = $2)
 FROM (
   SELECT
     $1 as settlement_id,
     oi.event_id,
     oi.ticket_type_id,
+    oi.product_variant_id,
     CASE oi.item_type WHEN 'EventFees' THEN 0 ELSE CAST(oi.unit_price_in_cents + COALESCE(oi_promo_code.unit_price_in_cents, 0) AS BIGINT) END as face_value_in_cents,
     -- Event fees record list the fee as part of the revenue share for that item with 0 face value
     CASE oi.item_type WHEN 'EventFees' THEN CAST(oi.client_fee_in_cents AS BIGINT) ELSE CAST(COALESCE(oi_t_fees.client_fee_in_cents, 0) AS BIGINT) END as revenue_share_value_in_cents,
@@ -100,7 +112,10 @@ FROM (
           CAST(SUM(COALESCE(oi_t_fees.quantity, 0)) AS BIGINT)
         END
     END as fee_sold_quantity,
-    CASE oi.item_type WHEN 'EventFees' THEN 'EventFees' ELSE 'TicketType' END as settlement_entry_type
+    CASE oi.item_type WHEN 'EventFees' THEN 'EventFees' WHEN 'Products' THEN 'Products' WHEN 'NameChangeFees' THEN 'NameChangeFees' ELSE 'TicketType' END as settlement_entry_type,
+    -- Unit taxes on the face value and on the fees, whether charged on top of the price or included in it
+    CASE oi.item_type WHEN 'EventFees' THEN 0 ELSE CAST(COALESCE(oi_tax.tax_in_cents, 0) AS BIGINT) END as face_value_tax_in_cents,
+    CASE oi.item_type WHEN 'EventFees' THEN CAST(COALESCE(oi_tax.tax_in_cents, 0) AS BIGINT) ELSE CAST(COALESCE(oi_t_fees_tax.tax_in_cents, 0) AS BIGINT) END as fee_tax_in_cents
   FROM order_items oi
   INNER JOIN order_item_ids oi_ids ON oi.id = oi_ids.id
   INNER JOIN orders o ON oi.order_id = o.id
@@ -108,24 +123,42 @@ FROM (
   LEFT JOIN order_items oi_promo_code ON (oi_promo_code.item_type = 'Discount' AND oi.id = oi_promo_code.parent_id)
   LEFT JOIN order_items oi_t_fees ON oi_t_fees.parent_id = oi.id AND oi_t_fees.item_type = 'PerUnitFees'
   LEFT JOIN refund_items oi_t_fees_r ON oi_t_fees_r.order_item_id = oi_t_fees.id AND oi_t_fees_r.refund_id = oi_ids.refund_id
+  LEFT JOIN (
+    SELECT tax.parent_id, SUM(tax.unit_price_in_cents + tax.included_tax_in_cents) AS tax_in_cents
+    FROM order_items tax
+    WHERE tax.item_type = 'Tax'
+    GROUP BY tax.parent_id
+  ) oi_tax ON oi_tax.parent_id = oi.id
+  LEFT JOIN (
+    SELECT tax.parent_id, SUM(tax.unit_price_in_cents + tax.included_tax_in_cents) AS tax_in_cents
+    FROM order_items tax
+    WHERE tax.item_type = 'Tax'
+    GROUP BY tax.parent_id
+  ) oi_t_fees_tax ON oi_t_fees_tax.parent_id = oi_t_fees.id
   GROUP BY
     oi.item_type,
     oi.event_id,
     oi.ticket_type_id,
+    oi.product_variant_id,
     oi.unit_price_in_cents,
     oi.client_fee_in_cents,
     oi_t_fees.client_fee_in_cents,
     oi_promo_code.unit_price_in_cents,
     oi_t_fees_r.quantity,
-    oi_r.quantity
+    oi_r.quantity,
+    oi_tax.tax_in_cents,
+    oi_t_fees_tax.tax_in_cents
 ) entries
   GROUP BY
     entries.settlement_id,
     entries.event_id,
     entries.ticket_type_id,
+    entries.product_variant_id,
     entries.face_value_in_cents,
     entries.revenue_share_value_in_cents,
-    entries.settlement_entry_type
+    entries.settlement_entry_type,
+    entries.face_value_tax_in_cents,
+    entries.fee_tax_in_cents
   -- Filter out any records where the sum of their quantities is 0
   -- Negative indicates a refund settlement adjustment, positive purchases
   HAVING
@@ -5,6 +5,7 @@ TABLE
             total                             BIGINT,
             event_name                        TEXT,
             event_date                        TIMESTAMP,
+            currency                          TEXT,
             ticket_name                       TEXT,
             face_value_in_cents               BIGINT,
             online_sale_count                 BIGINT,
@@ -48,6 +49,7 @@ INNER JOIN events e ON e.id = oi.event_id
 INNER JOIN orders o on oi.order_id = o.id
 WHERE (oi.item_type <> 'EventFees' OR oi.client_fee_in_cents > 0)
 AND oi.item_type <> 'CreditCardFees'
+AND oi.item_type <> 'Tax'
 AND ($1 IS NULL OR r.created_at >= $1)
 AND ($2 IS NULL OR r.created_at <= $2)
 AND e.organization_id = $5
@@ -59,8 +61,10 @@ RETURN QUERY (SELECT COUNT(*) OVER ()
           SELECT
               e.name                                                                                                   AS event_name,
               e.event_start                                                                                            AS event_date,
+              e.currency                                                                                               AS currency,
               CASE oi.item_type
                   WHEN 'EventFees' THEN 'Per Order Fee'
+                  WHEN 'Products' THEN concat(p.name, ' - ', pv.name)
                   ELSE
                       concat(
                           CASE tt.status WHEN 'Cancelled' THEN concat(tt.name, ' (Cancelled)') ELSE tt.name END,
@@ -127,8 +131,10 @@ RETURN QUERY (SELECT COUNT(*) OVER ()
             LEFT JOIN holds h ON oi.hold_id = h.id
             LEFT JOIN codes c ON oi.code_id = c.id
             LEFT JOIN ticket_types tt ON tt.id = oi.ticket_type_id
-        GROUP BY e.id, e.event_start, tt.id, tt.name, tt.rank, oi.item_type, tt.status, oi.unit_price_in_cents, oi_promo_code.unit_price_in_cents, c.name, h.name
-        ORDER BY e.event_start, tt.rank, tt.name, coalesce(h.name, c.name, '')
+            LEFT JOIN product_variants pv ON pv.id = oi.product_variant_id
+            LEFT JOIN products p ON p.id = pv.product_id
+        GROUP BY e.id, e.event_start, e.currency, tt.id, tt.name, tt.rank, oi.item_type, tt.status, oi.unit_price_in_cents, oi_promo_code.unit_price_in_cents, c.name, h.name, pv.id, pv.name, p.name
+        ORDER BY e.event_start, tt.rank, tt.name, coalesce(h.name, c.name, ''), p.name, pv.name
     ) r
 -- Filter out any records where the sum of their quantities is 0
 -- Negative indicates a refund adjustment, positive purchases
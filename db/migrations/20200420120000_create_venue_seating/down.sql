@@ -0,0 +1,10 @@
+DROP INDEX IF EXISTS index_ticket_instances_venue_seat_id;
+ALTER TABLE ticket_instances
+  DROP venue_seat_id;
+
+DROP INDEX IF EXISTS index_ticket_types_venue_section_id;
+ALTER TABLE ticket_types
+  DROP venue_section_id;
+
+DROP TABLE IF EXISTS venue_seats;
+DROP TABLE IF EXISTS venue_sections;
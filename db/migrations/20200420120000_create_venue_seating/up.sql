@@ -0,0 +1,33 @@
+CREATE TABLE venue_sections (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  venue_id uuid NOT NULL REFERENCES venues (id),
+  stage_id uuid REFERENCES stages (id),
+  name TEXT NOT NULL,
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_venue_sections_venue_id ON venue_sections (venue_id);
+CREATE UNIQUE INDEX index_venue_sections_venue_id_name ON venue_sections (venue_id, name);
+
+CREATE TABLE venue_seats (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  venue_section_id uuid NOT NULL REFERENCES venue_sections (id),
+  row_label TEXT NOT NULL,
+  seat_number TEXT NOT NULL,
+  accessible BOOLEAN NOT NULL DEFAULT FALSE,
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_venue_seats_venue_section_id_row_label_seat_number ON venue_seats (venue_section_id, row_label, seat_number);
+
+ALTER TABLE ticket_types
+  ADD venue_section_id uuid REFERENCES venue_sections (id);
+
+CREATE INDEX index_ticket_types_venue_section_id ON ticket_types (venue_section_id);
+
+ALTER TABLE ticket_instances
+  ADD venue_seat_id uuid REFERENCES venue_seats (id);
+
+CREATE INDEX index_ticket_instances_venue_seat_id ON ticket_instances (venue_seat_id);
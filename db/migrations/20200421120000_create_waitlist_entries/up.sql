@@ -0,0 +1,16 @@
+CREATE TABLE waitlist_entries (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  ticket_type_id uuid NOT NULL REFERENCES ticket_types (id),
+  user_id uuid NOT NULL REFERENCES users (id),
+  quantity INTEGER NOT NULL,
+  status TEXT NOT NULL DEFAULT 'Waiting',
+  order_id uuid REFERENCES orders (id),
+  offered_at TIMESTAMP WITHOUT TIME ZONE,
+  offer_expires_at TIMESTAMP WITHOUT TIME ZONE,
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_waitlist_entries_ticket_type_id_status ON waitlist_entries (ticket_type_id, status);
+CREATE INDEX index_waitlist_entries_user_id ON waitlist_entries (user_id);
+CREATE UNIQUE INDEX index_waitlist_entries_ticket_type_id_user_id_active ON waitlist_entries (ticket_type_id, user_id) WHERE status IN ('Waiting', 'Offered');
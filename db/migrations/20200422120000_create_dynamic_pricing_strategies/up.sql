@@ -0,0 +1,15 @@
+CREATE TABLE dynamic_pricing_strategies (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  ticket_type_id uuid NOT NULL REFERENCES ticket_types (id),
+  floor_price_in_cents BIGINT NOT NULL,
+  ceiling_price_in_cents BIGINT NOT NULL,
+  adjustment_percent INTEGER NOT NULL DEFAULT 10,
+  adjustment_interval_minutes INTEGER NOT NULL DEFAULT 60,
+  is_active BOOLEAN NOT NULL DEFAULT TRUE,
+  last_adjusted_at TIMESTAMP WITHOUT TIME ZONE,
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  CONSTRAINT dynamic_pricing_strategies_floor_below_ceiling CHECK (floor_price_in_cents <= ceiling_price_in_cents)
+);
+
+CREATE UNIQUE INDEX index_dynamic_pricing_strategies_ticket_type_id ON dynamic_pricing_strategies (ticket_type_id);
@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS index_order_items_pass_id;
+ALTER TABLE order_items
+  DROP pass_id;
+
+DROP TABLE IF EXISTS pass_events;
+DROP TABLE IF EXISTS passes;
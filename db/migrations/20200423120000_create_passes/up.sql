@@ -0,0 +1,30 @@
+CREATE TABLE passes (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  organization_id uuid NOT NULL REFERENCES organizations (id),
+  name TEXT NOT NULL,
+  description TEXT,
+  price_in_cents BIGINT NOT NULL,
+  status TEXT NOT NULL DEFAULT 'Draft',
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_passes_organization_id ON passes (organization_id);
+
+CREATE TABLE pass_events (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  pass_id uuid NOT NULL REFERENCES passes (id),
+  event_id uuid NOT NULL REFERENCES events (id),
+  ticket_type_id uuid NOT NULL REFERENCES ticket_types (id),
+  allocated_price_in_cents BIGINT NOT NULL,
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_pass_events_pass_id_event_id ON pass_events (pass_id, event_id);
+CREATE INDEX index_pass_events_ticket_type_id ON pass_events (ticket_type_id);
+
+ALTER TABLE order_items
+  ADD pass_id uuid REFERENCES passes (id);
+
+CREATE INDEX index_order_items_pass_id ON order_items (pass_id);
@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS index_ticket_instances_event_time_slot_id;
+ALTER TABLE ticket_instances
+  DROP event_time_slot_id;
+
+DROP TABLE IF EXISTS event_time_slots;
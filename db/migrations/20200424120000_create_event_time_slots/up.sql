@@ -0,0 +1,18 @@
+CREATE TABLE event_time_slots (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  event_id uuid NOT NULL REFERENCES events (id),
+  starts_at TIMESTAMP WITHOUT TIME ZONE NOT NULL,
+  ends_at TIMESTAMP WITHOUT TIME ZONE NOT NULL,
+  capacity INTEGER NOT NULL,
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  CHECK (ends_at > starts_at),
+  CHECK (capacity >= 0)
+);
+
+CREATE UNIQUE INDEX index_event_time_slots_event_id_starts_at ON event_time_slots (event_id, starts_at);
+
+ALTER TABLE ticket_instances
+  ADD event_time_slot_id uuid REFERENCES event_time_slots (id);
+
+CREATE INDEX index_ticket_instances_event_time_slot_id ON ticket_instances (event_time_slot_id);
@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS index_ticket_types_inventory_pool_id;
+ALTER TABLE ticket_types
+  DROP inventory_pool_id;
+
+DROP TABLE IF EXISTS inventory_pools;
@@ -0,0 +1,16 @@
+CREATE TABLE inventory_pools (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  event_id uuid NOT NULL REFERENCES events (id),
+  name TEXT NOT NULL,
+  capacity INTEGER NOT NULL,
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  CHECK (capacity >= 0)
+);
+
+CREATE INDEX index_inventory_pools_event_id ON inventory_pools (event_id);
+
+ALTER TABLE ticket_types
+  ADD inventory_pool_id uuid REFERENCES inventory_pools (id);
+
+CREATE INDEX index_ticket_types_inventory_pool_id ON ticket_types (inventory_pool_id);
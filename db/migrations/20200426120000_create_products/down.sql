@@ -0,0 +1,11 @@
+ALTER TABLE settlement_entries
+  DROP product_variant_id;
+
+DROP INDEX IF EXISTS index_order_items_product_variant_id;
+ALTER TABLE order_items
+  DROP CONSTRAINT constraint_order_items_product_variant_id,
+  DROP redeemed_quantity,
+  DROP product_variant_id;
+
+DROP TABLE IF EXISTS product_variants;
+DROP TABLE IF EXISTS products;
@@ -0,0 +1,41 @@
+CREATE TABLE products (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  organization_id uuid NOT NULL REFERENCES organizations (id),
+  event_id uuid REFERENCES events (id),
+  name TEXT NOT NULL,
+  description TEXT,
+  price_in_cents BIGINT NOT NULL,
+  per_ticket_limit INTEGER,
+  status TEXT NOT NULL DEFAULT 'Draft',
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  CHECK (price_in_cents >= 0),
+  CHECK (per_ticket_limit IS NULL OR per_ticket_limit >= 0)
+);
+
+CREATE INDEX index_products_organization_id ON products (organization_id);
+CREATE INDEX index_products_event_id ON products (event_id);
+
+CREATE TABLE product_variants (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  product_id uuid NOT NULL REFERENCES products (id),
+  name TEXT NOT NULL,
+  price_in_cents BIGINT,
+  stock_quantity INTEGER NOT NULL,
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  CHECK (price_in_cents IS NULL OR price_in_cents >= 0),
+  CHECK (stock_quantity >= 0)
+);
+
+CREATE UNIQUE INDEX index_product_variants_product_id_name ON product_variants (product_id, name);
+
+ALTER TABLE order_items
+  ADD product_variant_id uuid REFERENCES product_variants (id),
+  ADD redeemed_quantity BIGINT NOT NULL DEFAULT 0,
+  ADD CONSTRAINT constraint_order_items_product_variant_id CHECK (NOT (product_variant_id IS NULL AND item_type = 'Products'));
+
+CREATE INDEX index_order_items_product_variant_id ON order_items (product_variant_id);
+
+ALTER TABLE settlement_entries
+  ADD product_variant_id uuid REFERENCES product_variants (id);
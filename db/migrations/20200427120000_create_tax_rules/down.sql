@@ -0,0 +1,10 @@
+ALTER TABLE settlement_entries
+  DROP tax_in_cents;
+
+DROP INDEX IF EXISTS index_order_items_tax_rule_id;
+ALTER TABLE order_items
+  DROP CONSTRAINT constraint_order_items_tax_rule_id,
+  DROP included_tax_in_cents,
+  DROP tax_rule_id;
+
+DROP TABLE IF EXISTS tax_rules;
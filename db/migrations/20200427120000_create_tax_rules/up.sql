@@ -0,0 +1,25 @@
+CREATE TABLE tax_rules (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  name TEXT NOT NULL,
+  country TEXT NOT NULL,
+  state TEXT,
+  rate_percent REAL NOT NULL,
+  inclusive BOOLEAN NOT NULL DEFAULT 'F',
+  applies_to_face_value BOOLEAN NOT NULL DEFAULT 'T',
+  applies_to_fees BOOLEAN NOT NULL DEFAULT 'F',
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  CHECK (rate_percent >= 0 AND rate_percent <= 100)
+);
+
+CREATE INDEX index_tax_rules_country_state ON tax_rules (country, state);
+
+ALTER TABLE order_items
+  ADD tax_rule_id uuid REFERENCES tax_rules (id),
+  ADD included_tax_in_cents BIGINT NOT NULL DEFAULT 0,
+  ADD CONSTRAINT constraint_order_items_tax_rule_id CHECK (NOT (tax_rule_id IS NULL AND item_type = 'Tax'));
+
+CREATE INDEX index_order_items_tax_rule_id ON order_items (tax_rule_id);
+
+ALTER TABLE settlement_entries
+  ADD tax_in_cents BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,11 @@
+ALTER TABLE settlement_entries
+  DROP currency;
+
+ALTER TABLE payments
+  DROP currency;
+
+ALTER TABLE orders
+  DROP currency;
+
+ALTER TABLE events
+  DROP currency;
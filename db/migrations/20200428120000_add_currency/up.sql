@@ -0,0 +1,21 @@
+-- Existing rows were sold in the deployment's primary currency, which the db cli passes in from
+-- PRIMARY_CURRENCY. New rows default to USD until their currency is set.
+ALTER TABLE events
+  ADD currency TEXT NOT NULL DEFAULT UPPER(COALESCE(NULLIF(current_setting('bigneon.primary_currency', true), ''), 'USD'));
+ALTER TABLE events
+  ALTER COLUMN currency SET DEFAULT 'USD';
+
+ALTER TABLE orders
+  ADD currency TEXT NOT NULL DEFAULT UPPER(COALESCE(NULLIF(current_setting('bigneon.primary_currency', true), ''), 'USD'));
+ALTER TABLE orders
+  ALTER COLUMN currency SET DEFAULT 'USD';
+
+ALTER TABLE payments
+  ADD currency TEXT NOT NULL DEFAULT UPPER(COALESCE(NULLIF(current_setting('bigneon.primary_currency', true), ''), 'USD'));
+ALTER TABLE payments
+  ALTER COLUMN currency SET DEFAULT 'USD';
+
+ALTER TABLE settlement_entries
+  ADD currency TEXT NOT NULL DEFAULT UPPER(COALESCE(NULLIF(current_setting('bigneon.primary_currency', true), ''), 'USD'));
+ALTER TABLE settlement_entries
+  ALTER COLUMN currency SET DEFAULT 'USD';
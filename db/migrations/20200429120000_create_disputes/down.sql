@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS index_disputes_external_reference;
+DROP INDEX IF EXISTS index_disputes_payment_id;
+DROP INDEX IF EXISTS index_disputes_order_id;
+DROP TABLE IF EXISTS disputes;
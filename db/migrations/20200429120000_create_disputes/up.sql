@@ -0,0 +1,21 @@
+CREATE TABLE disputes (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  order_id uuid NOT NULL REFERENCES orders (id),
+  payment_id uuid NOT NULL REFERENCES payments (id),
+  settlement_adjustment_id uuid REFERENCES settlement_adjustments (id),
+  created_by uuid REFERENCES users (id),
+  external_reference TEXT,
+  amount_in_cents BIGINT NOT NULL,
+  reason TEXT,
+  status TEXT NOT NULL DEFAULT 'Open',
+  outcome TEXT,
+  evidence_due_by TIMESTAMP WITHOUT TIME ZONE,
+  resolved_at TIMESTAMP WITHOUT TIME ZONE,
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  CHECK (amount_in_cents >= 0)
+);
+
+CREATE INDEX index_disputes_order_id ON disputes (order_id);
+CREATE INDEX index_disputes_payment_id ON disputes (payment_id);
+CREATE UNIQUE INDEX index_disputes_external_reference ON disputes (external_reference);
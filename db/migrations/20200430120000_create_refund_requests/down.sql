@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS index_refund_requests_event_id_status;
+DROP INDEX IF EXISTS index_refund_requests_order_id;
+DROP TABLE IF EXISTS refund_requests;
+
+DROP INDEX IF EXISTS index_refund_policies_event_id;
+DROP TABLE IF EXISTS refund_policies;
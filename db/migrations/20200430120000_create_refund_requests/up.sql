@@ -0,0 +1,31 @@
+CREATE TABLE refund_policies (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  event_id uuid NOT NULL REFERENCES events (id),
+  approval_type TEXT NOT NULL DEFAULT 'Manual',
+  deadline_hours_before_event INTEGER NOT NULL DEFAULT 0,
+  retain_fees BOOLEAN NOT NULL DEFAULT true,
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  CHECK (deadline_hours_before_event >= 0)
+);
+
+CREATE UNIQUE INDEX index_refund_policies_event_id ON refund_policies (event_id);
+
+CREATE TABLE refund_requests (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  order_id uuid NOT NULL REFERENCES orders (id),
+  event_id uuid NOT NULL REFERENCES events (id),
+  user_id uuid NOT NULL REFERENCES users (id),
+  ticket_instance_ids uuid[] NOT NULL,
+  reason TEXT NOT NULL,
+  status TEXT NOT NULL DEFAULT 'Pending',
+  reviewed_by uuid REFERENCES users (id),
+  reviewed_at TIMESTAMP WITHOUT TIME ZONE,
+  response_note TEXT,
+  refund_id uuid REFERENCES refunds (id),
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_refund_requests_order_id ON refund_requests (order_id);
+CREATE INDEX index_refund_requests_event_id_status ON refund_requests (event_id, status);
@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS index_event_reschedule_responses_event_reschedule_id_order_id;
+DROP TABLE IF EXISTS event_reschedule_responses;
+
+DROP INDEX IF EXISTS index_event_reschedules_event_id;
+DROP TABLE IF EXISTS event_reschedules;
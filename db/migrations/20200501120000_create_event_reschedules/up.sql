@@ -0,0 +1,29 @@
+CREATE TABLE event_reschedules (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  event_id uuid NOT NULL REFERENCES events (id),
+  previous_event_start TIMESTAMP WITHOUT TIME ZONE,
+  previous_event_end TIMESTAMP WITHOUT TIME ZONE,
+  previous_door_time TIMESTAMP WITHOUT TIME ZONE,
+  event_start TIMESTAMP WITHOUT TIME ZONE NOT NULL,
+  event_end TIMESTAMP WITHOUT TIME ZONE,
+  door_time TIMESTAMP WITHOUT TIME ZONE,
+  response_deadline TIMESTAMP WITHOUT TIME ZONE NOT NULL,
+  created_by uuid REFERENCES users (id),
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_event_reschedules_event_id ON event_reschedules (event_id);
+
+CREATE TABLE event_reschedule_responses (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  event_reschedule_id uuid NOT NULL REFERENCES event_reschedules (id),
+  order_id uuid NOT NULL REFERENCES orders (id),
+  user_id uuid NOT NULL REFERENCES users (id),
+  response TEXT NOT NULL,
+  refund_id uuid REFERENCES refunds (id),
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_event_reschedule_responses_event_reschedule_id_order_id ON event_reschedule_responses (event_reschedule_id, order_id);
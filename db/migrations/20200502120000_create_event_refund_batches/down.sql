@@ -0,0 +1,9 @@
+DROP INDEX IF EXISTS index_event_refund_batch_orders_event_refund_batch_id_status;
+DROP INDEX IF EXISTS index_event_refund_batch_orders_event_refund_batch_id_order_id;
+DROP TABLE IF EXISTS event_refund_batch_orders;
+
+DROP INDEX IF EXISTS index_event_refund_batches_event_id;
+DROP TABLE IF EXISTS event_refund_batches;
+
+ALTER TABLE organizations
+    DROP refund_fees_on_event_cancellation;
@@ -0,0 +1,31 @@
+ALTER TABLE organizations
+    ADD refund_fees_on_event_cancellation BOOLEAN NOT NULL DEFAULT true;
+
+CREATE TABLE event_refund_batches (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  event_id uuid NOT NULL REFERENCES events (id),
+  status TEXT NOT NULL DEFAULT 'Pending',
+  refund_fees BOOLEAN NOT NULL,
+  created_by uuid NOT NULL REFERENCES users (id),
+  completed_at TIMESTAMP WITHOUT TIME ZONE,
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_event_refund_batches_event_id ON event_refund_batches (event_id);
+
+CREATE TABLE event_refund_batch_orders (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  event_refund_batch_id uuid NOT NULL REFERENCES event_refund_batches (id),
+  order_id uuid NOT NULL REFERENCES orders (id),
+  status TEXT NOT NULL DEFAULT 'Pending',
+  refund_id uuid REFERENCES refunds (id),
+  amount_refunded_in_cents BIGINT,
+  error TEXT,
+  attempted_at TIMESTAMP WITHOUT TIME ZONE,
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_event_refund_batch_orders_event_refund_batch_id_order_id ON event_refund_batch_orders (event_refund_batch_id, order_id);
+CREATE INDEX index_event_refund_batch_orders_event_refund_batch_id_status ON event_refund_batch_orders (event_refund_batch_id, status);
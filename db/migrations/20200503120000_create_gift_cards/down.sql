@@ -0,0 +1,11 @@
+DROP INDEX IF EXISTS index_store_credit_transactions_order_id;
+DROP INDEX IF EXISTS index_store_credit_transactions_user_id_organization_id;
+DROP TABLE IF EXISTS store_credit_transactions;
+
+DROP INDEX IF EXISTS index_gift_cards_order_item_id;
+DROP INDEX IF EXISTS index_gift_cards_organization_id;
+DROP INDEX IF EXISTS index_gift_cards_code;
+DROP TABLE IF EXISTS gift_cards;
+
+ALTER TABLE products
+    DROP gift_card;
@@ -0,0 +1,40 @@
+ALTER TABLE products
+  ADD gift_card BOOLEAN NOT NULL DEFAULT false;
+
+CREATE TABLE gift_cards (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  organization_id uuid NOT NULL REFERENCES organizations (id),
+  code TEXT NOT NULL,
+  initial_value_in_cents BIGINT NOT NULL,
+  balance_in_cents BIGINT NOT NULL,
+  currency TEXT NOT NULL,
+  expires_at TIMESTAMP WITHOUT TIME ZONE,
+  order_item_id uuid REFERENCES order_items (id),
+  created_by uuid REFERENCES users (id),
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  CHECK (initial_value_in_cents > 0),
+  CHECK (balance_in_cents >= 0 AND balance_in_cents <= initial_value_in_cents)
+);
+
+CREATE UNIQUE INDEX index_gift_cards_code ON gift_cards (code);
+CREATE INDEX index_gift_cards_organization_id ON gift_cards (organization_id);
+CREATE INDEX index_gift_cards_order_item_id ON gift_cards (order_item_id);
+
+CREATE TABLE store_credit_transactions (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  user_id uuid NOT NULL REFERENCES users (id),
+  organization_id uuid NOT NULL REFERENCES organizations (id),
+  amount_in_cents BIGINT NOT NULL,
+  currency TEXT NOT NULL,
+  order_id uuid REFERENCES orders (id),
+  refund_id uuid REFERENCES refunds (id),
+  note TEXT,
+  created_by uuid REFERENCES users (id),
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  CHECK (amount_in_cents <> 0)
+);
+
+CREATE INDEX index_store_credit_transactions_user_id_organization_id ON store_credit_transactions (user_id, organization_id);
+CREATE INDEX index_store_credit_transactions_order_id ON store_credit_transactions (order_id);
@@ -0,0 +1,9 @@
+DROP INDEX IF EXISTS index_installments_installment_plan_id;
+DROP TABLE IF EXISTS installments;
+
+DROP INDEX IF EXISTS index_installment_plans_event_id;
+DROP INDEX IF EXISTS index_installment_plans_order_id;
+DROP TABLE IF EXISTS installment_plans;
+
+DROP INDEX IF EXISTS index_installment_policies_event_id;
+DROP TABLE IF EXISTS installment_policies;
@@ -0,0 +1,58 @@
+CREATE TABLE installment_policies (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  event_id uuid NOT NULL REFERENCES events (id),
+  deposit_percent INTEGER NOT NULL,
+  installment_count INTEGER NOT NULL,
+  interval_days INTEGER NOT NULL DEFAULT 30,
+  max_attempts INTEGER NOT NULL DEFAULT 3,
+  retry_interval_hours INTEGER NOT NULL DEFAULT 24,
+  refund_deposit_on_default BOOLEAN NOT NULL DEFAULT false,
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  CHECK (deposit_percent > 0 AND deposit_percent < 100),
+  CHECK (installment_count > 0),
+  CHECK (interval_days > 0),
+  CHECK (max_attempts > 0),
+  CHECK (retry_interval_hours > 0)
+);
+
+CREATE UNIQUE INDEX index_installment_policies_event_id ON installment_policies (event_id);
+
+CREATE TABLE installment_plans (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  order_id uuid NOT NULL REFERENCES orders (id),
+  event_id uuid NOT NULL REFERENCES events (id),
+  payment_method_id uuid NOT NULL REFERENCES payment_methods (id),
+  status TEXT NOT NULL DEFAULT 'Pending',
+  total_in_cents BIGINT NOT NULL,
+  deposit_in_cents BIGINT NOT NULL,
+  max_attempts INTEGER NOT NULL,
+  retry_interval_hours INTEGER NOT NULL,
+  refund_deposit_on_default BOOLEAN NOT NULL,
+  created_by uuid NOT NULL REFERENCES users (id),
+  completed_at TIMESTAMP WITHOUT TIME ZONE,
+  defaulted_at TIMESTAMP WITHOUT TIME ZONE,
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  CHECK (deposit_in_cents > 0 AND deposit_in_cents < total_in_cents)
+);
+
+CREATE UNIQUE INDEX index_installment_plans_order_id ON installment_plans (order_id);
+CREATE INDEX index_installment_plans_event_id ON installment_plans (event_id);
+
+CREATE TABLE installments (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  installment_plan_id uuid NOT NULL REFERENCES installment_plans (id) ON DELETE CASCADE,
+  amount_in_cents BIGINT NOT NULL,
+  due_at TIMESTAMP WITHOUT TIME ZONE NOT NULL,
+  status TEXT NOT NULL DEFAULT 'Scheduled',
+  attempts INTEGER NOT NULL DEFAULT 0,
+  last_error TEXT,
+  payment_id uuid REFERENCES payments (id),
+  paid_at TIMESTAMP WITHOUT TIME ZONE,
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  CHECK (amount_in_cents > 0)
+);
+
+CREATE INDEX index_installments_installment_plan_id ON installments (installment_plan_id);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS index_idempotency_keys_expires_at;
+DROP INDEX IF EXISTS index_idempotency_keys_user_id_idempotency_key;
+DROP TABLE IF EXISTS idempotency_keys;
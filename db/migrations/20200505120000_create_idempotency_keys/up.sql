@@ -0,0 +1,15 @@
+CREATE TABLE idempotency_keys (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  user_id uuid NOT NULL REFERENCES users (id),
+  idempotency_key TEXT NOT NULL,
+  request_method TEXT NOT NULL,
+  request_path TEXT NOT NULL,
+  response_status INTEGER,
+  response_body TEXT,
+  expires_at TIMESTAMP WITHOUT TIME ZONE NOT NULL,
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_idempotency_keys_user_id_idempotency_key ON idempotency_keys (user_id, idempotency_key);
+CREATE INDEX index_idempotency_keys_expires_at ON idempotency_keys (expires_at);
@@ -0,0 +1,7 @@
+ALTER TABLE ticket_instances
+  ADD redeem_secret TEXT NULL;
+
+-- Existing tickets get a secret so events can opt in to rotating redeem codes after sales have started
+UPDATE ticket_instances
+SET redeem_secret = encode(gen_random_bytes(20), 'hex')
+WHERE redeem_key IS NOT NULL;
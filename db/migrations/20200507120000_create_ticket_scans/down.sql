@@ -0,0 +1,3 @@
+DROP TABLE ticket_scans;
+
+ALTER TABLE ticket_types DROP COLUMN max_entries;
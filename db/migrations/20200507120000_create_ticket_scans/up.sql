@@ -0,0 +1,24 @@
+ALTER TABLE ticket_types ADD max_entries INTEGER NOT NULL DEFAULT 1 CHECK (max_entries > 0);
+
+CREATE TABLE ticket_scans (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  ticket_instance_id uuid NOT NULL REFERENCES ticket_instances (id),
+  event_id uuid NOT NULL REFERENCES events (id),
+  direction TEXT NOT NULL,
+  check_in_source TEXT NULL,
+  scanned_by_user_id uuid NULL REFERENCES users (id),
+  scanned_at TIMESTAMP WITHOUT TIME ZONE NOT NULL,
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_ticket_scans_ticket_instance_id_scanned_at ON ticket_scans (ticket_instance_id, scanned_at);
+CREATE INDEX index_ticket_scans_event_id_scanned_at ON ticket_scans (event_id, scanned_at);
+
+-- Tickets redeemed before scans were logged count as a single entry
+INSERT INTO ticket_scans (ticket_instance_id, event_id, direction, check_in_source, scanned_by_user_id, scanned_at)
+SELECT ti.id, tt.event_id, 'In', ti.check_in_source, ti.redeemed_by_user_id, COALESCE(ti.redeemed_at, ti.updated_at)
+FROM ticket_instances ti
+JOIN assets a ON a.id = ti.asset_id
+JOIN ticket_types tt ON tt.id = a.ticket_type_id
+WHERE ti.status = 'Redeemed';
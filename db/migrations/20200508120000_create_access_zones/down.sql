@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS index_ticket_scans_access_zone_id_scanned_at;
+ALTER TABLE ticket_scans DROP COLUMN access_zone_id;
+DROP TABLE access_zone_users;
+DROP TABLE ticket_type_access_zones;
+DROP TABLE access_zones;
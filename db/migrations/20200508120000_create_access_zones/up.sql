@@ -0,0 +1,37 @@
+CREATE TABLE access_zones (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  event_id uuid NOT NULL REFERENCES events (id),
+  name TEXT NOT NULL,
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_access_zones_event_id_name ON access_zones (event_id, name);
+
+CREATE TABLE ticket_type_access_zones (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  ticket_type_id uuid NOT NULL REFERENCES ticket_types (id),
+  access_zone_id uuid NOT NULL REFERENCES access_zones (id),
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_ticket_type_access_zones_access_zone_id_ticket_type_id ON ticket_type_access_zones (access_zone_id, ticket_type_id);
+CREATE INDEX index_ticket_type_access_zones_ticket_type_id ON ticket_type_access_zones (ticket_type_id);
+
+-- A scanner works a single zone per event, scanning at the gate when unassigned
+CREATE TABLE access_zone_users (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  event_id uuid NOT NULL REFERENCES events (id),
+  access_zone_id uuid NOT NULL REFERENCES access_zones (id),
+  user_id uuid NOT NULL REFERENCES users (id),
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_access_zone_users_event_id_user_id ON access_zone_users (event_id, user_id);
+CREATE INDEX index_access_zone_users_access_zone_id ON access_zone_users (access_zone_id);
+
+-- Scans without a zone are gate check-ins
+ALTER TABLE ticket_scans ADD access_zone_id uuid NULL REFERENCES access_zones (id);
+CREATE INDEX index_ticket_scans_access_zone_id_scanned_at ON ticket_scans (access_zone_id, scanned_at);
@@ -0,0 +1,8 @@
+DROP TABLE ticket_attendee_changes;
+
+ALTER TABLE ticket_instances DROP COLUMN attendee_birth_date;
+ALTER TABLE ticket_instances DROP COLUMN attendee_email;
+
+ALTER TABLE ticket_types DROP COLUMN attendee_details_lock_hours;
+ALTER TABLE ticket_types DROP COLUMN attendee_name_change_fee_in_cents;
+ALTER TABLE ticket_types DROP COLUMN required_attendee_details;
@@ -0,0 +1,23 @@
+ALTER TABLE ticket_types ADD required_attendee_details TEXT[] NOT NULL DEFAULT '{}';
+ALTER TABLE ticket_types ADD attendee_name_change_fee_in_cents BIGINT NOT NULL DEFAULT 0 CHECK (attendee_name_change_fee_in_cents >= 0);
+ALTER TABLE ticket_types ADD attendee_details_lock_hours INTEGER NULL CHECK (attendee_details_lock_hours >= 0);
+
+ALTER TABLE ticket_instances ADD attendee_email TEXT NULL;
+ALTER TABLE ticket_instances ADD attendee_birth_date DATE NULL;
+
+CREATE TABLE ticket_attendee_changes (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  ticket_instance_id uuid NOT NULL REFERENCES ticket_instances (id),
+  first_name TEXT NULL,
+  last_name TEXT NULL,
+  email TEXT NULL,
+  birth_date DATE NULL,
+  changed_by_user_id uuid NOT NULL REFERENCES users (id),
+  fee_in_cents BIGINT NOT NULL DEFAULT 0,
+  payment_provider TEXT NULL,
+  external_payment_reference TEXT NULL,
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_ticket_attendee_changes_ticket_instance_id ON ticket_attendee_changes (ticket_instance_id);
@@ -0,0 +1,3 @@
+DROP INDEX index_ticket_instances_seat_event_id_venue_seat_id;
+
+ALTER TABLE ticket_instances DROP COLUMN seat_event_id;
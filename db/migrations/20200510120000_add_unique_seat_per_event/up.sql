@@ -0,0 +1,18 @@
+ALTER TABLE ticket_instances
+  ADD seat_event_id uuid REFERENCES events (id);
+
+-- Expired cart reservations still point at their seat until the ticket is reserved again
+UPDATE ticket_instances
+SET venue_seat_id = NULL
+WHERE venue_seat_id IS NOT NULL
+  AND status = 'Reserved'
+  AND reserved_until < now();
+
+UPDATE ticket_instances ti
+SET seat_event_id = tt.event_id
+FROM assets a
+         INNER JOIN ticket_types tt ON a.ticket_type_id = tt.id
+WHERE ti.asset_id = a.id
+  AND ti.venue_seat_id IS NOT NULL;
+
+CREATE UNIQUE INDEX index_ticket_instances_seat_event_id_venue_seat_id ON ticket_instances (seat_event_id, venue_seat_id) WHERE venue_seat_id IS NOT NULL AND status IN ('Reserved', 'Purchased', 'Redeemed');
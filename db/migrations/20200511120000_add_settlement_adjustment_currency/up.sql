@@ -0,0 +1,6 @@
+-- Existing adjustments were made in the deployment's primary currency, which the db cli passes in
+-- from PRIMARY_CURRENCY
+ALTER TABLE settlement_adjustments
+  ADD currency TEXT NOT NULL DEFAULT UPPER(COALESCE(NULLIF(current_setting('bigneon.primary_currency', true), ''), 'USD'));
+ALTER TABLE settlement_adjustments
+  ALTER COLUMN currency DROP DEFAULT;
@@ -0,0 +1,2 @@
+ALTER TABLE gift_cards
+  DROP voided_at;
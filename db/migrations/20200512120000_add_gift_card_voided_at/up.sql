@@ -0,0 +1,2 @@
+ALTER TABLE gift_cards
+  ADD voided_at TIMESTAMP WITHOUT TIME ZONE;
@@ -0,0 +1,2 @@
+ALTER TABLE ticket_attendee_changes
+  DROP order_id;
@@ -0,0 +1,4 @@
+ALTER TABLE ticket_attendee_changes
+  ADD order_id uuid NULL REFERENCES orders (id);
+
+CREATE INDEX index_ticket_attendee_changes_order_id ON ticket_attendee_changes (order_id);
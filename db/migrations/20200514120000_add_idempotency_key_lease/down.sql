@@ -0,0 +1,4 @@
+ALTER TABLE idempotency_keys
+  DROP locked_until;
+ALTER TABLE idempotency_keys
+  DROP request_hash;
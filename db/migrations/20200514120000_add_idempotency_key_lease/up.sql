@@ -0,0 +1,4 @@
+ALTER TABLE idempotency_keys
+  ADD request_hash TEXT NULL;
+ALTER TABLE idempotency_keys
+  ADD locked_until TIMESTAMP WITHOUT TIME ZONE NULL;
@@ -27,6 +27,7 @@ use clap::{App, Arg, SubCommand};
 use diesel::connection::SimpleConnection;
 use diesel::pg::PgConnection;
 use diesel::Connection;
+use std::env;
 use std::fs::{create_dir_all, File};
 use std::io::Write;
 use std::path::Path;
@@ -230,6 +231,15 @@ fn migrate_db(matches: &ArgMatches) {
 
     let connection = PgConnection::establish(conn_string).unwrap();
 
+    // Migrations backfill existing rows with the deployment's primary currency
+    let primary_currency = env::var("PRIMARY_CURRENCY")
+        .unwrap_or_else(|_| "usd".to_string())
+        .to_uppercase();
+    bigneon_db::validators::validate_currency(&primary_currency).expect("PRIMARY_CURRENCY is not a valid currency");
+    connection
+        .batch_execute(&format!("SET bigneon.primary_currency = '{}'", primary_currency))
+        .expect("Could not set primary currency");
+
     embedded_migrations::run_with_output(&connection, &mut std::io::stdout()).expect("Migration failed");
 
     run_function_migrations(matches);
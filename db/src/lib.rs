@@ -49,6 +49,7 @@ extern crate serde_json;
 extern crate validator_derive;
 extern crate tari_client;
 extern crate unidecode;
+extern crate untrusted;
 extern crate url;
 extern crate validator;
 
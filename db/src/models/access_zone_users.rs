@@ -0,0 +1,93 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::access_zone_users;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Assignment of a scanner to the access zone they are working for an event
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(AccessZone)]
+#[table_name = "access_zone_users"]
+pub struct AccessZoneUser {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub access_zone_id: Uuid,
+    pub user_id: Uuid,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "access_zone_users"]
+pub struct NewAccessZoneUser {
+    pub event_id: Uuid,
+    pub access_zone_id: Uuid,
+    pub user_id: Uuid,
+}
+
+impl NewAccessZoneUser {
+    pub fn commit(&self, conn: &PgConnection) -> Result<AccessZoneUser, DatabaseError> {
+        diesel::insert_into(access_zone_users::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not assign user to access zone")
+    }
+}
+
+impl AccessZoneUser {
+    pub fn create(event_id: Uuid, access_zone_id: Uuid, user_id: Uuid) -> NewAccessZoneUser {
+        NewAccessZoneUser {
+            event_id,
+            access_zone_id,
+            user_id,
+        }
+    }
+
+    pub fn find_for_access_zone(
+        access_zone_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<AccessZoneUser>, DatabaseError> {
+        access_zone_users::table
+            .filter(access_zone_users::access_zone_id.eq(access_zone_id))
+            .order_by(access_zone_users::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load access zone users")
+    }
+
+    pub fn find_for_event_user(
+        event_id: Uuid,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<AccessZoneUser>, DatabaseError> {
+        access_zone_users::table
+            .filter(access_zone_users::event_id.eq(event_id))
+            .filter(access_zone_users::user_id.eq(user_id))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Unable to load access zone user")
+    }
+
+    pub fn update_access_zone(
+        &self,
+        access_zone_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<AccessZoneUser, DatabaseError> {
+        diesel::update(self)
+            .set((
+                access_zone_users::access_zone_id.eq(access_zone_id),
+                access_zone_users::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update access zone user")
+    }
+
+    pub fn destroy(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove user from access zone")?;
+        Ok(())
+    }
+}
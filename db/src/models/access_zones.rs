@@ -0,0 +1,260 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::dsl::{exists, select};
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{access_zones, ticket_type_access_zones, ticket_types};
+use utils::errors::*;
+use uuid::Uuid;
+use validators::*;
+
+/// Named area within an event such as the floor, a lounge or backstage. Ticket types grant access
+/// to zones and door staff assigned to a zone scan tickets there instead of at the gate.
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Event)]
+#[table_name = "access_zones"]
+pub struct AccessZone {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub name: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(AsChangeset, Clone, Default, Deserialize, Serialize)]
+#[table_name = "access_zones"]
+pub struct AccessZoneEditableAttributes {
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub name: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "access_zones"]
+pub struct NewAccessZone {
+    pub event_id: Uuid,
+    pub name: String,
+}
+
+impl NewAccessZone {
+    pub fn commit(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<AccessZone, DatabaseError> {
+        let result: AccessZone = diesel::insert_into(access_zones::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create access zone")?;
+
+        DomainEvent::create(
+            DomainEventTypes::AccessZoneCreated,
+            "Access zone created".to_string(),
+            Tables::AccessZones,
+            Some(result.id),
+            current_user_id,
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+}
+
+impl AccessZone {
+    pub fn create(event_id: Uuid, name: String) -> NewAccessZone {
+        NewAccessZone { event_id, name }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<AccessZone, DatabaseError> {
+        access_zones::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading access zone")
+    }
+
+    pub fn find_for_event(event_id: Uuid, conn: &PgConnection) -> Result<Vec<AccessZone>, DatabaseError> {
+        access_zones::table
+            .filter(access_zones::event_id.eq(event_id))
+            .order_by(access_zones::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load access zones for event")
+    }
+
+    pub fn update(
+        &self,
+        attributes: AccessZoneEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<AccessZone, DatabaseError> {
+        let result = diesel::update(self)
+            .set((&attributes, access_zones::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update access zone")?;
+
+        DomainEvent::create(
+            DomainEventTypes::AccessZoneUpdated,
+            "Access zone updated".to_string(),
+            Tables::AccessZones,
+            Some(self.id),
+            current_user_id,
+            Some(json!(&attributes)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    pub fn ticket_types(&self, conn: &PgConnection) -> Result<Vec<TicketType>, DatabaseError> {
+        ticket_type_access_zones::table
+            .inner_join(ticket_types::table)
+            .filter(ticket_type_access_zones::access_zone_id.eq(self.id))
+            .filter(ticket_types::deleted_at.is_null())
+            .order_by(ticket_types::rank)
+            .then_order_by(ticket_types::name)
+            .select(ticket_types::all_columns)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load ticket types for access zone")
+    }
+
+    /// Replaces the ticket types granting access to this zone
+    pub fn set_ticket_types(
+        &self,
+        ticket_type_ids: &[Uuid],
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        let event_ticket_type_count: i64 = ticket_types::table
+            .filter(ticket_types::id.eq_any(ticket_type_ids))
+            .filter(ticket_types::event_id.eq(self.event_id))
+            .select(dsl::count(ticket_types::id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load ticket types for access zone")?;
+        let mut unique_ticket_type_ids = ticket_type_ids.to_vec();
+        unique_ticket_type_ids.sort();
+        unique_ticket_type_ids.dedup();
+        if event_ticket_type_count != unique_ticket_type_ids.len() as i64 {
+            return DatabaseError::validation_error(
+                "ticket_type_ids",
+                "Ticket types must belong to the access zone's event",
+            );
+        }
+
+        diesel::delete(ticket_type_access_zones::table.filter(ticket_type_access_zones::access_zone_id.eq(self.id)))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove ticket types from access zone")?;
+        let values: Vec<_> = unique_ticket_type_ids
+            .iter()
+            .map(|ticket_type_id| {
+                (
+                    ticket_type_access_zones::access_zone_id.eq(self.id),
+                    ticket_type_access_zones::ticket_type_id.eq(*ticket_type_id),
+                )
+            })
+            .collect();
+        if !values.is_empty() {
+            diesel::insert_into(ticket_type_access_zones::table)
+                .values(&values)
+                .execute(conn)
+                .to_db_error(ErrorCode::InsertError, "Could not add ticket types to access zone")?;
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::AccessZoneUpdated,
+            "Access zone ticket types updated".to_string(),
+            Tables::AccessZones,
+            Some(self.id),
+            current_user_id,
+            Some(json!({ "ticket_type_ids": unique_ticket_type_ids })),
+        )
+        .commit(conn)?;
+
+        Ok(())
+    }
+
+    pub fn grants_ticket_type(&self, ticket_type_id: Uuid, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        select(exists(
+            ticket_type_access_zones::table
+                .filter(ticket_type_access_zones::access_zone_id.eq(self.id))
+                .filter(ticket_type_access_zones::ticket_type_id.eq(ticket_type_id)),
+        ))
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not check access zone ticket types")
+    }
+
+    /// Assigns the scanner to this zone, replacing any zone they were assigned to for the event
+    pub fn assign_user(
+        &self,
+        user: &User,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<AccessZoneUser, DatabaseError> {
+        let organization = Organization::find_for_event(self.event_id, conn)?;
+        if !organization
+            .get_scopes_for_user(user, conn)?
+            .contains(&Scopes::RedeemTicket)
+        {
+            return DatabaseError::validation_error("user_id", "User is not able to scan tickets for this event");
+        }
+
+        let result = match AccessZoneUser::find_for_event_user(self.event_id, user.id, conn)? {
+            Some(access_zone_user) => access_zone_user.update_access_zone(self.id, conn)?,
+            None => AccessZoneUser::create(self.event_id, self.id, user.id).commit(conn)?,
+        };
+
+        DomainEvent::create(
+            DomainEventTypes::AccessZoneUserAssigned,
+            "User assigned to access zone".to_string(),
+            Tables::AccessZones,
+            Some(self.id),
+            current_user_id,
+            Some(json!({ "user_id": user.id })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    pub fn unassign_user(
+        &self,
+        user_id: Uuid,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        match AccessZoneUser::find_for_event_user(self.event_id, user_id, conn)? {
+            Some(ref access_zone_user) if access_zone_user.access_zone_id == self.id => {
+                access_zone_user.destroy(conn)?;
+            }
+            _ => {
+                return Err(DatabaseError::new(
+                    ErrorCode::NoResults,
+                    Some("User is not assigned to this access zone".to_string()),
+                ))
+            }
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::AccessZoneUserUnassigned,
+            "User unassigned from access zone".to_string(),
+            Tables::AccessZones,
+            Some(self.id),
+            current_user_id,
+            Some(json!({ "user_id": user_id })),
+        )
+        .commit(conn)?;
+
+        Ok(())
+    }
+
+    pub fn users(&self, conn: &PgConnection) -> Result<Vec<AccessZoneUser>, DatabaseError> {
+        AccessZoneUser::find_for_access_zone(self.id, conn)
+    }
+
+    /// The zone the scanner is assigned to for the event, if any
+    pub fn find_for_user(
+        event_id: Uuid,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<AccessZone>, DatabaseError> {
+        match AccessZoneUser::find_for_event_user(event_id, user_id, conn)? {
+            Some(access_zone_user) => Ok(Some(AccessZone::find(access_zone_user.access_zone_id, conn)?)),
+            None => Ok(None),
+        }
+    }
+}
@@ -105,6 +105,19 @@ pub enum ActivityItem {
         note: String,
         occurred_at: NaiveDateTime,
     },
+    Dispute {
+        dispute_id: Uuid,
+        order_id: Uuid,
+        order_number: String,
+        amount_in_cents: i64,
+        reason: Option<String>,
+        status: DisputeStatus,
+        outcome: Option<DisputeOutcome>,
+        evidence_due_by: Option<NaiveDateTime>,
+        resolved_at: Option<NaiveDateTime>,
+        created_by: Option<UserActivityItem>,
+        occurred_at: NaiveDateTime,
+    },
 }
 
 impl ActivityItem {
@@ -158,6 +171,14 @@ impl ActivityItem {
                 conn,
             )?);
         }
+        if activity_type.is_none() || activity_type == Some(ActivityType::Dispute) {
+            activity_items.append(&mut ActivityItem::load_disputes(
+                None,
+                Some(event_id),
+                Some(user_id),
+                conn,
+            )?);
+        }
         activity_items.sort_by_key(|activity| Reverse(activity.occurred_at()));
         Ok(activity_items)
     }
@@ -175,6 +196,7 @@ impl ActivityItem {
         activity_items.append(&mut ActivityItem::load_check_ins(Some(order.id), None, None, conn)?);
         activity_items.append(&mut ActivityItem::load_refunds(Some(order.id), None, None, conn)?);
         activity_items.append(&mut ActivityItem::load_notes(Some(order.id), None, None, conn)?);
+        activity_items.append(&mut ActivityItem::load_disputes(Some(order.id), None, None, conn)?);
         activity_items.sort_by_key(|activity| Reverse(activity.occurred_at()));
         Ok(activity_items)
     }
@@ -785,6 +807,11 @@ impl ActivityItem {
             pub company_fee_in_cents: i64,
             pub client_fee_in_cents: i64,
             pub refunded_quantity: i64,
+            pub pass_id: Option<Uuid>,
+            pub product_variant_id: Option<Uuid>,
+            pub redeemed_quantity: i64,
+            pub tax_rule_id: Option<Uuid>,
+            pub included_tax_in_cents: i64,
         };
 
         let refund_ids: Vec<Uuid> = refund_data.iter().map(|r| r.refund_id).collect();
@@ -814,6 +841,11 @@ impl ActivityItem {
                 order_items::company_fee_in_cents,
                 order_items::client_fee_in_cents,
                 order_items::refunded_quantity,
+                order_items::pass_id,
+                order_items::product_variant_id,
+                order_items::redeemed_quantity,
+                order_items::tax_rule_id,
+                order_items::included_tax_in_cents,
             ))
             .order_by(refunds::id)
             .load(conn)
@@ -849,6 +881,11 @@ impl ActivityItem {
                     company_fee_in_cents: item.company_fee_in_cents,
                     client_fee_in_cents: item.client_fee_in_cents,
                     refunded_quantity: item.refunded_quantity,
+                    pass_id: item.pass_id,
+                    product_variant_id: item.product_variant_id,
+                    redeemed_quantity: item.redeemed_quantity,
+                    tax_rule_id: item.tax_rule_id,
+                    included_tax_in_cents: item.included_tax_in_cents,
                 };
                 refund_items.push(RefundActivityItem {
                     id: item.id,
@@ -964,6 +1001,70 @@ impl ActivityItem {
         Ok(activity_items)
     }
 
+    fn load_disputes(
+        order_id: Option<Uuid>,
+        event_id: Option<Uuid>,
+        user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<ActivityItem>, DatabaseError> {
+        use schema::*;
+        if order_id.is_none() && event_id.is_none() || (event_id.is_some() && user_id.is_none()) {
+            return Err(DatabaseError::new(
+                ErrorCode::BusinessProcessError,
+                Some("Activity loading requires either order_id or event_id to be present".to_string()),
+            ));
+        }
+
+        let mut query = disputes::table
+            .inner_join(orders::table.on(disputes::order_id.eq(orders::id)))
+            .inner_join(order_items::table.on(order_items::order_id.eq(orders::id)))
+            .inner_join(events::table.on(order_items::event_id.eq(events::id.nullable())))
+            .into_boxed();
+
+        if let (Some(event_id), Some(user_id)) = (event_id, user_id) {
+            query = query.filter(events::id.eq(event_id)).filter(
+                orders::on_behalf_of_user_id
+                    .eq(Some(user_id))
+                    .or(orders::on_behalf_of_user_id.is_null().and(orders::user_id.eq(user_id))),
+            );
+        } else if let Some(order_id) = order_id {
+            query = query.filter(orders::id.eq(order_id));
+        }
+
+        let disputes: Vec<Dispute> = query
+            .select(disputes::all_columns)
+            .distinct()
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load disputes for activity")?;
+
+        let mut user_ids: Vec<Uuid> = disputes.iter().filter_map(|d| d.created_by).collect();
+        user_ids.sort();
+        user_ids.dedup();
+        let users = User::find_by_ids(&user_ids, conn)?;
+        let mut user_map: HashMap<Uuid, UserActivityItem> = HashMap::new();
+        for user in users {
+            user_map.insert(user.id, user.into());
+        }
+
+        let mut activity_items: Vec<ActivityItem> = Vec::new();
+        for dispute in disputes {
+            activity_items.push(ActivityItem::Dispute {
+                dispute_id: dispute.id,
+                order_id: dispute.order_id,
+                order_number: Order::parse_order_number(dispute.order_id),
+                amount_in_cents: dispute.amount_in_cents,
+                reason: dispute.reason,
+                status: dispute.status,
+                outcome: dispute.outcome,
+                evidence_due_by: dispute.evidence_due_by,
+                resolved_at: dispute.resolved_at,
+                created_by: dispute.created_by.and_then(|id| user_map.get(&id).cloned()),
+                occurred_at: dispute.created_at,
+            });
+        }
+        Ok(activity_items)
+    }
+
     pub fn occurred_at(&self) -> NaiveDateTime {
         match *self {
             ActivityItem::Purchase { occurred_at, .. } => occurred_at,
@@ -971,6 +1072,7 @@ impl ActivityItem {
             ActivityItem::CheckIn { occurred_at, .. } => occurred_at,
             ActivityItem::Refund { occurred_at, .. } => occurred_at,
             ActivityItem::Note { occurred_at, .. } => occurred_at,
+            ActivityItem::Dispute { occurred_at, .. } => occurred_at,
         }
     }
 }
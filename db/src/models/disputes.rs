@@ -0,0 +1,351 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{disputes, events, order_items, orders};
+use serde_with::rust::double_option;
+use utils::errors::*;
+use uuid::Uuid;
+use validators::{self, *};
+
+/// A payment dispute (chargeback) raised by the purchaser's card issuer against an order
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Order)]
+#[belongs_to(Payment)]
+#[table_name = "disputes"]
+pub struct Dispute {
+    pub id: Uuid,
+    pub order_id: Uuid,
+    pub payment_id: Uuid,
+    pub settlement_adjustment_id: Option<Uuid>,
+    pub created_by: Option<Uuid>,
+    pub external_reference: Option<String>,
+    pub amount_in_cents: i64,
+    pub reason: Option<String>,
+    pub status: DisputeStatus,
+    pub outcome: Option<DisputeOutcome>,
+    pub evidence_due_by: Option<NaiveDateTime>,
+    pub resolved_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(AsChangeset, Clone, Default, Deserialize, Serialize)]
+#[table_name = "disputes"]
+pub struct DisputeEditableAttributes {
+    pub status: Option<DisputeStatus>,
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub reason: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub evidence_due_by: Option<Option<NaiveDateTime>>,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "disputes"]
+pub struct NewDispute {
+    pub order_id: Uuid,
+    pub payment_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub external_reference: Option<String>,
+    pub amount_in_cents: i64,
+    pub reason: Option<String>,
+    pub evidence_due_by: Option<NaiveDateTime>,
+}
+
+impl NewDispute {
+    /// Records the dispute and adds its chargeback to the organization's open settlement. When
+    /// `nullify_tickets` is set the order's unredeemed tickets are voided as well.
+    pub fn commit(&self, nullify_tickets: bool, conn: &PgConnection) -> Result<Dispute, DatabaseError> {
+        self.validate(conn)?;
+
+        let dispute: Dispute = diesel::insert_into(disputes::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create dispute")?;
+
+        let nullified_ticket_ids: Vec<Uuid> = if nullify_tickets {
+            TicketInstance::nullify_for_order(dispute.order_id, dispute.created_by, conn)?
+                .iter()
+                .map(|t| t.id)
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let dispute = dispute.apply_chargeback(None, conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::DisputeCreated,
+            "Dispute created".to_string(),
+            Tables::Orders,
+            Some(dispute.order_id),
+            dispute.created_by,
+            Some(json!({
+                "dispute_id": dispute.id,
+                "payment_id": dispute.payment_id,
+                "amount_in_cents": dispute.amount_in_cents,
+                "reason": dispute.reason,
+                "nullified_ticket_ids": nullified_ticket_ids,
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(dispute)
+    }
+
+    fn validate(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let payment = Payment::find(self.payment_id, conn)?;
+        let mut validation_errors = validators::append_validation_error(
+            Ok(()),
+            "amount_in_cents",
+            validators::validate_greater_than_or_equal(
+                self.amount_in_cents,
+                0,
+                "number_must_be_positive",
+                "Amount must be positive",
+            ),
+        );
+        if payment.order_id != self.order_id {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "payment_id",
+                Err(create_validation_error(
+                    "invalid",
+                    "Payment does not belong to this order",
+                )),
+            );
+        }
+        if self.amount_in_cents > payment.amount {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "amount_in_cents",
+                Err(create_validation_error(
+                    "dispute_amount_exceeds_payment",
+                    "Amount cannot exceed the disputed payment",
+                )),
+            );
+        }
+        Ok(validation_errors?)
+    }
+}
+
+impl Dispute {
+    pub fn create(
+        order_id: Uuid,
+        payment_id: Uuid,
+        amount_in_cents: i64,
+        reason: Option<String>,
+        external_reference: Option<String>,
+        evidence_due_by: Option<NaiveDateTime>,
+        created_by: Option<Uuid>,
+    ) -> NewDispute {
+        NewDispute {
+            order_id,
+            payment_id,
+            created_by,
+            external_reference,
+            amount_in_cents,
+            reason,
+            evidence_due_by,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<Dispute, DatabaseError> {
+        disputes::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading dispute")
+    }
+
+    pub fn find_by_external_reference(
+        external_reference: &str,
+        conn: &PgConnection,
+    ) -> Result<Option<Dispute>, DatabaseError> {
+        disputes::table
+            .filter(disputes::external_reference.eq(external_reference))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Error loading dispute")
+    }
+
+    pub fn find_for_order(order_id: Uuid, conn: &PgConnection) -> Result<Vec<Dispute>, DatabaseError> {
+        disputes::table
+            .filter(disputes::order_id.eq(order_id))
+            .order_by(disputes::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load disputes for order")
+    }
+
+    pub fn order(&self, conn: &PgConnection) -> Result<Order, DatabaseError> {
+        Order::find(self.order_id, conn)
+    }
+
+    pub fn update(
+        &self,
+        attributes: DisputeEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Dispute, DatabaseError> {
+        if self.status == DisputeStatus::Closed {
+            return DatabaseError::business_process_error("Dispute has already been closed");
+        }
+        if attributes.status == Some(DisputeStatus::Closed) {
+            return DatabaseError::validation_error("status", "Disputes can only be closed by resolving them");
+        }
+
+        let result = diesel::update(self)
+            .set((&attributes, disputes::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update dispute")?;
+
+        DomainEvent::create(
+            DomainEventTypes::DisputeUpdated,
+            "Dispute updated".to_string(),
+            Tables::Orders,
+            Some(self.order_id),
+            current_user_id,
+            Some(json!({ "dispute_id": self.id, "attributes": &attributes })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    /// Closes the dispute with the given outcome. A won dispute credits the chargeback back to the
+    /// organization through its open settlement.
+    pub fn resolve(
+        &self,
+        outcome: DisputeOutcome,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Dispute, DatabaseError> {
+        if self.status == DisputeStatus::Closed {
+            return DatabaseError::business_process_error("Dispute has already been closed");
+        }
+
+        let result: Dispute = diesel::update(self)
+            .set((
+                disputes::status.eq(DisputeStatus::Closed),
+                disputes::outcome.eq(Some(outcome)),
+                disputes::resolved_at.eq(dsl::now.nullable()),
+                disputes::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not resolve dispute")?;
+
+        let mut reversal_adjustment_id = None;
+        if outcome == DisputeOutcome::Won && self.settlement_adjustment_id.is_some() {
+            let order = self.order(conn)?;
+            if let Some(settlement) = Dispute::open_settlement_for_order(&order, conn)? {
+                reversal_adjustment_id = Some(
+                    SettlementAdjustment::create(
+                        settlement.id,
+                        SettlementAdjustmentTypes::ManualCredit,
+                        Some(format!("Chargeback reversed for order {}", order.id)),
+                        self.amount_in_cents,
+                        order.currency.clone(),
+                    )
+                    .commit(conn)?
+                    .id,
+                );
+            }
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::DisputeResolved,
+            "Dispute resolved".to_string(),
+            Tables::Orders,
+            Some(self.order_id),
+            current_user_id,
+            Some(json!({
+                "dispute_id": self.id,
+                "outcome": outcome,
+                "settlement_adjustment_id": reversal_adjustment_id,
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    /// Adds chargebacks that could not be applied when their dispute was created (no settlement was
+    /// open yet) to the given settlement
+    pub fn apply_pending_chargebacks(settlement: &Settlement, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let pending_disputes: Vec<Dispute> = disputes::table
+            .inner_join(orders::table.on(orders::id.eq(disputes::order_id)))
+            .inner_join(order_items::table.on(order_items::order_id.eq(orders::id)))
+            .inner_join(events::table.on(order_items::event_id.eq(events::id.nullable())))
+            .filter(events::organization_id.eq(settlement.organization_id))
+            .filter(disputes::settlement_adjustment_id.is_null())
+            .filter(
+                disputes::outcome
+                    .is_null()
+                    .or(disputes::outcome.eq(Some(DisputeOutcome::Lost))),
+            )
+            .select(disputes::all_columns)
+            .distinct()
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load pending chargebacks")?;
+
+        for dispute in pending_disputes {
+            dispute.apply_chargeback(Some(settlement), conn)?;
+        }
+        Ok(())
+    }
+
+    fn apply_chargeback(self, settlement: Option<&Settlement>, conn: &PgConnection) -> Result<Dispute, DatabaseError> {
+        let order = self.order(conn)?;
+        let settlement = match settlement {
+            Some(settlement) => Dispute::single_organization_for_order(&order, conn)?
+                .filter(|organization| organization.id == settlement.organization_id)
+                .map(|_| settlement.clone()),
+            None => Dispute::open_settlement_for_order(&order, conn)?,
+        };
+        let settlement = match settlement {
+            Some(settlement) => settlement,
+            None => return Ok(self),
+        };
+
+        let adjustment = SettlementAdjustment::create(
+            settlement.id,
+            SettlementAdjustmentTypes::Chargeback,
+            Some(format!(
+                "Chargeback for order {}: {}",
+                order.id,
+                self.reason.clone().unwrap_or_else(|| "No reason given".to_string())
+            )),
+            self.amount_in_cents,
+            order.currency.clone(),
+        )
+        .commit(conn)?;
+
+        diesel::update(&self)
+            .set((
+                disputes::settlement_adjustment_id.eq(Some(adjustment.id)),
+                disputes::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update dispute")
+    }
+
+    /// Chargebacks are only settled automatically for single organization orders as the disputed
+    /// amount cannot be attributed when several organizations share an order
+    fn open_settlement_for_order(order: &Order, conn: &PgConnection) -> Result<Option<Settlement>, DatabaseError> {
+        match Dispute::single_organization_for_order(order, conn)? {
+            Some(organization) => Settlement::find_open_for_organization(&organization, conn),
+            None => Ok(None),
+        }
+    }
+
+    fn single_organization_for_order(
+        order: &Order,
+        conn: &PgConnection,
+    ) -> Result<Option<Organization>, DatabaseError> {
+        let mut organizations = order.organizations(conn)?;
+        if organizations.len() != 1 {
+            return Ok(None);
+        }
+        Ok(organizations.pop())
+    }
+}
@@ -166,6 +166,55 @@ impl DomainAction {
             .to_db_error(ErrorCode::QueryError, "Error loading domain actions")
     }
 
+    /// Ensures a pending action of `domain_action_type` exists for the resource, scheduled no later
+    /// than `run_at` (or immediately if `None`). An existing pending action is brought forward rather
+    /// than duplicated. Busy actions are ignored so an action can reschedule itself while running.
+    pub fn schedule_for_resource(
+        domain_action_type: DomainActionTypes,
+        main_table: Tables,
+        main_table_id: Uuid,
+        run_at: Option<NaiveDateTime>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        let now = Utc::now().naive_utc();
+        let run_at = match run_at {
+            Some(run_at) if run_at > now => run_at,
+            _ => now,
+        };
+
+        let upcoming_action = DomainAction::find_by_resource(
+            Some(main_table),
+            Some(main_table_id),
+            domain_action_type,
+            DomainActionStatus::Pending,
+            conn,
+        )?
+        .into_iter()
+        .find(|action| action.blocked_until <= now);
+
+        match upcoming_action {
+            Some(action) => {
+                if action.scheduled_at > run_at {
+                    action.set_scheduled_at(run_at, conn)?;
+                }
+            }
+            None => {
+                let mut action = DomainAction::create(
+                    None,
+                    domain_action_type,
+                    None,
+                    json!({}),
+                    Some(main_table),
+                    Some(main_table_id),
+                );
+                action.schedule_at(run_at);
+                action.commit(conn)?;
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn find(id: Uuid, conn: &PgConnection) -> Result<DomainAction, DatabaseError> {
         DatabaseError::wrap(
             ErrorCode::QueryError,
@@ -0,0 +1,364 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::dsl::select;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::dynamic_pricing_strategies;
+use utils::errors::*;
+use uuid::Uuid;
+use validators::{self, *};
+
+/// How far sell-through may drift from the elapsed share of the sales window before the
+/// price is adjusted
+pub const DYNAMIC_PRICING_PACE_TOLERANCE: f64 = 0.1;
+pub const DYNAMIC_PRICING_DEFAULT_ADJUSTMENT_PERCENT: i32 = 10;
+pub const DYNAMIC_PRICING_DEFAULT_ADJUSTMENT_INTERVAL_MINUTES: i32 = 60;
+
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(TicketType)]
+#[table_name = "dynamic_pricing_strategies"]
+pub struct DynamicPricingStrategy {
+    pub id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub floor_price_in_cents: i64,
+    pub ceiling_price_in_cents: i64,
+    pub adjustment_percent: i32,
+    pub adjustment_interval_minutes: i32,
+    pub is_active: bool,
+    pub last_adjusted_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(AsChangeset, Clone, Default, Deserialize, Serialize)]
+#[table_name = "dynamic_pricing_strategies"]
+pub struct DynamicPricingStrategyEditableAttributes {
+    pub floor_price_in_cents: Option<i64>,
+    pub ceiling_price_in_cents: Option<i64>,
+    pub adjustment_percent: Option<i32>,
+    pub adjustment_interval_minutes: Option<i32>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "dynamic_pricing_strategies"]
+pub struct NewDynamicPricingStrategy {
+    pub ticket_type_id: Uuid,
+    pub floor_price_in_cents: i64,
+    pub ceiling_price_in_cents: i64,
+    pub adjustment_percent: i32,
+    pub adjustment_interval_minutes: i32,
+}
+
+impl NewDynamicPricingStrategy {
+    pub fn commit(
+        self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<DynamicPricingStrategy, DatabaseError> {
+        DynamicPricingStrategy::validate_values(
+            self.floor_price_in_cents,
+            self.ceiling_price_in_cents,
+            self.adjustment_percent,
+            self.adjustment_interval_minutes,
+        )?;
+
+        let result: DynamicPricingStrategy = diesel::insert_into(dynamic_pricing_strategies::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create dynamic pricing strategy")?;
+
+        DomainEvent::create(
+            DomainEventTypes::DynamicPricingStrategyCreated,
+            "Dynamic pricing strategy created".to_string(),
+            Tables::DynamicPricingStrategies,
+            Some(result.id),
+            current_user_id,
+            Some(json!(&self)),
+        )
+        .commit(conn)?;
+
+        result.schedule_adjustment(None, conn)?;
+
+        Ok(result)
+    }
+}
+
+impl DynamicPricingStrategy {
+    pub fn create(
+        ticket_type_id: Uuid,
+        floor_price_in_cents: i64,
+        ceiling_price_in_cents: i64,
+        adjustment_percent: i32,
+        adjustment_interval_minutes: i32,
+    ) -> NewDynamicPricingStrategy {
+        NewDynamicPricingStrategy {
+            ticket_type_id,
+            floor_price_in_cents,
+            ceiling_price_in_cents,
+            adjustment_percent,
+            adjustment_interval_minutes,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<DynamicPricingStrategy, DatabaseError> {
+        dynamic_pricing_strategies::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading dynamic pricing strategy")
+    }
+
+    pub fn find_by_ticket_type_id(
+        ticket_type_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<DynamicPricingStrategy>, DatabaseError> {
+        dynamic_pricing_strategies::table
+            .filter(dynamic_pricing_strategies::ticket_type_id.eq(ticket_type_id))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Error loading dynamic pricing strategy")
+    }
+
+    pub fn update(
+        &self,
+        attributes: DynamicPricingStrategyEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<DynamicPricingStrategy, DatabaseError> {
+        DynamicPricingStrategy::validate_values(
+            attributes.floor_price_in_cents.unwrap_or(self.floor_price_in_cents),
+            attributes.ceiling_price_in_cents.unwrap_or(self.ceiling_price_in_cents),
+            attributes.adjustment_percent.unwrap_or(self.adjustment_percent),
+            attributes
+                .adjustment_interval_minutes
+                .unwrap_or(self.adjustment_interval_minutes),
+        )?;
+
+        let result: DynamicPricingStrategy = diesel::update(self)
+            .set((&attributes, dynamic_pricing_strategies::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update dynamic pricing strategy")?;
+
+        DomainEvent::create(
+            DomainEventTypes::DynamicPricingStrategyUpdated,
+            "Dynamic pricing strategy updated".to_string(),
+            Tables::DynamicPricingStrategies,
+            Some(self.id),
+            current_user_id,
+            Some(json!(&attributes)),
+        )
+        .commit(conn)?;
+
+        if result.is_active {
+            result.schedule_adjustment(None, conn)?;
+        } else if let Some(action) = DomainAction::upcoming_domain_action(
+            Some(Tables::TicketTypes),
+            Some(result.ticket_type_id),
+            DomainActionTypes::UpdateDynamicPricing,
+            conn,
+        )? {
+            action.set_cancelled(conn)?;
+        }
+
+        Ok(result)
+    }
+
+    /// Moves the price one step towards demand. When sales are ahead of the elapsed share of the
+    /// sales window the price is raised by `adjustment_percent`, when they are behind it is lowered.
+    /// The result is always kept between the floor and ceiling prices.
+    pub fn calculate_price(&self, current_price_in_cents: i64, sell_through: f64, time_elapsed: f64) -> i64 {
+        let pace = sell_through - time_elapsed;
+        let step = (current_price_in_cents as f64 * self.adjustment_percent as f64 / 100.0).round() as i64;
+
+        let price_in_cents = if pace > DYNAMIC_PRICING_PACE_TOLERANCE {
+            current_price_in_cents + step
+        } else if pace < -DYNAMIC_PRICING_PACE_TOLERANCE {
+            current_price_in_cents - step
+        } else {
+            current_price_in_cents
+        };
+
+        price_in_cents
+            .max(self.floor_price_in_cents)
+            .min(self.ceiling_price_in_cents)
+    }
+
+    /// Recalculates the ticket type's price. A price change ends the current ticket pricing period
+    /// now and starts a new period at the new price, so carts keep the price they were quoted.
+    pub fn adjust_pricing(&self, conn: &PgConnection) -> Result<Option<TicketPricing>, DatabaseError> {
+        if !self.is_active {
+            return Ok(None);
+        }
+
+        let ticket_type = TicketType::find(self.ticket_type_id, conn)?;
+        if ticket_type.status == TicketTypeStatus::Cancelled || ticket_type.deleted_at.is_some() {
+            return Ok(None);
+        }
+
+        // Ticket pricing periods are resolved against database time
+        let now: NaiveDateTime = select(dsl::now)
+            .get_result(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load current time")?;
+        let sales_start = ticket_type.start_date(conn)?;
+        let sales_end = ticket_type.end_date(conn)?;
+        if now >= sales_end {
+            return Ok(None);
+        }
+
+        diesel::update(self)
+            .set((
+                dynamic_pricing_strategies::last_adjusted_at.eq(now),
+                dynamic_pricing_strategies::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update dynamic pricing strategy")?;
+
+        let next_adjustment_at = now + Duration::minutes(self.adjustment_interval_minutes as i64);
+        if next_adjustment_at < sales_end {
+            self.schedule_adjustment(Some(next_adjustment_at), conn)?;
+        }
+
+        let current_pricing =
+            match TicketPricing::get_current_ticket_pricing(ticket_type.id, false, false, conn).optional()? {
+                Some(ticket_pricing) => ticket_pricing,
+                None => return Ok(None),
+            };
+        if current_pricing.status != TicketPricingStatus::Published || current_pricing.start_date >= now {
+            return Ok(None);
+        }
+
+        let ticket_count = ticket_type.valid_ticket_count(conn)?;
+        let sell_through = if ticket_count == 0 {
+            0.0
+        } else {
+            (ticket_count - ticket_type.valid_unsold_ticket_count(conn)?) as f64 / ticket_count as f64
+        };
+        let sales_window = (sales_end - sales_start).num_seconds();
+        let time_elapsed = if sales_window <= 0 {
+            1.0
+        } else {
+            ((now - sales_start).num_seconds() as f64 / sales_window as f64)
+                .max(0.0)
+                .min(1.0)
+        };
+
+        let price_in_cents = self.calculate_price(current_pricing.price_in_cents, sell_through, time_elapsed);
+        if price_in_cents == current_pricing.price_in_cents {
+            return Ok(None);
+        }
+
+        current_pricing.update(
+            TicketPricingEditableAttributes {
+                end_date: Some(now),
+                ..Default::default()
+            },
+            None,
+            conn,
+        )?;
+        let new_pricing = TicketPricing::create(
+            ticket_type.id,
+            current_pricing.name.clone(),
+            now,
+            current_pricing.end_date,
+            price_in_cents,
+            current_pricing.is_box_office_only,
+            Some(TicketPricingStatus::Published),
+            Some(current_pricing.id),
+        )
+        .commit(None, conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::TicketPricingUpdated,
+            format!(
+                "Ticket pricing '{}' adjusted from {} to {}",
+                new_pricing.name, current_pricing.price_in_cents, price_in_cents
+            ),
+            Tables::TicketPricing,
+            Some(new_pricing.id),
+            None,
+            Some(json!({
+                "dynamic_pricing_strategy_id": self.id,
+                "previous_ticket_pricing_id": current_pricing.id,
+                "previous_price_in_cents": current_pricing.price_in_cents,
+                "price_in_cents": price_in_cents,
+                "sell_through": sell_through,
+                "time_elapsed": time_elapsed
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(Some(new_pricing))
+    }
+
+    pub fn schedule_adjustment(&self, run_at: Option<NaiveDateTime>, conn: &PgConnection) -> Result<(), DatabaseError> {
+        DomainAction::schedule_for_resource(
+            DomainActionTypes::UpdateDynamicPricing,
+            Tables::TicketTypes,
+            self.ticket_type_id,
+            run_at,
+            conn,
+        )
+    }
+
+    fn validate_values(
+        floor_price_in_cents: i64,
+        ceiling_price_in_cents: i64,
+        adjustment_percent: i32,
+        adjustment_interval_minutes: i32,
+    ) -> Result<(), DatabaseError> {
+        let validation_errors = validators::append_validation_error(
+            Ok(()),
+            "floor_price_in_cents",
+            validators::validate_greater_than_or_equal(
+                floor_price_in_cents,
+                0,
+                "number_must_be_positive",
+                "Floor price must be positive",
+            ),
+        );
+        let validation_errors = validators::append_validation_error(
+            validation_errors,
+            "ceiling_price_in_cents",
+            validators::validate_greater_than_or_equal(
+                ceiling_price_in_cents,
+                floor_price_in_cents,
+                "ceiling_price_below_floor_price",
+                "Ceiling price must not be below the floor price",
+            ),
+        );
+        let validation_errors = validators::append_validation_error(
+            validation_errors,
+            "adjustment_percent",
+            validators::validate_greater_than(
+                adjustment_percent,
+                0,
+                "number_must_be_positive",
+                "Adjustment percent must be greater than zero",
+            ),
+        );
+        let validation_errors = validators::append_validation_error(
+            validation_errors,
+            "adjustment_percent",
+            validators::validate_less_than_or_equal(
+                adjustment_percent,
+                100,
+                "adjustment_percent_too_large",
+                "Adjustment percent must not exceed 100",
+            ),
+        );
+        let validation_errors = validators::append_validation_error(
+            validation_errors,
+            "adjustment_interval_minutes",
+            validators::validate_greater_than(
+                adjustment_interval_minutes,
+                0,
+                "number_must_be_positive",
+                "Adjustment interval must be greater than zero",
+            ),
+        );
+
+        Ok(validation_errors?)
+    }
+}
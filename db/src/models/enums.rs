@@ -92,21 +92,33 @@ macro_rules! string_enum {
     }
 }
 
-define_enum! { ActivityType [Purchase, Transfer, CheckIn, Refund, Note]}
+define_enum! { ActivityType [Purchase, Transfer, CheckIn, Refund, Note, Dispute]}
 define_enum! { AnnouncementEngagementAction [Dismiss] }
 define_enum! { AssetStatus [Unsynced] }
+define_enum! { AttendeeDetails [Name, BirthDate, Email] }
 define_enum! { BroadcastAudience [ PeopleAtTheEvent, TicketHolders, OrganizationMembers ]}
 define_enum! { CartItemStatus [CodeExpired, HoldExpired, TicketNullified, TicketNotReserved, Valid] }
 define_enum! { CheckInSource [GuestList, Scanned, LootBox] }
 define_enum! { CodeTypes [Access, Discount] }
 define_enum! { CommunicationChannelType [Email, Sms, Push, Webhook]}
 define_enum! { CommunicationType [EmailTemplate, Sms, Push, Webhook]}
+define_enum! { DisputeOutcome [Won, Lost] }
+define_enum! { DisputeStatus [Open, UnderReview, Closed] }
 define_enum! { DomainEventTypes [
+    AccessZoneCreated,
+    AccessZoneUpdated,
+    AccessZoneUserAssigned,
+    AccessZoneUserUnassigned,
     AnnouncementCreated,
     AnnouncementDeleted,
     CodeCreated,
     CodeDeleted,
     CodeUpdated,
+    DisputeCreated,
+    DisputeResolved,
+    DisputeUpdated,
+    DynamicPricingStrategyCreated,
+    DynamicPricingStrategyUpdated,
     EventArtistCreated,
     EventArtistAdded,
     EventCancelled,
@@ -115,18 +127,36 @@ define_enum! { DomainEventTypes [
     EventDeleted,
     EventInterestCreated,
     EventPublished,
+    EventRefundBatchCompleted,
+    EventRefundBatchCreated,
     EventReportSubscriberCreated,
     EventReportSubscriberDeleted,
+    EventRescheduleResponded,
+    EventRescheduled,
+    EventTimeSlotCreated,
+    EventTimeSlotDeleted,
     EventUpdated,
     EventUnpublished,
     ExternalLoginCreated,
     ExternalLoginDeleted,
     FeeScheduleCreated,
     GenresUpdated,
+    GiftCardBalanceUpdated,
+    GiftCardCreated,
     HoldAutomaticallyReleased,
     HoldCreated,
     HoldDeleted,
     HoldQuantityChanged,
+    InstallmentPaid,
+    InstallmentPaymentFailed,
+    InstallmentPlanCancelled,
+    InstallmentPlanCompleted,
+    InstallmentPlanCreated,
+    InstallmentPlanDefaulted,
+    InstallmentPolicyCreated,
+    InstallmentPolicyUpdated,
+    InventoryPoolCreated,
+    InventoryPoolUpdated,
     OrderBehalfOfUserChanged,
     OrderCompleted,
     OrderCreated,
@@ -138,6 +168,12 @@ define_enum! { DomainEventTypes [
     OrganizationCreated,
     NoteCreated,
     NoteDeleted,
+    PassCancelled,
+    PassCreated,
+    PassEventAdded,
+    PassEventRemoved,
+    PassPublished,
+    PassUpdated,
     PaymentCancelled,
     PaymentCreated,
     PaymentCompleted,
@@ -146,6 +182,11 @@ define_enum! { DomainEventTypes [
     PaymentMethodCreated,
     PaymentMethodUpdated,
     PaymentUpdated,
+    ProductCreated,
+    ProductUpdated,
+    ProductVariantCreated,
+    ProductVariantUpdated,
+    ProductVoucherRedeemed,
     UserCreated,
     UserDisabled,
     UserLogin,
@@ -154,7 +195,15 @@ define_enum! { DomainEventTypes [
     LostPassword,
     PurchaseCompleted,
     PushNotificationTokenCreated,
+    RefundPolicyCreated,
+    RefundPolicyUpdated,
+    RefundRequestApproved,
+    RefundRequestCreated,
+    RefundRequestDenied,
     SettlementReportProcessed,
+    StoreCreditTransactionCreated,
+    TaxRuleCreated,
+    TaxRuleUpdated,
     TransferTicketDripSourceSent,
     TransferTicketDripDestinationSent,
     TransferTicketCancelled,
@@ -178,22 +227,32 @@ define_enum! { DomainEventTypes [
     TicketTypeCreated,
     TicketTypeSalesStarted,
     TicketTypeSoldOut,
-    TicketTypeUpdated
+    TicketTypeUpdated,
+    WaitlistEntryCancelled,
+    WaitlistEntryCreated,
+    WaitlistOfferExpired,
+    WaitlistOfferMade
 ]}
 define_enum! { DomainActionTypes [
     BroadcastPushNotification,
     // Email/SMS/Push Communication
     Communication,
+    DefaultInstallmentPlan,
     FinalizeSettlements,
     PaymentProviderIPN,
+    ProcessEventRefundBatch,
+    ProcessInstallmentPayment,
     ProcessSettlementReport,
     ProcessTransferDrip,
+    ProcessWaitlist,
     RegenerateDripActions,
     ReleaseHoldInventory,
     RetargetAbandonedOrders,
     SendAutomaticReportEmails,
+    SendEventRescheduledCommunications,
     SendPurchaseCompletedCommunication,
     SubmitSitemapToSearchEngines,
+    UpdateDynamicPricing,
     UpdateGenres
 ]}
 define_enum! { BroadcastStatus [Pending, InProgress, Completed, Cancelled]}
@@ -204,44 +263,55 @@ define_enum! { EmailProvider [Sendgrid, CustomerIo]}
 define_enum! { Environment [Development, Production, Staging, Test]}
 define_enum! { EventStatus [Draft,Closed,Published,Offline]}
 define_enum! { EventSearchSortField [ Name, EventStart]}
+define_enum! { EventRefundBatchOrderStatus [Pending, InProgress, Refunded, Failed]}
+define_enum! { EventRefundBatchStatus [Pending, InProgress, Completed]}
+define_enum! { EventRescheduleResponseTypes [Keep, Refund]}
 define_enum! { EventOverrideStatus [PurchaseTickets,SoldOut,OnSaleSoon,TicketsAtTheDoor,Free,Rescheduled,Cancelled,OffSale,Ended]}
 define_enum! { EventTypes [ Music, Conference, Art, Culinary, Comedy, Sports, Tech, Other]}
 define_enum! { ExternalPaymentType [Cash, CreditCard, Voucher]}
 define_enum! { FanSortField [FirstName, LastName, Email, Phone, OrganizationId, UserCreated, Orders, FirstOrder, LastOrder, Revenue, FirstInteracted, LastInteracted] }
 define_enum! { HistoryType [Purchase]}
 define_enum! { HoldTypes [Discount, Comp] }
+define_enum! { InstallmentPlanStatus [Pending, Active, Completed, Defaulted, Cancelled] }
+define_enum! { InstallmentStatus [Scheduled, Paid, Failed] }
 define_enum! { ListingStatus [Pending, Published] }
 define_enum! { MarketplaceAccountStatus [ Pending, Linked ]}
 define_enum! { OrderStatus [Cancelled, Draft, Paid, PendingPayment] }
-define_enum! { OrderItemTypes [Tickets, PerUnitFees, EventFees, Discount, CreditCardFees]}
+define_enum! { OrderItemTypes [Tickets, PerUnitFees, EventFees, Discount, CreditCardFees, Products, Tax, NameChangeFees]}
 define_enum! { OrderTypes [Cart, BackOffice] }
-define_enum! { PaymentMethods [CreditCard, External, Free, Provider] }
-define_enum! { PaymentProviders [External, Globee, Free, Stripe] }
+define_enum! { PassStatus [Draft, Published, Cancelled] }
+define_enum! { PaymentMethods [CreditCard, External, Free, GiftCard, Provider, StoreCredit] }
+define_enum! { PaymentProviders [External, Globee, Free, Stripe, GiftCard, StoreCredit, Mock] }
 define_enum! { PaymentStatus [Authorized, Completed, Requested, Refunded, Unpaid, PendingConfirmation, Cancelled, Draft, Unknown, PendingIpn] }
 define_enum! { PastOrUpcoming [Past,Upcoming]}
 define_enum! { Platforms [Web, App, BoxOffice]}
+define_enum! { ProductStatus [Draft, Published, Archived] }
+define_enum! { RefundApprovalTypes [Automatic, Manual] }
+define_enum! { RefundRequestStatus [Pending, Approved, Denied] }
 define_enum! { ReportTypes [TicketCounts]}
 define_enum! { Roles [Admin, DoorPerson, OrgAdmin, OrgBoxOffice, OrgMember, OrgOwner, PrismIntegration, Promoter, PromoterReadOnly, User, Super] }
 define_enum! { SettlementStatus[PendingSettlement, FinalizedSettlement] }
 define_enum! { SettlementTypes [Rolling, PostEvent]}
 define_enum! { SettlementAdjustmentTypes [ManualCredit, ManualDeduction, Chargeback]}
-define_enum! { SettlementEntryTypes [EventFees, TicketType]}
+define_enum! { SettlementEntryTypes [EventFees, Products, TicketType, NameChangeFees]}
 define_enum! { SlugTypes[ Event, Organization, Venue, City, Genre, CityGenre ] }
 define_enum! { SortingDir[ Asc, Desc ] }
 define_enum! { SourceOrDestination [Destination,Source]}
 define_enum! { Tables [
-    Announcements, Artists, Broadcasts, Codes, DomainEventPublishers, Events, EventArtists, EventReportSubscribers, ExternalLogins, FeeSchedules,
-    Holds, Orders, Organizations, Notes, Payments, PaymentMethods, PushNotificationTokens, TemporaryUsers, TicketInstances, TicketTypes,
-    TicketPricing, Transfers, Users, Venues, Genres
+    AccessZones, Announcements, Artists, Broadcasts, Codes, DomainEventPublishers, DynamicPricingStrategies, Events, EventArtists, EventReportSubscribers, EventTimeSlots, ExternalLogins, FeeSchedules,
+    GiftCards, Holds, InstallmentPlans, InventoryPools, Orders, Organizations, Notes, Passes, Payments, PaymentMethods, Products, ProductVariants, PushNotificationTokens, StoreCreditTransactions, TaxRules, TemporaryUsers, TicketInstances, TicketTypes,
+    TicketPricing, Transfers, Users, Venues, Genres, WaitlistEntries
 ] }
 define_enum! { TicketInstanceStatus [Available, Reserved, Purchased, Redeemed, Nullified]}
 define_enum! { TicketPricingStatus [Published, Deleted, Default] }
+define_enum! { TicketScanDirection [In, Out] }
 define_enum! { TicketTypeEndDateType [DoorTime, EventEnd, EventStart, Manual] }
 define_enum! { TicketTypeStatus [NoActivePricing, Published, SoldOut, OnSaleSoon, SaleEnded, Cancelled, Deleted] }
 define_enum! { TicketTypeType [ Token, LootBox ]}
 define_enum! { TicketTypeVisibility [ Always, Hidden, WhenAvailable ]}
 define_enum! { TransferMessageType [Email, Phone] }
 define_enum! { TransferStatus [Pending, Cancelled, Completed, EventEnded] }
+define_enum! { WaitlistEntryStatus [Waiting, Offered, Claimed, Expired, Cancelled] }
 define_enum! { WebhookAdapters [CustomerIo]}
 
 impl Roles {
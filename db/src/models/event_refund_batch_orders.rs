@@ -0,0 +1,78 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::event_refund_batch_orders;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Outcome of refunding a single order as part of an event refund batch
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(EventRefundBatch)]
+#[table_name = "event_refund_batch_orders"]
+pub struct EventRefundBatchOrder {
+    pub id: Uuid,
+    pub event_refund_batch_id: Uuid,
+    pub order_id: Uuid,
+    pub status: EventRefundBatchOrderStatus,
+    pub refund_id: Option<Uuid>,
+    pub amount_refunded_in_cents: Option<i64>,
+    pub error: Option<String>,
+    pub attempted_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "event_refund_batch_orders"]
+pub struct NewEventRefundBatchOrder {
+    pub event_refund_batch_id: Uuid,
+    pub order_id: Uuid,
+}
+
+impl EventRefundBatchOrder {
+    /// Claims the order before its refund is sent to the payment processor so that no other run
+    /// refunds it again. Orders left in progress by an interrupted run need to be checked manually.
+    pub fn mark_in_progress(&self, conn: &PgConnection) -> Result<EventRefundBatchOrder, DatabaseError> {
+        diesel::update(self)
+            .set((
+                event_refund_batch_orders::status.eq(EventRefundBatchOrderStatus::InProgress),
+                event_refund_batch_orders::attempted_at.eq(dsl::now.nullable()),
+                event_refund_batch_orders::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not mark order refund in progress")
+    }
+
+    pub fn mark_refunded(
+        &self,
+        refund_id: Uuid,
+        amount_refunded_in_cents: i64,
+        conn: &PgConnection,
+    ) -> Result<EventRefundBatchOrder, DatabaseError> {
+        diesel::update(self)
+            .set((
+                event_refund_batch_orders::status.eq(EventRefundBatchOrderStatus::Refunded),
+                event_refund_batch_orders::refund_id.eq(Some(refund_id)),
+                event_refund_batch_orders::amount_refunded_in_cents.eq(Some(amount_refunded_in_cents)),
+                event_refund_batch_orders::error.eq(None::<String>),
+                event_refund_batch_orders::attempted_at.eq(dsl::now.nullable()),
+                event_refund_batch_orders::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not mark order refunded")
+    }
+
+    pub fn mark_failed(&self, error: &str, conn: &PgConnection) -> Result<EventRefundBatchOrder, DatabaseError> {
+        diesel::update(self)
+            .set((
+                event_refund_batch_orders::status.eq(EventRefundBatchOrderStatus::Failed),
+                event_refund_batch_orders::error.eq(Some(error)),
+                event_refund_batch_orders::attempted_at.eq(dsl::now.nullable()),
+                event_refund_batch_orders::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not mark order refund failed")
+    }
+}
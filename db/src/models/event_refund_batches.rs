@@ -0,0 +1,262 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl::count;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{event_refund_batch_orders, event_refund_batches, order_items, orders};
+use utils::dates;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Orders refunded each time the batch is processed
+pub const EVENT_REFUND_BATCH_SIZE: i64 = 25;
+/// Delay between processing runs to throttle calls to the payment processors
+pub const EVENT_REFUND_BATCH_DELAY_SECONDS: i64 = 30;
+
+/// Refunds every paid order for a cancelled event. Orders are refunded a few at a time by the
+/// `ProcessEventRefundBatch` domain action so that processing can resume after a failure.
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Event)]
+#[table_name = "event_refund_batches"]
+pub struct EventRefundBatch {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub status: EventRefundBatchStatus,
+    pub refund_fees: bool,
+    pub created_by: Uuid,
+    pub completed_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "event_refund_batches"]
+pub struct NewEventRefundBatch {
+    pub event_id: Uuid,
+    pub refund_fees: bool,
+    pub created_by: Uuid,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct EventRefundBatchSummary {
+    pub total_orders: i64,
+    pub pending_orders: i64,
+    pub in_progress_orders: i64,
+    pub refunded_orders: i64,
+    pub failed_orders: i64,
+    pub amount_refunded_in_cents: i64,
+}
+
+impl NewEventRefundBatch {
+    pub fn commit(&self, conn: &PgConnection) -> Result<EventRefundBatch, DatabaseError> {
+        let event = Event::find(self.event_id, conn)?;
+        if event.cancelled_at.is_none() {
+            return DatabaseError::business_process_error("Only cancelled events can be mass refunded");
+        }
+        let open_batch_count: i64 = event_refund_batches::table
+            .filter(event_refund_batches::event_id.eq(self.event_id))
+            .filter(event_refund_batches::status.ne(EventRefundBatchStatus::Completed))
+            .select(count(event_refund_batches::id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not check existing refund batches")?;
+        if open_batch_count > 0 {
+            return DatabaseError::business_process_error("Orders for this event are already being refunded");
+        }
+
+        let result: EventRefundBatch = diesel::insert_into(event_refund_batches::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create event refund batch")?;
+
+        let order_ids: Vec<Uuid> = orders::table
+            .inner_join(order_items::table.on(order_items::order_id.eq(orders::id)))
+            .filter(order_items::event_id.eq(self.event_id))
+            .filter(order_items::item_type.eq(OrderItemTypes::Tickets))
+            .filter(order_items::refunded_quantity.lt(order_items::quantity))
+            .filter(orders::status.eq(OrderStatus::Paid))
+            .select(orders::id)
+            .distinct()
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load paid orders for event")?;
+        let batch_orders: Vec<NewEventRefundBatchOrder> = order_ids
+            .into_iter()
+            .map(|order_id| NewEventRefundBatchOrder {
+                event_refund_batch_id: result.id,
+                order_id,
+            })
+            .collect();
+        diesel::insert_into(event_refund_batch_orders::table)
+            .values(&batch_orders)
+            .execute(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not add orders to event refund batch")?;
+
+        DomainEvent::create(
+            DomainEventTypes::EventRefundBatchCreated,
+            format!("Refunds started for {} orders", batch_orders.len()),
+            Tables::Events,
+            Some(self.event_id),
+            Some(self.created_by),
+            Some(json!({ "event_refund_batch_id": result.id, "refund_fees": self.refund_fees })),
+        )
+        .commit(conn)?;
+
+        result.queue_processing(None, conn)?;
+
+        Ok(result)
+    }
+}
+
+impl EventRefundBatch {
+    /// Fees are refunded according to the organization's cancellation setting
+    pub fn create(event: &Event, created_by: Uuid, conn: &PgConnection) -> Result<NewEventRefundBatch, DatabaseError> {
+        let organization = event.organization(conn)?;
+        Ok(NewEventRefundBatch {
+            event_id: event.id,
+            refund_fees: organization.refund_fees_on_event_cancellation,
+            created_by,
+        })
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<EventRefundBatch, DatabaseError> {
+        event_refund_batches::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading event refund batch")
+    }
+
+    pub fn find_for_event(event_id: Uuid, conn: &PgConnection) -> Result<Vec<EventRefundBatch>, DatabaseError> {
+        event_refund_batches::table
+            .filter(event_refund_batches::event_id.eq(event_id))
+            .order_by(event_refund_batches::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading event refund batches")
+    }
+
+    pub fn orders(&self, conn: &PgConnection) -> Result<Vec<EventRefundBatchOrder>, DatabaseError> {
+        event_refund_batch_orders::table
+            .filter(event_refund_batch_orders::event_refund_batch_id.eq(self.id))
+            .order_by(event_refund_batch_orders::created_at)
+            .then_order_by(event_refund_batch_orders::order_id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading event refund batch orders")
+    }
+
+    /// Locks the returned orders, skipping orders already locked by another run
+    pub fn pending_orders(&self, limit: i64, conn: &PgConnection) -> Result<Vec<EventRefundBatchOrder>, DatabaseError> {
+        event_refund_batch_orders::table
+            .filter(event_refund_batch_orders::event_refund_batch_id.eq(self.id))
+            .filter(event_refund_batch_orders::status.eq(EventRefundBatchOrderStatus::Pending))
+            .for_update()
+            .skip_locked()
+            .order_by(event_refund_batch_orders::created_at)
+            .then_order_by(event_refund_batch_orders::order_id)
+            .limit(limit)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading pending event refund batch orders")
+    }
+
+    pub fn summary(&self, conn: &PgConnection) -> Result<EventRefundBatchSummary, DatabaseError> {
+        let mut summary = EventRefundBatchSummary::default();
+        for batch_order in self.orders(conn)? {
+            summary.total_orders += 1;
+            match batch_order.status {
+                EventRefundBatchOrderStatus::Pending => summary.pending_orders += 1,
+                EventRefundBatchOrderStatus::InProgress => summary.in_progress_orders += 1,
+                EventRefundBatchOrderStatus::Refunded => summary.refunded_orders += 1,
+                EventRefundBatchOrderStatus::Failed => summary.failed_orders += 1,
+            }
+            summary.amount_refunded_in_cents += batch_order.amount_refunded_in_cents.unwrap_or(0);
+        }
+        Ok(summary)
+    }
+
+    /// Schedules the next processing run, `delay_in_seconds` after now when given
+    pub fn queue_processing(&self, delay_in_seconds: Option<i64>, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let mut action = DomainAction::create(
+            None,
+            DomainActionTypes::ProcessEventRefundBatch,
+            None,
+            json!({ "event_refund_batch_id": self.id }),
+            Some(Tables::Events),
+            Some(self.event_id),
+        );
+        if let Some(delay_in_seconds) = delay_in_seconds {
+            action.schedule_at(dates::now().add_seconds(delay_in_seconds).finish());
+        }
+        action.commit(conn)?;
+        Ok(())
+    }
+
+    pub fn mark_in_progress(&self, conn: &PgConnection) -> Result<EventRefundBatch, DatabaseError> {
+        self.set_status(EventRefundBatchStatus::InProgress, None, conn)
+    }
+
+    pub fn complete(&self, conn: &PgConnection) -> Result<EventRefundBatch, DatabaseError> {
+        let result = self.set_status(EventRefundBatchStatus::Completed, Some(Utc::now().naive_utc()), conn)?;
+
+        let summary = self.summary(conn)?;
+        DomainEvent::create(
+            DomainEventTypes::EventRefundBatchCompleted,
+            format!(
+                "Refunds completed with {} refunded and {} failed orders",
+                summary.refunded_orders, summary.failed_orders
+            ),
+            Tables::Events,
+            Some(self.event_id),
+            None,
+            Some(json!({ "event_refund_batch_id": self.id, "summary": summary })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    /// Returns failed orders to the queue and resumes processing once the batch has finished
+    pub fn retry_failed(&self, conn: &PgConnection) -> Result<EventRefundBatch, DatabaseError> {
+        let event_refund_batch: EventRefundBatch =
+            event_refund_batches::table
+                .find(self.id)
+                .for_update()
+                .first(conn)
+                .to_db_error(ErrorCode::QueryError, "Error loading event refund batch")?;
+        if event_refund_batch.status == EventRefundBatchStatus::InProgress {
+            return DatabaseError::business_process_error("Refunds for this batch are still being processed");
+        }
+
+        diesel::update(
+            event_refund_batch_orders::table
+                .filter(event_refund_batch_orders::event_refund_batch_id.eq(self.id))
+                .filter(event_refund_batch_orders::status.eq(EventRefundBatchOrderStatus::Failed)),
+        )
+        .set((
+            event_refund_batch_orders::status.eq(EventRefundBatchOrderStatus::Pending),
+            event_refund_batch_orders::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(
+            ErrorCode::UpdateError,
+            "Could not retry failed event refund batch orders",
+        )?;
+
+        let result = self.set_status(EventRefundBatchStatus::InProgress, None, conn)?;
+        result.queue_processing(None, conn)?;
+        Ok(result)
+    }
+
+    fn set_status(
+        &self,
+        status: EventRefundBatchStatus,
+        completed_at: Option<NaiveDateTime>,
+        conn: &PgConnection,
+    ) -> Result<EventRefundBatch, DatabaseError> {
+        diesel::update(self)
+            .set((
+                event_refund_batches::status.eq(status),
+                event_refund_batches::completed_at.eq(completed_at),
+                event_refund_batches::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update event refund batch")
+    }
+}
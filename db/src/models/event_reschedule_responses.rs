@@ -0,0 +1,90 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::prelude::*;
+use models::*;
+use schema::event_reschedule_responses;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// A ticket holder's choice to keep their tickets for the new dates or have them refunded
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(EventReschedule)]
+#[table_name = "event_reschedule_responses"]
+pub struct EventRescheduleResponse {
+    pub id: Uuid,
+    pub event_reschedule_id: Uuid,
+    pub order_id: Uuid,
+    pub user_id: Uuid,
+    pub response: EventRescheduleResponseTypes,
+    pub refund_id: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "event_reschedule_responses"]
+pub struct NewEventRescheduleResponse {
+    pub event_reschedule_id: Uuid,
+    pub order_id: Uuid,
+    pub user_id: Uuid,
+    pub response: EventRescheduleResponseTypes,
+    pub refund_id: Option<Uuid>,
+}
+
+impl NewEventRescheduleResponse {
+    pub fn commit(&self, conn: &PgConnection) -> Result<EventRescheduleResponse, DatabaseError> {
+        EventReschedule::find(self.event_reschedule_id, conn)?.validate_response(self.order_id, self.user_id, conn)?;
+        if self.response == EventRescheduleResponseTypes::Refund && self.refund_id.is_none() {
+            return DatabaseError::validation_error("refund_id", "Refund is required when claiming a refund");
+        }
+
+        let result: EventRescheduleResponse = diesel::insert_into(event_reschedule_responses::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not record reschedule response")?;
+
+        DomainEvent::create(
+            DomainEventTypes::EventRescheduleResponded,
+            format!(
+                "Ticket holder chose to {} tickets",
+                match self.response {
+                    EventRescheduleResponseTypes::Keep => "keep",
+                    EventRescheduleResponseTypes::Refund => "refund",
+                }
+            ),
+            Tables::Orders,
+            Some(self.order_id),
+            Some(self.user_id),
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+}
+
+impl EventRescheduleResponse {
+    pub fn create(
+        event_reschedule_id: Uuid,
+        order_id: Uuid,
+        user_id: Uuid,
+        response: EventRescheduleResponseTypes,
+        refund_id: Option<Uuid>,
+    ) -> NewEventRescheduleResponse {
+        NewEventRescheduleResponse {
+            event_reschedule_id,
+            order_id,
+            user_id,
+            response,
+            refund_id,
+        }
+    }
+
+    pub fn find_for_order(order_id: Uuid, conn: &PgConnection) -> Result<Vec<EventRescheduleResponse>, DatabaseError> {
+        event_reschedule_responses::table
+            .filter(event_reschedule_responses::order_id.eq(order_id))
+            .order_by(event_reschedule_responses::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading reschedule responses for order")
+    }
+}
@@ -0,0 +1,253 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl::count;
+use diesel::prelude::*;
+use models::*;
+use schema::{event_reschedule_responses, event_reschedules};
+use utils::errors::*;
+use uuid::Uuid;
+use validators::{self, *};
+
+/// New dates for a postponed event. Ticket holders may keep their tickets or claim a refund until
+/// the response deadline.
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Event)]
+#[table_name = "event_reschedules"]
+pub struct EventReschedule {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub previous_event_start: Option<NaiveDateTime>,
+    pub previous_event_end: Option<NaiveDateTime>,
+    pub previous_door_time: Option<NaiveDateTime>,
+    pub event_start: NaiveDateTime,
+    pub event_end: Option<NaiveDateTime>,
+    pub door_time: Option<NaiveDateTime>,
+    pub response_deadline: NaiveDateTime,
+    pub created_by: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "event_reschedules"]
+pub struct NewEventReschedule {
+    pub event_id: Uuid,
+    pub previous_event_start: Option<NaiveDateTime>,
+    pub previous_event_end: Option<NaiveDateTime>,
+    pub previous_door_time: Option<NaiveDateTime>,
+    pub event_start: NaiveDateTime,
+    pub event_end: Option<NaiveDateTime>,
+    pub door_time: Option<NaiveDateTime>,
+    pub response_deadline: NaiveDateTime,
+    pub created_by: Option<Uuid>,
+}
+
+impl NewEventReschedule {
+    pub fn commit(&self, conn: &PgConnection) -> Result<EventReschedule, DatabaseError> {
+        let event = Event::find(self.event_id, conn)?;
+        self.validate_record(&event)?;
+
+        // Moving the start of a published event regenerates its pending transfer drips
+        event.update(
+            self.created_by,
+            EventEditableAttributes {
+                event_start: Some(self.event_start),
+                event_end: self.event_end,
+                door_time: self.door_time,
+                override_status: Some(Some(EventOverrideStatus::Rescheduled)),
+                ..Default::default()
+            },
+            conn,
+        )?;
+
+        let result: EventReschedule = diesel::insert_into(event_reschedules::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create event reschedule")?;
+
+        DomainEvent::create(
+            DomainEventTypes::EventRescheduled,
+            format!("Event '{}' rescheduled", &event.name),
+            Tables::Events,
+            Some(self.event_id),
+            self.created_by,
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        DomainAction::create(
+            None,
+            DomainActionTypes::SendEventRescheduledCommunications,
+            None,
+            json!({ "event_reschedule_id": result.id }),
+            Some(Tables::Events),
+            Some(self.event_id),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn validate_record(&self, event: &Event) -> Result<(), DatabaseError> {
+        if event.cancelled_at.is_some() {
+            return DatabaseError::business_process_error("Cancelled events cannot be rescheduled");
+        }
+        if event.event_start == Some(self.event_start) {
+            return DatabaseError::validation_error("event_start", "Event start must change when rescheduling");
+        }
+
+        let validation_errors = validators::append_validation_error(
+            Ok(()),
+            "response_deadline",
+            validators::n_date_valid(
+                Some(Utc::now().naive_utc()),
+                Some(self.response_deadline),
+                "response_deadline_in_past",
+                "Response deadline must be in the future",
+                "now",
+                "response_deadline",
+            ),
+        );
+        let validation_errors = validators::append_validation_error(
+            validation_errors,
+            "response_deadline",
+            validators::n_date_valid(
+                Some(self.response_deadline),
+                Some(self.event_start),
+                "response_deadline_after_event_start",
+                "Response deadline must be before the new event start",
+                "response_deadline",
+                "event_start",
+            ),
+        );
+
+        Ok(validation_errors?)
+    }
+}
+
+impl EventReschedule {
+    /// Event end and door time move by the same amount as the event start unless provided
+    pub fn create(
+        event: &Event,
+        event_start: NaiveDateTime,
+        event_end: Option<NaiveDateTime>,
+        door_time: Option<NaiveDateTime>,
+        response_deadline: NaiveDateTime,
+        created_by: Option<Uuid>,
+    ) -> NewEventReschedule {
+        let shift = event.event_start.map(|previous_start| event_start - previous_start);
+        let shifted = |date: Option<NaiveDateTime>| match (date, shift) {
+            (Some(date), Some(shift)) => Some(date + shift),
+            _ => None,
+        };
+
+        NewEventReschedule {
+            event_id: event.id,
+            previous_event_start: event.event_start,
+            previous_event_end: event.event_end,
+            previous_door_time: event.door_time,
+            event_start,
+            event_end: event_end.or_else(|| shifted(event.event_end)),
+            door_time: door_time.or_else(|| shifted(event.door_time)),
+            response_deadline,
+            created_by,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<EventReschedule, DatabaseError> {
+        event_reschedules::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading event reschedule")
+    }
+
+    pub fn find_for_event(event_id: Uuid, conn: &PgConnection) -> Result<Vec<EventReschedule>, DatabaseError> {
+        event_reschedules::table
+            .filter(event_reschedules::event_id.eq(event_id))
+            .order_by(event_reschedules::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading event reschedules")
+    }
+
+    pub fn event(&self, conn: &PgConnection) -> Result<Event, DatabaseError> {
+        Event::find(self.event_id, conn)
+    }
+
+    pub fn responses(&self, conn: &PgConnection) -> Result<Vec<EventRescheduleResponse>, DatabaseError> {
+        event_reschedule_responses::table
+            .filter(event_reschedule_responses::event_reschedule_id.eq(self.id))
+            .order_by(event_reschedule_responses::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading event reschedule responses")
+    }
+
+    pub fn is_response_window_open(&self) -> bool {
+        self.response_deadline > Utc::now().naive_utc()
+    }
+
+    /// Ticket holders respond once per order and only while the response window is open
+    pub fn validate_response(&self, order_id: Uuid, user_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
+        if !self.is_response_window_open() {
+            return DatabaseError::business_process_error("The deadline to respond to this reschedule has passed");
+        }
+
+        let response_count: i64 = event_reschedule_responses::table
+            .filter(event_reschedule_responses::event_reschedule_id.eq(self.id))
+            .filter(event_reschedule_responses::order_id.eq(order_id))
+            .filter(event_reschedule_responses::user_id.eq(user_id))
+            .select(count(event_reschedule_responses::id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not check existing responses")?;
+        if response_count > 0 {
+            return DatabaseError::business_process_error("A response has already been recorded for this order");
+        }
+
+        Ok(())
+    }
+
+    /// Unredeemed tickets to this event from the order that the user currently holds. Tickets may
+    /// have been transferred since purchase, it is their current holder who responds for them.
+    pub fn tickets_held_by(
+        &self,
+        order: &Order,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<TicketInstance>, DatabaseError> {
+        let mut tickets = Vec::new();
+        for order_item in order.items(conn)? {
+            if order_item.event_id != Some(self.event_id) || order_item.item_type != OrderItemTypes::Tickets {
+                continue;
+            }
+            for ticket in TicketInstance::find_for_order_item(order_item.id, conn)? {
+                if ticket.status == TicketInstanceStatus::Purchased && ticket.owner(conn)?.id == user_id {
+                    tickets.push(ticket);
+                }
+            }
+        }
+        Ok(tickets)
+    }
+
+    /// Order items to pass to `Order::refund` for the order's unredeemed tickets to this event held
+    /// by the user
+    pub fn refund_items(
+        &self,
+        order: &Order,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<RefundItemRequest>, DatabaseError> {
+        let items: Vec<RefundItemRequest> = self
+            .tickets_held_by(order, user_id, conn)?
+            .into_iter()
+            .filter_map(|ticket| {
+                ticket.order_item_id.map(|order_item_id| RefundItemRequest {
+                    order_item_id,
+                    ticket_instance_id: Some(ticket.id),
+                })
+            })
+            .collect();
+        if items.is_empty() {
+            return DatabaseError::business_process_error("Order has no refundable tickets for this event");
+        }
+        Ok(items)
+    }
+}
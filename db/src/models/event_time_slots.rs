@@ -0,0 +1,240 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Int4, Timestamp, Uuid as dUuid};
+use models::*;
+use schema::{event_time_slots, ticket_instances};
+use utils::errors::*;
+use uuid::Uuid;
+use validators::{self, *};
+
+/// Minutes before the start and after the end of a time slot during which its tickets can
+/// still be redeemed
+pub const EVENT_TIME_SLOT_GRACE_PERIOD_MINUTES: i64 = 15;
+
+/// A timed entry window for an event with its own admission capacity
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Event)]
+#[table_name = "event_time_slots"]
+pub struct EventTimeSlot {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub starts_at: NaiveDateTime,
+    pub ends_at: NaiveDateTime,
+    pub capacity: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "event_time_slots"]
+pub struct NewEventTimeSlot {
+    pub event_id: Uuid,
+    pub starts_at: NaiveDateTime,
+    pub ends_at: NaiveDateTime,
+    pub capacity: i32,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, QueryableByName, Serialize)]
+pub struct EventTimeSlotAvailability {
+    #[sql_type = "dUuid"]
+    pub id: Uuid,
+    #[sql_type = "dUuid"]
+    pub event_id: Uuid,
+    #[sql_type = "Timestamp"]
+    pub starts_at: NaiveDateTime,
+    #[sql_type = "Timestamp"]
+    pub ends_at: NaiveDateTime,
+    #[sql_type = "Int4"]
+    pub capacity: i32,
+    #[sql_type = "BigInt"]
+    pub available: i64,
+}
+
+impl NewEventTimeSlot {
+    pub fn commit(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<EventTimeSlot, DatabaseError> {
+        self.validate_record()?;
+
+        let result: EventTimeSlot = diesel::insert_into(event_time_slots::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create event time slot")?;
+
+        DomainEvent::create(
+            DomainEventTypes::EventTimeSlotCreated,
+            "Event time slot created".to_string(),
+            Tables::EventTimeSlots,
+            Some(result.id),
+            current_user_id,
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn validate_record(&self) -> Result<(), DatabaseError> {
+        let validation_errors = validators::append_validation_error(
+            Ok(()),
+            "capacity",
+            validators::validate_greater_than_or_equal(
+                self.capacity,
+                0,
+                "number_must_be_positive",
+                "Capacity must be positive",
+            ),
+        );
+        let validation_errors = validators::append_validation_error(
+            validation_errors,
+            "starts_at",
+            validators::n_date_valid(
+                Some(self.starts_at),
+                Some(self.ends_at),
+                "starts_at_must_be_before_ends_at",
+                "Time slot must start before it ends",
+                "starts_at",
+                "ends_at",
+            ),
+        );
+        Ok(validation_errors?)
+    }
+}
+
+impl EventTimeSlot {
+    pub fn create(event_id: Uuid, starts_at: NaiveDateTime, ends_at: NaiveDateTime, capacity: i32) -> NewEventTimeSlot {
+        NewEventTimeSlot {
+            event_id,
+            starts_at,
+            ends_at,
+            capacity,
+        }
+    }
+
+    /// Creates back to back slots of `interval_minutes` between `starts_at` and `ends_at`, each
+    /// admitting `capacity` ticket holders
+    pub fn create_for_interval(
+        event_id: Uuid,
+        starts_at: NaiveDateTime,
+        ends_at: NaiveDateTime,
+        interval_minutes: i64,
+        capacity: i32,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<EventTimeSlot>, DatabaseError> {
+        validators::append_validation_error(
+            Ok(()),
+            "interval_minutes",
+            validators::validate_greater_than(
+                interval_minutes,
+                0,
+                "number_must_be_greater_than_zero",
+                "Interval must be greater than zero",
+            ),
+        )?;
+
+        let mut slots = Vec::new();
+        let mut slot_starts_at = starts_at;
+        while slot_starts_at + Duration::minutes(interval_minutes) <= ends_at {
+            let slot_ends_at = slot_starts_at + Duration::minutes(interval_minutes);
+            slots.push(
+                EventTimeSlot::create(event_id, slot_starts_at, slot_ends_at, capacity)
+                    .commit(current_user_id, conn)?,
+            );
+            slot_starts_at = slot_ends_at;
+        }
+
+        if slots.is_empty() {
+            return DatabaseError::validation_error("ends_at", "Time range is shorter than the slot interval");
+        }
+
+        Ok(slots)
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<EventTimeSlot, DatabaseError> {
+        event_time_slots::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading event time slot")
+    }
+
+    pub fn find_for_event(event_id: Uuid, conn: &PgConnection) -> Result<Vec<EventTimeSlot>, DatabaseError> {
+        event_time_slots::table
+            .filter(event_time_slots::event_id.eq(event_id))
+            .order_by(event_time_slots::starts_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load time slots for event")
+    }
+
+    pub fn availability_for_event(
+        event_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<EventTimeSlotAvailability>, DatabaseError> {
+        let query = include_str!("../queries/event_time_slot_availability.sql");
+        diesel::sql_query(query)
+            .bind::<dUuid, _>(event_id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load time slot availability for event")
+    }
+
+    /// Loads the slot with a row lock so that concurrent carts cannot oversell it
+    pub fn find_for_update(id: Uuid, conn: &PgConnection) -> Result<EventTimeSlot, DatabaseError> {
+        event_time_slots::table
+            .find(id)
+            .for_update()
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading event time slot")
+    }
+
+    /// Number of tickets holding a place in this slot, either sold or reserved in a cart,
+    /// ignoring the given tickets
+    pub fn allocated_count(&self, excluded_ticket_ids: &[Uuid], conn: &PgConnection) -> Result<i64, DatabaseError> {
+        ticket_instances::table
+            .filter(ticket_instances::event_time_slot_id.eq(self.id))
+            .filter(ticket_instances::id.ne_all(excluded_ticket_ids))
+            .filter(
+                ticket_instances::status
+                    .eq_any(vec![TicketInstanceStatus::Purchased, TicketInstanceStatus::Redeemed])
+                    .or(ticket_instances::status
+                        .eq(TicketInstanceStatus::Reserved)
+                        .and(ticket_instances::reserved_until.ge(dsl::now.nullable()))),
+            )
+            .select(dsl::count(ticket_instances::id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to count tickets for event time slot")
+    }
+
+    /// Whether a ticket for this slot may be redeemed at the given time
+    pub fn is_redeemable_at(&self, redeemed_at: NaiveDateTime) -> bool {
+        let grace_period = Duration::minutes(EVENT_TIME_SLOT_GRACE_PERIOD_MINUTES);
+        redeemed_at >= self.starts_at - grace_period && redeemed_at <= self.ends_at + grace_period
+    }
+
+    pub fn destroy(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let assigned_tickets: i64 = ticket_instances::table
+            .filter(ticket_instances::event_time_slot_id.eq(self.id))
+            .select(dsl::count(ticket_instances::id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to count tickets for event time slot")?;
+        if assigned_tickets > 0 {
+            return DatabaseError::business_process_error("Time slot has tickets assigned and cannot be removed");
+        }
+
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove event time slot")?;
+
+        DomainEvent::create(
+            DomainEventTypes::EventTimeSlotDeleted,
+            "Event time slot deleted".to_string(),
+            Tables::EventTimeSlots,
+            Some(self.id),
+            current_user_id,
+            Some(json!({ "event_id": self.event_id, "starts_at": self.starts_at, "ends_at": self.ends_at })),
+        )
+        .commit(conn)?;
+
+        Ok(())
+    }
+}
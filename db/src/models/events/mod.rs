@@ -23,7 +23,7 @@ use serde_json::Value;
 use serde_with::rust::double_option;
 use services::*;
 use std::borrow::Cow;
-use std::cmp::Ordering;
+use std::cmp::{self, Ordering};
 use std::collections::HashMap;
 use utils::errors::*;
 use utils::pagination::*;
@@ -65,11 +65,13 @@ pub struct Event {
     pub facebook_event_id: Option<String>,
     pub settled_at: Option<NaiveDateTime>,
     pub cloned_from_event_id: Option<Uuid>,
+    pub currency: String,
     pub cover_image_url: Option<String>,
     pub video_url: Option<String>,
     pub top_line_info: Option<String>,
     pub additional_info: Option<String>,
     pub promo_image_url: Option<String>,
+    pub rotating_redeem_codes: bool,
 }
 
 impl PartialOrd for Event {
@@ -123,6 +125,11 @@ pub struct NewEvent {
     #[serde(default, deserialize_with = "deserialize_unless_blank")]
     pub facebook_event_id: Option<String>,
     pub cloned_from_event_id: Option<Uuid>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
+    /// Tickets are redeemed with time-based codes shown in the app rather than their static redeem key
+    #[serde(default)]
+    pub rotating_redeem_codes: bool,
 }
 
 pub enum TicketHoldersCountType {
@@ -148,7 +155,7 @@ impl NewEvent {
             None => (),
         }
 
-        validators::append_validation_error(
+        let mut validation_errors = validators::append_validation_error(
             Ok(()),
             "event.event_end",
             validators::n_date_valid(
@@ -159,7 +166,16 @@ impl NewEvent {
                 "event_start",
                 "event_end",
             ),
-        )?;
+        );
+        if let Some(currency) = new_event.currency.as_mut() {
+            *currency = currency.to_uppercase();
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "event.currency",
+                validators::validate_currency(currency),
+            );
+        }
+        validation_errors?;
 
         let event_json_data = Some(json!(&new_event)); // for back compatibility
         let data: NewEventData = new_event.into();
@@ -249,6 +265,9 @@ pub struct EventEditableAttributes {
     #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
     pub facebook_event_id: Option<Option<String>>,
     pub cloned_from_event_id: Option<Option<Uuid>>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
+    pub rotating_redeem_codes: Option<bool>,
 }
 
 #[derive(Debug, Default, PartialEq, Serialize)]
@@ -311,6 +330,8 @@ impl Event {
         event.video_url = self.video_url.clone();
         event.is_external = self.is_external;
         event.external_url = self.external_url.clone();
+        event.currency = Some(self.currency.clone());
+        event.rotating_redeem_codes = self.rotating_redeem_codes;
         let event = event.commit(current_user_id, conn)?;
 
         for event_artist in EventArtist::find_all_from_event(self.id, conn)? {
@@ -475,6 +496,23 @@ impl Event {
             .to_db_error(ErrorCode::QueryError, "Could not retrieve events")?;
 
         events.append(&mut refund_events);
+
+        // Orders on an installment plan are settled when the last installment is collected
+        let mut installment_events: Vec<EventData> = installment_plans::table
+            .inner_join(events::table.on(installment_plans::event_id.eq(events::id)))
+            .filter(events::id.ne_all(events.iter().map(|e| e.id).collect::<Vec<Uuid>>()))
+            .filter(events::deleted_at.is_null())
+            .filter(events::organization_id.eq(organization_id))
+            .filter(events::status.eq(EventStatus::Published))
+            .filter(events::is_external.eq(false))
+            .filter(installment_plans::completed_at.ge(start))
+            .filter(installment_plans::completed_at.le(end))
+            .select(events::all_columns)
+            .distinct()
+            .get_results(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not retrieve events")?;
+
+        events.append(&mut installment_events);
         events.sort_by_key(|e| e.event_end);
         Ok(EventData::vec_into_events(events))
     }
@@ -690,9 +728,30 @@ impl Event {
             ),
         );
 
+        if let Some(ref currency) = attributes.currency {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "event.currency",
+                validators::validate_currency(currency),
+            );
+        }
+
         let associated_with_active_orders = self.associated_with_active_orders(conn)?;
 
         if associated_with_active_orders {
+            if let Some(ref currency) = attributes.currency {
+                if currency != &self.currency {
+                    validation_errors = validators::append_validation_error(
+                        validation_errors,
+                        "event.currency",
+                        Err(create_validation_error(
+                            "cannot_change_currency_with_sales",
+                            "Event with sales cannot change currency.",
+                        )),
+                    );
+                }
+            }
+
             if attributes.event_start != self.event_start {
                 if let Some(updated_date) = attributes.event_start {
                     if updated_date < Utc::now().naive_utc() {
@@ -748,8 +807,11 @@ impl Event {
         conn: &PgConnection,
     ) -> Result<Event, DatabaseError> {
         let previous_start = self.event_start;
-        self.validate_record(&attributes, conn)?;
         let mut event = attributes;
+        if let Some(currency) = event.currency.as_mut() {
+            *currency = currency.to_uppercase();
+        }
+        self.validate_record(&event, conn)?;
         if event.private_access_code.is_some() {
             let inner_value = event.private_access_code.clone().unwrap();
             if inner_value.is_some() {
@@ -965,6 +1027,19 @@ impl Event {
         None
     }
 
+    /// Limits a scan time reported by a device to between the event's doors and end, and to no
+    /// later than now
+    pub fn clamp_scan_time(&self, scanned_at: NaiveDateTime) -> NaiveDateTime {
+        let mut scanned_at = scanned_at;
+        if let Some(doors) = self.door_time.or(self.event_start) {
+            scanned_at = cmp::max(scanned_at, doors);
+        }
+        if let Some(event_end) = self.event_end {
+            scanned_at = cmp::min(scanned_at, event_end);
+        }
+        cmp::min(scanned_at, Utc::now().naive_utc())
+    }
+
     pub fn next_drip_date(&self, environment: Environment) -> Option<NaiveDateTime> {
         let now = Utc::now().naive_utc();
         if let Some(event_start) = self.event_start {
@@ -1168,6 +1243,7 @@ impl Event {
             .get_result::<EventData>(conn)
             .to_db_error(ErrorCode::UpdateError, "Could not update event")?
             .into();
+        InstallmentPlan::cancel_for_event(self.id, current_user_id, conn)?;
 
         DomainEvent::create(
             DomainEventTypes::EventCancelled,
@@ -1784,6 +1860,10 @@ impl Event {
                 , sql::<Timestamp>("ticket_instances.updated_at AS updated_at")
                 , sql::<Nullable<Text>>("CASE WHEN ticket_instances.redeemed_by_user_id IS NOT NULL THEN (SELECT CONCAT(u2.first_name, ' ', u2.last_name) FROM users u2 WHERE u2.id = ticket_instances.redeemed_by_user_id) ELSE NULL END  AS redeemed_by")
                 , sql::<Nullable<Timestamp>>("ticket_instances.redeemed_at AS redeemed_at")
+                , sql::<Nullable<Text>>("(SELECT CONCAT(vsec.name, ' Row ', vs.row_label, ' Seat ', vs.seat_number) FROM venue_seats vs JOIN venue_sections vsec ON vs.venue_section_id = vsec.id WHERE vs.id = ticket_instances.venue_seat_id) AS seat_label")
+                , sql::<Nullable<Text>>("CASE WHEN ticket_instances.first_name_override IS NOT NULL OR ticket_instances.last_name_override IS NOT NULL THEN TRIM(CONCAT(ticket_instances.first_name_override, ' ', ticket_instances.last_name_override)) ELSE NULL END AS attendee_name")
+                , sql::<Nullable<Date>>("ticket_instances.attendee_birth_date AS attendee_birth_date")
+                , sql::<Bool>("cardinality(ticket_types.required_attendee_details) > 0 AS named_ticket")
             ))
             .paginate(paging.page as i64)
             .per_page(paging.limit as i64)
@@ -2287,6 +2367,7 @@ impl Event {
             event_type: self.event_type,
             slug,
             cloned_from_event_id: self.cloned_from_event_id,
+            currency: self.currency.clone(),
         })
     }
 }
@@ -2315,6 +2396,7 @@ pub struct DisplayEvent {
     pub genres: Vec<String>,
     pub slug: String,
     pub cloned_from_event_id: Option<Uuid>,
+    pub currency: String,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -22,6 +22,8 @@ pub(crate) struct EventAdditionalJson {
     pub top_line_info: Option<String>,
     pub additional_info: Option<String>,
     pub promo_image_url: Option<String>,
+    #[serde(default)]
+    pub rotating_redeem_codes: bool,
 }
 
 impl FromSql<Jsonb, Pg> for EventAdditionalJson {
@@ -46,6 +48,7 @@ impl From<&Event> for EventAdditionalJson {
             top_line_info: event.top_line_info.clone(),
             additional_info: event.additional_info.clone(),
             promo_image_url: event.promo_image_url.clone(),
+            rotating_redeem_codes: event.rotating_redeem_codes,
         }
     }
 }
@@ -58,6 +61,7 @@ impl From<&NewEvent> for EventAdditionalJson {
             top_line_info: event.top_line_info.clone(),
             additional_info: event.additional_info.clone(),
             promo_image_url: event.promo_image_url.clone(),
+            rotating_redeem_codes: event.rotating_redeem_codes,
         }
     }
 }
@@ -111,6 +115,7 @@ pub(crate) struct EventData {
     pub settled_at: Option<NaiveDateTime>,
     pub cloned_from_event_id: Option<Uuid>,
     pub additional_json: EventAdditionalJson,
+    pub currency: String,
 }
 
 impl From<EventData> for Event {
@@ -146,11 +151,13 @@ impl From<EventData> for Event {
             facebook_event_id: event.facebook_event_id,
             settled_at: event.settled_at,
             cloned_from_event_id: event.cloned_from_event_id,
+            currency: event.currency,
             cover_image_url: event.additional_json.cover_image_url,
             video_url: event.additional_json.video_url,
             top_line_info: event.additional_json.top_line_info,
             additional_info: event.additional_json.additional_info,
             promo_image_url: event.additional_json.promo_image_url,
+            rotating_redeem_codes: event.additional_json.rotating_redeem_codes,
         }
     }
 }
@@ -190,6 +197,7 @@ impl From<Event> for EventData {
             settled_at: event.settled_at,
             cloned_from_event_id: event.cloned_from_event_id,
             additional_json,
+            currency: event.currency,
         }
     }
 }
@@ -223,6 +231,7 @@ pub(crate) struct NewEventData {
     pub facebook_event_id: Option<String>,
     pub cloned_from_event_id: Option<Uuid>,
     pub additional_json: EventAdditionalJson,
+    pub currency: Option<String>,
 }
 
 impl From<NewEvent> for NewEventData {
@@ -249,6 +258,7 @@ impl From<NewEvent> for NewEventData {
             facebook_event_id: event.facebook_event_id.clone(),
             cloned_from_event_id: event.cloned_from_event_id,
             additional_json,
+            currency: event.currency,
         }
     }
 }
@@ -275,6 +285,7 @@ pub(crate) struct EventEditableAttributesData {
     pub facebook_event_id: Option<Option<String>>,
     pub cloned_from_event_id: Option<Option<Uuid>>,
     pub additional_json: Option<EventAdditionalJson>,
+    pub currency: Option<String>,
 }
 
 impl EventEditableAttributesData {
@@ -305,6 +316,7 @@ impl EventEditableAttributesData {
             cancelled_at: event.cancelled_at,
             sendgrid_list_id: event.sendgrid_list_id,
             additional_json,
+            currency: event.currency,
         })
     }
 
@@ -318,6 +330,7 @@ impl EventEditableAttributesData {
             && event.top_line_info.is_none()
             && event.additional_info.is_none()
             && event.promo_image_url.is_none()
+            && event.rotating_redeem_codes.is_none()
         {
             return Ok(None);
         };
@@ -347,6 +360,7 @@ impl EventEditableAttributesData {
         check_and_update!(top_line_info);
         check_and_update!(additional_info);
         check_and_update!(promo_image_url);
+        check_and_update!(rotating_redeem_codes);
 
         if changed {
             Ok(Some(current))
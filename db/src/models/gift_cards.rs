@@ -0,0 +1,354 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl::{exists, select};
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{gift_cards, order_items, orders, payments};
+use utils::dates;
+use utils::errors::*;
+use utils::rand::random_alpha_string;
+use uuid::Uuid;
+use validators::{self, *};
+
+/// Days a gift card purchased through a gift card product remains redeemable
+pub const PURCHASED_GIFT_CARD_VALIDITY_DAYS: i64 = 5 * 365;
+const GIFT_CARD_CODE_LENGTH: usize = 16;
+
+/// Stored value issued by an organization that can be redeemed as payment for its orders
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Organization)]
+#[table_name = "gift_cards"]
+pub struct GiftCard {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub code: String,
+    pub initial_value_in_cents: i64,
+    pub balance_in_cents: i64,
+    pub currency: String,
+    pub expires_at: Option<NaiveDateTime>,
+    pub order_item_id: Option<Uuid>,
+    pub created_by: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub voided_at: Option<NaiveDateTime>,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "gift_cards"]
+pub struct NewGiftCard {
+    pub organization_id: Uuid,
+    pub code: String,
+    pub initial_value_in_cents: i64,
+    pub balance_in_cents: i64,
+    pub currency: String,
+    pub expires_at: Option<NaiveDateTime>,
+    pub order_item_id: Option<Uuid>,
+    pub created_by: Option<Uuid>,
+}
+
+impl NewGiftCard {
+    pub fn commit(&self, conn: &PgConnection) -> Result<GiftCard, DatabaseError> {
+        self.validate_record()?;
+
+        let result: GiftCard = diesel::insert_into(gift_cards::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create gift card")?;
+
+        DomainEvent::create(
+            DomainEventTypes::GiftCardCreated,
+            "Gift card created".to_string(),
+            Tables::GiftCards,
+            Some(result.id),
+            self.created_by,
+            Some(json!({
+                "organization_id": self.organization_id,
+                "initial_value_in_cents": self.initial_value_in_cents,
+                "currency": &self.currency,
+                "expires_at": self.expires_at,
+                "order_item_id": self.order_item_id,
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn validate_record(&self) -> Result<(), DatabaseError> {
+        let validation_errors = validators::append_validation_error(
+            Ok(()),
+            "initial_value_in_cents",
+            validators::validate_greater_than(
+                self.initial_value_in_cents,
+                0,
+                "number_must_be_positive",
+                "Gift card value must be greater than zero",
+            ),
+        );
+        let validation_errors =
+            validators::append_validation_error(validation_errors, "currency", validate_currency(&self.currency));
+        let validation_errors = validators::append_validation_error(
+            validation_errors,
+            "expires_at",
+            validators::n_date_valid(
+                Some(Utc::now().naive_utc()),
+                self.expires_at,
+                "expires_at_in_past",
+                "Gift card expiry must be in the future",
+                "now",
+                "expires_at",
+            ),
+        );
+
+        Ok(validation_errors?)
+    }
+}
+
+impl GiftCard {
+    pub fn create(
+        organization_id: Uuid,
+        initial_value_in_cents: i64,
+        currency: String,
+        expires_at: Option<NaiveDateTime>,
+        order_item_id: Option<Uuid>,
+        created_by: Option<Uuid>,
+    ) -> NewGiftCard {
+        NewGiftCard {
+            organization_id,
+            code: random_alpha_string(GIFT_CARD_CODE_LENGTH).to_uppercase(),
+            initial_value_in_cents,
+            balance_in_cents: initial_value_in_cents,
+            currency,
+            expires_at,
+            order_item_id,
+            created_by,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<GiftCard, DatabaseError> {
+        gift_cards::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading gift card")
+    }
+
+    /// Codes are matched ignoring case and surrounding whitespace
+    pub fn find_by_code(code: &str, conn: &PgConnection) -> Result<GiftCard, DatabaseError> {
+        gift_cards::table
+            .filter(gift_cards::code.eq(code.trim().to_uppercase()))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading gift card")
+    }
+
+    pub fn find_for_organization(organization_id: Uuid, conn: &PgConnection) -> Result<Vec<GiftCard>, DatabaseError> {
+        gift_cards::table
+            .filter(gift_cards::organization_id.eq(organization_id))
+            .order_by(gift_cards::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading gift cards for organization")
+    }
+
+    /// Gift cards issued for gift card products purchased on the order
+    pub fn find_for_order(order_id: Uuid, conn: &PgConnection) -> Result<Vec<GiftCard>, DatabaseError> {
+        gift_cards::table
+            .inner_join(order_items::table.on(gift_cards::order_item_id.eq(order_items::id.nullable())))
+            .filter(order_items::order_id.eq(order_id))
+            .select(gift_cards::all_columns)
+            .order_by(gift_cards::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading gift cards for order")
+    }
+
+    /// The user a purchased gift card was bought for, `None` for cards issued by the organization
+    pub fn purchaser_id(&self, conn: &PgConnection) -> Result<Option<Uuid>, DatabaseError> {
+        let order_item_id = match self.order_item_id {
+            Some(order_item_id) => order_item_id,
+            None => return Ok(None),
+        };
+        let (user_id, on_behalf_of_user_id): (Uuid, Option<Uuid>) = order_items::table
+            .inner_join(orders::table.on(order_items::order_id.eq(orders::id)))
+            .filter(order_items::id.eq(order_item_id))
+            .select((orders::user_id, orders::on_behalf_of_user_id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading gift card purchaser")?;
+        Ok(Some(on_behalf_of_user_id.unwrap_or(user_id)))
+    }
+
+    /// Whether the user has paid for one of their orders with the gift card
+    pub fn redeemed_by(&self, user_id: Uuid, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        select(exists(
+            payments::table
+                .inner_join(orders::table.on(payments::order_id.eq(orders::id)))
+                .filter(payments::payment_method.eq(PaymentMethods::GiftCard))
+                .filter(payments::external_reference.eq(self.id.to_string()))
+                .filter(orders::user_id.eq(user_id).or(orders::on_behalf_of_user_id.eq(user_id))),
+        ))
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Error checking gift card redemptions")
+    }
+
+    /// Issues a gift card for each unit of the gift card products on a paid order
+    pub fn issue_for_order(
+        order: &Order,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<GiftCard>, DatabaseError> {
+        let mut gift_cards = Vec::new();
+        for order_item in order.items(conn)? {
+            let product_variant_id = match (order_item.item_type, order_item.product_variant_id) {
+                (OrderItemTypes::Products, Some(product_variant_id)) => product_variant_id,
+                _ => continue,
+            };
+            let product = ProductVariant::find(product_variant_id, conn)?.product(conn)?;
+            if !product.gift_card {
+                continue;
+            }
+
+            for _ in 0..order_item.quantity {
+                gift_cards.push(
+                    GiftCard::create(
+                        product.organization_id,
+                        order_item.unit_price_in_cents,
+                        order.currency.clone(),
+                        Some(dates::now().add_days(PURCHASED_GIFT_CARD_VALIDITY_DAYS).finish()),
+                        Some(order_item.id),
+                        current_user_id,
+                    )
+                    .commit(conn)?,
+                );
+            }
+        }
+        Ok(gift_cards)
+    }
+
+    /// Voids an unredeemed gift card issued for the order item when one unit of it is refunded
+    pub fn void_for_refunded_item(
+        order_item: &OrderItem,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        let gift_card: Option<GiftCard> = gift_cards::table
+            .filter(gift_cards::order_item_id.eq(order_item.id))
+            .filter(gift_cards::voided_at.is_null())
+            .order_by(gift_cards::balance_in_cents.desc())
+            .for_update()
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Error loading gift cards for order item")?;
+        let gift_card = match gift_card {
+            Some(gift_card) => gift_card,
+            None => return Ok(()),
+        };
+        if gift_card.balance_in_cents < gift_card.initial_value_in_cents {
+            return DatabaseError::business_process_error(
+                "Gift card has already been redeemed so is ineligible for refund",
+            );
+        }
+
+        let result: GiftCard = diesel::update(&gift_card)
+            .set((
+                gift_cards::balance_in_cents.eq(0),
+                gift_cards::voided_at.eq(dsl::now.nullable()),
+                gift_cards::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not void gift card")?;
+        result.log_balance_change(-gift_card.balance_in_cents, current_user_id, conn)?;
+        Ok(())
+    }
+
+    pub fn is_voided(&self) -> bool {
+        self.voided_at.is_some()
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.map(|e| e < Utc::now().naive_utc()).unwrap_or(false)
+    }
+
+    /// Gift cards can only pay for orders from the issuing organization in the same currency
+    pub fn validate_for_order(&self, order: &Order, conn: &PgConnection) -> Result<(), DatabaseError> {
+        if self.is_voided() {
+            return DatabaseError::business_process_error("Gift card has been voided");
+        }
+        if self.is_expired() {
+            return DatabaseError::business_process_error("Gift card has expired");
+        }
+        if self.balance_in_cents == 0 {
+            return DatabaseError::business_process_error("Gift card has no remaining balance");
+        }
+        if self.currency != order.currency {
+            return DatabaseError::business_process_error("Gift card currency does not match the order currency");
+        }
+        let organizations = order.organizations(conn)?;
+        if organizations.is_empty() || organizations.iter().any(|o| o.id != self.organization_id) {
+            return DatabaseError::business_process_error("Gift card cannot be used for this order");
+        }
+        Ok(())
+    }
+
+    pub fn debit(
+        &self,
+        amount_in_cents: i64,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<GiftCard, DatabaseError> {
+        // Guard against a concurrent redemption having already spent the balance
+        let result: Option<GiftCard> = diesel::update(
+            gift_cards::table
+                .filter(gift_cards::id.eq(self.id))
+                .filter(gift_cards::balance_in_cents.ge(amount_in_cents)),
+        )
+        .set((
+            gift_cards::balance_in_cents.eq(gift_cards::balance_in_cents - amount_in_cents),
+            gift_cards::updated_at.eq(dsl::now),
+        ))
+        .get_result(conn)
+        .optional()
+        .to_db_error(ErrorCode::UpdateError, "Could not update gift card balance")?;
+
+        match result {
+            Some(gift_card) => {
+                gift_card.log_balance_change(-amount_in_cents, current_user_id, conn)?;
+                Ok(gift_card)
+            }
+            None => DatabaseError::business_process_error("Gift card balance is insufficient"),
+        }
+    }
+
+    /// Restores balance when a gift card payment is refunded
+    pub fn credit(
+        &self,
+        amount_in_cents: i64,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<GiftCard, DatabaseError> {
+        let result: GiftCard = diesel::update(self)
+            .set((
+                gift_cards::balance_in_cents.eq(gift_cards::balance_in_cents + amount_in_cents),
+                gift_cards::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update gift card balance")?;
+        result.log_balance_change(amount_in_cents, current_user_id, conn)?;
+        Ok(result)
+    }
+
+    fn log_balance_change(
+        &self,
+        amount_in_cents: i64,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        DomainEvent::create(
+            DomainEventTypes::GiftCardBalanceUpdated,
+            format!("Gift card balance changed by {}", amount_in_cents),
+            Tables::GiftCards,
+            Some(self.id),
+            current_user_id,
+            Some(json!({ "amount_in_cents": amount_in_cents, "balance_in_cents": self.balance_in_cents })),
+        )
+        .commit(conn)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,173 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::idempotency_keys;
+use utils::dates;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Hours a stored response is replayed for before the key can be reused
+pub const IDEMPOTENCY_KEY_EXPIRY_HOURS: i64 = 24;
+/// Seconds a request holds the key while in progress. Claims whose request never stored a
+/// response, e.g. after a crash, can be taken over by a retry once this has passed.
+pub const IDEMPOTENCY_KEY_LOCK_SECONDS: i64 = 60;
+
+/// Client supplied key identifying a mutating request so that retries of the request replay the
+/// original response instead of being processed again
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(User)]
+#[table_name = "idempotency_keys"]
+pub struct IdempotencyKey {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub idempotency_key: String,
+    pub request_method: String,
+    pub request_path: String,
+    pub response_status: Option<i32>,
+    pub response_body: Option<String>,
+    pub expires_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    /// SHA-256 of the request body, retries must send the same body
+    pub request_hash: Option<String>,
+    pub locked_until: Option<NaiveDateTime>,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "idempotency_keys"]
+struct NewIdempotencyKey {
+    user_id: Uuid,
+    idempotency_key: String,
+    request_method: String,
+    request_path: String,
+    expires_at: NaiveDateTime,
+    request_hash: Option<String>,
+    locked_until: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum IdempotencyKeyClaim {
+    /// The key was unused and is now held by this request
+    Claimed(IdempotencyKey),
+    /// The key is held by an earlier request, which is still in progress if it has no response
+    Existing(IdempotencyKey),
+}
+
+impl IdempotencyKey {
+    /// Claims the key for a request, expired keys are removed first so they can be reused. A claim
+    /// for the same request whose lock has lapsed without a response is taken over.
+    pub fn claim(
+        user_id: Uuid,
+        idempotency_key: &str,
+        request_method: &str,
+        request_path: &str,
+        request_hash: &str,
+        conn: &PgConnection,
+    ) -> Result<IdempotencyKeyClaim, DatabaseError> {
+        diesel::delete(
+            idempotency_keys::table
+                .filter(idempotency_keys::user_id.eq(user_id))
+                .filter(idempotency_keys::idempotency_key.eq(idempotency_key))
+                .filter(idempotency_keys::expires_at.lt(dsl::now)),
+        )
+        .execute(conn)
+        .to_db_error(ErrorCode::DeleteError, "Could not remove expired idempotency key")?;
+
+        let claimed: Option<IdempotencyKey> = diesel::insert_into(idempotency_keys::table)
+            .values(NewIdempotencyKey {
+                user_id,
+                idempotency_key: idempotency_key.to_string(),
+                request_method: request_method.to_string(),
+                request_path: request_path.to_string(),
+                expires_at: dates::now().add_hours(IDEMPOTENCY_KEY_EXPIRY_HOURS).finish(),
+                request_hash: Some(request_hash.to_string()),
+                locked_until: Some(IdempotencyKey::lock_expiry()),
+            })
+            .on_conflict_do_nothing()
+            .get_result(conn)
+            .optional()
+            .to_db_error(ErrorCode::InsertError, "Could not claim idempotency key")?;
+        if let Some(claimed) = claimed {
+            return Ok(IdempotencyKeyClaim::Claimed(claimed));
+        }
+
+        let taken_over: Option<IdempotencyKey> = diesel::update(
+            idempotency_keys::table
+                .filter(idempotency_keys::user_id.eq(user_id))
+                .filter(idempotency_keys::idempotency_key.eq(idempotency_key))
+                .filter(idempotency_keys::request_method.eq(request_method))
+                .filter(idempotency_keys::request_path.eq(request_path))
+                .filter(
+                    idempotency_keys::request_hash
+                        .eq(request_hash)
+                        .or(idempotency_keys::request_hash.is_null()),
+                )
+                .filter(idempotency_keys::response_status.is_null())
+                .filter(
+                    idempotency_keys::locked_until
+                        .is_null()
+                        .or(idempotency_keys::locked_until.lt(dsl::now.nullable())),
+                ),
+        )
+        .set((
+            idempotency_keys::request_hash.eq(request_hash),
+            idempotency_keys::locked_until.eq(IdempotencyKey::lock_expiry()),
+            idempotency_keys::updated_at.eq(dsl::now),
+        ))
+        .get_result(conn)
+        .optional()
+        .to_db_error(ErrorCode::UpdateError, "Could not take over idempotency key")?;
+
+        match taken_over {
+            Some(claimed) => Ok(IdempotencyKeyClaim::Claimed(claimed)),
+            None => idempotency_keys::table
+                .filter(idempotency_keys::user_id.eq(user_id))
+                .filter(idempotency_keys::idempotency_key.eq(idempotency_key))
+                .first(conn)
+                .to_db_error(ErrorCode::QueryError, "Could not load idempotency key")
+                .map(IdempotencyKeyClaim::Existing),
+        }
+    }
+
+    pub fn matches_request(&self, request_method: &str, request_path: &str, request_hash: &str) -> bool {
+        self.request_method == request_method
+            && self.request_path == request_path
+            && self.request_hash.as_ref().map_or(true, |hash| hash == request_hash)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.response_status.is_some()
+    }
+
+    /// Stores the response to replay for retries of the request
+    pub fn complete(
+        &self,
+        response_status: i32,
+        response_body: &str,
+        conn: &PgConnection,
+    ) -> Result<IdempotencyKey, DatabaseError> {
+        diesel::update(self)
+            .set((
+                idempotency_keys::response_status.eq(response_status),
+                idempotency_keys::response_body.eq(response_body),
+                idempotency_keys::locked_until.eq(None::<NaiveDateTime>),
+                idempotency_keys::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not store idempotency key response")
+    }
+
+    fn lock_expiry() -> NaiveDateTime {
+        dates::now().add_seconds(IDEMPOTENCY_KEY_LOCK_SECONDS).finish()
+    }
+
+    /// Frees the key so a retry of a failed request is processed again
+    pub fn release(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not release idempotency key")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,421 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl::{exists, select, sum};
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{installment_plans, installments, order_items, ticket_instances};
+use std::cmp;
+use utils::dates;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Splits the amount due on an order into a deposit taken at checkout and installments charged
+/// later to the purchaser's saved payment method. Each installment is charged by a
+/// `ProcessInstallmentPayment` domain action; a plan that can't be collected is defaulted by the
+/// `DefaultInstallmentPlan` action, which nullifies the order's tickets. Refunding the order or
+/// cancelling its event cancels the plan.
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Order)]
+#[table_name = "installment_plans"]
+pub struct InstallmentPlan {
+    pub id: Uuid,
+    pub order_id: Uuid,
+    pub event_id: Uuid,
+    pub payment_method_id: Uuid,
+    pub status: InstallmentPlanStatus,
+    pub total_in_cents: i64,
+    pub deposit_in_cents: i64,
+    pub max_attempts: i32,
+    pub retry_interval_hours: i32,
+    pub refund_deposit_on_default: bool,
+    pub created_by: Uuid,
+    pub completed_at: Option<NaiveDateTime>,
+    pub defaulted_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "installment_plans"]
+pub struct NewInstallmentPlan {
+    pub order_id: Uuid,
+    pub event_id: Uuid,
+    pub payment_method_id: Uuid,
+    pub total_in_cents: i64,
+    pub deposit_in_cents: i64,
+    pub max_attempts: i32,
+    pub retry_interval_hours: i32,
+    pub refund_deposit_on_default: bool,
+    pub created_by: Uuid,
+}
+
+impl InstallmentPlan {
+    /// Creates a pending plan for a draft order using the event's installment policy. The plan
+    /// is activated once the deposit has been paid.
+    pub fn create_for_order(
+        order: &Order,
+        payment_method: &PaymentMethod,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<InstallmentPlan, DatabaseError> {
+        if order.status != OrderStatus::Draft {
+            return DatabaseError::business_process_error("Installment plans can only be created for draft orders");
+        }
+        if payment_method.user_id != order.user_id {
+            return DatabaseError::business_process_error("Payment method does not belong to the purchaser");
+        }
+        let mut events = order.events(conn)?;
+        if events.len() != 1 {
+            return DatabaseError::business_process_error(
+                "Installment plans are only available for orders containing tickets for a single event",
+            );
+        }
+        let event = events.remove(0);
+        let policy = match InstallmentPolicy::find_for_event(event.id, conn)? {
+            Some(policy) => policy,
+            None => return DatabaseError::business_process_error("Installment plans are not available for this event"),
+        };
+
+        InstallmentPlan::remove_pending_for_order(order.id, conn)?;
+        if InstallmentPlan::find_for_order(order.id, conn)?.is_some() {
+            return DatabaseError::business_process_error("Order already has an installment plan");
+        }
+
+        let total = order.amount_due(conn)?;
+        let deposit = total * policy.deposit_percent as i64 / 100;
+        let installment_count = policy.installment_count as i64;
+        if deposit <= 0 || total - deposit < installment_count {
+            return DatabaseError::business_process_error("Order total is too low for an installment plan");
+        }
+
+        let now = dates::now().finish();
+        let due_dates: Vec<NaiveDateTime> = (1..=installment_count)
+            .map(|i| now + Duration::days(policy.interval_days as i64 * i))
+            .collect();
+        if let (Some(event_start), Some(last_due_at)) = (event.event_start, due_dates.last()) {
+            if *last_due_at >= event_start {
+                return DatabaseError::business_process_error(
+                    "Installments can not be completed before the event starts",
+                );
+            }
+        }
+
+        let plan: InstallmentPlan = diesel::insert_into(installment_plans::table)
+            .values(NewInstallmentPlan {
+                order_id: order.id,
+                event_id: event.id,
+                payment_method_id: payment_method.id,
+                total_in_cents: total,
+                deposit_in_cents: deposit,
+                max_attempts: policy.max_attempts,
+                retry_interval_hours: policy.retry_interval_hours,
+                refund_deposit_on_default: policy.refund_deposit_on_default,
+                created_by: current_user_id,
+            })
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create installment plan")?;
+
+        // Any cents that don't divide evenly are added to the earliest installments
+        let remaining = total - deposit;
+        let new_installments: Vec<NewInstallment> = due_dates
+            .into_iter()
+            .enumerate()
+            .map(|(i, due_at)| NewInstallment {
+                installment_plan_id: plan.id,
+                amount_in_cents: remaining / installment_count
+                    + if (i as i64) < remaining % installment_count {
+                        1
+                    } else {
+                        0
+                    },
+                due_at,
+            })
+            .collect();
+        diesel::insert_into(installments::table)
+            .values(&new_installments)
+            .execute(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create installments")?;
+
+        DomainEvent::create(
+            DomainEventTypes::InstallmentPlanCreated,
+            "Installment plan created".to_string(),
+            Tables::InstallmentPlans,
+            Some(plan.id),
+            Some(current_user_id),
+            Some(json!({
+                "order_id": order.id,
+                "total_in_cents": total,
+                "deposit_in_cents": deposit,
+                "installment_count": installment_count
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(plan)
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<InstallmentPlan, DatabaseError> {
+        installment_plans::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading installment plan")
+    }
+
+    pub fn find_for_order(order_id: Uuid, conn: &PgConnection) -> Result<Option<InstallmentPlan>, DatabaseError> {
+        installment_plans::table
+            .filter(installment_plans::order_id.eq(order_id))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Error loading installment plan")
+    }
+
+    /// Removes a plan left behind by an earlier checkout attempt whose deposit was never paid
+    pub fn remove_pending_for_order(order_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
+        diesel::delete(
+            installment_plans::table
+                .filter(installment_plans::order_id.eq(order_id))
+                .filter(installment_plans::status.eq(InstallmentPlanStatus::Pending)),
+        )
+        .execute(conn)
+        .to_db_error(ErrorCode::DeleteError, "Could not remove pending installment plan")?;
+        Ok(())
+    }
+
+    pub fn installments(&self, conn: &PgConnection) -> Result<Vec<Installment>, DatabaseError> {
+        Installment::find_for_plan(self.id, conn)
+    }
+
+    pub fn payment_method(&self, conn: &PgConnection) -> Result<PaymentMethod, DatabaseError> {
+        PaymentMethod::find(self.payment_method_id, conn)
+    }
+
+    /// Amount of the order still to be collected through installments. Orders are considered
+    /// paid once everything except this amount has been received.
+    pub fn scheduled_amount_for_order(order_id: Uuid, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        let amount: Option<i64> = installments::table
+            .inner_join(installment_plans::table)
+            .filter(installment_plans::order_id.eq(order_id))
+            .filter(
+                installment_plans::status.eq_any(vec![InstallmentPlanStatus::Pending, InstallmentPlanStatus::Active]),
+            )
+            .filter(installments::status.ne(InstallmentStatus::Paid))
+            .select(sum(installments::amount_in_cents))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load scheduled installment amount")?;
+        Ok(amount.unwrap_or(0))
+    }
+
+    /// Deposit plus any installments collected so far
+    pub fn amount_paid(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        let installments_paid: i64 = self
+            .installments(conn)?
+            .iter()
+            .filter(|i| i.status == InstallmentStatus::Paid)
+            .map(|i| i.amount_in_cents)
+            .sum();
+        Ok(self.deposit_in_cents + installments_paid)
+    }
+
+    /// Tickets on a plan that hasn't been fully paid can't be transferred or redeemed
+    pub fn has_outstanding_balance(ticket_ids: &[Uuid], conn: &PgConnection) -> Result<bool, DatabaseError> {
+        select(exists(
+            ticket_instances::table
+                .inner_join(order_items::table.on(ticket_instances::order_item_id.eq(order_items::id.nullable())))
+                .inner_join(installment_plans::table.on(installment_plans::order_id.eq(order_items::order_id)))
+                .filter(ticket_instances::id.eq_any(ticket_ids))
+                .filter(
+                    installment_plans::status
+                        .eq_any(vec![InstallmentPlanStatus::Pending, InstallmentPlanStatus::Active]),
+                ),
+        ))
+        .get_result(conn)
+        .to_db_error(
+            ErrorCode::QueryError,
+            "Could not check tickets for outstanding installments",
+        )
+    }
+
+    /// Called once the deposit has been paid, schedules a charge for each installment
+    pub(crate) fn activate(
+        &self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<InstallmentPlan, DatabaseError> {
+        if self.status != InstallmentPlanStatus::Pending {
+            return DatabaseError::business_process_error("Installment plan has already been activated");
+        }
+        let plan = self.update_status(InstallmentPlanStatus::Active, conn)?;
+        for installment in plan.installments(conn)? {
+            installment.queue_payment(installment.due_at, conn)?;
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::InstallmentPaid,
+            "Installment plan deposit paid".to_string(),
+            Tables::InstallmentPlans,
+            Some(plan.id),
+            current_user_id,
+            Some(json!({ "amount_in_cents": plan.deposit_in_cents })),
+        )
+        .commit(conn)?;
+
+        Ok(plan)
+    }
+
+    pub(crate) fn complete_if_fully_paid(&self, conn: &PgConnection) -> Result<InstallmentPlan, DatabaseError> {
+        if self
+            .installments(conn)?
+            .iter()
+            .any(|i| i.status != InstallmentStatus::Paid)
+        {
+            return Ok(self.clone());
+        }
+
+        let plan = self.update_status(InstallmentPlanStatus::Completed, conn)?;
+        DomainEvent::create(
+            DomainEventTypes::InstallmentPlanCompleted,
+            "Installment plan completed".to_string(),
+            Tables::InstallmentPlans,
+            Some(plan.id),
+            None,
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(plan)
+    }
+
+    /// Marks the plan as defaulted and nullifies the order's tickets. Refunding what was paid is
+    /// left to the caller as it requires the payment processor.
+    pub fn default(
+        &self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<InstallmentPlan, DatabaseError> {
+        if self.status != InstallmentPlanStatus::Active {
+            return DatabaseError::business_process_error("Only active installment plans can be defaulted");
+        }
+        let plan = self.update_status(InstallmentPlanStatus::Defaulted, conn)?;
+        let tickets = TicketInstance::nullify_for_order(plan.order_id, current_user_id, conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::InstallmentPlanDefaulted,
+            "Installment plan defaulted".to_string(),
+            Tables::InstallmentPlans,
+            Some(plan.id),
+            current_user_id,
+            Some(json!({
+                "order_id": plan.order_id,
+                "ticket_ids": tickets.iter().map(|t| t.id).collect::<Vec<Uuid>>()
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(plan)
+    }
+
+    /// Stops the remaining installments from being charged
+    pub fn cancel(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<InstallmentPlan, DatabaseError> {
+        if self.status != InstallmentPlanStatus::Active {
+            return DatabaseError::business_process_error("Only active installment plans can be cancelled");
+        }
+        let plan = self.update_status(InstallmentPlanStatus::Cancelled, conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::InstallmentPlanCancelled,
+            "Installment plan cancelled".to_string(),
+            Tables::InstallmentPlans,
+            Some(plan.id),
+            current_user_id,
+            Some(json!({ "order_id": plan.order_id })),
+        )
+        .commit(conn)?;
+
+        Ok(plan)
+    }
+
+    /// Cancels the active plans for a cancelled event
+    pub fn cancel_for_event(
+        event_id: Uuid,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        let plans: Vec<InstallmentPlan> = installment_plans::table
+            .filter(installment_plans::event_id.eq(event_id))
+            .filter(installment_plans::status.eq(InstallmentPlanStatus::Active))
+            .for_update()
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading installment plans for event")?;
+        for plan in plans {
+            plan.cancel(current_user_id, conn)?;
+        }
+        Ok(())
+    }
+
+    /// Called when the plan's order is refunded. An active plan is cancelled, which requires every
+    /// ticket to be refunded, and the refund is capped at what has been collected as installments
+    /// that were never charged are not returned.
+    pub fn refund_order(
+        &self,
+        order: &Order,
+        refund_due: i64,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<i64, DatabaseError> {
+        if self.status == InstallmentPlanStatus::Active {
+            if order
+                .items(conn)?
+                .iter()
+                .any(|i| i.item_type == OrderItemTypes::Tickets && i.refunded_quantity < i.quantity)
+            {
+                return DatabaseError::business_process_error(
+                    "Orders with an active installment plan can only be refunded in full",
+                );
+            }
+            self.cancel(current_user_id, conn)?;
+        }
+        if self.status == InstallmentPlanStatus::Completed {
+            return Ok(refund_due);
+        }
+        Ok(cmp::min(refund_due, order.refundable_payment_total(conn)?))
+    }
+
+    pub(crate) fn queue_default(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        DomainAction::create(
+            None,
+            DomainActionTypes::DefaultInstallmentPlan,
+            None,
+            json!({ "installment_plan_id": self.id }),
+            Some(Tables::InstallmentPlans),
+            Some(self.id),
+        )
+        .commit(conn)?;
+        Ok(())
+    }
+
+    fn update_status(
+        &self,
+        status: InstallmentPlanStatus,
+        conn: &PgConnection,
+    ) -> Result<InstallmentPlan, DatabaseError> {
+        let now = dates::now().finish();
+        diesel::update(self)
+            .set((
+                installment_plans::status.eq(status),
+                installment_plans::completed_at.eq(if status == InstallmentPlanStatus::Completed {
+                    Some(now)
+                } else {
+                    self.completed_at
+                }),
+                installment_plans::defaulted_at.eq(if status == InstallmentPlanStatus::Defaulted {
+                    Some(now)
+                } else {
+                    self.defaulted_at
+                }),
+                installment_plans::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update installment plan")
+    }
+}
@@ -0,0 +1,187 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::installment_policies;
+use utils::errors::*;
+use uuid::Uuid;
+use validators::{self, *};
+
+/// Allows an event's tickets to be bought with a deposit at checkout followed by scheduled
+/// charges to the purchaser's saved payment method. Events without a policy require full payment.
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Event)]
+#[table_name = "installment_policies"]
+pub struct InstallmentPolicy {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub deposit_percent: i32,
+    pub installment_count: i32,
+    pub interval_days: i32,
+    pub max_attempts: i32,
+    pub retry_interval_hours: i32,
+    pub refund_deposit_on_default: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(AsChangeset, Clone, Default, Deserialize, Serialize)]
+#[table_name = "installment_policies"]
+pub struct InstallmentPolicyEditableAttributes {
+    pub deposit_percent: Option<i32>,
+    pub installment_count: Option<i32>,
+    pub interval_days: Option<i32>,
+    pub max_attempts: Option<i32>,
+    pub retry_interval_hours: Option<i32>,
+    pub refund_deposit_on_default: Option<bool>,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "installment_policies"]
+pub struct NewInstallmentPolicy {
+    pub event_id: Uuid,
+    pub deposit_percent: i32,
+    pub installment_count: i32,
+    pub interval_days: i32,
+    pub max_attempts: i32,
+    pub retry_interval_hours: i32,
+    pub refund_deposit_on_default: bool,
+}
+
+impl NewInstallmentPolicy {
+    pub fn commit(
+        &self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<InstallmentPolicy, DatabaseError> {
+        InstallmentPolicy::validate(&InstallmentPolicyEditableAttributes {
+            deposit_percent: Some(self.deposit_percent),
+            installment_count: Some(self.installment_count),
+            interval_days: Some(self.interval_days),
+            max_attempts: Some(self.max_attempts),
+            retry_interval_hours: Some(self.retry_interval_hours),
+            refund_deposit_on_default: Some(self.refund_deposit_on_default),
+        })?;
+
+        let result: InstallmentPolicy = diesel::insert_into(installment_policies::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create installment policy")?;
+
+        DomainEvent::create(
+            DomainEventTypes::InstallmentPolicyCreated,
+            "Installment policy created".to_string(),
+            Tables::Events,
+            Some(self.event_id),
+            current_user_id,
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+}
+
+impl InstallmentPolicy {
+    pub fn create(
+        event_id: Uuid,
+        deposit_percent: i32,
+        installment_count: i32,
+        interval_days: i32,
+        max_attempts: i32,
+        retry_interval_hours: i32,
+        refund_deposit_on_default: bool,
+    ) -> NewInstallmentPolicy {
+        NewInstallmentPolicy {
+            event_id,
+            deposit_percent,
+            installment_count,
+            interval_days,
+            max_attempts,
+            retry_interval_hours,
+            refund_deposit_on_default,
+        }
+    }
+
+    pub fn find_for_event(event_id: Uuid, conn: &PgConnection) -> Result<Option<InstallmentPolicy>, DatabaseError> {
+        installment_policies::table
+            .filter(installment_policies::event_id.eq(event_id))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Error loading installment policy")
+    }
+
+    pub fn update(
+        &self,
+        attributes: InstallmentPolicyEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<InstallmentPolicy, DatabaseError> {
+        InstallmentPolicy::validate(&attributes)?;
+
+        let result = diesel::update(self)
+            .set((&attributes, installment_policies::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update installment policy")?;
+
+        DomainEvent::create(
+            DomainEventTypes::InstallmentPolicyUpdated,
+            "Installment policy updated".to_string(),
+            Tables::Events,
+            Some(self.event_id),
+            current_user_id,
+            Some(json!(&attributes)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn validate(attributes: &InstallmentPolicyEditableAttributes) -> Result<(), DatabaseError> {
+        let mut validation_errors = Ok(());
+        if let Some(deposit_percent) = attributes.deposit_percent {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "deposit_percent",
+                validators::validate_greater_than(
+                    deposit_percent,
+                    0,
+                    "number_must_be_positive",
+                    "Deposit must be greater than zero",
+                ),
+            );
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "deposit_percent",
+                validators::validate_less_than(
+                    deposit_percent,
+                    100,
+                    "deposit_percent_too_high",
+                    "Deposit must be less than the full amount",
+                ),
+            );
+        }
+        for (field, value) in &[
+            ("installment_count", attributes.installment_count),
+            ("interval_days", attributes.interval_days),
+            ("max_attempts", attributes.max_attempts),
+            ("retry_interval_hours", attributes.retry_interval_hours),
+        ] {
+            if let Some(value) = value {
+                validation_errors = validators::append_validation_error(
+                    validation_errors,
+                    *field,
+                    validators::validate_greater_than(
+                        *value,
+                        0,
+                        "number_must_be_positive",
+                        "Value must be greater than zero",
+                    ),
+                );
+            }
+        }
+
+        Ok(validation_errors?)
+    }
+}
@@ -0,0 +1,170 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::installments;
+use serde_json;
+use utils::dates;
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(InstallmentPlan)]
+#[table_name = "installments"]
+pub struct Installment {
+    pub id: Uuid,
+    pub installment_plan_id: Uuid,
+    pub amount_in_cents: i64,
+    pub due_at: NaiveDateTime,
+    pub status: InstallmentStatus,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub payment_id: Option<Uuid>,
+    pub paid_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "installments"]
+pub struct NewInstallment {
+    pub installment_plan_id: Uuid,
+    pub amount_in_cents: i64,
+    pub due_at: NaiveDateTime,
+}
+
+impl Installment {
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<Installment, DatabaseError> {
+        installments::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading installment")
+    }
+
+    pub fn find_for_plan(installment_plan_id: Uuid, conn: &PgConnection) -> Result<Vec<Installment>, DatabaseError> {
+        installments::table
+            .filter(installments::installment_plan_id.eq(installment_plan_id))
+            .order_by(installments::due_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading installments")
+    }
+
+    pub fn installment_plan(&self, conn: &PgConnection) -> Result<InstallmentPlan, DatabaseError> {
+        InstallmentPlan::find(self.installment_plan_id, conn)
+    }
+
+    pub fn queue_payment(&self, scheduled_at: NaiveDateTime, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let mut action = DomainAction::create(
+            None,
+            DomainActionTypes::ProcessInstallmentPayment,
+            None,
+            json!({ "installment_id": self.id }),
+            Some(Tables::InstallmentPlans),
+            Some(self.installment_plan_id),
+        );
+        action.schedule_at(scheduled_at);
+        action.commit(conn)?;
+        Ok(())
+    }
+
+    /// Records a successful charge against the plan's payment method and completes the plan once
+    /// every installment has been paid
+    pub fn record_payment(
+        &self,
+        provider: PaymentProviders,
+        external_reference: String,
+        raw_data: serde_json::Value,
+        conn: &PgConnection,
+    ) -> Result<Payment, DatabaseError> {
+        if self.status != InstallmentStatus::Scheduled {
+            return DatabaseError::business_process_error("Installment is not awaiting payment");
+        }
+        let plan = self.installment_plan(conn)?;
+        let order = Order::find(plan.order_id, conn)?;
+
+        let payment = Payment::create(
+            order.id,
+            None,
+            PaymentStatus::Completed,
+            PaymentMethods::CreditCard,
+            provider,
+            Some(external_reference),
+            self.amount_in_cents,
+            order.currency.clone(),
+            Some(raw_data),
+            None,
+            None,
+        )
+        .commit(None, conn)?;
+
+        diesel::update(self)
+            .set((
+                installments::status.eq(InstallmentStatus::Paid),
+                installments::payment_id.eq(payment.id),
+                installments::paid_at.eq(dates::now().finish()),
+                installments::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update installment")?;
+
+        DomainEvent::create(
+            DomainEventTypes::InstallmentPaid,
+            "Installment paid".to_string(),
+            Tables::InstallmentPlans,
+            Some(plan.id),
+            None,
+            Some(
+                json!({ "installment_id": self.id, "payment_id": payment.id, "amount_in_cents": self.amount_in_cents }),
+            ),
+        )
+        .commit(conn)?;
+
+        plan.complete_if_fully_paid(conn)?;
+
+        Ok(payment)
+    }
+
+    /// Records a failed charge. The charge is retried after the plan's retry interval until the
+    /// maximum number of attempts is reached, at which point the plan is queued to be defaulted.
+    pub fn record_failed_attempt(&self, error: &str, conn: &PgConnection) -> Result<Installment, DatabaseError> {
+        if self.status != InstallmentStatus::Scheduled {
+            return DatabaseError::business_process_error("Installment is not awaiting payment");
+        }
+        let plan = self.installment_plan(conn)?;
+        let attempts = self.attempts + 1;
+        let status = if attempts >= plan.max_attempts {
+            InstallmentStatus::Failed
+        } else {
+            InstallmentStatus::Scheduled
+        };
+
+        let installment: Installment = diesel::update(self)
+            .set((
+                installments::attempts.eq(attempts),
+                installments::status.eq(status),
+                installments::last_error.eq(error),
+                installments::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update installment")?;
+
+        DomainEvent::create(
+            DomainEventTypes::InstallmentPaymentFailed,
+            "Installment payment failed".to_string(),
+            Tables::InstallmentPlans,
+            Some(plan.id),
+            None,
+            Some(json!({ "installment_id": self.id, "attempts": attempts, "error": error })),
+        )
+        .commit(conn)?;
+
+        if status == InstallmentStatus::Failed {
+            plan.queue_default(conn)?;
+        } else {
+            installment.queue_payment(dates::now().add_hours(plan.retry_interval_hours as i64).finish(), conn)?;
+        }
+
+        Ok(installment)
+    }
+}
@@ -0,0 +1,207 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Nullable, Uuid as dUuid};
+use models::*;
+use schema::{assets, inventory_pools, ticket_instances, ticket_types};
+use utils::errors::*;
+use uuid::Uuid;
+use validators::{self, *};
+
+/// Event level capacity shared by several ticket types. Each ticket type keeps its own ticket
+/// instances but can only sell them while the pool has capacity remaining.
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Event)]
+#[table_name = "inventory_pools"]
+pub struct InventoryPool {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub name: String,
+    pub capacity: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(AsChangeset, Clone, Default, Deserialize, Serialize)]
+#[table_name = "inventory_pools"]
+pub struct InventoryPoolEditableAttributes {
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub name: Option<String>,
+    pub capacity: Option<i32>,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "inventory_pools"]
+pub struct NewInventoryPool {
+    pub event_id: Uuid,
+    pub name: String,
+    pub capacity: i32,
+}
+
+/// Availability of a pooled ticket type alongside the remaining capacity of its pool
+#[derive(Clone, Debug, Deserialize, PartialEq, QueryableByName, Serialize)]
+pub struct InventoryPoolTicketTypeAvailability {
+    #[sql_type = "dUuid"]
+    pub organization_id: Uuid,
+    #[sql_type = "dUuid"]
+    pub event_id: Uuid,
+    #[sql_type = "dUuid"]
+    pub inventory_pool_id: Uuid,
+    #[sql_type = "dUuid"]
+    pub ticket_type_id: Uuid,
+    #[sql_type = "BigInt"]
+    pub available_count: i64,
+    #[sql_type = "BigInt"]
+    pub pool_available_count: i64,
+}
+
+impl NewInventoryPool {
+    pub fn commit(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<InventoryPool, DatabaseError> {
+        InventoryPool::validate_capacity(self.capacity)?;
+
+        let result: InventoryPool = diesel::insert_into(inventory_pools::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create inventory pool")?;
+
+        DomainEvent::create(
+            DomainEventTypes::InventoryPoolCreated,
+            "Inventory pool created".to_string(),
+            Tables::InventoryPools,
+            Some(result.id),
+            current_user_id,
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+}
+
+impl InventoryPool {
+    pub fn create(event_id: Uuid, name: String, capacity: i32) -> NewInventoryPool {
+        NewInventoryPool {
+            event_id,
+            name,
+            capacity,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<InventoryPool, DatabaseError> {
+        inventory_pools::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading inventory pool")
+    }
+
+    /// Loads the pool with a row lock so that ticket types sharing it cannot oversell it
+    pub fn find_for_update(id: Uuid, conn: &PgConnection) -> Result<InventoryPool, DatabaseError> {
+        inventory_pools::table
+            .find(id)
+            .for_update()
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading inventory pool")
+    }
+
+    pub fn find_for_event(event_id: Uuid, conn: &PgConnection) -> Result<Vec<InventoryPool>, DatabaseError> {
+        inventory_pools::table
+            .filter(inventory_pools::event_id.eq(event_id))
+            .order_by(inventory_pools::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load inventory pools for event")
+    }
+
+    pub fn ticket_types(&self, conn: &PgConnection) -> Result<Vec<TicketType>, DatabaseError> {
+        ticket_types::table
+            .filter(ticket_types::inventory_pool_id.eq(self.id))
+            .filter(ticket_types::deleted_at.is_null())
+            .order_by(ticket_types::rank)
+            .then_order_by(ticket_types::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load ticket types for inventory pool")
+    }
+
+    /// Number of tickets sold or currently reserved across all ticket types in the pool.
+    /// Held tickets only count against the pool once they are reserved.
+    pub fn allocated_count(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        ticket_instances::table
+            .inner_join(assets::table.inner_join(ticket_types::table))
+            .filter(ticket_types::inventory_pool_id.eq(self.id))
+            .filter(
+                ticket_instances::status
+                    .eq_any(vec![TicketInstanceStatus::Purchased, TicketInstanceStatus::Redeemed])
+                    .or(ticket_instances::status
+                        .eq(TicketInstanceStatus::Reserved)
+                        .and(ticket_instances::reserved_until.ge(dsl::now.nullable()))),
+            )
+            .select(dsl::count(ticket_instances::id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to count tickets for inventory pool")
+    }
+
+    pub fn available_count(&self, conn: &PgConnection) -> Result<u32, DatabaseError> {
+        let available = self.capacity as i64 - self.allocated_count(conn)?;
+        Ok(if available > 0 { available as u32 } else { 0 })
+    }
+
+    pub fn ticket_type_availability(
+        event_id: Option<Uuid>,
+        organization_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<InventoryPoolTicketTypeAvailability>, DatabaseError> {
+        let query = include_str!("../queries/inventory_pool_ticket_type_availability.sql");
+        diesel::sql_query(query)
+            .bind::<Nullable<dUuid>, _>(event_id)
+            .bind::<Nullable<dUuid>, _>(organization_id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load inventory pool availability")
+    }
+
+    pub fn update(
+        &self,
+        attributes: InventoryPoolEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<InventoryPool, DatabaseError> {
+        if let Some(capacity) = attributes.capacity {
+            InventoryPool::validate_capacity(capacity)?;
+            if (capacity as i64) < self.allocated_count(conn)? {
+                return DatabaseError::validation_error(
+                    "capacity",
+                    "Capacity cannot be lower than the number of tickets already sold or reserved",
+                );
+            }
+        }
+
+        let result = diesel::update(self)
+            .set((&attributes, inventory_pools::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update inventory pool")?;
+
+        DomainEvent::create(
+            DomainEventTypes::InventoryPoolUpdated,
+            "Inventory pool updated".to_string(),
+            Tables::InventoryPools,
+            Some(self.id),
+            current_user_id,
+            Some(json!(&attributes)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn validate_capacity(capacity: i32) -> Result<(), DatabaseError> {
+        Ok(validators::append_validation_error(
+            Ok(()),
+            "capacity",
+            validators::validate_greater_than_or_equal(
+                capacity,
+                0,
+                "number_must_be_positive",
+                "Capacity must be positive",
+            ),
+        )?)
+    }
+}
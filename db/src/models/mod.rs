@@ -1,3 +1,5 @@
+pub use self::access_zone_users::*;
+pub use self::access_zones::*;
 pub use self::activities::*;
 pub use self::announcement_engagements::*;
 pub use self::announcements::*;
@@ -9,13 +11,20 @@ pub use self::codes::*;
 pub use self::collection_items::*;
 pub use self::collections::*;
 pub use self::communication::*;
+pub use self::disputes::*;
 pub use self::domain_actions::*;
 pub use self::domain_event_publishers::*;
 pub use self::domain_events::*;
+pub use self::dynamic_pricing_strategies::*;
 pub use self::enums::*;
 pub use self::event_artists::*;
 pub use self::event_interest::*;
+pub use self::event_refund_batch_orders::*;
+pub use self::event_refund_batches::*;
 pub use self::event_report_subscribers::*;
+pub use self::event_reschedule_responses::*;
+pub use self::event_reschedules::*;
+pub use self::event_time_slots::*;
 pub use self::event_users::*;
 pub use self::events::*;
 pub use self::external_logins::FACEBOOK_SITE;
@@ -25,9 +34,15 @@ pub use self::fee_schedule_ranges::*;
 pub use self::fee_schedules::*;
 pub use self::for_display::*;
 pub use self::genres::*;
+pub use self::gift_cards::*;
 pub use self::global::*;
 pub use self::history_item::*;
 pub use self::holds::*;
+pub use self::idempotency_keys::*;
+pub use self::installment_plans::*;
+pub use self::installment_policies::*;
+pub use self::installments::*;
+pub use self::inventory_pools::*;
 pub use self::listings::*;
 pub use self::loot_box_contents::*;
 pub use self::marketplace_accounts::*;
@@ -40,13 +55,19 @@ pub use self::organization_users::*;
 pub use self::organization_venues::*;
 pub use self::organizations::*;
 pub use self::paging::*;
+pub use self::pass_events::*;
+pub use self::passes::*;
 pub use self::payment_methods::*;
 pub use self::payments::*;
 pub use self::platforms::*;
+pub use self::product_variants::*;
+pub use self::products::*;
 pub use self::push_notification_tokens::*;
 pub use self::rarities::*;
 pub use self::redeemable_ticket::*;
 pub use self::refund_items::*;
+pub use self::refund_policies::*;
+pub use self::refund_requests::*;
 pub use self::refunded_tickets::*;
 pub use self::refunds::*;
 pub use self::regions::*;
@@ -57,21 +78,30 @@ pub use self::settlement_entries::*;
 pub use self::settlements::*;
 pub use self::slugs::*;
 pub use self::stages::*;
+pub use self::store_credit_transactions::*;
+pub use self::tax_rules::*;
 pub use self::temporary_users::*;
+pub use self::ticket_attendee_changes::*;
 pub use self::ticket_instances::RedeemResults;
 pub use self::ticket_instances::*;
 pub use self::ticket_pricing::*;
+pub use self::ticket_scans::*;
 pub use self::ticket_type_codes::*;
 pub use self::ticket_types::*;
 pub use self::transfer_tickets::*;
 pub use self::transfers::*;
 pub use self::users::*;
+pub use self::venue_seats::*;
+pub use self::venue_sections::*;
 pub use self::venues::*;
+pub use self::waitlist_entries::*;
 pub use self::wallets::*;
 
 use serde::{Deserialize, Deserializer};
 use serde_json::Value;
 
+mod access_zone_users;
+mod access_zones;
 pub mod concerns;
 
 mod activities;
@@ -86,13 +116,20 @@ mod codes;
 mod collection_items;
 mod collections;
 mod communication;
+mod disputes;
 mod domain_actions;
 mod domain_event_publishers;
 mod domain_events;
+mod dynamic_pricing_strategies;
 pub mod enums;
 mod event_artists;
 mod event_interest;
+mod event_refund_batch_orders;
+mod event_refund_batches;
 mod event_report_subscribers;
+mod event_reschedule_responses;
+mod event_reschedules;
+mod event_time_slots;
 mod event_users;
 mod events;
 mod external_logins;
@@ -101,9 +138,15 @@ mod fee_schedule_ranges;
 mod fee_schedules;
 mod for_display;
 mod genres;
+mod gift_cards;
 pub mod global;
 mod history_item;
 mod holds;
+mod idempotency_keys;
+mod installment_plans;
+mod installment_policies;
+mod installments;
+mod inventory_pools;
 mod listings;
 mod loot_box_contents;
 mod marketplace_accounts;
@@ -116,13 +159,19 @@ mod organization_users;
 mod organization_venues;
 mod organizations;
 mod paging;
+mod pass_events;
+mod passes;
 mod payment_methods;
 mod payments;
 mod platforms;
+mod product_variants;
+mod products;
 mod push_notification_tokens;
 mod rarities;
 mod redeemable_ticket;
 mod refund_items;
+mod refund_policies;
+mod refund_requests;
 mod refunded_tickets;
 mod refunds;
 mod regions;
@@ -133,15 +182,22 @@ mod settlement_entries;
 mod settlements;
 mod slugs;
 mod stages;
+mod store_credit_transactions;
+mod tax_rules;
 mod temporary_users;
+mod ticket_attendee_changes;
 mod ticket_instances;
 mod ticket_pricing;
+mod ticket_scans;
 mod ticket_type_codes;
 mod ticket_types;
 mod transfer_tickets;
 mod transfers;
 mod users;
+mod venue_seats;
+mod venue_sections;
 mod venues;
+mod waitlist_entries;
 mod wallets;
 
 pub fn deserialize_unless_blank<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
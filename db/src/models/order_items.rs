@@ -39,6 +39,11 @@ pub struct OrderItem {
     pub company_fee_in_cents: i64,
     pub client_fee_in_cents: i64,
     pub refunded_quantity: i64,
+    pub pass_id: Option<Uuid>,
+    pub product_variant_id: Option<Uuid>,
+    pub redeemed_quantity: i64,
+    pub tax_rule_id: Option<Uuid>,
+    pub included_tax_in_cents: i64,
 }
 
 impl OrderItem {
@@ -68,6 +73,14 @@ impl OrderItem {
             .to_db_error(ErrorCode::QueryError, "Could not retrieve order item discount")
     }
 
+    pub fn find_tax_items(&self, conn: &PgConnection) -> Result<Vec<OrderItem>, DatabaseError> {
+        order_items::table
+            .filter(order_items::parent_id.eq(self.id))
+            .filter(order_items::item_type.eq(OrderItemTypes::Tax))
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not retrieve order item taxes")
+    }
+
     pub fn description(&self, conn: &PgConnection) -> Result<String, DatabaseError> {
         use models::OrderItemTypes::*;
         let res = match self.item_type {
@@ -81,6 +94,15 @@ impl OrderItem {
             }
             Discount => "Discount".to_string(),
             CreditCardFees => "Credit Card Fees".to_string(),
+            NameChangeFees => "Attendee Name Change Fee".to_string(),
+            Products => match self.product_variant_id {
+                Some(product_variant_id) => ProductVariant::find(product_variant_id, conn)?.description(conn)?,
+                None => "Other".to_string(),
+            },
+            Tax => match self.tax_rule_id {
+                Some(tax_rule_id) => format!("Tax - {}", TaxRule::find(tax_rule_id, conn)?.name),
+                None => "Tax".to_string(),
+            },
             _ => {
                 let ticket_type = self.ticket_type(conn)?;
                 match ticket_type {
@@ -115,6 +137,10 @@ impl OrderItem {
         }
 
         let mut refund_amount_in_cents = self.unit_price_in_cents + discount_amount;
+        // Tax is refunded alongside the item it was charged on
+        for mut tax_item in self.find_tax_items(conn)? {
+            refund_amount_in_cents += tax_item.refund_one_unit(true, conn)?;
+        }
         // Refund fees if ticket is being refunded
         if refund_fees && self.item_type == OrderItemTypes::Tickets {
             let fee_item = self.find_fee_item(conn)?;
@@ -133,6 +159,62 @@ impl OrderItem {
         Ok(refund_amount_in_cents)
     }
 
+    /// Marks units of a purchased product as handed over, e.g. when a drink voucher or parking
+    /// pass is presented at the box office
+    pub fn redeem_product_voucher(
+        &self,
+        quantity: u32,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<OrderItem, DatabaseError> {
+        if self.item_type != OrderItemTypes::Products {
+            return DatabaseError::business_process_error("Only product order items can be redeemed as vouchers");
+        }
+        if self.order(conn)?.status != OrderStatus::Paid {
+            return DatabaseError::business_process_error("Order must be paid before vouchers can be redeemed");
+        }
+
+        let order_item: OrderItem = order_items::table
+            .find(self.id)
+            .for_update()
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not retrieve order item")?;
+        let remaining_quantity = order_item.quantity - order_item.refunded_quantity - order_item.redeemed_quantity;
+        if quantity == 0 || quantity as i64 > remaining_quantity {
+            let mut validation_error =
+                create_validation_error("voucher_quantity_unavailable", "Not enough unredeemed vouchers remain");
+            validation_error.add_param(Cow::from("remaining_quantity"), &remaining_quantity);
+            validation_error.add_param(Cow::from("quantity"), &quantity);
+            let mut errors = ValidationErrors::new();
+            errors.add("quantity", validation_error);
+            return Err(errors.into());
+        }
+
+        let result: OrderItem = diesel::update(&order_item)
+            .set((
+                order_items::redeemed_quantity.eq(order_item.redeemed_quantity + quantity as i64),
+                order_items::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not redeem product voucher")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ProductVoucherRedeemed,
+            "Product voucher redeemed".to_string(),
+            Tables::Orders,
+            Some(self.order_id),
+            current_user_id,
+            Some(json!({
+                "order_item_id": self.id,
+                "product_variant_id": self.product_variant_id,
+                "quantity": quantity
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
     pub fn code(&self, conn: &PgConnection) -> Result<Option<Code>, DatabaseError> {
         match self.code_id {
             Some(code_id) => codes::table
@@ -157,6 +239,8 @@ impl OrderItem {
             || self.item_type == OrderItemTypes::EventFees
             || self.item_type == OrderItemTypes::Discount
             || self.item_type == OrderItemTypes::CreditCardFees
+            || self.item_type == OrderItemTypes::Products
+            || self.item_type == OrderItemTypes::Tax
         {
             return Ok(());
         }
@@ -480,6 +564,12 @@ impl OrderItem {
             event_id: Uuid,
             #[sql_type = "dUuid"]
             order_id: Uuid,
+            #[sql_type = "Nullable<dUuid>"]
+            product_variant_id: Option<Uuid>,
+            #[sql_type = "BigInt"]
+            redeemed_quantity: i64,
+            #[sql_type = "BigInt"]
+            included_tax_in_cents: i64,
         }
 
         let results: Vec<R> = diesel::sql_query(
@@ -498,6 +588,8 @@ impl OrderItem {
              WHEN item_type = 'EventFees' THEN 'Event Fees - ' || e.name
              WHEN item_type = 'Discount' THEN 'Discount'
              WHEN item_type = 'CreditCardFees' THEN 'Credit Card Fees'
+             WHEN item_type = 'Products' THEN p.name || ' - ' || pv.name
+             WHEN item_type = 'Tax' THEN 'Tax - ' || tr.name
              ELSE e.name || ' - ' || tt.name
            END AS description,
            COALESCE(h.redemption_code, c.redemption_code) as redemption_code,
@@ -512,7 +604,10 @@ impl OrderItem {
              ELSE 'Valid'
            END AS cart_item_status,
            e.id AS event_id,
-           oi.order_id
+           oi.order_id,
+           oi.product_variant_id,
+           oi.redeemed_quantity,
+           oi.included_tax_in_cents
         FROM order_items oi
            JOIN orders o ON oi.order_id = o.id
            LEFT JOIN ticket_pricing tp ON tp.id = oi.ticket_pricing_id
@@ -532,6 +627,9 @@ impl OrderItem {
                LIMIT 1
            )
            LEFT JOIN codes c ON oi.code_id = c.id
+           LEFT JOIN product_variants pv ON oi.product_variant_id = pv.id
+           LEFT JOIN products p ON pv.product_id = p.id
+           LEFT JOIN tax_rules tr ON oi.tax_rule_id = tr.id
            LEFT JOIN (
                SELECT count(ti.id) as count, oi.id
                FROM order_items oi
@@ -580,6 +678,9 @@ impl OrderItem {
                     redemption_code: item.redemption_code,
                     cart_item_status: item.cart_item_status,
                     event_id: item.event_id,
+                    product_variant_id: item.product_variant_id,
+                    redeemed_quantity: item.redeemed_quantity,
+                    included_tax_in_cents: item.included_tax_in_cents,
                 });
             }
             order_items.insert(order_id, display_items);
@@ -630,6 +731,7 @@ pub(crate) struct NewTicketsOrderItem {
     pub ticket_pricing_id: Uuid,
     pub hold_id: Option<Uuid>,
     pub code_id: Option<Uuid>,
+    pub pass_id: Option<Uuid>,
 }
 
 impl NewTicketsOrderItem {
@@ -690,6 +792,67 @@ impl NewFeesOrderItem {
     }
 }
 
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name = "order_items"]
+pub(crate) struct NewProductsOrderItem {
+    pub order_id: Uuid,
+    pub item_type: OrderItemTypes,
+    pub event_id: Option<Uuid>,
+    pub quantity: i64,
+    pub unit_price_in_cents: i64,
+    pub product_variant_id: Uuid,
+}
+
+impl NewProductsOrderItem {
+    pub(crate) fn commit(self, conn: &PgConnection) -> Result<OrderItem, DatabaseError> {
+        diesel::insert_into(order_items::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create order item")
+    }
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name = "order_items"]
+pub(crate) struct NewNameChangeFeesOrderItem {
+    pub order_id: Uuid,
+    pub item_type: OrderItemTypes,
+    pub event_id: Option<Uuid>,
+    pub quantity: i64,
+    pub unit_price_in_cents: i64,
+}
+
+impl NewNameChangeFeesOrderItem {
+    pub(crate) fn commit(self, conn: &PgConnection) -> Result<OrderItem, DatabaseError> {
+        diesel::insert_into(order_items::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create order item")
+    }
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name = "order_items"]
+pub(crate) struct NewTaxOrderItem {
+    pub order_id: Uuid,
+    pub item_type: OrderItemTypes,
+    pub event_id: Option<Uuid>,
+    pub quantity: i64,
+    pub unit_price_in_cents: i64,
+    pub included_tax_in_cents: i64,
+    pub tax_rule_id: Uuid,
+    pub parent_id: Uuid,
+}
+
+impl NewTaxOrderItem {
+    pub(crate) fn commit(self, conn: &PgConnection) -> Result<OrderItem, DatabaseError> {
+        diesel::insert_into(order_items::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create order item")
+    }
+}
+
 #[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
 #[table_name = "order_items"]
 pub(crate) struct NewDiscountOrderItem {
@@ -740,4 +903,10 @@ pub struct DisplayOrderItem {
     pub cart_item_status: Option<CartItemStatus>,
     #[sql_type = "dUuid"]
     pub event_id: Uuid,
+    #[sql_type = "Nullable<dUuid>"]
+    pub product_variant_id: Option<Uuid>,
+    #[sql_type = "BigInt"]
+    pub redeemed_quantity: i64,
+    #[sql_type = "BigInt"]
+    pub included_tax_in_cents: i64,
 }
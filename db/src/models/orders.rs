@@ -3,7 +3,7 @@ use chrono::prelude::*;
 use chrono::Duration;
 use dev::times;
 use diesel;
-use diesel::dsl::{exists, select};
+use diesel::dsl::{exists, not, select};
 use diesel::expression::dsl;
 use diesel::expression::sql_literal::sql;
 use diesel::pg::types::sql_types::Array;
@@ -14,12 +14,13 @@ use itertools::Itertools;
 use log::Level::{self, Debug};
 use models::*;
 use schema::{
-    event_users, events, order_items, order_transfers, orders, organization_users, organizations, payments, refunds,
-    transfers, users,
+    event_users, events, order_items, order_transfers, orders, organization_users, organizations, payments,
+    product_variants, refunds, transfers, users,
 };
 use serde_json;
 use serde_json::Value;
 use std::borrow::Cow;
+use std::cmp;
 use std::collections::HashMap;
 use url::Url;
 use utils::dates::*;
@@ -64,6 +65,7 @@ pub struct Order {
     #[serde(skip_serializing)]
     pub settlement_id: Option<Uuid>,
     pub referrer: Option<String>,
+    pub currency: String,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -110,6 +112,8 @@ pub struct OrderDetailsLineItem {
     pub pending_transfer_id: Option<Uuid>,
     #[sql_type = "Nullable<BigInt>"]
     pub discount_price_in_cents: Option<i64>,
+    #[sql_type = "Nullable<Text>"]
+    pub seat_label: Option<String>,
 }
 
 #[derive(Debug)]
@@ -292,6 +296,10 @@ impl Order {
             if item.item_type != OrderItemTypes::Tickets {
                 continue;
             }
+            if item.pass_id.is_some() {
+                valid = false;
+                break;
+            }
             if let Some(ticket_type_id) = item.ticket_type_id {
                 let (ticket_type, available_quantity) = if let Some(ticket_types_map) = ticket_type_cache {
                     let (tt, q) = ticket_types_map.get(&ticket_type_id).ok_or_else(|| {
@@ -445,6 +453,8 @@ impl Order {
         let event_count = order_items::table
             .filter(order_items::order_id.eq(id))
             .filter(order_items::event_id.is_not_null())
+            // Passes span several events by design
+            .filter(order_items::pass_id.is_null())
             .select(sql::<BigInt>("count(distinct event_id) AS event_count"))
             .get_result::<i64>(conn)
             .to_db_error(ErrorCode::QueryError, "Could not get count of unique events in cart")?;
@@ -499,6 +509,18 @@ impl Order {
             );
         }
 
+        // delete grandchildren order items such as taxes charged on per unit fees
+        diesel::delete(
+            order_items::table.filter(
+                sql("order_items.parent_id IN (SELECT oi.id FROM order_items oi WHERE oi.parent_id = ")
+                    .bind::<dUuid, _>(item_id)
+                    .sql(")"),
+            ),
+        )
+        .execute(conn)
+        .map(|_| ())
+        .to_db_error(ErrorCode::DeleteError, "Could not delete child order item")?;
+
         // delete children order items
         diesel::delete(order_items::table.filter(order_items::parent_id.eq(item_id)))
             .execute(conn)
@@ -571,6 +593,156 @@ impl Order {
         reason: Option<String>,
         manual_override: bool,
         conn: &PgConnection,
+    ) -> Result<(Refund, i64), DatabaseError> {
+        self.refund_items(refund_data, user_id, reason, manual_override, false, conn)
+    }
+
+    /// Refund items for the order's purchased tickets to the event that have not been transferred.
+    /// Unrefunded event fees are included when `include_event_fees` is set.
+    pub fn refundable_items_for_event(
+        &self,
+        event_id: Uuid,
+        include_event_fees: bool,
+        conn: &PgConnection,
+    ) -> Result<Vec<RefundItemRequest>, DatabaseError> {
+        let mut items = Vec::new();
+        for order_item in self.items(conn)? {
+            if order_item.event_id != Some(event_id) {
+                continue;
+            }
+            match order_item.item_type {
+                OrderItemTypes::Tickets => {
+                    for ticket_instance in TicketInstance::find_for_order_item(order_item.id, conn)? {
+                        if ticket_instance.status == TicketInstanceStatus::Purchased
+                            && !ticket_instance.was_transferred(conn)?
+                        {
+                            items.push(RefundItemRequest {
+                                order_item_id: order_item.id,
+                                ticket_instance_id: Some(ticket_instance.id),
+                            });
+                        }
+                    }
+                }
+                OrderItemTypes::EventFees
+                    if include_event_fees && order_item.refunded_quantity < order_item.quantity =>
+                {
+                    items.push(RefundItemRequest {
+                        order_item_id: order_item.id,
+                        ticket_instance_id: None,
+                    });
+                }
+                _ => (),
+            }
+        }
+        Ok(items)
+    }
+
+    /// Completed payments with the amount each has left to refund. Payments sharing an external
+    /// reference are a single charge with the processor and are returned against the first
+    /// completed payment for that reference.
+    pub fn refundable_payments(&self, conn: &PgConnection) -> Result<Vec<(Payment, i64)>, DatabaseError> {
+        let payments = self.payments(conn)?;
+
+        // Negative payments / refunds cancel out remaining payment balance
+        let mut remaining_balances: HashMap<Option<String>, i64> = HashMap::new();
+        for payment in &payments {
+            // Ignore payments that were only authorized
+            if payment.status == PaymentStatus::Authorized {
+                continue;
+            }
+            *remaining_balances
+                .entry(payment.external_reference.clone())
+                .or_insert(0) += payment.amount;
+        }
+
+        let mut legs: Vec<(Payment, i64)> = Vec::new();
+        for payment in payments {
+            if payment.status != PaymentStatus::Completed {
+                continue;
+            }
+            if let Some(remaining_balance) = remaining_balances.remove(&payment.external_reference) {
+                if remaining_balance > 0 {
+                    legs.push((payment, remaining_balance));
+                }
+            }
+        }
+        Ok(legs)
+    }
+
+    /// Total that can still be refunded across the order's payments
+    pub fn refundable_payment_total(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        Ok(self
+            .refundable_payments(conn)?
+            .iter()
+            .map(|(_, remaining_balance)| remaining_balance)
+            .sum())
+    }
+
+    /// Splits a refund across the order's refundable payments in proportion to the amount each
+    /// payment has left to refund
+    pub fn refund_allocations(
+        &self,
+        refund_amount: i64,
+        conn: &PgConnection,
+    ) -> Result<Vec<(Payment, i64)>, DatabaseError> {
+        let legs = self.refundable_payments(conn)?;
+        let total_remaining: i64 = legs.iter().map(|(_, remaining_balance)| remaining_balance).sum();
+        if refund_amount > total_remaining {
+            return DatabaseError::business_process_error(&format!(
+                "Unable to refund amount owed {}, only {} remains to be refunded",
+                refund_amount, total_remaining
+            ));
+        }
+        if refund_amount <= 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut allocations: Vec<(Payment, i64, i64)> = legs
+            .into_iter()
+            .map(|(payment, remaining_balance)| {
+                let share = (refund_amount as i128 * remaining_balance as i128 / total_remaining as i128) as i64;
+                (payment, share, remaining_balance)
+            })
+            .collect();
+        // Rounding leaves at most one cent per leg unallocated
+        let mut unallocated = refund_amount - allocations.iter().map(|(_, share, _)| share).sum::<i64>();
+        for (_, share, remaining_balance) in allocations.iter_mut() {
+            if unallocated == 0 {
+                break;
+            }
+            if *share < *remaining_balance {
+                *share += 1;
+                unallocated -= 1;
+            }
+        }
+
+        Ok(allocations
+            .into_iter()
+            .filter(|(_, share, _)| *share > 0)
+            .map(|(payment, share, _)| (payment, share))
+            .collect())
+    }
+
+    /// Refunds tickets without their per unit fees, which are kept by the organizer as allowed by
+    /// the event's refund policy
+    pub fn refund_retaining_fees(
+        &mut self,
+        refund_data: &[RefundItemRequest],
+        user_id: Uuid,
+        reason: Option<String>,
+        conn: &PgConnection,
+    ) -> Result<(Refund, i64), DatabaseError> {
+        self.refund_items(refund_data, user_id, reason, false, true, conn)
+    }
+
+    fn refund_items(
+        &mut self,
+        refund_data: &[RefundItemRequest],
+        user_id: Uuid,
+        reason: Option<String>,
+        manual_override: bool,
+        retain_fees: bool,
+        conn: &PgConnection,
     ) -> Result<(Refund, i64), DatabaseError> {
         self.lock_version(conn)?;
         let mut total_to_be_refunded: i64 = 0;
@@ -583,6 +755,10 @@ impl Order {
             let mut order_item = OrderItem::find(refund_datum.order_item_id, conn)?;
             if order_item.item_type == OrderItemTypes::Discount {
                 return DatabaseError::business_process_error("Discount order items can not be refunded");
+            } else if order_item.item_type == OrderItemTypes::Tax {
+                return DatabaseError::business_process_error(
+                    "Tax order items are refunded with the order item they were charged on",
+                );
             } else if order_item.order_id != self.id {
                 return DatabaseError::business_process_error("Order item id does not belong to this order");
             }
@@ -601,12 +777,20 @@ impl Order {
                         );
                     }
                     Some(ref ticket_instance) => {
-                        total_to_be_refunded +=
-                            Order::refund_ticket_instance(&ticket_instance, &mut order_item, user_id, conn)?;
+                        total_to_be_refunded += Order::refund_ticket_instance(
+                            &ticket_instance,
+                            &mut order_item,
+                            retain_fees,
+                            user_id,
+                            conn,
+                        )?;
                     }
                 }
             } else {
                 total_to_be_refunded += order_item.refund_one_unit(true, conn)?;
+                if order_item.item_type == OrderItemTypes::Products {
+                    GiftCard::void_for_refunded_item(&order_item, Some(user_id), conn)?;
+                }
             }
         }
 
@@ -645,6 +829,7 @@ impl Order {
     fn refund_ticket_instance(
         ticket_instance: &TicketInstance,
         order_item: &mut OrderItem,
+        retain_fees: bool,
         user_id: Uuid,
         conn: &PgConnection,
     ) -> Result<i64, DatabaseError> {
@@ -659,10 +844,15 @@ impl Order {
         if ticket_instance.was_transferred(conn)? {
             return DatabaseError::business_process_error("Ticket was transferred so ineligible for refund");
         }
-        let refund_fees = refunded_ticket.fee_refunded_at.is_none();
+        if retain_fees && order_item.item_type == OrderItemTypes::PerUnitFees {
+            return DatabaseError::business_process_error("Fees are not refundable for this ticket");
+        }
+        let refund_fees = !retain_fees && refunded_ticket.fee_refunded_at.is_none();
 
         if order_item.item_type == OrderItemTypes::PerUnitFees {
             refunded_ticket.mark_fee_only_refunded(conn)?;
+        } else if retain_fees {
+            refunded_ticket.mark_ticket_only_refunded(conn)?;
         } else {
             refunded_ticket.mark_ticket_and_fee_refunded(conn)?;
         }
@@ -785,6 +975,24 @@ impl Order {
             return DatabaseError::business_process_error("Cart is not expired");
         }
 
+        if let Err(e) = self.reserve_refreshed_items(new_expires_at, current_user_id, conn) {
+            // The cart can no longer be completed so applied gift cards and store credit are returned
+            self.reverse_stored_value_payments(current_user_id, conn)?;
+            return Err(e);
+        }
+
+        // Update cart expiration
+        self.set_expiry(None, Some(new_expires_at), true, conn)?;
+
+        Ok(())
+    }
+
+    fn reserve_refreshed_items(
+        &self,
+        new_expires_at: NaiveDateTime,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
         for item in self.items(conn)? {
             if item.item_type != OrderItemTypes::Tickets {
                 continue;
@@ -807,10 +1015,61 @@ impl Order {
                 conn,
             )?;
         }
+        Ok(())
+    }
 
-        // Update cart expiration
-        self.set_expiry(None, Some(new_expires_at), true, conn)?;
+    /// Cancels the gift card and store credit payments applied to a draft order, returning their
+    /// value to the gift card or store credit they were taken from
+    pub fn reverse_stored_value_payments(
+        &self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        if self.status != OrderStatus::Draft {
+            return Ok(());
+        }
+        for payment in self.payments(conn)? {
+            if payment.status == PaymentStatus::Completed
+                && (payment.payment_method == PaymentMethods::GiftCard
+                    || payment.payment_method == PaymentMethods::StoreCredit)
+            {
+                payment.reverse_stored_value(current_user_id, conn)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns gift card and store credit payments held by expired carts without a payment in progress
+    pub fn reverse_expired_stored_value_payments(conn: &PgConnection) -> Result<(), DatabaseError> {
+        let expired_carts: Vec<Order> = orders::table
+            .filter(orders::status.eq(OrderStatus::Draft))
+            .filter(orders::expires_at.lt(dsl::now.nullable()))
+            .filter(exists(
+                payments::table
+                    .filter(payments::order_id.eq(orders::id))
+                    .filter(payments::status.eq(PaymentStatus::Completed))
+                    .filter(
+                        payments::payment_method.eq_any(vec![PaymentMethods::GiftCard, PaymentMethods::StoreCredit]),
+                    ),
+            ))
+            .filter(not(exists(
+                payments::table
+                    .filter(payments::order_id.eq(orders::id))
+                    .filter(payments::status.eq_any(vec![
+                        PaymentStatus::Requested,
+                        PaymentStatus::Authorized,
+                        PaymentStatus::PendingIpn,
+                        PaymentStatus::PendingConfirmation,
+                    ])),
+            )))
+            .for_update()
+            .skip_locked()
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load expired carts")?;
 
+        for cart in expired_carts {
+            cart.reverse_stored_value_payments(None, conn)?;
+        }
         Ok(())
     }
 
@@ -993,6 +1252,57 @@ impl Order {
         Ok(order)
     }
 
+    /// Creates a paid order for the name change fee charged to a ticket holder so that the fee is
+    /// included in the event's settlements and payment reports
+    pub(crate) fn create_for_name_change_fee(
+        ticket: &TicketInstance,
+        fee_payment: &NameChangeFeePayment,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Order, DatabaseError> {
+        let event = ticket.event(conn)?;
+        let mut order: Order = diesel::insert_into(orders::table)
+            .values((
+                orders::user_id.eq(current_user_id),
+                orders::status.eq(OrderStatus::Draft),
+                orders::order_type.eq(OrderTypes::Cart),
+                orders::currency.eq(&event.currency),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create name change fee order")?;
+
+        DomainEvent::create(
+            DomainEventTypes::OrderCreated,
+            "Order created".into(),
+            Tables::Orders,
+            Some(order.id),
+            Some(current_user_id),
+            Some(json!(order)),
+        )
+        .commit(conn)?;
+
+        NewNameChangeFeesOrderItem {
+            order_id: order.id,
+            item_type: OrderItemTypes::NameChangeFees,
+            event_id: Some(event.id),
+            quantity: 1,
+            unit_price_in_cents: fee_payment.amount_in_cents,
+        }
+        .commit(conn)?;
+
+        order.add_credit_card_payment(
+            current_user_id,
+            fee_payment.amount_in_cents,
+            fee_payment.payment_provider,
+            fee_payment.external_reference.clone(),
+            PaymentStatus::Completed,
+            fee_payment.provider_data.clone(),
+            conn,
+        )?;
+
+        Ok(order)
+    }
+
     pub fn find_cart_for_user(user_id: Uuid, conn: &PgConnection) -> Result<Option<Order>, DatabaseError> {
         users::table
             .inner_join(orders::table.on(users::last_cart_id.eq(orders::id.nullable())))
@@ -1319,8 +1629,13 @@ impl Order {
     pub fn clear_cart(&mut self, user_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
         jlog!(Level::Debug, "Clearing cart");
         self.lock_version(conn)?;
+        self.reverse_stored_value_payments(Some(user_id), conn)?;
 
         for current_line in self.items(conn)? {
+            if current_line.item_type == OrderItemTypes::Products {
+                self.destroy_item(current_line.id, conn)?;
+                continue;
+            }
             if current_line.item_type != OrderItemTypes::Tickets {
                 continue;
             }
@@ -1341,6 +1656,7 @@ impl Order {
         conn: &PgConnection,
     ) -> Result<(), DatabaseError> {
         self.lock_version(conn)?;
+        self.reverse_stored_value_payments(Some(current_user_id), conn)?;
 
         jlog!(Debug, "Update order quantities", {"items": items,"remove_others":remove_others, "user_id": current_user_id, "box_office_pricing":box_office_pricing });
 
@@ -1409,7 +1725,9 @@ impl Order {
             let mut index_to_remove: Option<usize> = None;
             {
                 let matching_result: Option<&MatchData> = mapped.iter().find(|match_data| {
+                    // Pass items are only changed through update_pass_quantity
                     match_data.index.is_some()
+                        && current_line.pass_id.is_none()
                         && Some(match_data.update_order_item.ticket_type_id) == current_line.ticket_type_id
                         && match_data.hold_id == current_line.hold_id
                         && match_data.code_id == current_line.code_id
@@ -1458,6 +1776,7 @@ impl Order {
                                 unit_price_in_cents: price_in_cents,
                                 hold_id: match_data.hold_id,
                                 code_id: match_data.code_id,
+                                pass_id: None,
                             }
                             .commit(conn)?;
                             TicketInstance::reserve_tickets(
@@ -1532,6 +1851,7 @@ impl Order {
                 unit_price_in_cents: price_in_cents,
                 hold_id: match_data.hold_id,
                 code_id: match_data.code_id,
+                pass_id: None,
             }
             .commit(conn);
 
@@ -1549,6 +1869,21 @@ impl Order {
         if self.items(conn)?.len() == 0 {
             self.remove_expiry(current_user_id, conn)?;
         }
+        self.validate_ticket_limits(check_ticket_limits, conn)?;
+        self.limit_products_to_ticket_quantities(conn)?;
+        self.update_currency(conn)?;
+        self.update_fees_and_discounts(conn)?;
+        self.validate_record(conn)?;
+        self.check_for_sold_out_triggers(current_user_id, conn)?;
+
+        Ok(())
+    }
+
+    fn validate_ticket_limits(
+        &self,
+        check_ticket_limits: Vec<LimitCheck>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
         for limit_check in check_ticket_limits {
             let ordered_quantity = Order::quantity_for_user_for_ticket_type(
                 self.user_id,
@@ -1585,18 +1920,271 @@ impl Order {
                 return Err(errors.into());
             }
         }
-        self.update_fees_and_discounts(conn)?;
-        self.validate_record(conn)?;
+        Ok(())
+    }
+
+    fn check_for_sold_out_triggers(&self, current_user_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
         // Beware there could be multiple orders that meet this condition
         for (ticket_type_id, remaining) in self.ticket_types(conn)? {
             if remaining == 0 {
                 TicketType::find(ticket_type_id, conn)?.check_for_sold_out_triggers(Some(current_user_id), conn)?;
             }
         }
+        Ok(())
+    }
+
+    /// Sets the quantity of a pass in the cart. Each pass adds a ticket order item for every
+    /// included event, priced at the event's allocated share of the pass price.
+    pub fn update_pass_quantity(
+        &mut self,
+        current_user_id: Uuid,
+        pass_id: Uuid,
+        quantity: u32,
+        event_time_slot_ids: &[Uuid],
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        self.lock_version(conn)?;
+        self.reverse_stored_value_payments(Some(current_user_id), conn)?;
+
+        jlog!(Debug, "Update pass quantity", {"pass_id": pass_id, "quantity": quantity, "user_id": current_user_id});
+
+        if self.box_office_pricing {
+            return DatabaseError::business_process_error("Passes cannot be purchased with box office pricing");
+        }
+
+        let pass = Pass::find(pass_id, conn)?;
+        if quantity > 0 && pass.status != PassStatus::Published {
+            return DatabaseError::validation_error("pass_id", "Pass is not available for purchase");
+        }
+
+        for current_line in self.items(conn)? {
+            if current_line.item_type != OrderItemTypes::Tickets || current_line.pass_id != Some(pass.id) {
+                continue;
+            }
+            let current_quantity = current_line.calculate_quantity(conn)?;
+            TicketInstance::release_tickets(&current_line, current_quantity as u32, Some(current_user_id), conn)?;
+            self.destroy_item(current_line.id, conn)?;
+        }
+
+        let mut check_ticket_limits: Vec<LimitCheck> = vec![];
+        if quantity > 0 {
+            if self.expires_at.is_none() {
+                self.set_expiry(Some(current_user_id), None, false, conn)?;
+            }
+
+            for pass_event in pass.events(conn)? {
+                let ticket_type = TicketType::find(pass_event.ticket_type_id, conn)?;
+                check_ticket_limits.push(LimitCheck {
+                    ticket_type_id: ticket_type.id,
+                    hold_id: None,
+                    code_id: None,
+                    limit_per_person: ticket_type.limit_per_person as u32,
+                    redemption_code: None,
+                });
+
+                let ticket_pricing =
+                    TicketPricing::get_current_ticket_pricing(pass_event.ticket_type_id, false, false, conn)?;
+                let order_item = NewTicketsOrderItem {
+                    order_id: self.id,
+                    item_type: OrderItemTypes::Tickets,
+                    quantity: quantity as i64,
+                    ticket_type_id: pass_event.ticket_type_id,
+                    ticket_pricing_id: ticket_pricing.id,
+                    event_id: Some(pass_event.event_id),
+                    unit_price_in_cents: pass_event.allocated_price_in_cents,
+                    hold_id: None,
+                    code_id: None,
+                    pass_id: Some(pass.id),
+                }
+                .commit(conn)?;
+
+                TicketInstance::reserve_tickets(
+                    &order_item,
+                    self.expires_at,
+                    pass_event.ticket_type_id,
+                    None,
+                    quantity,
+                    conn,
+                )?;
+
+                // Pass tickets get the best available seats and must be placed in a time slot like
+                // tickets bought on their own
+                self.assign_seats(pass_event.ticket_type_id, &[], conn)?;
+                let mut event_time_slot_id = None;
+                for id in event_time_slot_ids {
+                    let event_time_slot = EventTimeSlot::find(*id, conn)?;
+                    if event_time_slot.event_id == pass_event.event_id {
+                        event_time_slot_id = Some(event_time_slot.id);
+                    }
+                }
+                self.assign_time_slot(pass_event.ticket_type_id, event_time_slot_id, conn)?;
+            }
+        }
+
+        if self.items(conn)?.len() == 0 {
+            self.remove_expiry(current_user_id, conn)?;
+        }
+        self.validate_ticket_limits(check_ticket_limits, conn)?;
+        self.update_currency(conn)?;
+        self.update_fees_and_discounts(conn)?;
+        self.validate_record(conn)?;
+        self.check_for_sold_out_triggers(current_user_id, conn)?;
 
         Ok(())
     }
 
+    /// Sets the quantity of a product variant in the cart. Products are sold as add-ons to an
+    /// event, so organization wide products are attached to the event of the tickets in the cart.
+    pub fn update_product_quantity(
+        &mut self,
+        current_user_id: Uuid,
+        product_variant_id: Uuid,
+        quantity: u32,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        self.lock_version(conn)?;
+
+        jlog!(Debug, "Update product quantity", {"product_variant_id": product_variant_id, "quantity": quantity, "user_id": current_user_id});
+
+        let product_variant = ProductVariant::find_for_update(product_variant_id, conn)?;
+        let product = product_variant.product(conn)?;
+        let current_line = self
+            .items(conn)?
+            .into_iter()
+            .find(|i| i.product_variant_id == Some(product_variant.id));
+
+        if quantity > 0 {
+            if product.status != ProductStatus::Published {
+                return DatabaseError::validation_error("product_variant_id", "Product is not available for purchase");
+            }
+
+            let ticket_event_id = self
+                .items(conn)?
+                .iter()
+                .find(|i| i.item_type == OrderItemTypes::Tickets)
+                .and_then(|i| i.event_id);
+            let event = match product.event_id.or(ticket_event_id) {
+                Some(event_id) => Event::find(event_id, conn)?,
+                None => {
+                    return DatabaseError::validation_error(
+                        "product_variant_id",
+                        "Tickets for an event must be in the cart before adding this product",
+                    );
+                }
+            };
+            if event.organization_id != product.organization_id {
+                return DatabaseError::validation_error(
+                    "product_variant_id",
+                    "Product is not available for the event in the cart",
+                );
+            }
+
+            let available = product_variant.available_count(Some(self.id), conn)?;
+            if (quantity as i64) > available {
+                let mut validation_error = create_validation_error("product_stock_unavailable", "Not enough stock");
+                validation_error.add_param(Cow::from("product_variant_id"), &product_variant.id);
+                validation_error.add_param(Cow::from("available"), &available);
+                let mut errors = ValidationErrors::new();
+                errors.add("quantity", validation_error);
+                return Err(errors.into());
+            }
+
+            if let Some(per_ticket_limit) = product.per_ticket_limit {
+                let limit = per_ticket_limit as i64 * self.ticket_quantity_for_event(event.id, conn)?;
+                let current_quantity = current_line.as_ref().map(|i| i.quantity).unwrap_or(0);
+                if self.product_quantity(product.id, conn)? - current_quantity + quantity as i64 > limit {
+                    let mut validation_error = create_validation_error(
+                        "product_per_ticket_limit_exceeded",
+                        "You have exceeded the limit for this product for the tickets in your cart.",
+                    );
+                    validation_error.add_param(Cow::from("product_id"), &product.id);
+                    validation_error.add_param(Cow::from("per_ticket_limit"), &per_ticket_limit);
+                    let mut errors = ValidationErrors::new();
+                    errors.add("quantity", validation_error);
+                    return Err(errors.into());
+                }
+            }
+
+            if let Some(current_line) = current_line {
+                self.destroy_item(current_line.id, conn)?;
+            }
+            if self.expires_at.is_none() {
+                self.set_expiry(Some(current_user_id), None, false, conn)?;
+            }
+
+            NewProductsOrderItem {
+                order_id: self.id,
+                item_type: OrderItemTypes::Products,
+                event_id: Some(event.id),
+                quantity: quantity as i64,
+                unit_price_in_cents: product_variant.unit_price_in_cents(&product),
+                product_variant_id: product_variant.id,
+            }
+            .commit(conn)?;
+        } else if let Some(current_line) = current_line {
+            self.destroy_item(current_line.id, conn)?;
+        }
+
+        if self.items(conn)?.len() == 0 {
+            self.remove_expiry(current_user_id, conn)?;
+        }
+        self.update_currency(conn)?;
+        self.update_fees_and_discounts(conn)?;
+        self.validate_record(conn)?;
+
+        Ok(())
+    }
+
+    /// Reduces product quantities that are limited per ticket after tickets are removed from the cart
+    fn limit_products_to_ticket_quantities(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        for mut item in self.items(conn)? {
+            let product_variant_id = match item.product_variant_id {
+                Some(product_variant_id) => product_variant_id,
+                None => continue,
+            };
+            let product = ProductVariant::find(product_variant_id, conn)?.product(conn)?;
+            let per_ticket_limit = match product.per_ticket_limit {
+                Some(per_ticket_limit) => per_ticket_limit as i64,
+                None => continue,
+            };
+            let ticket_quantity = match item.event_id {
+                Some(event_id) => self.ticket_quantity_for_event(event_id, conn)?,
+                None => 0,
+            };
+
+            let excess = self.product_quantity(product.id, conn)? - per_ticket_limit * ticket_quantity;
+            if excess <= 0 {
+                continue;
+            }
+            if excess >= item.quantity {
+                self.destroy_item(item.id, conn)?;
+            } else {
+                item.quantity -= excess;
+                item.update(conn)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn ticket_quantity_for_event(&self, event_id: Uuid, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        Ok(self
+            .items(conn)?
+            .iter()
+            .filter(|i| i.item_type == OrderItemTypes::Tickets && i.event_id == Some(event_id))
+            .map(|i| i.quantity)
+            .sum())
+    }
+
+    fn product_quantity(&self, product_id: Uuid, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        order_items::table
+            .inner_join(product_variants::table)
+            .filter(order_items::order_id.eq(self.id))
+            .filter(product_variants::product_id.eq(product_id))
+            .select(sql::<BigInt>("CAST(COALESCE(SUM(order_items.quantity), 0) AS BIGINT)"))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not count products in cart")
+    }
+
     fn check_ticket_limits(ticket_type: &TicketType, match_data: &MatchData) -> Vec<LimitCheck> {
         let mut check_ticket_limits: Vec<LimitCheck> = vec![];
         check_ticket_limits.push(LimitCheck {
@@ -1632,6 +2220,32 @@ impl Order {
             .to_db_error(ErrorCode::QueryError, "Could not check if order items exist")
     }
 
+    /// Orders are charged in the currency of the events they contain, so items priced in
+    /// different currencies cannot be combined in one order
+    fn update_currency(&mut self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let mut currencies: Vec<String> = self.events(conn)?.into_iter().map(|e| e.currency).collect();
+        currencies.sort();
+        currencies.dedup();
+        if currencies.len() > 1 {
+            return DatabaseError::validation_error(
+                "currency",
+                "All items in an order must be priced in the same currency",
+            );
+        }
+
+        if let Some(currency) = currencies.pop() {
+            if currency != self.currency {
+                diesel::update(&*self)
+                    .set((orders::currency.eq(&currency), orders::updated_at.eq(dsl::now)))
+                    .execute(conn)
+                    .to_db_error(ErrorCode::UpdateError, "Could not update order currency")?;
+                self.currency = currency;
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn update_fees_and_discounts(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
         let items = self.items(conn)?;
 
@@ -1640,13 +2254,14 @@ impl Order {
             match o.item_type {
                 OrderItemTypes::EventFees => self.destroy_item(o.id, conn)?,
                 OrderItemTypes::CreditCardFees => self.destroy_item(o.id, conn)?,
+                OrderItemTypes::Tax => self.destroy_item(o.id, conn)?,
                 _ => {}
             }
         }
 
         // Box office purchased tickets do not have fees at this time
         if self.box_office_pricing {
-            return Ok(());
+            return self.update_taxes(conn);
         }
 
         let mut per_event_fees_included: HashMap<Uuid, bool> = HashMap::new();
@@ -1690,6 +2305,12 @@ impl Order {
                             all_zero_price = false;
                         }
                     }
+                    // Products do not carry per unit fees but still incur the event and credit card fees
+                    OrderItemTypes::Products => {
+                        if o.unit_price_in_cents > 0 {
+                            all_zero_price = false;
+                        }
+                    }
                     _ => {}
                 }
             }
@@ -1742,6 +2363,73 @@ impl Order {
             }
         }
 
+        self.update_taxes(conn)
+    }
+
+    /// Creates tax lines using the tax rules for each event's venue. Each tax line is a child of the
+    /// item it is charged on so that partial refunds also refund the tax on the refunded units.
+    fn update_taxes(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let mut tax_rules_for_event: HashMap<Uuid, Vec<TaxRule>> = HashMap::new();
+
+        for item in self.items(conn)? {
+            let event_id = match item.event_id {
+                Some(event_id) => event_id,
+                None => continue,
+            };
+            let taxable_amount_in_cents = match item.item_type {
+                OrderItemTypes::Tickets => match item.find_discount_item(conn)? {
+                    Some(discount_item) => item.unit_price_in_cents + discount_item.unit_price_in_cents,
+                    None => item.unit_price_in_cents,
+                },
+                OrderItemTypes::Products
+                | OrderItemTypes::PerUnitFees
+                | OrderItemTypes::EventFees
+                | OrderItemTypes::CreditCardFees => item.unit_price_in_cents,
+                _ => continue,
+            };
+            if taxable_amount_in_cents <= 0 {
+                continue;
+            }
+
+            if !tax_rules_for_event.contains_key(&event_id) {
+                let tax_rules = match Event::find(event_id, conn)?.venue(conn)? {
+                    Some(venue) => TaxRule::find_for_venue(&venue, conn)?,
+                    None => Vec::new(),
+                };
+                tax_rules_for_event.insert(event_id, tax_rules);
+            }
+
+            for tax_rule in &tax_rules_for_event[&event_id] {
+                let applies = if item.item_type.is_fee() {
+                    tax_rule.applies_to_fees
+                } else {
+                    tax_rule.applies_to_face_value
+                };
+                let tax_in_cents = tax_rule.tax_for_amount(taxable_amount_in_cents);
+                if !applies || tax_in_cents == 0 {
+                    continue;
+                }
+
+                // Inclusive taxes are already part of the price so they do not add to the order total
+                let (unit_price_in_cents, included_tax_in_cents) = if tax_rule.inclusive {
+                    (0, tax_in_cents)
+                } else {
+                    (tax_in_cents, 0)
+                };
+                NewTaxOrderItem {
+                    order_id: self.id,
+                    item_type: OrderItemTypes::Tax,
+                    event_id: item.event_id,
+                    quantity: item.quantity,
+                    unit_price_in_cents,
+                    included_tax_in_cents,
+                    tax_rule_id: tax_rule.id,
+                    parent_id: item.id,
+                }
+                .commit(conn)?;
+            }
+        }
+
         Ok(())
     }
 
@@ -1853,6 +2541,195 @@ impl Order {
         Ok(result)
     }
 
+    /// Assigns seats to the reserved tickets of a reserved seating ticket type in this cart.
+    /// If no seats are requested, tickets without a seat are given the best available seats instead.
+    pub fn assign_seats(
+        &mut self,
+        ticket_type_id: Uuid,
+        seat_ids: &[Uuid],
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        if self.status != OrderStatus::Draft {
+            return DatabaseError::business_process_error("Cannot assign seats to an order that is not a cart");
+        }
+
+        let ticket_type = TicketType::find(ticket_type_id, conn)?;
+        let venue_section_id = match ticket_type.venue_section_id {
+            Some(venue_section_id) => venue_section_id,
+            None => {
+                if seat_ids.is_empty() {
+                    return Ok(());
+                }
+                return DatabaseError::validation_error("seat_ids", "Ticket type does not have reserved seating");
+            }
+        };
+
+        self.lock_version(conn)?;
+
+        let tickets: Vec<TicketInstance> = self
+            .tickets(Some(ticket_type_id), conn)?
+            .into_iter()
+            .filter(|t| t.status == TicketInstanceStatus::Reserved)
+            .collect();
+        let ticket_ids: Vec<Uuid> = tickets.iter().map(|t| t.id).collect();
+
+        let (tickets_to_seat, seats) = if seat_ids.is_empty() {
+            let unseated_tickets: Vec<TicketInstance> =
+                tickets.into_iter().filter(|t| t.venue_seat_id.is_none()).collect();
+            if unseated_tickets.is_empty() {
+                return Ok(());
+            }
+            let seats = VenueSeat::find_available_for_event(
+                venue_section_id,
+                ticket_type.event_id,
+                None,
+                &ticket_ids,
+                unseated_tickets.len() as i64,
+                conn,
+            )?;
+            (unseated_tickets, seats)
+        } else {
+            let mut requested_seat_ids = seat_ids.to_vec();
+            requested_seat_ids.sort();
+            requested_seat_ids.dedup();
+            if requested_seat_ids.len() != tickets.len() {
+                return DatabaseError::validation_error(
+                    "seat_ids",
+                    "Number of seats selected must match the number of tickets",
+                );
+            }
+            let seats = VenueSeat::find_available_for_event(
+                venue_section_id,
+                ticket_type.event_id,
+                Some(requested_seat_ids.clone()),
+                &ticket_ids,
+                requested_seat_ids.len() as i64,
+                conn,
+            )?;
+            (tickets, seats)
+        };
+
+        if seats.len() != tickets_to_seat.len() {
+            return DatabaseError::validation_error("seat_ids", "Selected seats are no longer available");
+        }
+
+        // Seats are unique per event so clear the seats being swapped between this cart's tickets and
+        // any left on expired reservations before assigning them
+        for ticket in tickets_to_seat.iter().filter(|t| t.venue_seat_id.is_some()) {
+            ticket.assign_seat(None, conn)?;
+        }
+        let seat_ids: Vec<Uuid> = seats.iter().map(|s| s.id).collect();
+        TicketInstance::release_expired_seats(ticket_type.event_id, &seat_ids, conn)?;
+        for (ticket, seat) in tickets_to_seat.iter().zip(seats.iter()) {
+            ticket.assign_seat(Some(seat.id), conn)?;
+        }
+
+        Ok(())
+    }
+
+    /// Places the reserved tickets for the ticket type in the chosen time slot. Events with timed
+    /// entry require a slot to be chosen and the slot capacity is checked against sold and
+    /// reserved tickets while holding a lock on the slot.
+    pub fn assign_time_slot(
+        &mut self,
+        ticket_type_id: Uuid,
+        event_time_slot_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        if self.status != OrderStatus::Draft {
+            return DatabaseError::business_process_error("Cannot assign a time slot to an order that is not a cart");
+        }
+
+        let ticket_type = TicketType::find(ticket_type_id, conn)?;
+        let event_time_slot_id = match event_time_slot_id {
+            Some(event_time_slot_id) => event_time_slot_id,
+            None => {
+                if EventTimeSlot::find_for_event(ticket_type.event_id, conn)?.is_empty() {
+                    return Ok(());
+                }
+                return DatabaseError::validation_error(
+                    "event_time_slot_id",
+                    "A time slot must be selected for this event",
+                );
+            }
+        };
+
+        self.lock_version(conn)?;
+
+        let slot = EventTimeSlot::find_for_update(event_time_slot_id, conn)?;
+        if slot.event_id != ticket_type.event_id {
+            return DatabaseError::validation_error(
+                "event_time_slot_id",
+                "Time slot does not belong to the event for this ticket type",
+            );
+        }
+
+        let tickets: Vec<TicketInstance> = self
+            .tickets(Some(ticket_type_id), conn)?
+            .into_iter()
+            .filter(|t| t.status == TicketInstanceStatus::Reserved)
+            .collect();
+        let ticket_ids: Vec<Uuid> = tickets.iter().map(|t| t.id).collect();
+
+        if slot.allocated_count(&ticket_ids, conn)? + tickets.len() as i64 > slot.capacity as i64 {
+            return DatabaseError::validation_error(
+                "event_time_slot_id",
+                "Selected time slot does not have enough capacity",
+            );
+        }
+
+        for ticket in tickets.iter().filter(|t| t.event_time_slot_id != Some(slot.id)) {
+            ticket.assign_time_slot(Some(slot.id), conn)?;
+        }
+
+        Ok(())
+    }
+
+    /// Confirms every ticket for a timed entry event has a time slot and that no slot is oversold,
+    /// whichever way the tickets were added to the order
+    fn validate_time_slots(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let ticket_type_ids: Vec<Uuid> = self
+            .items(conn)?
+            .iter()
+            .filter(|i| i.item_type == OrderItemTypes::Tickets)
+            .filter_map(|i| i.ticket_type_id)
+            .unique()
+            .collect();
+
+        for ticket_type_id in ticket_type_ids {
+            let ticket_type = TicketType::find(ticket_type_id, conn)?;
+            if EventTimeSlot::find_for_event(ticket_type.event_id, conn)?.is_empty() {
+                continue;
+            }
+
+            let mut tickets: Vec<TicketInstance> = self
+                .tickets(Some(ticket_type_id), conn)?
+                .into_iter()
+                .filter(|t| t.status == TicketInstanceStatus::Reserved)
+                .collect();
+            tickets.sort_by_key(|t| t.event_time_slot_id);
+            if tickets.iter().any(|t| t.event_time_slot_id.is_none()) {
+                return DatabaseError::validation_error(
+                    "event_time_slot_id",
+                    "A time slot must be selected for this event",
+                );
+            }
+
+            for (event_time_slot_id, slot_tickets) in &tickets.iter().group_by(|t| t.event_time_slot_id) {
+                let slot_ticket_ids: Vec<Uuid> = slot_tickets.map(|t| t.id).collect();
+                let slot = EventTimeSlot::find_for_update(event_time_slot_id.unwrap(), conn)?;
+                if slot.allocated_count(&slot_ticket_ids, conn)? + slot_ticket_ids.len() as i64 > slot.capacity as i64 {
+                    return DatabaseError::validation_error(
+                        "event_time_slot_id",
+                        "Selected time slot does not have enough capacity",
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn events(&self, conn: &PgConnection) -> Result<Vec<Event>, DatabaseError> {
         let mut unique_events: Vec<Uuid> = self.items(conn)?.iter().filter_map(|i| i.event_id).collect();
         unique_events.sort();
@@ -2035,6 +2912,8 @@ impl Order {
             checkout_url: Option<String>,
             #[sql_type = "Nullable<Timestamp>"]
             checkout_url_expires: Option<NaiveDateTime>,
+            #[sql_type = "Text"]
+            currency: String,
             #[sql_type = "Nullable<Array<Text>>"]
             payment_methods: Option<Vec<PaymentMethods>>,
             #[sql_type = "Nullable<Array<Text>>"]
@@ -2065,6 +2944,7 @@ impl Order {
                 o.platform,
                 o.checkout_url,
                 o.checkout_url_expires,
+                o.currency,
                 p.payment_methods,
                 p.providers,
                 CAST(COALESCE(SUM(oi.unit_price_in_cents * oi.quantity), 0) as BigInt) as total_in_cents,
@@ -2105,6 +2985,7 @@ impl Order {
                 o.expires_at,
                 o.checkout_url_expires,
                 o.checkout_url,
+                o.currency,
                 p.payment_methods,
                 p.providers
             ORDER BY o.order_date desc
@@ -2274,6 +3155,7 @@ impl Order {
                 limited_tickets_remaining,
                 total_in_cents: result.total_in_cents,
                 total_refunded_in_cents: result.total_refunded_in_cents,
+                currency: result.currency.clone(),
                 seconds_until_expiry,
                 user_id: result.user_id,
                 user,
@@ -2403,6 +3285,7 @@ impl Order {
             },
             Some("Free Checkout".to_string()),
             0,
+            self.currency.clone(),
             None,
             None,
             None,
@@ -2428,6 +3311,7 @@ impl Order {
             PaymentProviders::External,
             external_reference,
             amount,
+            self.currency.clone(),
             None,
             None,
             None,
@@ -2454,6 +3338,7 @@ impl Order {
             provider,
             external_reference,
             amount,
+            self.currency.clone(),
             Some(data),
             url_nonce,
             None,
@@ -2480,6 +3365,7 @@ impl Order {
             provider,
             Some(external_reference),
             amount,
+            self.currency.clone(),
             Some(provider_data),
             None,
             None,
@@ -2488,6 +3374,89 @@ impl Order {
         self.add_payment(payment, Some(current_user_id), conn)
     }
 
+    /// Pays as much of the remaining balance as the gift card covers
+    pub fn add_gift_card_payment(
+        &mut self,
+        gift_card: &GiftCard,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Payment, DatabaseError> {
+        if self.status != OrderStatus::Draft {
+            return DatabaseError::business_process_error("Gift cards can only be applied to draft orders");
+        }
+        gift_card.validate_for_order(self, conn)?;
+        let amount = cmp::min(gift_card.balance_in_cents, self.amount_due(conn)?);
+        if amount == 0 {
+            return DatabaseError::business_process_error("Order has no remaining balance to pay");
+        }
+        gift_card.debit(amount, Some(current_user_id), conn)?;
+
+        let payment = Payment::create(
+            self.id,
+            Some(current_user_id),
+            PaymentStatus::Completed,
+            PaymentMethods::GiftCard,
+            PaymentProviders::GiftCard,
+            Some(gift_card.id.to_string()),
+            amount,
+            self.currency.clone(),
+            Some(json!({ "gift_card_id": gift_card.id })),
+            None,
+            None,
+        );
+        self.add_payment(payment, Some(current_user_id), conn)
+    }
+
+    /// Pays as much of the remaining balance as the purchaser's store credit with the organization covers
+    pub fn add_store_credit_payment(
+        &mut self,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Payment, DatabaseError> {
+        if self.status != OrderStatus::Draft {
+            return DatabaseError::business_process_error("Store credit can only be applied to draft orders");
+        }
+        let mut organizations = self.organizations(conn)?;
+        if organizations.len() != 1 {
+            return DatabaseError::business_process_error(
+                "Store credit can only be used for orders from a single organization",
+            );
+        }
+        let organization = organizations.remove(0);
+        let user_id = self.on_behalf_of_user_id.unwrap_or(self.user_id);
+        let balance = StoreCreditTransaction::balance(user_id, organization.id, &self.currency, conn)?;
+        let amount = cmp::min(balance, self.amount_due(conn)?);
+        if amount <= 0 {
+            return DatabaseError::business_process_error("No store credit is available for this order");
+        }
+        StoreCreditTransaction::create(
+            user_id,
+            organization.id,
+            -amount,
+            self.currency.clone(),
+            Some(self.id),
+            None,
+            None,
+            Some(current_user_id),
+        )
+        .commit(conn)?;
+
+        let payment = Payment::create(
+            self.id,
+            Some(current_user_id),
+            PaymentStatus::Completed,
+            PaymentMethods::StoreCredit,
+            PaymentProviders::StoreCredit,
+            Some("Store Credit".to_string()),
+            amount,
+            self.currency.clone(),
+            Some(json!({ "organization_id": organization.id, "user_id": user_id })),
+            None,
+            None,
+        );
+        self.add_payment(payment, Some(current_user_id), conn)
+    }
+
     pub fn user(&self, conn: &PgConnection) -> Result<User, DatabaseError> {
         users::table
             .filter(users::id.eq(self.on_behalf_of_user_id.unwrap_or(self.user_id)))
@@ -2564,9 +3533,15 @@ impl Order {
         for item in self.items(conn)? {
             item.confirm_code_valid(conn)?;
         }
+        if self.status == OrderStatus::Draft {
+            self.validate_time_slots(conn)?;
+        }
 
         let p = payment.commit(current_user_id, conn)?;
-        if p.status != PaymentStatus::Requested {
+        // Split payments, gift cards and store credit can cover part of an order, the cart stays
+        // available until the remainder is paid
+        let partial_payment = p.status == PaymentStatus::Completed && self.amount_due(conn)? > 0;
+        if p.status != PaymentStatus::Requested && !partial_payment {
             self.clear_user_cart(conn)?;
         }
 
@@ -2584,7 +3559,7 @@ impl Order {
         }
 
         let total_paid = self.total_paid(conn)?;
-        let total_required = self.calculate_total(conn)?;
+        let total_required = self.calculate_total(conn)? - InstallmentPlan::scheduled_amount_for_order(self.id, conn)?;
         if total_paid >= total_required {
             self.update_status(current_user_id, OrderStatus::Paid, conn)?;
             //Mark tickets as Purchased
@@ -2596,6 +3571,12 @@ impl Order {
             {
                 TicketInstance::mark_as_purchased(item, self.on_behalf_of_user_id.unwrap_or(self.user_id), conn)?;
             }
+            GiftCard::issue_for_order(self, current_user_id, conn)?;
+            if let Some(installment_plan) = InstallmentPlan::find_for_order(self.id, conn)? {
+                if installment_plan.status == InstallmentPlanStatus::Pending {
+                    installment_plan.activate(current_user_id, conn)?;
+                }
+            }
 
             let ticket_ids = TicketInstance::find_ids_for_order(self.id, conn)?;
             let domain_event = DomainEvent::create(
@@ -2639,6 +3620,17 @@ impl Order {
         Ok(())
     }
 
+    /// Order total less completed payments and any installments scheduled for later, what
+    /// remains to be charged at checkout
+    pub fn amount_due(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        Ok(cmp::max(
+            self.calculate_total(conn)?
+                - self.total_paid(conn)?
+                - InstallmentPlan::scheduled_amount_for_order(self.id, conn)?,
+            0,
+        ))
+    }
+
     pub fn total_paid(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
         #[derive(QueryableByName)]
         struct ResultForSum {
@@ -2865,6 +3857,7 @@ pub struct DisplayOrder {
     pub limited_tickets_remaining: Vec<TicketsRemaining>,
     pub total_in_cents: i64,
     pub total_refunded_in_cents: i64,
+    pub currency: String,
     pub user_id: Uuid,
     pub user: DisplayUser,
     pub order_number: String,
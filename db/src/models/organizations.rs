@@ -54,6 +54,7 @@ pub struct Organization {
     pub slug_id: Option<Uuid>,
     pub google_ads_conversion_id: Option<String>,
     pub google_ads_conversion_labels: Vec<String>,
+    pub refund_fees_on_event_cancellation: bool,
 }
 
 #[derive(Serialize)]
@@ -214,6 +215,7 @@ pub struct OrganizationEditableAttributes {
     pub google_ads_conversion_id: Option<Option<String>>,
     #[serde(default)]
     pub google_ads_conversion_labels: Option<Vec<String>>,
+    pub refund_fees_on_event_cancellation: Option<bool>,
 }
 
 impl Organization {
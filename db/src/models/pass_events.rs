@@ -0,0 +1,59 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use models::*;
+use schema::pass_events;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// An event included in a pass. Buying the pass issues one ticket from `ticket_type_id` for this
+/// event and the event is settled at `allocated_price_in_cents` for each pass sold.
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Pass)]
+#[table_name = "pass_events"]
+pub struct PassEvent {
+    pub id: Uuid,
+    pub pass_id: Uuid,
+    pub event_id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub allocated_price_in_cents: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "pass_events"]
+pub struct NewPassEvent {
+    pub pass_id: Uuid,
+    pub event_id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub allocated_price_in_cents: i64,
+}
+
+impl NewPassEvent {
+    pub(crate) fn commit(&self, conn: &PgConnection) -> Result<PassEvent, DatabaseError> {
+        diesel::insert_into(pass_events::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not add event to pass")
+    }
+}
+
+impl PassEvent {
+    pub fn find_by_pass_id(pass_id: Uuid, conn: &PgConnection) -> Result<Vec<PassEvent>, DatabaseError> {
+        pass_events::table
+            .filter(pass_events::pass_id.eq(pass_id))
+            .order_by(pass_events::created_at)
+            .then_order_by(pass_events::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load pass events")
+    }
+
+    pub fn event(&self, conn: &PgConnection) -> Result<Event, DatabaseError> {
+        Event::find(self.event_id, conn)
+    }
+
+    pub fn ticket_type(&self, conn: &PgConnection) -> Result<TicketType, DatabaseError> {
+        TicketType::find(self.ticket_type_id, conn)
+    }
+}
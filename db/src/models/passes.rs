@@ -0,0 +1,300 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{pass_events, passes};
+use utils::errors::*;
+use uuid::Uuid;
+use validators::{self, *};
+
+/// An organization level product that grants entry to a set or series of events
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Organization)]
+#[table_name = "passes"]
+pub struct Pass {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub price_in_cents: i64,
+    pub status: PassStatus,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(AsChangeset, Clone, Default, Deserialize, Serialize)]
+#[table_name = "passes"]
+pub struct PassEditableAttributes {
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub description: Option<Option<String>>,
+    pub price_in_cents: Option<i64>,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "passes"]
+pub struct NewPass {
+    pub organization_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub price_in_cents: i64,
+}
+
+impl NewPass {
+    pub fn commit(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<Pass, DatabaseError> {
+        Pass::validate_price(self.price_in_cents)?;
+
+        let result: Pass = diesel::insert_into(passes::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create pass")?;
+
+        DomainEvent::create(
+            DomainEventTypes::PassCreated,
+            "Pass created".to_string(),
+            Tables::Passes,
+            Some(result.id),
+            current_user_id,
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+}
+
+impl Pass {
+    pub fn create(organization_id: Uuid, name: String, description: Option<String>, price_in_cents: i64) -> NewPass {
+        NewPass {
+            organization_id,
+            name,
+            description,
+            price_in_cents,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<Pass, DatabaseError> {
+        passes::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading pass")
+    }
+
+    pub fn find_for_organization(organization_id: Uuid, conn: &PgConnection) -> Result<Vec<Pass>, DatabaseError> {
+        passes::table
+            .filter(passes::organization_id.eq(organization_id))
+            .order_by(passes::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load passes for organization")
+    }
+
+    pub fn organization(&self, conn: &PgConnection) -> Result<Organization, DatabaseError> {
+        Organization::find(self.organization_id, conn)
+    }
+
+    pub fn events(&self, conn: &PgConnection) -> Result<Vec<PassEvent>, DatabaseError> {
+        PassEvent::find_by_pass_id(self.id, conn)
+    }
+
+    pub fn update(
+        &self,
+        attributes: PassEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Pass, DatabaseError> {
+        if let Some(price_in_cents) = attributes.price_in_cents {
+            Pass::validate_price(price_in_cents)?;
+            if price_in_cents != self.price_in_cents && self.status != PassStatus::Draft {
+                return DatabaseError::validation_error(
+                    "price_in_cents",
+                    "Price cannot be changed once the pass has been published",
+                );
+            }
+        }
+
+        let result = diesel::update(self)
+            .set((&attributes, passes::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update pass")?;
+
+        DomainEvent::create(
+            DomainEventTypes::PassUpdated,
+            "Pass updated".to_string(),
+            Tables::Passes,
+            Some(self.id),
+            current_user_id,
+            Some(json!(&attributes)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    /// Includes an event in the pass, issuing tickets for it from the given ticket type
+    pub fn add_event(
+        &self,
+        ticket_type_id: Uuid,
+        allocated_price_in_cents: i64,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<PassEvent, DatabaseError> {
+        self.confirm_draft()?;
+        let ticket_type = TicketType::find(ticket_type_id, conn)?;
+        if ticket_type.event(conn)?.organization_id != self.organization_id {
+            return DatabaseError::validation_error(
+                "ticket_type_id",
+                "Ticket type does not belong to an event for this organization",
+            );
+        }
+        validators::append_validation_error(
+            Ok(()),
+            "allocated_price_in_cents",
+            validators::validate_greater_than_or_equal(
+                allocated_price_in_cents,
+                0,
+                "number_must_be_positive",
+                "Allocated price must be positive",
+            ),
+        )?;
+
+        let pass_event = NewPassEvent {
+            pass_id: self.id,
+            event_id: ticket_type.event_id,
+            ticket_type_id: ticket_type.id,
+            allocated_price_in_cents,
+        }
+        .commit(conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::PassEventAdded,
+            "Event added to pass".to_string(),
+            Tables::Passes,
+            Some(self.id),
+            current_user_id,
+            Some(json!({
+                "event_id": pass_event.event_id,
+                "ticket_type_id": pass_event.ticket_type_id,
+                "allocated_price_in_cents": pass_event.allocated_price_in_cents
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(pass_event)
+    }
+
+    pub fn remove_event(
+        &self,
+        event_id: Uuid,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        self.confirm_draft()?;
+        let removed = diesel::delete(
+            pass_events::table
+                .filter(pass_events::pass_id.eq(self.id))
+                .filter(pass_events::event_id.eq(event_id)),
+        )
+        .execute(conn)
+        .to_db_error(ErrorCode::DeleteError, "Could not remove event from pass")?;
+        if removed == 0 {
+            return DatabaseError::no_results("Event is not included in this pass");
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::PassEventRemoved,
+            "Event removed from pass".to_string(),
+            Tables::Passes,
+            Some(self.id),
+            current_user_id,
+            Some(json!({ "event_id": event_id })),
+        )
+        .commit(conn)?;
+
+        Ok(())
+    }
+
+    /// Opens the pass for sale. The prices allocated to the included events must add up to the
+    /// pass price so that each event settles its share of the revenue.
+    pub fn publish(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<Pass, DatabaseError> {
+        self.confirm_draft()?;
+        let pass_events = self.events(conn)?;
+        if pass_events.is_empty() {
+            return DatabaseError::validation_error("events", "Pass must include at least one event");
+        }
+        let allocated_price_in_cents: i64 = pass_events.iter().map(|pe| pe.allocated_price_in_cents).sum();
+        if allocated_price_in_cents != self.price_in_cents {
+            return DatabaseError::validation_error(
+                "price_in_cents",
+                "Allocated event prices must add up to the pass price",
+            );
+        }
+
+        self.set_status(
+            PassStatus::Published,
+            DomainEventTypes::PassPublished,
+            current_user_id,
+            conn,
+        )
+    }
+
+    /// Stops further sales of the pass. Tickets already issued for it remain valid.
+    pub fn cancel(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<Pass, DatabaseError> {
+        if self.status == PassStatus::Cancelled {
+            return DatabaseError::business_process_error("Pass has already been cancelled");
+        }
+
+        self.set_status(
+            PassStatus::Cancelled,
+            DomainEventTypes::PassCancelled,
+            current_user_id,
+            conn,
+        )
+    }
+
+    fn set_status(
+        &self,
+        status: PassStatus,
+        domain_event_type: DomainEventTypes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Pass, DatabaseError> {
+        let result = diesel::update(self)
+            .set((passes::status.eq(status), passes::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update pass status")?;
+
+        DomainEvent::create(
+            domain_event_type,
+            format!("Pass status changed to {}", status),
+            Tables::Passes,
+            Some(self.id),
+            current_user_id,
+            Some(json!({ "old_status": self.status, "new_status": status })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn confirm_draft(&self) -> Result<(), DatabaseError> {
+        if self.status != PassStatus::Draft {
+            return DatabaseError::business_process_error("Pass has already been published");
+        }
+        Ok(())
+    }
+
+    fn validate_price(price_in_cents: i64) -> Result<(), DatabaseError> {
+        Ok(validators::append_validation_error(
+            Ok(()),
+            "price_in_cents",
+            validators::validate_greater_than_or_equal(
+                price_in_cents,
+                0,
+                "number_must_be_positive",
+                "Price must be positive",
+            ),
+        )?)
+    }
+}
@@ -2,9 +2,11 @@ use chrono::Duration;
 use chrono::NaiveDateTime;
 use chrono::Utc;
 use diesel;
+use diesel::dsl::sql;
 use diesel::expression::dsl;
 use diesel::pg::PgConnection;
 use diesel::prelude::*;
+use diesel::sql_types::BigInt;
 use models::*;
 use schema::{orders, payments};
 use serde_json;
@@ -27,6 +29,7 @@ pub struct Payment {
     updated_at: NaiveDateTime,
     pub url_nonce: Option<String>,
     pub refund_id: Option<Uuid>,
+    pub currency: String,
 }
 
 impl Payment {
@@ -38,6 +41,7 @@ impl Payment {
         provider: PaymentProviders,
         external_reference: Option<String>,
         amount: i64,
+        currency: String,
         raw_data: Option<serde_json::Value>,
         url_nonce: Option<String>,
         refund_id: Option<Uuid>,
@@ -50,6 +54,7 @@ impl Payment {
             provider,
             external_reference,
             amount,
+            currency,
             raw_data,
             url_nonce,
             refund_id,
@@ -96,6 +101,27 @@ impl Payment {
             .expect_single()
     }
 
+    /// Finds the original (non-refund) payment recorded for a payment provider reference
+    pub fn find_by_external_reference(external_reference: &str, conn: &PgConnection) -> Result<Payment, DatabaseError> {
+        payments::table
+            .filter(payments::external_reference.eq(external_reference))
+            .filter(payments::refund_id.is_null())
+            .order_by(payments::created_at.asc())
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find payment for external reference")
+    }
+
+    /// Amount refunded so far against the payment's charge with the payment processor
+    pub fn refunded_amount(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        payments::table
+            .filter(payments::order_id.eq(self.order_id))
+            .filter(payments::external_reference.eq(&self.external_reference))
+            .filter(payments::refund_id.is_not_null())
+            .select(sql::<BigInt>("CAST(COALESCE(-SUM(payments.amount), 0) AS BIGINT)"))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load refunded amount for payment")
+    }
+
     pub fn log_refund(
         &self,
         current_user_id: Uuid,
@@ -112,12 +138,25 @@ impl Payment {
             self.provider.clone(),
             self.external_reference.clone(),
             -refund_amount,
+            self.currency.clone(),
             refund_data.clone(),
             None,
             Some(refund.id),
         )
         .commit(Some(current_user_id), conn)?;
 
+        // Stored value payments are refunded back to the gift card or store credit they were paid from
+        match self.payment_method {
+            PaymentMethods::GiftCard => {
+                self.gift_card(conn)?
+                    .credit(refund_amount, Some(current_user_id), conn)?;
+            }
+            PaymentMethods::StoreCredit => {
+                self.issue_store_credit(current_user_id, refund, refund_amount, conn)?;
+            }
+            _ => (),
+        }
+
         DomainEvent::create(
             DomainEventTypes::PaymentRefund,
             "Payment was refunded".to_string(),
@@ -130,6 +169,144 @@ impl Payment {
         Ok(refund_payment)
     }
 
+    /// Refunds the amount to the purchaser's store credit instead of the original payment method
+    pub fn log_store_credit_refund(
+        &self,
+        current_user_id: Uuid,
+        refund: &Refund,
+        refund_amount: i64,
+        conn: &PgConnection,
+    ) -> Result<Payment, DatabaseError> {
+        let transaction = self.issue_store_credit(current_user_id, refund, refund_amount, conn)?;
+        let refund_payment = Payment::create(
+            self.order_id,
+            self.created_by,
+            PaymentStatus::Refunded,
+            PaymentMethods::StoreCredit,
+            PaymentProviders::StoreCredit,
+            // Shares the original payment's reference so the refund is deducted from its balance
+            self.external_reference.clone(),
+            -refund_amount,
+            self.currency.clone(),
+            Some(json!({ "store_credit_transaction_id": transaction.id })),
+            None,
+            Some(refund.id),
+        )
+        .commit(Some(current_user_id), conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::PaymentRefund,
+            "Payment was refunded to store credit".to_string(),
+            Tables::Payments,
+            Some(self.id),
+            Some(current_user_id),
+            Some(json!({ "store_credit_transaction_id": transaction.id })),
+        )
+        .commit(conn)?;
+        Ok(refund_payment)
+    }
+
+    pub fn gift_card(&self, conn: &PgConnection) -> Result<GiftCard, DatabaseError> {
+        match self.external_reference.as_ref().and_then(|r| Uuid::parse_str(r).ok()) {
+            Some(gift_card_id) if self.payment_method == PaymentMethods::GiftCard => GiftCard::find(gift_card_id, conn),
+            _ => DatabaseError::business_process_error("Payment was not made with a gift card"),
+        }
+    }
+
+    /// Cancels a completed gift card or store credit payment on an unpaid order and returns the
+    /// amount to the gift card or store credit it was taken from
+    pub(crate) fn reverse_stored_value(
+        &self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        if self.payment_method != PaymentMethods::GiftCard && self.payment_method != PaymentMethods::StoreCredit {
+            return DatabaseError::business_process_error("Only gift card and store credit payments can be reversed");
+        }
+        // Only the caller that moves the payment out of completed returns the value
+        let updated = diesel::update(
+            payments::table
+                .filter(payments::id.eq(self.id))
+                .filter(payments::status.eq(PaymentStatus::Completed)),
+        )
+        .set((
+            payments::status.eq(PaymentStatus::Cancelled),
+            payments::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not change the status of payment")?;
+        if updated == 0 {
+            return Ok(());
+        }
+
+        if self.payment_method == PaymentMethods::GiftCard {
+            self.gift_card(conn)?.credit(self.amount, current_user_id, conn)?;
+        } else {
+            let raw_uuid = |key: &str| {
+                self.raw_data
+                    .as_ref()
+                    .and_then(|d| d.get(key))
+                    .and_then(|v| v.as_str())
+                    .and_then(|v| Uuid::parse_str(v).ok())
+            };
+            let (user_id, organization_id) = match (raw_uuid("user_id"), raw_uuid("organization_id")) {
+                (Some(user_id), Some(organization_id)) => (user_id, organization_id),
+                _ => {
+                    return DatabaseError::business_process_error("Store credit payment is missing its balance details")
+                }
+            };
+            StoreCreditTransaction::create(
+                user_id,
+                organization_id,
+                self.amount,
+                self.currency.clone(),
+                Some(self.order_id),
+                None,
+                Some("Returned from unpaid cart".to_string()),
+                current_user_id,
+            )
+            .commit(conn)?;
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::PaymentCancelled,
+            "Stored value payment was returned from unpaid order".to_string(),
+            Tables::Payments,
+            Some(self.id),
+            current_user_id,
+            Some(json!({ "amount": self.amount })),
+        )
+        .commit(conn)?;
+        Ok(())
+    }
+
+    fn issue_store_credit(
+        &self,
+        current_user_id: Uuid,
+        refund: &Refund,
+        amount: i64,
+        conn: &PgConnection,
+    ) -> Result<StoreCreditTransaction, DatabaseError> {
+        let order = Order::find(self.order_id, conn)?;
+        let mut organizations = order.organizations(conn)?;
+        if organizations.len() != 1 {
+            return DatabaseError::business_process_error(
+                "Store credit can only be issued for orders from a single organization",
+            );
+        }
+        StoreCreditTransaction::create(
+            order.on_behalf_of_user_id.unwrap_or(order.user_id),
+            organizations.remove(0).id,
+            amount,
+            self.currency.clone(),
+            Some(order.id),
+            Some(refund.id),
+            None,
+            Some(current_user_id),
+        )
+        .commit(conn)
+    }
+
     pub fn add_ipn(
         &self,
         new_status: PaymentStatus,
@@ -138,6 +315,15 @@ impl Payment {
         conn: &PgConnection,
     ) -> Result<(), DatabaseError> {
         self.update_status(new_status, current_user_id, conn)?;
+        self.log_ipn(raw_data, current_user_id, conn)
+    }
+
+    pub fn log_ipn(
+        &self,
+        raw_data: serde_json::Value,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
         DomainEvent::create(
             DomainEventTypes::PaymentProviderIPN,
             "Payment IPN received".to_string(),
@@ -289,6 +475,7 @@ pub struct NewPayment {
     raw_data: Option<serde_json::Value>,
     url_nonce: Option<String>,
     refund_id: Option<Uuid>,
+    currency: String,
 }
 
 impl NewPayment {
@@ -0,0 +1,213 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::dsl::sql;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use diesel::sql_types::BigInt;
+use models::*;
+use schema::{order_items, orders, product_variants};
+use utils::errors::*;
+use uuid::Uuid;
+use validators::{self, *};
+
+/// A purchasable option of a product, e.g. a shirt size or colour, with its own stock count
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Product)]
+#[table_name = "product_variants"]
+pub struct ProductVariant {
+    pub id: Uuid,
+    pub product_id: Uuid,
+    pub name: String,
+    pub price_in_cents: Option<i64>,
+    pub stock_quantity: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(AsChangeset, Clone, Default, Deserialize, Serialize)]
+#[table_name = "product_variants"]
+pub struct ProductVariantEditableAttributes {
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub price_in_cents: Option<Option<i64>>,
+    pub stock_quantity: Option<i32>,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "product_variants"]
+pub struct NewProductVariant {
+    pub product_id: Uuid,
+    pub name: String,
+    pub price_in_cents: Option<i64>,
+    pub stock_quantity: i32,
+}
+
+impl NewProductVariant {
+    pub fn commit(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<ProductVariant, DatabaseError> {
+        ProductVariant::validate_attributes(self.price_in_cents, Some(self.stock_quantity))?;
+
+        let result: ProductVariant = diesel::insert_into(product_variants::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create product variant")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ProductVariantCreated,
+            "Product variant created".to_string(),
+            Tables::ProductVariants,
+            Some(result.id),
+            current_user_id,
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+}
+
+impl ProductVariant {
+    pub fn create(
+        product_id: Uuid,
+        name: String,
+        price_in_cents: Option<i64>,
+        stock_quantity: i32,
+    ) -> NewProductVariant {
+        NewProductVariant {
+            product_id,
+            name,
+            price_in_cents,
+            stock_quantity,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<ProductVariant, DatabaseError> {
+        product_variants::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading product variant")
+    }
+
+    /// Loads the variant with a row lock so that concurrent carts cannot oversell its stock
+    pub fn find_for_update(id: Uuid, conn: &PgConnection) -> Result<ProductVariant, DatabaseError> {
+        product_variants::table
+            .find(id)
+            .for_update()
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading product variant")
+    }
+
+    pub fn find_for_product(product_id: Uuid, conn: &PgConnection) -> Result<Vec<ProductVariant>, DatabaseError> {
+        product_variants::table
+            .filter(product_variants::product_id.eq(product_id))
+            .order_by(product_variants::created_at)
+            .then_order_by(product_variants::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load variants for product")
+    }
+
+    pub fn product(&self, conn: &PgConnection) -> Result<Product, DatabaseError> {
+        Product::find(self.product_id, conn)
+    }
+
+    pub fn description(&self, conn: &PgConnection) -> Result<String, DatabaseError> {
+        Ok(format!("{} - {}", self.product(conn)?.name, self.name))
+    }
+
+    /// The variant price, falling back to the price of its product
+    pub fn unit_price_in_cents(&self, product: &Product) -> i64 {
+        self.price_in_cents.unwrap_or(product.price_in_cents)
+    }
+
+    /// Units sold or held in unexpired carts, ignoring the given order
+    pub fn allocated_count(&self, excluded_order_id: Option<Uuid>, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        let mut query = order_items::table
+            .inner_join(orders::table)
+            .filter(order_items::product_variant_id.eq(self.id))
+            .filter(
+                orders::status.eq(OrderStatus::Paid).or(orders::status
+                    .eq(OrderStatus::Draft)
+                    .and(orders::expires_at.ge(dsl::now.nullable()))),
+            )
+            .select(sql::<BigInt>(
+                "CAST(COALESCE(SUM(order_items.quantity - order_items.refunded_quantity), 0) AS BIGINT)",
+            ))
+            .into_boxed();
+        if let Some(excluded_order_id) = excluded_order_id {
+            query = query.filter(orders::id.ne(excluded_order_id));
+        }
+
+        query.first(conn).to_db_error(
+            ErrorCode::QueryError,
+            "Unable to count allocated stock for product variant",
+        )
+    }
+
+    pub fn available_count(&self, excluded_order_id: Option<Uuid>, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        let available = self.stock_quantity as i64 - self.allocated_count(excluded_order_id, conn)?;
+        Ok(if available > 0 { available } else { 0 })
+    }
+
+    pub fn update(
+        &self,
+        attributes: ProductVariantEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<ProductVariant, DatabaseError> {
+        ProductVariant::validate_attributes(attributes.price_in_cents.unwrap_or(None), attributes.stock_quantity)?;
+        if let Some(stock_quantity) = attributes.stock_quantity {
+            if (stock_quantity as i64) < self.allocated_count(None, conn)? {
+                return DatabaseError::validation_error(
+                    "stock_quantity",
+                    "Stock cannot be lower than the number of units already sold or reserved",
+                );
+            }
+        }
+
+        let result = diesel::update(self)
+            .set((&attributes, product_variants::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update product variant")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ProductVariantUpdated,
+            "Product variant updated".to_string(),
+            Tables::ProductVariants,
+            Some(self.id),
+            current_user_id,
+            Some(json!(&attributes)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn validate_attributes(price_in_cents: Option<i64>, stock_quantity: Option<i32>) -> Result<(), DatabaseError> {
+        let mut validation_errors = Ok(());
+        if let Some(price_in_cents) = price_in_cents {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "price_in_cents",
+                validators::validate_greater_than_or_equal(
+                    price_in_cents,
+                    0,
+                    "number_must_be_positive",
+                    "Price must be positive",
+                ),
+            );
+        }
+        if let Some(stock_quantity) = stock_quantity {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "stock_quantity",
+                validators::validate_greater_than_or_equal(
+                    stock_quantity,
+                    0,
+                    "number_must_be_positive",
+                    "Stock quantity must be positive",
+                ),
+            );
+        }
+        Ok(validation_errors?)
+    }
+}
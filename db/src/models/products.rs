@@ -0,0 +1,201 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::products;
+use utils::errors::*;
+use uuid::Uuid;
+use validators::{self, *};
+
+/// An add-on such as merchandise, parking or drink vouchers sold alongside tickets. Products
+/// belong to an organization and can optionally be limited to a single event.
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Organization)]
+#[table_name = "products"]
+pub struct Product {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub event_id: Option<Uuid>,
+    pub name: String,
+    pub description: Option<String>,
+    pub price_in_cents: i64,
+    pub per_ticket_limit: Option<i32>,
+    pub status: ProductStatus,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub gift_card: bool,
+}
+
+#[derive(AsChangeset, Clone, Default, Deserialize, Serialize)]
+#[table_name = "products"]
+pub struct ProductEditableAttributes {
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub description: Option<Option<String>>,
+    pub price_in_cents: Option<i64>,
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub per_ticket_limit: Option<Option<i32>>,
+    pub status: Option<ProductStatus>,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "products"]
+pub struct NewProduct {
+    pub organization_id: Uuid,
+    pub event_id: Option<Uuid>,
+    pub name: String,
+    pub description: Option<String>,
+    pub price_in_cents: i64,
+    pub per_ticket_limit: Option<i32>,
+    pub gift_card: bool,
+}
+
+impl NewProduct {
+    pub fn commit(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<Product, DatabaseError> {
+        Product::validate_price(self.price_in_cents)?;
+        Product::validate_per_ticket_limit(self.per_ticket_limit)?;
+        if self.gift_card && self.price_in_cents == 0 {
+            return DatabaseError::validation_error("price_in_cents", "Gift card products must have a value");
+        }
+        if let Some(event_id) = self.event_id {
+            if Event::find(event_id, conn)?.organization_id != self.organization_id {
+                return DatabaseError::validation_error("event_id", "Event does not belong to this organization");
+            }
+        }
+
+        let result: Product = diesel::insert_into(products::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create product")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ProductCreated,
+            "Product created".to_string(),
+            Tables::Products,
+            Some(result.id),
+            current_user_id,
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+}
+
+impl Product {
+    pub fn create(
+        organization_id: Uuid,
+        event_id: Option<Uuid>,
+        name: String,
+        description: Option<String>,
+        price_in_cents: i64,
+        per_ticket_limit: Option<i32>,
+    ) -> NewProduct {
+        NewProduct {
+            organization_id,
+            event_id,
+            name,
+            description,
+            price_in_cents,
+            per_ticket_limit,
+            gift_card: false,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<Product, DatabaseError> {
+        products::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading product")
+    }
+
+    pub fn find_for_organization(organization_id: Uuid, conn: &PgConnection) -> Result<Vec<Product>, DatabaseError> {
+        products::table
+            .filter(products::organization_id.eq(organization_id))
+            .order_by(products::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load products for organization")
+    }
+
+    /// Products that can be added to a cart for the event, including organization wide products
+    pub fn find_published_for_event(event_id: Uuid, conn: &PgConnection) -> Result<Vec<Product>, DatabaseError> {
+        let event = Event::find(event_id, conn)?;
+        products::table
+            .filter(products::organization_id.eq(event.organization_id))
+            .filter(products::event_id.eq(event_id).or(products::event_id.is_null()))
+            .filter(products::status.eq(ProductStatus::Published))
+            .order_by(products::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load products for event")
+    }
+
+    pub fn organization(&self, conn: &PgConnection) -> Result<Organization, DatabaseError> {
+        Organization::find(self.organization_id, conn)
+    }
+
+    pub fn variants(&self, conn: &PgConnection) -> Result<Vec<ProductVariant>, DatabaseError> {
+        ProductVariant::find_for_product(self.id, conn)
+    }
+
+    pub fn update(
+        &self,
+        attributes: ProductEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Product, DatabaseError> {
+        if let Some(price_in_cents) = attributes.price_in_cents {
+            Product::validate_price(price_in_cents)?;
+        }
+        if let Some(per_ticket_limit) = attributes.per_ticket_limit {
+            Product::validate_per_ticket_limit(per_ticket_limit)?;
+        }
+
+        let result = diesel::update(self)
+            .set((&attributes, products::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update product")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ProductUpdated,
+            "Product updated".to_string(),
+            Tables::Products,
+            Some(self.id),
+            current_user_id,
+            Some(json!(&attributes)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn validate_price(price_in_cents: i64) -> Result<(), DatabaseError> {
+        Ok(validators::append_validation_error(
+            Ok(()),
+            "price_in_cents",
+            validators::validate_greater_than_or_equal(
+                price_in_cents,
+                0,
+                "number_must_be_positive",
+                "Price must be positive",
+            ),
+        )?)
+    }
+
+    fn validate_per_ticket_limit(per_ticket_limit: Option<i32>) -> Result<(), DatabaseError> {
+        if let Some(per_ticket_limit) = per_ticket_limit {
+            validators::append_validation_error(
+                Ok(()),
+                "per_ticket_limit",
+                validators::validate_greater_than_or_equal(
+                    per_ticket_limit,
+                    0,
+                    "number_must_be_positive",
+                    "Per ticket limit must be positive",
+                ),
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use chrono::prelude::*;
-use diesel::sql_types::{BigInt, Nullable, Text, Timestamp, Uuid as dUuid};
+use diesel::sql_types::{BigInt, Bool, Date, Nullable, Text, Timestamp, Uuid as dUuid};
 use models::*;
 use uuid::Uuid;
 
@@ -49,4 +49,13 @@ pub struct RedeemableTicket {
     pub redeemed_by: Option<String>,
     #[sql_type = "Nullable<Timestamp>"]
     pub redeemed_at: Option<NaiveDateTime>,
+    #[sql_type = "Nullable<Text>"]
+    pub seat_label: Option<String>,
+    /// Name given for the ticket, which door staff check against ID for named tickets
+    #[sql_type = "Nullable<Text>"]
+    pub attendee_name: Option<String>,
+    #[sql_type = "Nullable<Date>"]
+    pub attendee_birth_date: Option<NaiveDate>,
+    #[sql_type = "Bool"]
+    pub named_ticket: bool,
 }
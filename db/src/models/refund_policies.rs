@@ -0,0 +1,136 @@
+use chrono::{Duration, NaiveDateTime};
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::refund_policies;
+use utils::errors::*;
+use uuid::Uuid;
+use validators::{self, *};
+
+/// Controls whether fans can request refunds for an event themselves and how those requests are
+/// handled. Events without a policy only accept refunds issued by the organizer.
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Event)]
+#[table_name = "refund_policies"]
+pub struct RefundPolicy {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub approval_type: RefundApprovalTypes,
+    pub deadline_hours_before_event: i32,
+    pub retain_fees: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(AsChangeset, Clone, Default, Deserialize, Serialize)]
+#[table_name = "refund_policies"]
+pub struct RefundPolicyEditableAttributes {
+    pub approval_type: Option<RefundApprovalTypes>,
+    pub deadline_hours_before_event: Option<i32>,
+    pub retain_fees: Option<bool>,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "refund_policies"]
+pub struct NewRefundPolicy {
+    pub event_id: Uuid,
+    pub approval_type: RefundApprovalTypes,
+    pub deadline_hours_before_event: i32,
+    pub retain_fees: bool,
+}
+
+impl NewRefundPolicy {
+    pub fn commit(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<RefundPolicy, DatabaseError> {
+        RefundPolicy::validate_deadline(self.deadline_hours_before_event)?;
+
+        let result: RefundPolicy = diesel::insert_into(refund_policies::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create refund policy")?;
+
+        DomainEvent::create(
+            DomainEventTypes::RefundPolicyCreated,
+            "Refund policy created".to_string(),
+            Tables::Events,
+            Some(self.event_id),
+            current_user_id,
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+}
+
+impl RefundPolicy {
+    pub fn create(
+        event_id: Uuid,
+        approval_type: RefundApprovalTypes,
+        deadline_hours_before_event: i32,
+        retain_fees: bool,
+    ) -> NewRefundPolicy {
+        NewRefundPolicy {
+            event_id,
+            approval_type,
+            deadline_hours_before_event,
+            retain_fees,
+        }
+    }
+
+    pub fn find_for_event(event_id: Uuid, conn: &PgConnection) -> Result<Option<RefundPolicy>, DatabaseError> {
+        refund_policies::table
+            .filter(refund_policies::event_id.eq(event_id))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Error loading refund policy")
+    }
+
+    /// Last moment refunds can be requested, `None` when the event has no start date
+    pub fn request_deadline(&self, event: &Event) -> Option<NaiveDateTime> {
+        event
+            .event_start
+            .map(|event_start| event_start - Duration::hours(self.deadline_hours_before_event as i64))
+    }
+
+    pub fn update(
+        &self,
+        attributes: RefundPolicyEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<RefundPolicy, DatabaseError> {
+        if let Some(deadline_hours_before_event) = attributes.deadline_hours_before_event {
+            RefundPolicy::validate_deadline(deadline_hours_before_event)?;
+        }
+
+        let result = diesel::update(self)
+            .set((&attributes, refund_policies::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update refund policy")?;
+
+        DomainEvent::create(
+            DomainEventTypes::RefundPolicyUpdated,
+            "Refund policy updated".to_string(),
+            Tables::Events,
+            Some(self.event_id),
+            current_user_id,
+            Some(json!(&attributes)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn validate_deadline(deadline_hours_before_event: i32) -> Result<(), DatabaseError> {
+        Ok(validators::append_validation_error(
+            Ok(()),
+            "deadline_hours_before_event",
+            validators::validate_greater_than_or_equal(
+                deadline_hours_before_event,
+                0,
+                "number_must_be_positive",
+                "Deadline must be positive",
+            ),
+        )?)
+    }
+}
@@ -0,0 +1,323 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl::count;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{events, refund_requests};
+use utils::errors::*;
+use utils::pagination::*;
+use uuid::Uuid;
+
+/// A fan's request to refund tickets on their order, reviewed by the organizer unless the event's
+/// refund policy approves requests automatically
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Order)]
+#[table_name = "refund_requests"]
+pub struct RefundRequest {
+    pub id: Uuid,
+    pub order_id: Uuid,
+    pub event_id: Uuid,
+    pub user_id: Uuid,
+    pub ticket_instance_ids: Vec<Uuid>,
+    pub reason: String,
+    pub status: RefundRequestStatus,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<NaiveDateTime>,
+    pub response_note: Option<String>,
+    pub refund_id: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "refund_requests"]
+pub struct NewRefundRequest {
+    pub order_id: Uuid,
+    pub user_id: Uuid,
+    pub ticket_instance_ids: Vec<Uuid>,
+    pub reason: String,
+}
+
+impl NewRefundRequest {
+    pub fn commit(&self, conn: &PgConnection) -> Result<RefundRequest, DatabaseError> {
+        let event_id = self.validate(conn)?;
+
+        let result: RefundRequest = diesel::insert_into(refund_requests::table)
+            .values((self, refund_requests::event_id.eq(event_id)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create refund request")?;
+
+        DomainEvent::create(
+            DomainEventTypes::RefundRequestCreated,
+            "Refund requested".to_string(),
+            Tables::Orders,
+            Some(self.order_id),
+            Some(self.user_id),
+            Some(json!({
+                "refund_request_id": result.id,
+                "ticket_instance_ids": self.ticket_instance_ids,
+                "reason": self.reason,
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    /// Checks the tickets can be refunded under their event's refund policy, returning the event id
+    fn validate(&self, conn: &PgConnection) -> Result<Uuid, DatabaseError> {
+        if self.reason.trim().is_empty() {
+            return DatabaseError::validation_error("reason", "Reason is required");
+        }
+        if self.ticket_instance_ids.is_empty() {
+            return DatabaseError::validation_error("ticket_instance_ids", "At least one ticket is required");
+        }
+
+        let order = Order::find(self.order_id, conn)?;
+        if order.status != OrderStatus::Paid {
+            return DatabaseError::business_process_error("Refunds can only be requested for paid orders");
+        }
+
+        let mut event_ids = Vec::new();
+        for ticket_instance_id in &self.ticket_instance_ids {
+            let ticket_instance = TicketInstance::find(*ticket_instance_id, conn)?;
+            let order_item = match ticket_instance.order_item_id {
+                Some(order_item_id) => OrderItem::find(order_item_id, conn)?,
+                None => {
+                    return DatabaseError::validation_error(
+                        "ticket_instance_ids",
+                        "Ticket does not belong to this order",
+                    );
+                }
+            };
+            if order_item.order_id != self.order_id {
+                return DatabaseError::validation_error("ticket_instance_ids", "Ticket does not belong to this order");
+            }
+            if ticket_instance.status != TicketInstanceStatus::Purchased {
+                return DatabaseError::validation_error(
+                    "ticket_instance_ids",
+                    "Only purchased tickets that have not been redeemed can be refunded",
+                );
+            }
+            if ticket_instance.was_transferred(conn)? {
+                return DatabaseError::validation_error(
+                    "ticket_instance_ids",
+                    "Ticket was transferred so ineligible for refund",
+                );
+            }
+            if let Some(event_id) = order_item.event_id {
+                event_ids.push(event_id);
+            }
+        }
+        event_ids.sort();
+        event_ids.dedup();
+        if event_ids.len() != 1 {
+            return DatabaseError::validation_error(
+                "ticket_instance_ids",
+                "Refund requests are limited to tickets for a single event",
+            );
+        }
+
+        let event = Event::find(event_ids[0], conn)?;
+        let refund_policy = match RefundPolicy::find_for_event(event.id, conn)? {
+            Some(refund_policy) => refund_policy,
+            None => return DatabaseError::business_process_error("Refunds cannot be requested for this event"),
+        };
+        if let Some(deadline) = refund_policy.request_deadline(&event) {
+            if deadline < Utc::now().naive_utc() {
+                return DatabaseError::business_process_error(
+                    "The deadline to request refunds for this event has passed",
+                );
+            }
+        }
+
+        let pending_count: i64 = refund_requests::table
+            .filter(refund_requests::status.eq(RefundRequestStatus::Pending))
+            .filter(refund_requests::ticket_instance_ids.overlaps_with(&self.ticket_instance_ids))
+            .select(count(refund_requests::id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not check pending refund requests")?;
+        if pending_count > 0 {
+            return DatabaseError::validation_error(
+                "ticket_instance_ids",
+                "A refund has already been requested for these tickets",
+            );
+        }
+
+        Ok(event.id)
+    }
+}
+
+impl RefundRequest {
+    pub fn create(order_id: Uuid, user_id: Uuid, ticket_instance_ids: Vec<Uuid>, reason: String) -> NewRefundRequest {
+        NewRefundRequest {
+            order_id,
+            user_id,
+            ticket_instance_ids,
+            reason,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<RefundRequest, DatabaseError> {
+        refund_requests::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading refund request")
+    }
+
+    pub fn find_for_order(order_id: Uuid, conn: &PgConnection) -> Result<Vec<RefundRequest>, DatabaseError> {
+        refund_requests::table
+            .filter(refund_requests::order_id.eq(order_id))
+            .order_by(refund_requests::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load refund requests for order")
+    }
+
+    /// Refund requests awaiting (or having had) review by the organization, oldest first
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        event_id: Option<Uuid>,
+        status: Option<RefundRequestStatus>,
+        page: u32,
+        limit: u32,
+        conn: &PgConnection,
+    ) -> Result<Payload<RefundRequest>, DatabaseError> {
+        let mut query = refund_requests::table
+            .inner_join(events::table.on(events::id.eq(refund_requests::event_id)))
+            .filter(events::organization_id.eq(organization_id))
+            .select(refund_requests::all_columns)
+            .into_boxed();
+
+        if let Some(event_id) = event_id {
+            query = query.filter(refund_requests::event_id.eq(event_id));
+        }
+        if let Some(status) = status {
+            query = query.filter(refund_requests::status.eq(status));
+        }
+
+        let (refund_requests, record_count): (Vec<RefundRequest>, i64) = query
+            .order_by(refund_requests::created_at.asc())
+            .paginate(page as i64)
+            .per_page(limit as i64)
+            .load_and_count_pages(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading refund requests")?;
+
+        Ok(Payload::from_data(
+            refund_requests,
+            page,
+            limit,
+            Some(record_count as u64),
+        ))
+    }
+
+    pub fn event(&self, conn: &PgConnection) -> Result<Event, DatabaseError> {
+        Event::find(self.event_id, conn)
+    }
+
+    pub fn order(&self, conn: &PgConnection) -> Result<Order, DatabaseError> {
+        Order::find(self.order_id, conn)
+    }
+
+    pub fn user(&self, conn: &PgConnection) -> Result<User, DatabaseError> {
+        User::find(self.user_id, conn)
+    }
+
+    pub fn refund_policy(&self, conn: &PgConnection) -> Result<RefundPolicy, DatabaseError> {
+        match RefundPolicy::find_for_event(self.event_id, conn)? {
+            Some(refund_policy) => Ok(refund_policy),
+            None => DatabaseError::business_process_error("Refunds cannot be requested for this event"),
+        }
+    }
+
+    /// Order items to pass to `Order::refund` for the requested tickets
+    pub fn refund_items(&self, conn: &PgConnection) -> Result<Vec<RefundItemRequest>, DatabaseError> {
+        let mut items = Vec::new();
+        for ticket_instance_id in &self.ticket_instance_ids {
+            let ticket_instance = TicketInstance::find(*ticket_instance_id, conn)?;
+            match ticket_instance.order_item_id {
+                Some(order_item_id) => items.push(RefundItemRequest {
+                    order_item_id,
+                    ticket_instance_id: Some(ticket_instance.id),
+                }),
+                None => {
+                    return DatabaseError::business_process_error("Ticket is no longer associated with this order");
+                }
+            }
+        }
+        Ok(items)
+    }
+
+    /// Records the refund issued for this request. `reviewed_by` is `None` when the request was
+    /// approved automatically.
+    pub fn approve(
+        &self,
+        refund: &Refund,
+        reviewed_by: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<RefundRequest, DatabaseError> {
+        self.validate_pending()?;
+
+        let result: RefundRequest = diesel::update(self)
+            .set((
+                refund_requests::status.eq(RefundRequestStatus::Approved),
+                refund_requests::refund_id.eq(Some(refund.id)),
+                refund_requests::reviewed_by.eq(reviewed_by),
+                refund_requests::reviewed_at.eq(dsl::now.nullable()),
+                refund_requests::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not approve refund request")?;
+
+        DomainEvent::create(
+            DomainEventTypes::RefundRequestApproved,
+            "Refund request approved".to_string(),
+            Tables::Orders,
+            Some(self.order_id),
+            reviewed_by,
+            Some(json!({ "refund_request_id": self.id, "refund_id": refund.id })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    pub fn deny(
+        &self,
+        response_note: Option<String>,
+        reviewed_by: Uuid,
+        conn: &PgConnection,
+    ) -> Result<RefundRequest, DatabaseError> {
+        self.validate_pending()?;
+
+        let result: RefundRequest = diesel::update(self)
+            .set((
+                refund_requests::status.eq(RefundRequestStatus::Denied),
+                refund_requests::response_note.eq(&response_note),
+                refund_requests::reviewed_by.eq(Some(reviewed_by)),
+                refund_requests::reviewed_at.eq(dsl::now.nullable()),
+                refund_requests::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not deny refund request")?;
+
+        DomainEvent::create(
+            DomainEventTypes::RefundRequestDenied,
+            "Refund request denied".to_string(),
+            Tables::Orders,
+            Some(self.order_id),
+            Some(reviewed_by),
+            Some(json!({ "refund_request_id": self.id, "response_note": response_note })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    pub fn validate_pending(&self) -> Result<(), DatabaseError> {
+        if self.status != RefundRequestStatus::Pending {
+            return DatabaseError::business_process_error("Refund request has already been reviewed");
+        }
+        Ok(())
+    }
+}
@@ -70,6 +70,17 @@ impl RefundedTicket {
         self.mark_refunded(true, conn)
     }
 
+    /// Marks the ticket refunded while its fee is kept by the organizer
+    pub fn mark_ticket_only_refunded(&mut self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        self.updated_at = Utc::now().naive_utc();
+
+        if self.ticket_refunded_at.is_none() {
+            self.ticket_refunded_at = Some(self.updated_at);
+        }
+
+        self.save(conn)
+    }
+
     pub fn mark_refunded(&mut self, just_fee: bool, conn: &PgConnection) -> Result<(), DatabaseError> {
         self.updated_at = Utc::now().naive_utc();
 
@@ -81,6 +92,10 @@ impl RefundedTicket {
             self.fee_refunded_at = Some(self.updated_at);
         }
 
+        self.save(conn)
+    }
+
+    fn save(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
         diesel::update(refunded_tickets::table.filter(refunded_tickets::id.eq(self.id)))
             .set((
                 refunded_tickets::updated_at.eq(self.updated_at),
@@ -198,6 +198,8 @@ pub struct SalesSummaryReportRow {
     #[sql_type = "Nullable<Timestamp>"]
     pub event_date: Option<NaiveDateTime>,
     #[sql_type = "Text"]
+    pub currency: String,
+    #[sql_type = "Text"]
     pub ticket_name: String,
     #[sql_type = "BigInt"]
     pub face_value_in_cents: i64,
@@ -236,6 +238,8 @@ pub struct DomainTransactionReportRow {
     pub point_of_sale: Option<String>,
     #[sql_type = "Text"]
     pub payment_method: String,
+    #[sql_type = "Text"]
+    pub currency: String,
     #[sql_type = "BigInt"]
     pub qty_tickets_sold: i64,
     #[sql_type = "BigInt"]
@@ -293,10 +297,18 @@ pub struct TransactionReportRow {
     #[serde(skip_serializing)]
     #[sql_type = "BigInt"]
     pub event_fee_client_in_cents_total: i64,
+    #[sql_type = "BigInt"]
+    pub tax_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub included_tax_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub tax_in_cents_total: i64,
     #[sql_type = "Nullable<dUuid>"]
     pub fee_range_id: Option<Uuid>,
     #[sql_type = "Text"]
     pub order_type: OrderTypes,
+    #[sql_type = "Text"]
+    pub currency: String,
     #[sql_type = "Nullable<Text>"]
     pub payment_method: Option<String>,
     #[sql_type = "Nullable<Text>"]
@@ -341,6 +353,9 @@ pub struct TransactionReportRow {
     pub platform: Option<String>,
     #[sql_type = "Nullable<Text>"]
     pub check_in_source: Option<String>,
+    /// Seats held by the line's tickets, comma separated
+    #[sql_type = "Nullable<Text>"]
+    pub seat_label: Option<String>,
     #[sql_type = "Nullable<Text>"]
     pub headline_artist_alt_genres: Option<String>,
     #[sql_type = "Nullable<Text>"]
@@ -376,6 +391,8 @@ pub struct BoxOfficeSalesSummaryReportRow {
     pub event_name: Option<String>,
     #[sql_type = "Nullable<Timestamp>"]
     pub event_date: Option<NaiveDateTime>,
+    #[sql_type = "Text"]
+    pub currency: String,
     #[sql_type = "Nullable<Text>"]
     pub external_payment_type: Option<ExternalPaymentType>,
     #[sql_type = "BigInt"]
@@ -397,6 +414,7 @@ pub struct BoxOfficeSalesSummaryReport {
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct BoxOfficeSalesSummaryPaymentRow {
     pub payment_type: ExternalPaymentType,
+    pub currency: String,
     pub quantity: u32,
     pub total_sales_in_cents: u32,
 }
@@ -413,6 +431,7 @@ pub struct BoxOfficeSalesSummaryOperatorRow {
 pub struct BoxOfficeSalesSummaryOperatorEventRow {
     pub event_name: Option<String>,
     pub event_date: Option<NaiveDateTime>,
+    pub currency: String,
     pub number_of_tickets: u32,
     pub face_value_in_cents: u32,
     pub revenue_share_value_in_cents: u32,
@@ -508,22 +527,67 @@ pub struct ScanCountReportRow {
     pub scanned_count: i64,
     #[sql_type = "BigInt"]
     pub not_scanned_count: i64,
+    /// Scanned tickets whose holders have not been scanned out since
+    #[sql_type = "BigInt"]
+    pub inside_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Queryable, QueryableByName)]
+pub struct OccupancyReportRow {
+    #[sql_type = "Timestamp"]
+    pub period_start: NaiveDateTime,
+    #[sql_type = "BigInt"]
+    pub entries: i64,
+    #[sql_type = "BigInt"]
+    pub exits: i64,
+    /// Tickets inside at the end of the period
+    #[sql_type = "BigInt"]
+    pub occupancy: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ScanCountReport {
+    #[serde(flatten)]
+    pub scan_counts: Payload<ScanCountReportRow>,
+    /// Entries, exits and occupancy per hour
+    pub occupancy: Vec<OccupancyReportRow>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct ReconciliationSummaryResult {
+    pub currency: String,
     pub payment_method: String,
     pub payment_provider: String,
     pub quantity: i64,
     pub unit_price_in_cents: i64,
     pub client_fee_in_cents: i64,
     pub event_fee_in_cents: i64,
+    pub tax_in_cents: i64,
     pub sales_total: i64,
     pub refund_quantity: i64,
     pub refund_unit_price_in_cents: i64,
     pub refund_client_fee_in_cents: i64,
     pub refund_event_fee_in_cents: i64,
+    pub refund_tax_in_cents: i64,
+    pub refund_total: i64,
+    pub total: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, QueryableByName)]
+pub struct ReconciliationPaymentResult {
+    #[sql_type = "Text"]
+    pub currency: String,
+    #[sql_type = "Text"]
+    pub payment_method: String,
+    #[sql_type = "Text"]
+    pub payment_provider: String,
+    #[sql_type = "BigInt"]
+    pub payment_count: i64,
+    #[sql_type = "BigInt"]
+    pub sales_total: i64,
+    #[sql_type = "BigInt"]
     pub refund_total: i64,
+    #[sql_type = "BigInt"]
     pub total: i64,
 }
 
@@ -545,11 +609,13 @@ pub struct ReconciliationDetailResult {
     pub unit_price_in_cents: i64,
     pub client_fee_in_cents: Vec<ReconciliationFeeRangeResult>,
     pub event_fee_in_cents: i64,
+    pub tax_in_cents: i64,
     pub sales_total: i64,
     pub refund_quantity: i64,
     pub refund_unit_price_in_cents: i64,
     pub refund_client_fee_in_cents: Vec<ReconciliationFeeRangeResult>,
     pub refund_event_fee_in_cents: i64,
+    pub refund_tax_in_cents: i64,
     pub refund_total: i64,
     pub total: i64,
 }
@@ -559,6 +625,7 @@ pub struct ReconciliationDetailEventResult {
     pub event_id: Uuid,
     pub event_name: String,
     pub event_start: Option<NaiveDateTime>,
+    pub currency: String,
     pub entries: Vec<ReconciliationDetailResult>,
 }
 
@@ -663,13 +730,54 @@ impl TicketCountRow {
             .time();
         let group_by = group_by_string(group_by_ticket_type, false, false, group_by_event);
         let query_ticket_counts = include_str!("../queries/reports/reports_tickets_counts.sql");
-        diesel::sql_query(query_ticket_counts)
+        let mut rows: Vec<TicketCountRow> = diesel::sql_query(query_ticket_counts)
             .bind::<Nullable<dUuid>, _>(event_id)
             .bind::<Nullable<dUuid>, _>(organization_id)
             .bind::<Nullable<Text>, _>(group_by)
             .bind::<Time, _>(four_am_pacific)
             .get_results(conn)
-            .to_db_error(ErrorCode::QueryError, "Could not fetch ticket counts")
+            .to_db_error(ErrorCode::QueryError, "Could not fetch ticket counts")?;
+
+        TicketCountRow::apply_inventory_pool_limits(&mut rows, event_id, organization_id, conn)?;
+        Ok(rows)
+    }
+
+    /// Ticket types sharing an inventory pool each have their own unsold tickets but can only sell
+    /// what is left in the pool, so their combined availability is capped at the pool remainder
+    fn apply_inventory_pool_limits(
+        rows: &mut Vec<TicketCountRow>,
+        event_id: Option<Uuid>,
+        organization_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        let pooled_ticket_types = InventoryPool::ticket_type_availability(event_id, organization_id, conn)?;
+        if pooled_ticket_types.is_empty() {
+            return Ok(());
+        }
+
+        for row in rows.iter_mut() {
+            let matching_ticket_types =
+                pooled_ticket_types
+                    .iter()
+                    .filter(|p| match (row.ticket_type_id, row.event_id) {
+                        (Some(ticket_type_id), _) => p.ticket_type_id == ticket_type_id,
+                        (None, Some(event_id)) => p.event_id == event_id,
+                        (None, None) => row.organization_id == Some(p.organization_id),
+                    });
+
+            let mut unavailable_count = 0;
+            for (_, pool_ticket_types) in &matching_ticket_types.group_by(|p| p.inventory_pool_id) {
+                let pool_ticket_types: Vec<&InventoryPoolTicketTypeAvailability> = pool_ticket_types.collect();
+                let available_count: i64 = pool_ticket_types.iter().map(|p| p.available_count).sum();
+                let pool_available_count = pool_ticket_types[0].pool_available_count;
+                if available_count > pool_available_count {
+                    unavailable_count += available_count - pool_available_count;
+                }
+            }
+            row.available_for_purchase_count -= unavailable_count;
+        }
+
+        Ok(())
     }
 }
 
@@ -766,35 +874,40 @@ impl Report {
             .get_results(conn)
             .to_db_error(ErrorCode::QueryError, "Could not fetch report results")?;
 
-        let mut payment_totals: HashMap<ExternalPaymentType, BoxOfficeSalesSummaryPaymentRow> = HashMap::new();
+        // Payments are totalled per currency as operators may sell events priced in different currencies
+        let mut payment_totals: HashMap<(String, ExternalPaymentType), BoxOfficeSalesSummaryPaymentRow> =
+            HashMap::new();
         let mut operator_data: Vec<BoxOfficeSalesSummaryOperatorRow> = Vec::new();
         let mut operator_payments: HashMap<Uuid, Vec<BoxOfficeSalesSummaryPaymentRow>> = HashMap::new();
         for (operator_id, group) in &payment_box_office_summary_rows
             .into_iter()
             .group_by(|row| row.operator_id)
         {
-            let mut payments: HashMap<ExternalPaymentType, BoxOfficeSalesSummaryPaymentRow> = HashMap::new();
+            let mut payments: HashMap<(String, ExternalPaymentType), BoxOfficeSalesSummaryPaymentRow> = HashMap::new();
             for group_item in group {
                 if let Some(external_payment_type) = group_item.external_payment_type {
+                    let key = (group_item.currency.clone(), external_payment_type);
                     payment_totals
-                        .entry(external_payment_type)
+                        .entry(key.clone())
                         .and_modify(|e| {
                             e.quantity += group_item.number_of_tickets as u32;
                             e.total_sales_in_cents += group_item.total_sales_in_cents as u32;
                         })
                         .or_insert_with(|| BoxOfficeSalesSummaryPaymentRow {
                             payment_type: external_payment_type,
+                            currency: group_item.currency.clone(),
                             quantity: group_item.number_of_tickets as u32,
                             total_sales_in_cents: group_item.total_sales_in_cents as u32,
                         });
                     payments
-                        .entry(external_payment_type)
+                        .entry(key)
                         .and_modify(|e| {
                             e.quantity += group_item.number_of_tickets as u32;
                             e.total_sales_in_cents += group_item.total_sales_in_cents as u32;
                         })
                         .or_insert_with(|| BoxOfficeSalesSummaryPaymentRow {
                             payment_type: external_payment_type,
+                            currency: group_item.currency.clone(),
                             quantity: group_item.number_of_tickets as u32,
                             total_sales_in_cents: group_item.total_sales_in_cents as u32,
                         });
@@ -805,7 +918,7 @@ impl Report {
                 .values()
                 .map(|v| (*v).clone())
                 .collect::<Vec<BoxOfficeSalesSummaryPaymentRow>>();
-            payments.sort_by_key(|p| p.payment_type.to_string());
+            payments.sort_by_key(|p| (p.currency.clone(), p.payment_type.to_string()));
             operator_payments.insert(operator_id, payments);
         }
 
@@ -820,6 +933,7 @@ impl Report {
                 events.push(BoxOfficeSalesSummaryOperatorEventRow {
                     event_name: group_item.event_name.clone(),
                     event_date: group_item.event_date,
+                    currency: group_item.currency.clone(),
                     number_of_tickets: group_item.number_of_tickets as u32,
                     face_value_in_cents: group_item.face_value_in_cents as u32,
                     revenue_share_value_in_cents: group_item.revenue_share_value_in_cents as u32,
@@ -839,7 +953,7 @@ impl Report {
             .values()
             .map(|v| (*v).clone())
             .collect::<Vec<BoxOfficeSalesSummaryPaymentRow>>();
-        payment_totals.sort_by_key(|p| p.payment_type.to_string());
+        payment_totals.sort_by_key(|p| (p.currency.clone(), p.payment_type.to_string()));
 
         Ok(BoxOfficeSalesSummaryReport {
             operators: operator_data,
@@ -1083,6 +1197,14 @@ impl Report {
         Ok(Payload::new(scan_count_rows, paging))
     }
 
+    pub fn occupancy_report(event_id: Uuid, conn: &PgConnection) -> Result<Vec<OccupancyReportRow>, DatabaseError> {
+        let query = include_str!("../queries/reports/reports_occupancy.sql");
+        diesel::sql_query(query)
+            .bind::<dUuid, _>(event_id)
+            .get_results(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not fetch report results")
+    }
+
     pub fn promo_code_report(
         event_id: Option<Uuid>,
         organization_id: Option<Uuid>,
@@ -1147,13 +1269,16 @@ impl Report {
         let mut results: Vec<ReconciliationSummaryResult> = Vec::new();
         for row in transaction_rows {
             if row.payment_method.is_some() && row.payment_provider.is_some() {
+                // Amounts in different currencies are never summed together
                 let entry_exists = results.iter().any(|r| {
-                    r.payment_method == row.payment_method.clone().unwrap()
+                    r.currency == row.currency
+                        && r.payment_method == row.payment_method.clone().unwrap()
                         && r.payment_provider == row.payment_provider.clone().unwrap()
                 });
                 if entry_exists {
                     if let Some(entry) = results.iter_mut().find(|r| {
-                        r.payment_method == row.payment_method.clone().unwrap()
+                        r.currency == row.currency
+                            && r.payment_method == row.payment_method.clone().unwrap()
                             && r.payment_provider == row.payment_provider.clone().unwrap()
                     }) {
                         let ticket_face = row.unit_price_in_cents * row.actual_quantity;
@@ -1162,17 +1287,25 @@ impl Report {
                         let refund_ticket_face = row.unit_price_in_cents * row.refunded_quantity;
                         let refund_client_fee = row.client_fee_in_cents * row.refunded_quantity;
                         let refund_event_fee = row.event_fee_client_in_cents * row.refunded_quantity;
-                        let sales_total = ticket_face + client_fee + event_fee;
-                        let refund_total = refund_ticket_face + refund_client_fee + refund_event_fee;
+                        // Inclusive taxes are already part of the face value so only exclusive taxes add to the totals
+                        let tax = (row.tax_in_cents + row.included_tax_in_cents) * row.actual_quantity;
+                        let refund_tax = (row.tax_in_cents + row.included_tax_in_cents) * row.refunded_quantity;
+                        let sales_total = ticket_face + client_fee + event_fee + row.tax_in_cents * row.actual_quantity;
+                        let refund_total = refund_ticket_face
+                            + refund_client_fee
+                            + refund_event_fee
+                            + row.tax_in_cents * row.refunded_quantity;
                         entry.quantity += row.actual_quantity;
                         entry.unit_price_in_cents += ticket_face;
                         entry.client_fee_in_cents += client_fee;
                         entry.event_fee_in_cents += event_fee;
+                        entry.tax_in_cents += tax;
                         entry.sales_total += sales_total;
                         entry.refund_quantity += row.refunded_quantity;
                         entry.refund_unit_price_in_cents += refund_ticket_face;
                         entry.refund_client_fee_in_cents += refund_client_fee;
                         entry.refund_event_fee_in_cents += refund_event_fee;
+                        entry.refund_tax_in_cents += refund_tax;
                         entry.refund_total += refund_total;
                         entry.total += sales_total - refund_total;
                     }
@@ -1183,20 +1316,28 @@ impl Report {
                     let refund_ticket_face = row.unit_price_in_cents * row.refunded_quantity;
                     let refund_client_fee = row.client_fee_in_cents * row.refunded_quantity;
                     let refund_event_fee = row.event_fee_client_in_cents * row.refunded_quantity;
-                    let sales_total = ticket_face + client_fee + event_fee;
-                    let refund_total = refund_ticket_face + refund_client_fee + refund_event_fee;
+                    let tax = (row.tax_in_cents + row.included_tax_in_cents) * row.actual_quantity;
+                    let refund_tax = (row.tax_in_cents + row.included_tax_in_cents) * row.refunded_quantity;
+                    let sales_total = ticket_face + client_fee + event_fee + row.tax_in_cents * row.actual_quantity;
+                    let refund_total = refund_ticket_face
+                        + refund_client_fee
+                        + refund_event_fee
+                        + row.tax_in_cents * row.refunded_quantity;
                     results.push(ReconciliationSummaryResult {
+                        currency: row.currency,
                         payment_method: row.payment_method.unwrap(),
                         payment_provider: row.payment_provider.unwrap(),
                         quantity: row.actual_quantity,
                         unit_price_in_cents: ticket_face,
                         client_fee_in_cents: client_fee,
                         event_fee_in_cents: event_fee,
+                        tax_in_cents: tax,
                         sales_total,
                         refund_quantity: row.refunded_quantity,
                         refund_unit_price_in_cents: refund_ticket_face,
                         refund_client_fee_in_cents: refund_client_fee,
                         refund_event_fee_in_cents: refund_event_fee,
+                        refund_tax_in_cents: refund_tax,
                         refund_total,
                         total: sales_total - refund_total,
                     });
@@ -1207,6 +1348,22 @@ impl Report {
         Ok(results)
     }
 
+    /// Amounts collected and refunded through each payment method, including gift cards and store credit
+    pub fn reconciliation_payments_report(
+        organization_id: Uuid,
+        start: Option<NaiveDateTime>,
+        end: Option<NaiveDateTime>,
+        conn: &PgConnection,
+    ) -> Result<Vec<ReconciliationPaymentResult>, DatabaseError> {
+        let query = include_str!("../queries/reports/reports_reconciliation_payments.sql");
+        diesel::sql_query(query)
+            .bind::<dUuid, _>(organization_id)
+            .bind::<Nullable<Timestamp>, _>(start)
+            .bind::<Nullable<Timestamp>, _>(end)
+            .get_results(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not fetch report results")
+    }
+
     pub fn reconciliation_detail_report(
         organization_id: Uuid,
         start: Option<NaiveDateTime>,
@@ -1290,6 +1447,7 @@ impl Report {
                     event_id: row.event_id.clone(),
                     event_name: row.event_name.clone(),
                     event_start: row.event_start.clone(),
+                    currency: row.currency.clone(),
                     entries: Vec::new(),
                 });
             }
@@ -1324,18 +1482,26 @@ impl Report {
                                 let refund_ticket_face = row.unit_price_in_cents * row.refunded_quantity;
                                 let refund_client_fee = row.client_fee_in_cents * row.refunded_quantity;
                                 let refund_event_fee = row.event_fee_client_in_cents * row.refunded_quantity;
-                                let sales_total = ticket_face + client_fee + event_fee;
-                                let refund_total = refund_ticket_face + refund_client_fee + refund_event_fee;
+                                let tax = (row.tax_in_cents + row.included_tax_in_cents) * row.actual_quantity;
+                                let refund_tax = (row.tax_in_cents + row.included_tax_in_cents) * row.refunded_quantity;
+                                let sales_total =
+                                    ticket_face + client_fee + event_fee + row.tax_in_cents * row.actual_quantity;
+                                let refund_total = refund_ticket_face
+                                    + refund_client_fee
+                                    + refund_event_fee
+                                    + row.tax_in_cents * row.refunded_quantity;
 
                                 entry.quantity += row.actual_quantity;
                                 entry.unit_price_in_cents += ticket_face;
                                 entry.client_fee_in_cents[column_idx].client_fee_in_cents += client_fee;
                                 entry.event_fee_in_cents += event_fee;
+                                entry.tax_in_cents += tax;
                                 entry.sales_total += sales_total;
                                 entry.refund_quantity += row.refunded_quantity;
                                 entry.refund_unit_price_in_cents += refund_ticket_face;
                                 entry.refund_client_fee_in_cents[column_idx].client_fee_in_cents += refund_client_fee;
                                 entry.refund_event_fee_in_cents += refund_event_fee;
+                                entry.refund_tax_in_cents += refund_tax;
                                 entry.refund_total += refund_total;
                                 entry.total += sales_total - refund_total;
                             }
@@ -1346,8 +1512,14 @@ impl Report {
                             let refund_ticket_face = row.unit_price_in_cents * row.refunded_quantity;
                             let refund_client_fee = row.client_fee_in_cents * row.refunded_quantity;
                             let refund_event_fee = row.event_fee_client_in_cents * row.refunded_quantity;
-                            let sales_total = ticket_face + client_fee + event_fee;
-                            let refund_total = refund_ticket_face + refund_client_fee + refund_event_fee;
+                            let tax = (row.tax_in_cents + row.included_tax_in_cents) * row.actual_quantity;
+                            let refund_tax = (row.tax_in_cents + row.included_tax_in_cents) * row.refunded_quantity;
+                            let sales_total =
+                                ticket_face + client_fee + event_fee + row.tax_in_cents * row.actual_quantity;
+                            let refund_total = refund_ticket_face
+                                + refund_client_fee
+                                + refund_event_fee
+                                + row.tax_in_cents * row.refunded_quantity;
 
                             let mut client_fee_in_cents = fee_schedule_range_columns.clone();
                             let mut refund_client_fee_in_cents = fee_schedule_range_columns.clone();
@@ -1362,11 +1534,13 @@ impl Report {
                                 unit_price_in_cents: ticket_face,
                                 client_fee_in_cents,
                                 event_fee_in_cents: event_fee,
+                                tax_in_cents: tax,
                                 sales_total,
                                 refund_quantity: row.refunded_quantity,
                                 refund_unit_price_in_cents: refund_ticket_face,
                                 refund_client_fee_in_cents,
                                 refund_event_fee_in_cents: refund_event_fee,
+                                refund_tax_in_cents: refund_tax,
                                 refund_total,
                                 total: sales_total - refund_total,
                             });
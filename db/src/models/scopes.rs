@@ -21,6 +21,7 @@ pub enum Scopes {
     CompRead,
     CompWrite,
     DashboardRead,
+    DisputeWrite,
     EventBroadcast,
     EventCancel,
     EventClone,
@@ -72,6 +73,8 @@ pub enum Scopes {
     SettlementRead,
     SettlementReadEarly,
     SettlementWrite,
+    TaxRuleRead,
+    TaxRuleWrite,
     TemporaryUserPromote,
     TransferCancel,
     TransferCancelAccepted,
@@ -118,6 +121,7 @@ impl fmt::Display for Scopes {
             Scopes::CompRead => "comp:read",
             Scopes::CompWrite => "comp:write",
             Scopes::DashboardRead => "dashboard:read",
+            Scopes::DisputeWrite => "dispute:write",
             Scopes::EventBroadcast => "event:broadcast",
             Scopes::EventCancel => "event:cancel",
             Scopes::EventClone => "event:clone",
@@ -169,6 +173,8 @@ impl fmt::Display for Scopes {
             Scopes::SettlementRead => "settlement:read",
             Scopes::SettlementReadEarly => "settlement:read-early",
             Scopes::SettlementWrite => "settlement:write",
+            Scopes::TaxRuleRead => "tax-rule:read",
+            Scopes::TaxRuleWrite => "tax-rule:write",
             Scopes::TicketAdmin => "ticket:admin",
             Scopes::TicketRead => "ticket:read",
             Scopes::TicketWrite => "ticket:write",
@@ -211,6 +217,7 @@ impl FromStr for Scopes {
             "comp:read" => Scopes::CompRead,
             "comp:write" => Scopes::CompWrite,
             "dashboard:read" => Scopes::DashboardRead,
+            "dispute:write" => Scopes::DisputeWrite,
             "event:broadcast" => Scopes::EventBroadcast,
             "event:cancel" => Scopes::EventCancel,
             "event:clone" => Scopes::EventClone,
@@ -262,6 +269,8 @@ impl FromStr for Scopes {
             "settlement:read" => Scopes::SettlementRead,
             "settlement:read-early" => Scopes::SettlementReadEarly,
             "settlement:write" => Scopes::SettlementWrite,
+            "tax-rule:read" => Scopes::TaxRuleRead,
+            "tax-rule:write" => Scopes::TaxRuleWrite,
             "temporary-user:promote" => Scopes::TemporaryUserPromote,
             "ticket:admin" => Scopes::TicketAdmin,
             "ticket:read" => Scopes::TicketRead,
@@ -469,6 +478,7 @@ fn get_scopes_for_role(role: Roles) -> Vec<Scopes> {
                 Scopes::AnnouncementDelete,
                 Scopes::AnnouncementRead,
                 Scopes::AnnouncementWrite,
+                Scopes::DisputeWrite,
                 Scopes::OrderRefundOverride,
                 Scopes::OrgAdmin,
                 Scopes::OrgFinancialReports,
@@ -482,6 +492,8 @@ fn get_scopes_for_role(role: Roles) -> Vec<Scopes> {
                 Scopes::SettlementAdjustmentWrite,
                 Scopes::SettlementReadEarly,
                 Scopes::SettlementWrite,
+                Scopes::TaxRuleRead,
+                Scopes::TaxRuleWrite,
                 Scopes::TransferCancelAccepted,
                 Scopes::UserDelete,
             ];
@@ -674,6 +686,7 @@ fn get_scopes_test() {
             "comp:read",
             "comp:write",
             "dashboard:read",
+            "dispute:write",
             "event:broadcast",
             "event:cancel",
             "event:clone",
@@ -725,6 +738,8 @@ fn get_scopes_test() {
             "settlement:read",
             "settlement:read-early",
             "settlement:write",
+            "tax-rule:read",
+            "tax-rule:write",
             "ticket-type:read",
             "ticket-type:write",
             "ticket:admin",
@@ -766,6 +781,7 @@ fn get_scopes_test() {
             "comp:read",
             "comp:write",
             "dashboard:read",
+            "dispute:write",
             "event:broadcast",
             "event:cancel",
             "event:clone",
@@ -817,6 +833,8 @@ fn get_scopes_test() {
             "settlement:read",
             "settlement:read-early",
             "settlement:write",
+            "tax-rule:read",
+            "tax-rule:write",
             "ticket-type:read",
             "ticket-type:write",
             "ticket:admin",
@@ -857,6 +875,7 @@ fn get_scopes_test() {
             "comp:read",
             "comp:write",
             "dashboard:read",
+            "dispute:write",
             "event:broadcast",
             "event:cancel",
             "event:clone",
@@ -908,6 +927,8 @@ fn get_scopes_test() {
             "settlement:read",
             "settlement:read-early",
             "settlement:write",
+            "tax-rule:read",
+            "tax-rule:write",
             "ticket:admin",
             "ticket:read",
             "ticket:transfer",
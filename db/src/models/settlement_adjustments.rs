@@ -7,6 +7,7 @@ use utils::errors::ConvertToDatabaseError;
 use utils::errors::DatabaseError;
 use utils::errors::ErrorCode;
 use uuid::Uuid;
+use validators::{self, *};
 
 #[derive(AsChangeset, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, QueryableByName, Serialize)]
 #[table_name = "settlement_adjustments"]
@@ -18,6 +19,7 @@ pub struct SettlementAdjustment {
     pub settlement_adjustment_type: SettlementAdjustmentTypes,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub currency: String,
 }
 
 impl SettlementAdjustment {
@@ -26,12 +28,14 @@ impl SettlementAdjustment {
         settlement_adjustment_type: SettlementAdjustmentTypes,
         note: Option<String>,
         amount_in_cents: i64,
+        currency: String,
     ) -> NewSettlementAdjustment {
         NewSettlementAdjustment {
             settlement_id,
             amount_in_cents,
             note,
             settlement_adjustment_type,
+            currency: currency.to_uppercase(),
         }
     }
 
@@ -56,9 +60,11 @@ pub struct NewSettlementAdjustment {
     pub amount_in_cents: i64,
     pub note: Option<String>,
     pub settlement_adjustment_type: SettlementAdjustmentTypes,
+    pub currency: String,
 }
 impl NewSettlementAdjustment {
     pub fn commit(&self, conn: &PgConnection) -> Result<SettlementAdjustment, DatabaseError> {
+        validators::append_validation_error(Ok(()), "currency", validate_currency(&self.currency))?;
         DatabaseError::wrap(
             ErrorCode::InsertError,
             "Could not create new settlement adjustment",
@@ -5,7 +5,7 @@ use diesel::prelude::*;
 use diesel::sql_types::{Nullable, Text};
 use itertools::Itertools;
 use models::*;
-use schema::{events, settlement_entries, ticket_types};
+use schema::{events, product_variants, products, settlement_entries, ticket_types};
 use utils::errors::ConvertToDatabaseError;
 use utils::errors::DatabaseError;
 use utils::errors::ErrorCode;
@@ -26,6 +26,9 @@ pub struct SettlementEntry {
     pub settlement_entry_type: SettlementEntryTypes,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub product_variant_id: Option<Uuid>,
+    pub tax_in_cents: i64,
+    pub currency: String,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Queryable, Serialize)]
@@ -43,6 +46,9 @@ pub struct DisplaySettlementEntry {
     pub settlement_entry_type: SettlementEntryTypes,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub product_variant_id: Option<Uuid>,
+    pub tax_in_cents: i64,
+    pub currency: String,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Queryable, Serialize)]
@@ -58,6 +64,10 @@ impl SettlementEntry {
     ) -> Result<Vec<EventGroupedSettlementEntry>, DatabaseError> {
         let entries: Vec<DisplaySettlementEntry> = settlement_entries::table
             .left_join(ticket_types::table.on(settlement_entries::ticket_type_id.eq(ticket_types::id.nullable())))
+            .left_join(
+                product_variants::table.on(settlement_entries::product_variant_id.eq(product_variants::id.nullable())),
+            )
+            .left_join(products::table.on(products::id.nullable().eq(product_variants::product_id.nullable())))
             .inner_join(events::table.on(events::id.eq(settlement_entries::event_id)))
             .filter(settlement_entries::settlement_id.eq(settlement.id))
             .select((
@@ -65,7 +75,9 @@ impl SettlementEntry {
                 settlement_entries::settlement_id,
                 settlement_entries::event_id,
                 settlement_entries::ticket_type_id,
-                sql::<Nullable<Text>>("ticket_types.name AS ticket_type_name"),
+                sql::<Nullable<Text>>(
+                    "COALESCE(ticket_types.name, products.name || ' - ' || product_variants.name) AS ticket_type_name",
+                ),
                 settlement_entries::face_value_in_cents,
                 settlement_entries::revenue_share_value_in_cents,
                 settlement_entries::online_sold_quantity,
@@ -74,11 +86,15 @@ impl SettlementEntry {
                 settlement_entries::settlement_entry_type,
                 settlement_entries::created_at,
                 settlement_entries::updated_at,
+                settlement_entries::product_variant_id,
+                settlement_entries::tax_in_cents,
+                settlement_entries::currency,
             ))
             .order_by(events::event_start)
             .then_order_by(settlement_entries::event_id)
             .then_order_by(settlement_entries::settlement_entry_type.nullable().desc())
             .then_order_by(ticket_types::rank)
+            .then_order_by(products::name)
             .then_order_by(settlement_entries::face_value_in_cents)
             .load(conn)
             .to_db_error(ErrorCode::QueryError, "Could not load Settlement Entries")?;
@@ -7,6 +7,7 @@ use diesel::sql_types::{Nullable, Timestamp, Uuid as dUuid};
 use diesel::{self, dsl};
 use models::*;
 use schema::{settlement_adjustments, settlements};
+use std::collections::BTreeMap;
 use utils::errors::ConvertToDatabaseError;
 use utils::errors::DatabaseError;
 use utils::errors::ErrorCode;
@@ -48,6 +49,16 @@ pub struct DisplaySettlement {
     pub settlement: Settlement,
     pub adjustments: Vec<SettlementAdjustment>,
     pub event_entries: Vec<EventGroupedSettlementEntry>,
+    pub currency_totals: Vec<SettlementCurrencyTotal>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SettlementCurrencyTotal {
+    pub currency: String,
+    pub total_sales_in_cents: i64,
+    pub tax_in_cents: i64,
+    /// Credits less deductions and chargebacks
+    pub adjustments_in_cents: i64,
 }
 
 impl NewSettlement {
@@ -185,6 +196,20 @@ impl Settlement {
             .to_db_error(ErrorCode::QueryError, "Could not load settlement")
     }
 
+    /// The most recent settlement for the organization that can still take adjustments
+    pub fn find_open_for_organization(
+        organization: &Organization,
+        conn: &PgConnection,
+    ) -> Result<Option<Settlement>, DatabaseError> {
+        settlements::table
+            .filter(settlements::organization_id.eq(organization.id))
+            .filter(settlements::status.eq(SettlementStatus::PendingSettlement))
+            .order_by(settlements::end_time.desc())
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load settlement")
+    }
+
     pub fn process_settlement_for_organization(
         organization: &Organization,
         settlement_period_in_days: Option<u32>,
@@ -220,6 +245,7 @@ impl Settlement {
             organization.settlement_type == SettlementTypes::PostEvent,
         )
         .commit(None, conn)?;
+        Dispute::apply_pending_chargebacks(&settlement, conn)?;
 
         Ok(settlement)
     }
@@ -237,10 +263,42 @@ impl Settlement {
             .get_results::<SettlementAdjustment>(conn)
             .to_db_error(ErrorCode::QueryError, "Could not load Settlement Adjustments")?;
 
+        let event_entries = SettlementEntry::find_for_settlement_by_event(self, conn)?;
+        let mut currency_totals: BTreeMap<String, SettlementCurrencyTotal> = BTreeMap::new();
+        for entry in event_entries.iter().flat_map(|e| e.entries.iter()) {
+            let total = currency_totals
+                .entry(entry.currency.clone())
+                .or_insert_with(|| SettlementCurrencyTotal {
+                    currency: entry.currency.clone(),
+                    total_sales_in_cents: 0,
+                    tax_in_cents: 0,
+                    adjustments_in_cents: 0,
+                });
+            total.total_sales_in_cents += entry.total_sales_in_cents;
+            total.tax_in_cents += entry.tax_in_cents;
+        }
+        for adjustment in &adjustments {
+            let total = currency_totals
+                .entry(adjustment.currency.clone())
+                .or_insert_with(|| SettlementCurrencyTotal {
+                    currency: adjustment.currency.clone(),
+                    total_sales_in_cents: 0,
+                    tax_in_cents: 0,
+                    adjustments_in_cents: 0,
+                });
+            total.adjustments_in_cents += match adjustment.settlement_adjustment_type {
+                SettlementAdjustmentTypes::ManualCredit => adjustment.amount_in_cents,
+                SettlementAdjustmentTypes::ManualDeduction | SettlementAdjustmentTypes::Chargeback => {
+                    -adjustment.amount_in_cents
+                }
+            };
+        }
+
         Ok(DisplaySettlement {
             settlement: self.clone(),
             adjustments,
-            event_entries: SettlementEntry::find_for_settlement_by_event(self, conn)?,
+            event_entries,
+            currency_totals: currency_totals.into_iter().map(|(_, total)| total).collect(),
         })
     }
 
@@ -0,0 +1,136 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl::sql;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Text};
+use models::*;
+use schema::store_credit_transactions;
+use utils::errors::*;
+use uuid::Uuid;
+use validators::{self, *};
+
+/// Ledger entry for a user's store credit with an organization. Credits are positive, for example
+/// refunds issued as store credit, and debits are negative when the credit pays for an order.
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(User)]
+#[table_name = "store_credit_transactions"]
+pub struct StoreCreditTransaction {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub organization_id: Uuid,
+    pub amount_in_cents: i64,
+    pub currency: String,
+    pub order_id: Option<Uuid>,
+    pub refund_id: Option<Uuid>,
+    pub note: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "store_credit_transactions"]
+pub struct NewStoreCreditTransaction {
+    pub user_id: Uuid,
+    pub organization_id: Uuid,
+    pub amount_in_cents: i64,
+    pub currency: String,
+    pub order_id: Option<Uuid>,
+    pub refund_id: Option<Uuid>,
+    pub note: Option<String>,
+    pub created_by: Option<Uuid>,
+}
+
+impl NewStoreCreditTransaction {
+    pub fn commit(&self, conn: &PgConnection) -> Result<StoreCreditTransaction, DatabaseError> {
+        if self.amount_in_cents == 0 {
+            return DatabaseError::validation_error("amount_in_cents", "Amount must not be zero");
+        }
+        validators::append_validation_error(Ok(()), "currency", validate_currency(&self.currency))?;
+        if self.amount_in_cents < 0 {
+            // Debits against the same balance are serialized so concurrent checkouts cannot overspend it
+            diesel::sql_query("SELECT pg_advisory_xact_lock(hashtext($1))")
+                .bind::<Text, _>(format!(
+                    "store_credit:{}:{}:{}",
+                    self.user_id, self.organization_id, self.currency
+                ))
+                .execute(conn)
+                .to_db_error(ErrorCode::QueryError, "Could not lock store credit balance")?;
+            let balance = StoreCreditTransaction::balance(self.user_id, self.organization_id, &self.currency, conn)?;
+            if balance + self.amount_in_cents < 0 {
+                return DatabaseError::business_process_error("Store credit balance is insufficient");
+            }
+        }
+
+        let result: StoreCreditTransaction = diesel::insert_into(store_credit_transactions::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create store credit transaction")?;
+
+        DomainEvent::create(
+            DomainEventTypes::StoreCreditTransactionCreated,
+            format!("Store credit changed by {}", self.amount_in_cents),
+            Tables::Users,
+            Some(self.user_id),
+            self.created_by,
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+}
+
+impl StoreCreditTransaction {
+    pub fn create(
+        user_id: Uuid,
+        organization_id: Uuid,
+        amount_in_cents: i64,
+        currency: String,
+        order_id: Option<Uuid>,
+        refund_id: Option<Uuid>,
+        note: Option<String>,
+        created_by: Option<Uuid>,
+    ) -> NewStoreCreditTransaction {
+        NewStoreCreditTransaction {
+            user_id,
+            organization_id,
+            amount_in_cents,
+            currency,
+            order_id,
+            refund_id,
+            note,
+            created_by,
+        }
+    }
+
+    pub fn balance(
+        user_id: Uuid,
+        organization_id: Uuid,
+        currency: &str,
+        conn: &PgConnection,
+    ) -> Result<i64, DatabaseError> {
+        store_credit_transactions::table
+            .filter(store_credit_transactions::user_id.eq(user_id))
+            .filter(store_credit_transactions::organization_id.eq(organization_id))
+            .filter(store_credit_transactions::currency.eq(currency))
+            .select(sql::<BigInt>(
+                "CAST(COALESCE(SUM(store_credit_transactions.amount_in_cents), 0) AS BIGINT)",
+            ))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not calculate store credit balance")
+    }
+
+    pub fn find_for_user(
+        user_id: Uuid,
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<StoreCreditTransaction>, DatabaseError> {
+        store_credit_transactions::table
+            .filter(store_credit_transactions::user_id.eq(user_id))
+            .filter(store_credit_transactions::organization_id.eq(organization_id))
+            .order_by(store_credit_transactions::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading store credit transactions")
+    }
+}
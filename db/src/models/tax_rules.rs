@@ -0,0 +1,180 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::expression::dsl::sql;
+use diesel::prelude::*;
+use diesel::sql_types::Text;
+use models::*;
+use schema::tax_rules;
+use utils::errors::*;
+use utils::pagination::*;
+use uuid::Uuid;
+
+/// A sales tax or VAT rate charged on sales for venues in a jurisdiction. Rules without a state
+/// apply to the whole country and are combined with any state specific rules.
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "tax_rules"]
+pub struct TaxRule {
+    pub id: Uuid,
+    pub name: String,
+    pub country: String,
+    pub state: Option<String>,
+    pub rate_percent: f32,
+    pub inclusive: bool,
+    pub applies_to_face_value: bool,
+    pub applies_to_fees: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(AsChangeset, Clone, Default, Deserialize, Serialize)]
+#[table_name = "tax_rules"]
+pub struct TaxRuleEditableAttributes {
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub country: Option<String>,
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub state: Option<Option<String>>,
+    pub rate_percent: Option<f32>,
+    pub inclusive: Option<bool>,
+    pub applies_to_face_value: Option<bool>,
+    pub applies_to_fees: Option<bool>,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "tax_rules"]
+pub struct NewTaxRule {
+    pub name: String,
+    pub country: String,
+    pub state: Option<String>,
+    pub rate_percent: f32,
+    pub inclusive: bool,
+    pub applies_to_face_value: bool,
+    pub applies_to_fees: bool,
+}
+
+impl NewTaxRule {
+    pub fn commit(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<TaxRule, DatabaseError> {
+        TaxRule::validate_rate_percent(self.rate_percent)?;
+
+        let result: TaxRule = diesel::insert_into(tax_rules::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create tax rule")?;
+
+        DomainEvent::create(
+            DomainEventTypes::TaxRuleCreated,
+            "Tax rule created".to_string(),
+            Tables::TaxRules,
+            Some(result.id),
+            current_user_id,
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+}
+
+impl TaxRule {
+    pub fn create(
+        name: String,
+        country: String,
+        state: Option<String>,
+        rate_percent: f32,
+        inclusive: bool,
+        applies_to_face_value: bool,
+        applies_to_fees: bool,
+    ) -> NewTaxRule {
+        NewTaxRule {
+            name,
+            country,
+            state,
+            rate_percent,
+            inclusive,
+            applies_to_face_value,
+            applies_to_fees,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<TaxRule, DatabaseError> {
+        tax_rules::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading tax rule")
+    }
+
+    pub fn all(page: u32, limit: u32, conn: &PgConnection) -> Result<Payload<TaxRule>, DatabaseError> {
+        let (tax_rules, record_count): (Vec<TaxRule>, i64) = tax_rules::table
+            .order_by(tax_rules::country)
+            .then_order_by(tax_rules::state)
+            .then_order_by(tax_rules::name)
+            .paginate(page as i64)
+            .per_page(limit as i64)
+            .load_and_count_pages(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load tax rules")?;
+
+        Ok(Payload::from_data(tax_rules, page, limit, Some(record_count as u64)))
+    }
+
+    /// Rules for the venue's country, both country wide and for the venue's state
+    pub fn find_for_venue(venue: &Venue, conn: &PgConnection) -> Result<Vec<TaxRule>, DatabaseError> {
+        tax_rules::table
+            .filter(sql("LOWER(country)=").bind::<Text, _>(venue.country.to_lowercase()))
+            .filter(
+                sql("(state IS NULL OR LOWER(state)=")
+                    .bind::<Text, _>(venue.state.to_lowercase())
+                    .sql(")"),
+            )
+            .order_by(tax_rules::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load tax rules for venue")
+    }
+
+    /// The tax on a single unit sold at the given amount. For inclusive rules the tax is the portion
+    /// of the amount that is tax rather than an amount charged on top.
+    pub fn tax_for_amount(&self, amount_in_cents: i64) -> i64 {
+        let rate = self.rate_percent as f64 / 100f64;
+        if self.inclusive {
+            (amount_in_cents as f64 - amount_in_cents as f64 / (1f64 + rate)).round() as i64
+        } else {
+            (amount_in_cents as f64 * rate).round() as i64
+        }
+    }
+
+    pub fn update(
+        &self,
+        attributes: TaxRuleEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<TaxRule, DatabaseError> {
+        if let Some(rate_percent) = attributes.rate_percent {
+            TaxRule::validate_rate_percent(rate_percent)?;
+        }
+
+        let result = diesel::update(self)
+            .set((&attributes, tax_rules::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update tax rule")?;
+
+        DomainEvent::create(
+            DomainEventTypes::TaxRuleUpdated,
+            "Tax rule updated".to_string(),
+            Tables::TaxRules,
+            Some(self.id),
+            current_user_id,
+            Some(json!(&attributes)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn validate_rate_percent(rate_percent: f32) -> Result<(), DatabaseError> {
+        if rate_percent < 0f32 || rate_percent > 100f32 {
+            return DatabaseError::validation_error("rate_percent", "Rate must be between 0 and 100 percent");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::prelude::*;
+use models::*;
+use schema::ticket_attendee_changes;
+use serde_json::Value;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Attendee details given for a ticket, logged each time they are set or changed along with any
+/// name change fee the ticket holder paid for the change and the order it was collected on
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(TicketInstance)]
+#[table_name = "ticket_attendee_changes"]
+pub struct TicketAttendeeChange {
+    pub id: Uuid,
+    pub ticket_instance_id: Uuid,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub email: Option<String>,
+    pub birth_date: Option<NaiveDate>,
+    pub changed_by_user_id: Uuid,
+    pub fee_in_cents: i64,
+    pub payment_provider: Option<PaymentProviders>,
+    pub external_payment_reference: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub order_id: Option<Uuid>,
+}
+
+#[derive(Clone, Debug, Insertable)]
+#[table_name = "ticket_attendee_changes"]
+pub struct NewTicketAttendeeChange {
+    pub ticket_instance_id: Uuid,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub email: Option<String>,
+    pub birth_date: Option<NaiveDate>,
+    pub changed_by_user_id: Uuid,
+    pub fee_in_cents: i64,
+    pub payment_provider: Option<PaymentProviders>,
+    pub external_payment_reference: Option<String>,
+    pub order_id: Option<Uuid>,
+}
+
+/// Charge made to the ticket holder for a name change fee
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct NameChangeFeePayment {
+    pub amount_in_cents: i64,
+    pub payment_provider: PaymentProviders,
+    pub external_reference: String,
+    pub provider_data: Value,
+}
+
+impl NewTicketAttendeeChange {
+    pub fn commit(self, conn: &PgConnection) -> Result<TicketAttendeeChange, DatabaseError> {
+        diesel::insert_into(ticket_attendee_changes::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create ticket attendee change")
+    }
+}
+
+impl TicketAttendeeChange {
+    pub fn create(
+        ticket: &TicketInstance,
+        changed_by_user_id: Uuid,
+        fee_payment: Option<NameChangeFeePayment>,
+        order_id: Option<Uuid>,
+    ) -> NewTicketAttendeeChange {
+        NewTicketAttendeeChange {
+            ticket_instance_id: ticket.id,
+            first_name: ticket.first_name_override.clone(),
+            last_name: ticket.last_name_override.clone(),
+            email: ticket.attendee_email.clone(),
+            birth_date: ticket.attendee_birth_date,
+            changed_by_user_id,
+            fee_in_cents: fee_payment.as_ref().map(|p| p.amount_in_cents).unwrap_or(0),
+            payment_provider: fee_payment.as_ref().map(|p| p.payment_provider),
+            external_payment_reference: fee_payment.map(|p| p.external_reference),
+            order_id,
+        }
+    }
+
+    pub fn find_for_ticket_instance(
+        ticket_instance_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<TicketAttendeeChange>, DatabaseError> {
+        ticket_attendee_changes::table
+            .filter(ticket_attendee_changes::ticket_instance_id.eq(ticket_instance_id))
+            .order_by(ticket_attendee_changes::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load ticket attendee changes")
+    }
+}
@@ -15,13 +15,20 @@ use rand::Rng;
 use schema::{
     assets, events, order_items, orders, organizations, ticket_instances, ticket_types, transfers, users, wallets,
 };
+use serde_with::rust::double_option;
 use std::cmp;
 use tari_client::*;
 use utils::errors::*;
+use utils::hash::pbkdf2;
+use utils::totp;
 use uuid::Uuid;
+use validator::validate_email;
 use validators::*;
 
 const TICKET_NUMBER_LENGTH: usize = 8;
+/// PBKDF2 iterations for offline scanning redeem key hashes, redeem keys are short enough that a
+/// fast hash could be brute forced from a leaked manifest
+pub const OFFLINE_REDEEM_KEY_HASH_ITERATIONS: u32 = 5_000;
 
 #[derive(Clone, Debug, Identifiable, PartialEq, Deserialize, Serialize, Queryable, QueryableByName)]
 #[table_name = "ticket_instances"]
@@ -44,8 +51,18 @@ pub struct TicketInstance {
     pub check_in_source: Option<CheckInSource>,
     parent_id: Option<Uuid>,
     pub listing_id: Option<Uuid>,
+    pub venue_seat_id: Option<Uuid>,
+    pub event_time_slot_id: Option<Uuid>,
+    /// Secret the ticket holder's app derives rotating redeem codes from
+    #[serde(skip_serializing)]
+    pub redeem_secret: Option<String>,
+    pub attendee_email: Option<String>,
+    pub attendee_birth_date: Option<NaiveDate>,
+    /// Event the seat is held for, a seat can only be held by one live ticket per event
+    pub seat_event_id: Option<Uuid>,
 }
 
+/// Attendee details for the ticket, the name overrides are the attendee's name
 #[derive(AsChangeset, Clone, Deserialize, Serialize)]
 #[table_name = "ticket_instances"]
 pub struct UpdateTicketInstanceAttributes {
@@ -53,6 +70,10 @@ pub struct UpdateTicketInstanceAttributes {
     pub first_name_override: Option<Option<String>>,
     #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
     pub last_name_override: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub attendee_email: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub attendee_birth_date: Option<Option<NaiveDate>>,
 }
 
 impl TicketInstance {
@@ -148,7 +169,8 @@ impl TicketInstance {
         conn: &PgConnection,
     ) -> Result<(), DatabaseError> {
         let query = include_str!("../queries/release_tickets.sql");
-        let new_status = if self.ticket_type(conn)?.status == TicketTypeStatus::Cancelled {
+        let ticket_type = self.ticket_type(conn)?;
+        let new_status = if ticket_type.status == TicketTypeStatus::Cancelled {
             TicketInstanceStatus::Nullified
         } else {
             TicketInstanceStatus::Available
@@ -169,6 +191,8 @@ impl TicketInstance {
 
         if new_status == TicketInstanceStatus::Nullified {
             tickets[0].create_nullified_domain_event(Some(user_id), conn)?;
+        } else {
+            WaitlistEntry::schedule_processing_if_waiting(ticket_type.id, conn)?;
         }
 
         Ok(())
@@ -241,6 +265,16 @@ impl TicketInstance {
                 transfers::transfer_address.nullable(),
                 ticket_instances::check_in_source,
                 ticket_types::promo_image_url,
+                sql::<Nullable<Text>>(
+                    "(SELECT CONCAT(vsec.name, ' Row ', vs.row_label, ' Seat ', vs.seat_number)
+                    FROM venue_seats vs
+                    JOIN venue_sections vsec ON vs.venue_section_id = vsec.id
+                    WHERE vs.id = ticket_instances.venue_seat_id)",
+                ),
+                sql::<Nullable<Text>>(
+                    "CASE WHEN (events.additional_json->>'rotating_redeem_codes')::boolean
+                    THEN ticket_instances.redeem_secret END",
+                ),
             ))
             .first::<DisplayTicketIntermediary>(conn)
             .to_db_error(ErrorCode::QueryError, "Unable to load ticket")?;
@@ -268,6 +302,70 @@ impl TicketInstance {
             .to_db_error(ErrorCode::QueryError, "Unable to load ticket")
     }
 
+    /// Tickets for the event's offline scanning manifest. Without `updated_since` only tickets that
+    /// can still be scanned are returned, otherwise every ticket that changed so that devices can
+    /// also drop tickets that have been refunded, nullified or transferred to a new key.
+    pub fn find_for_offline_scanning(
+        event_id: Uuid,
+        updated_since: Option<NaiveDateTime>,
+        conn: &PgConnection,
+    ) -> Result<Vec<OfflineScanningTicket>, DatabaseError> {
+        let mut query = ticket_instances::table
+            .inner_join(assets::table.on(ticket_instances::asset_id.eq(assets::id)))
+            .inner_join(order_items::table.on(ticket_instances::order_item_id.eq(order_items::id.nullable())))
+            .inner_join(ticket_types::table.on(assets::ticket_type_id.eq(ticket_types::id)))
+            .filter(ticket_types::event_id.eq(event_id))
+            .filter(ticket_instances::redeem_key.is_not_null())
+            .into_boxed();
+        query = match updated_since {
+            Some(updated_since) => query.filter(ticket_instances::updated_at.ge(updated_since)),
+            None => query.filter(
+                ticket_instances::status.eq_any(vec![TicketInstanceStatus::Purchased, TicketInstanceStatus::Redeemed]),
+            ),
+        };
+
+        let tickets: Vec<(
+            Uuid,
+            Option<String>,
+            Uuid,
+            TicketInstanceStatus,
+            Option<NaiveDateTime>,
+            NaiveDateTime,
+        )> = query
+            .select((
+                ticket_instances::id,
+                ticket_instances::redeem_key,
+                assets::ticket_type_id,
+                ticket_instances::status,
+                ticket_instances::redeemed_at,
+                ticket_instances::updated_at,
+            ))
+            .order_by(ticket_instances::updated_at)
+            .then_order_by(ticket_instances::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load tickets for offline scanning")?;
+
+        Ok(tickets
+            .into_iter()
+            .map(
+                |(id, redeem_key, ticket_type_id, status, redeemed_at, updated_at)| OfflineScanningTicket {
+                    id,
+                    redeem_key_hash: TicketInstance::offline_redeem_key_hash(event_id, &redeem_key.unwrap_or_default()),
+                    ticket_type_id,
+                    status,
+                    redeemed_at,
+                    updated_at,
+                },
+            )
+            .collect())
+    }
+
+    /// Hash door apps compare scanned redeem keys against when offline, PBKDF2-HMAC-SHA256 salted
+    /// with the event so that hashes can't be matched across events
+    pub fn offline_redeem_key_hash(event_id: Uuid, redeem_key: &str) -> String {
+        pbkdf2::derive(redeem_key, &event_id.to_string(), OFFLINE_REDEEM_KEY_HASH_ITERATIONS)
+    }
+
     pub fn find_for_processing(
         id: Uuid,
         event_id: Uuid,
@@ -359,6 +457,16 @@ impl TicketInstance {
                 transfers::transfer_address.nullable(),
                 ticket_instances::check_in_source,
                 ticket_types::promo_image_url,
+                sql::<Nullable<Text>>(
+                    "(SELECT CONCAT(vsec.name, ' Row ', vs.row_label, ' Seat ', vs.seat_number)
+                    FROM venue_seats vs
+                    JOIN venue_sections vsec ON vs.venue_section_id = vsec.id
+                    WHERE vs.id = ticket_instances.venue_seat_id)",
+                ),
+                sql::<Nullable<Text>>(
+                    "CASE WHEN (events.additional_json->>'rotating_redeem_codes')::boolean
+                    THEN ticket_instances.redeem_secret END",
+                ),
             ))
             .order_by(events::event_start.asc())
             .then_order_by(events::name.asc())
@@ -460,6 +568,33 @@ impl TicketInstance {
             Some("Expiration date was not set on cart prior to reserving tickets".to_string()),
         ))?;
 
+        // Inventory released while fans are waiting is held for their offers
+        if ticket_holding_id.is_none() {
+            let held_quantity = WaitlistEntry::held_quantity_for_order_item(order_item, ticket_type_id, conn)?;
+            if held_quantity > 0 {
+                let ticket_type = TicketType::find_for_update(ticket_type_id, conn)?;
+                if (ticket_type.valid_available_ticket_count(conn)? as i64) < quantity as i64 + held_quantity {
+                    jlog!(Debug, "Could not reserve tickets, remaining tickets are held for the waitlist", {"ticket_type_id": ticket_type_id, "quantity": quantity, "held_quantity": held_quantity});
+                    return DatabaseError::validation_error(
+                        "quantity",
+                        "Could not reserve tickets, not enough tickets are available",
+                    );
+                }
+            }
+        }
+
+        // Lock the shared pool so concurrent reservations across its ticket types cannot exceed it
+        if let Some(inventory_pool_id) = TicketType::find(ticket_type_id, conn)?.inventory_pool_id {
+            let inventory_pool = InventoryPool::find_for_update(inventory_pool_id, conn)?;
+            if inventory_pool.available_count(conn)? < quantity {
+                jlog!(Debug, "Could not reserve tickets, inventory pool capacity reached", {"inventory_pool_id": inventory_pool.id, "ticket_type_id": ticket_type_id, "quantity": quantity});
+                return DatabaseError::validation_error(
+                    "quantity",
+                    "Could not reserve tickets, not enough tickets are available",
+                );
+            }
+        }
+
         let query = include_str!("../queries/reserve_tickets.sql");
         let q = diesel::sql_query(query)
             .bind::<sql_types::Uuid, _>(order_item.id)
@@ -500,7 +635,11 @@ impl TicketInstance {
         Ok(tickets)
     }
 
-    fn validate_record(&self, update_attrs: &UpdateTicketInstanceAttributes) -> Result<(), DatabaseError> {
+    fn validate_record(
+        &self,
+        update_attrs: &UpdateTicketInstanceAttributes,
+        ticket_type: &TicketType,
+    ) -> Result<(), DatabaseError> {
         let mut validation_errors = Ok(());
         let first_name = update_attrs
             .first_name_override
@@ -530,9 +669,114 @@ impl TicketInstance {
                 )),
             );
         }
+
+        let email = update_attrs
+            .attendee_email
+            .clone()
+            .unwrap_or(self.attendee_email.clone());
+        let birth_date = update_attrs.attendee_birth_date.unwrap_or(self.attendee_birth_date);
+        if let Some(ref email) = email {
+            if !validate_email(email.as_str()) {
+                validation_errors = append_validation_error(
+                    validation_errors,
+                    "attendee_email",
+                    Err(create_validation_error("email", "Attendee email is invalid")),
+                );
+            }
+        }
+        if birth_date.map(|d| d >= Utc::now().naive_utc().date()).unwrap_or(false) {
+            validation_errors = append_validation_error(
+                validation_errors,
+                "attendee_birth_date",
+                Err(create_validation_error(
+                    "invalid",
+                    "Attendee birth date must be in the past",
+                )),
+            );
+        }
+
+        // Named tickets must be given every required detail, the other details are optional
+        let required = &ticket_type.required_attendee_details;
+        if required.contains(&AttendeeDetails::Name) && first_name.is_none() && last_name.is_none() {
+            validation_errors = append_validation_error(
+                validation_errors,
+                "first_name_override",
+                Err(create_validation_error(
+                    "required",
+                    "Attendee name is required for this ticket",
+                )),
+            );
+        }
+        if required.contains(&AttendeeDetails::Email) && email.is_none() {
+            validation_errors = append_validation_error(
+                validation_errors,
+                "attendee_email",
+                Err(create_validation_error(
+                    "required",
+                    "Attendee email is required for this ticket",
+                )),
+            );
+        }
+        if required.contains(&AttendeeDetails::BirthDate) && birth_date.is_none() {
+            validation_errors = append_validation_error(
+                validation_errors,
+                "attendee_birth_date",
+                Err(create_validation_error(
+                    "required",
+                    "Attendee birth date is required for this ticket",
+                )),
+            );
+        }
         Ok(validation_errors?)
     }
 
+    /// Fee due for the update, charged when changing the attendee name of a ticket that has already
+    /// been personalized. Transfers clear the name but the ticket stays personalized, only the first
+    /// name given for a ticket is free.
+    pub fn attendee_name_change_fee(
+        &self,
+        update_attrs: &UpdateTicketInstanceAttributes,
+        conn: &PgConnection,
+    ) -> Result<i64, DatabaseError> {
+        let first_name = update_attrs
+            .first_name_override
+            .clone()
+            .unwrap_or(self.first_name_override.clone());
+        let last_name = update_attrs
+            .last_name_override
+            .clone()
+            .unwrap_or(self.last_name_override.clone());
+        if first_name == self.first_name_override && last_name == self.last_name_override {
+            return Ok(0);
+        }
+        let personalized = self.first_name_override.is_some()
+            || self.last_name_override.is_some()
+            || TicketAttendeeChange::find_for_ticket_instance(self.id, conn)?
+                .iter()
+                .any(|change| change.first_name.is_some() || change.last_name.is_some());
+        if !personalized {
+            return Ok(0);
+        }
+        Ok(self.ticket_type(conn)?.attendee_name_change_fee_in_cents)
+    }
+
+    /// Whether the ticket type's lock window before the event has started, after which attendee
+    /// details can't be changed and named tickets can't be transferred
+    pub fn attendee_details_locked(
+        &self,
+        ticket_type: &TicketType,
+        conn: &PgConnection,
+    ) -> Result<bool, DatabaseError> {
+        let lock_hours = match ticket_type.attendee_details_lock_hours {
+            Some(lock_hours) => lock_hours,
+            None => return Ok(false),
+        };
+        Ok(match ticket_type.event(conn)?.event_start {
+            Some(event_start) => Utc::now().naive_utc() >= event_start - Duration::hours(lock_hours as i64),
+            None => false,
+        })
+    }
+
     pub fn release_tickets(
         order_item: &OrderItem,
         quantity: u32,
@@ -569,6 +813,8 @@ impl TicketInstance {
             for ticket in &tickets {
                 ticket.create_nullified_domain_event(user_id, conn)?;
             }
+        } else if let Some(ticket_type_id) = order_item.ticket_type_id {
+            WaitlistEntry::schedule_processing_if_waiting(ticket_type_id, conn)?;
         }
 
         Ok(tickets)
@@ -844,6 +1090,8 @@ impl TicketInstance {
             .commit(conn)?;
         }
 
+        WaitlistEntry::schedule_processing_if_waiting(ticket_type_id, conn)?;
+
         Ok(tickets)
     }
 
@@ -955,6 +1203,68 @@ impl TicketInstance {
         Ok(())
     }
 
+    pub fn assign_seat(
+        &self,
+        venue_seat_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<TicketInstance, DatabaseError> {
+        let seat_event_id = match venue_seat_id {
+            Some(_) => Some(self.ticket_type(conn)?.event_id),
+            None => None,
+        };
+        diesel::update(self)
+            .set((
+                ticket_instances::venue_seat_id.eq(venue_seat_id),
+                ticket_instances::seat_event_id.eq(seat_event_id),
+                ticket_instances::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not assign seat to ticket")
+    }
+
+    pub fn release_expired_seats(
+        event_id: Uuid,
+        venue_seat_ids: &[Uuid],
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        diesel::update(
+            ticket_instances::table
+                .filter(ticket_instances::seat_event_id.eq(event_id))
+                .filter(ticket_instances::venue_seat_id.eq_any(venue_seat_ids))
+                .filter(ticket_instances::status.eq(TicketInstanceStatus::Reserved))
+                .filter(ticket_instances::reserved_until.lt(dsl::now.nullable())),
+        )
+        .set((
+            ticket_instances::venue_seat_id.eq(None::<Uuid>),
+            ticket_instances::seat_event_id.eq(None::<Uuid>),
+            ticket_instances::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not release expired seat reservations")?;
+        Ok(())
+    }
+
+    pub fn assign_time_slot(
+        &self,
+        event_time_slot_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<TicketInstance, DatabaseError> {
+        diesel::update(self)
+            .set((
+                ticket_instances::event_time_slot_id.eq(event_time_slot_id),
+                ticket_instances::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not assign time slot to ticket")
+    }
+
+    pub fn time_slot(&self, conn: &PgConnection) -> Result<Option<EventTimeSlot>, DatabaseError> {
+        match self.event_time_slot_id {
+            Some(event_time_slot_id) => Ok(Some(EventTimeSlot::find(event_time_slot_id, conn)?)),
+            None => Ok(None),
+        }
+    }
+
     // Note: Transfer mechanism should be used in most cases over this method
     pub fn set_wallet(&self, wallet: &Wallet, conn: &PgConnection) -> Result<(), DatabaseError> {
         diesel::update(ticket_instances::table.filter(ticket_instances::id.eq(self.id)))
@@ -1015,18 +1325,53 @@ impl TicketInstance {
             key = generate_redeem_key(9);
         }
 
+        // The secret is replaced along with the key so previous holders can't generate valid codes
         diesel::update(self)
-            .set(ticket_instances::redeem_key.eq(key.clone()))
+            .set((
+                ticket_instances::redeem_key.eq(key.clone()),
+                ticket_instances::redeem_secret.eq(totp::generate_secret()?),
+                ticket_instances::updated_at.eq(dsl::now),
+            ))
             .execute(conn)
             .to_db_error(ErrorCode::InternalError, "Could not write redeem key")?;
 
         Ok(key)
     }
 
+    /// Checks a rotating redeem code shown by the ticket holder's app, for events using rotating codes
+    pub fn verify_redeem_code(&self, code: Option<&str>, at: NaiveDateTime) -> Result<bool, DatabaseError> {
+        match (self.redeem_secret.as_ref(), code) {
+            (Some(secret), Some(code)) => totp::verify(secret, code, at),
+            _ => Ok(false),
+        }
+    }
+
     pub fn has_pending_transfer(&self, conn: &PgConnection) -> Result<bool, DatabaseError> {
         Ok(TransferTicket::pending_transfer(self.id, conn)?.is_some())
     }
 
+    /// Whether the ticket was refunded for its current order item. Redeemed tickets keep their
+    /// status when refunded, unlike purchased tickets which are released.
+    pub fn is_refunded(&self, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        Ok(RefundedTicket::find_by_ticket_instance_ids(vec![self.id], conn)?
+            .iter()
+            .any(|r| Some(r.order_item_id) == self.order_item_id && r.ticket_refunded_at.is_some()))
+    }
+
+    /// The result to give instead of scanning the ticket in at the gate or into an access zone,
+    /// if it has a transfer in process, a balance outstanding or was refunded
+    fn scan_blocked(&self, conn: &PgConnection) -> Result<Option<RedeemResults>, DatabaseError> {
+        Ok(if self.has_pending_transfer(conn)? {
+            Some(RedeemResults::TicketTransferInProcess)
+        } else if InstallmentPlan::has_outstanding_balance(&[self.id], conn)? {
+            Some(RedeemResults::TicketPaymentOutstanding)
+        } else if self.is_refunded(conn)? {
+            Some(RedeemResults::TicketInvalid)
+        } else {
+            None
+        })
+    }
+
     pub fn redeem_ticket(
         ticket_id: Uuid,
         redeem_key: String,
@@ -1034,21 +1379,53 @@ impl TicketInstance {
         check_in_source: CheckInSource,
         conn: &PgConnection,
     ) -> Result<RedeemResults, DatabaseError> {
+        TicketInstance::redeem_ticket_at(
+            ticket_id,
+            redeem_key,
+            user_id,
+            check_in_source,
+            Utc::now().naive_utc(),
+            false,
+            conn,
+        )
+    }
+
+    /// Redeems the ticket recording `redeemed_at` as the time of entry, used for scans made offline
+    /// that are only synced later. Tickets for timed entry events are only redeemed within their
+    /// time slot unless `ignore_time_slot` is set.
+    pub fn redeem_ticket_at(
+        ticket_id: Uuid,
+        redeem_key: String,
+        user_id: Uuid,
+        check_in_source: CheckInSource,
+        redeemed_at: NaiveDateTime,
+        ignore_time_slot: bool,
+        conn: &PgConnection,
+    ) -> Result<RedeemResults, DatabaseError> {
+        // Locked so that concurrent scans of the same ticket, in or out, are applied one at a time
         let ticket: TicketInstance = ticket_instances::table
             .find(ticket_id)
+            .for_update()
             .first(conn)
             .to_db_error(ErrorCode::QueryError, "Unable to load ticket")?;
-        if ticket.has_pending_transfer(conn)? {
-            return Ok(RedeemResults::TicketTransferInProcess);
+        if let Some(result) = ticket.scan_blocked(conn)? {
+            return Ok(result);
         } else if ticket.status == TicketInstanceStatus::Purchased
             && ticket.redeem_key.is_some()
             && ticket.redeem_key.clone().unwrap() == redeem_key
         {
+            if !ignore_time_slot {
+                if let Some(time_slot) = ticket.time_slot(conn)? {
+                    if !time_slot.is_redeemable_at(redeemed_at) {
+                        return Ok(RedeemResults::TicketOutsideTimeSlot);
+                    }
+                }
+            }
             diesel::update(ticket_instances::table.filter(ticket_instances::id.eq(ticket_id)))
                 .set((
                     ticket_instances::status.eq(TicketInstanceStatus::Redeemed),
                     ticket_instances::redeemed_by_user_id.eq(user_id),
-                    ticket_instances::redeemed_at.eq(dsl::now),
+                    ticket_instances::redeemed_at.eq(redeemed_at),
                     ticket_instances::check_in_source.eq(check_in_source),
                     ticket_instances::updated_at.eq(dsl::now),
                 ))
@@ -1064,6 +1441,28 @@ impl TicketInstance {
                 None,
             )
             .commit(conn)?;
+            ticket.record_scan(
+                TicketScanDirection::In,
+                Some(check_in_source),
+                user_id,
+                redeemed_at,
+                conn,
+            )?;
+        } else if ticket.status == TicketInstanceStatus::Redeemed
+            && ticket.redeem_key.as_ref() == Some(&redeem_key)
+            && !ticket.is_inside(conn)?
+        {
+            if TicketScan::entry_count(ticket.id, conn)? >= ticket.ticket_type(conn)?.max_entries as i64 {
+                return Ok(RedeemResults::TicketMaxEntriesReached);
+            }
+            ticket.record_scan(
+                TicketScanDirection::In,
+                Some(check_in_source),
+                user_id,
+                redeemed_at,
+                conn,
+            )?;
+            return Ok(RedeemResults::TicketReentrySuccess);
         } else if ticket.status == TicketInstanceStatus::Redeemed {
             return Ok(RedeemResults::TicketAlreadyRedeemed);
         } else {
@@ -1072,6 +1471,114 @@ impl TicketInstance {
         Ok(RedeemResults::TicketRedeemSuccess)
     }
 
+    /// Scans a redeemed ticket out of the event so that it can be scanned back in later, if its
+    /// ticket type allows re-entry
+    pub fn scan_out(
+        ticket_id: Uuid,
+        redeem_key: String,
+        user_id: Uuid,
+        scanned_at: NaiveDateTime,
+        conn: &PgConnection,
+    ) -> Result<RedeemResults, DatabaseError> {
+        let ticket: TicketInstance = ticket_instances::table
+            .find(ticket_id)
+            .for_update()
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load ticket")?;
+        if ticket.redeem_key.as_ref() != Some(&redeem_key) {
+            return Ok(RedeemResults::TicketInvalid);
+        } else if ticket.status != TicketInstanceStatus::Redeemed || !ticket.is_inside(conn)? {
+            return Ok(RedeemResults::TicketNotCheckedIn);
+        }
+        ticket.record_scan(TicketScanDirection::Out, None, user_id, scanned_at, conn)?;
+        Ok(RedeemResults::TicketExitSuccess)
+    }
+
+    /// Whether the holder of this redeemed ticket is currently inside, i.e. was last scanned in
+    pub fn is_inside(&self, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        Ok(TicketScan::find_latest_for_ticket_instance(self.id, None, conn)?
+            .map(|scan| scan.direction == TicketScanDirection::In)
+            .unwrap_or(self.status == TicketInstanceStatus::Redeemed))
+    }
+
+    /// Scans a ticket into or out of an access zone. The ticket must already have been redeemed at
+    /// the gate, the holder must be inside to enter the zone and its ticket type must grant access
+    /// to the zone. Zone scans are logged separately
+    /// from gate scans so they do not count towards the ticket's entries.
+    pub fn scan_zone(
+        ticket_id: Uuid,
+        redeem_key: String,
+        access_zone: &AccessZone,
+        user_id: Uuid,
+        direction: TicketScanDirection,
+        scanned_at: NaiveDateTime,
+        conn: &PgConnection,
+    ) -> Result<RedeemResults, DatabaseError> {
+        let ticket: TicketInstance = ticket_instances::table
+            .find(ticket_id)
+            .for_update()
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load ticket")?;
+        let ticket_type = ticket.ticket_type(conn)?;
+        if ticket.redeem_key.as_ref() != Some(&redeem_key) || ticket_type.event_id != access_zone.event_id {
+            return Ok(RedeemResults::TicketInvalid);
+        } else if !access_zone.grants_ticket_type(ticket_type.id, conn)? {
+            return Ok(RedeemResults::TicketZoneAccessDenied);
+        } else if ticket.status != TicketInstanceStatus::Redeemed {
+            return Ok(RedeemResults::TicketNotCheckedIn);
+        } else if direction == TicketScanDirection::In {
+            if let Some(result) = ticket.scan_blocked(conn)? {
+                return Ok(result);
+            } else if !ticket.is_inside(conn)? {
+                return Ok(RedeemResults::TicketNotCheckedIn);
+            }
+        }
+
+        let inside_zone = TicketScan::find_latest_for_ticket_instance(ticket.id, Some(access_zone.id), conn)?
+            .map(|scan| scan.direction == TicketScanDirection::In)
+            .unwrap_or(false);
+        match direction {
+            TicketScanDirection::In if inside_zone => Ok(RedeemResults::TicketAlreadyRedeemed),
+            TicketScanDirection::Out if !inside_zone => Ok(RedeemResults::TicketNotCheckedIn),
+            _ => {
+                TicketScan::create(
+                    ticket.id,
+                    access_zone.event_id,
+                    direction,
+                    Some(CheckInSource::Scanned),
+                    Some(user_id),
+                    scanned_at,
+                    Some(access_zone.id),
+                )
+                .commit(conn)?;
+                Ok(match direction {
+                    TicketScanDirection::In => RedeemResults::TicketZoneEntrySuccess,
+                    TicketScanDirection::Out => RedeemResults::TicketExitSuccess,
+                })
+            }
+        }
+    }
+
+    fn record_scan(
+        &self,
+        direction: TicketScanDirection,
+        check_in_source: Option<CheckInSource>,
+        user_id: Uuid,
+        scanned_at: NaiveDateTime,
+        conn: &PgConnection,
+    ) -> Result<TicketScan, DatabaseError> {
+        TicketScan::create(
+            self.id,
+            self.ticket_type(conn)?.event_id,
+            direction,
+            check_in_source,
+            Some(user_id),
+            scanned_at,
+            None,
+        )
+        .commit(conn)
+    }
+
     pub fn show_redeemable_ticket(ticket_id: Uuid, conn: &PgConnection) -> Result<RedeemableTicket, DatabaseError> {
         let tickets_and_counts = Event::guest_list_tickets(None, Some(ticket_id), None, &None, None, conn)?;
 
@@ -1154,6 +1661,10 @@ impl TicketInstance {
             return DatabaseError::business_process_error("Redeemed tickets cannot be transferred");
         } else if !all_tickets_valid || tickets.len() == 0 {
             return DatabaseError::business_process_error("User does not own all requested tickets");
+        } else if InstallmentPlan::has_outstanding_balance(ticket_ids, conn)? {
+            return DatabaseError::business_process_error(
+                "Tickets on an installment plan cannot be transferred until fully paid",
+            );
         }
 
         Ok((wallet_id, ticket_ids_and_updated_at))
@@ -1171,6 +1682,16 @@ impl TicketInstance {
         let (wallet_id, ticket_ids_and_updated_at) =
             TicketInstance::verify_tickets_belong_to_user(user.id, ticket_ids, conn)?;
 
+        //Named tickets can't change hands once their attendee details are locked
+        for ticket in TicketInstance::find_by_ids(ticket_ids, conn)? {
+            let ticket_type = ticket.ticket_type(conn)?;
+            if ticket_type.requires_attendee_details() && ticket.attendee_details_locked(&ticket_type, conn)? {
+                return DatabaseError::business_process_error(
+                    "Named tickets can no longer be transferred for this event.",
+                );
+            }
+        }
+
         //Generate transfer_key and store keys and set transfer_expiry date
         let transfer_key = Uuid::new_v4();
 
@@ -1223,6 +1744,19 @@ impl TicketInstance {
         attrs: UpdateTicketInstanceAttributes,
         current_user_id: Uuid,
         conn: &PgConnection,
+    ) -> Result<TicketInstance, DatabaseError> {
+        self.update_with_name_change_fee(attrs, current_user_id, None, conn)
+    }
+
+    /// Updates the attendee details, recording the name change fee the ticket holder paid for the
+    /// change if any on a paid order for the event. Charging the fee is left to the caller so that
+    /// staff can waive it.
+    pub fn update_with_name_change_fee(
+        self,
+        attrs: UpdateTicketInstanceAttributes,
+        current_user_id: Uuid,
+        fee_payment: Option<NameChangeFeePayment>,
+        conn: &PgConnection,
     ) -> Result<TicketInstance, DatabaseError> {
         if self.status == TicketInstanceStatus::Redeemed {
             return DatabaseError::business_process_error("Unable to update ticket as it has already been redeemed.");
@@ -1230,7 +1764,11 @@ impl TicketInstance {
             return DatabaseError::business_process_error("Unable to update ticket as it is not purchased.");
         }
 
-        self.validate_record(&attrs)?;
+        let ticket_type = self.ticket_type(conn)?;
+        if self.attendee_details_locked(&ticket_type, conn)? {
+            return DatabaseError::business_process_error("Attendee details can no longer be changed for this event.");
+        }
+        self.validate_record(&attrs, &ticket_type)?;
 
         DomainEvent::create(
             DomainEventTypes::TicketInstanceUpdated,
@@ -1242,10 +1780,19 @@ impl TicketInstance {
         )
         .commit(conn)?;
 
-        diesel::update(&self)
+        let ticket: TicketInstance = diesel::update(&self)
             .set((attrs, ticket_instances::updated_at.eq(dsl::now)))
             .get_result(conn)
-            .to_db_error(ErrorCode::UpdateError, "Could not update ticket instance")
+            .to_db_error(ErrorCode::UpdateError, "Could not update ticket instance")?;
+        let order_id = match fee_payment {
+            Some(ref fee_payment) => {
+                Some(Order::create_for_name_change_fee(&ticket, fee_payment, current_user_id, conn)?.id)
+            }
+            None => None,
+        };
+        TicketAttendeeChange::create(&ticket, current_user_id, fee_payment, order_id).commit(conn)?;
+
+        Ok(ticket)
     }
 
     pub fn receive_ticket_transfer(
@@ -1321,6 +1868,8 @@ impl TicketInstance {
                 ticket_instances::updated_at.eq(dsl::now),
                 ticket_instances::first_name_override.eq(&name_override),
                 ticket_instances::last_name_override.eq(&name_override),
+                ticket_instances::attendee_email.eq(&name_override),
+                ticket_instances::attendee_birth_date.eq(None::<NaiveDate>),
             ))
             .execute(conn)
             .to_db_error(ErrorCode::UpdateError, "Could not update ticket instance")?;
@@ -1375,6 +1924,39 @@ impl TicketInstance {
         }
         Ok(updated_ticket_instances)
     }
+
+    /// Nullifies the purchased (but not yet redeemed) tickets of an order. Unlike `nullify_tickets`,
+    /// which only removes unsold inventory, this voids tickets already held by the purchaser.
+    pub fn nullify_for_order(
+        order_id: Uuid,
+        user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<TicketInstance>, DatabaseError> {
+        let ticket_ids: Vec<Uuid> = ticket_instances::table
+            .inner_join(order_items::table.on(ticket_instances::order_item_id.eq(order_items::id.nullable())))
+            .filter(order_items::order_id.eq(order_id))
+            .filter(ticket_instances::status.eq(TicketInstanceStatus::Purchased))
+            .select(ticket_instances::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load tickets for order")?;
+
+        let updated_ticket_instances: Vec<TicketInstance> = diesel::update(
+            ticket_instances::table
+                .filter(ticket_instances::id.eq_any(ticket_ids))
+                .filter(ticket_instances::status.eq(TicketInstanceStatus::Purchased)),
+        )
+        .set((
+            ticket_instances::status.eq(TicketInstanceStatus::Nullified),
+            ticket_instances::updated_at.eq(dsl::now),
+        ))
+        .get_results(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not nullify tickets")?;
+
+        for ticket_instance in &updated_ticket_instances {
+            ticket_instance.create_nullified_domain_event(user_id, conn)?;
+        }
+        Ok(updated_ticket_instances)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -1402,6 +1984,9 @@ pub struct DisplayTicket {
     pub transfer_address: Option<String>,
     pub check_in_source: Option<CheckInSource>,
     pub promo_image_url: Option<String>,
+    pub seat_label: Option<String>,
+    /// Only for events using rotating redeem codes, the app shows codes derived from it instead of the redeem key
+    pub redeem_secret: Option<String>,
 }
 
 #[derive(Queryable, QueryableByName)]
@@ -1446,6 +2031,10 @@ pub struct DisplayTicketIntermediary {
     pub check_in_source: Option<CheckInSource>,
     #[sql_type = "Nullable<Text>"]
     pub promo_image_url: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub seat_label: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub redeem_secret: Option<String>,
 }
 
 impl From<DisplayTicketIntermediary> for DisplayTicket {
@@ -1467,10 +2056,13 @@ impl From<DisplayTicketIntermediary> for DisplayTicket {
             .or(day_before_event_start)
             .unwrap();
 
-        let redeem_key = if Utc::now().naive_utc() > redemption_allowed_after_date {
-            ticket_intermediary.redeem_key.clone()
+        let (redeem_key, redeem_secret) = if Utc::now().naive_utc() > redemption_allowed_after_date {
+            (
+                ticket_intermediary.redeem_key.clone(),
+                ticket_intermediary.redeem_secret.clone(),
+            )
         } else {
-            None
+            (None, None)
         };
 
         DisplayTicket {
@@ -1489,6 +2081,8 @@ impl From<DisplayTicketIntermediary> for DisplayTicket {
             transfer_address: ticket_intermediary.transfer_address,
             check_in_source: ticket_intermediary.check_in_source,
             promo_image_url: ticket_intermediary.promo_image_url,
+            seat_label: ticket_intermediary.seat_label,
+            redeem_secret,
         }
     }
 }
@@ -1542,12 +2136,34 @@ struct NewTicketInstance {
     wallet_id: Uuid,
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct OfflineScanningTicket {
+    pub id: Uuid,
+    pub redeem_key_hash: String,
+    pub ticket_type_id: Uuid,
+    pub status: TicketInstanceStatus,
+    pub redeemed_at: Option<NaiveDateTime>,
+    pub updated_at: NaiveDateTime,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum RedeemResults {
     TicketRedeemSuccess,
+    /// A redeemed ticket that had been scanned out was scanned back in
+    TicketReentrySuccess,
+    TicketExitSuccess,
     TicketAlreadyRedeemed,
+    TicketMaxEntriesReached,
+    TicketNotCheckedIn,
+    /// Scanned into an access zone after being redeemed at the gate
+    TicketZoneEntrySuccess,
+    /// The ticket's type does not grant access to the scanner's access zone
+    TicketZoneAccessDenied,
     TicketInvalid,
     TicketTransferInProcess,
+    TicketPaymentOutstanding,
+    /// Scanned outside of the entry time slot the ticket was sold for
+    TicketOutsideTimeSlot,
 }
 
 fn generate_redeem_key(len: u32) -> String {
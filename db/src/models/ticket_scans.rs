@@ -0,0 +1,150 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl::count_star;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Nullable, Uuid as dUuid};
+use models::*;
+use schema::ticket_scans;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// A ticket being scanned into or out of an event. Redeeming a ticket records its first entry,
+/// tickets that allow re-entry record each time they are scanned out and back in. Scans with an
+/// `access_zone_id` were made at that zone rather than at the gate.
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+pub struct TicketScan {
+    pub id: Uuid,
+    pub ticket_instance_id: Uuid,
+    pub event_id: Uuid,
+    pub direction: TicketScanDirection,
+    pub check_in_source: Option<CheckInSource>,
+    pub scanned_by_user_id: Option<Uuid>,
+    pub scanned_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub access_zone_id: Option<Uuid>,
+}
+
+#[derive(QueryableByName)]
+struct OccupancyResult {
+    #[sql_type = "BigInt"]
+    occupancy: i64,
+}
+
+impl TicketScan {
+    pub fn create(
+        ticket_instance_id: Uuid,
+        event_id: Uuid,
+        direction: TicketScanDirection,
+        check_in_source: Option<CheckInSource>,
+        scanned_by_user_id: Option<Uuid>,
+        scanned_at: NaiveDateTime,
+        access_zone_id: Option<Uuid>,
+    ) -> NewTicketScan {
+        NewTicketScan {
+            ticket_instance_id,
+            event_id,
+            direction,
+            check_in_source,
+            scanned_by_user_id,
+            scanned_at,
+            access_zone_id,
+        }
+    }
+
+    pub fn find_by_ticket_instance_id(
+        ticket_instance_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<TicketScan>, DatabaseError> {
+        ticket_scans::table
+            .filter(ticket_scans::ticket_instance_id.eq(ticket_instance_id))
+            .order_by(ticket_scans::scanned_at)
+            .then_order_by(ticket_scans::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load ticket scans")
+    }
+
+    /// Latest scan of the ticket at the given zone, or at the gate when no zone is given
+    pub fn find_latest_for_ticket_instance(
+        ticket_instance_id: Uuid,
+        access_zone_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Option<TicketScan>, DatabaseError> {
+        let mut query = ticket_scans::table
+            .filter(ticket_scans::ticket_instance_id.eq(ticket_instance_id))
+            .into_boxed();
+        query = match access_zone_id {
+            Some(access_zone_id) => query.filter(ticket_scans::access_zone_id.eq(access_zone_id)),
+            None => query.filter(ticket_scans::access_zone_id.is_null()),
+        };
+        query
+            .order_by(ticket_scans::scanned_at.desc())
+            .then_order_by(ticket_scans::created_at.desc())
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load latest ticket scan")
+    }
+
+    pub fn entry_count(ticket_instance_id: Uuid, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        ticket_scans::table
+            .filter(ticket_scans::ticket_instance_id.eq(ticket_instance_id))
+            .filter(ticket_scans::direction.eq(TicketScanDirection::In))
+            .filter(ticket_scans::access_zone_id.is_null())
+            .select(count_star())
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not count ticket entries")
+    }
+
+    /// Number of tickets whose most recent gate scan for the event was an entry
+    pub fn current_occupancy(event_id: Uuid, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        TicketScan::occupancy(event_id, None, conn)
+    }
+
+    /// Number of tickets whose most recent scan at the zone was an entry
+    pub fn zone_occupancy(access_zone: &AccessZone, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        TicketScan::occupancy(access_zone.event_id, Some(access_zone.id), conn)
+    }
+
+    fn occupancy(event_id: Uuid, access_zone_id: Option<Uuid>, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        let result: OccupancyResult = diesel::sql_query(
+            r#"
+            SELECT CAST(COUNT(*) AS BIGINT) AS occupancy
+            FROM (
+                SELECT DISTINCT ON (ticket_instance_id) direction
+                FROM ticket_scans
+                WHERE event_id = $1
+                AND access_zone_id IS NOT DISTINCT FROM $2
+                ORDER BY ticket_instance_id, scanned_at DESC, created_at DESC
+            ) latest_scans
+            WHERE direction = 'In';
+            "#,
+        )
+        .bind::<dUuid, _>(event_id)
+        .bind::<Nullable<dUuid>, _>(access_zone_id)
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not calculate event occupancy")?;
+
+        Ok(result.occupancy)
+    }
+}
+
+#[derive(Clone, Insertable)]
+#[table_name = "ticket_scans"]
+pub struct NewTicketScan {
+    pub ticket_instance_id: Uuid,
+    pub event_id: Uuid,
+    pub direction: TicketScanDirection,
+    pub check_in_source: Option<CheckInSource>,
+    pub scanned_by_user_id: Option<Uuid>,
+    pub scanned_at: NaiveDateTime,
+    pub access_zone_id: Option<Uuid>,
+}
+
+impl NewTicketScan {
+    pub fn commit(self, conn: &PgConnection) -> Result<TicketScan, DatabaseError> {
+        diesel::insert_into(ticket_scans::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create ticket scan")
+    }
+}
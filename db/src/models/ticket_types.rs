@@ -48,6 +48,17 @@ pub struct TicketType {
     pub ticket_type_type: TicketTypeType,
     pub promo_image_url: Option<String>,
     pub content_url: Option<String>,
+    pub venue_section_id: Option<Uuid>,
+    pub inventory_pool_id: Option<Uuid>,
+    /// Times a ticket can be scanned in, tickets allowing more than one can be scanned out and back in
+    pub max_entries: i32,
+    /// Details ticket holders must provide about the attendee, tickets requiring any are named
+    /// tickets and scanners should check the attendee's ID
+    pub required_attendee_details: Vec<AttendeeDetails>,
+    /// Charged for changing the attendee name once a ticket has been personalized
+    pub attendee_name_change_fee_in_cents: i64,
+    /// Attendee details can't be changed within this many hours of the event starting
+    pub attendee_details_lock_hours: Option<i32>,
 }
 
 impl PartialOrd for TicketType {
@@ -79,11 +90,24 @@ pub struct TicketTypeEditableAttributes {
     pub box_office_sales_enabled: Option<bool>,
     pub app_sales_enabled: Option<bool>,
     pub rank: Option<i32>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub venue_section_id: Option<Option<Uuid>>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub inventory_pool_id: Option<Option<Uuid>>,
+    pub max_entries: Option<i32>,
+    pub required_attendee_details: Option<Vec<AttendeeDetails>>,
+    pub attendee_name_change_fee_in_cents: Option<i64>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub attendee_details_lock_hours: Option<Option<i32>>,
 }
 
 impl TicketType {
     // Properties at the top
 
+    pub fn requires_attendee_details(&self) -> bool {
+        !self.required_attendee_details.is_empty()
+    }
+
     pub fn event(&self, conn: &PgConnection) -> Result<Event, DatabaseError> {
         let res: Event = ticket_types::table
             .inner_join(events::table)
@@ -96,6 +120,13 @@ impl TicketType {
         Ok(res)
     }
 
+    pub fn inventory_pool(&self, conn: &PgConnection) -> Result<Option<InventoryPool>, DatabaseError> {
+        match self.inventory_pool_id {
+            Some(inventory_pool_id) => Ok(Some(InventoryPool::find(inventory_pool_id, conn)?)),
+            None => Ok(None),
+        }
+    }
+
     pub fn fee_schedule(&self, conn: &PgConnection) -> Result<FeeSchedule, DatabaseError> {
         ticket_types::table
             .inner_join(events::table.inner_join(organizations::table.inner_join(fee_schedules::table)))
@@ -365,11 +396,79 @@ impl TicketType {
             .to_db_error(ErrorCode::QueryError, "Could not find ticket type")
     }
 
+    /// Loads the ticket type with a row lock to serialize reservations against it
+    pub fn find_for_update(id: Uuid, conn: &PgConnection) -> Result<TicketType, DatabaseError> {
+        ticket_types::table
+            .filter(ticket_types::id.eq(id))
+            .for_update()
+            .get_result(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find ticket type")
+    }
+
     pub fn validate_record(
         &self,
         attributes: &mut TicketTypeEditableAttributes,
         conn: &PgConnection,
     ) -> Result<(), DatabaseError> {
+        if let Some(Some(venue_section_id)) = attributes.venue_section_id {
+            let venue_section = VenueSection::find(venue_section_id, conn)?;
+            if Some(venue_section.venue_id) != self.event(conn)?.venue_id {
+                return Ok(validators::simple_error(
+                    "venue_section_id",
+                    "Venue section must belong to the venue of the event",
+                )?);
+            }
+        }
+
+        if let Some(Some(inventory_pool_id)) = attributes.inventory_pool_id {
+            if InventoryPool::find(inventory_pool_id, conn)?.event_id != self.event_id {
+                return Ok(validators::simple_error(
+                    "inventory_pool_id",
+                    "Inventory pool must belong to the event of the ticket type",
+                )?);
+            }
+        }
+
+        if attributes
+            .max_entries
+            .map(|max_entries| max_entries < 1)
+            .unwrap_or(false)
+        {
+            return Ok(validators::simple_error(
+                "max_entries",
+                "Ticket type must allow at least one entry",
+            )?);
+        }
+
+        if let Some(ref required_attendee_details) = attributes.required_attendee_details {
+            if !required_attendee_details.is_empty() && !required_attendee_details.contains(&AttendeeDetails::Name) {
+                return Ok(validators::simple_error(
+                    "required_attendee_details",
+                    "Attendee name must be required when requiring other attendee details",
+                )?);
+            }
+        }
+
+        if attributes
+            .attendee_name_change_fee_in_cents
+            .map(|fee| fee < 0)
+            .unwrap_or(false)
+        {
+            return Ok(validators::simple_error(
+                "attendee_name_change_fee_in_cents",
+                "Name change fee cannot be negative",
+            )?);
+        }
+
+        if let Some(Some(lock_hours)) = attributes.attendee_details_lock_hours {
+            if lock_hours < 0 {
+                return Ok(validators::simple_error(
+                    "attendee_details_lock_hours",
+                    "Attendee details lock hours cannot be negative",
+                )?);
+            }
+        }
+
         if attributes.end_date_type.unwrap_or(self.end_date_type) == TicketTypeEndDateType::Manual
             && (attributes.end_date == Some(None) || (attributes.end_date.is_none() && self.end_date.is_none()))
         {
@@ -472,10 +571,18 @@ impl TicketType {
             return Ok(());
         }
 
+        // Selling out a shared pool sells out every ticket type drawing from it
+        let sold_out_ticket_types = match self.inventory_pool(conn)? {
+            Some(inventory_pool) if inventory_pool.available_count(conn)? == 0 => inventory_pool.ticket_types(conn)?,
+            _ => vec![self.clone()],
+        };
+
         // Find child ticket types
-        for child in self.find_dependent_ticket_types(conn)? {
-            if child.start_date.is_none() {
-                child.start_sales(current_user_id, conn)?;
+        for ticket_type in sold_out_ticket_types {
+            for child in ticket_type.find_dependent_ticket_types(conn)? {
+                if child.start_date.is_none() {
+                    child.start_sales(current_user_id, conn)?;
+                }
             }
         }
 
@@ -637,7 +744,15 @@ impl TicketType {
         let valid_available_ticket_count: i64 = query
             .first(conn)
             .to_db_error(ErrorCode::QueryError, "Could not load ticket count for ticket type")?;
-        Ok(valid_available_ticket_count as u32)
+
+        // Ticket types sharing an inventory pool are limited by the capacity remaining in the pool
+        match self.inventory_pool(conn)? {
+            Some(inventory_pool) => Ok(cmp::min(
+                valid_available_ticket_count as u32,
+                inventory_pool.available_count(conn)?,
+            )),
+            None => Ok(valid_available_ticket_count as u32),
+        }
     }
 
     pub fn current_ticket_pricing(
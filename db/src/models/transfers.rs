@@ -578,6 +578,8 @@ impl Transfer {
                     ticket_instances::updated_at.eq(dsl::now),
                     ticket_instances::first_name_override.eq(&name_override),
                     ticket_instances::last_name_override.eq(&name_override),
+                    ticket_instances::attendee_email.eq(&name_override),
+                    ticket_instances::attendee_birth_date.eq(None::<NaiveDate>),
                 ))
                 .execute(conn)
                 .to_db_error(ErrorCode::UpdateError, "Could not update ticket instance")?;
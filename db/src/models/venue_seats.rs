@@ -0,0 +1,146 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use diesel::sql_types::{Array, BigInt, Bool, Nullable, Text, Uuid as dUuid};
+use models::*;
+use schema::{venue_seats, venue_sections};
+use utils::errors::ConvertToDatabaseError;
+use utils::errors::DatabaseError;
+use utils::errors::ErrorCode;
+use uuid::Uuid;
+
+#[derive(Clone, Associations, Identifiable, Queryable, QueryableByName, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(VenueSection)]
+#[table_name = "venue_seats"]
+pub struct VenueSeat {
+    pub id: Uuid,
+    pub venue_section_id: Uuid,
+    pub row_label: String,
+    pub seat_number: String,
+    pub accessible: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Default, Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "venue_seats"]
+pub struct NewVenueSeat {
+    pub venue_section_id: Uuid,
+    pub row_label: String,
+    pub seat_number: String,
+    #[serde(default)]
+    pub accessible: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, QueryableByName, Serialize)]
+pub struct EventSeat {
+    #[sql_type = "dUuid"]
+    pub id: Uuid,
+    #[sql_type = "dUuid"]
+    pub venue_section_id: Uuid,
+    #[sql_type = "Text"]
+    pub section_name: String,
+    #[sql_type = "Text"]
+    pub row_label: String,
+    #[sql_type = "Text"]
+    pub seat_number: String,
+    #[sql_type = "Bool"]
+    pub accessible: bool,
+    #[sql_type = "dUuid"]
+    pub ticket_type_id: Uuid,
+    #[sql_type = "Bool"]
+    pub available: bool,
+}
+
+impl NewVenueSeat {
+    pub fn commit(&self, conn: &PgConnection) -> Result<VenueSeat, DatabaseError> {
+        diesel::insert_into(venue_seats::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create venue seat")
+    }
+
+    pub fn commit_multiple(seats: &[NewVenueSeat], conn: &PgConnection) -> Result<Vec<VenueSeat>, DatabaseError> {
+        diesel::insert_into(venue_seats::table)
+            .values(seats)
+            .get_results(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create venue seats")
+    }
+}
+
+impl VenueSeat {
+    pub fn create(venue_section_id: Uuid, row_label: String, seat_number: String, accessible: bool) -> NewVenueSeat {
+        NewVenueSeat {
+            venue_section_id,
+            row_label,
+            seat_number,
+            accessible,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<VenueSeat, DatabaseError> {
+        venue_seats::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading venue seat")
+    }
+
+    pub fn find_by_venue_section_id(
+        venue_section_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<VenueSeat>, DatabaseError> {
+        venue_seats::table
+            .filter(venue_seats::venue_section_id.eq(venue_section_id))
+            .order_by(venue_seats::row_label)
+            .then_order_by(venue_seats::seat_number)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load venue seats")
+    }
+
+    /// Seats in the section that are not sold or held by an unexpired cart reservation for the event.
+    /// Returned rows are locked until the end of the transaction so two carts cannot claim the same seat.
+    pub fn find_available_for_event(
+        venue_section_id: Uuid,
+        event_id: Uuid,
+        seat_ids: Option<Vec<Uuid>>,
+        excluded_ticket_instance_ids: &[Uuid],
+        limit: i64,
+        conn: &PgConnection,
+    ) -> Result<Vec<VenueSeat>, DatabaseError> {
+        let query = include_str!("../queries/find_available_venue_seats.sql");
+        diesel::sql_query(query)
+            .bind::<dUuid, _>(venue_section_id)
+            .bind::<dUuid, _>(event_id)
+            .bind::<Nullable<Array<dUuid>>, _>(seat_ids)
+            .bind::<Array<dUuid>, _>(excluded_ticket_instance_ids)
+            .bind::<BigInt, _>(limit)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load available venue seats")
+    }
+
+    pub fn find_for_event(event_id: Uuid, conn: &PgConnection) -> Result<Vec<EventSeat>, DatabaseError> {
+        let query = include_str!("../queries/event_seats.sql");
+        diesel::sql_query(query)
+            .bind::<dUuid, _>(event_id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load seats for event")
+    }
+
+    pub fn label(&self, conn: &PgConnection) -> Result<String, DatabaseError> {
+        let section_name: String = venue_sections::table
+            .filter(venue_sections::id.eq(self.venue_section_id))
+            .select(venue_sections::name)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load venue section for seat")?;
+        Ok(format!(
+            "{} Row {} Seat {}",
+            section_name, self.row_label, self.seat_number
+        ))
+    }
+
+    pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Failed to delete venue seat")
+    }
+}
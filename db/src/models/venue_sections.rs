@@ -0,0 +1,112 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{venue_seats, venue_sections};
+use utils::errors::ConvertToDatabaseError;
+use utils::errors::DatabaseError;
+use utils::errors::ErrorCode;
+use uuid::Uuid;
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(Venue)]
+#[table_name = "venue_sections"]
+pub struct VenueSection {
+    pub id: Uuid,
+    pub venue_id: Uuid,
+    pub stage_id: Option<Uuid>,
+    pub name: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(AsChangeset, Default, Deserialize)]
+#[table_name = "venue_sections"]
+pub struct VenueSectionEditableAttributes {
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub stage_id: Option<Option<Uuid>>,
+}
+
+#[derive(Default, Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "venue_sections"]
+pub struct NewVenueSection {
+    pub venue_id: Uuid,
+    pub stage_id: Option<Uuid>,
+    pub name: String,
+}
+
+impl NewVenueSection {
+    pub fn commit(&self, conn: &PgConnection) -> Result<VenueSection, DatabaseError> {
+        if let Some(stage_id) = self.stage_id {
+            if Stage::find(stage_id, conn)?.venue_id != self.venue_id {
+                return DatabaseError::validation_error("stage_id", "Stage does not belong to this venue");
+            }
+        }
+
+        diesel::insert_into(venue_sections::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create venue section")
+    }
+}
+
+impl VenueSection {
+    pub fn create(venue_id: Uuid, stage_id: Option<Uuid>, name: String) -> NewVenueSection {
+        NewVenueSection {
+            venue_id,
+            stage_id,
+            name,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<VenueSection, DatabaseError> {
+        venue_sections::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading venue section")
+    }
+
+    pub fn find_by_venue_id(venue_id: Uuid, conn: &PgConnection) -> Result<Vec<VenueSection>, DatabaseError> {
+        venue_sections::table
+            .filter(venue_sections::venue_id.eq(venue_id))
+            .order_by(venue_sections::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load venue sections")
+    }
+
+    pub fn venue(&self, conn: &PgConnection) -> Result<Venue, DatabaseError> {
+        Venue::find(self.venue_id, conn)
+    }
+
+    pub fn seats(&self, conn: &PgConnection) -> Result<Vec<VenueSeat>, DatabaseError> {
+        VenueSeat::find_by_venue_section_id(self.id, conn)
+    }
+
+    pub fn seat_count(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        venue_seats::table
+            .filter(venue_seats::venue_section_id.eq(self.id))
+            .count()
+            .get_result(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to count venue section seats")
+    }
+
+    pub fn update(
+        &self,
+        attributes: VenueSectionEditableAttributes,
+        conn: &PgConnection,
+    ) -> Result<VenueSection, DatabaseError> {
+        if let Some(Some(stage_id)) = attributes.stage_id {
+            if Stage::find(stage_id, conn)?.venue_id != self.venue_id {
+                return DatabaseError::validation_error("stage_id", "Stage does not belong to this venue");
+            }
+        }
+
+        diesel::update(self)
+            .set((attributes, venue_sections::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update venue section")
+    }
+}
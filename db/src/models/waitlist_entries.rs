@@ -0,0 +1,425 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::dsl::{exists, select};
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use log::Level;
+use models::*;
+use schema::{assets, ticket_instances, waitlist_entries};
+use utils::errors::*;
+use uuid::Uuid;
+
+pub const WAITLIST_OFFER_EXPIRY_TIME_MINUTES: i64 = 30;
+
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(TicketType)]
+#[belongs_to(User)]
+#[table_name = "waitlist_entries"]
+pub struct WaitlistEntry {
+    pub id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub user_id: Uuid,
+    pub quantity: i32,
+    pub status: WaitlistEntryStatus,
+    pub order_id: Option<Uuid>,
+    pub offered_at: Option<NaiveDateTime>,
+    pub offer_expires_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[table_name = "waitlist_entries"]
+pub struct NewWaitlistEntry {
+    pub ticket_type_id: Uuid,
+    pub user_id: Uuid,
+    pub quantity: i32,
+}
+
+impl NewWaitlistEntry {
+    pub fn commit(&self, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        if self.quantity <= 0 {
+            return DatabaseError::validation_error("quantity", "Quantity must be greater than zero");
+        }
+
+        let ticket_type = TicketType::find(self.ticket_type_id, conn)?;
+        if ticket_type.status == TicketTypeStatus::Cancelled || ticket_type.deleted_at.is_some() {
+            return DatabaseError::business_process_error("Ticket type is no longer available");
+        }
+
+        if WaitlistEntry::find_active_for_user(self.ticket_type_id, self.user_id, conn)?.is_some() {
+            return DatabaseError::validation_error("ticket_type_id", "Already on the waitlist for this ticket type");
+        }
+
+        let waitlist_entry: WaitlistEntry = diesel::insert_into(waitlist_entries::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create waitlist entry")?;
+
+        DomainEvent::create(
+            DomainEventTypes::WaitlistEntryCreated,
+            "Joined waitlist".to_string(),
+            Tables::WaitlistEntries,
+            Some(waitlist_entry.id),
+            Some(waitlist_entry.user_id),
+            Some(json!({"ticket_type_id": waitlist_entry.ticket_type_id, "quantity": waitlist_entry.quantity})),
+        )
+        .commit(conn)?;
+
+        WaitlistEntry::schedule_processing(waitlist_entry.ticket_type_id, None, conn)?;
+
+        Ok(waitlist_entry)
+    }
+}
+
+impl WaitlistEntry {
+    pub fn create(ticket_type_id: Uuid, user_id: Uuid, quantity: i32) -> NewWaitlistEntry {
+        NewWaitlistEntry {
+            ticket_type_id,
+            user_id,
+            quantity,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        waitlist_entries::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading waitlist entry")
+    }
+
+    pub fn find_active_for_user(
+        ticket_type_id: Uuid,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<WaitlistEntry>, DatabaseError> {
+        waitlist_entries::table
+            .filter(waitlist_entries::ticket_type_id.eq(ticket_type_id))
+            .filter(waitlist_entries::user_id.eq(user_id))
+            .filter(waitlist_entries::status.eq_any(vec![WaitlistEntryStatus::Waiting, WaitlistEntryStatus::Offered]))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Error loading waitlist entry")
+    }
+
+    pub fn find_for_user(user_id: Uuid, conn: &PgConnection) -> Result<Vec<WaitlistEntry>, DatabaseError> {
+        waitlist_entries::table
+            .filter(waitlist_entries::user_id.eq(user_id))
+            .order_by(waitlist_entries::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load waitlist entries for user")
+    }
+
+    /// Entries for the ticket type in the order they joined the waitlist
+    pub fn find_by_ticket_type_id(
+        ticket_type_id: Uuid,
+        status: Option<WaitlistEntryStatus>,
+        conn: &PgConnection,
+    ) -> Result<Vec<WaitlistEntry>, DatabaseError> {
+        let mut query = waitlist_entries::table
+            .filter(waitlist_entries::ticket_type_id.eq(ticket_type_id))
+            .into_boxed();
+
+        if let Some(status) = status {
+            query = query.filter(waitlist_entries::status.eq(status));
+        }
+
+        query
+            .order_by(waitlist_entries::created_at)
+            .then_order_by(waitlist_entries::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load waitlist entries for ticket type")
+    }
+
+    /// Position in the queue of fans still waiting, starting at 1. Returns `None` once the entry
+    /// is no longer waiting.
+    pub fn position(&self, conn: &PgConnection) -> Result<Option<i64>, DatabaseError> {
+        if self.status != WaitlistEntryStatus::Waiting {
+            return Ok(None);
+        }
+
+        let ahead: i64 = waitlist_entries::table
+            .filter(waitlist_entries::ticket_type_id.eq(self.ticket_type_id))
+            .filter(waitlist_entries::status.eq(WaitlistEntryStatus::Waiting))
+            .filter(waitlist_entries::created_at.lt(self.created_at))
+            .count()
+            .get_result(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load waitlist position")?;
+
+        Ok(Some(ahead + 1))
+    }
+
+    /// Quantity of released tickets held back from a reservation for fans still waiting on the
+    /// ticket type (or any ticket type sharing its inventory pool). Reservations made for a
+    /// waitlist offer draw on the held tickets so nothing is held back from them.
+    pub fn held_quantity_for_order_item(
+        order_item: &OrderItem,
+        ticket_type_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<i64, DatabaseError> {
+        let offered: bool = select(exists(
+            waitlist_entries::table
+                .filter(waitlist_entries::ticket_type_id.eq(ticket_type_id))
+                .filter(waitlist_entries::order_id.eq(order_item.order_id))
+                .filter(waitlist_entries::status.eq(WaitlistEntryStatus::Offered)),
+        ))
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not check for waitlist offers")?;
+        if offered {
+            return Ok(0);
+        }
+
+        let ticket_type_ids: Vec<Uuid> = match TicketType::find(ticket_type_id, conn)?.inventory_pool(conn)? {
+            Some(inventory_pool) => inventory_pool.ticket_types(conn)?.iter().map(|tt| tt.id).collect(),
+            None => vec![ticket_type_id],
+        };
+        let user_id = Order::find(order_item.order_id, conn)?.user_id;
+        let held_quantity: Option<i64> = waitlist_entries::table
+            .filter(waitlist_entries::ticket_type_id.eq_any(ticket_type_ids))
+            .filter(waitlist_entries::status.eq(WaitlistEntryStatus::Waiting))
+            .filter(waitlist_entries::user_id.ne(user_id))
+            .select(dsl::sum(waitlist_entries::quantity))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load waitlist quantity")?;
+
+        Ok(held_quantity.unwrap_or(0))
+    }
+
+    pub fn ticket_type(&self, conn: &PgConnection) -> Result<TicketType, DatabaseError> {
+        TicketType::find(self.ticket_type_id, conn)
+    }
+
+    pub fn user(&self, conn: &PgConnection) -> Result<User, DatabaseError> {
+        User::find(self.user_id, conn)
+    }
+
+    pub fn cancel(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        if self.status != WaitlistEntryStatus::Waiting && self.status != WaitlistEntryStatus::Offered {
+            return DatabaseError::business_process_error("Waitlist entry is no longer active");
+        }
+
+        if self.status == WaitlistEntryStatus::Offered {
+            // Hand the offered tickets back so the next person on the waitlist can be offered them
+            if let Some(order_id) = self.order_id {
+                let mut order = Order::find(order_id, conn)?;
+                if order.status == OrderStatus::Draft {
+                    let box_office_pricing = order.box_office_pricing;
+                    order.update_quantities(
+                        self.user_id,
+                        &[UpdateOrderItem {
+                            ticket_type_id: self.ticket_type_id,
+                            quantity: 0,
+                            redemption_code: None,
+                        }],
+                        box_office_pricing,
+                        false,
+                        conn,
+                    )?;
+                }
+            }
+            WaitlistEntry::schedule_processing(self.ticket_type_id, None, conn)?;
+        }
+
+        let waitlist_entry = self.set_status(WaitlistEntryStatus::Cancelled, conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::WaitlistEntryCancelled,
+            "Left waitlist".to_string(),
+            Tables::WaitlistEntries,
+            Some(self.id),
+            current_user_id,
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(waitlist_entry)
+    }
+
+    /// Schedules a `ProcessWaitlist` action for the ticket type when there are fans waiting on it.
+    /// Called whenever inventory is returned to the ticket type's general pool.
+    pub fn schedule_processing_if_waiting(ticket_type_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
+        // Capacity released in a shared pool can be offered on any ticket type drawing from it
+        let ticket_type_ids: Vec<Uuid> = match TicketType::find(ticket_type_id, conn)?.inventory_pool(conn)? {
+            Some(inventory_pool) => inventory_pool.ticket_types(conn)?.iter().map(|tt| tt.id).collect(),
+            None => vec![ticket_type_id],
+        };
+
+        for ticket_type_id in ticket_type_ids {
+            let waiting: bool = select(exists(
+                waitlist_entries::table
+                    .filter(waitlist_entries::ticket_type_id.eq(ticket_type_id))
+                    .filter(waitlist_entries::status.eq(WaitlistEntryStatus::Waiting)),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not check for waitlist entries")?;
+
+            if waiting {
+                WaitlistEntry::schedule_processing(ticket_type_id, None, conn)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn schedule_processing(
+        ticket_type_id: Uuid,
+        run_at: Option<NaiveDateTime>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        DomainAction::schedule_for_resource(
+            DomainActionTypes::ProcessWaitlist,
+            Tables::TicketTypes,
+            ticket_type_id,
+            run_at,
+            conn,
+        )
+    }
+
+    /// Expires unclaimed offers and offers any available inventory to the fans at the front of the
+    /// waitlist, returning the entries that received a new offer. Processing is rescheduled for the
+    /// next offer expiry or, while fans are still waiting, the next cart reservation expiry.
+    pub fn process(ticket_type_id: Uuid, conn: &PgConnection) -> Result<Vec<WaitlistEntry>, DatabaseError> {
+        let now = Utc::now().naive_utc();
+        let ticket_type = TicketType::find(ticket_type_id, conn)?;
+
+        for entry in WaitlistEntry::find_by_ticket_type_id(ticket_type_id, Some(WaitlistEntryStatus::Offered), conn)? {
+            let order = match entry.order_id {
+                Some(order_id) => Some(Order::find(order_id, conn)?),
+                None => None,
+            };
+            if order.map(|o| o.status == OrderStatus::Paid).unwrap_or(false) {
+                entry.set_status(WaitlistEntryStatus::Claimed, conn)?;
+            } else if entry.offer_expires_at.map(|e| e <= now).unwrap_or(true) {
+                entry.set_status(WaitlistEntryStatus::Expired, conn)?;
+                DomainEvent::create(
+                    DomainEventTypes::WaitlistOfferExpired,
+                    "Waitlist offer expired".to_string(),
+                    Tables::WaitlistEntries,
+                    Some(entry.id),
+                    None,
+                    None,
+                )
+                .commit(conn)?;
+            }
+        }
+
+        let mut offered_entries = vec![];
+        if ticket_type.status == TicketTypeStatus::Cancelled || ticket_type.deleted_at.is_some() {
+            for entry in
+                WaitlistEntry::find_by_ticket_type_id(ticket_type_id, Some(WaitlistEntryStatus::Waiting), conn)?
+            {
+                entry.set_status(WaitlistEntryStatus::Cancelled, conn)?;
+            }
+        } else {
+            let mut available = ticket_type.valid_available_ticket_count(conn)? as i32;
+            for entry in
+                WaitlistEntry::find_by_ticket_type_id(ticket_type_id, Some(WaitlistEntryStatus::Waiting), conn)?
+            {
+                // Offers are made strictly in the order fans joined the waitlist
+                if entry.quantity > available {
+                    break;
+                }
+
+                // Offers that can't be fulfilled (e.g. ticket limits) are rolled back so the rest can proceed
+                match conn.transaction(|| entry.make_offer(conn)) {
+                    Ok(offered_entry) => {
+                        available -= offered_entry.quantity;
+                        offered_entries.push(offered_entry);
+                    }
+                    Err(e) => {
+                        jlog!(Level::Warn, "Could not make waitlist offer", {"waitlist_entry_id": entry.id, "error": e.to_string()});
+                        entry.set_status(WaitlistEntryStatus::Expired, conn)?;
+                    }
+                }
+            }
+        }
+
+        let mut next_run_at =
+            WaitlistEntry::find_by_ticket_type_id(ticket_type_id, Some(WaitlistEntryStatus::Offered), conn)?
+                .into_iter()
+                .filter_map(|e| e.offer_expires_at)
+                .min();
+        if !WaitlistEntry::find_by_ticket_type_id(ticket_type_id, Some(WaitlistEntryStatus::Waiting), conn)?.is_empty()
+        {
+            let next_reservation_expiry: Option<NaiveDateTime> = ticket_instances::table
+                .inner_join(assets::table)
+                .filter(assets::ticket_type_id.eq(ticket_type_id))
+                .filter(ticket_instances::status.eq(TicketInstanceStatus::Reserved))
+                .filter(ticket_instances::reserved_until.gt(now))
+                .select(dsl::min(ticket_instances::reserved_until))
+                .first(conn)
+                .to_db_error(ErrorCode::QueryError, "Could not load next reservation expiry")?;
+            next_run_at = match (next_run_at, next_reservation_expiry) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+        }
+
+        if let Some(next_run_at) = next_run_at {
+            WaitlistEntry::schedule_processing(ticket_type_id, Some(next_run_at), conn)?;
+        }
+
+        Ok(offered_entries)
+    }
+
+    fn make_offer(&self, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        let user = self.user(conn)?;
+        let offer_expires_at = Utc::now().naive_utc() + Duration::minutes(WAITLIST_OFFER_EXPIRY_TIME_MINUTES);
+        let mut cart = Order::find_or_create_cart(&user, conn)?;
+
+        // Marked as offered first so the reservation below can draw on the tickets held for the waitlist
+        let waitlist_entry: WaitlistEntry = diesel::update(self)
+            .set((
+                waitlist_entries::status.eq(WaitlistEntryStatus::Offered),
+                waitlist_entries::order_id.eq(cart.id),
+                waitlist_entries::offered_at.eq(dsl::now),
+                waitlist_entries::offer_expires_at.eq(offer_expires_at),
+                waitlist_entries::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update waitlist entry")?;
+
+        let box_office_pricing = cart.box_office_pricing;
+        cart.update_quantities(
+            user.id,
+            &[UpdateOrderItem {
+                ticket_type_id: self.ticket_type_id,
+                quantity: self.quantity as u32,
+                redemption_code: None,
+            }],
+            box_office_pricing,
+            false,
+            conn,
+        )?;
+
+        // Only the offered tickets are held until the offer expires, the rest of the cart keeps its expiry
+        for item in cart.items(conn)? {
+            if item.ticket_type_id == Some(self.ticket_type_id) && item.hold_id.is_none() {
+                TicketInstance::update_reserved_time(&item, offer_expires_at, conn)?;
+            }
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::WaitlistOfferMade,
+            "Waitlist offer made".to_string(),
+            Tables::WaitlistEntries,
+            Some(waitlist_entry.id),
+            None,
+            Some(json!({"order_id": cart.id, "offer_expires_at": offer_expires_at})),
+        )
+        .commit(conn)?;
+
+        Ok(waitlist_entry)
+    }
+
+    fn set_status(&self, status: WaitlistEntryStatus, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        diesel::update(self)
+            .set((
+                waitlist_entries::status.eq(status),
+                waitlist_entries::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update waitlist entry")
+    }
+}
@@ -0,0 +1,23 @@
+SELECT vs.id,
+       vs.venue_section_id,
+       vsec.name AS section_name,
+       vs.row_label,
+       vs.seat_number,
+       vs.accessible,
+       tt.id     AS ticket_type_id,
+       NOT EXISTS(
+               SELECT ti.id
+               FROM ticket_instances ti
+                        INNER JOIN assets a ON ti.asset_id = a.id
+                        INNER JOIN ticket_types tt2 ON a.ticket_type_id = tt2.id
+               WHERE ti.venue_seat_id = vs.id
+                 AND tt2.event_id = tt.event_id
+                 AND (ti.status IN ('Purchased', 'Redeemed') OR (ti.status = 'Reserved' AND ti.reserved_until >= now()))
+           )     AS available
+FROM ticket_types tt
+         INNER JOIN venue_sections vsec ON tt.venue_section_id = vsec.id
+         INNER JOIN venue_seats vs ON vs.venue_section_id = vsec.id
+WHERE tt.event_id = $1
+  AND tt.deleted_at IS NULL
+  AND tt.status <> 'Cancelled'
+ORDER BY vsec.name, vs.row_label, length(vs.seat_number), vs.seat_number;
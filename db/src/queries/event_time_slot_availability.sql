@@ -0,0 +1,14 @@
+SELECT ets.id,
+       ets.event_id,
+       ets.starts_at,
+       ets.ends_at,
+       ets.capacity,
+       GREATEST(ets.capacity - (
+           SELECT count(ti.id)
+           FROM ticket_instances ti
+           WHERE ti.event_time_slot_id = ets.id
+             AND (ti.status IN ('Purchased', 'Redeemed') OR (ti.status = 'Reserved' AND ti.reserved_until >= now()))
+       ), 0) AS available
+FROM event_time_slots ets
+WHERE ets.event_id = $1
+ORDER BY ets.starts_at;
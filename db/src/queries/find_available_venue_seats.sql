@@ -0,0 +1,16 @@
+SELECT vs.*
+FROM venue_seats vs
+WHERE vs.venue_section_id = $1
+  AND ($3 IS NULL OR vs.id = ANY ($3))
+  AND NOT EXISTS(
+        SELECT ti.id
+        FROM ticket_instances ti
+                 INNER JOIN assets a ON ti.asset_id = a.id
+                 INNER JOIN ticket_types tt ON a.ticket_type_id = tt.id
+        WHERE ti.venue_seat_id = vs.id
+          AND tt.event_id = $2
+          AND ti.id <> ALL ($4)
+          AND (ti.status IN ('Purchased', 'Redeemed') OR (ti.status = 'Reserved' AND ti.reserved_until >= now()))
+    )
+ORDER BY vs.row_label, length(vs.seat_number), vs.seat_number
+LIMIT $5 FOR UPDATE OF vs SKIP LOCKED;
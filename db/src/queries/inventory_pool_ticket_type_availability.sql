@@ -0,0 +1,27 @@
+SELECT e.organization_id,
+       tt.event_id,
+       tt.inventory_pool_id,
+       tt.id                     AS ticket_type_id,
+       (
+           SELECT count(ti.id)
+           FROM ticket_instances ti
+                    INNER JOIN assets a ON ti.asset_id = a.id
+           WHERE a.ticket_type_id = tt.id
+             AND ti.hold_id IS NULL
+             AND (ti.status = 'Available' OR (ti.status = 'Reserved' AND ti.reserved_until < now()))
+       )                         AS available_count,
+       GREATEST(ip.capacity - (
+           SELECT count(ti.id)
+           FROM ticket_instances ti
+                    INNER JOIN assets a ON ti.asset_id = a.id
+                    INNER JOIN ticket_types tt2 ON a.ticket_type_id = tt2.id
+           WHERE tt2.inventory_pool_id = ip.id
+             AND (ti.status IN ('Purchased', 'Redeemed') OR (ti.status = 'Reserved' AND ti.reserved_until >= now()))
+       ), 0)                     AS pool_available_count
+FROM ticket_types tt
+         INNER JOIN inventory_pools ip ON tt.inventory_pool_id = ip.id
+         INNER JOIN events e ON tt.event_id = e.id
+WHERE ($1 IS NULL OR tt.event_id = $1)
+  AND ($2 IS NULL OR e.organization_id = $2)
+  AND tt.deleted_at IS NULL
+ORDER BY tt.inventory_pool_id, tt.rank;
@@ -18,7 +18,8 @@ SELECT ticket_instance_id,
        code,
        code_type,
        pending_transfer_id,
-       discount_price_in_cents
+       discount_price_in_cents,
+       seat_label
 FROM (
          SELECT t.ticket_instance_id,
                 t.order_item_id                    AS order_item_id,
@@ -52,7 +53,10 @@ FROM (
                 coalesce(h.redemption_code, c.redemption_code)           AS code,
                 coalesce(h.hold_type, c.code_type) AS code_type,
                 tfs.id                             AS pending_transfer_id,
-                dis.unit_price_in_cents            AS discount_price_in_cents
+                dis.unit_price_in_cents            AS discount_price_in_cents,
+                CASE
+                    WHEN vs.id IS NOT NULL THEN vsec.name || ' Row ' || vs.row_label || ' Seat ' || vs.seat_number
+                    END                            AS seat_label
 
          FROM (
                   SELECT DISTINCT ticket_instance_id, order_item_id
@@ -88,6 +92,8 @@ FROM (
                   LEFT JOIN codes c ON oi.code_id = c.id
                   LEFT JOIN wallets w ON ti.wallet_id = w.id
                   LEFT JOIN users wallet_owner ON w.user_id = wallet_owner.id
+                  LEFT JOIN venue_seats vs ON ti.venue_seat_id = vs.id
+                  LEFT JOIN venue_sections vsec ON vs.venue_section_id = vsec.id
                   LEFT JOIN refunded_tickets rt ON rt.ticket_instance_id = ti.id AND rt.order_item_id = oi.id
                   LEFT JOIN order_items fi ON fi.parent_id = oi.id AND fi.item_type = 'PerUnitFees'
                   LEFT JOIN order_items dis ON dis.parent_id = oi.id AND dis.item_type = 'Discount'
@@ -6,11 +6,14 @@ WITH cte AS (SELECT t.id
                AND t.id = COALESCE($4, t.id)
              LIMIT $2 FOR UPDATE OF t SKIP LOCKED)
 UPDATE ticket_instances
-SET order_item_id  = NULL,
-    reserved_until = NULL,
-    redeem_key     = NULL,
-    status         = $5,
-    updated_at     = now()
+SET order_item_id      = NULL,
+    reserved_until     = NULL,
+    redeem_key         = NULL,
+    venue_seat_id      = NULL,
+    seat_event_id      = NULL,
+    event_time_slot_id = NULL,
+    status             = $5,
+    updated_at         = now()
 FROM cte
 WHERE cte.id = ticket_instances.id RETURNING ticket_instances.*;
 
@@ -3,6 +3,7 @@ SELECT
   entries.operator_id,
   entries.event_name,
   entries.event_date,
+  entries.currency,
   entries.external_payment_type,
   CAST(SUM(entries.number_of_tickets) AS BIGINT) as number_of_tickets,
   entries.face_value_in_cents,
@@ -14,6 +15,7 @@ FROM (
     concat(u.first_name, ' ', u.last_name) as operator_name,
     e.name as event_name,
     e.event_start as event_date,
+    o.currency,
     -- If set to false, the logic does not group on external payment type allowing the collection to reflect box office entries
     CASE WHEN $4 THEN o.external_payment_type ELSE null END as external_payment_type,
     CAST(SUM(oi.quantity - oi.refunded_quantity) FILTER (WHERE oi.item_type = 'Tickets') AS BIGINT) as number_of_tickets,
@@ -46,6 +48,7 @@ FROM (
     operator_id,
     event_name,
     event_date,
+    o.currency,
     o.external_payment_type,
     oi.item_type,
     oi.unit_price_in_cents,
@@ -58,6 +61,7 @@ GROUP BY
   entries.operator_id,
   entries.event_name,
   entries.event_date,
+  entries.currency,
   entries.external_payment_type,
   entries.face_value_in_cents,
   entries.revenue_share_value_in_cents
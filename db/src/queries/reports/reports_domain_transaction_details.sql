@@ -9,6 +9,7 @@ SELECT COUNT(*) OVER ()
         ,o.paid_at                                                                                                          AS transaction_date
         ,o.platform                                                                                                         AS point_of_sale
         ,p.payment_method                                                                                                   AS payment_method
+        ,o.currency                                                                                                         AS currency
         ,oi_tickets.quantity                                                                                                AS qty_tickets_sold
         ,oi_tickets.refunded_quantity                                                                                       AS qty_tickets_refunded
         ,(oi_tickets.quantity - oi_tickets.refunded_quantity)                                                               AS qty_tickets_sold_net
@@ -0,0 +1,16 @@
+SELECT
+  period_start,
+  entries,
+  exits,
+  CAST(SUM(entries - exits) OVER (ORDER BY period_start) AS BIGINT) AS occupancy
+FROM (
+  SELECT
+    date_trunc('hour', ts.scanned_at)                                   AS period_start,
+    CAST(COUNT(*) FILTER(WHERE ts.direction = 'In') AS BIGINT)          AS entries,
+    CAST(COUNT(*) FILTER(WHERE ts.direction = 'Out') AS BIGINT)         AS exits
+  FROM ticket_scans ts
+  WHERE ts.event_id = $1
+  AND ts.access_zone_id IS NULL
+  GROUP BY date_trunc('hour', ts.scanned_at)
+) scans_per_period
+ORDER BY period_start;
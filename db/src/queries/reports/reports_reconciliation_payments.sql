@@ -0,0 +1,25 @@
+-- Payments and refunds per payment method, orders paid with several methods are split by the amount taken by each.
+-- Orders for products without an event, such as gift cards, belong to the product's organization
+SELECT
+  p.currency,
+  p.payment_method,
+  p.provider AS payment_provider,
+  CAST(COUNT(*) FILTER (WHERE p.amount > 0) AS BIGINT) AS payment_count,
+  CAST(COALESCE(SUM(p.amount) FILTER (WHERE p.amount > 0), 0) AS BIGINT) AS sales_total,
+  CAST(COALESCE(-SUM(p.amount) FILTER (WHERE p.amount < 0), 0) AS BIGINT) AS refund_total,
+  CAST(COALESCE(SUM(p.amount), 0) AS BIGINT) AS total
+FROM payments p
+WHERE p.status IN ('Completed', 'Refunded')
+  AND EXISTS (
+    SELECT 1
+    FROM order_items oi
+    LEFT JOIN events e ON e.id = oi.event_id
+    LEFT JOIN product_variants pv ON pv.id = oi.product_variant_id
+    LEFT JOIN products pr ON pr.id = pv.product_id
+    WHERE oi.order_id = p.order_id
+      AND COALESCE(e.organization_id, pr.organization_id) = $1
+  )
+  AND ($2 IS NULL OR p.created_at >= $2)
+  AND ($3 IS NULL OR p.created_at <= $3)
+GROUP BY p.currency, p.payment_method, p.provider
+ORDER BY p.currency, p.payment_method, p.provider;
@@ -2,12 +2,22 @@ SELECT
   COUNT(*) OVER ()                                                                          AS total,
   tt.name                                                                                   AS ticket_type_name,
   CAST(COALESCE(COUNT(DISTINCT ti.id) FILTER(WHERE ti.status = 'Redeemed'), 0) AS BIGINT)   AS scanned_count,
-  CAST(COALESCE(COUNT(DISTINCT ti.id) FILTER(WHERE ti.status = 'Purchased'), 0) AS BIGINT)  AS not_scanned_count
+  CAST(COALESCE(COUNT(DISTINCT ti.id) FILTER(WHERE ti.status = 'Purchased'), 0) AS BIGINT)  AS not_scanned_count,
+  -- Redeemed tickets without scans were redeemed before scans were logged and never left
+  CAST(COALESCE(COUNT(DISTINCT ti.id) FILTER(WHERE ti.status = 'Redeemed' AND COALESCE(ls.direction, 'In') = 'In'), 0) AS BIGINT) AS inside_count
 FROM ticket_types tt
 JOIN assets a ON tt.id = a.ticket_type_id
 LEFT JOIN ticket_instances ti ON a.id = ti.asset_id
 -- Confirm this isn't a refunded redeemed (they keep their redeemed status and order association unlike normal refunds)
 LEFT JOIN refunded_tickets rt ON rt.ticket_instance_id = ti.id AND ti.order_item_id = rt.order_item_id
+LEFT JOIN LATERAL (
+  SELECT ts.direction
+  FROM ticket_scans ts
+  WHERE ts.ticket_instance_id = ti.id
+  AND ts.access_zone_id IS NULL
+  ORDER BY ts.scanned_at DESC, ts.created_at DESC
+  LIMIT 1
+) ls ON true
 WHERE tt.event_id = $1
 AND tt.status <> 'Cancelled'
 AND rt.id IS NULL
@@ -24,9 +24,19 @@ SELECT COUNT(*) OVER ()
            COALESCE(oi_event_fees.client_fee_in_cents, 0) *
            (COALESCE(oi_event_fees.quantity, 0) -
             COALESCE(oi_event_fees.refunded_quantity, 0)) AS BIGINT)                                  AS event_fee_client_in_cents_total,
+    -- taxes charged on the ticket and its per unit fees, inclusive taxes are already part of the price
+    CAST(COALESCE(oi_tax.unit_price_in_cents, 0)
+    + COALESCE(oi_fees_tax.unit_price_in_cents, 0) AS BIGINT)                                          AS tax_in_cents,
+    CAST(COALESCE(oi_tax.included_tax_in_cents, 0)
+    + COALESCE(oi_fees_tax.included_tax_in_cents, 0) AS BIGINT)                                        AS included_tax_in_cents,
+    CAST((COALESCE(oi_tax.unit_price_in_cents, 0) + COALESCE(oi_tax.included_tax_in_cents, 0))
+    * (COALESCE(oi.quantity, 0) - COALESCE(oi.refunded_quantity, 0))
+    + (COALESCE(oi_fees_tax.unit_price_in_cents, 0) + COALESCE(oi_fees_tax.included_tax_in_cents, 0))
+    * (COALESCE(oi_fees.quantity, 0) - COALESCE(oi_fees.refunded_quantity, 0)) AS BIGINT)              AS tax_in_cents_total,
     oi_fees.fee_schedule_range_id                                                                      AS fee_range_id,
     o.paid_at                                                                                          AS transaction_date,
     o.order_type,
+    o.currency,
     p.payment_method,
     p.payment_provider,
     h.redemption_code,
@@ -49,6 +59,7 @@ SELECT COUNT(*) OVER ()
     o.content,
     o.platform,
     ti_agg.check_in_source,
+    ti_agg.seat_label,
     g.headline_artist_alt_genres,
     g.headline_artist_main_genre
 FROM orders o
@@ -58,6 +69,18 @@ FROM orders o
         ON (oi_event_fees.item_type = 'EventFees' AND o.id = oi_event_fees.order_id)
     LEFT JOIN order_items oi_promo_code
         ON (oi_promo_code.item_type = 'Discount' AND oi.id = oi_promo_code.parent_id)
+    LEFT JOIN (SELECT tax.parent_id,
+        SUM(tax.unit_price_in_cents)    AS unit_price_in_cents,
+        SUM(tax.included_tax_in_cents)  AS included_tax_in_cents
+        FROM order_items tax
+        WHERE tax.item_type = 'Tax'
+        GROUP BY tax.parent_id) AS oi_tax ON oi_tax.parent_id = oi.id
+    LEFT JOIN (SELECT tax.parent_id,
+        SUM(tax.unit_price_in_cents)    AS unit_price_in_cents,
+        SUM(tax.included_tax_in_cents)  AS included_tax_in_cents
+        FROM order_items tax
+        WHERE tax.item_type = 'Tax'
+        GROUP BY tax.parent_id) AS oi_fees_tax ON oi_fees_tax.parent_id = oi_fees.id
     LEFT JOIN codes c ON oi.code_id = c.id
     LEFT JOIN ticket_types tt ON (oi.ticket_type_id = tt.id)
     LEFT JOIN (SELECT order_id,
@@ -67,8 +90,12 @@ FROM orders o
         WHERE p.status IN ('Completed','Refunded')
         GROUP BY p.order_id) AS p on o.id = p.order_id
     LEFT JOIN (SELECT ti.order_item_id,
-        NULLIF(ARRAY_TO_STRING(ARRAY_AGG(DISTINCT ti.check_in_source), ', '), '') AS check_in_source
+        NULLIF(ARRAY_TO_STRING(ARRAY_AGG(DISTINCT ti.check_in_source), ', '), '') AS check_in_source,
+        STRING_AGG(vsec.name || ' Row ' || vs.row_label || ' Seat ' || vs.seat_number, ', '
+            ORDER BY vsec.name, vs.row_label, vs.seat_number)                       AS seat_label
         FROM ticket_instances ti
+        LEFT JOIN venue_seats vs ON ti.venue_seat_id = vs.id
+        LEFT JOIN venue_sections vsec ON vs.venue_section_id = vsec.id
         GROUP BY ti.order_item_id) AS ti_agg ON ti_agg.order_item_id = oi.id
     LEFT JOIN holds h ON oi.hold_id = h.id
     LEFT JOIN events e ON oi.event_id = e.id
@@ -10,10 +10,13 @@ WITH r AS (SELECT t.id
 
 UPDATE ticket_instances
 
-SET order_item_id  = $1,
-    reserved_until = $2,
-    status         = 'Reserved',
-    updated_at     = now()
+SET order_item_id      = $1,
+    reserved_until     = $2,
+    status             = 'Reserved',
+    venue_seat_id      = NULL,
+    seat_event_id      = NULL,
+    event_time_slot_id = NULL,
+    updated_at         = now()
 FROM r
 WHERE ticket_instances.id = r.id RETURNING ticket_instances.*;
 
@@ -1,3 +1,24 @@
+table! {
+    access_zone_users (id) {
+        id -> Uuid,
+        event_id -> Uuid,
+        access_zone_id -> Uuid,
+        user_id -> Uuid,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    access_zones (id) {
+        id -> Uuid,
+        event_id -> Uuid,
+        name -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     analytics_page_views (id) {
         id -> Uuid,
@@ -152,6 +173,25 @@ table! {
     }
 }
 
+table! {
+    disputes (id) {
+        id -> Uuid,
+        order_id -> Uuid,
+        payment_id -> Uuid,
+        settlement_adjustment_id -> Nullable<Uuid>,
+        created_by -> Nullable<Uuid>,
+        external_reference -> Nullable<Text>,
+        amount_in_cents -> Int8,
+        reason -> Nullable<Text>,
+        status -> Text,
+        outcome -> Nullable<Text>,
+        evidence_due_by -> Nullable<Timestamp>,
+        resolved_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     domain_actions (id) {
         id -> Uuid,
@@ -216,6 +256,21 @@ table! {
     }
 }
 
+table! {
+    dynamic_pricing_strategies (id) {
+        id -> Uuid,
+        ticket_type_id -> Uuid,
+        floor_price_in_cents -> Int8,
+        ceiling_price_in_cents -> Int8,
+        adjustment_percent -> Int4,
+        adjustment_interval_minutes -> Int4,
+        is_active -> Bool,
+        last_adjusted_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     event_artists (id) {
         id -> Uuid,
@@ -250,6 +305,34 @@ table! {
     }
 }
 
+table! {
+    event_refund_batch_orders (id) {
+        id -> Uuid,
+        event_refund_batch_id -> Uuid,
+        order_id -> Uuid,
+        status -> Text,
+        refund_id -> Nullable<Uuid>,
+        amount_refunded_in_cents -> Nullable<Int8>,
+        error -> Nullable<Text>,
+        attempted_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    event_refund_batches (id) {
+        id -> Uuid,
+        event_id -> Uuid,
+        status -> Text,
+        refund_fees -> Bool,
+        created_by -> Uuid,
+        completed_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     event_report_subscribers (id) {
         id -> Uuid,
@@ -261,6 +344,48 @@ table! {
     }
 }
 
+table! {
+    event_reschedule_responses (id) {
+        id -> Uuid,
+        event_reschedule_id -> Uuid,
+        order_id -> Uuid,
+        user_id -> Uuid,
+        response -> Text,
+        refund_id -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    event_reschedules (id) {
+        id -> Uuid,
+        event_id -> Uuid,
+        previous_event_start -> Nullable<Timestamp>,
+        previous_event_end -> Nullable<Timestamp>,
+        previous_door_time -> Nullable<Timestamp>,
+        event_start -> Timestamp,
+        event_end -> Nullable<Timestamp>,
+        door_time -> Nullable<Timestamp>,
+        response_deadline -> Timestamp,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    event_time_slots (id) {
+        id -> Uuid,
+        event_id -> Uuid,
+        starts_at -> Timestamp,
+        ends_at -> Timestamp,
+        capacity -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     event_users (id) {
         id -> Uuid,
@@ -305,6 +430,7 @@ table! {
         settled_at -> Nullable<Timestamp>,
         cloned_from_event_id -> Nullable<Uuid>,
         additional_json -> Jsonb,
+        currency -> Text,
     }
 }
 
@@ -355,6 +481,23 @@ table! {
     }
 }
 
+table! {
+    gift_cards (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        code -> Text,
+        initial_value_in_cents -> Int8,
+        balance_in_cents -> Int8,
+        currency -> Text,
+        expires_at -> Nullable<Timestamp>,
+        order_item_id -> Nullable<Uuid>,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        voided_at -> Nullable<Timestamp>,
+    }
+}
+
 table! {
     holds (id) {
         id -> Uuid,
@@ -375,6 +518,85 @@ table! {
     }
 }
 
+table! {
+    idempotency_keys (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        idempotency_key -> Text,
+        request_method -> Text,
+        request_path -> Text,
+        response_status -> Nullable<Int4>,
+        response_body -> Nullable<Text>,
+        expires_at -> Timestamp,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        request_hash -> Nullable<Text>,
+        locked_until -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    installment_plans (id) {
+        id -> Uuid,
+        order_id -> Uuid,
+        event_id -> Uuid,
+        payment_method_id -> Uuid,
+        status -> Text,
+        total_in_cents -> Int8,
+        deposit_in_cents -> Int8,
+        max_attempts -> Int4,
+        retry_interval_hours -> Int4,
+        refund_deposit_on_default -> Bool,
+        created_by -> Uuid,
+        completed_at -> Nullable<Timestamp>,
+        defaulted_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    installment_policies (id) {
+        id -> Uuid,
+        event_id -> Uuid,
+        deposit_percent -> Int4,
+        installment_count -> Int4,
+        interval_days -> Int4,
+        max_attempts -> Int4,
+        retry_interval_hours -> Int4,
+        refund_deposit_on_default -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    installments (id) {
+        id -> Uuid,
+        installment_plan_id -> Uuid,
+        amount_in_cents -> Int8,
+        due_at -> Timestamp,
+        status -> Text,
+        attempts -> Int4,
+        last_error -> Nullable<Text>,
+        payment_id -> Nullable<Uuid>,
+        paid_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    inventory_pools (id) {
+        id -> Uuid,
+        event_id -> Uuid,
+        name -> Text,
+        capacity -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     listings (id) {
         id -> Uuid,
@@ -450,6 +672,11 @@ table! {
         company_fee_in_cents -> Int8,
         client_fee_in_cents -> Int8,
         refunded_quantity -> Int8,
+        pass_id -> Nullable<Uuid>,
+        product_variant_id -> Nullable<Uuid>,
+        redeemed_quantity -> Int8,
+        tax_rule_id -> Nullable<Uuid>,
+        included_tax_in_cents -> Int8,
     }
 }
 
@@ -491,6 +718,7 @@ table! {
         platform -> Nullable<Text>,
         settlement_id -> Nullable<Uuid>,
         referrer -> Nullable<Text>,
+        currency -> Text,
     }
 }
 
@@ -575,6 +803,32 @@ table! {
         slug_id -> Nullable<Uuid>,
         google_ads_conversion_id -> Nullable<Text>,
         google_ads_conversion_labels -> Array<Text>,
+        refund_fees_on_event_cancellation -> Bool,
+    }
+}
+
+table! {
+    pass_events (id) {
+        id -> Uuid,
+        pass_id -> Uuid,
+        event_id -> Uuid,
+        ticket_type_id -> Uuid,
+        allocated_price_in_cents -> Int8,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    passes (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        name -> Text,
+        description -> Nullable<Text>,
+        price_in_cents -> Int8,
+        status -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -606,6 +860,35 @@ table! {
         updated_at -> Timestamp,
         url_nonce -> Nullable<Text>,
         refund_id -> Nullable<Uuid>,
+        currency -> Text,
+    }
+}
+
+table! {
+    product_variants (id) {
+        id -> Uuid,
+        product_id -> Uuid,
+        name -> Text,
+        price_in_cents -> Nullable<Int8>,
+        stock_quantity -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    products (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        event_id -> Nullable<Uuid>,
+        name -> Text,
+        description -> Nullable<Text>,
+        price_in_cents -> Int8,
+        per_ticket_limit -> Nullable<Int4>,
+        status -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        gift_card -> Bool,
     }
 }
 
@@ -644,6 +927,36 @@ table! {
     }
 }
 
+table! {
+    refund_policies (id) {
+        id -> Uuid,
+        event_id -> Uuid,
+        approval_type -> Text,
+        deadline_hours_before_event -> Int4,
+        retain_fees -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    refund_requests (id) {
+        id -> Uuid,
+        order_id -> Uuid,
+        event_id -> Uuid,
+        user_id -> Uuid,
+        ticket_instance_ids -> Array<Uuid>,
+        reason -> Text,
+        status -> Text,
+        reviewed_by -> Nullable<Uuid>,
+        reviewed_at -> Nullable<Timestamp>,
+        response_note -> Nullable<Text>,
+        refund_id -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     refunded_tickets (id) {
         id -> Uuid,
@@ -687,6 +1000,7 @@ table! {
         settlement_adjustment_type -> Text,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        currency -> Text,
     }
 }
 
@@ -704,6 +1018,9 @@ table! {
         settlement_entry_type -> Text,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        product_variant_id -> Nullable<Uuid>,
+        tax_in_cents -> Int8,
+        currency -> Text,
     }
 }
 
@@ -757,6 +1074,37 @@ table! {
     }
 }
 
+table! {
+    store_credit_transactions (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        organization_id -> Uuid,
+        amount_in_cents -> Int8,
+        currency -> Text,
+        order_id -> Nullable<Uuid>,
+        refund_id -> Nullable<Uuid>,
+        note -> Nullable<Text>,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    tax_rules (id) {
+        id -> Uuid,
+        name -> Text,
+        country -> Text,
+        state -> Nullable<Text>,
+        rate_percent -> Float4,
+        inclusive -> Bool,
+        applies_to_face_value -> Bool,
+        applies_to_fees -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     temporary_user_links (temporary_user_id, user_id) {
         temporary_user_id -> Uuid,
@@ -776,6 +1124,24 @@ table! {
     }
 }
 
+table! {
+    ticket_attendee_changes (id) {
+        id -> Uuid,
+        ticket_instance_id -> Uuid,
+        first_name -> Nullable<Text>,
+        last_name -> Nullable<Text>,
+        email -> Nullable<Text>,
+        birth_date -> Nullable<Date>,
+        changed_by_user_id -> Uuid,
+        fee_in_cents -> Int8,
+        payment_provider -> Nullable<Text>,
+        external_payment_reference -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        order_id -> Nullable<Uuid>,
+    }
+}
+
 table! {
     ticket_instances (id) {
         id -> Uuid,
@@ -796,6 +1162,12 @@ table! {
         check_in_source -> Nullable<Text>,
         parent_id -> Nullable<Uuid>,
         listing_id -> Nullable<Uuid>,
+        venue_seat_id -> Nullable<Uuid>,
+        event_time_slot_id -> Nullable<Uuid>,
+        redeem_secret -> Nullable<Text>,
+        attendee_email -> Nullable<Text>,
+        attendee_birth_date -> Nullable<Date>,
+        seat_event_id -> Nullable<Uuid>,
     }
 }
 
@@ -815,6 +1187,31 @@ table! {
     }
 }
 
+table! {
+    ticket_scans (id) {
+        id -> Uuid,
+        ticket_instance_id -> Uuid,
+        event_id -> Uuid,
+        direction -> Text,
+        check_in_source -> Nullable<Text>,
+        scanned_by_user_id -> Nullable<Uuid>,
+        scanned_at -> Timestamp,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        access_zone_id -> Nullable<Uuid>,
+    }
+}
+
+table! {
+    ticket_type_access_zones (id) {
+        id -> Uuid,
+        ticket_type_id -> Uuid,
+        access_zone_id -> Uuid,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     ticket_type_codes (id) {
         id -> Uuid,
@@ -853,6 +1250,12 @@ table! {
         ticket_type_type -> Varchar,
         promo_image_url -> Nullable<Text>,
         content_url -> Nullable<Text>,
+        venue_section_id -> Nullable<Uuid>,
+        inventory_pool_id -> Nullable<Uuid>,
+        max_entries -> Int4,
+        required_attendee_details -> Array<Text>,
+        attendee_name_change_fee_in_cents -> Int8,
+        attendee_details_lock_hours -> Nullable<Int4>,
     }
 }
 
@@ -919,6 +1322,29 @@ table! {
     }
 }
 
+table! {
+    venue_seats (id) {
+        id -> Uuid,
+        venue_section_id -> Uuid,
+        row_label -> Text,
+        seat_number -> Text,
+        accessible -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    venue_sections (id) {
+        id -> Uuid,
+        venue_id -> Uuid,
+        stage_id -> Nullable<Uuid>,
+        name -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     venues (id) {
         id -> Uuid,
@@ -942,6 +1368,21 @@ table! {
     }
 }
 
+table! {
+    waitlist_entries (id) {
+        id -> Uuid,
+        ticket_type_id -> Uuid,
+        user_id -> Uuid,
+        quantity -> Int4,
+        status -> Text,
+        order_id -> Nullable<Uuid>,
+        offered_at -> Nullable<Timestamp>,
+        offer_expires_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     wallets (id) {
         id -> Uuid,
@@ -956,6 +1397,10 @@ table! {
     }
 }
 
+joinable!(access_zone_users -> access_zones (access_zone_id));
+joinable!(access_zone_users -> events (event_id));
+joinable!(access_zone_users -> users (user_id));
+joinable!(access_zones -> events (event_id));
 joinable!(announcement_engagements -> announcements (announcement_id));
 joinable!(announcement_engagements -> users (user_id));
 joinable!(announcements -> organizations (organization_id));
@@ -970,12 +1415,17 @@ joinable!(collection_items -> collections (collection_id));
 joinable!(collection_items -> ticket_types (collectible_id));
 joinable!(collections -> ticket_types (featured_collectible_id));
 joinable!(collections -> users (user_id));
+joinable!(disputes -> orders (order_id));
+joinable!(disputes -> payments (payment_id));
+joinable!(disputes -> settlement_adjustments (settlement_adjustment_id));
+joinable!(disputes -> users (created_by));
 joinable!(domain_actions -> domain_events (domain_event_id));
 joinable!(domain_event_published -> domain_event_publishers (domain_event_publisher_id));
 joinable!(domain_event_published -> domain_events (domain_event_id));
 joinable!(domain_event_publishers -> organizations (organization_id));
 joinable!(domain_events -> organizations (organization_id));
 joinable!(domain_events -> users (user_id));
+joinable!(dynamic_pricing_strategies -> ticket_types (ticket_type_id));
 joinable!(event_artists -> artists (artist_id));
 joinable!(event_artists -> events (event_id));
 joinable!(event_artists -> stages (stage_id));
@@ -983,15 +1433,38 @@ joinable!(event_genres -> events (event_id));
 joinable!(event_genres -> genres (genre_id));
 joinable!(event_interest -> events (event_id));
 joinable!(event_interest -> users (user_id));
+joinable!(event_refund_batch_orders -> event_refund_batches (event_refund_batch_id));
+joinable!(event_refund_batch_orders -> orders (order_id));
+joinable!(event_refund_batch_orders -> refunds (refund_id));
+joinable!(event_refund_batches -> events (event_id));
+joinable!(event_refund_batches -> users (created_by));
 joinable!(event_report_subscribers -> events (event_id));
+joinable!(event_reschedule_responses -> event_reschedules (event_reschedule_id));
+joinable!(event_reschedule_responses -> orders (order_id));
+joinable!(event_reschedule_responses -> refunds (refund_id));
+joinable!(event_reschedule_responses -> users (user_id));
+joinable!(event_reschedules -> events (event_id));
+joinable!(event_reschedules -> users (created_by));
+joinable!(event_time_slots -> events (event_id));
 joinable!(event_users -> events (event_id));
 joinable!(event_users -> users (user_id));
 joinable!(events -> organizations (organization_id));
 joinable!(events -> venues (venue_id));
 joinable!(external_logins -> users (user_id));
 joinable!(fee_schedule_ranges -> fee_schedules (fee_schedule_id));
+joinable!(gift_cards -> order_items (order_item_id));
+joinable!(gift_cards -> organizations (organization_id));
 joinable!(holds -> events (event_id));
 joinable!(holds -> ticket_types (ticket_type_id));
+joinable!(idempotency_keys -> users (user_id));
+joinable!(installment_plans -> events (event_id));
+joinable!(installment_plans -> orders (order_id));
+joinable!(installment_plans -> payment_methods (payment_method_id));
+joinable!(installment_plans -> users (created_by));
+joinable!(installment_policies -> events (event_id));
+joinable!(installments -> installment_plans (installment_plan_id));
+joinable!(installments -> payments (payment_id));
+joinable!(inventory_pools -> events (event_id));
 joinable!(listings -> users (user_id));
 joinable!(loot_box_contents -> events (content_event_id));
 joinable!(marketplace_accounts -> users (user_id));
@@ -1000,6 +1473,9 @@ joinable!(order_items -> events (event_id));
 joinable!(order_items -> fee_schedule_ranges (fee_schedule_range_id));
 joinable!(order_items -> holds (hold_id));
 joinable!(order_items -> orders (order_id));
+joinable!(order_items -> passes (pass_id));
+joinable!(order_items -> product_variants (product_variant_id));
+joinable!(order_items -> tax_rules (tax_rule_id));
 joinable!(order_items -> ticket_pricing (ticket_pricing_id));
 joinable!(order_items -> ticket_types (ticket_type_id));
 joinable!(order_transfers -> orders (order_id));
@@ -1013,14 +1489,25 @@ joinable!(organization_users -> users (user_id));
 joinable!(organization_venues -> organizations (organization_id));
 joinable!(organization_venues -> venues (venue_id));
 joinable!(organizations -> fee_schedules (fee_schedule_id));
+joinable!(pass_events -> events (event_id));
+joinable!(pass_events -> passes (pass_id));
+joinable!(pass_events -> ticket_types (ticket_type_id));
+joinable!(passes -> organizations (organization_id));
 joinable!(payment_methods -> users (user_id));
 joinable!(payments -> orders (order_id));
 joinable!(payments -> refunds (refund_id));
 joinable!(payments -> users (created_by));
+joinable!(product_variants -> products (product_id));
+joinable!(products -> events (event_id));
+joinable!(products -> organizations (organization_id));
 joinable!(push_notification_tokens -> users (user_id));
 joinable!(rarities -> events (event_id));
 joinable!(refund_items -> order_items (order_item_id));
 joinable!(refund_items -> refunds (refund_id));
+joinable!(refund_policies -> events (event_id));
+joinable!(refund_requests -> events (event_id));
+joinable!(refund_requests -> orders (order_id));
+joinable!(refund_requests -> refunds (refund_id));
 joinable!(refunded_tickets -> order_items (order_item_id));
 joinable!(refunded_tickets -> ticket_instances (ticket_instance_id));
 joinable!(refunds -> orders (order_id));
@@ -1028,30 +1515,57 @@ joinable!(refunds -> settlements (settlement_id));
 joinable!(refunds -> users (user_id));
 joinable!(settlement_adjustments -> settlements (settlement_id));
 joinable!(settlement_entries -> events (event_id));
+joinable!(settlement_entries -> product_variants (product_variant_id));
 joinable!(settlement_entries -> settlements (settlement_id));
 joinable!(settlement_entries -> ticket_types (ticket_type_id));
 joinable!(settlements -> organizations (organization_id));
+joinable!(store_credit_transactions -> orders (order_id));
+joinable!(store_credit_transactions -> organizations (organization_id));
+joinable!(store_credit_transactions -> refunds (refund_id));
+joinable!(store_credit_transactions -> users (user_id));
 joinable!(temporary_user_links -> temporary_users (temporary_user_id));
 joinable!(temporary_user_links -> users (user_id));
+joinable!(ticket_attendee_changes -> orders (order_id));
+joinable!(ticket_attendee_changes -> ticket_instances (ticket_instance_id));
+joinable!(ticket_attendee_changes -> users (changed_by_user_id));
 joinable!(ticket_instances -> assets (asset_id));
+joinable!(ticket_instances -> events (seat_event_id));
+joinable!(ticket_instances -> event_time_slots (event_time_slot_id));
 joinable!(ticket_instances -> holds (hold_id));
 joinable!(ticket_instances -> listings (listing_id));
 joinable!(ticket_instances -> order_items (order_item_id));
+joinable!(ticket_instances -> venue_seats (venue_seat_id));
 joinable!(ticket_instances -> wallets (wallet_id));
 joinable!(ticket_pricing -> ticket_types (ticket_type_id));
+joinable!(ticket_scans -> access_zones (access_zone_id));
+joinable!(ticket_scans -> events (event_id));
+joinable!(ticket_scans -> ticket_instances (ticket_instance_id));
+joinable!(ticket_scans -> users (scanned_by_user_id));
+joinable!(ticket_type_access_zones -> access_zones (access_zone_id));
+joinable!(ticket_type_access_zones -> ticket_types (ticket_type_id));
 joinable!(ticket_type_codes -> codes (code_id));
 joinable!(ticket_type_codes -> ticket_types (ticket_type_id));
 joinable!(ticket_types -> events (event_id));
+joinable!(ticket_types -> inventory_pools (inventory_pool_id));
 joinable!(ticket_types -> rarities (rarity_id));
+joinable!(ticket_types -> venue_sections (venue_section_id));
 joinable!(transfer_tickets -> ticket_instances (ticket_instance_id));
 joinable!(transfer_tickets -> transfers (transfer_id));
 joinable!(user_genres -> genres (genre_id));
 joinable!(user_genres -> users (user_id));
+joinable!(venue_seats -> venue_sections (venue_section_id));
+joinable!(venue_sections -> stages (stage_id));
+joinable!(venue_sections -> venues (venue_id));
 joinable!(venues -> regions (region_id));
+joinable!(waitlist_entries -> orders (order_id));
+joinable!(waitlist_entries -> ticket_types (ticket_type_id));
+joinable!(waitlist_entries -> users (user_id));
 joinable!(wallets -> organizations (organization_id));
 joinable!(wallets -> users (user_id));
 
 allow_tables_to_appear_in_same_query!(
+    access_zone_users,
+    access_zones,
     analytics_page_views,
     announcement_engagements,
     announcements,
@@ -1062,21 +1576,34 @@ allow_tables_to_appear_in_same_query!(
     codes,
     collection_items,
     collections,
+    disputes,
     domain_actions,
     domain_event_published,
     domain_event_publishers,
     domain_events,
+    dynamic_pricing_strategies,
     event_artists,
     event_genres,
     event_interest,
+    event_refund_batch_orders,
+    event_refund_batches,
     event_report_subscribers,
+    event_reschedule_responses,
+    event_reschedules,
+    event_time_slots,
     event_users,
     events,
     external_logins,
     fee_schedule_ranges,
     fee_schedules,
     genres,
+    gift_cards,
     holds,
+    idempotency_keys,
+    installment_plans,
+    installment_policies,
+    installments,
+    inventory_pools,
     listings,
     loot_box_contents,
     marketplace_accounts,
@@ -1089,11 +1616,17 @@ allow_tables_to_appear_in_same_query!(
     organization_users,
     organization_venues,
     organizations,
+    pass_events,
+    passes,
     payment_methods,
     payments,
+    product_variants,
+    products,
     push_notification_tokens,
     rarities,
     refund_items,
+    refund_policies,
+    refund_requests,
     refunded_tickets,
     refunds,
     regions,
@@ -1103,16 +1636,24 @@ allow_tables_to_appear_in_same_query!(
     slugs,
     source_aliases,
     stages,
+    store_credit_transactions,
+    tax_rules,
     temporary_user_links,
     temporary_users,
+    ticket_attendee_changes,
     ticket_instances,
     ticket_pricing,
+    ticket_scans,
+    ticket_type_access_zones,
     ticket_type_codes,
     ticket_types,
     transfer_tickets,
     transfers,
     user_genres,
     users,
+    venue_seats,
+    venue_sections,
     venues,
+    waitlist_entries,
     wallets,
 );
@@ -30,6 +30,8 @@ pub struct EventBuilder<'a> {
     event_type: Option<EventTypes>,
     additional_info: Option<String>,
     top_line_info: Option<String>,
+    currency: Option<String>,
+    rotating_redeem_codes: bool,
 }
 
 impl<'a> EventBuilder<'a> {
@@ -55,6 +57,8 @@ impl<'a> EventBuilder<'a> {
             event_type: None,
             additional_info: None,
             top_line_info: None,
+            currency: None,
+            rotating_redeem_codes: false,
         }
     }
 
@@ -88,6 +92,16 @@ impl<'a> EventBuilder<'a> {
         self
     }
 
+    pub fn with_currency(mut self, currency: &str) -> Self {
+        self.currency = Some(currency.to_string());
+        self
+    }
+
+    pub fn with_rotating_redeem_codes(mut self) -> Self {
+        self.rotating_redeem_codes = true;
+        self
+    }
+
     pub fn with_status(mut self, status: EventStatus) -> Self {
         if status != EventStatus::Published {
             self.publish_date = None;
@@ -203,6 +217,14 @@ impl<'a> EventBuilder<'a> {
             attributes.is_external = Some(true);
         }
 
+        if self.currency.is_some() {
+            attributes.currency = self.currency.clone();
+        }
+
+        if self.rotating_redeem_codes {
+            attributes.rotating_redeem_codes = Some(true);
+        }
+
         let event = event.update(None, attributes, self.connection).unwrap();
 
         if self.with_tickets {
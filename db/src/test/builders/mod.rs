@@ -29,6 +29,8 @@ pub use self::stage_builder::*;
 pub use self::ticket_type_builder::*;
 pub use self::user_builder::*;
 pub use self::venue_builder::*;
+pub use self::venue_section_builder::*;
+pub use self::waitlist_entry_builder::*;
 
 mod announcement_builder;
 mod announcement_engagement_builder;
@@ -61,3 +63,5 @@ mod stage_builder;
 mod ticket_type_builder;
 mod user_builder;
 mod venue_builder;
+mod venue_section_builder;
+mod waitlist_entry_builder;
@@ -46,6 +46,7 @@ impl<'a> SettlementAdjustmentBuilder<'a> {
             SettlementAdjustmentTypes::ManualDeduction,
             self.note.clone(),
             self.amount_in_cents,
+            "USD".to_string(),
         )
         .commit(self.connection)
         .unwrap()
@@ -0,0 +1,75 @@
+use diesel::prelude::*;
+use models::*;
+use test::builders::*;
+use uuid::Uuid;
+
+pub struct VenueSectionBuilder<'a> {
+    name: String,
+    venue_id: Option<Uuid>,
+    stage_id: Option<Uuid>,
+    rows: Vec<String>,
+    seats_per_row: u32,
+    connection: &'a PgConnection,
+}
+
+impl<'a> VenueSectionBuilder<'a> {
+    pub fn new(connection: &PgConnection) -> VenueSectionBuilder {
+        let x: u32 = rand::random();
+
+        VenueSectionBuilder {
+            connection,
+            name: format!("Section {}", x).into(),
+            venue_id: None,
+            stage_id: None,
+            rows: vec![],
+            seats_per_row: 0,
+        }
+    }
+
+    pub fn with_name(mut self, name: String) -> Self {
+        self.name = name;
+        self
+    }
+
+    pub fn with_venue_id(mut self, venue_id: Uuid) -> Self {
+        self.venue_id = Some(venue_id);
+        self
+    }
+
+    pub fn with_stage_id(mut self, stage_id: Uuid) -> Self {
+        self.stage_id = Some(stage_id);
+        self
+    }
+
+    pub fn with_seats(mut self, rows: Vec<&str>, seats_per_row: u32) -> Self {
+        self.rows = rows.into_iter().map(|r| r.to_string()).collect();
+        self.seats_per_row = seats_per_row;
+        self
+    }
+
+    pub fn finish(self) -> VenueSection {
+        let venue_id = self
+            .venue_id
+            .unwrap_or_else(|| VenueBuilder::new(self.connection).finish().id);
+        let venue_section = VenueSection::create(venue_id, self.stage_id, self.name)
+            .commit(self.connection)
+            .unwrap();
+
+        let mut seats = vec![];
+        for row_label in &self.rows {
+            for seat_number in 1..=self.seats_per_row {
+                seats.push(VenueSeat::create(
+                    venue_section.id,
+                    row_label.clone(),
+                    seat_number.to_string(),
+                    false,
+                ));
+            }
+        }
+        if !seats.is_empty() {
+            NewVenueSeat::commit_multiple(&seats, self.connection).unwrap();
+        }
+
+        venue_section
+    }
+}
@@ -0,0 +1,57 @@
+use diesel::prelude::*;
+use models::*;
+use test::builders::*;
+use uuid::Uuid;
+
+pub struct WaitlistEntryBuilder<'a> {
+    ticket_type_id: Option<Uuid>,
+    user_id: Option<Uuid>,
+    quantity: i32,
+    connection: &'a PgConnection,
+}
+
+impl<'a> WaitlistEntryBuilder<'a> {
+    pub fn new(connection: &PgConnection) -> WaitlistEntryBuilder {
+        WaitlistEntryBuilder {
+            connection,
+            ticket_type_id: None,
+            user_id: None,
+            quantity: 1,
+        }
+    }
+
+    pub fn with_ticket_type_id(mut self, ticket_type_id: Uuid) -> Self {
+        self.ticket_type_id = Some(ticket_type_id);
+        self
+    }
+
+    pub fn with_user(mut self, user: &User) -> Self {
+        self.user_id = Some(user.id);
+        self
+    }
+
+    pub fn with_quantity(mut self, quantity: i32) -> Self {
+        self.quantity = quantity;
+        self
+    }
+
+    pub fn finish(self) -> WaitlistEntry {
+        let ticket_type_id = self.ticket_type_id.unwrap_or_else(|| {
+            EventBuilder::new(self.connection)
+                .with_tickets()
+                .with_ticket_pricing()
+                .finish()
+                .ticket_types(true, None, self.connection)
+                .unwrap()
+                .remove(0)
+                .id
+        });
+        let user_id = self
+            .user_id
+            .unwrap_or_else(|| UserBuilder::new(self.connection).finish().id);
+
+        WaitlistEntry::create(ticket_type_id, user_id, self.quantity)
+            .commit(self.connection)
+            .unwrap()
+    }
+}
@@ -147,6 +147,14 @@ impl TestProject {
         StageBuilder::new(&self.connection)
     }
 
+    pub fn create_venue_section(&self) -> VenueSectionBuilder {
+        VenueSectionBuilder::new(&self.connection)
+    }
+
+    pub fn create_waitlist_entry(&self) -> WaitlistEntryBuilder {
+        WaitlistEntryBuilder::new(&self.connection)
+    }
+
     pub fn create_event_artist(&self) -> EventArtistBuilder {
         EventArtistBuilder::new(&self.connection)
     }
@@ -16,3 +16,42 @@ pub mod sha1 {
         assert_eq!(sha, "3abef1a14ccecd20d6ce892cbe042ae6d74946c8");
     }
 }
+
+pub mod sha256 {
+    use hex;
+    use ring::digest;
+
+    pub fn digest(bytes: &[u8]) -> String {
+        hex::encode(digest::digest(&digest::SHA256, bytes))
+    }
+
+    #[test]
+    fn sha256_digest() {
+        let sha = digest(b"testme");
+        assert_eq!(sha, "3bcc367a3488e113dca68b67e5fa262fe4fd2df48b1b72fd3292b30358911aab");
+    }
+}
+
+pub mod pbkdf2 {
+    use hex;
+    use ring::{digest, pbkdf2};
+
+    /// Hex encoded PBKDF2-HMAC-SHA256 of `s`
+    pub fn derive(s: &str, salt: &str, iterations: u32) -> String {
+        let mut hash = [0u8; digest::SHA256_OUTPUT_LEN];
+        pbkdf2::derive(&digest::SHA256, iterations, salt.as_bytes(), s.as_bytes(), &mut hash);
+        hex::encode(hash)
+    }
+
+    #[test]
+    fn pbkdf2_derive() {
+        assert_eq!(
+            derive("password", "salt", 1),
+            "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b"
+        );
+        assert_eq!(
+            derive("password", "salt", 2),
+            "ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43"
+        );
+    }
+}
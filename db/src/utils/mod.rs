@@ -9,6 +9,8 @@ pub mod pagination;
 pub mod passwords;
 pub mod rand;
 pub mod regexes;
+pub mod signing;
 pub mod text;
+pub mod totp;
 pub use self::math::*;
 pub mod boxed_query;
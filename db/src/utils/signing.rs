@@ -0,0 +1,69 @@
+use hex;
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::signature::{self, Ed25519KeyPair};
+use untrusted;
+use utils::errors::*;
+
+const ED25519_SEED_LENGTH: usize = 32;
+
+/// Generates a hex encoded Ed25519 private key (the 32 byte seed)
+pub fn generate_private_key() -> Result<String, DatabaseError> {
+    let mut seed = vec![0; ED25519_SEED_LENGTH];
+    SystemRandom::new().fill(&mut seed)?;
+    Ok(hex::encode(seed))
+}
+
+/// The hex encoded Ed25519 public key for a hex encoded private key
+pub fn public_key(private_key: &str) -> Result<String, DatabaseError> {
+    Ok(hex::encode(key_pair(private_key)?.public_key_bytes()))
+}
+
+/// Hex encoded Ed25519 signature of `message`
+pub fn sign(message: &str, private_key: &str) -> Result<String, DatabaseError> {
+    Ok(hex::encode(key_pair(private_key)?.sign(message.as_bytes())))
+}
+
+/// Whether `signature` is a valid Ed25519 signature of `message` for the hex encoded public key
+pub fn verify(message: &str, signature: &str, public_key: &str) -> bool {
+    match (hex::decode(public_key), hex::decode(signature)) {
+        (Ok(public_key), Ok(signature)) => signature::verify(
+            &signature::ED25519,
+            untrusted::Input::from(&public_key),
+            untrusted::Input::from(message.as_bytes()),
+            untrusted::Input::from(&signature),
+        )
+        .is_ok(),
+        _ => false,
+    }
+}
+
+fn key_pair(private_key: &str) -> Result<Ed25519KeyPair, DatabaseError> {
+    let seed = hex::decode(private_key)
+        .map_err(|_| DatabaseError::new(ErrorCode::InternalError, Some("Invalid signing key".to_string())))?;
+    Ok(Ed25519KeyPair::from_seed_unchecked(untrusted::Input::from(&seed))?)
+}
+
+#[test]
+fn sign_rfc_8032_vector() {
+    // TEST 2 from RFC 8032 section 7.1
+    let private_key = "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb";
+    let public = "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c";
+    let signature = "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da\
+                     085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00";
+    assert_eq!(public_key(private_key).unwrap(), public);
+    assert_eq!(sign("r", private_key).unwrap(), signature);
+    assert!(verify("r", signature, public));
+    assert!(!verify("s", signature, public));
+}
+
+#[test]
+fn generated_keys_sign() {
+    let private_key = generate_private_key().unwrap();
+    let signature = sign("manifest", &private_key).unwrap();
+    assert!(verify("manifest", &signature, &public_key(&private_key).unwrap()));
+    assert!(!verify(
+        "manifest",
+        &signature,
+        &public_key(&generate_private_key().unwrap()).unwrap()
+    ));
+}
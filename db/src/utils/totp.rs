@@ -0,0 +1,92 @@
+use chrono::NaiveDateTime;
+use hex;
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::{constant_time, digest, hmac};
+use utils::errors::*;
+
+/// Seconds each code is valid for before the next one is displayed
+pub const TOTP_STEP_SECONDS: i64 = 30;
+/// Codes this many steps either side of the current one are accepted to allow for clock drift
+pub const TOTP_ALLOWED_DRIFT_STEPS: i64 = 1;
+pub const TOTP_DIGITS: u32 = 6;
+const TOTP_SECRET_LENGTH: usize = 20;
+
+/// Generates a hex encoded secret for deriving time-based codes
+pub fn generate_secret() -> Result<String, DatabaseError> {
+    let mut secret = vec![0; TOTP_SECRET_LENGTH];
+    SystemRandom::new().fill(&mut secret)?;
+    Ok(hex::encode(secret))
+}
+
+/// The code (RFC 6238, HMAC-SHA1) for the time step containing `at`
+pub fn code_at(secret: &str, at: NaiveDateTime) -> Result<String, DatabaseError> {
+    code_for_step(secret, at.timestamp() / TOTP_STEP_SECONDS)
+}
+
+/// Whether the code is valid at `at`, allowing for `TOTP_ALLOWED_DRIFT_STEPS` of clock drift
+pub fn verify(secret: &str, code: &str, at: NaiveDateTime) -> Result<bool, DatabaseError> {
+    let step = at.timestamp() / TOTP_STEP_SECONDS;
+    for candidate in (step - TOTP_ALLOWED_DRIFT_STEPS)..=(step + TOTP_ALLOWED_DRIFT_STEPS) {
+        let expected = code_for_step(secret, candidate)?;
+        if constant_time::verify_slices_are_equal(expected.as_bytes(), code.trim().as_bytes()).is_ok() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn code_for_step(secret: &str, step: i64) -> Result<String, DatabaseError> {
+    let secret = hex::decode(secret)
+        .map_err(|_| DatabaseError::new(ErrorCode::InternalError, Some("Invalid TOTP secret".to_string())))?;
+    let key = hmac::SigningKey::new(&digest::SHA1, &secret);
+    let signature = hmac::sign(&key, &(step as u64).to_be_bytes());
+    let hash = signature.as_ref();
+
+    // Dynamic truncation as described in RFC 4226
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = ((hash[offset] as u32 & 0x7f) << 24)
+        | ((hash[offset + 1] as u32) << 16)
+        | ((hash[offset + 2] as u32) << 8)
+        | (hash[offset + 3] as u32);
+    Ok(format!(
+        "{:0width$}",
+        binary % 10u32.pow(TOTP_DIGITS),
+        width = TOTP_DIGITS as usize
+    ))
+}
+
+#[test]
+fn code_at_rfc_6238_vectors() {
+    // "12345678901234567890", the RFC 6238 SHA1 test secret
+    let secret = "3132333435363738393031323334353637383930";
+    assert_eq!(code_at(secret, NaiveDateTime::from_timestamp(59, 0)).unwrap(), "287082");
+    assert_eq!(
+        code_at(secret, NaiveDateTime::from_timestamp(1_111_111_109, 0)).unwrap(),
+        "081804"
+    );
+    assert_eq!(
+        code_at(secret, NaiveDateTime::from_timestamp(2_000_000_000, 0)).unwrap(),
+        "279037"
+    );
+}
+
+#[test]
+fn verify_allows_drift() {
+    let secret = generate_secret().unwrap();
+    let now = NaiveDateTime::from_timestamp(1_600_000_000, 0);
+    let code = code_at(&secret, now).unwrap();
+    assert!(verify(&secret, &code, now).unwrap());
+    assert!(verify(
+        &secret,
+        &code,
+        NaiveDateTime::from_timestamp(1_600_000_000 + TOTP_STEP_SECONDS, 0)
+    )
+    .unwrap());
+    assert!(!verify(
+        &secret,
+        &code,
+        NaiveDateTime::from_timestamp(1_600_000_000 + 3 * TOTP_STEP_SECONDS, 0)
+    )
+    .unwrap());
+    assert!(!verify(&secret, "000000x", now).unwrap());
+}
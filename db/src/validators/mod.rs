@@ -1,3 +1,4 @@
+mod currency_validator;
 mod event_ids_belong_to_organization;
 mod n_date_before_m_date_validator;
 mod number_validators;
@@ -5,6 +6,7 @@ mod redemption_code_uniqueness_validator;
 mod start_date_before_end_date_validator;
 mod url_array_validator;
 
+pub use self::currency_validator::validate_currency;
 pub use self::event_ids_belong_to_organization::event_ids_belong_to_organization_validation;
 pub use self::n_date_before_m_date_validator::n_date_valid;
 pub use self::number_validators::*;
@@ -0,0 +1,246 @@
+use db::dev::TestProject;
+use db::prelude::*;
+use db::utils::errors::ErrorCode::ValidationError;
+use uuid::Uuid;
+
+#[test]
+fn set_ticket_types() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let other_event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let other_ticket_type = other_event.ticket_types(true, None, connection).unwrap().remove(0);
+    let access_zone = AccessZone::create(event.id, "VIP Lounge".to_string())
+        .commit(None, connection)
+        .unwrap();
+    assert_eq!(
+        AccessZone::find_for_event(event.id, connection).unwrap(),
+        vec![access_zone.clone()]
+    );
+
+    access_zone
+        .set_ticket_types(&[ticket_type.id], None, connection)
+        .unwrap();
+    assert_eq!(access_zone.ticket_types(connection).unwrap(), vec![ticket_type.clone()]);
+    assert!(access_zone.grants_ticket_type(ticket_type.id, connection).unwrap());
+
+    let result = access_zone.set_ticket_types(&[ticket_type.id, other_ticket_type.id], None, connection);
+    assert_eq!(result.unwrap_err().error_code, ValidationError);
+    assert_eq!(access_zone.ticket_types(connection).unwrap(), vec![ticket_type.clone()]);
+
+    access_zone.set_ticket_types(&[], None, connection).unwrap();
+    assert!(!access_zone.grants_ticket_type(ticket_type.id, connection).unwrap());
+}
+
+#[test]
+fn assign_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let door_person = project.create_user().finish();
+    let fan = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_member(&door_person, Roles::DoorPerson)
+        .finish();
+    let event = project.create_event().with_organization(&organization).finish();
+    let floor = AccessZone::create(event.id, "Floor".to_string())
+        .commit(None, connection)
+        .unwrap();
+    let lounge = AccessZone::create(event.id, "VIP Lounge".to_string())
+        .commit(None, connection)
+        .unwrap();
+    assert_eq!(
+        AccessZone::find_for_user(event.id, door_person.id, connection).unwrap(),
+        None
+    );
+
+    let result = floor.assign_user(&fan, None, connection);
+    assert_eq!(result.unwrap_err().error_code, ValidationError);
+
+    floor.assign_user(&door_person, None, connection).unwrap();
+    assert_eq!(
+        AccessZone::find_for_user(event.id, door_person.id, connection).unwrap(),
+        Some(floor.clone())
+    );
+
+    // Scanners work one zone at a time
+    lounge.assign_user(&door_person, None, connection).unwrap();
+    assert_eq!(
+        AccessZone::find_for_user(event.id, door_person.id, connection).unwrap(),
+        Some(lounge.clone())
+    );
+    assert!(floor.users(connection).unwrap().is_empty());
+
+    assert!(floor.unassign_user(door_person.id, None, connection).is_err());
+    lounge.unassign_user(door_person.id, None, connection).unwrap();
+    assert_eq!(
+        AccessZone::find_for_user(event.id, door_person.id, connection).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn scan_zone() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let door_person = project.create_user().finish();
+    let user = project.create_user().finish();
+    let event = project
+        .create_event()
+        .with_ticket_pricing()
+        .with_ticket_type_count(2)
+        .finish();
+    let mut ticket_types = event.ticket_types(true, None, connection).unwrap();
+    let vip_ticket_type = ticket_types.remove(0);
+    let ga_ticket_type = ticket_types.remove(0);
+    let lounge = AccessZone::create(event.id, "VIP Lounge".to_string())
+        .commit(None, connection)
+        .unwrap();
+    lounge
+        .set_ticket_types(&[vip_ticket_type.id], None, connection)
+        .unwrap();
+    let vip_ticket_type = vip_ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                max_entries: Some(2),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    let mut vip_order = project
+        .create_order()
+        .for_tickets(vip_ticket_type.id)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    project
+        .create_order()
+        .for_tickets(ga_ticket_type.id)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let tickets = TicketInstance::find_for_user(user.id, connection).unwrap();
+    let vip_ticket = tickets
+        .iter()
+        .find(|t| t.ticket_type(connection).unwrap().id == vip_ticket_type.id)
+        .unwrap();
+    let ga_ticket = tickets
+        .iter()
+        .find(|t| t.ticket_type(connection).unwrap().id == ga_ticket_type.id)
+        .unwrap();
+    let scan = |ticket: &TicketInstance, direction: TicketScanDirection| {
+        TicketInstance::scan_zone(
+            ticket.id,
+            ticket.redeem_key.clone().unwrap(),
+            &lounge,
+            door_person.id,
+            direction,
+            dates::now().finish(),
+            connection,
+        )
+        .unwrap()
+    };
+
+    assert_eq!(
+        scan(ga_ticket, TicketScanDirection::In),
+        RedeemResults::TicketZoneAccessDenied
+    );
+    // Must be redeemed at the gate first
+    assert_eq!(
+        scan(vip_ticket, TicketScanDirection::In),
+        RedeemResults::TicketNotCheckedIn
+    );
+    TicketInstance::redeem_ticket(
+        vip_ticket.id,
+        vip_ticket.redeem_key.clone().unwrap(),
+        door_person.id,
+        CheckInSource::Scanned,
+        connection,
+    )
+    .unwrap();
+
+    assert_eq!(
+        scan(vip_ticket, TicketScanDirection::Out),
+        RedeemResults::TicketNotCheckedIn
+    );
+    assert_eq!(
+        scan(vip_ticket, TicketScanDirection::In),
+        RedeemResults::TicketZoneEntrySuccess
+    );
+    assert_eq!(
+        scan(vip_ticket, TicketScanDirection::In),
+        RedeemResults::TicketAlreadyRedeemed
+    );
+    assert_eq!(TicketScan::zone_occupancy(&lounge, connection).unwrap(), 1);
+    assert_eq!(
+        scan(vip_ticket, TicketScanDirection::Out),
+        RedeemResults::TicketExitSuccess
+    );
+    assert_eq!(TicketScan::zone_occupancy(&lounge, connection).unwrap(), 0);
+
+    // Zone scans don't affect the gate
+    assert_eq!(TicketScan::current_occupancy(event.id, connection).unwrap(), 1);
+    assert_eq!(TicketScan::entry_count(vip_ticket.id, connection).unwrap(), 1);
+    let vip_ticket = TicketInstance::find(vip_ticket.id, connection).unwrap();
+    assert!(vip_ticket.is_inside(connection).unwrap());
+    let scans = TicketScan::find_by_ticket_instance_id(vip_ticket.id, connection).unwrap();
+    assert_eq!(
+        scans.iter().map(|s| s.access_zone_id).collect::<Vec<Option<Uuid>>>(),
+        vec![None, Some(lounge.id), Some(lounge.id)]
+    );
+
+    // Holders who have left the event can't enter the zone until they're scanned back in
+    assert_eq!(
+        TicketInstance::scan_out(
+            vip_ticket.id,
+            vip_ticket.redeem_key.clone().unwrap(),
+            door_person.id,
+            dates::now().finish(),
+            connection,
+        )
+        .unwrap(),
+        RedeemResults::TicketExitSuccess
+    );
+    assert_eq!(
+        scan(&vip_ticket, TicketScanDirection::In),
+        RedeemResults::TicketNotCheckedIn
+    );
+    assert_eq!(
+        TicketInstance::redeem_ticket(
+            vip_ticket.id,
+            vip_ticket.redeem_key.clone().unwrap(),
+            door_person.id,
+            CheckInSource::Scanned,
+            connection,
+        )
+        .unwrap(),
+        RedeemResults::TicketReentrySuccess
+    );
+
+    // Refunded tickets keep their redeemed status but no longer grant access
+    let order_item = vip_order
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+    vip_order
+        .refund(
+            &[RefundItemRequest {
+                order_item_id: order_item.id,
+                ticket_instance_id: Some(vip_ticket.id),
+            }],
+            door_person.id,
+            None,
+            false,
+            connection,
+        )
+        .unwrap();
+    assert!(vip_ticket.is_refunded(connection).unwrap());
+    assert_eq!(scan(&vip_ticket, TicketScanDirection::In), RedeemResults::TicketInvalid);
+}
@@ -671,10 +671,23 @@ fn occurred_at() {
             note_id: Uuid::new_v4(),
             order_id: Uuid::new_v4(),
             order_number: "".to_string(),
-            created_by: user.into(),
+            created_by: user.clone().into(),
             note: "note".to_string(),
             occurred_at: now,
         },
+        ActivityItem::Dispute {
+            dispute_id: Uuid::new_v4(),
+            order_id: Uuid::new_v4(),
+            order_number: "".to_string(),
+            amount_in_cents: 10,
+            reason: None,
+            status: DisputeStatus::Open,
+            outcome: None,
+            evidence_due_by: None,
+            resolved_at: None,
+            created_by: Some(user.into()),
+            occurred_at: now,
+        },
     ];
     for example in examples {
         assert_eq!(example.occurred_at().round_subsecs(4), now.round_subsecs(4));
@@ -871,5 +884,18 @@ fn verify_activity_item_data(activity_item: ActivityItem, connection: &PgConnect
             assert_eq!(expected_created_by, created_by);
             ("Note".to_string(), note_id, None)
         }
+        ActivityItem::Dispute {
+            dispute_id,
+            order_id,
+            amount_in_cents,
+            status,
+            ..
+        } => {
+            let found_dispute = Dispute::find(dispute_id, connection).unwrap();
+            assert_eq!(found_dispute.order_id, order_id);
+            assert_eq!(found_dispute.amount_in_cents, amount_in_cents);
+            assert_eq!(found_dispute.status, status);
+            ("Dispute".to_string(), dispute_id, None)
+        }
     }
 }
@@ -0,0 +1,315 @@
+use chrono::prelude::*;
+use db::dev::TestProject;
+use db::models::*;
+use db::schema::order_items;
+use db::utils::errors::ErrorCode::ValidationError;
+use diesel::prelude::*;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let order = project.create_order().for_event(&event).quantity(2).is_paid().finish();
+    let payment = order.payments(connection).unwrap().remove(0);
+    let settlement = project.create_settlement().with_organization(&organization).finish();
+    let user = project.create_user().finish();
+    let evidence_due_by = NaiveDate::from_ymd(2050, 7, 8).and_hms(9, 10, 11);
+
+    let dispute = Dispute::create(
+        order.id,
+        payment.id,
+        payment.amount,
+        Some("fraudulent".to_string()),
+        Some("dp_123".to_string()),
+        Some(evidence_due_by),
+        Some(user.id),
+    )
+    .commit(false, connection)
+    .unwrap();
+    assert_eq!(dispute.status, DisputeStatus::Open);
+    assert_eq!(dispute.outcome, None);
+    assert_eq!(dispute.evidence_due_by, Some(evidence_due_by));
+
+    // Chargeback is added to the open settlement
+    let adjustments = settlement.adjustments(connection).unwrap();
+    assert_eq!(adjustments.len(), 1);
+    assert_eq!(Some(adjustments[0].id), dispute.settlement_adjustment_id);
+    assert_eq!(
+        adjustments[0].settlement_adjustment_type,
+        SettlementAdjustmentTypes::Chargeback
+    );
+    assert_eq!(adjustments[0].amount_in_cents, payment.amount);
+
+    // Tickets are left untouched
+    for ticket in order.tickets(None, connection).unwrap() {
+        assert_eq!(ticket.status, TicketInstanceStatus::Purchased);
+    }
+
+    let domain_events = DomainEvent::find(
+        Tables::Orders,
+        Some(order.id),
+        Some(DomainEventTypes::DisputeCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+    assert_eq!(
+        Dispute::find_by_external_reference("dp_123", connection).unwrap(),
+        Some(dispute.clone())
+    );
+    assert_eq!(Dispute::find_for_order(order.id, connection).unwrap(), vec![dispute]);
+}
+
+#[test]
+fn create_with_nullified_tickets() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let order = project.create_order().for_event(&event).quantity(2).is_paid().finish();
+    let payment = order.payments(connection).unwrap().remove(0);
+
+    let dispute = Dispute::create(order.id, payment.id, payment.amount, None, None, None, None)
+        .commit(true, connection)
+        .unwrap();
+    // No settlement is open so the chargeback is not yet applied
+    assert_eq!(dispute.settlement_adjustment_id, None);
+
+    let tickets = order.tickets(None, connection).unwrap();
+    assert_eq!(tickets.len(), 2);
+    for ticket in tickets {
+        assert_eq!(ticket.status, TicketInstanceStatus::Nullified);
+    }
+}
+
+#[test]
+fn create_with_invalid_amount() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let order = project.create_order().is_paid().finish();
+    let other_order = project.create_order().is_paid().finish();
+    let payment = order.payments(connection).unwrap().remove(0);
+
+    let result =
+        Dispute::create(order.id, payment.id, payment.amount + 1, None, None, None, None).commit(false, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("amount_in_cents"));
+                assert_eq!(errors["amount_in_cents"][0].code, "dispute_amount_exceeds_payment");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let result =
+        Dispute::create(other_order.id, payment.id, payment.amount, None, None, None, None).commit(false, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("payment_id"));
+                assert_eq!(errors["payment_id"][0].code, "invalid");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn apply_pending_chargebacks() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let order = project.create_order().for_event(&event).is_paid().finish();
+    let payment = order.payments(connection).unwrap().remove(0);
+    let dispute = Dispute::create(order.id, payment.id, payment.amount, None, None, None, None)
+        .commit(false, connection)
+        .unwrap();
+    assert_eq!(dispute.settlement_adjustment_id, None);
+
+    let settlement = Settlement::process_settlement_for_organization(&organization, None, connection).unwrap();
+    let adjustments = settlement.adjustments(connection).unwrap();
+    assert_eq!(adjustments.len(), 1);
+    assert_eq!(
+        adjustments[0].settlement_adjustment_type,
+        SettlementAdjustmentTypes::Chargeback
+    );
+    let dispute = Dispute::find(dispute.id, connection).unwrap();
+    assert_eq!(dispute.settlement_adjustment_id, Some(adjustments[0].id));
+
+    // Already applied chargebacks are not added again
+    Dispute::apply_pending_chargebacks(&settlement, connection).unwrap();
+    assert_eq!(settlement.adjustments(connection).unwrap().len(), 1);
+}
+
+#[test]
+fn apply_pending_chargebacks_for_multiple_organizations() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let organization2 = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let event2 = project
+        .create_event()
+        .with_organization(&organization2)
+        .with_ticket_pricing()
+        .finish();
+    let order = project.create_order().for_event(&event).is_paid().finish();
+    let order2 = project.create_order().for_event(&event2).is_paid().finish();
+    diesel::update(order_items::table.filter(order_items::order_id.eq(order2.id)))
+        .set(order_items::order_id.eq(order.id))
+        .execute(connection)
+        .unwrap();
+    let payment = order.payments(connection).unwrap().remove(0);
+    let dispute = Dispute::create(order.id, payment.id, payment.amount, None, None, None, None)
+        .commit(false, connection)
+        .unwrap();
+
+    // The disputed amount cannot be attributed to either organization
+    let settlement = Settlement::process_settlement_for_organization(&organization, None, connection).unwrap();
+    assert!(settlement.adjustments(connection).unwrap().is_empty());
+    let dispute = Dispute::find(dispute.id, connection).unwrap();
+    assert_eq!(dispute.settlement_adjustment_id, None);
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let order = project.create_order().is_paid().finish();
+    let payment = order.payments(connection).unwrap().remove(0);
+    let dispute = Dispute::create(order.id, payment.id, payment.amount, None, None, None, None)
+        .commit(false, connection)
+        .unwrap();
+    let evidence_due_by = NaiveDate::from_ymd(2050, 7, 8).and_hms(9, 10, 11);
+
+    let dispute = dispute
+        .update(
+            DisputeEditableAttributes {
+                status: Some(DisputeStatus::UnderReview),
+                reason: Some(Some("product_not_received".to_string())),
+                evidence_due_by: Some(Some(evidence_due_by)),
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(dispute.status, DisputeStatus::UnderReview);
+    assert_eq!(dispute.reason, Some("product_not_received".to_string()));
+    assert_eq!(dispute.evidence_due_by, Some(evidence_due_by));
+
+    // Closing requires an outcome
+    assert!(dispute
+        .update(
+            DisputeEditableAttributes {
+                status: Some(DisputeStatus::Closed),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .is_err());
+}
+
+#[test]
+fn resolve() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let order = project.create_order().for_event(&event).is_paid().finish();
+    let order2 = project.create_order().for_event(&event).is_paid().finish();
+    let settlement = project.create_settlement().with_organization(&organization).finish();
+    let payment = order.payments(connection).unwrap().remove(0);
+    let payment2 = order2.payments(connection).unwrap().remove(0);
+    let dispute = Dispute::create(order.id, payment.id, payment.amount, None, None, None, None)
+        .commit(false, connection)
+        .unwrap();
+    let dispute2 = Dispute::create(order2.id, payment2.id, payment2.amount, None, None, None, None)
+        .commit(false, connection)
+        .unwrap();
+    assert_eq!(settlement.adjustments(connection).unwrap().len(), 2);
+
+    // Lost disputes keep the chargeback
+    let dispute = dispute.resolve(DisputeOutcome::Lost, None, connection).unwrap();
+    assert_eq!(dispute.status, DisputeStatus::Closed);
+    assert_eq!(dispute.outcome, Some(DisputeOutcome::Lost));
+    assert!(dispute.resolved_at.is_some());
+    assert_eq!(settlement.adjustments(connection).unwrap().len(), 2);
+    assert!(dispute.resolve(DisputeOutcome::Won, None, connection).is_err());
+
+    // Won disputes are credited back
+    let dispute2 = dispute2.resolve(DisputeOutcome::Won, None, connection).unwrap();
+    assert_eq!(dispute2.outcome, Some(DisputeOutcome::Won));
+    let adjustments = settlement.adjustments(connection).unwrap();
+    assert_eq!(adjustments.len(), 3);
+    assert!(adjustments.iter().any(
+        |a| a.settlement_adjustment_type == SettlementAdjustmentTypes::ManualCredit
+            && a.amount_in_cents == payment2.amount
+    ));
+}
+
+#[test]
+fn activity() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let order = project.create_order().is_paid().finish();
+    let payment = order.payments(connection).unwrap().remove(0);
+    let dispute = Dispute::create(
+        order.id,
+        payment.id,
+        payment.amount,
+        Some("fraudulent".to_string()),
+        None,
+        None,
+        None,
+    )
+    .commit(false, connection)
+    .unwrap();
+
+    let activity_items = ActivityItem::load_for_order(&order, connection).unwrap();
+    let dispute_items: Vec<&ActivityItem> = activity_items
+        .iter()
+        .filter(|a| match a {
+            ActivityItem::Dispute { .. } => true,
+            _ => false,
+        })
+        .collect();
+    assert_eq!(dispute_items.len(), 1);
+    assert_eq!(
+        dispute_items[0],
+        &ActivityItem::Dispute {
+            dispute_id: dispute.id,
+            order_id: order.id,
+            order_number: Order::parse_order_number(order.id),
+            amount_in_cents: payment.amount,
+            reason: Some("fraudulent".to_string()),
+            status: DisputeStatus::Open,
+            outcome: None,
+            evidence_due_by: None,
+            resolved_at: None,
+            created_by: None,
+            occurred_at: dispute.created_at,
+        }
+    );
+}
@@ -0,0 +1,206 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use db::dev::TestProject;
+use db::prelude::*;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+
+    let strategy = DynamicPricingStrategy::create(ticket_type.id, 100, 300, 10, 60)
+        .commit(None, connection)
+        .unwrap();
+    assert_eq!(strategy.ticket_type_id, ticket_type.id);
+    assert_eq!(strategy.floor_price_in_cents, 100);
+    assert_eq!(strategy.ceiling_price_in_cents, 300);
+    assert!(strategy.is_active);
+    assert_eq!(
+        DynamicPricingStrategy::find_by_ticket_type_id(ticket_type.id, connection).unwrap(),
+        Some(strategy)
+    );
+    assert!(DomainAction::upcoming_domain_action(
+        Some(Tables::TicketTypes),
+        Some(ticket_type.id),
+        DomainActionTypes::UpdateDynamicPricing,
+        connection,
+    )
+    .unwrap()
+    .is_some());
+}
+
+#[test]
+fn commit_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+
+    let result = DynamicPricingStrategy::create(ticket_type.id, 300, 100, 0, 0).commit(None, connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert!(errors.contains_key("ceiling_price_in_cents"));
+                assert!(errors.contains_key("adjustment_percent"));
+                assert!(errors.contains_key("adjustment_interval_minutes"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let strategy = DynamicPricingStrategy::create(ticket_type.id, 100, 300, 10, 60)
+        .commit(None, connection)
+        .unwrap();
+
+    let result = strategy.update(
+        DynamicPricingStrategyEditableAttributes {
+            floor_price_in_cents: Some(400),
+            ..Default::default()
+        },
+        None,
+        connection,
+    );
+    assert!(result.is_err());
+
+    let strategy = strategy
+        .update(
+            DynamicPricingStrategyEditableAttributes {
+                ceiling_price_in_cents: Some(500),
+                is_active: Some(false),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(strategy.ceiling_price_in_cents, 500);
+    assert!(!strategy.is_active);
+    assert!(DomainAction::upcoming_domain_action(
+        Some(Tables::TicketTypes),
+        Some(ticket_type.id),
+        DomainActionTypes::UpdateDynamicPricing,
+        connection,
+    )
+    .unwrap()
+    .is_none());
+}
+
+#[test]
+fn calculate_price() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let strategy = DynamicPricingStrategy::create(ticket_type.id, 100, 300, 10, 60)
+        .commit(None, connection)
+        .unwrap();
+
+    // Selling ahead of schedule
+    assert_eq!(strategy.calculate_price(200, 0.8, 0.5), 220);
+    // Selling behind schedule
+    assert_eq!(strategy.calculate_price(200, 0.2, 0.5), 180);
+    // Selling on pace
+    assert_eq!(strategy.calculate_price(200, 0.5, 0.45), 200);
+    // Clamped to the ceiling and floor
+    assert_eq!(strategy.calculate_price(290, 1.0, 0.1), 300);
+    assert_eq!(strategy.calculate_price(105, 0.0, 0.9), 100);
+}
+
+#[test]
+fn adjust_pricing() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let now = Utc::now().naive_utc();
+    let event = project
+        .create_event()
+        .with_sales_starting(now - Duration::days(10))
+        .with_sales_ending(now + Duration::days(2))
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let previous_pricing = TicketPricing::get_current_ticket_pricing(ticket_type.id, false, false, connection).unwrap();
+    assert_eq!(previous_pricing.price_in_cents, 150);
+
+    let strategy = DynamicPricingStrategy::create(ticket_type.id, 100, 300, 10, 60)
+        .commit(None, connection)
+        .unwrap();
+    DomainAction::upcoming_domain_action(
+        Some(Tables::TicketTypes),
+        Some(ticket_type.id),
+        DomainActionTypes::UpdateDynamicPricing,
+        connection,
+    )
+    .unwrap()
+    .unwrap()
+    .set_done(connection)
+    .unwrap();
+
+    // Most of the sales window has passed with little sold so the price drops
+    let new_pricing = strategy.adjust_pricing(connection).unwrap().unwrap();
+    assert_eq!(new_pricing.price_in_cents, 135);
+    assert_eq!(new_pricing.previous_ticket_pricing_id, Some(previous_pricing.id));
+    assert_eq!(new_pricing.end_date, previous_pricing.end_date);
+    assert_eq!(
+        TicketPricing::get_current_ticket_pricing(ticket_type.id, false, false, connection).unwrap(),
+        new_pricing
+    );
+    let previous_pricing = TicketPricing::find(previous_pricing.id, connection).unwrap();
+    assert_eq!(previous_pricing.end_date, new_pricing.start_date);
+    assert!(
+        DomainEvent::find(Tables::TicketPricing, Some(new_pricing.id), None, connection)
+            .unwrap()
+            .iter()
+            .any(|e| e.event_type == DomainEventTypes::TicketPricingUpdated)
+    );
+
+    // Existing cart keeps the price it was quoted
+    let order_item = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+    assert_eq!(order_item.ticket_pricing_id, Some(previous_pricing.id));
+    assert_eq!(order_item.unit_price_in_cents, 150);
+
+    let strategy = DynamicPricingStrategy::find(strategy.id, connection).unwrap();
+    assert!(strategy.last_adjusted_at.is_some());
+    let action = DomainAction::upcoming_domain_action(
+        Some(Tables::TicketTypes),
+        Some(ticket_type.id),
+        DomainActionTypes::UpdateDynamicPricing,
+        connection,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+        action.scheduled_at,
+        strategy.last_adjusted_at.unwrap() + Duration::minutes(60)
+    );
+}
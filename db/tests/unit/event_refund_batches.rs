@@ -0,0 +1,297 @@
+use db::dev::TestProject;
+use db::models::*;
+use db::utils::errors::ErrorCode::BusinessProcessError;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let order2 = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    // Unpaid carts are not refunded
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .finish();
+    let event = event.cancel(Some(user.id), connection).unwrap();
+
+    let event_refund_batch = EventRefundBatch::create(&event, user.id, connection)
+        .unwrap()
+        .commit(connection)
+        .unwrap();
+    assert_eq!(event_refund_batch.event_id, event.id);
+    assert_eq!(event_refund_batch.status, EventRefundBatchStatus::Pending);
+    assert!(event_refund_batch.refund_fees);
+    assert_eq!(event_refund_batch.created_by, user.id);
+
+    let mut order_ids: Vec<_> = event_refund_batch
+        .orders(connection)
+        .unwrap()
+        .into_iter()
+        .map(|o| o.order_id)
+        .collect();
+    order_ids.sort();
+    let mut expected = vec![order.id, order2.id];
+    expected.sort();
+    assert_eq!(order_ids, expected);
+    assert_eq!(
+        EventRefundBatch::find_for_event(event.id, connection).unwrap(),
+        vec![event_refund_batch.clone()]
+    );
+
+    let domain_events = DomainEvent::find(
+        Tables::Events,
+        Some(event.id),
+        Some(DomainEventTypes::EventRefundBatchCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+
+    let domain_actions = DomainAction::find_by_resource(
+        Some(Tables::Events),
+        Some(event.id),
+        DomainActionTypes::ProcessEventRefundBatch,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_actions.len(), 1);
+    assert_eq!(
+        domain_actions[0].payload,
+        json!({ "event_refund_batch_id": event_refund_batch.id })
+    );
+}
+
+#[test]
+fn create_uses_organization_fee_setting() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let organization = organization
+        .update(
+            OrganizationEditableAttributes {
+                refund_fees_on_event_cancellation: Some(false),
+                ..Default::default()
+            },
+            None,
+            &"".to_string(),
+            connection,
+        )
+        .unwrap();
+    assert!(!organization.refund_fees_on_event_cancellation);
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let event = event.cancel(Some(user.id), connection).unwrap();
+
+    let new_event_refund_batch = EventRefundBatch::create(&event, user.id, connection).unwrap();
+    assert!(!new_event_refund_batch.refund_fees);
+}
+
+#[test]
+fn create_requires_cancelled_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+
+    let result = EventRefundBatch::create(&event, user.id, connection)
+        .unwrap()
+        .commit(connection);
+    match result {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.error_code, BusinessProcessError),
+    }
+}
+
+#[test]
+fn create_rejects_open_batch() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let event = event.cancel(Some(user.id), connection).unwrap();
+
+    let event_refund_batch = EventRefundBatch::create(&event, user.id, connection)
+        .unwrap()
+        .commit(connection)
+        .unwrap();
+    let result = EventRefundBatch::create(&event, user.id, connection)
+        .unwrap()
+        .commit(connection);
+    match result {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.error_code, BusinessProcessError),
+    }
+
+    // A new batch can be started once the previous one completes
+    event_refund_batch.complete(connection).unwrap();
+    assert!(EventRefundBatch::create(&event, user.id, connection)
+        .unwrap()
+        .commit(connection)
+        .is_ok());
+}
+
+#[test]
+fn summary() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    for _ in 0..3 {
+        project
+            .create_order()
+            .for_event(&event)
+            .for_user(&user)
+            .quantity(1)
+            .is_paid()
+            .finish();
+    }
+    let event = event.cancel(Some(user.id), connection).unwrap();
+    let event_refund_batch = EventRefundBatch::create(&event, user.id, connection)
+        .unwrap()
+        .commit(connection)
+        .unwrap();
+    let batch_orders = event_refund_batch.orders(connection).unwrap();
+    assert_eq!(
+        event_refund_batch.summary(connection).unwrap(),
+        EventRefundBatchSummary {
+            total_orders: 3,
+            pending_orders: 3,
+            ..Default::default()
+        }
+    );
+
+    let order = Order::find(batch_orders[0].order_id, connection).unwrap();
+    let mut order_clone = order.clone();
+    let items = order.refundable_items_for_event(event.id, false, connection).unwrap();
+    let (refund, amount) = order_clone.refund(&items, user.id, None, false, connection).unwrap();
+    let refunded_order = batch_orders[0].mark_refunded(refund.id, amount, connection).unwrap();
+    assert_eq!(refunded_order.status, EventRefundBatchOrderStatus::Refunded);
+    assert_eq!(refunded_order.refund_id, Some(refund.id));
+    assert!(refunded_order.attempted_at.is_some());
+
+    let failed_order = batch_orders[1].mark_failed("Card declined", connection).unwrap();
+    assert_eq!(failed_order.status, EventRefundBatchOrderStatus::Failed);
+    assert_eq!(failed_order.error, Some("Card declined".to_string()));
+
+    assert_eq!(
+        event_refund_batch.summary(connection).unwrap(),
+        EventRefundBatchSummary {
+            total_orders: 3,
+            pending_orders: 1,
+            in_progress_orders: 0,
+            refunded_orders: 1,
+            failed_orders: 1,
+            amount_refunded_in_cents: amount,
+        }
+    );
+    assert_eq!(
+        event_refund_batch.pending_orders(10, connection).unwrap(),
+        vec![batch_orders[2].clone()]
+    );
+
+    // Orders claimed by a processing run are no longer pending
+    let in_progress_order = batch_orders[2].mark_in_progress(connection).unwrap();
+    assert_eq!(in_progress_order.status, EventRefundBatchOrderStatus::InProgress);
+    assert!(event_refund_batch.pending_orders(10, connection).unwrap().is_empty());
+    assert_eq!(event_refund_batch.summary(connection).unwrap().in_progress_orders, 1);
+}
+
+#[test]
+fn complete() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let event = event.cancel(Some(user.id), connection).unwrap();
+    let event_refund_batch = EventRefundBatch::create(&event, user.id, connection)
+        .unwrap()
+        .commit(connection)
+        .unwrap();
+
+    let event_refund_batch = event_refund_batch.mark_in_progress(connection).unwrap();
+    assert_eq!(event_refund_batch.status, EventRefundBatchStatus::InProgress);
+    assert!(event_refund_batch.completed_at.is_none());
+
+    let event_refund_batch = event_refund_batch.complete(connection).unwrap();
+    assert_eq!(event_refund_batch.status, EventRefundBatchStatus::Completed);
+    assert!(event_refund_batch.completed_at.is_some());
+
+    let domain_events = DomainEvent::find(
+        Tables::Events,
+        Some(event.id),
+        Some(DomainEventTypes::EventRefundBatchCompleted),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn retry_failed() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let event = event.cancel(Some(user.id), connection).unwrap();
+    let event_refund_batch = EventRefundBatch::create(&event, user.id, connection)
+        .unwrap()
+        .commit(connection)
+        .unwrap();
+    let batch_order = event_refund_batch.orders(connection).unwrap().remove(0);
+    batch_order.mark_failed("Card declined", connection).unwrap();
+
+    // Cannot retry while the batch is still being processed
+    let event_refund_batch = event_refund_batch.mark_in_progress(connection).unwrap();
+    match event_refund_batch.retry_failed(connection) {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.error_code, BusinessProcessError),
+    }
+
+    let event_refund_batch = event_refund_batch.complete(connection).unwrap();
+
+    let event_refund_batch = event_refund_batch.retry_failed(connection).unwrap();
+    assert_eq!(event_refund_batch.status, EventRefundBatchStatus::InProgress);
+    assert!(event_refund_batch.completed_at.is_none());
+    let summary = event_refund_batch.summary(connection).unwrap();
+    assert_eq!(summary.pending_orders, 1);
+    assert_eq!(summary.failed_orders, 0);
+
+    let domain_actions = DomainAction::find_by_resource(
+        Some(Tables::Events),
+        Some(event.id),
+        DomainActionTypes::ProcessEventRefundBatch,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_actions.len(), 2);
+}
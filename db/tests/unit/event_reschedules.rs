@@ -0,0 +1,345 @@
+use chrono::Duration;
+use db::dev::TestProject;
+use db::models::*;
+use db::utils::dates;
+use db::utils::errors::DatabaseError;
+use db::utils::errors::ErrorCode::ValidationError;
+use diesel;
+use diesel::sql_types;
+use diesel::RunQueryDsl;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project
+        .create_event()
+        .with_status(EventStatus::Published)
+        .with_ticket_pricing()
+        .finish();
+    let event_start = dates::now().add_days(30).finish();
+    let response_deadline = dates::now().add_days(7).finish();
+
+    let event_reschedule = EventReschedule::create(&event, event_start, None, None, response_deadline, Some(user.id))
+        .commit(connection)
+        .unwrap();
+    let shift = event_start - event.event_start.unwrap();
+    assert_eq!(event_reschedule.previous_event_start, event.event_start);
+    assert_eq!(event_reschedule.previous_event_end, event.event_end);
+    assert_eq!(event_reschedule.event_start, event_start);
+    assert_eq!(event_reschedule.event_end, Some(event.event_end.unwrap() + shift));
+    assert_eq!(event_reschedule.door_time, Some(event.door_time.unwrap() + shift));
+    assert_eq!(event_reschedule.response_deadline, response_deadline);
+    assert!(event_reschedule.is_response_window_open());
+
+    let event = Event::find(event.id, connection).unwrap();
+    assert_eq!(event.event_start, Some(event_start));
+    assert_eq!(event.event_end, event_reschedule.event_end);
+    assert_eq!(event.override_status, Some(EventOverrideStatus::Rescheduled));
+    assert_eq!(
+        EventReschedule::find_for_event(event.id, connection).unwrap(),
+        vec![event_reschedule.clone()]
+    );
+
+    let domain_events = DomainEvent::find(
+        Tables::Events,
+        Some(event.id),
+        Some(DomainEventTypes::EventRescheduled),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+
+    let domain_actions = DomainAction::find_by_resource(
+        Some(Tables::Events),
+        Some(event.id),
+        DomainActionTypes::SendEventRescheduledCommunications,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_actions.len(), 1);
+    assert_eq!(
+        domain_actions[0].payload,
+        json!({ "event_reschedule_id": event_reschedule.id })
+    );
+
+    // Pending transfer drips are regenerated for the new start
+    let domain_actions = DomainAction::find_by_resource(
+        Some(Tables::Events),
+        Some(event.id),
+        DomainActionTypes::RegenerateDripActions,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert!(!domain_actions.is_empty());
+}
+
+#[test]
+fn create_with_invalid_dates() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let event_start = dates::now().add_days(30).finish();
+
+    // Response deadline in the past
+    let result = EventReschedule::create(
+        &event,
+        event_start,
+        None,
+        None,
+        dates::now().add_days(-1).finish(),
+        None,
+    )
+    .commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("response_deadline"));
+                assert_eq!(errors["response_deadline"][0].code, "response_deadline_in_past");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Response deadline after the new start
+    let result = EventReschedule::create(&event, event_start, None, None, event_start + Duration::days(1), None)
+        .commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("response_deadline"));
+                assert_eq!(
+                    errors["response_deadline"][0].code,
+                    "response_deadline_after_event_start"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Event start unchanged
+    let result = EventReschedule::create(
+        &event,
+        event.event_start.unwrap(),
+        None,
+        None,
+        dates::now().add_hours(1).finish(),
+        None,
+    )
+    .commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("event_start"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Cancelled event
+    let event = event.cancel(None, connection).unwrap();
+    assert_eq!(
+        EventReschedule::create(&event, event_start, None, None, dates::now().add_days(7).finish(), None)
+            .commit(connection),
+        DatabaseError::business_process_error("Cancelled events cannot be rescheduled")
+    );
+}
+
+#[test]
+fn refund_items() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let other_event = project.create_event().with_ticket_pricing().finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let other_order = project
+        .create_order()
+        .for_event(&other_event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+    let event_reschedule = EventReschedule::create(
+        &event,
+        dates::now().add_days(30).finish(),
+        None,
+        None,
+        dates::now().add_days(7).finish(),
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let items = event_reschedule.refund_items(&order, user.id, connection).unwrap();
+    let tickets = order.tickets(None, connection).unwrap();
+    assert_eq!(items.len(), 2);
+    for item in items {
+        assert!(tickets.iter().any(|t| Some(t.id) == item.ticket_instance_id));
+    }
+
+    assert!(event_reschedule
+        .refund_items(&other_order, user.id, connection)
+        .is_err());
+
+    // Transferred tickets are refunded at the request of their new holder
+    let user2 = project.create_user().finish();
+    TicketInstance::direct_transfer(
+        &user,
+        &[tickets[0].id],
+        "nowhere",
+        TransferMessageType::Email,
+        user2.id,
+        connection,
+    )
+    .unwrap();
+    let items = event_reschedule.refund_items(&order, user.id, connection).unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].ticket_instance_id, Some(tickets[1].id));
+    let items = event_reschedule.refund_items(&order, user2.id, connection).unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].ticket_instance_id, Some(tickets[0].id));
+    assert_eq!(
+        event_reschedule.tickets_held_by(&order, user2.id, connection).unwrap(),
+        vec![TicketInstance::find(tickets[0].id, connection).unwrap()]
+    );
+}
+
+#[test]
+fn respond() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let mut order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let order2 = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+    let event_reschedule = EventReschedule::create(
+        &event,
+        dates::now().add_days(30).finish(),
+        None,
+        None,
+        dates::now().add_days(7).finish(),
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+
+    // Refund responses record the refund issued
+    assert!(EventRescheduleResponse::create(
+        event_reschedule.id,
+        order.id,
+        user.id,
+        EventRescheduleResponseTypes::Refund,
+        None
+    )
+    .commit(connection)
+    .is_err());
+    let items = event_reschedule.refund_items(&order, user.id, connection).unwrap();
+    let (refund, _) = order.refund(&items, user.id, None, false, connection).unwrap();
+    let response = EventRescheduleResponse::create(
+        event_reschedule.id,
+        order.id,
+        user.id,
+        EventRescheduleResponseTypes::Refund,
+        Some(refund.id),
+    )
+    .commit(connection)
+    .unwrap();
+    assert_eq!(response.response, EventRescheduleResponseTypes::Refund);
+    assert_eq!(response.refund_id, Some(refund.id));
+
+    // Only one response per order
+    assert_eq!(
+        event_reschedule.validate_response(order.id, user.id, connection),
+        DatabaseError::business_process_error("A response has already been recorded for this order")
+    );
+
+    let response2 = EventRescheduleResponse::create(
+        event_reschedule.id,
+        order2.id,
+        user.id,
+        EventRescheduleResponseTypes::Keep,
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    assert_eq!(
+        event_reschedule.responses(connection).unwrap(),
+        vec![response.clone(), response2]
+    );
+    assert_eq!(
+        EventRescheduleResponse::find_for_order(order.id, connection).unwrap(),
+        vec![response]
+    );
+}
+
+#[test]
+fn respond_after_deadline() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+    let event_reschedule = EventReschedule::create(
+        &event,
+        dates::now().add_days(30).finish(),
+        None,
+        None,
+        dates::now().add_days(7).finish(),
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    diesel::sql_query(
+        r#"
+        UPDATE event_reschedules
+        SET response_deadline = $2
+        WHERE id = $1;
+        "#,
+    )
+    .bind::<sql_types::Uuid, _>(event_reschedule.id)
+    .bind::<sql_types::Timestamp, _>(dates::now().add_days(-1).finish())
+    .execute(connection)
+    .unwrap();
+    let event_reschedule = EventReschedule::find(event_reschedule.id, connection).unwrap();
+    assert!(!event_reschedule.is_response_window_open());
+
+    assert_eq!(
+        EventRescheduleResponse::create(
+            event_reschedule.id,
+            order.id,
+            user.id,
+            EventRescheduleResponseTypes::Keep,
+            None
+        )
+        .commit(connection),
+        DatabaseError::business_process_error("The deadline to respond to this reschedule has passed")
+    );
+}
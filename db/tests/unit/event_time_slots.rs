@@ -0,0 +1,169 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use db::dev::TestProject;
+use db::prelude::*;
+use db::utils::errors::ErrorCode::ValidationError;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let starts_at = NaiveDate::from_ymd(2055, 7, 8).and_hms(10, 0, 0);
+
+    let time_slot = EventTimeSlot::create(event.id, starts_at, starts_at + Duration::minutes(30), 20)
+        .commit(None, connection)
+        .unwrap();
+    assert_eq!(time_slot.event_id, event.id);
+    assert_eq!(time_slot.capacity, 20);
+    assert_eq!(
+        EventTimeSlot::find_for_event(event.id, connection).unwrap(),
+        vec![time_slot]
+    );
+
+    // Slots must end after they start
+    let result = EventTimeSlot::create(event.id, starts_at, starts_at, 20).commit(None, connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("starts_at"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn create_for_interval() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let starts_at = NaiveDate::from_ymd(2055, 7, 8).and_hms(10, 0, 0);
+
+    let time_slots = EventTimeSlot::create_for_interval(
+        event.id,
+        starts_at,
+        starts_at + Duration::minutes(100),
+        30,
+        15,
+        None,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(time_slots.len(), 3);
+    assert_eq!(time_slots[0].starts_at, starts_at);
+    assert_eq!(time_slots[2].ends_at, starts_at + Duration::minutes(90));
+    assert!(time_slots.iter().all(|s| s.capacity == 15));
+
+    assert!(EventTimeSlot::create_for_interval(
+        event.id,
+        starts_at,
+        starts_at + Duration::minutes(20),
+        30,
+        15,
+        None,
+        connection,
+    )
+    .is_err());
+}
+
+#[test]
+fn availability_for_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let starts_at = NaiveDate::from_ymd(2055, 7, 8).and_hms(10, 0, 0);
+    let time_slot = EventTimeSlot::create(event.id, starts_at, starts_at + Duration::minutes(30), 5)
+        .commit(None, connection)
+        .unwrap();
+
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    cart.assign_time_slot(ticket_type.id, Some(time_slot.id), connection)
+        .unwrap();
+
+    let availability = EventTimeSlot::availability_for_event(event.id, connection).unwrap();
+    assert_eq!(availability.len(), 1);
+    assert_eq!(availability[0].id, time_slot.id);
+    assert_eq!(availability[0].available, 3);
+    assert_eq!(time_slot.allocated_count(&[], connection).unwrap(), 2);
+}
+
+#[test]
+fn is_redeemable_at() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let starts_at = NaiveDate::from_ymd(2055, 7, 8).and_hms(10, 0, 0);
+    let ends_at = starts_at + Duration::minutes(30);
+    let time_slot = EventTimeSlot::create(event.id, starts_at, ends_at, 5)
+        .commit(None, connection)
+        .unwrap();
+    let grace_period = Duration::minutes(EVENT_TIME_SLOT_GRACE_PERIOD_MINUTES);
+
+    assert!(time_slot.is_redeemable_at(starts_at));
+    assert!(time_slot.is_redeemable_at(starts_at - grace_period));
+    assert!(time_slot.is_redeemable_at(ends_at + grace_period));
+    assert!(!time_slot.is_redeemable_at(starts_at - grace_period - Duration::minutes(1)));
+    assert!(!time_slot.is_redeemable_at(ends_at + grace_period + Duration::minutes(1)));
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let starts_at = NaiveDate::from_ymd(2055, 7, 8).and_hms(10, 0, 0);
+    let time_slots = EventTimeSlot::create_for_interval(
+        event.id,
+        starts_at,
+        starts_at + Duration::minutes(60),
+        30,
+        5,
+        None,
+        connection,
+    )
+    .unwrap();
+
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    cart.assign_time_slot(ticket_type.id, Some(time_slots[0].id), connection)
+        .unwrap();
+
+    // Slots holding tickets cannot be removed
+    assert!(time_slots[0].destroy(None, connection).is_err());
+    time_slots[1].destroy(None, connection).unwrap();
+    assert_eq!(
+        EventTimeSlot::find_for_event(event.id, connection).unwrap(),
+        vec![time_slots[0].clone()]
+    );
+}
@@ -872,6 +872,30 @@ fn minutes_until_event() {
     assert_eq!(event.minutes_until_event(), Some(0));
 }
 
+#[test]
+fn clamp_scan_time() {
+    let project = TestProject::new();
+    let door_time = dates::now().add_hours(-3).finish();
+    let event_end = dates::now().add_hours(-1).finish();
+    let event = project
+        .create_event()
+        .with_door_time(door_time)
+        .with_event_start(dates::now().add_hours(-2).finish())
+        .with_event_end(event_end)
+        .finish();
+    let scanned_at = dates::now().add_minutes(-150).finish();
+    assert_eq!(event.clamp_scan_time(scanned_at), scanned_at);
+    assert_eq!(event.clamp_scan_time(dates::now().add_hours(-4).finish()), door_time);
+    assert_eq!(event.clamp_scan_time(dates::now().finish()), event_end);
+
+    // Scans can't be recorded in the future even when the event has not ended
+    let event = project
+        .create_event()
+        .with_event_start(dates::now().add_hours(-2).finish())
+        .finish();
+    assert!(event.clamp_scan_time(dates::now().add_days(1).finish()) <= Utc::now().naive_utc());
+}
+
 #[test]
 fn days_until_event() {
     let project = TestProject::new();
@@ -1753,6 +1777,8 @@ fn guest_list() {
             UpdateTicketInstanceAttributes {
                 first_name_override: Some(Some("First".to_string())),
                 last_name_override: Some(Some("Last".to_string())),
+                attendee_email: None,
+                attendee_birth_date: None,
             },
             user.id,
             &project.connection,
@@ -4768,3 +4794,62 @@ fn checked_in_users() {
     let users = Event::checked_in_users(event.id, connection).unwrap();
     assert_eq!(users.len(), 1);
 }
+
+#[test]
+fn update_currency() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    assert_eq!(event.currency, "USD");
+
+    let event = event
+        .update(
+            None,
+            EventEditableAttributes {
+                currency: Some("eur".to_string()),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    assert_eq!(event.currency, "EUR");
+
+    let result = event.update(
+        None,
+        EventEditableAttributes {
+            currency: Some("EURO".to_string()),
+            ..Default::default()
+        },
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("event.currency"));
+                assert_eq!(errors["event.currency"][0].code, "currency");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    project.create_order().for_event(&event).is_paid().finish();
+    let result = event.update(
+        None,
+        EventEditableAttributes {
+            currency: Some("GBP".to_string()),
+            ..Default::default()
+        },
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("event.currency"));
+                assert_eq!(errors["event.currency"][0].code, "cannot_change_currency_with_sales");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
@@ -0,0 +1,354 @@
+use db::dev::TestProject;
+use db::prelude::*;
+use db::utils::dates;
+use db::utils::errors::ErrorCode::{BusinessProcessError, ValidationError};
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let expires_at = dates::now().add_days(30).finish();
+
+    let gift_card = GiftCard::create(
+        organization.id,
+        5000,
+        "USD".to_string(),
+        Some(expires_at),
+        None,
+        Some(user.id),
+    )
+    .commit(connection)
+    .unwrap();
+    assert_eq!(gift_card.organization_id, organization.id);
+    assert_eq!(gift_card.code.len(), 16);
+    assert_eq!(gift_card.code, gift_card.code.to_uppercase());
+    assert_eq!(gift_card.initial_value_in_cents, 5000);
+    assert_eq!(gift_card.balance_in_cents, 5000);
+    assert_eq!(gift_card.expires_at, Some(expires_at));
+    assert!(!gift_card.is_expired());
+
+    assert_eq!(
+        GiftCard::find_by_code(&format!(" {} ", gift_card.code.to_lowercase()), connection).unwrap(),
+        gift_card
+    );
+    assert_eq!(
+        GiftCard::find_for_organization(organization.id, connection).unwrap(),
+        vec![gift_card.clone()]
+    );
+
+    let domain_events = DomainEvent::find(
+        Tables::GiftCards,
+        Some(gift_card.id),
+        Some(DomainEventTypes::GiftCardCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn create_with_invalid_fields() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+
+    let result = GiftCard::create(
+        organization.id,
+        0,
+        "usd".to_string(),
+        Some(dates::now().add_days(-1).finish()),
+        None,
+        None,
+    )
+    .commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("initial_value_in_cents"));
+                assert!(errors.contains_key("currency"));
+                assert!(errors.contains_key("expires_at"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn debit_and_credit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let gift_card = GiftCard::create(organization.id, 5000, "USD".to_string(), None, None, None)
+        .commit(connection)
+        .unwrap();
+
+    let result = gift_card.debit(6000, None, connection);
+    assert_eq!(result.unwrap_err().error_code, BusinessProcessError);
+
+    let gift_card = gift_card.debit(2000, None, connection).unwrap();
+    assert_eq!(gift_card.balance_in_cents, 3000);
+    let gift_card = gift_card.credit(500, None, connection).unwrap();
+    assert_eq!(gift_card.balance_in_cents, 3500);
+
+    let domain_events = DomainEvent::find(
+        Tables::GiftCards,
+        Some(gift_card.id),
+        Some(DomainEventTypes::GiftCardBalanceUpdated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 2);
+}
+
+#[test]
+fn add_gift_card_payment() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let mut order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .finish();
+    let total = order.calculate_total(connection).unwrap();
+    let gift_card = GiftCard::create(event.organization_id, total + 1000, "USD".to_string(), None, None, None)
+        .commit(connection)
+        .unwrap();
+
+    let payment = order.add_gift_card_payment(&gift_card, user.id, connection).unwrap();
+    assert_eq!(payment.payment_method, PaymentMethods::GiftCard);
+    assert_eq!(payment.provider, PaymentProviders::GiftCard);
+    assert_eq!(payment.amount, total);
+    assert_eq!(payment.gift_card(connection).unwrap().id, gift_card.id);
+    assert_eq!(order.status, OrderStatus::Paid);
+    assert_eq!(order.amount_due(connection).unwrap(), 0);
+    assert_eq!(GiftCard::find(gift_card.id, connection).unwrap().balance_in_cents, 1000);
+}
+
+#[test]
+fn add_gift_card_payment_partial() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let mut order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .finish();
+    let total = order.calculate_total(connection).unwrap();
+    let gift_card = GiftCard::create(event.organization_id, 500, "USD".to_string(), None, None, None)
+        .commit(connection)
+        .unwrap();
+
+    order.add_gift_card_payment(&gift_card, user.id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Draft);
+    assert_eq!(order.amount_due(connection).unwrap(), total - 500);
+    assert_eq!(GiftCard::find(gift_card.id, connection).unwrap().balance_in_cents, 0);
+    // Cart remains available for the remainder to be paid
+    assert_eq!(User::find(user.id, connection).unwrap().last_cart_id, Some(order.id));
+
+    order
+        .add_credit_card_payment(
+            user.id,
+            total - 500,
+            PaymentProviders::Stripe,
+            "ref".to_string(),
+            PaymentStatus::Completed,
+            json!(""),
+            connection,
+        )
+        .unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+}
+
+#[test]
+fn clear_cart_returns_partial_payment() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let mut order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .finish();
+    let gift_card = GiftCard::create(event.organization_id, 500, "USD".to_string(), None, None, None)
+        .commit(connection)
+        .unwrap();
+    let payment = order.add_gift_card_payment(&gift_card, user.id, connection).unwrap();
+
+    order.clear_cart(user.id, connection).unwrap();
+    assert_eq!(GiftCard::find(gift_card.id, connection).unwrap().balance_in_cents, 500);
+    let payments = order.payments(connection).unwrap();
+    assert_eq!(payments.len(), 1);
+    assert_eq!(payments[0].id, payment.id);
+    assert_eq!(payments[0].status, PaymentStatus::Cancelled);
+    assert_eq!(order.total_paid(connection).unwrap(), 0);
+
+    // Already returned payments are not credited again
+    order.reverse_stored_value_payments(Some(user.id), connection).unwrap();
+    assert_eq!(GiftCard::find(gift_card.id, connection).unwrap().balance_in_cents, 500);
+}
+
+#[test]
+fn add_gift_card_payment_invalid_gift_card() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let other_organization = project.create_organization().finish();
+    let mut order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .finish();
+
+    let gift_card = GiftCard::create(other_organization.id, 5000, "USD".to_string(), None, None, None)
+        .commit(connection)
+        .unwrap();
+    let result = order.add_gift_card_payment(&gift_card, user.id, connection);
+    assert_eq!(result.unwrap_err().error_code, BusinessProcessError);
+
+    let gift_card = GiftCard::create(event.organization_id, 5000, "EUR".to_string(), None, None, None)
+        .commit(connection)
+        .unwrap();
+    let result = order.add_gift_card_payment(&gift_card, user.id, connection);
+    assert_eq!(result.unwrap_err().error_code, BusinessProcessError);
+    assert!(order.payments(connection).unwrap().is_empty());
+}
+
+#[test]
+fn refund_restores_balance() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let mut order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .finish();
+    let total = order.calculate_total(connection).unwrap();
+    let gift_card = GiftCard::create(event.organization_id, total, "USD".to_string(), None, None, None)
+        .commit(connection)
+        .unwrap();
+    let payment = order.add_gift_card_payment(&gift_card, user.id, connection).unwrap();
+
+    let items = order.refundable_items_for_event(event.id, true, connection).unwrap();
+    let (refund, amount) = order.refund(&items, user.id, None, false, connection).unwrap();
+    let refund_payment = payment.log_refund(user.id, &refund, amount, None, connection).unwrap();
+    assert_eq!(refund_payment.payment_method, PaymentMethods::GiftCard);
+    assert_eq!(refund_payment.amount, -amount);
+    assert_eq!(
+        GiftCard::find(gift_card.id, connection).unwrap().balance_in_cents,
+        amount
+    );
+}
+
+#[test]
+fn issue_for_order() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let mut new_product = Product::create(organization.id, None, "Gift Card".to_string(), None, 2500, None);
+    new_product.gift_card = true;
+    let product = new_product
+        .commit(None, connection)
+        .unwrap()
+        .update(
+            ProductEditableAttributes {
+                status: Some(ProductStatus::Published),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    let variant = ProductVariant::create(product.id, "Digital".to_string(), None, 10)
+        .commit(None, connection)
+        .unwrap();
+
+    let mut order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .finish();
+    order
+        .update_product_quantity(user.id, variant.id, 2, connection)
+        .unwrap();
+    assert!(GiftCard::find_for_order(order.id, connection).unwrap().is_empty());
+
+    let total = order.calculate_total(connection).unwrap();
+    order
+        .add_credit_card_payment(
+            user.id,
+            total,
+            PaymentProviders::Stripe,
+            "ref".to_string(),
+            PaymentStatus::Completed,
+            json!(""),
+            connection,
+        )
+        .unwrap();
+
+    let gift_cards = GiftCard::find_for_order(order.id, connection).unwrap();
+    assert_eq!(gift_cards.len(), 2);
+    for gift_card in &gift_cards {
+        assert_eq!(gift_card.organization_id, organization.id);
+        assert_eq!(gift_card.initial_value_in_cents, 2500);
+        assert_eq!(gift_card.balance_in_cents, 2500);
+        assert_eq!(gift_card.currency, order.currency);
+        assert!(gift_card.expires_at.is_some());
+        assert!(!gift_card.is_voided());
+        assert_eq!(gift_card.purchaser_id(connection).unwrap(), Some(user.id));
+    }
+
+    // Refunding a gift card product voids one of its cards, redeemed cards can't be refunded
+    let product_item = order
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Products)
+        .unwrap();
+    let refund_items = vec![RefundItemRequest {
+        order_item_id: product_item.id,
+        ticket_instance_id: None,
+    }];
+    order.refund(&refund_items, user.id, None, false, connection).unwrap();
+    let gift_cards = GiftCard::find_for_order(order.id, connection).unwrap();
+    let voided: Vec<&GiftCard> = gift_cards.iter().filter(|g| g.is_voided()).collect();
+    assert_eq!(voided.len(), 1);
+    assert_eq!(voided[0].balance_in_cents, 0);
+
+    let remaining = gift_cards.iter().find(|g| !g.is_voided()).unwrap();
+    remaining.debit(100, None, connection).unwrap();
+    let result = order.refund(&refund_items, user.id, None, false, connection);
+    assert_eq!(result.unwrap_err().error_code, BusinessProcessError);
+}
+
+#[test]
+fn gift_card_products_require_a_value() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let mut new_product = Product::create(organization.id, None, "Gift Card".to_string(), None, 0, None);
+    new_product.gift_card = true;
+    assert!(new_product.commit(None, connection).is_err());
+}
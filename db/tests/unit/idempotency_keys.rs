@@ -0,0 +1,122 @@
+use chrono::prelude::*;
+use db::dev::TestProject;
+use db::prelude::*;
+use db::schema::idempotency_keys;
+use db::utils::dates;
+use diesel;
+use diesel::prelude::*;
+
+#[test]
+fn claim() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let other_user = project.create_user().finish();
+
+    let idempotency_key =
+        match IdempotencyKey::claim(user.id, "abc123", "POST", "/cart/checkout", "hash", connection).unwrap() {
+            IdempotencyKeyClaim::Claimed(idempotency_key) => idempotency_key,
+            IdempotencyKeyClaim::Existing(_) => panic!("Expected key to be claimed"),
+        };
+    assert_eq!(idempotency_key.user_id, user.id);
+    assert!(!idempotency_key.is_complete());
+    assert!(idempotency_key.matches_request("POST", "/cart/checkout", "hash"));
+    assert!(!idempotency_key.matches_request("PATCH", "/orders/1/refund", "hash"));
+    assert!(!idempotency_key.matches_request("POST", "/cart/checkout", "other_hash"));
+
+    // Duplicate request while the first is in progress
+    assert_eq!(
+        IdempotencyKey::claim(user.id, "abc123", "POST", "/cart/checkout", "hash", connection).unwrap(),
+        IdempotencyKeyClaim::Existing(idempotency_key.clone())
+    );
+
+    // Keys are scoped to the user
+    match IdempotencyKey::claim(other_user.id, "abc123", "POST", "/cart/checkout", "hash", connection).unwrap() {
+        IdempotencyKeyClaim::Claimed(other_key) => assert_ne!(other_key.id, idempotency_key.id),
+        IdempotencyKeyClaim::Existing(_) => panic!("Expected key to be claimed"),
+    }
+}
+
+#[test]
+fn complete() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let idempotency_key =
+        match IdempotencyKey::claim(user.id, "abc123", "POST", "/cart/checkout", "hash", connection).unwrap() {
+            IdempotencyKeyClaim::Claimed(idempotency_key) => idempotency_key,
+            IdempotencyKeyClaim::Existing(_) => panic!("Expected key to be claimed"),
+        };
+
+    let idempotency_key = idempotency_key
+        .complete(200, r#"{"status":"Paid"}"#, connection)
+        .unwrap();
+    assert!(idempotency_key.is_complete());
+    match IdempotencyKey::claim(user.id, "abc123", "POST", "/cart/checkout", "hash", connection).unwrap() {
+        IdempotencyKeyClaim::Existing(existing) => {
+            assert_eq!(existing.response_status, Some(200));
+            assert_eq!(existing.response_body, Some(r#"{"status":"Paid"}"#.to_string()));
+        }
+        IdempotencyKeyClaim::Claimed(_) => panic!("Expected existing key"),
+    }
+}
+
+#[test]
+fn release() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let idempotency_key =
+        match IdempotencyKey::claim(user.id, "abc123", "POST", "/cart/checkout", "hash", connection).unwrap() {
+            IdempotencyKeyClaim::Claimed(idempotency_key) => idempotency_key,
+            IdempotencyKeyClaim::Existing(_) => panic!("Expected key to be claimed"),
+        };
+
+    idempotency_key.release(connection).unwrap();
+    match IdempotencyKey::claim(user.id, "abc123", "POST", "/cart/checkout", "hash", connection).unwrap() {
+        IdempotencyKeyClaim::Claimed(claimed) => assert_ne!(claimed.id, idempotency_key.id),
+        IdempotencyKeyClaim::Existing(_) => panic!("Expected key to be claimed"),
+    }
+}
+
+#[test]
+fn claim_after_lock_lapses() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let idempotency_key =
+        match IdempotencyKey::claim(user.id, "abc123", "POST", "/cart/checkout", "hash", connection).unwrap() {
+            IdempotencyKeyClaim::Claimed(idempotency_key) => idempotency_key,
+            IdempotencyKeyClaim::Existing(_) => panic!("Expected key to be claimed"),
+        };
+    assert!(idempotency_key.locked_until.is_some());
+
+    // The request never stored a response, e.g. the server crashed while handling it
+    diesel::update(&idempotency_key)
+        .set(idempotency_keys::locked_until.eq(dates::now().add_seconds(-1).finish()))
+        .execute(connection)
+        .unwrap();
+
+    // A different request can't take over the key
+    match IdempotencyKey::claim(user.id, "abc123", "POST", "/cart/checkout", "other_hash", connection).unwrap() {
+        IdempotencyKeyClaim::Existing(existing) => assert_eq!(existing.id, idempotency_key.id),
+        IdempotencyKeyClaim::Claimed(_) => panic!("Expected existing key"),
+    }
+
+    // A retry of the same request does
+    let taken_over =
+        match IdempotencyKey::claim(user.id, "abc123", "POST", "/cart/checkout", "hash", connection).unwrap() {
+            IdempotencyKeyClaim::Claimed(taken_over) => taken_over,
+            IdempotencyKeyClaim::Existing(_) => panic!("Expected key to be claimed"),
+        };
+    assert_eq!(taken_over.id, idempotency_key.id);
+    assert!(taken_over.locked_until.unwrap() > Utc::now().naive_utc());
+
+    // Completed keys are replayed rather than taken over
+    let completed = taken_over.complete(200, "{}", connection).unwrap();
+    assert_eq!(completed.locked_until, None);
+    match IdempotencyKey::claim(user.id, "abc123", "POST", "/cart/checkout", "hash", connection).unwrap() {
+        IdempotencyKeyClaim::Existing(existing) => assert_eq!(existing.response_status, Some(200)),
+        IdempotencyKeyClaim::Claimed(_) => panic!("Expected existing key"),
+    }
+}
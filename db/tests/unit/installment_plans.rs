@@ -0,0 +1,315 @@
+use db::dev::TestProject;
+use db::prelude::*;
+use db::utils::dates;
+use db::utils::errors::ErrorCode::BusinessProcessError;
+use diesel::PgConnection;
+
+fn setup_order(project: &TestProject, max_attempts: i32) -> (User, Order, PaymentMethod) {
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project
+        .create_event()
+        .with_event_start(dates::now().add_days(120).finish())
+        .with_ticket_pricing()
+        .finish();
+    InstallmentPolicy::create(event.id, 25, 3, 30, max_attempts, 24, false)
+        .commit(None, connection)
+        .unwrap();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(10)
+        .finish();
+    let payment_method = PaymentMethod::create(
+        user.id,
+        PaymentProviders::Stripe,
+        true,
+        "cus_example".into(),
+        "abc".into(),
+    )
+    .commit(user.id, connection)
+    .unwrap();
+    (user, order, payment_method)
+}
+
+fn pay_deposit(order: &mut Order, plan: &InstallmentPlan, user: &User, connection: &PgConnection) {
+    order
+        .add_credit_card_payment(
+            user.id,
+            plan.deposit_in_cents,
+            PaymentProviders::Stripe,
+            "deposit".to_string(),
+            PaymentStatus::Completed,
+            json!(""),
+            connection,
+        )
+        .unwrap();
+}
+
+#[test]
+fn create_for_order() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (user, order, payment_method) = setup_order(&project, 3);
+    let total = order.amount_due(connection).unwrap();
+
+    let plan = InstallmentPlan::create_for_order(&order, &payment_method, user.id, connection).unwrap();
+    assert_eq!(plan.status, InstallmentPlanStatus::Pending);
+    assert_eq!(plan.total_in_cents, total);
+    assert_eq!(plan.deposit_in_cents, total * 25 / 100);
+    assert_eq!(plan.payment_method_id, payment_method.id);
+    assert_eq!(order.amount_due(connection).unwrap(), plan.deposit_in_cents);
+
+    let installments = plan.installments(connection).unwrap();
+    assert_eq!(installments.len(), 3);
+    assert_eq!(
+        installments.iter().map(|i| i.amount_in_cents).sum::<i64>(),
+        total - plan.deposit_in_cents
+    );
+    assert!(installments[0].due_at < installments[1].due_at);
+    assert!(installments[1].due_at < installments[2].due_at);
+    assert!(installments.iter().all(|i| i.status == InstallmentStatus::Scheduled));
+
+    let domain_events = DomainEvent::find(
+        Tables::InstallmentPlans,
+        Some(plan.id),
+        Some(DomainEventTypes::InstallmentPlanCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+
+    // Recreating replaces the pending plan
+    let new_plan = InstallmentPlan::create_for_order(&order, &payment_method, user.id, connection).unwrap();
+    assert_ne!(new_plan.id, plan.id);
+    assert_eq!(
+        InstallmentPlan::find_for_order(order.id, connection).unwrap(),
+        Some(new_plan)
+    );
+
+    InstallmentPlan::remove_pending_for_order(order.id, connection).unwrap();
+    assert_eq!(InstallmentPlan::find_for_order(order.id, connection).unwrap(), None);
+    assert_eq!(order.amount_due(connection).unwrap(), total);
+}
+
+#[test]
+fn create_for_order_unavailable() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let payment_method = PaymentMethod::create(
+        user.id,
+        PaymentProviders::Stripe,
+        true,
+        "cus_example".into(),
+        "abc".into(),
+    )
+    .commit(user.id, connection)
+    .unwrap();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(10)
+        .finish();
+
+    // No policy for the event
+    let result = InstallmentPlan::create_for_order(&order, &payment_method, user.id, connection);
+    assert_eq!(result.unwrap_err().error_code, BusinessProcessError);
+
+    // Installments would fall due after the event starts
+    InstallmentPolicy::create(event.id, 25, 3, 30, 3, 24, false)
+        .commit(None, connection)
+        .unwrap();
+    let result = InstallmentPlan::create_for_order(&order, &payment_method, user.id, connection);
+    assert_eq!(result.unwrap_err().error_code, BusinessProcessError);
+
+    // Payment method belongs to someone else
+    let (other_user, other_order, _) = setup_order(&project, 3);
+    let result = InstallmentPlan::create_for_order(&other_order, &payment_method, other_user.id, connection);
+    assert_eq!(result.unwrap_err().error_code, BusinessProcessError);
+}
+
+#[test]
+fn deposit_activates_plan_and_locks_tickets() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (user, mut order, payment_method) = setup_order(&project, 3);
+    let plan = InstallmentPlan::create_for_order(&order, &payment_method, user.id, connection).unwrap();
+
+    pay_deposit(&mut order, &plan, &user, connection);
+    assert_eq!(order.status, OrderStatus::Paid);
+    let plan = InstallmentPlan::find(plan.id, connection).unwrap();
+    assert_eq!(plan.status, InstallmentPlanStatus::Active);
+    let domain_actions = DomainAction::find_by_resource(
+        Some(Tables::InstallmentPlans),
+        Some(plan.id),
+        DomainActionTypes::ProcessInstallmentPayment,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_actions.len(), 3);
+
+    let ticket_ids = TicketInstance::find_ids_for_order(order.id, connection).unwrap();
+    assert!(InstallmentPlan::has_outstanding_balance(&ticket_ids, connection).unwrap());
+    let result = TicketInstance::create_transfer(&user, &ticket_ids[0..1], None, None, false, connection);
+    assert_eq!(result.unwrap_err().error_code, BusinessProcessError);
+    let ticket = TicketInstance::find(ticket_ids[0], connection).unwrap();
+    let result = TicketInstance::redeem_ticket(
+        ticket.id,
+        ticket.redeem_key.clone().unwrap(),
+        user.id,
+        CheckInSource::GuestList,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(result, RedeemResults::TicketPaymentOutstanding);
+}
+
+#[test]
+fn record_payment() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (user, mut order, payment_method) = setup_order(&project, 3);
+    let total = order.calculate_total(connection).unwrap();
+    let plan = InstallmentPlan::create_for_order(&order, &payment_method, user.id, connection).unwrap();
+    pay_deposit(&mut order, &plan, &user, connection);
+
+    for (i, installment) in plan.installments(connection).unwrap().iter().enumerate() {
+        let payment = installment
+            .record_payment(PaymentProviders::Stripe, format!("charge-{}", i), json!(""), connection)
+            .unwrap();
+        assert_eq!(payment.amount, installment.amount_in_cents);
+        assert_eq!(payment.status, PaymentStatus::Completed);
+        let installment = Installment::find(installment.id, connection).unwrap();
+        assert_eq!(installment.status, InstallmentStatus::Paid);
+        assert_eq!(installment.payment_id, Some(payment.id));
+        assert!(installment
+            .record_payment(PaymentProviders::Stripe, "again".to_string(), json!(""), connection)
+            .is_err());
+    }
+
+    let plan = InstallmentPlan::find(plan.id, connection).unwrap();
+    assert_eq!(plan.status, InstallmentPlanStatus::Completed);
+    assert!(plan.completed_at.is_some());
+    assert_eq!(plan.amount_paid(connection).unwrap(), total);
+    assert_eq!(order.total_paid(connection).unwrap(), total);
+    let ticket_ids = TicketInstance::find_ids_for_order(order.id, connection).unwrap();
+    assert!(!InstallmentPlan::has_outstanding_balance(&ticket_ids, connection).unwrap());
+}
+
+#[test]
+fn record_failed_attempt_and_default() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (user, mut order, payment_method) = setup_order(&project, 2);
+    let plan = InstallmentPlan::create_for_order(&order, &payment_method, user.id, connection).unwrap();
+    pay_deposit(&mut order, &plan, &user, connection);
+    let plan = InstallmentPlan::find(plan.id, connection).unwrap();
+    let installment = plan.installments(connection).unwrap().remove(0);
+
+    let installment = installment.record_failed_attempt("Card declined", connection).unwrap();
+    assert_eq!(installment.status, InstallmentStatus::Scheduled);
+    assert_eq!(installment.attempts, 1);
+    assert_eq!(installment.last_error, Some("Card declined".to_string()));
+    let domain_actions = DomainAction::find_by_resource(
+        Some(Tables::InstallmentPlans),
+        Some(plan.id),
+        DomainActionTypes::ProcessInstallmentPayment,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_actions.len(), 4);
+
+    let installment = installment.record_failed_attempt("Card declined", connection).unwrap();
+    assert_eq!(installment.status, InstallmentStatus::Failed);
+    assert_eq!(installment.attempts, 2);
+    let domain_actions = DomainAction::find_by_resource(
+        Some(Tables::InstallmentPlans),
+        Some(plan.id),
+        DomainActionTypes::DefaultInstallmentPlan,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_actions.len(), 1);
+
+    let ticket_ids = TicketInstance::find_ids_for_order(order.id, connection).unwrap();
+    let plan = plan.default(None, connection).unwrap();
+    assert_eq!(plan.status, InstallmentPlanStatus::Defaulted);
+    assert!(plan.defaulted_at.is_some());
+    assert_eq!(plan.amount_paid(connection).unwrap(), plan.deposit_in_cents);
+    for ticket_id in ticket_ids {
+        assert_eq!(
+            TicketInstance::find(ticket_id, connection).unwrap().status,
+            TicketInstanceStatus::Nullified
+        );
+    }
+    assert!(plan.default(None, connection).is_err());
+}
+
+#[test]
+fn refund_order() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (user, mut order, payment_method) = setup_order(&project, 3);
+    let plan = InstallmentPlan::create_for_order(&order, &payment_method, user.id, connection).unwrap();
+    pay_deposit(&mut order, &plan, &user, connection);
+    let plan = InstallmentPlan::find(plan.id, connection).unwrap();
+    let installment = plan.installments(connection).unwrap().remove(0);
+    installment
+        .record_payment(PaymentProviders::Stripe, "charge-0".to_string(), json!(""), connection)
+        .unwrap();
+    let collected = plan.amount_paid(connection).unwrap();
+
+    // Active plans can't be partially refunded
+    let items: Vec<RefundItemRequest> = order
+        .refundable_items_for_event(plan.event_id, false, connection)
+        .unwrap()
+        .into_iter()
+        .take(1)
+        .collect();
+    let (_, amount) = order.refund(&items, user.id, None, false, connection).unwrap();
+    let result = plan.refund_order(&order, amount, Some(user.id), connection);
+    assert_eq!(result.unwrap_err().error_code, BusinessProcessError);
+
+    let items = order
+        .refundable_items_for_event(plan.event_id, true, connection)
+        .unwrap();
+    let (_, amount) = order.refund(&items, user.id, None, false, connection).unwrap();
+    assert!(amount > collected);
+    let refund_due = plan.refund_order(&order, amount, Some(user.id), connection).unwrap();
+    assert_eq!(refund_due, collected);
+    let plan = InstallmentPlan::find(plan.id, connection).unwrap();
+    assert_eq!(plan.status, InstallmentPlanStatus::Cancelled);
+    assert_eq!(order.refund_allocations(refund_due, connection).unwrap().len(), 2);
+}
+
+#[test]
+fn cancel_for_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (user, mut order, payment_method) = setup_order(&project, 3);
+    let plan = InstallmentPlan::create_for_order(&order, &payment_method, user.id, connection).unwrap();
+    pay_deposit(&mut order, &plan, &user, connection);
+
+    Event::find(plan.event_id, connection)
+        .unwrap()
+        .cancel(Some(user.id), connection)
+        .unwrap();
+    let plan = InstallmentPlan::find(plan.id, connection).unwrap();
+    assert_eq!(plan.status, InstallmentPlanStatus::Cancelled);
+    let domain_events = DomainEvent::find(
+        Tables::InstallmentPlans,
+        Some(plan.id),
+        Some(DomainEventTypes::InstallmentPlanCancelled),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+    assert!(plan.cancel(None, connection).is_err());
+}
@@ -0,0 +1,93 @@
+use db::dev::TestProject;
+use db::models::*;
+use db::utils::errors::ErrorCode::ValidationError;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let user = project.create_user().finish();
+
+    let installment_policy = InstallmentPolicy::create(event.id, 20, 3, 30, 3, 24, true)
+        .commit(Some(user.id), connection)
+        .unwrap();
+    assert_eq!(installment_policy.event_id, event.id);
+    assert_eq!(installment_policy.deposit_percent, 20);
+    assert_eq!(installment_policy.installment_count, 3);
+    assert_eq!(installment_policy.interval_days, 30);
+    assert_eq!(installment_policy.max_attempts, 3);
+    assert_eq!(installment_policy.retry_interval_hours, 24);
+    assert!(installment_policy.refund_deposit_on_default);
+    assert_eq!(
+        InstallmentPolicy::find_for_event(event.id, connection).unwrap(),
+        Some(installment_policy)
+    );
+
+    let domain_events = DomainEvent::find(
+        Tables::Events,
+        Some(event.id),
+        Some(DomainEventTypes::InstallmentPolicyCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn create_with_invalid_fields() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+
+    let result = InstallmentPolicy::create(event.id, 100, 0, 30, 0, 24, false).commit(None, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(errors["deposit_percent"][0].code, "deposit_percent_too_high");
+                assert_eq!(errors["installment_count"][0].code, "number_must_be_positive");
+                assert_eq!(errors["max_attempts"][0].code, "number_must_be_positive");
+                assert!(!errors.contains_key("interval_days"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let user = project.create_user().finish();
+    let installment_policy = InstallmentPolicy::create(event.id, 20, 3, 30, 3, 24, false)
+        .commit(None, connection)
+        .unwrap();
+
+    let installment_policy = installment_policy
+        .update(
+            InstallmentPolicyEditableAttributes {
+                deposit_percent: Some(50),
+                installment_count: Some(2),
+                ..Default::default()
+            },
+            Some(user.id),
+            connection,
+        )
+        .unwrap();
+    assert_eq!(installment_policy.deposit_percent, 50);
+    assert_eq!(installment_policy.installment_count, 2);
+    assert_eq!(installment_policy.interval_days, 30);
+
+    assert!(installment_policy
+        .update(
+            InstallmentPolicyEditableAttributes {
+                deposit_percent: Some(0),
+                ..Default::default()
+            },
+            Some(user.id),
+            connection,
+        )
+        .is_err());
+}
@@ -0,0 +1,182 @@
+use db::dev::TestProject;
+use db::prelude::*;
+use db::utils::errors::ErrorCode::ValidationError;
+use diesel::PgConnection;
+
+fn pooled_ticket_types(event: &Event, pool: &InventoryPool, connection: &PgConnection) -> Vec<TicketType> {
+    event
+        .ticket_types(true, None, connection)
+        .unwrap()
+        .into_iter()
+        .map(|ticket_type| {
+            ticket_type
+                .update(
+                    TicketTypeEditableAttributes {
+                        inventory_pool_id: Some(Some(pool.id)),
+                        ..Default::default()
+                    },
+                    None,
+                    connection,
+                )
+                .unwrap()
+        })
+        .collect()
+}
+
+fn reserve(project: &TestProject, ticket_type: &TicketType, quantity: u32) -> Result<Order, DatabaseError> {
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )?;
+    Ok(cart)
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+
+    let pool = InventoryPool::create(event.id, "Floor".to_string(), 500)
+        .commit(None, connection)
+        .unwrap();
+    assert_eq!(pool.event_id, event.id);
+    assert_eq!(pool.capacity, 500);
+    assert_eq!(InventoryPool::find_for_event(event.id, connection).unwrap(), vec![pool]);
+
+    assert!(InventoryPool::create(event.id, "Floor".to_string(), -1)
+        .commit(None, connection)
+        .is_err());
+}
+
+#[test]
+fn ticket_type_must_belong_to_pool_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().finish();
+    let other_event = project.create_event().finish();
+    let pool = InventoryPool::create(other_event.id, "Floor".to_string(), 5)
+        .commit(None, connection)
+        .unwrap();
+
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let result = ticket_type.update(
+        TicketTypeEditableAttributes {
+            inventory_pool_id: Some(Some(pool.id)),
+            ..Default::default()
+        },
+        None,
+        connection,
+    );
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("inventory_pool_id"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn shared_availability() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_ticket_type_count(2)
+        .with_ticket_pricing()
+        .finish();
+    let pool = InventoryPool::create(event.id, "Floor".to_string(), 5)
+        .commit(None, connection)
+        .unwrap();
+    let ticket_types = pooled_ticket_types(&event, &pool, connection);
+    assert_eq!(ticket_types[0].valid_available_ticket_count(connection).unwrap(), 5);
+    assert_eq!(ticket_types[1].valid_available_ticket_count(connection).unwrap(), 5);
+
+    reserve(&project, &ticket_types[0], 3).unwrap();
+    assert_eq!(pool.allocated_count(connection).unwrap(), 3);
+    assert_eq!(ticket_types[0].valid_available_ticket_count(connection).unwrap(), 2);
+    assert_eq!(ticket_types[1].valid_available_ticket_count(connection).unwrap(), 2);
+
+    // Reservations cannot exceed the pool even though the ticket type has tickets left
+    assert!(reserve(&project, &ticket_types[1], 3).is_err());
+    reserve(&project, &ticket_types[1], 2).unwrap();
+    assert_eq!(
+        ticket_types[0].status(false, connection).unwrap(),
+        TicketTypeStatus::SoldOut
+    );
+    assert_eq!(
+        ticket_types[1].status(false, connection).unwrap(),
+        TicketTypeStatus::SoldOut
+    );
+
+    // Capacity cannot drop below what has already been allocated
+    assert!(pool
+        .update(
+            InventoryPoolEditableAttributes {
+                capacity: Some(4),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .is_err());
+    let pool = pool
+        .update(
+            InventoryPoolEditableAttributes {
+                capacity: Some(6),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(pool.available_count(connection).unwrap(), 1);
+    assert_eq!(ticket_types[1].valid_available_ticket_count(connection).unwrap(), 1);
+}
+
+#[test]
+fn ticket_count_report() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_ticket_type_count(2)
+        .with_ticket_pricing()
+        .finish();
+    let pool = InventoryPool::create(event.id, "Floor".to_string(), 5)
+        .commit(None, connection)
+        .unwrap();
+    let ticket_types = pooled_ticket_types(&event, &pool, connection);
+    reserve(&project, &ticket_types[0], 3).unwrap();
+
+    let counts = Report::ticket_count_report(Some(event.id), None, connection)
+        .unwrap()
+        .counts;
+    for ticket_type in &ticket_types {
+        let row = counts
+            .iter()
+            .find(|r| r.ticket_type_id == Some(ticket_type.id))
+            .unwrap();
+        assert_eq!(row.available_for_purchase_count, 2);
+    }
+
+    // Event totals count the remaining pool capacity once
+    let counts = TicketCountRow::fetch(Some(event.id), None, true, false, connection).unwrap();
+    assert_eq!(counts.len(), 1);
+    assert_eq!(counts[0].available_for_purchase_count, 2);
+}
@@ -1,3 +1,4 @@
+pub mod access_zones;
 pub mod activities;
 pub mod announcement_engagements;
 pub mod announcements;
@@ -10,20 +11,30 @@ pub mod collections;
 pub mod communication;
 pub mod comps;
 pub mod concerns;
+pub mod disputes;
 pub mod domain_actions;
 pub mod domain_event_publishers;
 pub mod domain_events;
+pub mod dynamic_pricing_strategies;
 pub mod event_artists;
 pub mod event_interest;
+pub mod event_refund_batches;
 pub mod event_report_subscribers;
+pub mod event_reschedules;
+pub mod event_time_slots;
 pub mod event_users;
 pub mod events;
 pub mod external_logins;
 pub mod fee_schedule_ranges;
 pub mod fee_schedules;
 pub mod genres;
+pub mod gift_cards;
 pub mod global;
 pub mod holds;
+pub mod idempotency_keys;
+pub mod installment_plans;
+pub mod installment_policies;
+pub mod inventory_pools;
 pub mod notes;
 pub mod order_items;
 pub mod orders;
@@ -33,10 +44,14 @@ pub mod organization_users;
 pub mod organization_venues;
 pub mod organizations;
 pub mod paging;
+pub mod passes;
 pub mod payment_methods;
 pub mod payments;
+pub mod products;
 pub mod push_notification_tokens;
 pub mod refund_items;
+pub mod refund_policies;
+pub mod refund_requests;
 pub mod refunded_tickets;
 pub mod refunds;
 pub mod regions;
@@ -47,12 +62,18 @@ pub mod settlement_entries;
 pub mod settlements;
 pub mod slugs;
 pub mod stages;
+pub mod store_credit_transactions;
+pub mod tax_rules;
 pub mod temporary_users;
 pub mod ticket_instances;
 pub mod ticket_pricing;
+pub mod ticket_scans;
 pub mod ticket_type_codes;
 pub mod ticket_types;
 pub mod transfer_tickets;
 pub mod transfers;
 pub mod users;
+pub mod venue_seats;
+pub mod venue_sections;
 pub mod venues;
+pub mod waitlist_entries;
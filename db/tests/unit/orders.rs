@@ -3556,6 +3556,7 @@ fn details() {
             code_type: None,
             pending_transfer_id: None,
             discount_price_in_cents: None,
+            seat_label: None,
         },
         OrderDetailsLineItem {
             ticket_instance_id: Some(ticket.id),
@@ -3576,6 +3577,7 @@ fn details() {
             code_type: None,
             pending_transfer_id: None,
             discount_price_in_cents: None,
+            seat_label: None,
         },
     ];
 
@@ -3599,6 +3601,7 @@ fn details() {
         code_type: None,
         pending_transfer_id: None,
         discount_price_in_cents: None,
+        seat_label: None,
     });
 
     let order_details = cart.details(&vec![organization.id], user2.id, connection).unwrap();
@@ -3645,6 +3648,7 @@ fn details() {
             code_type: None,
             pending_transfer_id: None,
             discount_price_in_cents: None,
+            seat_label: None,
         },
         OrderDetailsLineItem {
             ticket_instance_id: Some(ticket.id),
@@ -3665,6 +3669,7 @@ fn details() {
             code_type: None,
             pending_transfer_id: None,
             discount_price_in_cents: None,
+            seat_label: None,
         },
     ];
 
@@ -3688,6 +3693,7 @@ fn details() {
         code_type: None,
         pending_transfer_id: None,
         discount_price_in_cents: None,
+        seat_label: None,
     });
 
     let order_details = cart.details(&vec![organization.id], user2.id, connection).unwrap();
@@ -3748,6 +3754,7 @@ fn details() {
         code_type: None,
         pending_transfer_id: None,
         discount_price_in_cents: None,
+        seat_label: None,
     }];
 
     let order_details = box_office_order
@@ -3797,6 +3804,7 @@ fn details() {
             code_type: None,
             pending_transfer_id: None,
             discount_price_in_cents: None,
+            seat_label: None,
         },
         OrderDetailsLineItem {
             ticket_instance_id: None,
@@ -3817,6 +3825,7 @@ fn details() {
             code_type: None,
             pending_transfer_id: None,
             discount_price_in_cents: None,
+            seat_label: None,
         },
     ];
     let order_details = new_order.details(&vec![organization.id], user.id, connection).unwrap();
@@ -3873,6 +3882,7 @@ fn details() {
             code_type: None,
             pending_transfer_id: None,
             discount_price_in_cents: None,
+            seat_label: None,
         },
         OrderDetailsLineItem {
             ticket_instance_id: None,
@@ -3893,6 +3903,7 @@ fn details() {
             code_type: None,
             pending_transfer_id: None,
             discount_price_in_cents: None,
+            seat_label: None,
         },
     ];
     let order_details = new_order.details(&vec![organization.id], user.id, connection).unwrap();
@@ -3919,6 +3930,7 @@ fn details() {
             code_type: None,
             pending_transfer_id: None,
             discount_price_in_cents: None,
+            seat_label: None,
         },
         OrderDetailsLineItem {
             ticket_instance_id: None,
@@ -3939,6 +3951,7 @@ fn details() {
             code_type: None,
             pending_transfer_id: None,
             discount_price_in_cents: None,
+            seat_label: None,
         },
     ];
     let order_details = new_order2
@@ -3982,6 +3995,7 @@ fn details() {
             code_type: None,
             pending_transfer_id: None,
             discount_price_in_cents: None,
+            seat_label: None,
         },
         OrderDetailsLineItem {
             ticket_instance_id: None,
@@ -4002,6 +4016,7 @@ fn details() {
             code_type: None,
             pending_transfer_id: None,
             discount_price_in_cents: None,
+            seat_label: None,
         },
     ];
     let order_details = new_order2
@@ -4031,6 +4046,7 @@ fn details() {
             code_type: None,
             pending_transfer_id: None,
             discount_price_in_cents: None,
+            seat_label: None,
         },
         OrderDetailsLineItem {
             ticket_instance_id: None,
@@ -4051,6 +4067,7 @@ fn details() {
             code_type: None,
             pending_transfer_id: None,
             discount_price_in_cents: None,
+            seat_label: None,
         },
     ];
     let order_details = new_order2
@@ -5112,6 +5129,78 @@ fn add_external_payment() {
     );
 }
 
+#[test]
+fn split_payment_refund_allocations() {
+    let project = TestProject::new();
+    let conn = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let mut cart = Order::find_or_create_cart(&user, conn).unwrap();
+    let ticket = &event.ticket_types(true, None, conn).unwrap()[0];
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket.id,
+            quantity: 10,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        conn,
+    )
+    .unwrap();
+    assert_eq!(cart.calculate_total(conn).unwrap(), 2000);
+
+    let cash_payment = cart
+        .add_external_payment(Some("cash".to_string()), ExternalPaymentType::Cash, user.id, 1500, conn)
+        .unwrap();
+    // Cart remains available until the remainder is paid
+    assert_eq!(cart.amount_due(conn).unwrap(), 500);
+    assert_eq!(User::find(user.id, conn).unwrap().last_cart_id, Some(cart.id));
+
+    let card_payment = cart
+        .add_credit_card_payment(
+            user.id,
+            500,
+            PaymentProviders::Stripe,
+            "card".to_string(),
+            PaymentStatus::Completed,
+            json!(""),
+            conn,
+        )
+        .unwrap();
+    assert_eq!(cart.status, OrderStatus::Paid);
+    assert_eq!(User::find(user.id, conn).unwrap().last_cart_id, None);
+
+    let allocations = |cart: &Order, amount: i64| {
+        cart.refund_allocations(amount, conn)
+            .unwrap()
+            .into_iter()
+            .map(|(payment, amount)| (payment.id, amount))
+            .collect::<Vec<(Uuid, i64)>>()
+    };
+    assert_eq!(
+        allocations(&cart, 1000),
+        vec![(cash_payment.id, 750), (card_payment.id, 250)]
+    );
+    // Rounding remainder goes to the earliest payment with a balance left
+    assert_eq!(
+        allocations(&cart, 1001),
+        vec![(cash_payment.id, 751), (card_payment.id, 250)]
+    );
+    assert_eq!(
+        allocations(&cart, 2000),
+        vec![(cash_payment.id, 1500), (card_payment.id, 500)]
+    );
+    assert!(cart.refund_allocations(2001, conn).is_err());
+
+    // Previously refunded amounts reduce the balance left on each payment
+    let refund_items = cart.refundable_items_for_event(event.id, false, conn).unwrap();
+    let (refund, _) = cart.refund(&refund_items, user.id, None, false, conn).unwrap();
+    cash_payment.log_refund(user.id, &refund, 1500, None, conn).unwrap();
+    assert_eq!(allocations(&cart, 500), vec![(card_payment.id, 500)]);
+}
+
 #[test]
 fn add_external_payment_for_expired_code() {
     let project = TestProject::new();
@@ -6587,6 +6676,263 @@ pub fn additional_fee() {
     assert_eq!(fees_item.unit_price_in_cents, 10050);
 }
 
+#[test]
+fn assign_seats() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let venue_section = project
+        .create_venue_section()
+        .with_venue_id(venue.id)
+        .with_seats(vec!["A"], 3)
+        .finish();
+    let seats = venue_section.seats(connection).unwrap();
+    let event = project
+        .create_event()
+        .with_venue(&venue)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event
+        .ticket_types(true, None, connection)
+        .unwrap()
+        .remove(0)
+        .update(
+            TicketTypeEditableAttributes {
+                venue_section_id: Some(Some(venue_section.id)),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+
+    // Best available seats are assigned when none are selected
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    cart.assign_seats(ticket_type.id, &[], connection).unwrap();
+    let mut seat_ids: Vec<Option<Uuid>> = cart
+        .tickets(Some(ticket_type.id), connection)
+        .unwrap()
+        .into_iter()
+        .map(|t| t.venue_seat_id)
+        .collect();
+    seat_ids.sort();
+    let mut expected_seat_ids = vec![Some(seats[0].id), Some(seats[1].id)];
+    expected_seat_ids.sort();
+    assert_eq!(seat_ids, expected_seat_ids);
+
+    // Seats reserved by another cart cannot be selected
+    let user2 = project.create_user().finish();
+    let mut cart2 = Order::find_or_create_cart(&user2, connection).unwrap();
+    cart2
+        .update_quantities(
+            user2.id,
+            &[UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 1,
+                redemption_code: None,
+            }],
+            false,
+            false,
+            connection,
+        )
+        .unwrap();
+    let result = cart2.assign_seats(ticket_type.id, &[seats[0].id], connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("seat_ids"));
+                assert_eq!(errors["seat_ids"][0].code, "Selected seats are no longer available");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Seat count must match the ticket count
+    let result = cart2.assign_seats(ticket_type.id, &[seats[0].id, seats[2].id], connection);
+    assert!(result.is_err());
+
+    cart2.assign_seats(ticket_type.id, &[seats[2].id], connection).unwrap();
+    let tickets = cart2.tickets(Some(ticket_type.id), connection).unwrap();
+    assert_eq!(tickets[0].venue_seat_id, Some(seats[2].id));
+
+    // Seats from expired carts become available again
+    move_order_to_past(&cart, dates::now().add_minutes(-1).finish(), connection);
+    cart2.assign_seats(ticket_type.id, &[seats[0].id], connection).unwrap();
+    let tickets = cart2.tickets(Some(ticket_type.id), connection).unwrap();
+    assert_eq!(tickets[0].venue_seat_id, Some(seats[0].id));
+    assert_eq!(tickets[0].seat_event_id, Some(event.id));
+    let expired_tickets = cart.tickets(Some(ticket_type.id), connection).unwrap();
+    assert!(expired_tickets.iter().all(|t| t.venue_seat_id != Some(seats[0].id)));
+
+    // A seat can only be held by one live ticket for the event
+    let user3 = project.create_user().finish();
+    let mut cart3 = Order::find_or_create_cart(&user3, connection).unwrap();
+    cart3
+        .update_quantities(
+            user3.id,
+            &[UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 1,
+                redemption_code: None,
+            }],
+            false,
+            false,
+            connection,
+        )
+        .unwrap();
+    let ticket = cart3.tickets(Some(ticket_type.id), connection).unwrap().remove(0);
+    assert!(ticket.assign_seat(Some(seats[0].id), connection).is_err());
+}
+
+#[test]
+fn assign_time_slot() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let starts_at = NaiveDate::from_ymd(2055, 7, 8).and_hms(10, 0, 0);
+    let time_slot = EventTimeSlot::create(event.id, starts_at, starts_at + Duration::minutes(30), 3)
+        .commit(None, connection)
+        .unwrap();
+
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    // A time slot must be chosen for timed entry events
+    let result = cart.assign_time_slot(ticket_type.id, None, connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("event_time_slot_id"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    cart.assign_time_slot(ticket_type.id, Some(time_slot.id), connection)
+        .unwrap();
+    let tickets = cart.tickets(Some(ticket_type.id), connection).unwrap();
+    assert!(tickets.iter().all(|t| t.event_time_slot_id == Some(time_slot.id)));
+
+    // Reassigning the same tickets does not count against the slot twice
+    cart.assign_time_slot(ticket_type.id, Some(time_slot.id), connection)
+        .unwrap();
+
+    // Remaining capacity is shared with other carts
+    let user2 = project.create_user().finish();
+    let mut cart2 = Order::find_or_create_cart(&user2, connection).unwrap();
+    cart2
+        .update_quantities(
+            user2.id,
+            &[UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 2,
+                redemption_code: None,
+            }],
+            false,
+            false,
+            connection,
+        )
+        .unwrap();
+    let result = cart2.assign_time_slot(ticket_type.id, Some(time_slot.id), connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("event_time_slot_id"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Slots held by expired carts become available again
+    move_order_to_past(&cart, dates::now().add_minutes(-1).finish(), connection);
+    cart2
+        .assign_time_slot(ticket_type.id, Some(time_slot.id), connection)
+        .unwrap();
+
+    // Slots from other events cannot be chosen
+    let other_event = project.create_event().finish();
+    let other_time_slot = EventTimeSlot::create(other_event.id, starts_at, starts_at + Duration::minutes(30), 3)
+        .commit(None, connection)
+        .unwrap();
+    assert!(cart2
+        .assign_time_slot(ticket_type.id, Some(other_time_slot.id), connection)
+        .is_err());
+}
+
+#[test]
+fn assign_seats_for_general_admission_ticket_type() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let venue_section = project.create_venue_section().with_seats(vec!["A"], 1).finish();
+    let seats = venue_section.seats(connection).unwrap();
+    let mut cart = project.create_order().for_event(&event).is_paid().finish();
+
+    let result = cart.assign_seats(ticket_type.id, &[seats[0].id], connection);
+    assert!(result.is_err());
+
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    assert!(cart.assign_seats(ticket_type.id, &[], connection).is_ok());
+    let result = cart.assign_seats(ticket_type.id, &[seats[0].id], connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("seat_ids"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
 fn move_order_to_past(order: &Order, to_date: NaiveDateTime, connection: &PgConnection) {
     let tickets: Vec<Uuid> = order.tickets(None, connection).unwrap().iter().map(|t| t.id).collect();
     diesel::sql_query(
@@ -6612,3 +6958,66 @@ fn move_order_to_past(order: &Order, to_date: NaiveDateTime, connection: &PgConn
     .execute(connection)
     .unwrap();
 }
+
+#[test]
+fn currency() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let eur_event = project
+        .create_event()
+        .with_currency("EUR")
+        .with_ticket_pricing()
+        .finish();
+    let usd_event = project.create_event().with_ticket_pricing().finish();
+    let eur_ticket_type = &eur_event.ticket_types(true, None, connection).unwrap()[0];
+    let usd_ticket_type = &usd_event.ticket_types(true, None, connection).unwrap()[0];
+
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: eur_ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(cart.currency, "EUR");
+    assert_eq!(Order::find(cart.id, connection).unwrap().currency, "EUR");
+
+    let result = cart.update_quantities(
+        user.id,
+        &[
+            UpdateOrderItem {
+                ticket_type_id: eur_ticket_type.id,
+                quantity: 1,
+                redemption_code: None,
+            },
+            UpdateOrderItem {
+                ticket_type_id: usd_ticket_type.id,
+                quantity: 1,
+                redemption_code: None,
+            },
+        ],
+        false,
+        false,
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => assert!(errors.contains_key("currency")),
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let order = project.create_order().for_event(&eur_event).is_paid().finish();
+    assert_eq!(order.currency, "EUR");
+    let payments = order.payments(connection).unwrap();
+    assert_eq!(payments.len(), 1);
+    assert_eq!(payments[0].currency, "EUR");
+}
@@ -0,0 +1,290 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use db::dev::TestProject;
+use db::prelude::*;
+use db::utils::errors::ErrorCode::ValidationError;
+
+fn published_pass(project: &TestProject) -> (Pass, Vec<Event>) {
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let event2 = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let pass = Pass::create(organization.id, "Season pass".to_string(), None, 500)
+        .commit(None, connection)
+        .unwrap();
+    for (event, allocated_price_in_cents) in vec![(&event, 300), (&event2, 200)] {
+        let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+        pass.add_event(ticket_type.id, allocated_price_in_cents, None, connection)
+            .unwrap();
+    }
+    let pass = pass.publish(None, connection).unwrap();
+    (pass, vec![event, event2])
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+
+    let pass = Pass::create(organization.id, "Season pass".to_string(), None, 500)
+        .commit(None, connection)
+        .unwrap();
+    assert_eq!(pass.organization_id, organization.id);
+    assert_eq!(pass.price_in_cents, 500);
+    assert_eq!(pass.status, PassStatus::Draft);
+    assert_eq!(
+        Pass::find_for_organization(organization.id, connection).unwrap(),
+        vec![pass]
+    );
+
+    assert!(Pass::create(organization.id, "Season pass".to_string(), None, -1)
+        .commit(None, connection)
+        .is_err());
+}
+
+#[test]
+fn add_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .finish();
+    let other_event = project.create_event().with_tickets().finish();
+    let pass = Pass::create(organization.id, "Season pass".to_string(), None, 500)
+        .commit(None, connection)
+        .unwrap();
+
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let pass_event = pass.add_event(ticket_type.id, 500, None, connection).unwrap();
+    assert_eq!(pass_event.event_id, event.id);
+    assert_eq!(pass_event.ticket_type_id, ticket_type.id);
+    assert_eq!(pass.events(connection).unwrap(), vec![pass_event]);
+
+    // Events must belong to the pass organization
+    let other_ticket_type = other_event.ticket_types(true, None, connection).unwrap().remove(0);
+    let result = pass.add_event(other_ticket_type.id, 500, None, connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("ticket_type_id"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    pass.remove_event(event.id, None, connection).unwrap();
+    assert!(pass.events(connection).unwrap().is_empty());
+    assert!(pass.remove_event(event.id, None, connection).is_err());
+}
+
+#[test]
+fn publish() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .finish();
+    let pass = Pass::create(organization.id, "Season pass".to_string(), None, 500)
+        .commit(None, connection)
+        .unwrap();
+    assert!(pass.publish(None, connection).is_err());
+
+    // Allocations must add up to the pass price
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    pass.add_event(ticket_type.id, 400, None, connection).unwrap();
+    let result = pass.publish(None, connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("price_in_cents"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let pass = pass
+        .update(
+            PassEditableAttributes {
+                price_in_cents: Some(400),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    let pass = pass.publish(None, connection).unwrap();
+    assert_eq!(pass.status, PassStatus::Published);
+
+    // Published passes cannot change their events or price
+    assert!(pass.remove_event(event.id, None, connection).is_err());
+    assert!(pass
+        .update(
+            PassEditableAttributes {
+                price_in_cents: Some(500),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .is_err());
+
+    let pass = pass.cancel(None, connection).unwrap();
+    assert_eq!(pass.status, PassStatus::Cancelled);
+}
+
+#[test]
+fn update_pass_quantity() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (pass, events) = published_pass(&project);
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+
+    cart.update_pass_quantity(user.id, pass.id, 2, &[], connection).unwrap();
+    let items: Vec<OrderItem> = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .filter(|i| i.item_type == OrderItemTypes::Tickets)
+        .collect();
+    assert_eq!(items.len(), 2);
+    assert!(items.iter().all(|i| i.pass_id == Some(pass.id) && i.quantity == 2));
+    let item = items.iter().find(|i| i.event_id == Some(events[0].id)).unwrap();
+    assert_eq!(item.unit_price_in_cents, 300);
+    let item2 = items.iter().find(|i| i.event_id == Some(events[1].id)).unwrap();
+    assert_eq!(item2.unit_price_in_cents, 200);
+    assert_eq!(cart.tickets(None, connection).unwrap().len(), 4);
+
+    // Regular tickets can be added alongside the pass
+    let ticket_type = events[0].ticket_types(true, None, connection).unwrap().remove(0);
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(cart.tickets(None, connection).unwrap().len(), 5);
+
+    cart.update_pass_quantity(user.id, pass.id, 1, &[], connection).unwrap();
+    assert_eq!(cart.tickets(None, connection).unwrap().len(), 3);
+    cart.update_pass_quantity(user.id, pass.id, 0, &[], connection).unwrap();
+    assert_eq!(cart.tickets(None, connection).unwrap().len(), 1);
+
+    // Cancelled passes are no longer sold
+    let pass = pass.cancel(None, connection).unwrap();
+    assert!(cart.update_pass_quantity(user.id, pass.id, 1, &[], connection).is_err());
+}
+
+#[test]
+fn update_pass_quantity_validates_limits_and_time_slots() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (pass, events) = published_pass(&project);
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+
+    // Per person limits of the pass event ticket types apply
+    let ticket_type = events[0].ticket_types(true, None, connection).unwrap().remove(0);
+    ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                limit_per_person: Some(1),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    let result = cart.update_pass_quantity(user.id, pass.id, 2, &[], connection);
+    match result {
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(errors["quantity"][0].code, "limit_per_person_exceeded");
+            }
+            _ => panic!("Expected validation error"),
+        },
+        _ => panic!("Expected error"),
+    }
+    cart.update_pass_quantity(user.id, pass.id, 1, &[], connection).unwrap();
+
+    // Timed entry events require a time slot
+    let starts_at = NaiveDate::from_ymd(2055, 7, 8).and_hms(10, 0, 0);
+    let time_slot = EventTimeSlot::create(events[1].id, starts_at, starts_at + Duration::minutes(30), 3)
+        .commit(None, connection)
+        .unwrap();
+    assert!(cart.update_pass_quantity(user.id, pass.id, 1, &[], connection).is_err());
+    cart.update_pass_quantity(user.id, pass.id, 1, &[time_slot.id], connection)
+        .unwrap();
+    let tickets = cart.tickets(None, connection).unwrap();
+    assert_eq!(tickets.len(), 2);
+    let ticket = tickets.iter().find(|t| t.event_time_slot_id.is_some()).unwrap();
+    assert_eq!(ticket.event_time_slot_id, Some(time_slot.id));
+}
+
+#[test]
+fn purchase_and_redeem() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let admin = project.create_user().finish();
+    let (pass, events) = published_pass(&project);
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_pass_quantity(user.id, pass.id, 1, &[], connection).unwrap();
+
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("Test".to_string()),
+        ExternalPaymentType::CreditCard,
+        user.id,
+        total,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(cart.status, OrderStatus::Paid);
+
+    // One ticket is issued per included event, each redeemable on its own
+    let tickets = TicketInstance::find_for_user(user.id, connection).unwrap();
+    assert_eq!(tickets.len(), 2);
+    for event in &events {
+        let ticket = tickets
+            .iter()
+            .find(|t| t.ticket_type(connection).unwrap().event_id == event.id)
+            .unwrap();
+        let result = TicketInstance::redeem_ticket(
+            ticket.id,
+            ticket.redeem_key.clone().unwrap(),
+            admin.id,
+            CheckInSource::Scanned,
+            connection,
+        )
+        .unwrap();
+        assert_eq!(result, RedeemResults::TicketRedeemSuccess);
+    }
+}
@@ -47,6 +47,23 @@ fn find_by_order() {
     assert_eq!(payment, found_payment);
 }
 
+#[test]
+fn find_by_external_reference() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let refund = project.create_refund().finish();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    let payment = project.create_payment().with_user(&user).with_event(&event).finish();
+    payment.log_refund(user.id, &refund, 100, None, connection).unwrap();
+
+    assert_eq!(
+        Payment::find_by_external_reference("Test", connection).unwrap(),
+        payment
+    );
+    assert!(Payment::find_by_external_reference("Missing", connection).is_err());
+}
+
 #[test]
 fn find() {
     let project = TestProject::new();
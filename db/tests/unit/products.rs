@@ -0,0 +1,374 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use db::dev::TestProject;
+use db::prelude::*;
+use db::utils::errors::ErrorCode::ValidationError;
+use diesel::PgConnection;
+use uuid::Uuid;
+
+fn published_product(
+    project: &TestProject,
+    organization: &Organization,
+    event_id: Option<Uuid>,
+    per_ticket_limit: Option<i32>,
+    stock_quantity: i32,
+) -> (Product, ProductVariant) {
+    let connection = project.get_connection();
+    let product = Product::create(
+        organization.id,
+        event_id,
+        "Parking".to_string(),
+        None,
+        1000,
+        per_ticket_limit,
+    )
+    .commit(None, connection)
+    .unwrap();
+    let variant = ProductVariant::create(product.id, "Lot A".to_string(), None, stock_quantity)
+        .commit(None, connection)
+        .unwrap();
+    let product = product
+        .update(
+            ProductEditableAttributes {
+                status: Some(ProductStatus::Published),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    (product, variant)
+}
+
+fn add_tickets(cart: &mut Order, user: &User, ticket_type: &TicketType, quantity: u32, connection: &PgConnection) {
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity,
+            redemption_code: None,
+        }],
+        false,
+        true,
+        connection,
+    )
+    .unwrap();
+}
+
+fn assert_validation_error<T>(result: Result<T, DatabaseError>, field: &str) {
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key(field));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project.create_event().with_organization(&organization).finish();
+    let other_event = project.create_event().finish();
+
+    let product = Product::create(
+        organization.id,
+        Some(event.id),
+        "T-Shirt".to_string(),
+        Some("Tour shirt".to_string()),
+        2500,
+        Some(1),
+    )
+    .commit(None, connection)
+    .unwrap();
+    assert_eq!(product.organization_id, organization.id);
+    assert_eq!(product.event_id, Some(event.id));
+    assert_eq!(product.status, ProductStatus::Draft);
+    assert_eq!(
+        Product::find_for_organization(organization.id, connection).unwrap(),
+        vec![product.clone()]
+    );
+
+    let small = ProductVariant::create(product.id, "Small".to_string(), None, 10)
+        .commit(None, connection)
+        .unwrap();
+    let large = ProductVariant::create(product.id, "Large".to_string(), Some(3000), 5)
+        .commit(None, connection)
+        .unwrap();
+    assert_eq!(
+        product.variants(connection).unwrap(),
+        vec![small.clone(), large.clone()]
+    );
+    assert_eq!(small.unit_price_in_cents(&product), 2500);
+    assert_eq!(large.unit_price_in_cents(&product), 3000);
+    assert_eq!(small.available_count(None, connection).unwrap(), 10);
+
+    assert_validation_error(
+        ProductVariant::create(product.id, "Medium".to_string(), None, -1).commit(None, connection),
+        "stock_quantity",
+    );
+    assert_validation_error(
+        Product::create(
+            organization.id,
+            Some(other_event.id),
+            "T-Shirt".to_string(),
+            None,
+            2500,
+            None,
+        )
+        .commit(None, connection),
+        "event_id",
+    );
+}
+
+#[test]
+fn find_published_for_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project.create_event().with_organization(&organization).finish();
+    let event2 = project.create_event().with_organization(&organization).finish();
+    let (organization_product, _) = published_product(&project, &organization, None, None, 10);
+    let (event_product, _) = published_product(&project, &organization, Some(event.id), None, 10);
+    let (_event2_product, _) = published_product(&project, &organization, Some(event2.id), None, 10);
+    Product::create(organization.id, Some(event.id), "Draft".to_string(), None, 100, None)
+        .commit(None, connection)
+        .unwrap();
+
+    let products = Product::find_published_for_event(event.id, connection).unwrap();
+    assert_eq!(products.len(), 2);
+    assert!(products.contains(&organization_product));
+    assert!(products.contains(&event_product));
+}
+
+#[test]
+fn update_product_quantity() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_event_fee().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let (_, variant) = published_product(&project, &organization, None, Some(1), 3);
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+
+    // Organization wide products need tickets in the cart to attach to
+    assert_validation_error(
+        cart.update_product_quantity(user.id, variant.id, 1, connection),
+        "product_variant_id",
+    );
+
+    add_tickets(&mut cart, &user, &ticket_type, 2, connection);
+    let total_without_product = cart.calculate_total(connection).unwrap();
+    cart.update_product_quantity(user.id, variant.id, 2, connection)
+        .unwrap();
+    let items = cart.items(connection).unwrap();
+    let product_item = items.iter().find(|i| i.item_type == OrderItemTypes::Products).unwrap();
+    assert_eq!(product_item.product_variant_id, Some(variant.id));
+    assert_eq!(product_item.event_id, Some(event.id));
+    assert_eq!(product_item.quantity, 2);
+    assert_eq!(product_item.unit_price_in_cents, 1000);
+    assert_eq!(product_item.description(connection).unwrap(), "Parking - Lot A");
+    assert_eq!(cart.calculate_total(connection).unwrap(), total_without_product + 2000);
+    // Event fees are only charged once per event
+    assert_eq!(
+        items
+            .iter()
+            .filter(|i| i.item_type == OrderItemTypes::EventFees)
+            .count(),
+        1
+    );
+    assert_eq!(variant.available_count(None, connection).unwrap(), 1);
+
+    // One per ticket
+    assert_validation_error(
+        cart.update_product_quantity(user.id, variant.id, 3, connection),
+        "quantity",
+    );
+
+    // Removing tickets reduces products limited per ticket
+    add_tickets(&mut cart, &user, &ticket_type, 1, connection);
+    let product_item = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Products)
+        .unwrap();
+    assert_eq!(product_item.quantity, 1);
+
+    // Stock held by other carts is unavailable
+    let user2 = project.create_user().finish();
+    let mut cart2 = Order::find_or_create_cart(&user2, connection).unwrap();
+    add_tickets(&mut cart2, &user2, &ticket_type, 5, connection);
+    assert_validation_error(
+        cart2.update_product_quantity(user2.id, variant.id, 3, connection),
+        "quantity",
+    );
+    cart2
+        .update_product_quantity(user2.id, variant.id, 2, connection)
+        .unwrap();
+    assert_eq!(variant.available_count(None, connection).unwrap(), 0);
+
+    cart.update_product_quantity(user.id, variant.id, 0, connection)
+        .unwrap();
+    assert!(cart
+        .items(connection)
+        .unwrap()
+        .iter()
+        .all(|i| i.item_type != OrderItemTypes::Products));
+    assert_eq!(variant.available_count(None, connection).unwrap(), 1);
+}
+
+#[test]
+fn update_stock_quantity() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let (_, variant) = published_product(&project, &organization, Some(event.id), None, 5);
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    add_tickets(&mut cart, &user, &ticket_type, 1, connection);
+    cart.update_product_quantity(user.id, variant.id, 3, connection)
+        .unwrap();
+
+    assert_validation_error(
+        variant.update(
+            ProductVariantEditableAttributes {
+                stock_quantity: Some(2),
+                ..Default::default()
+            },
+            None,
+            connection,
+        ),
+        "stock_quantity",
+    );
+    let variant = variant
+        .update(
+            ProductVariantEditableAttributes {
+                stock_quantity: Some(3),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(variant.available_count(None, connection).unwrap(), 0);
+}
+
+#[test]
+fn purchase_redeem_and_report() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let admin = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .with_event_start(Utc::now().naive_utc() + Duration::days(20))
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let (_, variant) = published_product(&project, &organization, Some(event.id), None, 10);
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    add_tickets(&mut cart, &user, &ticket_type, 1, connection);
+    cart.update_product_quantity(user.id, variant.id, 2, connection)
+        .unwrap();
+    let product_item = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Products)
+        .unwrap();
+
+    // Vouchers cannot be redeemed before payment
+    assert!(product_item
+        .redeem_product_voucher(1, Some(admin.id), connection)
+        .is_err());
+
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("Test".to_string()),
+        ExternalPaymentType::CreditCard,
+        user.id,
+        total,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(cart.status, OrderStatus::Paid);
+    assert_eq!(variant.available_count(None, connection).unwrap(), 8);
+
+    let product_item = product_item
+        .redeem_product_voucher(1, Some(admin.id), connection)
+        .unwrap();
+    assert_eq!(product_item.redeemed_quantity, 1);
+    assert_validation_error(
+        product_item.redeem_product_voucher(2, Some(admin.id), connection),
+        "quantity",
+    );
+    let product_item = product_item
+        .redeem_product_voucher(1, Some(admin.id), connection)
+        .unwrap();
+    assert_eq!(product_item.redeemed_quantity, 2);
+    let domain_events = DomainEvent::find(
+        Tables::Orders,
+        Some(cart.id),
+        Some(DomainEventTypes::ProductVoucherRedeemed),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 2);
+
+    let cart = Order::find(cart.id, connection).unwrap();
+    let result = Report::sales_summary_report(
+        organization.id,
+        Some(cart.paid_at.unwrap() - Duration::hours(1)),
+        Some(cart.paid_at.unwrap() + Duration::hours(1)),
+        None,
+        None,
+        0,
+        10,
+        connection,
+    )
+    .unwrap();
+    let product_row = result.data.iter().find(|r| r.ticket_name == "Parking - Lot A").unwrap();
+    assert_eq!(product_row.face_value_in_cents, 1000);
+    assert_eq!(product_row.online_sale_count, 2);
+
+    let settlement = Settlement::create(
+        organization.id,
+        dates::now().add_days(-1).finish(),
+        dates::now().add_days(1).finish(),
+        SettlementStatus::PendingSettlement,
+        None,
+        false,
+    )
+    .commit(None, connection)
+    .unwrap();
+    let entries = SettlementEntry::find_for_settlement_by_event(&settlement, connection).unwrap();
+    let product_entry = entries[0]
+        .entries
+        .iter()
+        .find(|e| e.settlement_entry_type == SettlementEntryTypes::Products)
+        .unwrap();
+    assert_eq!(product_entry.product_variant_id, Some(variant.id));
+    assert_eq!(product_entry.ticket_type_name, Some("Parking - Lot A".to_string()));
+    assert_eq!(product_entry.online_sold_quantity, 2);
+    assert_eq!(product_entry.total_sales_in_cents, 2000);
+}
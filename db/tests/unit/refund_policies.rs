@@ -0,0 +1,117 @@
+use chrono::{Duration, NaiveDate};
+use db::dev::TestProject;
+use db::models::*;
+use db::utils::errors::ErrorCode::ValidationError;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let user = project.create_user().finish();
+
+    let refund_policy = RefundPolicy::create(event.id, RefundApprovalTypes::Automatic, 24, false)
+        .commit(Some(user.id), connection)
+        .unwrap();
+    assert_eq!(refund_policy.event_id, event.id);
+    assert_eq!(refund_policy.approval_type, RefundApprovalTypes::Automatic);
+    assert_eq!(refund_policy.deadline_hours_before_event, 24);
+    assert!(!refund_policy.retain_fees);
+
+    let domain_events = DomainEvent::find(
+        Tables::Events,
+        Some(event.id),
+        Some(DomainEventTypes::RefundPolicyCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn create_with_negative_deadline() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+
+    let result = RefundPolicy::create(event.id, RefundApprovalTypes::Manual, -1, true).commit(None, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("deadline_hours_before_event"));
+                assert_eq!(errors["deadline_hours_before_event"][0].code, "number_must_be_positive");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn find_for_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    assert_eq!(RefundPolicy::find_for_event(event.id, connection).unwrap(), None);
+
+    let refund_policy = RefundPolicy::create(event.id, RefundApprovalTypes::Manual, 0, true)
+        .commit(None, connection)
+        .unwrap();
+    assert_eq!(
+        RefundPolicy::find_for_event(event.id, connection).unwrap(),
+        Some(refund_policy)
+    );
+}
+
+#[test]
+fn request_deadline() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event_start = NaiveDate::from_ymd(2050, 7, 8).and_hms(20, 0, 0);
+    let event = project.create_event().with_event_start(event_start).finish();
+    let refund_policy = RefundPolicy::create(event.id, RefundApprovalTypes::Manual, 48, true)
+        .commit(None, connection)
+        .unwrap();
+
+    assert_eq!(
+        refund_policy.request_deadline(&event),
+        Some(event_start - Duration::hours(48))
+    );
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let user = project.create_user().finish();
+    let refund_policy = RefundPolicy::create(event.id, RefundApprovalTypes::Manual, 0, true)
+        .commit(None, connection)
+        .unwrap();
+
+    let refund_policy = refund_policy
+        .update(
+            RefundPolicyEditableAttributes {
+                approval_type: Some(RefundApprovalTypes::Automatic),
+                deadline_hours_before_event: Some(12),
+                ..Default::default()
+            },
+            Some(user.id),
+            connection,
+        )
+        .unwrap();
+    assert_eq!(refund_policy.approval_type, RefundApprovalTypes::Automatic);
+    assert_eq!(refund_policy.deadline_hours_before_event, 12);
+    assert!(refund_policy.retain_fees);
+
+    assert!(refund_policy
+        .update(
+            RefundPolicyEditableAttributes {
+                deadline_hours_before_event: Some(-12),
+                ..Default::default()
+            },
+            Some(user.id),
+            connection,
+        )
+        .is_err());
+}
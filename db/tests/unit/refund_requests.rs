@@ -0,0 +1,294 @@
+use db::dev::TestProject;
+use db::models::*;
+use db::utils::errors::ErrorCode::ValidationError;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    RefundPolicy::create(event.id, RefundApprovalTypes::Manual, 0, true)
+        .commit(None, connection)
+        .unwrap();
+    let ticket = &order.tickets(None, connection).unwrap()[0];
+
+    let refund_request = RefundRequest::create(order.id, user.id, vec![ticket.id], "Can't attend".to_string())
+        .commit(connection)
+        .unwrap();
+    assert_eq!(refund_request.event_id, event.id);
+    assert_eq!(refund_request.status, RefundRequestStatus::Pending);
+    assert_eq!(refund_request.reviewed_by, None);
+    assert_eq!(
+        RefundRequest::find_for_order(order.id, connection).unwrap(),
+        vec![refund_request.clone()]
+    );
+
+    let domain_events = DomainEvent::find(
+        Tables::Orders,
+        Some(order.id),
+        Some(DomainEventTypes::RefundRequestCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+
+    // Tickets already awaiting review cannot be requested again
+    let result =
+        RefundRequest::create(order.id, user.id, vec![ticket.id], "Can't attend".to_string()).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("ticket_instance_ids"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn create_without_refund_policy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+    let ticket = &order.tickets(None, connection).unwrap()[0];
+
+    assert_eq!(
+        RefundRequest::create(order.id, user.id, vec![ticket.id], "Can't attend".to_string()).commit(connection),
+        DatabaseError::business_process_error("Refunds cannot be requested for this event")
+    );
+}
+
+#[test]
+fn create_after_deadline() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    // Test events start in two days
+    let event = project.create_event().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+    RefundPolicy::create(event.id, RefundApprovalTypes::Manual, 72, true)
+        .commit(None, connection)
+        .unwrap();
+    let ticket = &order.tickets(None, connection).unwrap()[0];
+
+    assert_eq!(
+        RefundRequest::create(order.id, user.id, vec![ticket.id], "Can't attend".to_string()).commit(connection),
+        DatabaseError::business_process_error("The deadline to request refunds for this event has passed")
+    );
+}
+
+#[test]
+fn create_with_invalid_tickets() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+    let other_order = project.create_order().for_event(&event).is_paid().finish();
+    RefundPolicy::create(event.id, RefundApprovalTypes::Manual, 0, true)
+        .commit(None, connection)
+        .unwrap();
+    let other_ticket = &other_order.tickets(None, connection).unwrap()[0];
+
+    for (ticket_instance_ids, reason, field) in vec![
+        (vec![], "Can't attend", "ticket_instance_ids"),
+        (vec![other_ticket.id], "Can't attend", "ticket_instance_ids"),
+        (vec![other_ticket.id], " ", "reason"),
+    ] {
+        let result =
+            RefundRequest::create(order.id, user.id, ticket_instance_ids, reason.to_string()).commit(connection);
+        match result {
+            Ok(_) => panic!("Expected validation error"),
+            Err(error) => match &error.error_code {
+                ValidationError { errors } => {
+                    assert!(errors.contains_key(field));
+                }
+                _ => panic!("Expected validation error"),
+            },
+        }
+    }
+}
+
+#[test]
+fn approve() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let reviewer = project.create_user().finish();
+    let mut order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    RefundPolicy::create(event.id, RefundApprovalTypes::Manual, 0, true)
+        .commit(None, connection)
+        .unwrap();
+    let ticket = &order.tickets(None, connection).unwrap()[0];
+    let refund_request = RefundRequest::create(order.id, user.id, vec![ticket.id], "Can't attend".to_string())
+        .commit(connection)
+        .unwrap();
+
+    let items = refund_request.refund_items(connection).unwrap();
+    let order_item = OrderItem::find(ticket.order_item_id.unwrap(), connection).unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].order_item_id, order_item.id);
+    assert_eq!(items[0].ticket_instance_id, Some(ticket.id));
+
+    // Fees are kept by the organizer
+    let (refund, amount) = order
+        .refund_retaining_fees(&items, reviewer.id, None, connection)
+        .unwrap();
+    assert_eq!(amount, order_item.unit_price_in_cents);
+    let refunded_ticket = RefundedTicket::find_or_create_by_ticket_instance(ticket, connection).unwrap();
+    assert!(refunded_ticket.ticket_refunded_at.is_some());
+    assert!(refunded_ticket.fee_refunded_at.is_none());
+
+    let refund_request = refund_request.approve(&refund, Some(reviewer.id), connection).unwrap();
+    assert_eq!(refund_request.status, RefundRequestStatus::Approved);
+    assert_eq!(refund_request.refund_id, Some(refund.id));
+    assert_eq!(refund_request.reviewed_by, Some(reviewer.id));
+    assert!(refund_request.reviewed_at.is_some());
+
+    assert_eq!(
+        refund_request.approve(&refund, Some(reviewer.id), connection),
+        DatabaseError::business_process_error("Refund request has already been reviewed")
+    );
+}
+
+#[test]
+fn deny() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    let reviewer = project.create_user().finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+    RefundPolicy::create(event.id, RefundApprovalTypes::Manual, 0, true)
+        .commit(None, connection)
+        .unwrap();
+    let ticket = &order.tickets(None, connection).unwrap()[0];
+    let refund_request = RefundRequest::create(order.id, user.id, vec![ticket.id], "Can't attend".to_string())
+        .commit(connection)
+        .unwrap();
+
+    let refund_request = refund_request
+        .deny(Some("Event is still on".to_string()), reviewer.id, connection)
+        .unwrap();
+    assert_eq!(refund_request.status, RefundRequestStatus::Denied);
+    assert_eq!(refund_request.response_note, Some("Event is still on".to_string()));
+    assert_eq!(refund_request.reviewed_by, Some(reviewer.id));
+
+    // Denied tickets can be requested again
+    assert!(
+        RefundRequest::create(order.id, user.id, vec![ticket.id], "Can't attend".to_string())
+            .commit(connection)
+            .is_ok()
+    );
+}
+
+#[test]
+fn find_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let event2 = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    RefundPolicy::create(event.id, RefundApprovalTypes::Manual, 0, true)
+        .commit(None, connection)
+        .unwrap();
+    RefundPolicy::create(event2.id, RefundApprovalTypes::Manual, 0, true)
+        .commit(None, connection)
+        .unwrap();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+    let order2 = project
+        .create_order()
+        .for_event(&event2)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+    let ticket = &order.tickets(None, connection).unwrap()[0];
+    let ticket2 = &order2.tickets(None, connection).unwrap()[0];
+    let refund_request = RefundRequest::create(order.id, user.id, vec![ticket.id], "Can't attend".to_string())
+        .commit(connection)
+        .unwrap();
+    let refund_request2 = RefundRequest::create(order2.id, user.id, vec![ticket2.id], "Can't attend".to_string())
+        .commit(connection)
+        .unwrap();
+    let refund_request2 = refund_request2.deny(None, user.id, connection).unwrap();
+
+    let payload = RefundRequest::find_for_organization(organization.id, None, None, 0, 100, connection).unwrap();
+    assert_eq!(payload.data, vec![refund_request.clone(), refund_request2.clone()]);
+    assert_eq!(payload.paging.total, 2);
+
+    let payload = RefundRequest::find_for_organization(
+        organization.id,
+        None,
+        Some(RefundRequestStatus::Pending),
+        0,
+        100,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(payload.data, vec![refund_request.clone()]);
+
+    let payload =
+        RefundRequest::find_for_organization(organization.id, Some(event2.id), None, 0, 100, connection).unwrap();
+    assert_eq!(payload.data, vec![refund_request2]);
+
+    let other_organization = project.create_organization().finish();
+    let payload = RefundRequest::find_for_organization(other_organization.id, None, None, 0, 100, connection).unwrap();
+    assert!(payload.data.is_empty());
+}
@@ -1,4 +1,4 @@
-use chrono::{Datelike, Duration, NaiveDate, TimeZone, Utc};
+use chrono::{Datelike, Duration, NaiveDate, TimeZone, Timelike, Utc};
 use chrono_tz::Tz;
 use db::dev::TestProject;
 use db::models::*;
@@ -73,6 +73,7 @@ fn scan_count_report() {
     assert_eq!(&report_rows.data[0].ticket_type_name, &ticket_types[0].name);
     assert_eq!(report_rows.data[0].scanned_count, 2);
     assert_eq!(report_rows.data[0].not_scanned_count, 8);
+    assert_eq!(report_rows.data[0].inside_count, 2);
     assert_eq!(&report_rows.data[1].ticket_type_name, &ticket_types[1].name);
     assert_eq!(report_rows.data[1].scanned_count, 0);
     assert_eq!(report_rows.data[1].not_scanned_count, 5);
@@ -101,6 +102,76 @@ fn scan_count_report() {
     assert_eq!(report_rows.data[1].not_scanned_count, 5);
 }
 
+#[test]
+fn occupancy_report() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let ticket_type = ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                max_entries: Some(2),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    project
+        .create_order()
+        .for_tickets(ticket_type.id)
+        .for_user(&user)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let tickets = TicketInstance::find_for_user(user.id, connection).unwrap();
+    assert!(Report::occupancy_report(event.id, connection).unwrap().is_empty());
+
+    let first_hour = dates::now().add_hours(-3).finish();
+    let second_hour = dates::now().add_hours(-2).finish();
+    for ticket in &tickets {
+        TicketInstance::redeem_ticket_at(
+            ticket.id,
+            ticket.redeem_key.clone().unwrap(),
+            user.id,
+            CheckInSource::Scanned,
+            first_hour,
+            false,
+            connection,
+        )
+        .unwrap();
+    }
+    TicketInstance::scan_out(
+        tickets[0].id,
+        tickets[0].redeem_key.clone().unwrap(),
+        user.id,
+        second_hour,
+        connection,
+    )
+    .unwrap();
+
+    let report_rows = Report::occupancy_report(event.id, connection).unwrap();
+    assert_eq!(report_rows.len(), 2);
+    assert_eq!(
+        report_rows[0].period_start,
+        first_hour.date().and_hms(first_hour.hour(), 0, 0)
+    );
+    assert_eq!(
+        (report_rows[0].entries, report_rows[0].exits, report_rows[0].occupancy),
+        (2, 0, 2)
+    );
+    assert_eq!(
+        (report_rows[1].entries, report_rows[1].exits, report_rows[1].occupancy),
+        (0, 1, 1)
+    );
+
+    let report_rows = Report::scan_count_report(event.id, 0, 100, connection).unwrap();
+    assert_eq!(report_rows.data[0].scanned_count, 2);
+    assert_eq!(report_rows.data[0].inside_count, 1);
+}
+
 #[test]
 fn find_event_reports_for_processing() {
     let project = TestProject::new();
@@ -1585,6 +1656,7 @@ fn sales_summary_report() {
             total: 2,
             event_name: event.name.clone(),
             event_date: event.event_start,
+            currency: "USD".to_string(),
             ticket_name: ticket_type.name.clone(),
             face_value_in_cents: ticket_pricing.price_in_cents,
             online_sale_count: ticket_quantity,
@@ -1596,6 +1668,7 @@ fn sales_summary_report() {
             total: 2,
             event_name: event.name.clone(),
             event_date: event.event_start,
+            currency: "USD".to_string(),
             ticket_name: "Per Order Fee".to_string(),
             face_value_in_cents: 0,
             online_sale_count: 0,
@@ -1661,6 +1734,7 @@ fn sales_summary_report() {
             total: 3,
             event_name: event.name.clone(),
             event_date: event.event_start,
+            currency: "USD".to_string(),
             ticket_name: ticket_type.name.clone(),
             face_value_in_cents: ticket_pricing.price_in_cents,
             online_sale_count: ticket_quantity + 1,
@@ -1672,6 +1746,7 @@ fn sales_summary_report() {
             total: 3,
             event_name: event.name.clone(),
             event_date: event.event_start,
+            currency: "USD".to_string(),
             ticket_name: format!("{} - Hold - {}", ticket_type.name.clone(), comp.name),
             face_value_in_cents: 0,
             online_sale_count: 0,
@@ -1683,6 +1758,7 @@ fn sales_summary_report() {
             total: 3,
             event_name: event.name.clone(),
             event_date: event.event_start,
+            currency: "USD".to_string(),
             ticket_name: "Per Order Fee".to_string(),
             face_value_in_cents: 0,
             online_sale_count: 0,
@@ -1742,6 +1818,7 @@ fn sales_summary_report() {
             total: 3,
             event_name: event.name.clone(),
             event_date: event.event_start,
+            currency: "USD".to_string(),
             ticket_name: ticket_type.name.clone(),
             face_value_in_cents: ticket_pricing.price_in_cents,
             online_sale_count: ticket_quantity,
@@ -1753,6 +1830,7 @@ fn sales_summary_report() {
             total: 3,
             event_name: event.name.clone(),
             event_date: event.event_start,
+            currency: "USD".to_string(),
             ticket_name: format!("{} - Hold - {}", ticket_type.name.clone(), comp.name),
             face_value_in_cents: 0,
             online_sale_count: 0,
@@ -1764,6 +1842,7 @@ fn sales_summary_report() {
             total: 3,
             event_name: event.name.clone(),
             event_date: event.event_start,
+            currency: "USD".to_string(),
             ticket_name: "Per Order Fee".to_string(),
             face_value_in_cents: 0,
             online_sale_count: 0,
@@ -1793,6 +1872,7 @@ fn sales_summary_report() {
         total: 1,
         event_name: event.name.clone(),
         event_date: event.event_start,
+        currency: "USD".to_string(),
         ticket_name: ticket_type.name.clone(),
         face_value_in_cents: ticket_pricing.price_in_cents,
         online_sale_count: -1,
@@ -1825,6 +1905,7 @@ fn sales_summary_report() {
         total: 1,
         event_name: event.name.clone(),
         event_date: event.event_start,
+        currency: "USD".to_string(),
         ticket_name: "Per Order Fee".to_string(),
         face_value_in_cents: 0,
         online_sale_count: 0,
@@ -1858,6 +1939,7 @@ fn sales_summary_report() {
             total: 5,
             event_name: event.name.clone(),
             event_date: event.event_start,
+            currency: "USD".to_string(),
             ticket_name: ticket_type.name.clone(),
             face_value_in_cents: ticket_pricing.price_in_cents,
             online_sale_count: ticket_quantity + 1,
@@ -1869,6 +1951,7 @@ fn sales_summary_report() {
             total: 5,
             event_name: event.name.clone(),
             event_date: event.event_start,
+            currency: "USD".to_string(),
             ticket_name: format!("{} - Hold - {}", ticket_type.name.clone(), comp.name),
             face_value_in_cents: 0,
             online_sale_count: 0,
@@ -1880,6 +1963,7 @@ fn sales_summary_report() {
             total: 5,
             event_name: event.name.clone(),
             event_date: event.event_start,
+            currency: "USD".to_string(),
             ticket_name: "Per Order Fee".to_string(),
             face_value_in_cents: 0,
             online_sale_count: 0,
@@ -1891,6 +1975,7 @@ fn sales_summary_report() {
             total: 5,
             event_name: event2.name.clone(),
             event_date: event2.event_start,
+            currency: "USD".to_string(),
             ticket_name: ticket_type2.name.clone(),
             face_value_in_cents: ticket_pricing2.price_in_cents,
             online_sale_count: 1,
@@ -1902,6 +1987,7 @@ fn sales_summary_report() {
             total: 5,
             event_name: event2.name.clone(),
             event_date: event2.event_start,
+            currency: "USD".to_string(),
             ticket_name: "Per Order Fee".to_string(),
             face_value_in_cents: 0,
             online_sale_count: 0,
@@ -1997,6 +2083,7 @@ fn domain_transaction_detail_report() {
         transaction_date: order.paid_at.unwrap(),
         point_of_sale: None,
         payment_method: PaymentMethods::CreditCard.to_string(),
+        currency: "USD".to_string(),
         qty_tickets_sold: 2,
         qty_tickets_refunded: 0,
         qty_tickets_sold_net: 2,
@@ -2122,6 +2209,7 @@ fn box_office_sales_summary_report() {
                     BoxOfficeSalesSummaryOperatorEventRow {
                         event_name: Some("Event1".to_string()),
                         event_date: Some(NaiveDate::from_ymd(2016, 7, 8).and_hms(9, 10, 11)),
+                        currency: "USD".to_string(),
                         number_of_tickets: 2,
                         face_value_in_cents: 150,
                         revenue_share_value_in_cents: 0,
@@ -2130,6 +2218,7 @@ fn box_office_sales_summary_report() {
                     BoxOfficeSalesSummaryOperatorEventRow {
                         event_name: Some("Event2".to_string()),
                         event_date: Some(NaiveDate::from_ymd(2017, 7, 8).and_hms(9, 10, 11)),
+                        currency: "USD".to_string(),
                         number_of_tickets: 2,
                         face_value_in_cents: 150,
                         revenue_share_value_in_cents: 0,
@@ -2139,16 +2228,19 @@ fn box_office_sales_summary_report() {
                 payments: vec![
                     BoxOfficeSalesSummaryPaymentRow {
                         payment_type: ExternalPaymentType::Cash,
+                        currency: "USD".to_string(),
                         quantity: 1,
                         total_sales_in_cents: 150,
                     },
                     BoxOfficeSalesSummaryPaymentRow {
                         payment_type: ExternalPaymentType::CreditCard,
+                        currency: "USD".to_string(),
                         quantity: 1,
                         total_sales_in_cents: 150,
                     },
                     BoxOfficeSalesSummaryPaymentRow {
                         payment_type: ExternalPaymentType::Voucher,
+                        currency: "USD".to_string(),
                         quantity: 2,
                         total_sales_in_cents: 300,
                     },
@@ -2161,6 +2253,7 @@ fn box_office_sales_summary_report() {
                     BoxOfficeSalesSummaryOperatorEventRow {
                         event_name: Some("Event1".to_string()),
                         event_date: Some(NaiveDate::from_ymd(2016, 7, 8).and_hms(9, 10, 11)),
+                        currency: "USD".to_string(),
                         number_of_tickets: 1,
                         face_value_in_cents: 140,
                         revenue_share_value_in_cents: 0,
@@ -2169,6 +2262,7 @@ fn box_office_sales_summary_report() {
                     BoxOfficeSalesSummaryOperatorEventRow {
                         event_name: Some("Event1".to_string()),
                         event_date: Some(NaiveDate::from_ymd(2016, 7, 8).and_hms(9, 10, 11)),
+                        currency: "USD".to_string(),
                         number_of_tickets: 2,
                         face_value_in_cents: 150,
                         revenue_share_value_in_cents: 0,
@@ -2177,6 +2271,7 @@ fn box_office_sales_summary_report() {
                 ],
                 payments: vec![BoxOfficeSalesSummaryPaymentRow {
                     payment_type: ExternalPaymentType::Cash,
+                    currency: "USD".to_string(),
                     quantity: 3,
                     total_sales_in_cents: 440,
                 }],
@@ -2185,16 +2280,19 @@ fn box_office_sales_summary_report() {
         payments: vec![
             BoxOfficeSalesSummaryPaymentRow {
                 payment_type: ExternalPaymentType::Cash,
+                currency: "USD".to_string(),
                 quantity: 4,
                 total_sales_in_cents: 590,
             },
             BoxOfficeSalesSummaryPaymentRow {
                 payment_type: ExternalPaymentType::CreditCard,
+                currency: "USD".to_string(),
                 quantity: 1,
                 total_sales_in_cents: 150,
             },
             BoxOfficeSalesSummaryPaymentRow {
                 payment_type: ExternalPaymentType::Voucher,
+                currency: "USD".to_string(),
                 quantity: 2,
                 total_sales_in_cents: 300,
             },
@@ -2205,6 +2303,71 @@ fn box_office_sales_summary_report() {
     assert_eq!(expected_report_data, report_data);
 }
 
+#[test]
+fn transaction_detail_report_seat_label() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let venue_section = project
+        .create_venue_section()
+        .with_venue_id(venue.id)
+        .with_name("Orchestra".to_string())
+        .with_seats(vec!["A"], 3)
+        .finish();
+    let seats = venue_section.seats(connection).unwrap();
+    let event = project
+        .create_event()
+        .with_venue(&venue)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event
+        .ticket_types(true, None, connection)
+        .unwrap()
+        .remove(0)
+        .update(
+            TicketTypeEditableAttributes {
+                venue_section_id: Some(Some(venue_section.id)),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    cart.assign_seats(ticket_type.id, &[seats[2].id, seats[0].id], connection)
+        .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("test".to_string()),
+        ExternalPaymentType::CreditCard,
+        user.id,
+        total,
+        connection,
+    )
+    .unwrap();
+
+    let result = Report::transaction_detail_report(None, Some(event.id), None, None, None, 0, 100, connection).unwrap();
+    assert_eq!(result.data.len(), 1);
+    assert_eq!(
+        result.data[0].seat_label,
+        Some("Orchestra Row A Seat 1, Orchestra Row A Seat 3".to_string())
+    );
+}
+
 fn build_transaction_report_row(
     total: i64,
     organization: &Organization,
@@ -2231,8 +2394,12 @@ fn build_transaction_report_row(
         client_fee_in_cents_total: fee_schedule_range.client_fee_in_cents * quantity,
         event_fee_client_in_cents: organization.client_event_fee_in_cents,
         event_fee_client_in_cents_total: organization.client_event_fee_in_cents,
+        tax_in_cents: 0,
+        included_tax_in_cents: 0,
+        tax_in_cents_total: 0,
         fee_range_id: Some(fee_schedule_range.id),
         order_type: OrderTypes::Cart,
+        currency: "USD".to_string(),
         payment_method: Some(PaymentMethods::CreditCard.to_string()),
         payment_provider: Some(PaymentProviders::Stripe.to_string()),
         transaction_date: order.paid_at.clone().unwrap(),
@@ -2255,6 +2422,7 @@ fn build_transaction_report_row(
         content: None,
         platform: None,
         check_in_source: None,
+        seat_label: None,
         headline_artist_alt_genres: None,
         headline_artist_main_genre: None,
     }
@@ -2454,3 +2622,47 @@ fn promo_code_report() {
 
     assert_eq!(test_pass_count, 5);
 }
+
+#[test]
+fn reconciliation_payments_report() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let mut order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .finish();
+    let total = order.calculate_total(connection).unwrap();
+    let gift_card = GiftCard::create(organization.id, 500, "USD".to_string(), None, None, None)
+        .commit(connection)
+        .unwrap();
+    order.add_gift_card_payment(&gift_card, user.id, connection).unwrap();
+    order
+        .add_credit_card_payment(
+            user.id,
+            total - 500,
+            PaymentProviders::Stripe,
+            "ref".to_string(),
+            PaymentStatus::Completed,
+            json!(""),
+            connection,
+        )
+        .unwrap();
+
+    let report = Report::reconciliation_payments_report(organization.id, None, None, connection).unwrap();
+    assert_eq!(report.len(), 2);
+    assert_eq!(report[0].payment_method, PaymentMethods::CreditCard.to_string());
+    assert_eq!(report[0].payment_count, 1);
+    assert_eq!(report[0].total, total - 500);
+    assert_eq!(report[1].payment_method, PaymentMethods::GiftCard.to_string());
+    assert_eq!(report[1].sales_total, 500);
+    assert_eq!(report[1].refund_total, 0);
+}
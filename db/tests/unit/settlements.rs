@@ -101,6 +101,35 @@ fn find_last_settlement_for_organization() {
     );
 }
 
+#[test]
+fn find_open_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    assert!(Settlement::find_open_for_organization(&organization, connection)
+        .unwrap()
+        .is_none());
+
+    project
+        .create_settlement()
+        .with_organization(&organization)
+        .finalized()
+        .finish();
+    assert!(Settlement::find_open_for_organization(&organization, connection)
+        .unwrap()
+        .is_none());
+
+    let settlement = project
+        .create_settlement()
+        .with_organization(&organization)
+        .with_start_time(dates::now().add_days(-1).finish())
+        .finish();
+    assert_eq!(
+        Settlement::find_open_for_organization(&organization, connection).unwrap(),
+        Some(settlement)
+    );
+}
+
 #[test]
 fn process_settlement_for_organization() {
     let project = TestProject::new();
@@ -344,6 +373,9 @@ fn create_post_event_entries() {
 
     let display_settlement = settlement.clone().for_display(connection).unwrap();
     assert_eq!(display_settlement.event_entries.len(), 1);
+    assert_eq!(display_settlement.currency_totals.len(), 1);
+    assert_eq!(display_settlement.currency_totals[0].currency, "USD");
+    assert_eq!(display_settlement.currency_totals[0].adjustments_in_cents, 0);
     assert_eq!(
         display_settlement.event_entries[0].event,
         past_event.for_display(connection).unwrap()
@@ -1352,3 +1384,43 @@ fn find_for_organization() {
     assert_eq!(settlements.len(), 1);
     assert_eq!(settlements[0].id, settlement.id);
 }
+
+#[test]
+fn for_display_currency_totals_include_adjustments() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let settlement = project.create_settlement().finish();
+    project
+        .create_settlement_adjustment()
+        .with_settlement(&settlement)
+        .with_amount_in_cents(150)
+        .finish();
+    SettlementAdjustment::create(
+        settlement.id,
+        SettlementAdjustmentTypes::ManualCredit,
+        None,
+        500,
+        "eur".to_string(),
+    )
+    .commit(connection)
+    .unwrap();
+
+    let display_settlement = settlement.for_display(connection).unwrap();
+    assert_eq!(
+        display_settlement.currency_totals,
+        vec![
+            SettlementCurrencyTotal {
+                currency: "EUR".to_string(),
+                total_sales_in_cents: 0,
+                tax_in_cents: 0,
+                adjustments_in_cents: 500,
+            },
+            SettlementCurrencyTotal {
+                currency: "USD".to_string(),
+                total_sales_in_cents: 0,
+                tax_in_cents: 0,
+                adjustments_in_cents: -150,
+            },
+        ]
+    );
+}
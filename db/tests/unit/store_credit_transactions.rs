@@ -0,0 +1,297 @@
+use db::dev::TestProject;
+use db::prelude::*;
+use db::utils::dates;
+use db::utils::errors::ErrorCode::{BusinessProcessError, ValidationError};
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let admin = project.create_user().finish();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+
+    let transaction = StoreCreditTransaction::create(
+        user.id,
+        organization.id,
+        2500,
+        "USD".to_string(),
+        None,
+        None,
+        Some("Goodwill credit".to_string()),
+        Some(admin.id),
+    )
+    .commit(connection)
+    .unwrap();
+    assert_eq!(transaction.user_id, user.id);
+    assert_eq!(transaction.organization_id, organization.id);
+    assert_eq!(transaction.amount_in_cents, 2500);
+    assert_eq!(
+        StoreCreditTransaction::find_for_user(user.id, organization.id, connection).unwrap(),
+        vec![transaction]
+    );
+
+    let domain_events = DomainEvent::find(
+        Tables::Users,
+        Some(user.id),
+        Some(DomainEventTypes::StoreCreditTransactionCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn create_with_invalid_amount() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+
+    let result = StoreCreditTransaction::create(user.id, organization.id, 0, "USD".to_string(), None, None, None, None)
+        .commit(connection);
+    match result.unwrap_err().error_code {
+        ValidationError { errors } => assert!(errors.contains_key("amount_in_cents")),
+        _ => panic!("Expected validation error"),
+    }
+
+    let result = StoreCreditTransaction::create(
+        user.id,
+        organization.id,
+        -100,
+        "USD".to_string(),
+        None,
+        None,
+        None,
+        None,
+    )
+    .commit(connection);
+    assert_eq!(result.unwrap_err().error_code, BusinessProcessError);
+}
+
+#[test]
+fn balance() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let other_organization = project.create_organization().finish();
+    assert_eq!(
+        StoreCreditTransaction::balance(user.id, organization.id, "USD", connection).unwrap(),
+        0
+    );
+
+    for (organization_id, amount, currency) in &[
+        (organization.id, 1000, "USD"),
+        (organization.id, -400, "USD"),
+        (organization.id, 700, "EUR"),
+        (other_organization.id, 300, "USD"),
+    ] {
+        StoreCreditTransaction::create(
+            user.id,
+            *organization_id,
+            *amount,
+            currency.to_string(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .commit(connection)
+        .unwrap();
+    }
+
+    assert_eq!(
+        StoreCreditTransaction::balance(user.id, organization.id, "USD", connection).unwrap(),
+        600
+    );
+    assert_eq!(
+        StoreCreditTransaction::balance(user.id, organization.id, "EUR", connection).unwrap(),
+        700
+    );
+    assert_eq!(
+        StoreCreditTransaction::balance(user.id, other_organization.id, "USD", connection).unwrap(),
+        300
+    );
+}
+
+#[test]
+fn add_store_credit_payment() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let mut order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .finish();
+    let total = order.calculate_total(connection).unwrap();
+
+    // No credit available
+    let result = order.add_store_credit_payment(user.id, connection);
+    assert_eq!(result.unwrap_err().error_code, BusinessProcessError);
+
+    StoreCreditTransaction::create(
+        user.id,
+        event.organization_id,
+        500,
+        "USD".to_string(),
+        None,
+        None,
+        None,
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    let payment = order.add_store_credit_payment(user.id, connection).unwrap();
+    assert_eq!(payment.payment_method, PaymentMethods::StoreCredit);
+    assert_eq!(payment.amount, 500);
+    assert_eq!(order.status, OrderStatus::Draft);
+    assert_eq!(order.amount_due(connection).unwrap(), total - 500);
+    assert_eq!(
+        StoreCreditTransaction::balance(user.id, event.organization_id, "USD", connection).unwrap(),
+        0
+    );
+
+    StoreCreditTransaction::create(
+        user.id,
+        event.organization_id,
+        total,
+        "USD".to_string(),
+        None,
+        None,
+        None,
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    let payment = order.add_store_credit_payment(user.id, connection).unwrap();
+    assert_eq!(payment.amount, total - 500);
+    assert_eq!(order.status, OrderStatus::Paid);
+    assert_eq!(
+        StoreCreditTransaction::balance(user.id, event.organization_id, "USD", connection).unwrap(),
+        500
+    );
+}
+
+#[test]
+fn expired_cart_returns_partial_payment() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let mut order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .finish();
+    StoreCreditTransaction::create(
+        user.id,
+        event.organization_id,
+        500,
+        "USD".to_string(),
+        None,
+        None,
+        None,
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    order.add_store_credit_payment(user.id, connection).unwrap();
+    assert_eq!(
+        StoreCreditTransaction::balance(user.id, event.organization_id, "USD", connection).unwrap(),
+        0
+    );
+
+    // Unexpired carts keep their payments
+    Order::reverse_expired_stored_value_payments(connection).unwrap();
+    assert_eq!(order.total_paid(connection).unwrap(), 500);
+
+    order
+        .set_expiry(
+            Some(user.id),
+            Some(dates::now().add_minutes(-5).finish()),
+            true,
+            connection,
+        )
+        .unwrap();
+    Order::reverse_expired_stored_value_payments(connection).unwrap();
+    assert_eq!(order.total_paid(connection).unwrap(), 0);
+    assert_eq!(
+        StoreCreditTransaction::balance(user.id, event.organization_id, "USD", connection).unwrap(),
+        500
+    );
+}
+
+#[test]
+fn refund_to_store_credit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let admin = project.create_user().finish();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let mut order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let payment = order.payments(connection).unwrap().remove(0);
+
+    let items = order.refundable_items_for_event(event.id, true, connection).unwrap();
+    let (refund, amount) = order.refund(&items, admin.id, None, false, connection).unwrap();
+    let refund_payment = payment
+        .log_store_credit_refund(admin.id, &refund, amount, connection)
+        .unwrap();
+    assert_eq!(refund_payment.payment_method, PaymentMethods::StoreCredit);
+    assert_eq!(refund_payment.amount, -amount);
+    assert_eq!(refund_payment.refund_id, Some(refund.id));
+    assert_eq!(
+        StoreCreditTransaction::balance(user.id, event.organization_id, "USD", connection).unwrap(),
+        amount
+    );
+    let transactions = StoreCreditTransaction::find_for_user(user.id, event.organization_id, connection).unwrap();
+    assert_eq!(transactions[0].refund_id, Some(refund.id));
+    assert_eq!(transactions[0].order_id, Some(order.id));
+}
+
+#[test]
+fn refund_store_credit_payment() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let mut order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .finish();
+    let total = order.calculate_total(connection).unwrap();
+    StoreCreditTransaction::create(
+        user.id,
+        event.organization_id,
+        total,
+        "USD".to_string(),
+        None,
+        None,
+        None,
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    let payment = order.add_store_credit_payment(user.id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+
+    let items = order.refundable_items_for_event(event.id, true, connection).unwrap();
+    let (refund, amount) = order.refund(&items, user.id, None, false, connection).unwrap();
+    payment.log_refund(user.id, &refund, amount, None, connection).unwrap();
+    assert_eq!(
+        StoreCreditTransaction::balance(user.id, event.organization_id, "USD", connection).unwrap(),
+        amount
+    );
+}
@@ -0,0 +1,353 @@
+use db::dev::TestProject;
+use db::prelude::*;
+use db::utils::errors::ErrorCode::ValidationError;
+use diesel::PgConnection;
+
+fn cart_with_tickets(project: &TestProject, quantity: u32, connection: &PgConnection) -> (Order, User) {
+    let organization = project.create_organization().with_fees().finish();
+    let venue = project
+        .create_venue()
+        .with_country("US".to_string())
+        .with_state("California".to_string())
+        .finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    (cart, user)
+}
+
+fn ticket_item(cart: &Order, connection: &PgConnection) -> OrderItem {
+    cart.items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap()
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let tax_rule = TaxRule::create(
+        "CA Sales Tax".to_string(),
+        "US".to_string(),
+        Some("California".to_string()),
+        7.25,
+        false,
+        true,
+        false,
+    )
+    .commit(None, connection)
+    .unwrap();
+    assert_eq!(tax_rule.name, "CA Sales Tax");
+    assert_eq!(TaxRule::find(tax_rule.id, connection).unwrap(), tax_rule);
+
+    let result = TaxRule::create("Invalid".to_string(), "US".to_string(), None, 101.0, false, true, false)
+        .commit(None, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => assert!(errors.contains_key("rate_percent")),
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn find_for_venue() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project
+        .create_venue()
+        .with_country("US".to_string())
+        .with_state("California".to_string())
+        .finish();
+    let federal = TaxRule::create("Federal".to_string(), "us".to_string(), None, 1.0, false, true, false)
+        .commit(None, connection)
+        .unwrap();
+    let state = TaxRule::create(
+        "State".to_string(),
+        "US".to_string(),
+        Some("california".to_string()),
+        5.0,
+        false,
+        true,
+        false,
+    )
+    .commit(None, connection)
+    .unwrap();
+    TaxRule::create(
+        "Other state".to_string(),
+        "US".to_string(),
+        Some("Nevada".to_string()),
+        5.0,
+        false,
+        true,
+        false,
+    )
+    .commit(None, connection)
+    .unwrap();
+    TaxRule::create("VAT".to_string(), "GB".to_string(), None, 20.0, true, true, true)
+        .commit(None, connection)
+        .unwrap();
+
+    assert_eq!(
+        TaxRule::find_for_venue(&venue, connection).unwrap(),
+        vec![federal, state]
+    );
+}
+
+#[test]
+fn all() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let vat = TaxRule::create("VAT".to_string(), "GB".to_string(), None, 20.0, true, true, true)
+        .commit(None, connection)
+        .unwrap();
+    let federal = TaxRule::create("Federal".to_string(), "US".to_string(), None, 1.0, false, true, false)
+        .commit(None, connection)
+        .unwrap();
+
+    let page = TaxRule::all(0, 1, connection).unwrap();
+    assert_eq!(page.data, vec![vat]);
+    assert_eq!(page.paging.total, 2);
+    let page = TaxRule::all(1, 1, connection).unwrap();
+    assert_eq!(page.data, vec![federal]);
+}
+
+#[test]
+fn tax_for_amount() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let exclusive = TaxRule::create(
+        "Sales Tax".to_string(),
+        "US".to_string(),
+        None,
+        10.0,
+        false,
+        true,
+        false,
+    )
+    .commit(None, connection)
+    .unwrap();
+    let inclusive = TaxRule::create("VAT".to_string(), "GB".to_string(), None, 20.0, true, true, false)
+        .commit(None, connection)
+        .unwrap();
+
+    assert_eq!(exclusive.tax_for_amount(1000), 100);
+    assert_eq!(exclusive.tax_for_amount(155), 16);
+    assert_eq!(inclusive.tax_for_amount(1200), 200);
+    assert_eq!(inclusive.tax_for_amount(0), 0);
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let tax_rule = TaxRule::create(
+        "Sales Tax".to_string(),
+        "US".to_string(),
+        None,
+        10.0,
+        false,
+        true,
+        false,
+    )
+    .commit(None, connection)
+    .unwrap();
+
+    let tax_rule = tax_rule
+        .update(
+            TaxRuleEditableAttributes {
+                rate_percent: Some(8.5),
+                applies_to_fees: Some(true),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(tax_rule.rate_percent, 8.5);
+    assert!(tax_rule.applies_to_fees);
+    assert!(tax_rule
+        .update(
+            TaxRuleEditableAttributes {
+                rate_percent: Some(-1.0),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .is_err());
+}
+
+#[test]
+fn exclusive_tax_added_to_cart() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let tax_rule = TaxRule::create(
+        "CA Sales Tax".to_string(),
+        "US".to_string(),
+        Some("California".to_string()),
+        10.0,
+        false,
+        true,
+        false,
+    )
+    .commit(None, connection)
+    .unwrap();
+    let (cart, _) = cart_with_tickets(&project, 2, connection);
+
+    let ticket_item = ticket_item(&cart, connection);
+    let items = cart.items(connection).unwrap();
+    let tax_items: Vec<&OrderItem> = items.iter().filter(|i| i.item_type == OrderItemTypes::Tax).collect();
+    assert_eq!(tax_items.len(), 1);
+    let tax_item = tax_items[0];
+    assert_eq!(tax_item.parent_id, Some(ticket_item.id));
+    assert_eq!(tax_item.tax_rule_id, Some(tax_rule.id));
+    assert_eq!(tax_item.quantity, 2);
+    assert_eq!(
+        tax_item.unit_price_in_cents,
+        tax_rule.tax_for_amount(ticket_item.unit_price_in_cents)
+    );
+    assert_eq!(tax_item.description(connection).unwrap(), "Tax - CA Sales Tax");
+
+    let total_without_tax: i64 = items
+        .iter()
+        .filter(|i| i.item_type != OrderItemTypes::Tax)
+        .map(|i| i.unit_price_in_cents * i.quantity)
+        .sum();
+    assert_eq!(
+        cart.calculate_total(connection).unwrap(),
+        total_without_tax + tax_item.unit_price_in_cents * 2
+    );
+}
+
+#[test]
+fn tax_applied_to_fees() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    TaxRule::create("Fee Tax".to_string(), "US".to_string(), None, 10.0, false, false, true)
+        .commit(None, connection)
+        .unwrap();
+    let (cart, _) = cart_with_tickets(&project, 1, connection);
+
+    let ticket_item = ticket_item(&cart, connection);
+    let fee_item = ticket_item.find_fee_item(connection).unwrap().unwrap();
+    let items = cart.items(connection).unwrap();
+    assert!(items
+        .iter()
+        .filter(|i| i.item_type == OrderItemTypes::Tax)
+        .all(|i| i.parent_id != Some(ticket_item.id)));
+    assert!(items
+        .iter()
+        .any(|i| i.item_type == OrderItemTypes::Tax && i.parent_id == Some(fee_item.id)));
+}
+
+#[test]
+fn inclusive_tax_does_not_change_total() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    TaxRule::create("VAT".to_string(), "US".to_string(), None, 20.0, true, true, false)
+        .commit(None, connection)
+        .unwrap();
+    let (cart, _) = cart_with_tickets(&project, 1, connection);
+
+    let ticket_item = ticket_item(&cart, connection);
+    let items = cart.items(connection).unwrap();
+    let tax_item = items.iter().find(|i| i.item_type == OrderItemTypes::Tax).unwrap();
+    assert_eq!(tax_item.unit_price_in_cents, 0);
+    assert_eq!(
+        tax_item.included_tax_in_cents,
+        (ticket_item.unit_price_in_cents as f64 - ticket_item.unit_price_in_cents as f64 / 1.2).round() as i64
+    );
+    let total_without_tax: i64 = items
+        .iter()
+        .filter(|i| i.item_type != OrderItemTypes::Tax)
+        .map(|i| i.unit_price_in_cents * i.quantity)
+        .sum();
+    assert_eq!(cart.calculate_total(connection).unwrap(), total_without_tax);
+}
+
+#[test]
+fn refund_includes_tax() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let tax_rule = TaxRule::create(
+        "Sales Tax".to_string(),
+        "US".to_string(),
+        None,
+        10.0,
+        false,
+        true,
+        false,
+    )
+    .commit(None, connection)
+    .unwrap();
+    let (mut cart, user) = cart_with_tickets(&project, 2, connection);
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("Test".to_string()),
+        ExternalPaymentType::CreditCard,
+        user.id,
+        total,
+        connection,
+    )
+    .unwrap();
+
+    let ticket_item = ticket_item(&cart, connection);
+    let fee_item = ticket_item.find_fee_item(connection).unwrap().unwrap();
+    let tax_item = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Tax)
+        .unwrap();
+
+    // Tax lines cannot be refunded on their own
+    assert!(cart
+        .refund(
+            &[RefundItemRequest {
+                order_item_id: tax_item.id,
+                ticket_instance_id: None,
+            }],
+            user.id,
+            None,
+            false,
+            connection,
+        )
+        .is_err());
+
+    let tickets = TicketInstance::find_for_order_item(ticket_item.id, connection).unwrap();
+    let refund_items = vec![RefundItemRequest {
+        order_item_id: ticket_item.id,
+        ticket_instance_id: Some(tickets[0].id),
+    }];
+    let (_refund, amount) = cart.refund(&refund_items, user.id, None, false, connection).unwrap();
+    assert_eq!(
+        amount,
+        ticket_item.unit_price_in_cents
+            + fee_item.unit_price_in_cents
+            + tax_rule.tax_for_amount(ticket_item.unit_price_in_cents)
+    );
+    let tax_item = OrderItem::find(tax_item.id, connection).unwrap();
+    assert_eq!(tax_item.refunded_quantity, 1);
+}
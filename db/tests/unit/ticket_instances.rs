@@ -411,6 +411,8 @@ pub fn update() {
     let attrs = UpdateTicketInstanceAttributes {
         first_name_override: Some(Some("First".to_string())),
         last_name_override: Some(Some("Last".to_string())),
+        attendee_email: None,
+        attendee_birth_date: None,
     };
 
     let domain_event_count = DomainEvent::find(
@@ -462,6 +464,8 @@ pub fn update_with_validation_errors() {
     let attrs = UpdateTicketInstanceAttributes {
         first_name_override: Some(Some("First".to_string())),
         last_name_override: None,
+        attendee_email: None,
+        attendee_birth_date: None,
     };
     let result = ticket.clone().update(attrs, user.id, connection);
     match result {
@@ -485,6 +489,8 @@ pub fn update_with_validation_errors() {
     let attrs = UpdateTicketInstanceAttributes {
         first_name_override: None,
         last_name_override: Some(Some("Last".to_string())),
+        attendee_email: None,
+        attendee_birth_date: None,
     };
     let result = ticket.clone().update(attrs, user.id, connection);
     match result {
@@ -508,6 +514,8 @@ pub fn update_with_validation_errors() {
     let attrs = UpdateTicketInstanceAttributes {
         first_name_override: Some(Some("First".to_string())),
         last_name_override: Some(Some("Last".to_string())),
+        attendee_email: None,
+        attendee_birth_date: None,
     };
     assert!(ticket.clone().update(attrs.clone(), user.id, connection).is_ok());
 
@@ -540,6 +548,277 @@ pub fn update_with_validation_errors() {
     );
 }
 
+#[test]
+fn update_attendee_details() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_ticket_pricing()
+        .with_ticket_type_count(1)
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                required_attendee_details: Some(vec![AttendeeDetails::Name, AttendeeDetails::BirthDate]),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    let user = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = TicketInstance::find_for_user(user.id, connection)
+        .unwrap()
+        .pop()
+        .unwrap();
+
+    let attrs = UpdateTicketInstanceAttributes {
+        first_name_override: Some(Some("First".to_string())),
+        last_name_override: Some(Some("Last".to_string())),
+        attendee_email: Some(Some("not an email".to_string())),
+        attendee_birth_date: None,
+    };
+    match ticket.clone().update(attrs, user.id, connection) {
+        Ok(_) => panic!("Expected error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(errors["attendee_email"][0].code, "email");
+                assert_eq!(errors["attendee_birth_date"][0].code, "required");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let birth_date = NaiveDate::from_ymd(1990, 1, 15);
+    let attrs = UpdateTicketInstanceAttributes {
+        first_name_override: Some(Some("First".to_string())),
+        last_name_override: Some(Some("Last".to_string())),
+        attendee_email: Some(Some("attendee@tari.com".to_string())),
+        attendee_birth_date: Some(Some(birth_date)),
+    };
+    let updated = ticket.update(attrs, user.id, connection).unwrap();
+    assert_eq!(updated.attendee_email, Some("attendee@tari.com".to_string()));
+    assert_eq!(updated.attendee_birth_date, Some(birth_date));
+
+    let changes = TicketAttendeeChange::find_for_ticket_instance(updated.id, connection).unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].first_name, Some("First".to_string()));
+    assert_eq!(changes[0].birth_date, Some(birth_date));
+    assert_eq!(changes[0].changed_by_user_id, user.id);
+    assert_eq!(changes[0].fee_in_cents, 0);
+
+    let redeemable_ticket = TicketInstance::show_redeemable_ticket(updated.id, connection).unwrap();
+    assert_eq!(redeemable_ticket.attendee_name, Some("First Last".to_string()));
+    assert_eq!(redeemable_ticket.attendee_birth_date, Some(birth_date));
+    assert!(redeemable_ticket.named_ticket);
+}
+
+#[test]
+fn attendee_name_change_fee() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_ticket_pricing()
+        .with_ticket_type_count(1)
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                attendee_name_change_fee_in_cents: Some(500),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    let user = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = TicketInstance::find_for_user(user.id, connection)
+        .unwrap()
+        .pop()
+        .unwrap();
+    let attrs = UpdateTicketInstanceAttributes {
+        first_name_override: Some(Some("First".to_string())),
+        last_name_override: Some(Some("Last".to_string())),
+        attendee_email: None,
+        attendee_birth_date: None,
+    };
+
+    // Naming the ticket for the first time is free
+    assert_eq!(ticket.attendee_name_change_fee(&attrs, connection).unwrap(), 0);
+    let ticket = ticket.update(attrs.clone(), user.id, connection).unwrap();
+
+    // Keeping the same name is free
+    assert_eq!(ticket.attendee_name_change_fee(&attrs, connection).unwrap(), 0);
+    let attrs = UpdateTicketInstanceAttributes {
+        first_name_override: None,
+        last_name_override: None,
+        attendee_email: Some(Some("attendee@tari.com".to_string())),
+        attendee_birth_date: None,
+    };
+    assert_eq!(ticket.attendee_name_change_fee(&attrs, connection).unwrap(), 0);
+
+    let attrs = UpdateTicketInstanceAttributes {
+        first_name_override: Some(Some("Other".to_string())),
+        last_name_override: Some(Some("Person".to_string())),
+        attendee_email: None,
+        attendee_birth_date: None,
+    };
+    assert_eq!(ticket.attendee_name_change_fee(&attrs, connection).unwrap(), 500);
+
+    // Transfers clear the name but the ticket remains personalized
+    let receiver = project.create_user().finish();
+    TicketInstance::direct_transfer(
+        &user,
+        &vec![ticket.id],
+        "nowhere",
+        TransferMessageType::Email,
+        receiver.id,
+        connection,
+    )
+    .unwrap();
+    let ticket = TicketInstance::find(ticket.id, connection).unwrap();
+    assert_eq!(ticket.first_name_override, None);
+    assert_eq!(ticket.attendee_name_change_fee(&attrs, connection).unwrap(), 500);
+}
+
+#[test]
+fn attendee_details_locked() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_event_start(dates::now().add_hours(12).finish())
+        .with_ticket_pricing()
+        .with_ticket_type_count(1)
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let user = project.create_user().finish();
+    let receiver = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = TicketInstance::find_for_user(user.id, connection)
+        .unwrap()
+        .pop()
+        .unwrap();
+    assert!(!ticket.attendee_details_locked(&ticket_type, connection).unwrap());
+
+    let ticket_type = ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                required_attendee_details: Some(vec![AttendeeDetails::Name]),
+                attendee_details_lock_hours: Some(Some(6)),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    assert!(!ticket.attendee_details_locked(&ticket_type, connection).unwrap());
+
+    let ticket_type = ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                attendee_details_lock_hours: Some(Some(24)),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    assert!(ticket.attendee_details_locked(&ticket_type, connection).unwrap());
+
+    let attrs = UpdateTicketInstanceAttributes {
+        first_name_override: Some(Some("First".to_string())),
+        last_name_override: Some(Some("Last".to_string())),
+        attendee_email: None,
+        attendee_birth_date: None,
+    };
+    assert_eq!(
+        ticket.update(attrs, user.id, connection),
+        DatabaseError::business_process_error("Attendee details can no longer be changed for this event.",)
+    );
+    assert_eq!(
+        TicketInstance::direct_transfer(
+            &user,
+            &vec![ticket.id],
+            "nowhere",
+            TransferMessageType::Email,
+            receiver.id,
+            connection,
+        ),
+        DatabaseError::business_process_error("Named tickets can no longer be transferred for this event.",)
+    );
+}
+
+#[test]
+fn direct_transfer_clears_attendee_details() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_ticket_pricing()
+        .with_ticket_type_count(1)
+        .finish();
+    let user = project.create_user().finish();
+    let receiver = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = TicketInstance::find_for_user(user.id, connection)
+        .unwrap()
+        .pop()
+        .unwrap();
+    let attrs = UpdateTicketInstanceAttributes {
+        first_name_override: Some(Some("First".to_string())),
+        last_name_override: Some(Some("Last".to_string())),
+        attendee_email: Some(Some("attendee@tari.com".to_string())),
+        attendee_birth_date: Some(Some(NaiveDate::from_ymd(1990, 1, 15))),
+    };
+    let ticket = ticket.update(attrs, user.id, connection).unwrap();
+
+    TicketInstance::direct_transfer(
+        &user,
+        &vec![ticket.id],
+        "nowhere",
+        TransferMessageType::Email,
+        receiver.id,
+        connection,
+    )
+    .unwrap();
+    let ticket = TicketInstance::find(ticket.id, connection).unwrap();
+    assert_eq!(ticket.first_name_override, None);
+    assert_eq!(ticket.last_name_override, None);
+    assert_eq!(ticket.attendee_email, None);
+    assert_eq!(ticket.attendee_birth_date, None);
+}
+
 #[test]
 fn find_ids_for_order() {
     let project = TestProject::new();
@@ -906,6 +1185,8 @@ fn find() {
         transfer_address: None,
         check_in_source: None,
         promo_image_url: None,
+        seat_label: None,
+        redeem_secret: None,
     };
     assert_eq!(
         (display_event, None, expected_ticket),
@@ -977,6 +1258,8 @@ fn find_show_no_token() {
         transfer_address: None,
         check_in_source: None,
         promo_image_url: None,
+        seat_label: None,
+        redeem_secret: None,
     };
     let (found_event, found_user, found_ticket) = TicketInstance::find_for_display(ticket.id, connection).unwrap();
     assert_eq!(
@@ -1318,6 +1601,186 @@ fn redeem_ticket() {
     assert_eq!(result, RedeemResults::TicketRedeemSuccess);
 }
 
+#[test]
+fn redeem_ticket_at() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let admin = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = TicketInstance::find_for_user(user.id, connection).unwrap().remove(0);
+
+    let scanned_at = dates::now().add_minutes(-30).finish().with_nanosecond(0).unwrap();
+    let result = TicketInstance::redeem_ticket_at(
+        ticket.id,
+        ticket.redeem_key.clone().unwrap(),
+        admin.id,
+        CheckInSource::Scanned,
+        scanned_at,
+        false,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(result, RedeemResults::TicketRedeemSuccess);
+    let ticket = TicketInstance::find(ticket.id, connection).unwrap();
+    assert_eq!(ticket.status, TicketInstanceStatus::Redeemed);
+    assert_eq!(ticket.redeemed_at, Some(scanned_at));
+    assert_eq!(ticket.redeemed_by_user_id, Some(admin.id));
+    assert_eq!(ticket.check_in_source, Some(CheckInSource::Scanned));
+}
+
+#[test]
+fn redeem_ticket_at_outside_time_slot() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let admin = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let starts_at = NaiveDate::from_ymd(2055, 7, 8).and_hms(10, 0, 0);
+    let time_slot = EventTimeSlot::create(event.id, starts_at, starts_at + Duration::minutes(30), 3)
+        .commit(None, connection)
+        .unwrap();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    // Tickets for timed entry events cannot be bought without a time slot
+    let total = cart.calculate_total(connection).unwrap();
+    assert!(cart
+        .add_external_payment(
+            Some("Test".to_string()),
+            ExternalPaymentType::CreditCard,
+            user.id,
+            total,
+            connection
+        )
+        .is_err());
+    cart.assign_time_slot(ticket_type.id, Some(time_slot.id), connection)
+        .unwrap();
+    cart.add_external_payment(
+        Some("Test".to_string()),
+        ExternalPaymentType::CreditCard,
+        user.id,
+        total,
+        connection,
+    )
+    .unwrap();
+    let ticket = TicketInstance::find_for_user(user.id, connection).unwrap().remove(0);
+
+    let result = TicketInstance::redeem_ticket_at(
+        ticket.id,
+        ticket.redeem_key.clone().unwrap(),
+        admin.id,
+        CheckInSource::Scanned,
+        starts_at - Duration::hours(2),
+        false,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(result, RedeemResults::TicketOutsideTimeSlot);
+    let ticket = TicketInstance::find(ticket.id, connection).unwrap();
+    assert_eq!(ticket.status, TicketInstanceStatus::Purchased);
+
+    let result = TicketInstance::redeem_ticket_at(
+        ticket.id,
+        ticket.redeem_key.clone().unwrap(),
+        admin.id,
+        CheckInSource::Scanned,
+        starts_at - Duration::hours(2),
+        true,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(result, RedeemResults::TicketRedeemSuccess);
+}
+
+#[test]
+fn find_for_offline_scanning() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let admin = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let mut tickets = TicketInstance::find_for_user(user.id, connection).unwrap();
+    tickets.sort_by_key(|t| t.id);
+    let ticket = tickets.remove(0);
+    let ticket2 = tickets.remove(0);
+
+    let mut offline_tickets = TicketInstance::find_for_offline_scanning(event.id, None, connection).unwrap();
+    offline_tickets.sort_by_key(|t| t.id);
+    assert_eq!(
+        offline_tickets.iter().map(|t| t.id).collect::<Vec<Uuid>>(),
+        vec![ticket.id, ticket2.id]
+    );
+    assert_eq!(
+        offline_tickets[0].redeem_key_hash,
+        TicketInstance::offline_redeem_key_hash(event.id, &ticket.redeem_key.clone().unwrap())
+    );
+    assert_ne!(
+        offline_tickets[0].redeem_key_hash,
+        TicketInstance::offline_redeem_key_hash(Uuid::new_v4(), &ticket.redeem_key.clone().unwrap())
+    );
+    assert_eq!(offline_tickets[0].status, TicketInstanceStatus::Purchased);
+
+    // Only tickets changed since are included in incremental manifests
+    diesel::sql_query("UPDATE ticket_instances SET updated_at = $1 WHERE id = ANY($2);")
+        .bind::<sql_types::Timestamp, _>(dates::now().add_days(-1).finish())
+        .bind::<sql_types::Array<sql_types::Uuid>, _>(vec![ticket.id, ticket2.id])
+        .execute(connection)
+        .unwrap();
+    let since = dates::now().add_hours(-1).finish();
+    assert!(
+        TicketInstance::find_for_offline_scanning(event.id, Some(since), connection)
+            .unwrap()
+            .is_empty()
+    );
+
+    TicketInstance::redeem_ticket(
+        ticket.id,
+        ticket.redeem_key.clone().unwrap(),
+        admin.id,
+        CheckInSource::Scanned,
+        connection,
+    )
+    .unwrap();
+    let offline_tickets = TicketInstance::find_for_offline_scanning(event.id, Some(since), connection).unwrap();
+    assert_eq!(offline_tickets.len(), 1);
+    assert_eq!(offline_tickets[0].id, ticket.id);
+    assert_eq!(offline_tickets[0].status, TicketInstanceStatus::Redeemed);
+
+    // A new redeem key is picked up by the next manifest
+    let old_hash = TicketInstance::offline_redeem_key_hash(event.id, &ticket2.redeem_key.clone().unwrap());
+    ticket2.associate_redeem_key(connection).unwrap();
+    let offline_tickets = TicketInstance::find_for_offline_scanning(event.id, Some(since), connection).unwrap();
+    let offline_ticket2 = offline_tickets.iter().find(|t| t.id == ticket2.id).unwrap();
+    assert_ne!(offline_ticket2.redeem_key_hash, old_hash);
+}
+
 #[test]
 fn organization() {
     let project = TestProject::new();
@@ -1688,6 +2151,8 @@ fn receive_ticket_transfer() {
             UpdateTicketInstanceAttributes {
                 first_name_override: Some(Some("Janus".to_string())),
                 last_name_override: Some(Some("Zeal".to_string())),
+                attendee_email: None,
+                attendee_birth_date: None,
             },
             user.id,
             connection,
@@ -0,0 +1,138 @@
+use db::dev::TestProject;
+use db::prelude::*;
+
+#[test]
+fn reentry() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let door_person = project.create_user().finish();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let ticket_type = ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                max_entries: Some(2),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    project
+        .create_order()
+        .for_tickets(ticket_type.id)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = TicketInstance::find_for_user(user.id, connection).unwrap().remove(0);
+    let redeem_key = ticket.redeem_key.clone().unwrap();
+    let scan_in = |minutes_ago: i64| {
+        TicketInstance::redeem_ticket_at(
+            ticket.id,
+            redeem_key.clone(),
+            door_person.id,
+            CheckInSource::Scanned,
+            dates::now().add_minutes(-minutes_ago).finish(),
+            false,
+            connection,
+        )
+        .unwrap()
+    };
+    let scan_out = |minutes_ago: i64| {
+        TicketInstance::scan_out(
+            ticket.id,
+            redeem_key.clone(),
+            door_person.id,
+            dates::now().add_minutes(-minutes_ago).finish(),
+            connection,
+        )
+        .unwrap()
+    };
+
+    // Can't leave before entering
+    assert_eq!(scan_out(50), RedeemResults::TicketNotCheckedIn);
+    assert_eq!(TicketScan::current_occupancy(event.id, connection).unwrap(), 0);
+
+    assert_eq!(scan_in(40), RedeemResults::TicketRedeemSuccess);
+    assert_eq!(scan_in(39), RedeemResults::TicketAlreadyRedeemed);
+    assert_eq!(TicketScan::current_occupancy(event.id, connection).unwrap(), 1);
+
+    assert_eq!(scan_out(30), RedeemResults::TicketExitSuccess);
+    assert_eq!(scan_out(29), RedeemResults::TicketNotCheckedIn);
+    assert_eq!(TicketScan::current_occupancy(event.id, connection).unwrap(), 0);
+
+    assert_eq!(scan_in(20), RedeemResults::TicketReentrySuccess);
+    assert_eq!(TicketScan::current_occupancy(event.id, connection).unwrap(), 1);
+    assert_eq!(TicketScan::entry_count(ticket.id, connection).unwrap(), 2);
+
+    // Second entry was the last one allowed
+    assert_eq!(scan_out(10), RedeemResults::TicketExitSuccess);
+    assert_eq!(scan_in(5), RedeemResults::TicketMaxEntriesReached);
+    assert_eq!(TicketScan::current_occupancy(event.id, connection).unwrap(), 0);
+
+    let scans = TicketScan::find_by_ticket_instance_id(ticket.id, connection).unwrap();
+    assert_eq!(
+        scans.iter().map(|s| s.direction).collect::<Vec<TicketScanDirection>>(),
+        vec![
+            TicketScanDirection::In,
+            TicketScanDirection::Out,
+            TicketScanDirection::In,
+            TicketScanDirection::Out
+        ]
+    );
+    assert_eq!(scans[0].check_in_source, Some(CheckInSource::Scanned));
+    assert_eq!(scans[0].scanned_by_user_id, Some(door_person.id));
+    assert_eq!(scans[0].event_id, event.id);
+
+    // Original redemption is kept on the ticket
+    let ticket = TicketInstance::find(ticket.id, connection).unwrap();
+    assert_eq!(ticket.status, TicketInstanceStatus::Redeemed);
+}
+
+#[test]
+fn single_entry_by_default() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let door_person = project.create_user().finish();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = TicketInstance::find_for_user(user.id, connection).unwrap().remove(0);
+    let redeem_key = ticket.redeem_key.clone().unwrap();
+    assert_eq!(ticket.ticket_type(connection).unwrap().max_entries, 1);
+
+    TicketInstance::redeem_ticket(
+        ticket.id,
+        redeem_key.clone(),
+        door_person.id,
+        CheckInSource::Scanned,
+        connection,
+    )
+    .unwrap();
+    let result = TicketInstance::scan_out(
+        ticket.id,
+        redeem_key.clone(),
+        door_person.id,
+        dates::now().finish(),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(result, RedeemResults::TicketExitSuccess);
+    let result = TicketInstance::redeem_ticket(
+        ticket.id,
+        redeem_key,
+        door_person.id,
+        CheckInSource::Scanned,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(result, RedeemResults::TicketMaxEntriesReached);
+}
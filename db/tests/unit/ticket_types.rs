@@ -1034,6 +1034,64 @@ pub fn update_with_validation_errors() {
     }
 }
 
+#[test]
+pub fn update_with_attendee_detail_validation_errors() {
+    let db = TestProject::new();
+    let connection = db.get_connection();
+    let event = db.create_event().with_tickets().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let update_parameters = TicketTypeEditableAttributes {
+        required_attendee_details: Some(vec![AttendeeDetails::Email]),
+        ..Default::default()
+    };
+    match ticket_type.clone().update(update_parameters, None, connection) {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("required_attendee_details"));
+                assert_eq!(
+                    errors["required_attendee_details"][0]
+                        .message
+                        .clone()
+                        .unwrap()
+                        .into_owned(),
+                    "Attendee name must be required when requiring other attendee details"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let update_parameters = TicketTypeEditableAttributes {
+        attendee_name_change_fee_in_cents: Some(-100),
+        ..Default::default()
+    };
+    match ticket_type.clone().update(update_parameters, None, connection) {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("attendee_name_change_fee_in_cents"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let update_parameters = TicketTypeEditableAttributes {
+        required_attendee_details: Some(vec![AttendeeDetails::Name, AttendeeDetails::Email]),
+        attendee_name_change_fee_in_cents: Some(500),
+        attendee_details_lock_hours: Some(Some(48)),
+        ..Default::default()
+    };
+    let ticket_type = ticket_type.update(update_parameters, None, connection).unwrap();
+    assert_eq!(
+        ticket_type.required_attendee_details,
+        vec![AttendeeDetails::Name, AttendeeDetails::Email]
+    );
+    assert_eq!(ticket_type.attendee_name_change_fee_in_cents, 500);
+    assert_eq!(ticket_type.attendee_details_lock_hours, Some(48));
+    assert!(ticket_type.requires_attendee_details());
+}
+
 #[test]
 fn find() {
     let db = TestProject::new();
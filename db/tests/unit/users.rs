@@ -2270,6 +2270,7 @@ fn get_global_scopes() {
             "comp:read",
             "comp:write",
             "dashboard:read",
+            "dispute:write",
             "event:broadcast",
             "event:cancel",
             "event:clone",
@@ -2321,6 +2322,8 @@ fn get_global_scopes() {
             "settlement:read",
             "settlement:read-early",
             "settlement:write",
+            "tax-rule:read",
+            "tax-rule:write",
             "ticket:admin",
             "ticket:read",
             "ticket:transfer",
@@ -2360,6 +2363,7 @@ fn get_global_scopes() {
             "comp:read",
             "comp:write",
             "dashboard:read",
+            "dispute:write",
             "event:broadcast",
             "event:cancel",
             "event:clone",
@@ -2411,6 +2415,8 @@ fn get_global_scopes() {
             "settlement:read",
             "settlement:read-early",
             "settlement:write",
+            "tax-rule:read",
+            "tax-rule:write",
             "ticket:admin",
             "ticket:read",
             "ticket:transfer",
@@ -0,0 +1,114 @@
+use db::dev::TestProject;
+use db::prelude::*;
+use uuid::Uuid;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue_section = project.create_venue_section().finish();
+
+    let venue_seat = VenueSeat::create(venue_section.id, "A".to_string(), "1".to_string(), true)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(venue_seat.venue_section_id, venue_section.id);
+    assert_eq!(venue_seat.row_label, "A".to_string());
+    assert_eq!(venue_seat.seat_number, "1".to_string());
+    assert!(venue_seat.accessible);
+}
+
+#[test]
+fn commit_multiple() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue_section = project.create_venue_section().finish();
+
+    let venue_seats = NewVenueSeat::commit_multiple(
+        &[
+            VenueSeat::create(venue_section.id, "A".to_string(), "1".to_string(), false),
+            VenueSeat::create(venue_section.id, "A".to_string(), "2".to_string(), false),
+        ],
+        connection,
+    )
+    .unwrap();
+    assert_eq!(venue_seats.len(), 2);
+    assert_eq!(
+        VenueSeat::find_by_venue_section_id(venue_section.id, connection).unwrap(),
+        venue_seats
+    );
+}
+
+#[test]
+fn label() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue_section = project
+        .create_venue_section()
+        .with_name("Balcony".to_string())
+        .with_seats(vec!["C"], 1)
+        .finish();
+    let venue_seat = &venue_section.seats(connection).unwrap()[0];
+
+    assert_eq!(
+        venue_seat.label(connection).unwrap(),
+        "Balcony Row C Seat 1".to_string()
+    );
+}
+
+#[test]
+fn find_for_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let venue_section = project
+        .create_venue_section()
+        .with_venue_id(venue.id)
+        .with_seats(vec!["A"], 3)
+        .finish();
+    let event = project
+        .create_event()
+        .with_venue(&venue)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let ticket_type = ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                venue_section_id: Some(Some(venue_section.id)),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(ticket_type.venue_section_id, Some(venue_section.id));
+
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let seats = venue_section.seats(connection).unwrap();
+    cart.assign_seats(ticket_type.id, &[seats[1].id], connection).unwrap();
+
+    let event_seats = VenueSeat::find_for_event(event.id, connection).unwrap();
+    assert_eq!(event_seats.len(), 3);
+    assert_eq!(
+        event_seats
+            .iter()
+            .map(|s| (s.id, s.available))
+            .collect::<Vec<(Uuid, bool)>>(),
+        vec![(seats[0].id, true), (seats[1].id, false), (seats[2].id, true)]
+    );
+    assert!(event_seats.iter().all(|s| s.ticket_type_id == ticket_type.id));
+}
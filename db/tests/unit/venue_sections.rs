@@ -0,0 +1,96 @@
+use db::dev::TestProject;
+use db::prelude::*;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+
+    let venue_section = VenueSection::create(venue.id, Some(stage.id), "Orchestra".to_string())
+        .commit(connection)
+        .unwrap();
+    assert_eq!(venue_section.name, "Orchestra".to_string());
+    assert_eq!(venue_section.venue_id, venue.id);
+    assert_eq!(venue_section.stage_id, Some(stage.id));
+}
+
+#[test]
+fn commit_with_stage_from_other_venue() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let venue2 = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue2.id).finish();
+
+    let result = VenueSection::create(venue.id, Some(stage.id), "Orchestra".to_string()).commit(connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert!(errors.contains_key("stage_id"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue_section = project.create_venue_section().finish();
+
+    let parameters = VenueSectionEditableAttributes {
+        name: Some("Balcony".to_string()),
+        ..Default::default()
+    };
+    let venue_section = venue_section.update(parameters, connection).unwrap();
+    assert_eq!(venue_section.name, "Balcony".to_string());
+}
+
+#[test]
+fn find() {
+    let project = TestProject::new();
+    let venue_section = project.create_venue_section().finish();
+
+    let found_venue_section = VenueSection::find(venue_section.id, project.get_connection()).unwrap();
+    assert_eq!(venue_section, found_venue_section);
+}
+
+#[test]
+fn find_by_venue_id() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let venue_section = project
+        .create_venue_section()
+        .with_name("B Section".to_string())
+        .with_venue_id(venue.id)
+        .finish();
+    let venue_section2 = project
+        .create_venue_section()
+        .with_name("A Section".to_string())
+        .with_venue_id(venue.id)
+        .finish();
+    project.create_venue_section().finish();
+
+    let venue_sections = VenueSection::find_by_venue_id(venue.id, connection).unwrap();
+    assert_eq!(venue_sections, vec![venue_section2, venue_section]);
+}
+
+#[test]
+fn seats() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue_section = project.create_venue_section().with_seats(vec!["A", "B"], 3).finish();
+
+    let seats = venue_section.seats(connection).unwrap();
+    assert_eq!(seats.len(), 6);
+    assert_eq!(venue_section.seat_count(connection).unwrap(), 6);
+    assert_eq!(seats[0].row_label, "A".to_string());
+    assert_eq!(seats[0].seat_number, "1".to_string());
+}
@@ -0,0 +1,305 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use db::dev::TestProject;
+use db::prelude::*;
+use db::schema::{orders, ticket_instances, waitlist_entries};
+use diesel;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let user = project.create_user().finish();
+
+    let waitlist_entry = WaitlistEntry::create(ticket_type.id, user.id, 2)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(waitlist_entry.ticket_type_id, ticket_type.id);
+    assert_eq!(waitlist_entry.user_id, user.id);
+    assert_eq!(waitlist_entry.quantity, 2);
+    assert_eq!(waitlist_entry.status, WaitlistEntryStatus::Waiting);
+
+    let domain_actions = DomainAction::find_by_resource(
+        Some(Tables::TicketTypes),
+        Some(ticket_type.id),
+        DomainActionTypes::ProcessWaitlist,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_actions.len(), 1);
+
+    // Joining again reuses the pending action
+    project
+        .create_waitlist_entry()
+        .with_ticket_type_id(ticket_type.id)
+        .finish();
+    let domain_actions = DomainAction::find_by_resource(
+        Some(Tables::TicketTypes),
+        Some(ticket_type.id),
+        DomainActionTypes::ProcessWaitlist,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_actions.len(), 1);
+}
+
+#[test]
+fn commit_when_already_waiting() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let waitlist_entry = project.create_waitlist_entry().with_user(&user).finish();
+
+    let result = WaitlistEntry::create(waitlist_entry.ticket_type_id, user.id, 1).commit(connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert!(errors.contains_key("ticket_type_id"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let result = WaitlistEntry::create(waitlist_entry.ticket_type_id, Uuid::new_v4(), 0).commit(connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert!(errors.contains_key("quantity"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn position() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let waitlist_entry = project.create_waitlist_entry().finish();
+    let waitlist_entry2 = project
+        .create_waitlist_entry()
+        .with_ticket_type_id(waitlist_entry.ticket_type_id)
+        .finish();
+    let waitlist_entry2: WaitlistEntry =
+        diesel::update(waitlist_entries::table.filter(waitlist_entries::id.eq(waitlist_entry2.id)))
+            .set(waitlist_entries::created_at.eq(Utc::now().naive_utc() + Duration::minutes(1)))
+            .get_result(connection)
+            .unwrap();
+    assert_eq!(waitlist_entry.position(connection).unwrap(), Some(1));
+    assert_eq!(waitlist_entry2.position(connection).unwrap(), Some(2));
+
+    let waitlist_entry = waitlist_entry.cancel(None, connection).unwrap();
+    assert_eq!(waitlist_entry.position(connection).unwrap(), None);
+    assert_eq!(waitlist_entry2.position(connection).unwrap(), Some(1));
+}
+
+#[test]
+fn cancel() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let waitlist_entry = project.create_waitlist_entry().with_user(&user).finish();
+
+    let waitlist_entry = waitlist_entry.cancel(Some(user.id), connection).unwrap();
+    assert_eq!(waitlist_entry.status, WaitlistEntryStatus::Cancelled);
+    assert!(
+        WaitlistEntry::find_active_for_user(waitlist_entry.ticket_type_id, user.id, connection)
+            .unwrap()
+            .is_none()
+    );
+    assert!(waitlist_entry.cancel(Some(user.id), connection).is_err());
+
+    // Can rejoin once cancelled
+    project
+        .create_waitlist_entry()
+        .with_ticket_type_id(waitlist_entry.ticket_type_id)
+        .with_user(&user)
+        .finish();
+}
+
+#[test]
+fn process() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_ticket_pricing()
+        .with_a_specific_number_of_tickets(2)
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    let waitlist_entry = project
+        .create_waitlist_entry()
+        .with_ticket_type_id(ticket_type.id)
+        .with_user(&user2)
+        .finish();
+
+    // Nothing available yet
+    assert!(WaitlistEntry::process(ticket_type.id, connection).unwrap().is_empty());
+    let waitlist_entry = WaitlistEntry::find(waitlist_entry.id, connection).unwrap();
+    assert_eq!(waitlist_entry.status, WaitlistEntryStatus::Waiting);
+
+    // Releasing a ticket from the cart makes it available for the waitlist
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    // The released ticket is held for the waitlist rather than returned to public sale
+    let user3 = project.create_user().finish();
+    let mut cart3 = Order::find_or_create_cart(&user3, connection).unwrap();
+    assert!(cart3
+        .update_quantities(
+            user3.id,
+            &[UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 1,
+                redemption_code: None,
+            }],
+            false,
+            false,
+            connection,
+        )
+        .is_err());
+
+    let offered_entries = WaitlistEntry::process(ticket_type.id, connection).unwrap();
+    assert_eq!(offered_entries.len(), 1);
+    let waitlist_entry = WaitlistEntry::find(waitlist_entry.id, connection).unwrap();
+    assert_eq!(offered_entries[0], waitlist_entry);
+    assert_eq!(waitlist_entry.status, WaitlistEntryStatus::Offered);
+    assert!(waitlist_entry.offered_at.is_some());
+
+    let cart2 = Order::find_cart_for_user(user2.id, connection).unwrap().unwrap();
+    assert_eq!(waitlist_entry.order_id, Some(cart2.id));
+    assert_eq!(cart2.tickets(Some(ticket_type.id), connection).unwrap().len(), 1);
+    let offer_expires_at = Utc::now().naive_utc() + Duration::minutes(WAITLIST_OFFER_EXPIRY_TIME_MINUTES);
+    assert!((offer_expires_at.timestamp() - waitlist_entry.offer_expires_at.unwrap().timestamp()).abs() < 2);
+    let tickets = cart2.tickets(Some(ticket_type.id), connection).unwrap();
+    assert_eq!(tickets[0].reserved_until, waitlist_entry.offer_expires_at);
+    assert_ne!(cart2.expires_at, waitlist_entry.offer_expires_at);
+}
+
+#[test]
+fn process_expires_unclaimed_offers() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_ticket_pricing()
+        .with_a_specific_number_of_tickets(1)
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let waitlist_entry = project
+        .create_waitlist_entry()
+        .with_ticket_type_id(ticket_type.id)
+        .finish();
+    let waitlist_entry2 = project
+        .create_waitlist_entry()
+        .with_ticket_type_id(ticket_type.id)
+        .finish();
+    diesel::update(waitlist_entries::table.filter(waitlist_entries::id.eq(waitlist_entry2.id)))
+        .set(waitlist_entries::created_at.eq(Utc::now().naive_utc() + Duration::minutes(1)))
+        .execute(connection)
+        .unwrap();
+
+    let offered_entries = WaitlistEntry::process(ticket_type.id, connection).unwrap();
+    assert_eq!(offered_entries.len(), 1);
+    assert_eq!(offered_entries[0].id, waitlist_entry.id);
+
+    // Offer lapses without the fan checking out
+    let past_expiry = Utc::now().naive_utc() - Duration::minutes(5);
+    diesel::update(waitlist_entries::table.filter(waitlist_entries::id.eq(waitlist_entry.id)))
+        .set(waitlist_entries::offer_expires_at.eq(past_expiry))
+        .execute(connection)
+        .unwrap();
+    diesel::update(orders::table.filter(orders::id.eq(offered_entries[0].order_id.unwrap())))
+        .set(orders::expires_at.eq(past_expiry))
+        .execute(connection)
+        .unwrap();
+    diesel::update(ticket_instances::table.filter(ticket_instances::status.eq(TicketInstanceStatus::Reserved)))
+        .set(ticket_instances::reserved_until.eq(past_expiry))
+        .execute(connection)
+        .unwrap();
+
+    let offered_entries = WaitlistEntry::process(ticket_type.id, connection).unwrap();
+    assert_eq!(offered_entries.len(), 1);
+    assert_eq!(offered_entries[0].id, waitlist_entry2.id);
+    let waitlist_entry = WaitlistEntry::find(waitlist_entry.id, connection).unwrap();
+    assert_eq!(waitlist_entry.status, WaitlistEntryStatus::Expired);
+}
+
+#[test]
+fn schedule_processing_if_waiting() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+
+    WaitlistEntry::schedule_processing_if_waiting(ticket_type.id, connection).unwrap();
+    assert!(DomainAction::upcoming_domain_action(
+        Some(Tables::TicketTypes),
+        Some(ticket_type.id),
+        DomainActionTypes::ProcessWaitlist,
+        connection,
+    )
+    .unwrap()
+    .is_none());
+
+    let waitlist_entry = WaitlistEntry::create(ticket_type.id, project.create_user().finish().id, 1)
+        .commit(connection)
+        .unwrap();
+    let action = DomainAction::upcoming_domain_action(
+        Some(Tables::TicketTypes),
+        Some(ticket_type.id),
+        DomainActionTypes::ProcessWaitlist,
+        connection,
+    )
+    .unwrap()
+    .unwrap();
+    action.set_done(connection).unwrap();
+
+    WaitlistEntry::schedule_processing_if_waiting(waitlist_entry.ticket_type_id, connection).unwrap();
+    assert!(DomainAction::upcoming_domain_action(
+        Some(Tables::TicketTypes),
+        Some(ticket_type.id),
+        DomainActionTypes::ProcessWaitlist,
+        connection,
+    )
+    .unwrap()
+    .is_some());
+}
@@ -4,6 +4,7 @@ use serde_json;
 
 pub struct Customer {
     pub id: String,
+    pub default_payment_method: Option<String>,
     pub raw_data: String,
 }
 
@@ -13,14 +14,55 @@ impl Customer {
     }
     pub async fn from_response(resp: reqwest::Response) -> Result<Customer, StripeError> {
         let raw_data: String = resp.text().await?;
+        Customer::from_json(raw_data)
+    }
+
+    fn from_json(raw_data: String) -> Result<Customer, StripeError> {
+        #[derive(Deserialize)]
+        struct InvoiceSettings {
+            default_payment_method: Option<String>,
+        }
         #[derive(Deserialize)]
         struct R {
             id: String,
+            default_source: Option<String>,
+            invoice_settings: Option<InvoiceSettings>,
         }
         let result: R = serde_json::from_str(&raw_data)?;
+        // Cards attached with `source` are only referenced by default_source
+        let default_payment_method = result
+            .invoice_settings
+            .and_then(|s| s.default_payment_method)
+            .or(result.default_source);
         Ok(Customer {
             id: result.id,
+            default_payment_method,
             raw_data,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_json_default_payment_method() {
+        let customer = Customer::from_json(
+            r#"{"id":"cus_1","default_source":"card_1","invoice_settings":{"default_payment_method":"pm_1"}}"#
+                .to_string(),
+        )
+        .unwrap();
+        assert_eq!(customer.default_payment_method, Some("pm_1".to_string()));
+
+        let customer = Customer::from_json(
+            r#"{"id":"cus_1","default_source":"card_1","invoice_settings":{"default_payment_method":null}}"#
+                .to_string(),
+        )
+        .unwrap();
+        assert_eq!(customer.default_payment_method, Some("card_1".to_string()));
+
+        let customer = Customer::from_json(r#"{"id":"cus_1","default_source":null}"#.to_string()).unwrap();
+        assert_eq!(customer.default_payment_method, None);
+    }
+}
@@ -4,6 +4,7 @@
 // Unused results is more often than not an error
 #![deny(unused_must_use)]
 extern crate reqwest;
+extern crate ring;
 extern crate serde;
 extern crate serde_json;
 #[macro_use]
@@ -11,12 +12,16 @@ extern crate serde_derive;
 
 pub use self::charge_result::ChargeResult;
 pub use self::customer::*;
+pub use self::payment_intent::*;
 pub use self::refund_result::RefundResult;
 pub use self::stripe_client::StripeClient;
 pub use self::stripe_error::StripeError;
+pub use self::webhook::*;
 
 mod charge_result;
 mod customer;
+mod payment_intent;
 mod refund_result;
 mod stripe_client;
 mod stripe_error;
+mod webhook;
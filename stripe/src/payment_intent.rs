@@ -0,0 +1,64 @@
+use crate::StripeError;
+use reqwest;
+use serde_json;
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentIntentStatus {
+    RequiresPaymentMethod,
+    RequiresConfirmation,
+    RequiresAction,
+    Processing,
+    RequiresCapture,
+    Canceled,
+    Succeeded,
+    #[serde(other)]
+    Unknown,
+}
+
+pub struct PaymentIntent {
+    pub id: String,
+    pub status: PaymentIntentStatus,
+    pub amount: i64,
+    pub client_secret: Option<String>,
+    pub redirect_url: Option<String>,
+    pub raw_data: String,
+}
+
+impl PaymentIntent {
+    pub fn to_json(&self) -> String {
+        self.raw_data.clone()
+    }
+
+    pub async fn from_response(resp: reqwest::Response) -> Result<PaymentIntent, StripeError> {
+        Self::from_json(resp.text().await?)
+    }
+
+    pub fn from_json(raw_data: String) -> Result<PaymentIntent, StripeError> {
+        #[derive(Deserialize)]
+        struct RedirectToUrl {
+            url: String,
+        }
+        #[derive(Deserialize)]
+        struct NextAction {
+            redirect_to_url: Option<RedirectToUrl>,
+        }
+        #[derive(Deserialize)]
+        struct R {
+            id: String,
+            status: PaymentIntentStatus,
+            amount: i64,
+            client_secret: Option<String>,
+            next_action: Option<NextAction>,
+        }
+        let result: R = serde_json::from_str(&raw_data)?;
+        Ok(PaymentIntent {
+            id: result.id,
+            status: result.status,
+            amount: result.amount,
+            client_secret: result.client_secret,
+            redirect_url: result.next_action.and_then(|n| n.redirect_to_url).map(|r| r.url),
+            raw_data,
+        })
+    }
+}
@@ -1,17 +1,29 @@
 use crate::ChargeResult;
 use crate::Customer;
+use crate::PaymentIntent;
 use crate::RefundResult;
 use crate::StripeError;
 use reqwest;
 
+const DEFAULT_BASE_URL: &str = "https://api.stripe.com";
+
 #[derive(Clone)]
 pub struct StripeClient {
     api_key: String,
+    base_url: String,
 }
 
 impl StripeClient {
     pub fn new(api_key: String) -> StripeClient {
-        StripeClient { api_key }
+        StripeClient::with_base_url(api_key, DEFAULT_BASE_URL.to_string())
+    }
+
+    /// Allows pointing the client at another host, e.g. a local stripe-mock server
+    pub fn with_base_url(api_key: String, base_url: String) -> StripeClient {
+        StripeClient {
+            api_key,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
     }
 
     pub async fn charge(
@@ -50,7 +62,7 @@ impl StripeClient {
 
         let client = reqwest::Client::new();
         let resp = client
-            .post(&format!("https://api.stripe.com/v1/charges/{}", charge_id))
+            .post(&format!("{}/v1/charges/{}", self.base_url, charge_id))
             .basic_auth(&self.api_key, Some(""))
             .form(&params)
             .send()
@@ -92,7 +104,7 @@ impl StripeClient {
         }
         let client = reqwest::Client::new();
         let resp = client
-            .post("https://api.stripe.com/v1/charges")
+            .post(&format!("{}/v1/charges", self.base_url))
             .basic_auth(&self.api_key, Some(""))
             .form(&params)
             .send()
@@ -106,11 +118,11 @@ impl StripeClient {
     }
 
     pub async fn refund(&self, charge_id: &str) -> Result<RefundResult, StripeError> {
-        let params = vec![("charge".to_string(), charge_id.to_string())];
+        let params = vec![(Self::refund_target(charge_id), charge_id.to_string())];
 
         let client = reqwest::Client::new();
         let resp = client
-            .post("https://api.stripe.com/v1/refunds")
+            .post(&format!("{}/v1/refunds", self.base_url))
             .basic_auth(&self.api_key, Some(""))
             .form(&params)
             .send()
@@ -125,13 +137,13 @@ impl StripeClient {
 
     pub async fn partial_refund(&self, charge_id: &str, amount: i64) -> Result<RefundResult, StripeError> {
         let params = vec![
-            ("charge".to_string(), charge_id.to_string()),
+            (Self::refund_target(charge_id), charge_id.to_string()),
             ("amount".to_string(), amount.to_string()),
         ];
 
         let client = reqwest::Client::new();
         let resp = client
-            .post("https://api.stripe.com/v1/refunds")
+            .post(&format!("{}/v1/refunds", self.base_url))
             .basic_auth(&self.api_key, Some(""))
             .form(&params)
             .send()
@@ -146,13 +158,13 @@ impl StripeClient {
 
     pub fn partial_refund_blocking(&self, charge_id: &str, amount: i64) -> Result<RefundResult, StripeError> {
         let params = vec![
-            ("charge".to_string(), charge_id.to_string()),
+            (Self::refund_target(charge_id), charge_id.to_string()),
             ("amount".to_string(), amount.to_string()),
         ];
 
         let client = reqwest::blocking::Client::new();
         let resp = client
-            .post("https://api.stripe.com/v1/refunds")
+            .post(&format!("{}/v1/refunds", self.base_url))
             .basic_auth(&self.api_key, Some(""))
             .form(&params)
             .send()?;
@@ -168,7 +180,7 @@ impl StripeClient {
         let client = reqwest::Client::new();
 
         let resp = client
-            .post(&format!("https://api.stripe.com/v1/charges/{}/capture", charge_id))
+            .post(&format!("{}/v1/charges/{}/capture", self.base_url, charge_id))
             .basic_auth(&self.api_key, Some(""))
             .send()
             .await?;
@@ -197,7 +209,7 @@ impl StripeClient {
         }
         let client = reqwest::Client::new();
         let resp = client
-            .post(&format!("https://api.stripe.com/v1/customers/{}", client_id,))
+            .post(&format!("{}/v1/customers/{}", self.base_url, client_id))
             .basic_auth(&self.api_key, Some(""))
             .form(&params)
             .send()
@@ -226,7 +238,7 @@ impl StripeClient {
         }
         let client = reqwest::Client::new();
         let resp = client
-            .post("https://api.stripe.com/v1/customers")
+            .post(&format!("{}/v1/customers", self.base_url))
             .basic_auth(&self.api_key, Some(""))
             .form(&params)
             .send()
@@ -238,4 +250,101 @@ impl StripeClient {
             _ => return Err(StripeError::from_response(resp).await),
         }
     }
+
+    pub async fn retrieve_customer(&self, customer_id: &str) -> Result<Customer, StripeError> {
+        let client = reqwest::Client::new();
+        let resp = client
+            .get(&format!("{}/v1/customers/{}", self.base_url, customer_id))
+            .basic_auth(&self.api_key, Some(""))
+            .send()
+            .await?;
+        match resp.status() {
+            reqwest::StatusCode::OK => {
+                return Customer::from_response(resp).await;
+            }
+            _ => return Err(StripeError::from_response(resp).await),
+        }
+    }
+
+    pub async fn create_payment_intent(
+        &self,
+        token: &str,
+        amount: i64,
+        currency: &str,
+        description: &str,
+        return_url: &str,
+        metadata: Vec<(String, String)>,
+    ) -> Result<PaymentIntent, StripeError> {
+        let mut params = vec![
+            ("currency".to_string(), currency.to_lowercase()),
+            ("amount".to_string(), amount.to_string()),
+            ("description".to_string(), description.to_string()),
+            ("confirm".to_string(), "true".to_string()),
+        ];
+        if token.starts_with("tok_") {
+            params.push(("payment_method_data[type]".to_string(), "card".to_string()));
+            params.push(("payment_method_data[card][token]".to_string(), token.to_string()));
+            params.push(("return_url".to_string(), return_url.to_string()));
+        } else if token.starts_with("cus_") {
+            // Stored customers are charged off session with their default payment method,
+            // Stripe cannot confirm an intent from the customer alone
+            let payment_method = match self.retrieve_customer(token).await?.default_payment_method {
+                Some(payment_method) => payment_method,
+                None => {
+                    return Err(StripeError {
+                        description: format!("Stripe customer {} has no default payment method", token),
+                        cause: None,
+                        error_code: None,
+                    })
+                }
+            };
+            params.push(("customer".to_string(), token.to_string()));
+            params.push(("payment_method".to_string(), payment_method));
+            params.push(("off_session".to_string(), "true".to_string()));
+        } else {
+            params.push(("payment_method".to_string(), token.to_string()));
+            params.push(("return_url".to_string(), return_url.to_string()));
+        }
+
+        for key_value in metadata {
+            params.push((format!("metadata[{}]", key_value.0), key_value.1));
+        }
+        let client = reqwest::Client::new();
+        let resp = client
+            .post(&format!("{}/v1/payment_intents", self.base_url))
+            .basic_auth(&self.api_key, Some(""))
+            .form(&params)
+            .send()
+            .await?;
+        match resp.status() {
+            reqwest::StatusCode::OK => {
+                return PaymentIntent::from_response(resp).await;
+            }
+            _ => return Err(StripeError::from_response(resp).await),
+        }
+    }
+
+    pub async fn retrieve_payment_intent(&self, payment_intent_id: &str) -> Result<PaymentIntent, StripeError> {
+        let client = reqwest::Client::new();
+        let resp = client
+            .get(&format!("{}/v1/payment_intents/{}", self.base_url, payment_intent_id))
+            .basic_auth(&self.api_key, Some(""))
+            .send()
+            .await?;
+        match resp.status() {
+            reqwest::StatusCode::OK => {
+                return PaymentIntent::from_response(resp).await;
+            }
+            _ => return Err(StripeError::from_response(resp).await),
+        }
+    }
+
+    // Charges made through payment intents are refunded by their intent id
+    fn refund_target(charge_id: &str) -> String {
+        if charge_id.starts_with("pi_") {
+            "payment_intent".to_string()
+        } else {
+            "charge".to_string()
+        }
+    }
 }
@@ -0,0 +1,110 @@
+use crate::StripeError;
+use ring::{digest, hmac};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Default window Stripe recommends for rejecting replayed webhook events
+pub const DEFAULT_TOLERANCE_IN_SECONDS: i64 = 300;
+
+/// Verifies the `Stripe-Signature` header sent with a webhook against the raw request body.
+pub fn verify_webhook_signature(
+    payload: &[u8],
+    signature_header: &str,
+    secret: &str,
+    tolerance_in_seconds: i64,
+) -> Result<(), StripeError> {
+    let mut timestamp: Option<i64> = None;
+    let mut signatures: Vec<Vec<u8>> = Vec::new();
+    for part in signature_header.split(',') {
+        let mut key_value = part.trim().splitn(2, '=');
+        match (key_value.next(), key_value.next()) {
+            (Some("t"), Some(t)) => timestamp = t.parse().ok(),
+            (Some("v1"), Some(v1)) => {
+                if let Some(signature) = decode_hex(v1) {
+                    signatures.push(signature);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let timestamp = timestamp.ok_or_else(|| webhook_error("Stripe signature header is missing a timestamp"))?;
+    if (now() - timestamp).abs() > tolerance_in_seconds {
+        return Err(webhook_error(
+            "Stripe signature timestamp is outside the tolerance zone",
+        ));
+    }
+
+    let key = hmac::SigningKey::new(&digest::SHA256, secret.as_bytes());
+    let signed_payload = signed_payload(timestamp, payload);
+    if signatures
+        .iter()
+        .any(|s| hmac::verify_with_own_key(&key, &signed_payload, s).is_ok())
+    {
+        Ok(())
+    } else {
+        Err(webhook_error("Stripe signature does not match the payload"))
+    }
+}
+
+/// Builds a `Stripe-Signature` header for a payload, used when testing against mock events
+pub fn webhook_signature_header(payload: &[u8], secret: &str, timestamp: i64) -> String {
+    let key = hmac::SigningKey::new(&digest::SHA256, secret.as_bytes());
+    let signature = hmac::sign(&key, &signed_payload(timestamp, payload));
+    let hex: String = signature.as_ref().iter().map(|b| format!("{:02x}", b)).collect();
+    format!("t={},v1={}", timestamp, hex)
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+fn signed_payload(timestamp: i64, payload: &[u8]) -> Vec<u8> {
+    let mut signed_payload = format!("{}.", timestamp).into_bytes();
+    signed_payload.extend_from_slice(payload);
+    signed_payload
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 2 != 0 {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).ok())
+        .collect()
+}
+
+fn webhook_error(description: &str) -> StripeError {
+    StripeError {
+        description: description.to_string(),
+        cause: None,
+        error_code: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_signature() {
+        let payload = br#"{"id":"evt_1"}"#;
+        let header = webhook_signature_header(payload, "whsec_test", now());
+        assert!(verify_webhook_signature(payload, &header, "whsec_test", DEFAULT_TOLERANCE_IN_SECONDS).is_ok());
+        assert!(verify_webhook_signature(payload, &header, "whsec_other", DEFAULT_TOLERANCE_IN_SECONDS).is_err());
+        assert!(verify_webhook_signature(
+            br#"{"id":"evt_2"}"#,
+            &header,
+            "whsec_test",
+            DEFAULT_TOLERANCE_IN_SECONDS
+        )
+        .is_err());
+
+        let old_header = webhook_signature_header(payload, "whsec_test", now() - 1000);
+        assert!(verify_webhook_signature(payload, &old_header, "whsec_test", DEFAULT_TOLERANCE_IN_SECONDS).is_err());
+        assert!(verify_webhook_signature(payload, "v1=abcd", "whsec_test", DEFAULT_TOLERANCE_IN_SECONDS).is_err());
+    }
+}